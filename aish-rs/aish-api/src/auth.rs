@@ -17,3 +17,13 @@ pub(crate) fn add_auth_headers<A: AuthProvider>(auth: &A, mut req: Request) -> R
     }
     req
 }
+
+/// Messages endpoints authenticate with `x-api-key` instead of a bearer token.
+pub(crate) fn add_api_key_header<A: AuthProvider>(auth: &A, mut req: Request) -> Request {
+    if let Some(token) = auth.bearer_token()
+        && let Ok(header) = token.parse()
+    {
+        let _ = req.headers.insert("x-api-key", header);
+    }
+    req
+}
@@ -16,6 +16,7 @@ use aish_protocol::protocol::SessionSource;
 use futures::Stream;
 use http::HeaderMap;
 use serde_json::Value;
+use serde_json::json;
 use std::collections::VecDeque;
 use std::pin::Pin;
 use std::task::Context;
@@ -66,8 +67,11 @@ impl<T: HttpTransport, A: AuthProvider> ChatClient<T, A> {
         body: Value,
         extra_headers: HeaderMap,
     ) -> Result<ResponseStream, ApiError> {
+        let usage_requested = body.pointer("/stream_options/include_usage") == Some(&json!(true));
         self.streaming
-            .stream(self.path(), body, extra_headers, spawn_chat_stream)
+            .stream(self.path(), body, extra_headers, move |response, idle| {
+                spawn_chat_stream(response, idle, usage_requested)
+            })
             .await
     }
 }
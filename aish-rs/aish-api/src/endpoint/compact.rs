@@ -30,7 +30,7 @@ impl<T: HttpTransport, A: AuthProvider> CompactClient<T, A> {
     fn path(&self) -> Result<&'static str, ApiError> {
         match self.provider.wire {
             WireApi::Compact | WireApi::Responses => Ok("responses/compact"),
-            WireApi::Chat => Err(ApiError::Stream(
+            WireApi::Chat | WireApi::Messages => Err(ApiError::Stream(
                 "compact endpoint requires responses wire api".to_string(),
             )),
         }
@@ -77,6 +77,7 @@ struct CompactHistoryResponse {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::provider::PromptCacheHints;
     use crate::provider::RetryConfig;
     use aish_client::Request;
     use aish_client::Response;
@@ -115,6 +116,7 @@ mod tests {
             base_url: "https://example.com/v1".to_string(),
             query_params: None,
             wire,
+            prompt_cache: PromptCacheHints::None,
             headers: HeaderMap::new(),
             retry: RetryConfig {
                 max_attempts: 1,
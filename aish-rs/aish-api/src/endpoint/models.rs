@@ -71,6 +71,7 @@ impl<T: HttpTransport, A: AuthProvider> ModelsClient<T, A> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::provider::PromptCacheHints;
     use crate::provider::RetryConfig;
     use crate::provider::WireApi;
     use aish_client::Request;
@@ -140,6 +141,7 @@ mod tests {
             base_url: base_url.to_string(),
             query_params: None,
             wire: WireApi::Responses,
+            prompt_cache: PromptCacheHints::None,
             headers: HeaderMap::new(),
             retry: RetryConfig {
                 max_attempts: 1,
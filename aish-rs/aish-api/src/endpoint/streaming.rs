@@ -1,8 +1,10 @@
 use crate::auth::AuthProvider;
+use crate::auth::add_api_key_header;
 use crate::auth::add_auth_headers;
 use crate::common::ResponseStream;
 use crate::error::ApiError;
 use crate::provider::Provider;
+use crate::provider::WireApi;
 use aish_client::HttpTransport;
 use aish_client::StreamResponse;
 use aish_client::run_with_retry;
@@ -35,7 +37,7 @@ impl<T: HttpTransport, A: AuthProvider> StreamingClient<T, A> {
         path: &str,
         body: Value,
         extra_headers: HeaderMap,
-        spawner: fn(StreamResponse, Duration) -> ResponseStream,
+        spawner: impl FnOnce(StreamResponse, Duration) -> ResponseStream,
     ) -> Result<ResponseStream, ApiError> {
         let builder = || {
             let mut req = self.provider.build_request(Method::POST, path);
@@ -45,7 +47,10 @@ impl<T: HttpTransport, A: AuthProvider> StreamingClient<T, A> {
                 http::HeaderValue::from_static("text/event-stream"),
             );
             req.body = Some(body.clone());
-            add_auth_headers(&self.auth, req)
+            match self.provider.wire {
+                WireApi::Messages => add_api_key_header(&self.auth, req),
+                _ => add_auth_headers(&self.auth, req),
+            }
         };
 
         let stream_response = run_with_retry(self.provider.retry.to_policy(), builder, |req| {
@@ -12,6 +12,23 @@ pub enum WireApi {
     Responses,
     Chat,
     Compact,
+    /// Anthropic-style Messages API at `/v1/messages`.
+    Messages,
+}
+
+/// Hints added to Chat Completions requests so the provider can reuse a
+/// cached prompt prefix across turns.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum PromptCacheHints {
+    /// Send the request as-is.
+    #[default]
+    None,
+    /// Anthropic-style `cache_control` breakpoints on the system prompt and
+    /// the latest message, as accepted by OpenRouter, DashScope and others.
+    CacheControl,
+    /// OpenAI-style `prompt_cache_key`, set to the conversation id so every
+    /// request of a session is routed to the same cache.
+    PromptCacheKey,
 }
 
 /// High-level retry configuration for a provider.
@@ -51,6 +68,7 @@ pub struct Provider {
     pub base_url: String,
     pub query_params: Option<HashMap<String, String>>,
     pub wire: WireApi,
+    pub prompt_cache: PromptCacheHints,
     pub headers: HeaderMap,
     pub retry: RetryConfig,
     pub stream_idle_timeout: Duration,
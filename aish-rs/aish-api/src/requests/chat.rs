@@ -1,4 +1,5 @@
 use crate::error::ApiError;
+use crate::provider::PromptCacheHints;
 use crate::provider::Provider;
 use crate::requests::headers::build_conversation_headers;
 use crate::requests::headers::insert_header;
@@ -55,7 +56,7 @@ impl<'a> ChatRequestBuilder<'a> {
         self
     }
 
-    pub fn build(self, _provider: &Provider) -> Result<ChatRequest, ApiError> {
+    pub fn build(self, provider: &Provider) -> Result<ChatRequest, ApiError> {
         let mut messages = Vec::<Value>::new();
         messages.push(json!({"role": "system", "content": self.instructions}));
 
@@ -309,12 +310,36 @@ impl<'a> ChatRequestBuilder<'a> {
             }
         }
 
-        let payload = json!({
+        if provider.prompt_cache == PromptCacheHints::CacheControl {
+            // The system prompt rarely changes, and the latest message marks
+            // the prefix the next turn will extend.
+            if let Some(system) = messages.first_mut() {
+                add_cache_breakpoint(system);
+            }
+            if let Some((_, rest)) = messages.split_first_mut() {
+                rest.iter_mut().rev().any(add_cache_breakpoint);
+            }
+        }
+
+        let mut payload = json!({
             "model": self.model,
             "messages": messages,
             "stream": true,
             "tools": self.tools,
         });
+        // Only providers configured for prompt caching are asked for a usage
+        // chunk, so requests to every other provider stay unchanged.
+        if provider.prompt_cache != PromptCacheHints::None
+            && let Some(obj) = payload.as_object_mut()
+        {
+            obj.insert("stream_options".to_string(), json!({"include_usage": true}));
+        }
+        if provider.prompt_cache == PromptCacheHints::PromptCacheKey
+            && let Some(conversation_id) = &self.conversation_id
+            && let Some(obj) = payload.as_object_mut()
+        {
+            obj.insert("prompt_cache_key".to_string(), json!(conversation_id));
+        }
 
         let mut headers = build_conversation_headers(self.conversation_id);
         if let Some(subagent) = subagent_header(&self.session_source) {
@@ -328,6 +353,30 @@ impl<'a> ChatRequestBuilder<'a> {
     }
 }
 
+/// Marks the end of `message` as a cache breakpoint, turning string content
+/// into a single text part first. Returns `false` for messages without
+/// content (e.g. assistant tool calls), which cannot carry one.
+fn add_cache_breakpoint(message: &mut Value) -> bool {
+    let Some(content) = message.get_mut("content") else {
+        return false;
+    };
+    if let Some(text) = content.as_str() {
+        if text.is_empty() {
+            return false;
+        }
+        *content = json!([{"type": "text", "text": text}]);
+    }
+    let Some(part) = content
+        .as_array_mut()
+        .and_then(|parts| parts.last_mut())
+        .and_then(Value::as_object_mut)
+    else {
+        return false;
+    };
+    part.insert("cache_control".to_string(), json!({"type": "ephemeral"}));
+    true
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -340,11 +389,16 @@ mod tests {
     use std::time::Duration;
 
     fn provider() -> Provider {
+        provider_with_cache(PromptCacheHints::None)
+    }
+
+    fn provider_with_cache(prompt_cache: PromptCacheHints) -> Provider {
         Provider {
             name: "openai".to_string(),
             base_url: "https://api.aish.com/v1".to_string(),
             query_params: None,
             wire: WireApi::Chat,
+            prompt_cache,
             headers: HeaderMap::new(),
             retry: RetryConfig {
                 max_attempts: 1,
@@ -387,4 +441,70 @@ mod tests {
             Some(&HeaderValue::from_static("my-task"))
         );
     }
+
+    fn user(text: &str) -> ResponseItem {
+        ResponseItem::Message {
+            id: None,
+            role: "user".to_string(),
+            content: vec![ContentItem::InputText {
+                text: text.to_string(),
+            }],
+        }
+    }
+
+    #[test]
+    fn cache_control_marks_system_prompt_and_latest_message() {
+        let prompt_input = vec![
+            user("first"),
+            ResponseItem::FunctionCall {
+                id: None,
+                name: "shell".to_string(),
+                arguments: "{}".to_string(),
+                call_id: "call-1".to_string(),
+            },
+        ];
+        let req = ChatRequestBuilder::new("gpt-test", "inst", &prompt_input, &[])
+            .build(&provider_with_cache(PromptCacheHints::CacheControl))
+            .expect("request");
+
+        // The trailing tool call has no content, so the breakpoint moves to
+        // the user message before it.
+        let ephemeral = json!({"type": "ephemeral"});
+        assert_eq!(
+            req.body["messages"][0]["content"],
+            json!([{"type": "text", "text": "inst", "cache_control": ephemeral}])
+        );
+        assert_eq!(
+            req.body["messages"][1]["content"],
+            json!([{"type": "text", "text": "first", "cache_control": ephemeral}])
+        );
+        assert_eq!(req.body["messages"][2]["content"], Value::Null);
+        assert!(req.body.get("prompt_cache_key").is_none());
+        assert_eq!(req.body["stream_options"], json!({"include_usage": true}));
+    }
+
+    #[test]
+    fn no_stream_options_without_prompt_cache() {
+        let prompt_input = vec![user("hi")];
+        let req = ChatRequestBuilder::new("gpt-test", "inst", &prompt_input, &[])
+            .conversation_id(Some("conv-1".into()))
+            .build(&provider())
+            .expect("request");
+
+        assert!(req.body.get("stream_options").is_none());
+        assert!(req.body.get("prompt_cache_key").is_none());
+    }
+
+    #[test]
+    fn prompt_cache_key_uses_conversation_id() {
+        let prompt_input = vec![user("hi")];
+        let req = ChatRequestBuilder::new("gpt-test", "inst", &prompt_input, &[])
+            .conversation_id(Some("conv-1".into()))
+            .build(&provider_with_cache(PromptCacheHints::PromptCacheKey))
+            .expect("request");
+
+        assert_eq!(req.body["prompt_cache_key"], json!("conv-1"));
+        assert_eq!(req.body["messages"][1]["content"], json!("hi"));
+        assert_eq!(req.body["stream_options"], json!({"include_usage": true}));
+    }
 }
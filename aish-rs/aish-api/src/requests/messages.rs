@@ -0,0 +1,561 @@
+use crate::error::ApiError;
+use crate::provider::Provider;
+use crate::requests::headers::build_conversation_headers;
+use crate::requests::headers::insert_header;
+use crate::requests::headers::subagent_header;
+use aish_protocol::models::ContentItem;
+use aish_protocol::models::FunctionCallOutputContentItem;
+use aish_protocol::models::ReasoningItemContent;
+use aish_protocol::models::ResponseItem;
+use aish_protocol::protocol::SessionSource;
+use http::HeaderMap;
+use serde_json::Value;
+use serde_json::json;
+use std::collections::HashSet;
+
+/// Version sent in the `anthropic-version` header unless the provider
+/// configures its own.
+pub const ANTHROPIC_VERSION: &str = "2023-06-01";
+
+/// `max_tokens` is mandatory for the Messages API.
+pub const DEFAULT_MAX_TOKENS: u64 = 16_384;
+
+/// Thinking blocks must be replayed with their signature and redacted ones
+/// with their opaque data. Both are kept in the reasoning item's
+/// `encrypted_content` behind these markers, which also sets them apart from
+/// reasoning produced by other wire APIs; the server cannot verify that, so it
+/// is dropped.
+pub(crate) const THINKING_SIGNATURE_PREFIX: &str = "messages-thinking:";
+pub(crate) const REDACTED_THINKING_PREFIX: &str = "messages-redacted-thinking:";
+
+/// Assembled request body plus headers for Messages streaming calls.
+pub struct MessagesRequest {
+    pub body: Value,
+    pub headers: HeaderMap,
+}
+
+pub struct MessagesRequestBuilder<'a> {
+    model: &'a str,
+    instructions: &'a str,
+    input: &'a [ResponseItem],
+    tools: &'a [Value],
+    parallel_tool_calls: bool,
+    max_tokens: u64,
+    thinking_budget: Option<u64>,
+    conversation_id: Option<String>,
+    session_source: Option<SessionSource>,
+}
+
+impl<'a> MessagesRequestBuilder<'a> {
+    /// `tools` must already be in the Messages format
+    /// (`{name, description, input_schema}`).
+    pub fn new(
+        model: &'a str,
+        instructions: &'a str,
+        input: &'a [ResponseItem],
+        tools: &'a [Value],
+    ) -> Self {
+        Self {
+            model,
+            instructions,
+            input,
+            tools,
+            parallel_tool_calls: true,
+            max_tokens: DEFAULT_MAX_TOKENS,
+            thinking_budget: None,
+            conversation_id: None,
+            session_source: None,
+        }
+    }
+
+    pub fn parallel_tool_calls(mut self, enabled: bool) -> Self {
+        self.parallel_tool_calls = enabled;
+        self
+    }
+
+    pub fn max_tokens(mut self, max_tokens: u64) -> Self {
+        self.max_tokens = max_tokens;
+        self
+    }
+
+    /// Enables extended thinking with the given token budget.
+    pub fn thinking_budget(mut self, budget: Option<u64>) -> Self {
+        self.thinking_budget = budget;
+        self
+    }
+
+    pub fn conversation_id(mut self, id: Option<String>) -> Self {
+        self.conversation_id = id;
+        self
+    }
+
+    pub fn session_source(mut self, source: Option<SessionSource>) -> Self {
+        self.session_source = source;
+        self
+    }
+
+    pub fn build(self, provider: &Provider) -> Result<MessagesRequest, ApiError> {
+        let mut messages = MessageList::default();
+        // tool_result blocks must answer a tool_use in the previous assistant
+        // message, so outputs of calls that were not replayed are dropped.
+        let mut tool_use_ids: HashSet<&str> = HashSet::new();
+
+        for item in self.input {
+            match item {
+                ResponseItem::Message { role, content, .. } => {
+                    let role = if role == "assistant" {
+                        "assistant"
+                    } else {
+                        "user"
+                    };
+                    for content_item in content {
+                        let block = match content_item {
+                            ContentItem::InputText { text } | ContentItem::OutputText { text } => {
+                                text_block(text)
+                            }
+                            ContentItem::InputImage { image_url } => Some(image_block(image_url)),
+                        };
+                        if let Some(block) = block {
+                            messages.push(role, block);
+                        }
+                    }
+                }
+                ResponseItem::Reasoning {
+                    content,
+                    encrypted_content: Some(encrypted_content),
+                    ..
+                } => {
+                    if let Some(data) = encrypted_content.strip_prefix(REDACTED_THINKING_PREFIX) {
+                        messages.push(
+                            "assistant",
+                            json!({"type": "redacted_thinking", "data": data}),
+                        );
+                    } else if let Some(signature) =
+                        encrypted_content.strip_prefix(THINKING_SIGNATURE_PREFIX)
+                    {
+                        let thinking: String = content
+                            .iter()
+                            .flatten()
+                            .map(|entry| match entry {
+                                ReasoningItemContent::ReasoningText { text }
+                                | ReasoningItemContent::Text { text } => text.as_str(),
+                            })
+                            .collect();
+                        messages.push(
+                            "assistant",
+                            json!({
+                                "type": "thinking",
+                                "thinking": thinking,
+                                "signature": signature,
+                            }),
+                        );
+                    }
+                }
+                ResponseItem::FunctionCall {
+                    name,
+                    arguments,
+                    call_id,
+                    ..
+                } => {
+                    let input = serde_json::from_str::<Value>(arguments)
+                        .ok()
+                        .filter(Value::is_object)
+                        .unwrap_or_else(|| json!({}));
+                    tool_use_ids.insert(call_id.as_str());
+                    messages.push(
+                        "assistant",
+                        json!({
+                            "type": "tool_use",
+                            "id": call_id,
+                            "name": name,
+                            "input": input,
+                        }),
+                    );
+                }
+                ResponseItem::FunctionCallOutput { call_id, output } => {
+                    if !tool_use_ids.contains(call_id.as_str()) {
+                        continue;
+                    }
+                    let content = match &output.content_items {
+                        Some(items) => Value::Array(
+                            items
+                                .iter()
+                                .filter_map(|item| match item {
+                                    FunctionCallOutputContentItem::InputText { text } => {
+                                        text_block(text)
+                                    }
+                                    FunctionCallOutputContentItem::InputImage { image_url } => {
+                                        Some(image_block(image_url))
+                                    }
+                                })
+                                .collect(),
+                        ),
+                        None => json!(output.content),
+                    };
+                    let mut block = json!({
+                        "type": "tool_result",
+                        "tool_use_id": call_id,
+                        "content": content,
+                    });
+                    if output.success == Some(false)
+                        && let Some(obj) = block.as_object_mut()
+                    {
+                        obj.insert("is_error".to_string(), json!(true));
+                    }
+                    messages.push("user", block);
+                }
+                ResponseItem::Reasoning { .. }
+                | ResponseItem::LocalShellCall { .. }
+                | ResponseItem::CustomToolCall { .. }
+                | ResponseItem::CustomToolCallOutput { .. }
+                | ResponseItem::WebSearchCall { .. }
+                | ResponseItem::GhostSnapshot { .. }
+                | ResponseItem::Compaction { .. }
+                | ResponseItem::Other => {}
+            }
+        }
+
+        // Cache breakpoints: the system prompt, the tool list and the whole
+        // conversation so far, so the next turn only pays for new input.
+        let system = json!([{
+            "type": "text",
+            "text": self.instructions,
+            "cache_control": ephemeral(),
+        }]);
+        let mut tools = self.tools.to_vec();
+        if let Some(obj) = tools.last_mut().and_then(Value::as_object_mut) {
+            obj.insert("cache_control".to_string(), ephemeral());
+        }
+        let mut messages = messages.into_inner();
+        mark_last_cacheable_block(&mut messages);
+
+        let max_tokens = match self.thinking_budget {
+            Some(budget) => self.max_tokens.max(budget + DEFAULT_MAX_TOKENS),
+            None => self.max_tokens,
+        };
+        let mut payload = json!({
+            "model": self.model,
+            "max_tokens": max_tokens,
+            "system": system,
+            "messages": messages,
+            "stream": true,
+        });
+        if let Some(obj) = payload.as_object_mut() {
+            if !tools.is_empty() {
+                obj.insert("tools".to_string(), json!(tools));
+                obj.insert(
+                    "tool_choice".to_string(),
+                    json!({
+                        "type": "auto",
+                        "disable_parallel_tool_use": !self.parallel_tool_calls,
+                    }),
+                );
+            }
+            if let Some(budget) = self.thinking_budget {
+                obj.insert(
+                    "thinking".to_string(),
+                    json!({"type": "enabled", "budget_tokens": budget}),
+                );
+            }
+        }
+
+        let mut headers = build_conversation_headers(self.conversation_id);
+        if let Some(subagent) = subagent_header(&self.session_source) {
+            insert_header(&mut headers, "x-openai-subagent", &subagent);
+        }
+        if !provider.headers.contains_key("anthropic-version") {
+            insert_header(&mut headers, "anthropic-version", ANTHROPIC_VERSION);
+        }
+
+        Ok(MessagesRequest {
+            body: payload,
+            headers,
+        })
+    }
+}
+
+/// Messages in request order. Consecutive blocks for the same role share a
+/// message, since the API requires roles to alternate.
+#[derive(Default)]
+struct MessageList {
+    messages: Vec<(&'static str, Vec<Value>)>,
+}
+
+impl MessageList {
+    fn push(&mut self, role: &'static str, block: Value) {
+        match self.messages.last_mut() {
+            Some((last_role, blocks)) if *last_role == role => blocks.push(block),
+            _ => self.messages.push((role, vec![block])),
+        }
+    }
+
+    fn into_inner(self) -> Vec<Value> {
+        self.messages
+            .into_iter()
+            .map(|(role, content)| json!({"role": role, "content": content}))
+            .collect()
+    }
+}
+
+fn ephemeral() -> Value {
+    json!({"type": "ephemeral"})
+}
+
+/// Thinking blocks cannot carry `cache_control`, so the breakpoint goes on
+/// the last block of the last message that can.
+fn mark_last_cacheable_block(messages: &mut [Value]) {
+    let Some(blocks) = messages
+        .last_mut()
+        .and_then(|message| message.get_mut("content"))
+        .and_then(Value::as_array_mut)
+    else {
+        return;
+    };
+    let cacheable = blocks.iter_mut().rev().find(|block| {
+        !matches!(
+            block.get("type").and_then(Value::as_str),
+            Some("thinking" | "redacted_thinking")
+        )
+    });
+    if let Some(obj) = cacheable.and_then(Value::as_object_mut) {
+        obj.insert("cache_control".to_string(), ephemeral());
+    }
+}
+
+/// The API rejects empty text blocks.
+fn text_block(text: &str) -> Option<Value> {
+    if text.is_empty() {
+        None
+    } else {
+        Some(json!({"type": "text", "text": text}))
+    }
+}
+
+/// Images arrive as `data:` URLs (local files) or remote URLs.
+fn image_block(image_url: &str) -> Value {
+    if let Some(rest) = image_url.strip_prefix("data:")
+        && let Some((media_type, data)) = rest.split_once(";base64,")
+    {
+        json!({
+            "type": "image",
+            "source": {"type": "base64", "media_type": media_type, "data": data},
+        })
+    } else {
+        json!({
+            "type": "image",
+            "source": {"type": "url", "url": image_url},
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::provider::PromptCacheHints;
+    use crate::provider::RetryConfig;
+    use crate::provider::WireApi;
+    use aish_protocol::models::FunctionCallOutputPayload;
+    use http::HeaderValue;
+    use pretty_assertions::assert_eq;
+    use std::time::Duration;
+
+    fn provider(headers: HeaderMap) -> Provider {
+        Provider {
+            name: "anthropic".to_string(),
+            base_url: "https://api.anthropic.com/v1".to_string(),
+            query_params: None,
+            wire: WireApi::Messages,
+            prompt_cache: PromptCacheHints::None,
+            headers,
+            retry: RetryConfig {
+                max_attempts: 1,
+                base_delay: Duration::from_millis(10),
+                retry_429: false,
+                retry_5xx: true,
+                retry_transport: true,
+            },
+            stream_idle_timeout: Duration::from_secs(1),
+        }
+    }
+
+    fn user(text: &str) -> ResponseItem {
+        ResponseItem::Message {
+            id: None,
+            role: "user".to_string(),
+            content: vec![ContentItem::InputText {
+                text: text.to_string(),
+            }],
+        }
+    }
+
+    fn function_call(call_id: &str) -> ResponseItem {
+        ResponseItem::FunctionCall {
+            id: None,
+            name: "shell".to_string(),
+            arguments: r#"{"command":["ls"]}"#.to_string(),
+            call_id: call_id.to_string(),
+        }
+    }
+
+    fn function_output(call_id: &str, content: &str) -> ResponseItem {
+        ResponseItem::FunctionCallOutput {
+            call_id: call_id.to_string(),
+            output: FunctionCallOutputPayload {
+                content: content.to_string(),
+                content_items: None,
+                success: Some(true),
+            },
+        }
+    }
+
+    #[test]
+    fn groups_parallel_tool_calls_and_results_by_role() {
+        let input = vec![
+            user("list files"),
+            ResponseItem::Reasoning {
+                id: String::new(),
+                summary: Vec::new(),
+                content: Some(vec![ReasoningItemContent::ReasoningText {
+                    text: "run ls".to_string(),
+                }]),
+                encrypted_content: Some(format!("{THINKING_SIGNATURE_PREFIX}sig")),
+            },
+            function_call("toolu_1"),
+            function_call("toolu_2"),
+            function_output("toolu_1", "a.txt"),
+            function_output("toolu_2", "b.txt"),
+        ];
+        let tools = vec![json!({"name": "shell", "input_schema": {"type": "object"}})];
+
+        let req = MessagesRequestBuilder::new("claude-test", "be brief", &input, &tools)
+            .build(&provider(HeaderMap::new()))
+            .expect("request");
+
+        assert_eq!(
+            req.body["messages"],
+            json!([
+                {"role": "user", "content": [{"type": "text", "text": "list files"}]},
+                {"role": "assistant", "content": [
+                    {"type": "thinking", "thinking": "run ls", "signature": "sig"},
+                    {"type": "tool_use", "id": "toolu_1", "name": "shell", "input": {"command": ["ls"]}},
+                    {"type": "tool_use", "id": "toolu_2", "name": "shell", "input": {"command": ["ls"]}},
+                ]},
+                {"role": "user", "content": [
+                    {"type": "tool_result", "tool_use_id": "toolu_1", "content": "a.txt"},
+                    {"type": "tool_result", "tool_use_id": "toolu_2", "content": "b.txt",
+                     "cache_control": {"type": "ephemeral"}},
+                ]},
+            ])
+        );
+        assert_eq!(
+            req.body["system"],
+            json!([{"type": "text", "text": "be brief", "cache_control": {"type": "ephemeral"}}])
+        );
+        assert_eq!(
+            req.body["tools"],
+            json!([{"name": "shell", "input_schema": {"type": "object"},
+                    "cache_control": {"type": "ephemeral"}}])
+        );
+        assert_eq!(req.body["max_tokens"], json!(DEFAULT_MAX_TOKENS));
+        assert_eq!(
+            req.headers.get("anthropic-version"),
+            Some(&HeaderValue::from_static(ANTHROPIC_VERSION))
+        );
+    }
+
+    #[test]
+    fn drops_reasoning_and_outputs_it_cannot_replay() {
+        let input = vec![
+            user("hi"),
+            // Reasoning from the Responses API: not ours to replay.
+            ResponseItem::Reasoning {
+                id: "rs_1".to_string(),
+                summary: Vec::new(),
+                content: None,
+                encrypted_content: Some("opaque".to_string()),
+            },
+            ResponseItem::Reasoning {
+                id: String::new(),
+                summary: Vec::new(),
+                content: None,
+                encrypted_content: Some(format!("{REDACTED_THINKING_PREFIX}blob")),
+            },
+            ResponseItem::LocalShellCall {
+                id: None,
+                call_id: Some("call_1".to_string()),
+                status: aish_protocol::models::LocalShellStatus::Completed,
+                action: aish_protocol::models::LocalShellAction::Exec(
+                    aish_protocol::models::LocalShellExecAction {
+                        command: vec!["ls".to_string()],
+                        timeout_ms: None,
+                        working_directory: None,
+                        env: None,
+                        user: None,
+                    },
+                ),
+            },
+            function_output("call_1", "a.txt"),
+            ResponseItem::Message {
+                id: None,
+                role: "assistant".to_string(),
+                content: vec![ContentItem::OutputText {
+                    text: "done".to_string(),
+                }],
+            },
+        ];
+
+        let req = MessagesRequestBuilder::new("claude-test", "inst", &input, &[])
+            .build(&provider(HeaderMap::new()))
+            .expect("request");
+
+        assert_eq!(
+            req.body["messages"],
+            json!([
+                {"role": "user", "content": [{"type": "text", "text": "hi"}]},
+                {"role": "assistant", "content": [
+                    {"type": "redacted_thinking", "data": "blob"},
+                    {"type": "text", "text": "done", "cache_control": {"type": "ephemeral"}},
+                ]},
+            ])
+        );
+        assert!(req.body.get("tools").is_none());
+    }
+
+    #[test]
+    fn thinking_budget_raises_max_tokens_and_keeps_provider_version() {
+        let mut headers = HeaderMap::new();
+        headers.insert("anthropic-version", HeaderValue::from_static("2099-01-01"));
+        let input = vec![user("hi")];
+
+        let req = MessagesRequestBuilder::new("claude-test", "inst", &input, &[])
+            .thinking_budget(Some(8_192))
+            .max_tokens(4_096)
+            .build(&provider(headers))
+            .expect("request");
+
+        assert_eq!(
+            req.body["thinking"],
+            json!({"type": "enabled", "budget_tokens": 8_192})
+        );
+        assert_eq!(req.body["max_tokens"], json!(8_192 + DEFAULT_MAX_TOKENS));
+        assert!(req.headers.get("anthropic-version").is_none());
+    }
+
+    #[test]
+    fn data_url_images_become_base64_sources() {
+        assert_eq!(
+            image_block("data:image/png;base64,AAAA"),
+            json!({
+                "type": "image",
+                "source": {"type": "base64", "media_type": "image/png", "data": "AAAA"},
+            })
+        );
+        assert_eq!(
+            image_block("https://example.com/a.png"),
+            json!({
+                "type": "image",
+                "source": {"type": "url", "url": "https://example.com/a.png"},
+            })
+        );
+    }
+}
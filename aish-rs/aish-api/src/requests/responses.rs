@@ -172,6 +172,7 @@ fn attach_item_ids(payload_json: &mut Value, original_items: &[ResponseItem]) {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::provider::PromptCacheHints;
     use crate::provider::RetryConfig;
     use crate::provider::WireApi;
     use aish_protocol::protocol::SubAgentSource;
@@ -185,6 +186,7 @@ mod tests {
             base_url: base_url.to_string(),
             query_params: None,
             wire: WireApi::Responses,
+            prompt_cache: PromptCacheHints::None,
             headers: HeaderMap::new(),
             retry: RetryConfig {
                 max_attempts: 1,
@@ -5,9 +5,11 @@ use aish_client::StreamResponse;
 use aish_protocol::models::ContentItem;
 use aish_protocol::models::ReasoningItemContent;
 use aish_protocol::models::ResponseItem;
+use aish_protocol::protocol::TokenUsage;
 use eventsource_stream::Eventsource;
 use futures::Stream;
 use futures::StreamExt;
+use serde::Deserialize;
 use std::collections::HashMap;
 use std::collections::HashSet;
 use std::time::Duration;
@@ -17,21 +19,37 @@ use tokio::time::timeout;
 use tracing::debug;
 use tracing::trace;
 
+/// How long to wait for the usage chunk after `finish_reason` before
+/// completing without it.
+const USAGE_GRACE_PERIOD: Duration = Duration::from_secs(2);
+
 pub(crate) fn spawn_chat_stream(
     stream_response: StreamResponse,
     idle_timeout: Duration,
+    usage_requested: bool,
 ) -> ResponseStream {
     let (tx_event, rx_event) = mpsc::channel::<Result<ResponseEvent, ApiError>>(1600);
     tokio::spawn(async move {
-        process_chat_sse(stream_response.bytes, tx_event, idle_timeout).await;
+        process_chat_sse(
+            stream_response.bytes,
+            tx_event,
+            idle_timeout,
+            usage_requested,
+        )
+        .await;
     });
     ResponseStream { rx_event }
 }
 
+/// Parses a Chat Completions stream into [`ResponseEvent`]s. When
+/// `usage_requested` (the request set `stream_options.include_usage`),
+/// `Completed` waits up to [`USAGE_GRACE_PERIOD`] after `finish_reason` for
+/// the usage chunk; otherwise it is sent as soon as a choice finishes.
 pub async fn process_chat_sse<S>(
     stream: S,
     tx_event: mpsc::Sender<Result<ResponseEvent, ApiError>>,
     idle_timeout: Duration,
+    usage_requested: bool,
 ) where
     S: Stream<Item = Result<bytes::Bytes, aish_client::TransportError>> + Unpin,
 {
@@ -52,11 +70,19 @@ pub async fn process_chat_sse<S>(
     let mut last_tool_call_index: Option<usize> = None;
     let mut assistant_item: Option<ResponseItem> = None;
     let mut reasoning_item: Option<ResponseItem> = None;
-    let mut completed_sent = false;
+    let mut token_usage: Option<TokenUsage> = None;
+    // Set once a choice finishes; usage arrives in a chunk after the one
+    // carrying `finish_reason`.
+    let mut finish_seen = false;
 
     loop {
         let _start = Instant::now();
-        let response = timeout(idle_timeout, stream.next()).await;
+        let wait = if finish_seen {
+            idle_timeout.min(USAGE_GRACE_PERIOD)
+        } else {
+            idle_timeout
+        };
+        let response = timeout(wait, stream.next()).await;
         let sse = match response {
             Ok(Some(Ok(sse))) => sse,
             Ok(Some(Err(e))) => {
@@ -65,25 +91,13 @@ pub async fn process_chat_sse<S>(
                 return;
             }
             Ok(None) => {
-                if let Some(reasoning) = reasoning_item {
-                    let _ = tx_event
-                        .send(Ok(ResponseEvent::OutputItemDone(reasoning)))
-                        .await;
-                }
-
-                if let Some(assistant) = assistant_item {
-                    let _ = tx_event
-                        .send(Ok(ResponseEvent::OutputItemDone(assistant)))
-                        .await;
-                }
-                if !completed_sent {
-                    let _ = tx_event
-                        .send(Ok(ResponseEvent::Completed {
-                            response_id: String::new(),
-                            token_usage: None,
-                        }))
-                        .await;
-                }
+                complete_response(&tx_event, reasoning_item, assistant_item, token_usage).await;
+                return;
+            }
+            // The usage chunk never came, or the provider keeps the
+            // connection open instead of sending `[DONE]`.
+            Err(_) if finish_seen => {
+                complete_response(&tx_event, reasoning_item, assistant_item, token_usage).await;
                 return;
             }
             Err(_) => {
@@ -101,25 +115,7 @@ pub async fn process_chat_sse<S>(
         }
 
         if sse.data.trim() == "[DONE]" {
-            if let Some(reasoning) = reasoning_item {
-                let _ = tx_event
-                    .send(Ok(ResponseEvent::OutputItemDone(reasoning)))
-                    .await;
-            }
-
-            if let Some(assistant) = assistant_item {
-                let _ = tx_event
-                    .send(Ok(ResponseEvent::OutputItemDone(assistant)))
-                    .await;
-            }
-            if !completed_sent {
-                let _ = tx_event
-                    .send(Ok(ResponseEvent::Completed {
-                        response_id: String::new(),
-                        token_usage: None,
-                    }))
-                    .await;
-            }
+            complete_response(&tx_event, reasoning_item, assistant_item, token_usage).await;
             break;
         }
 
@@ -134,9 +130,22 @@ pub async fn process_chat_sse<S>(
             }
         };
 
-        let Some(choices) = value.get("choices").and_then(|c| c.as_array()) else {
-            continue;
-        };
+        let mut chunk_has_usage = false;
+        if let Some(usage) = value.get("usage").filter(|usage| !usage.is_null()) {
+            match serde_json::from_value::<ChatUsage>(usage.clone()) {
+                Ok(usage) => {
+                    token_usage = Some(usage.into());
+                    chunk_has_usage = true;
+                }
+                Err(err) => debug!("Failed to parse ChatCompletions usage: {err}"),
+            }
+        }
+
+        let choices = value
+            .get("choices")
+            .and_then(|c| c.as_array())
+            .map(Vec::as_slice)
+            .unwrap_or_default();
 
         for choice in choices {
             if let Some(delta) = choice.get("delta") {
@@ -251,15 +260,7 @@ pub async fn process_chat_sse<S>(
                         .send(Ok(ResponseEvent::OutputItemDone(assistant)))
                         .await;
                 }
-                if !completed_sent {
-                    let _ = tx_event
-                        .send(Ok(ResponseEvent::Completed {
-                            response_id: String::new(),
-                            token_usage: None,
-                        }))
-                        .await;
-                    completed_sent = true;
-                }
+                finish_seen = true;
                 continue;
             }
 
@@ -297,8 +298,93 @@ pub async fn process_chat_sse<S>(
                     };
                     let _ = tx_event.send(Ok(ResponseEvent::OutputItemDone(item))).await;
                 }
+                finish_seen = true;
             }
         }
+
+        // Without a requested usage chunk the finished choice is the last one
+        // that matters, and with one the usage chunk is; don't wait for a
+        // `[DONE]` the provider may never send.
+        if finish_seen && (chunk_has_usage || !usage_requested) {
+            complete_response(&tx_event, reasoning_item, assistant_item, token_usage).await;
+            return;
+        }
+    }
+}
+
+/// Flushes the items still being streamed and sends `Completed`.
+async fn complete_response(
+    tx_event: &mpsc::Sender<Result<ResponseEvent, ApiError>>,
+    reasoning_item: Option<ResponseItem>,
+    assistant_item: Option<ResponseItem>,
+    token_usage: Option<TokenUsage>,
+) {
+    if let Some(reasoning) = reasoning_item {
+        let _ = tx_event
+            .send(Ok(ResponseEvent::OutputItemDone(reasoning)))
+            .await;
+    }
+
+    if let Some(assistant) = assistant_item {
+        let _ = tx_event
+            .send(Ok(ResponseEvent::OutputItemDone(assistant)))
+            .await;
+    }
+    let _ = tx_event
+        .send(Ok(ResponseEvent::Completed {
+            response_id: String::new(),
+            token_usage,
+        }))
+        .await;
+}
+
+/// `usage` of a Chat Completions stream, sent with the last chunk.
+/// Providers report prompt cache hits in different places: OpenAI and most
+/// compatible APIs under `prompt_tokens_details`, DeepSeek as
+/// `prompt_cache_hit_tokens` and Moonshot as a top-level `cached_tokens`.
+#[derive(Debug, Deserialize)]
+struct ChatUsage {
+    #[serde(default)]
+    prompt_tokens: i64,
+    #[serde(default)]
+    completion_tokens: i64,
+    total_tokens: Option<i64>,
+    prompt_tokens_details: Option<PromptTokensDetails>,
+    completion_tokens_details: Option<CompletionTokensDetails>,
+    prompt_cache_hit_tokens: Option<i64>,
+    cached_tokens: Option<i64>,
+}
+
+#[derive(Debug, Deserialize)]
+struct PromptTokensDetails {
+    cached_tokens: Option<i64>,
+}
+
+#[derive(Debug, Deserialize)]
+struct CompletionTokensDetails {
+    reasoning_tokens: Option<i64>,
+}
+
+impl From<ChatUsage> for TokenUsage {
+    fn from(val: ChatUsage) -> Self {
+        let cached_input_tokens = val
+            .prompt_tokens_details
+            .and_then(|details| details.cached_tokens)
+            .or(val.prompt_cache_hit_tokens)
+            .or(val.cached_tokens)
+            .unwrap_or(0);
+        TokenUsage {
+            input_tokens: val.prompt_tokens,
+            cached_input_tokens,
+            output_tokens: val.completion_tokens,
+            reasoning_output_tokens: val
+                .completion_tokens_details
+                .and_then(|details| details.reasoning_tokens)
+                .unwrap_or(0),
+            total_tokens: val
+                .total_tokens
+                .unwrap_or(val.prompt_tokens + val.completion_tokens),
+        }
     }
 }
 
@@ -384,7 +470,12 @@ mod tests {
         let reader = ReaderStream::new(std::io::Cursor::new(body.to_string()))
             .map_err(|err| aish_client::TransportError::Network(err.to_string()));
         let (tx, mut rx) = mpsc::channel::<Result<ResponseEvent, ApiError>>(16);
-        tokio::spawn(process_chat_sse(reader, tx, Duration::from_millis(1000)));
+        tokio::spawn(process_chat_sse(
+            reader,
+            tx,
+            Duration::from_millis(1000),
+            true,
+        ));
 
         let mut out = Vec::new();
         while let Some(ev) = rx.recv().await {
@@ -393,6 +484,31 @@ mod tests {
         out
     }
 
+    /// Like `collect_events`, but the connection stays open after `body` and
+    /// idles for far longer than the usage grace period.
+    async fn collect_events_from_open_stream(
+        body: &str,
+        usage_requested: bool,
+    ) -> Vec<Result<ResponseEvent, ApiError>> {
+        let stream = futures::stream::iter([Ok::<_, aish_client::TransportError>(
+            bytes::Bytes::from(body.to_string()),
+        )])
+        .chain(futures::stream::pending());
+        let (tx, mut rx) = mpsc::channel::<Result<ResponseEvent, ApiError>>(16);
+        tokio::spawn(process_chat_sse(
+            stream,
+            tx,
+            Duration::from_secs(300),
+            usage_requested,
+        ));
+
+        let mut out = Vec::new();
+        while let Some(ev) = rx.recv().await {
+            out.push(ev);
+        }
+        out
+    }
+
     #[tokio::test]
     async fn concatenates_tool_call_arguments_across_deltas() {
         let delta_name = json!({
@@ -679,4 +795,132 @@ mod tests {
         }));
         assert_matches!(events.last(), Some(ResponseEvent::Completed { .. }));
     }
+
+    #[tokio::test]
+    async fn completed_carries_usage_sent_after_finish_reason() {
+        let delta = json!({"choices": [{"delta": {"content": "hi"}}]});
+        let finish_stop = json!({"choices": [{"finish_reason": "stop"}]});
+        let usage = json!({
+            "choices": [],
+            "usage": {
+                "prompt_tokens": 1200,
+                "completion_tokens": 30,
+                "total_tokens": 1230,
+                "prompt_tokens_details": {"cached_tokens": 1024},
+                "completion_tokens_details": {"reasoning_tokens": 12}
+            }
+        });
+
+        let mut body = build_body(&[delta, finish_stop, usage]);
+        body.push_str("event: message\ndata: [DONE]\n\n");
+        let events = collect_events(&body).await;
+
+        let Some(ResponseEvent::Completed {
+            token_usage: Some(usage),
+            ..
+        }) = events.last()
+        else {
+            panic!("expected Completed with usage, got {events:?}");
+        };
+        assert_eq!(usage.input_tokens, 1200);
+        assert_eq!(usage.cached_input_tokens, 1024);
+        assert_eq!(usage.output_tokens, 30);
+        assert_eq!(usage.reasoning_output_tokens, 12);
+        assert_eq!(usage.total_tokens, 1230);
+        assert_eq!(
+            events
+                .iter()
+                .filter(|ev| matches!(ev, ResponseEvent::Completed { .. }))
+                .count(),
+            1
+        );
+    }
+
+    #[tokio::test]
+    async fn reads_deepseek_cache_hit_tokens() {
+        let usage = json!({
+            "choices": [{"delta": {"content": "hi"}, "finish_reason": "stop"}],
+            "usage": {
+                "prompt_tokens": 800,
+                "completion_tokens": 5,
+                "prompt_cache_hit_tokens": 640,
+                "prompt_cache_miss_tokens": 160
+            }
+        });
+
+        let events = collect_events(&build_body(&[usage])).await;
+
+        let Some(ResponseEvent::Completed {
+            token_usage: Some(usage),
+            ..
+        }) = events.last()
+        else {
+            panic!("expected Completed with usage, got {events:?}");
+        };
+        assert_eq!(usage.cached_input_tokens, 640);
+        assert_eq!(usage.total_tokens, 805);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn completes_after_usage_when_stream_stays_open() {
+        let finish_stop =
+            json!({"choices": [{"delta": {"content": "hi"}, "finish_reason": "stop"}]});
+        let usage = json!({"choices": [], "usage": {"prompt_tokens": 10, "completion_tokens": 2}});
+
+        let events =
+            collect_events_from_open_stream(&build_body(&[finish_stop, usage]), true).await;
+
+        assert_matches!(
+            events.last(),
+            Some(Ok(ResponseEvent::Completed {
+                token_usage: Some(usage),
+                ..
+            })) if usage.total_tokens == 12
+        );
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn missing_usage_completes_after_the_grace_period() {
+        let finish_stop =
+            json!({"choices": [{"delta": {"content": "hi"}, "finish_reason": "stop"}]});
+
+        let started = Instant::now();
+        let events = collect_events_from_open_stream(&build_body(&[finish_stop]), true).await;
+
+        assert_matches!(
+            events.last(),
+            Some(Ok(ResponseEvent::Completed {
+                token_usage: None,
+                ..
+            }))
+        );
+        assert!((USAGE_GRACE_PERIOD..Duration::from_secs(300)).contains(&started.elapsed()));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn completes_at_finish_reason_when_usage_was_not_requested() {
+        let finish_stop =
+            json!({"choices": [{"delta": {"content": "hi"}, "finish_reason": "stop"}]});
+
+        let started = Instant::now();
+        let events = collect_events_from_open_stream(&build_body(&[finish_stop]), false).await;
+
+        assert_matches!(
+            events.last(),
+            Some(Ok(ResponseEvent::Completed {
+                token_usage: None,
+                ..
+            }))
+        );
+        assert!(started.elapsed() < USAGE_GRACE_PERIOD);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn idle_timeout_before_finish_reason_is_an_error() {
+        let delta = json!({"choices": [{"delta": {"content": "hi"}}]});
+
+        let events = collect_events_from_open_stream(&build_body(&[delta]), true).await;
+
+        assert_matches!(events.last(), Some(Err(ApiError::Stream(_))));
+    }
 }
@@ -0,0 +1,530 @@
+use crate::common::ResponseEvent;
+use crate::common::ResponseStream;
+use crate::error::ApiError;
+use crate::requests::messages::REDACTED_THINKING_PREFIX;
+use crate::requests::messages::THINKING_SIGNATURE_PREFIX;
+use aish_client::StreamResponse;
+use aish_protocol::models::ContentItem;
+use aish_protocol::models::ReasoningItemContent;
+use aish_protocol::models::ResponseItem;
+use aish_protocol::protocol::TokenUsage;
+use eventsource_stream::Eventsource;
+use futures::Stream;
+use futures::StreamExt;
+use serde::Deserialize;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tokio::time::timeout;
+use tracing::debug;
+use tracing::trace;
+
+pub(crate) fn spawn_messages_stream(
+    stream_response: StreamResponse,
+    idle_timeout: Duration,
+) -> ResponseStream {
+    let (tx_event, rx_event) = mpsc::channel::<Result<ResponseEvent, ApiError>>(1600);
+    tokio::spawn(async move {
+        process_messages_sse(stream_response.bytes, tx_event, idle_timeout).await;
+    });
+    ResponseStream { rx_event }
+}
+
+#[derive(Debug, Deserialize)]
+struct SseEvent {
+    #[serde(rename = "type")]
+    kind: String,
+    index: Option<usize>,
+    message: Option<MessageStart>,
+    content_block: Option<Value>,
+    delta: Option<Value>,
+    usage: Option<Usage>,
+    error: Option<Error>,
+}
+
+#[derive(Debug, Deserialize)]
+struct MessageStart {
+    id: String,
+    usage: Option<Usage>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Error {
+    r#type: Option<String>,
+    message: Option<String>,
+}
+
+/// Token counts as reported by `message_start` and updated by
+/// `message_delta`. `input_tokens` excludes tokens read from or written to
+/// the prompt cache.
+#[derive(Debug, Default, Deserialize)]
+struct Usage {
+    input_tokens: Option<i64>,
+    cache_creation_input_tokens: Option<i64>,
+    cache_read_input_tokens: Option<i64>,
+    output_tokens: Option<i64>,
+}
+
+impl Usage {
+    fn merge(&mut self, other: Usage) {
+        self.input_tokens = other.input_tokens.or(self.input_tokens);
+        self.cache_creation_input_tokens = other
+            .cache_creation_input_tokens
+            .or(self.cache_creation_input_tokens);
+        self.cache_read_input_tokens = other
+            .cache_read_input_tokens
+            .or(self.cache_read_input_tokens);
+        self.output_tokens = other.output_tokens.or(self.output_tokens);
+    }
+}
+
+impl From<Usage> for TokenUsage {
+    fn from(val: Usage) -> Self {
+        let cached_input_tokens = val.cache_read_input_tokens.unwrap_or(0);
+        let input_tokens = val.input_tokens.unwrap_or(0)
+            + val.cache_creation_input_tokens.unwrap_or(0)
+            + cached_input_tokens;
+        let output_tokens = val.output_tokens.unwrap_or(0);
+        TokenUsage {
+            input_tokens,
+            cached_input_tokens,
+            output_tokens,
+            reasoning_output_tokens: 0,
+            total_tokens: input_tokens + output_tokens,
+        }
+    }
+}
+
+/// A content block between its `content_block_start` and
+/// `content_block_stop`.
+#[derive(Debug)]
+enum Block {
+    Text(String),
+    Thinking {
+        thinking: String,
+        signature: String,
+    },
+    RedactedThinking(String),
+    ToolUse {
+        id: String,
+        name: String,
+        input: String,
+    },
+}
+
+impl Block {
+    fn into_item(self) -> ResponseItem {
+        match self {
+            Block::Text(text) => ResponseItem::Message {
+                id: None,
+                role: "assistant".to_string(),
+                content: vec![ContentItem::OutputText { text }],
+            },
+            Block::Thinking {
+                thinking,
+                signature,
+            } => ResponseItem::Reasoning {
+                id: String::new(),
+                summary: Vec::new(),
+                content: Some(vec![ReasoningItemContent::ReasoningText { text: thinking }]),
+                encrypted_content: Some(format!("{THINKING_SIGNATURE_PREFIX}{signature}")),
+            },
+            Block::RedactedThinking(data) => ResponseItem::Reasoning {
+                id: String::new(),
+                summary: Vec::new(),
+                content: None,
+                encrypted_content: Some(format!("{REDACTED_THINKING_PREFIX}{data}")),
+            },
+            Block::ToolUse { id, name, input } => ResponseItem::FunctionCall {
+                id: None,
+                name,
+                // A tool without parameters streams no input at all.
+                arguments: if input.is_empty() {
+                    "{}".to_string()
+                } else {
+                    input
+                },
+                call_id: id,
+            },
+        }
+    }
+}
+
+pub async fn process_messages_sse<S>(
+    stream: S,
+    tx_event: mpsc::Sender<Result<ResponseEvent, ApiError>>,
+    idle_timeout: Duration,
+) where
+    S: Stream<Item = Result<bytes::Bytes, aish_client::TransportError>> + Unpin,
+{
+    let mut stream = stream.eventsource();
+    let mut response_id = String::new();
+    let mut usage = Usage::default();
+    let mut blocks: HashMap<usize, Block> = HashMap::new();
+    let mut response_error: Option<ApiError> = None;
+
+    loop {
+        let sse = match timeout(idle_timeout, stream.next()).await {
+            Ok(Some(Ok(sse))) => sse,
+            Ok(Some(Err(e))) => {
+                debug!("Failed to read SSE stream: {e}");
+                let _ = tx_event.send(Err(ApiError::Stream(e.to_string()))).await;
+                return;
+            }
+            Ok(None) => {
+                let error = response_error
+                    .unwrap_or(ApiError::Stream("stream closed before message_stop".into()));
+                let _ = tx_event.send(Err(error)).await;
+                return;
+            }
+            Err(_) => {
+                let _ = tx_event
+                    .send(Err(ApiError::Stream("idle timeout waiting for SSE".into())))
+                    .await;
+                return;
+            }
+        };
+
+        trace!("SSE event: {}", sse.data);
+
+        let event: SseEvent = match serde_json::from_str(&sse.data) {
+            Ok(event) => event,
+            Err(err) => {
+                debug!(
+                    "Failed to parse Messages SSE event: {err}, data: {}",
+                    &sse.data
+                );
+                continue;
+            }
+        };
+
+        let mut events = Vec::new();
+        match event.kind.as_str() {
+            "message_start" => {
+                if let Some(message) = event.message {
+                    response_id = message.id;
+                    if let Some(start_usage) = message.usage {
+                        usage.merge(start_usage);
+                    }
+                }
+                events.push(ResponseEvent::Created);
+            }
+            "content_block_start" => {
+                let (Some(index), Some(block)) = (event.index, event.content_block) else {
+                    continue;
+                };
+                let str_field = |name: &str| {
+                    block
+                        .get(name)
+                        .and_then(Value::as_str)
+                        .unwrap_or_default()
+                        .to_string()
+                };
+                let block = match block.get("type").and_then(Value::as_str) {
+                    Some("text") => {
+                        events.push(ResponseEvent::OutputItemAdded(ResponseItem::Message {
+                            id: None,
+                            role: "assistant".to_string(),
+                            content: Vec::new(),
+                        }));
+                        let text = str_field("text");
+                        if !text.is_empty() {
+                            events.push(ResponseEvent::OutputTextDelta(text.clone()));
+                        }
+                        Block::Text(text)
+                    }
+                    Some("thinking") => {
+                        events.push(ResponseEvent::OutputItemAdded(ResponseItem::Reasoning {
+                            id: String::new(),
+                            summary: Vec::new(),
+                            content: Some(Vec::new()),
+                            encrypted_content: None,
+                        }));
+                        Block::Thinking {
+                            thinking: str_field("thinking"),
+                            signature: str_field("signature"),
+                        }
+                    }
+                    Some("redacted_thinking") => Block::RedactedThinking(str_field("data")),
+                    Some("tool_use") => Block::ToolUse {
+                        id: str_field("id"),
+                        name: str_field("name"),
+                        input: String::new(),
+                    },
+                    other => {
+                        debug!("Ignoring unknown Messages content block type: {other:?}");
+                        continue;
+                    }
+                };
+                blocks.insert(index, block);
+            }
+            "content_block_delta" => {
+                let (Some(index), Some(delta)) = (event.index, event.delta) else {
+                    continue;
+                };
+                let Some(block) = blocks.get_mut(&index) else {
+                    continue;
+                };
+                let str_field =
+                    |name: &str| delta.get(name).and_then(Value::as_str).unwrap_or_default();
+                match (delta.get("type").and_then(Value::as_str), block) {
+                    (Some("text_delta"), Block::Text(text)) => {
+                        let delta = str_field("text");
+                        text.push_str(delta);
+                        events.push(ResponseEvent::OutputTextDelta(delta.to_string()));
+                    }
+                    (Some("thinking_delta"), Block::Thinking { thinking, .. }) => {
+                        let delta = str_field("thinking");
+                        thinking.push_str(delta);
+                        events.push(ResponseEvent::ReasoningContentDelta {
+                            delta: delta.to_string(),
+                            content_index: 0,
+                        });
+                    }
+                    (Some("signature_delta"), Block::Thinking { signature, .. }) => {
+                        signature.push_str(str_field("signature"));
+                    }
+                    (Some("input_json_delta"), Block::ToolUse { input, .. }) => {
+                        input.push_str(str_field("partial_json"));
+                    }
+                    (kind, _) => debug!("Ignoring Messages delta {kind:?} for block {index}"),
+                }
+            }
+            "content_block_stop" => {
+                if let Some(block) = event.index.and_then(|index| blocks.remove(&index)) {
+                    events.push(ResponseEvent::OutputItemDone(block.into_item()));
+                }
+            }
+            "message_delta" => {
+                if let Some(delta_usage) = event.usage {
+                    usage.merge(delta_usage);
+                }
+                let stop_reason = event
+                    .delta
+                    .as_ref()
+                    .and_then(|delta| delta.get("stop_reason"))
+                    .and_then(Value::as_str);
+                if stop_reason == Some("model_context_window_exceeded") {
+                    response_error = Some(ApiError::ContextWindowExceeded);
+                }
+            }
+            "message_stop" => {
+                let event = match response_error.take() {
+                    Some(error) => Err(error),
+                    None => Ok(ResponseEvent::Completed {
+                        response_id: std::mem::take(&mut response_id),
+                        token_usage: Some(std::mem::take(&mut usage).into()),
+                    }),
+                };
+                let _ = tx_event.send(event).await;
+                return;
+            }
+            "error" => {
+                let error = event
+                    .error
+                    .map(map_error)
+                    .unwrap_or(ApiError::Stream("error event received".into()));
+                let _ = tx_event.send(Err(error)).await;
+                return;
+            }
+            _ => {}
+        }
+
+        for event in events {
+            if tx_event.send(Ok(event)).await.is_err() {
+                return;
+            }
+        }
+    }
+}
+
+fn map_error(error: Error) -> ApiError {
+    let message = error.message.unwrap_or_default();
+    match error.r#type.as_deref() {
+        Some("overloaded_error" | "api_error" | "rate_limit_error") => ApiError::Retryable {
+            message,
+            delay: None,
+        },
+        Some("invalid_request_error") if message.contains("prompt is too long") => {
+            ApiError::ContextWindowExceeded
+        }
+        _ => ApiError::Stream(message),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use assert_matches::assert_matches;
+    use futures::TryStreamExt;
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+    use tokio_util::io::ReaderStream;
+
+    fn build_body(events: &[Value]) -> String {
+        let mut body = String::new();
+        for e in events {
+            let kind = e["type"].as_str().unwrap_or_default();
+            body.push_str(&format!("event: {kind}\ndata: {e}\n\n"));
+        }
+        body
+    }
+
+    async fn collect_events(body: &str) -> Vec<Result<ResponseEvent, ApiError>> {
+        let reader = ReaderStream::new(std::io::Cursor::new(body.to_string()))
+            .map_err(|err| aish_client::TransportError::Network(err.to_string()));
+        let (tx, mut rx) = mpsc::channel::<Result<ResponseEvent, ApiError>>(16);
+        tokio::spawn(process_messages_sse(
+            reader,
+            tx,
+            Duration::from_millis(1000),
+        ));
+
+        let mut out = Vec::new();
+        while let Some(ev) = rx.recv().await {
+            out.push(ev);
+        }
+        out
+    }
+
+    fn message_start() -> Value {
+        json!({
+            "type": "message_start",
+            "message": {
+                "id": "msg_1",
+                "usage": {
+                    "input_tokens": 10,
+                    "cache_creation_input_tokens": 20,
+                    "cache_read_input_tokens": 100,
+                    "output_tokens": 1,
+                },
+            },
+        })
+    }
+
+    #[tokio::test]
+    async fn parses_thinking_text_and_tool_use_blocks() {
+        let body = build_body(&[
+            message_start(),
+            json!({"type": "content_block_start", "index": 0,
+                   "content_block": {"type": "thinking", "thinking": ""}}),
+            json!({"type": "content_block_delta", "index": 0,
+                   "delta": {"type": "thinking_delta", "thinking": "let me look"}}),
+            json!({"type": "content_block_delta", "index": 0,
+                   "delta": {"type": "signature_delta", "signature": "sig"}}),
+            json!({"type": "content_block_stop", "index": 0}),
+            json!({"type": "content_block_start", "index": 1,
+                   "content_block": {"type": "text", "text": ""}}),
+            json!({"type": "content_block_delta", "index": 1,
+                   "delta": {"type": "text_delta", "text": "Listing"}}),
+            json!({"type": "content_block_stop", "index": 1}),
+            json!({"type": "ping"}),
+            json!({"type": "content_block_start", "index": 2,
+                   "content_block": {"type": "tool_use", "id": "toolu_1", "name": "shell", "input": {}}}),
+            json!({"type": "content_block_delta", "index": 2,
+                   "delta": {"type": "input_json_delta", "partial_json": "{\"command\":"}}),
+            json!({"type": "content_block_delta", "index": 2,
+                   "delta": {"type": "input_json_delta", "partial_json": "[\"ls\"]}"}}),
+            json!({"type": "content_block_stop", "index": 2}),
+            json!({"type": "message_delta", "delta": {"stop_reason": "tool_use"},
+                   "usage": {"output_tokens": 42}}),
+            json!({"type": "message_stop"}),
+        ]);
+
+        let events: Vec<ResponseEvent> = collect_events(&body)
+            .await
+            .into_iter()
+            .map(|ev| ev.expect("stream error"))
+            .collect();
+
+        assert_matches!(
+            &events[..],
+            [
+                ResponseEvent::Created,
+                ResponseEvent::OutputItemAdded(ResponseItem::Reasoning { .. }),
+                ResponseEvent::ReasoningContentDelta { delta: thinking_delta, .. },
+                ResponseEvent::OutputItemDone(ResponseItem::Reasoning {
+                    content: Some(thinking),
+                    encrypted_content: Some(signature),
+                    ..
+                }),
+                ResponseEvent::OutputItemAdded(ResponseItem::Message { .. }),
+                ResponseEvent::OutputTextDelta(text_delta),
+                ResponseEvent::OutputItemDone(ResponseItem::Message { .. }),
+                ResponseEvent::OutputItemDone(ResponseItem::FunctionCall {
+                    name,
+                    arguments,
+                    call_id,
+                    ..
+                }),
+                ResponseEvent::Completed { response_id, token_usage: Some(usage) },
+            ] if thinking_delta == "let me look"
+                && thinking == &vec![ReasoningItemContent::ReasoningText {
+                    text: "let me look".to_string(),
+                }]
+                && signature == "messages-thinking:sig"
+                && text_delta == "Listing"
+                && name == "shell"
+                && arguments == "{\"command\":[\"ls\"]}"
+                && call_id == "toolu_1"
+                && response_id == "msg_1"
+                && usage.input_tokens == 130
+                && usage.cached_input_tokens == 100
+                && usage.output_tokens == 42
+                && usage.total_tokens == 172
+        );
+    }
+
+    #[tokio::test]
+    async fn tool_without_input_gets_empty_object_arguments() {
+        let body = build_body(&[
+            message_start(),
+            json!({"type": "content_block_start", "index": 0,
+                   "content_block": {"type": "tool_use", "id": "toolu_1", "name": "list", "input": {}}}),
+            json!({"type": "content_block_stop", "index": 0}),
+            json!({"type": "message_stop"}),
+        ]);
+
+        let events = collect_events(&body).await;
+        assert_matches!(
+            &events[1],
+            Ok(ResponseEvent::OutputItemDone(ResponseItem::FunctionCall { arguments, .. }))
+                if arguments == "{}"
+        );
+    }
+
+    #[tokio::test]
+    async fn error_events_are_mapped() {
+        let body = build_body(&[
+            message_start(),
+            json!({"type": "error", "error": {"type": "overloaded_error", "message": "Overloaded"}}),
+        ]);
+        let events = collect_events(&body).await;
+        assert_matches!(
+            events.last(),
+            Some(Err(ApiError::Retryable { message, .. })) if message == "Overloaded"
+        );
+
+        let body = build_body(&[
+            message_start(),
+            json!({"type": "error", "error": {
+                "type": "invalid_request_error",
+                "message": "prompt is too long: 210000 tokens > 200000 maximum",
+            }}),
+        ]);
+        let events = collect_events(&body).await;
+        assert_matches!(events.last(), Some(Err(ApiError::ContextWindowExceeded)));
+    }
+
+    #[tokio::test]
+    async fn stream_without_message_stop_is_an_error() {
+        let body = build_body(&[message_start()]);
+        let events = collect_events(&body).await;
+        assert_eq!(events.len(), 2);
+        assert_matches!(
+            &events[1],
+            Err(ApiError::Stream(message)) if message == "stream closed before message_stop"
+        );
+    }
+}
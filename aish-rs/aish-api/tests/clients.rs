@@ -4,6 +4,7 @@ use std::time::Duration;
 
 use aish_api::AuthProvider;
 use aish_api::ChatClient;
+use aish_api::PromptCacheHints;
 use aish_api::Provider;
 use aish_api::ResponsesClient;
 use aish_api::ResponsesOptions;
@@ -118,6 +119,7 @@ fn provider(name: &str, wire: WireApi) -> Provider {
         base_url: "https://example.com/v1".to_string(),
         query_params: None,
         wire,
+        prompt_cache: PromptCacheHints::None,
         headers: HeaderMap::new(),
         retry: aish_api::provider::RetryConfig {
             max_attempts: 1,
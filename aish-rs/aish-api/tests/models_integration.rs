@@ -1,5 +1,6 @@
 use aish_api::AuthProvider;
 use aish_api::ModelsClient;
+use aish_api::provider::PromptCacheHints;
 use aish_api::provider::Provider;
 use aish_api::provider::RetryConfig;
 use aish_api::provider::WireApi;
@@ -34,6 +35,7 @@ fn provider(base_url: &str) -> Provider {
         base_url: base_url.to_string(),
         query_params: None,
         wire: WireApi::Responses,
+        prompt_cache: PromptCacheHints::None,
         headers: HeaderMap::new(),
         retry: RetryConfig {
             max_attempts: 1,
@@ -3,6 +3,7 @@ mod pid_tracker;
 #[cfg(target_os = "macos")]
 mod seatbelt;
 
+use std::path::Path;
 use std::path::PathBuf;
 
 use aish_common::CliConfigOverrides;
@@ -10,12 +11,19 @@ use aish_core::config::Config;
 use aish_core::config::ConfigOverrides;
 use aish_core::exec_env::create_env;
 use aish_core::landlock::spawn_command_under_linux_sandbox;
+use aish_core::protocol::SandboxPolicy;
+use aish_core::sandboxing::container::container_name;
+use aish_core::sandboxing::container::spawn_command_under_container;
 #[cfg(target_os = "macos")]
 use aish_core::seatbelt::spawn_command_under_seatbelt;
 use aish_core::spawn::StdioPolicy;
 use aish_protocol::config_types::SandboxMode;
+use aish_utils_absolute_path::AbsolutePathBuf;
 
 use crate::LandlockCommand;
+use crate::SandboxBackendArg;
+use crate::SandboxBackendCommand;
+use crate::SandboxPathArgs;
 use crate::SeatbeltCommand;
 use crate::exit_status::handle_exit_status;
 
@@ -30,11 +38,13 @@ pub async fn run_command_under_seatbelt(
     let SeatbeltCommand {
         full_auto,
         log_denials,
+        sandbox_paths,
         config_overrides,
         command,
     } = command;
     run_command_under_sandbox(
         full_auto,
+        sandbox_paths,
         command,
         config_overrides,
         aish_linux_sandbox_exe,
@@ -58,11 +68,13 @@ pub async fn run_command_under_landlock(
 ) -> anyhow::Result<()> {
     let LandlockCommand {
         full_auto,
+        sandbox_paths,
         config_overrides,
         command,
     } = command;
     run_command_under_sandbox(
         full_auto,
+        sandbox_paths,
         command,
         config_overrides,
         aish_linux_sandbox_exe,
@@ -72,21 +84,54 @@ pub async fn run_command_under_landlock(
     .await
 }
 
+/// Entry point for `aish sandbox --backend <platform|container> -- COMMAND`.
+pub async fn run_command_under_backend(
+    command: SandboxBackendCommand,
+    aish_linux_sandbox_exe: Option<PathBuf>,
+) -> anyhow::Result<()> {
+    let SandboxBackendCommand {
+        backend,
+        full_auto,
+        sandbox_paths,
+        config_overrides,
+        command,
+    } = command;
+    let sandbox_type = match backend {
+        #[cfg(target_os = "macos")]
+        SandboxBackendArg::Platform => SandboxType::Seatbelt,
+        #[cfg(not(target_os = "macos"))]
+        SandboxBackendArg::Platform => SandboxType::Landlock,
+        SandboxBackendArg::Container => SandboxType::Container,
+    };
+    run_command_under_sandbox(
+        full_auto,
+        sandbox_paths,
+        command,
+        config_overrides,
+        aish_linux_sandbox_exe,
+        sandbox_type,
+        false,
+    )
+    .await
+}
+
 enum SandboxType {
     #[cfg(target_os = "macos")]
     Seatbelt,
     Landlock,
+    Container,
 }
 
 async fn run_command_under_sandbox(
     full_auto: bool,
+    sandbox_paths: SandboxPathArgs,
     command: Vec<String>,
     config_overrides: CliConfigOverrides,
     aish_linux_sandbox_exe: Option<PathBuf>,
     sandbox_type: SandboxType,
     log_denials: bool,
 ) -> anyhow::Result<()> {
-    let sandbox_mode = create_sandbox_mode(full_auto);
+    let sandbox_mode = create_sandbox_mode(full_auto || !sandbox_paths.is_empty());
     let config = Config::load_with_cli_overrides_and_harness_overrides(
         config_overrides
             .parse_overrides()
@@ -106,6 +151,7 @@ async fn run_command_under_sandbox(
     // sandbox policy. In the future, we could add a CLI option to set them
     // separately.
     let sandbox_policy_cwd = cwd.clone();
+    let sandbox_policy = apply_sandbox_path_args(config.sandbox_policy.get(), sandbox_paths, &cwd)?;
 
     let stdio_policy = StdioPolicy::Inherit;
     let env = create_env(&config.shell_environment_policy);
@@ -121,7 +167,7 @@ async fn run_command_under_sandbox(
             spawn_command_under_seatbelt(
                 command,
                 cwd,
-                config.sandbox_policy.get(),
+                &sandbox_policy,
                 sandbox_policy_cwd.as_path(),
                 stdio_policy,
                 env,
@@ -137,13 +183,26 @@ async fn run_command_under_sandbox(
                 aish_linux_sandbox_exe,
                 command,
                 cwd,
-                config.sandbox_policy.get(),
+                &sandbox_policy,
                 sandbox_policy_cwd.as_path(),
                 stdio_policy,
                 env,
             )
             .await?
         }
+        SandboxType::Container => {
+            spawn_command_under_container(
+                &config.sandbox_container,
+                command,
+                cwd,
+                &sandbox_policy,
+                sandbox_policy_cwd.as_path(),
+                stdio_policy,
+                env,
+                &container_name("debug"),
+            )
+            .await?
+        }
     };
 
     #[cfg(target_os = "macos")]
@@ -169,6 +228,36 @@ async fn run_command_under_sandbox(
     handle_exit_status(status);
 }
 
+/// Layers `--writable-root` and `--deny-read` on top of the configured
+/// current-dir-write policy so the rules can be tried without editing
+/// `config.toml`.
+fn apply_sandbox_path_args(
+    sandbox_policy: &SandboxPolicy,
+    sandbox_paths: SandboxPathArgs,
+    cwd: &Path,
+) -> anyhow::Result<SandboxPolicy> {
+    let mut sandbox_policy = sandbox_policy.clone();
+    let SandboxPathArgs {
+        writable_roots: extra_roots,
+        deny_read: extra_deny_read,
+    } = sandbox_paths;
+    match &mut sandbox_policy {
+        SandboxPolicy::CurrentDirWrite {
+            writable_roots,
+            deny_read,
+            ..
+        } => {
+            for root in extra_roots {
+                writable_roots.push(AbsolutePathBuf::resolve_path_against_base(root, cwd)?);
+            }
+            deny_read.extend(extra_deny_read);
+        }
+        _ if extra_roots.is_empty() && extra_deny_read.is_empty() => {}
+        _ => anyhow::bail!("--writable-root and --deny-read require the current-dir-write sandbox"),
+    }
+    Ok(sandbox_policy)
+}
+
 pub fn create_sandbox_mode(full_auto: bool) -> SandboxMode {
     if full_auto {
         SandboxMode::CurrentDirWrite
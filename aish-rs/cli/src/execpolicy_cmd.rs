@@ -0,0 +1,258 @@
+use std::path::Path;
+use std::path::PathBuf;
+
+use aish_common::CliConfigOverrides;
+use aish_core::config::Config;
+use aish_core::exec_policy_commands;
+use aish_core::exec_policy_files;
+use aish_core::features::Feature;
+use aish_core::load_exec_policy_definitions;
+use aish_execpolicy::ExecPolicyCheckCommand;
+use aish_execpolicy::PolicyParser;
+use aish_execpolicy::RuleDefinition;
+use aish_execpolicy::RuleMatch;
+use aish_execpolicy::blocking_remove_rule;
+use aish_execpolicy::definition::render_definitions;
+use aish_execpolicy::definition::render_explanation;
+use anyhow::Context;
+use anyhow::Result;
+use anyhow::bail;
+
+/// Subcommands:
+/// - `check` — evaluate a command against explicit rule files
+/// - `list` — show the rules Aish loads, by file and line
+/// - `explain` — show which rules decide a command
+/// - `remove` — delete a rule by `FILE:LINE`
+/// - `test` — re-run every rule's `match` / `not_match` examples
+#[derive(Debug, clap::Parser)]
+pub struct ExecpolicyCli {
+    #[clap(flatten)]
+    pub config_overrides: CliConfigOverrides,
+
+    #[command(subcommand)]
+    pub subcommand: ExecpolicySubcommand,
+}
+
+#[derive(Debug, clap::Subcommand)]
+pub enum ExecpolicySubcommand {
+    /// Check execpolicy files against a command.
+    Check(ExecPolicyCheckCommand),
+
+    /// List the rules loaded from every `rules/*.rules` file.
+    List(ListArgs),
+
+    /// Show which rules match a command and the decision they produce.
+    Explain(ExplainArgs),
+
+    /// Remove the rule defined at `FILE:LINE` (as printed by `list`).
+    Remove(RemoveArgs),
+
+    /// Validate every rules file and its `match` / `not_match` examples.
+    Test,
+}
+
+#[derive(Debug, clap::Parser)]
+pub struct ListArgs {
+    /// Output the rules as JSON.
+    #[arg(long)]
+    pub json: bool,
+}
+
+#[derive(Debug, clap::Parser)]
+pub struct ExplainArgs {
+    /// Command to explain. `bash -lc "<script>"` is split into the commands
+    /// of the script, as when Aish runs it.
+    #[arg(
+        value_name = "COMMAND",
+        required = true,
+        trailing_var_arg = true,
+        allow_hyphen_values = true
+    )]
+    pub command: Vec<String>,
+}
+
+#[derive(Debug, clap::Parser)]
+pub struct RemoveArgs {
+    /// Location of the rule, e.g. `~/.aish/rules/default.rules:12`.
+    #[arg(value_name = "FILE:LINE")]
+    pub rule: String,
+}
+
+impl ExecpolicyCli {
+    pub async fn run(self) -> Result<()> {
+        let ExecpolicyCli {
+            config_overrides,
+            subcommand,
+        } = self;
+        if let ExecpolicySubcommand::Check(cmd) = subcommand {
+            // `check` evaluates the files it is given and needs no config.
+            return cmd.run();
+        }
+
+        let overrides = config_overrides
+            .parse_overrides()
+            .map_err(anyhow::Error::msg)?;
+        let config = Config::load_with_cli_overrides(overrides)
+            .await
+            .context("failed to load configuration")?;
+        if !config.features.enabled(Feature::ExecPolicy) {
+            eprintln!("Note: the exec_policy feature is disabled, so these rules are not applied.");
+        }
+
+        match subcommand {
+            ExecpolicySubcommand::Check(_) => unreachable!("handled above"),
+            ExecpolicySubcommand::List(args) => run_list(&config, args).await,
+            ExecpolicySubcommand::Explain(args) => run_explain(&config, args).await,
+            ExecpolicySubcommand::Remove(args) => run_remove(&config, args).await,
+            ExecpolicySubcommand::Test => run_test(&config).await,
+        }
+    }
+}
+
+async fn run_list(config: &Config, args: ListArgs) -> Result<()> {
+    let definitions = load_exec_policy_definitions(&config.config_layer_stack).await?;
+    if args.json {
+        let json: Vec<serde_json::Value> = definitions.iter().map(definition_to_json).collect();
+        println!("{}", serde_json::to_string_pretty(&json)?);
+        return Ok(());
+    }
+    if definitions.is_empty() {
+        println!("No execpolicy rules are defined.");
+        return Ok(());
+    }
+    print!("{}", render_definitions(&definitions));
+    Ok(())
+}
+
+async fn run_explain(config: &Config, args: ExplainArgs) -> Result<()> {
+    let definitions = load_exec_policy_definitions(&config.config_layer_stack).await?;
+    let commands = exec_policy_commands(&args.command);
+    print!("{}", render_explanation(&definitions, &commands));
+    Ok(())
+}
+
+async fn run_remove(config: &Config, args: RemoveArgs) -> Result<()> {
+    let Some((file, line)) = args.rule.rsplit_once(':') else {
+        bail!("expected FILE:LINE, got `{}`", args.rule);
+    };
+    let line: usize = line
+        .parse()
+        .with_context(|| format!("invalid line number in `{}`", args.rule))?;
+    let file = canonical(Path::new(file));
+
+    let definitions = load_exec_policy_definitions(&config.config_layer_stack).await?;
+    let at_line: Vec<&RuleDefinition> = definitions
+        .iter()
+        .filter(|definition| canonical(Path::new(&definition.source)) == file)
+        .filter(|definition| {
+            definition
+                .lines
+                .as_ref()
+                .is_some_and(|lines| lines.contains(&line))
+        })
+        .collect();
+    let definition = match at_line.as_slice() {
+        [] => bail!(
+            "no rule is defined at {}:{line}; see `aish execpolicy list`",
+            file.display()
+        ),
+        [definition] => *definition,
+        [first, ..] => bail!(
+            "{} defines {} rules (a loop or helper function); edit the file by hand",
+            first.location(),
+            at_line.len()
+        ),
+    };
+
+    let summary = definition.summary();
+    let location = definition.location();
+    let definition = definition.clone();
+    tokio::task::spawn_blocking(move || blocking_remove_rule(&file, &definition))
+        .await
+        .context("failed to join rule removal task")??;
+    println!("Removed {location}: {summary}");
+    println!("Running sessions keep the old rules until they are restarted.");
+    Ok(())
+}
+
+async fn run_test(config: &Config) -> Result<()> {
+    let policy_paths = exec_policy_files(&config.config_layer_stack).await?;
+    if policy_paths.is_empty() {
+        println!("No execpolicy rules files found.");
+        return Ok(());
+    }
+
+    let mut failures = 0;
+    for path in &policy_paths {
+        match parse_file(path) {
+            Ok(definitions) => {
+                let examples: usize = definitions
+                    .iter()
+                    .map(|definition| definition.matches.len() + definition.not_matches.len())
+                    .sum();
+                println!(
+                    "ok    {} ({} rules, {examples} examples)",
+                    path.display(),
+                    definitions.len()
+                );
+            }
+            Err(err) => {
+                failures += 1;
+                println!("FAIL  {}\n      {err:#}", path.display());
+            }
+        }
+    }
+    if failures > 0 {
+        bail!("{failures} of {} rules files failed", policy_paths.len());
+    }
+
+    // Each file is valid on its own; point out `match` examples that a rule
+    // from another file (or a later rule) decides more strictly.
+    let definitions = load_exec_policy_definitions(&config.config_layer_stack).await?;
+    for definition in &definitions {
+        for example in &definition.matches {
+            let strictest = definitions
+                .iter()
+                .filter_map(|other| other.matches(example))
+                .max_by_key(RuleMatch::decision);
+            if let Some(strictest) = strictest
+                && strictest.decision() != definition.decision
+            {
+                println!(
+                    "note  {}: `{}` is {} overall, not {}",
+                    definition.location(),
+                    example.join(" "),
+                    strictest.decision().as_str(),
+                    definition.decision.as_str()
+                );
+            }
+        }
+    }
+    Ok(())
+}
+
+fn parse_file(path: &Path) -> Result<Vec<RuleDefinition>> {
+    let contents = std::fs::read_to_string(path)
+        .with_context(|| format!("failed to read {}", path.display()))?;
+    let mut parser = PolicyParser::new();
+    parser.parse(&path.to_string_lossy(), &contents)?;
+    let (_, definitions) = parser.build_with_definitions();
+    Ok(definitions)
+}
+
+/// Resolves `path` so `~/.aish/rules/x.rules` and a relative spelling of
+/// the same file compare equal.
+fn canonical(path: &Path) -> PathBuf {
+    std::fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf())
+}
+
+fn definition_to_json(definition: &RuleDefinition) -> serde_json::Value {
+    serde_json::json!({
+        "source": definition.source,
+        "line": definition.lines.as_ref().map(|lines| *lines.start()),
+        "decision": definition.decision,
+        "rule": definition.summary(),
+        "match": definition.matches,
+        "not_match": definition.not_matches,
+    })
+}
@@ -1,8 +1,12 @@
 pub mod debug_sandbox;
 mod exit_status;
 
+use std::path::PathBuf;
+
 use aish_common::CliConfigOverrides;
+use clap::Args;
 use clap::Parser;
+use clap::ValueEnum;
 
 #[derive(Debug, Parser)]
 pub struct SeatbeltCommand {
@@ -14,6 +18,9 @@ pub struct SeatbeltCommand {
     #[arg(long = "log-denials", default_value_t = false)]
     pub log_denials: bool,
 
+    #[clap(flatten)]
+    pub sandbox_paths: SandboxPathArgs,
+
     #[clap(skip)]
     pub config_overrides: CliConfigOverrides,
 
@@ -28,6 +35,9 @@ pub struct LandlockCommand {
     #[arg(long = "full-auto", default_value_t = false)]
     pub full_auto: bool,
 
+    #[clap(flatten)]
+    pub sandbox_paths: SandboxPathArgs,
+
     #[clap(skip)]
     pub config_overrides: CliConfigOverrides,
 
@@ -35,3 +45,52 @@ pub struct LandlockCommand {
     #[arg(trailing_var_arg = true)]
     pub command: Vec<String>,
 }
+
+#[derive(Debug, Parser)]
+pub struct SandboxBackendCommand {
+    /// Sandbox implementation to run the command under. `container` uses the
+    /// `[sandbox_container]` runtime and image from config.toml.
+    #[arg(long = "backend", value_enum, default_value_t = SandboxBackendArg::Platform)]
+    pub backend: SandboxBackendArg,
+
+    /// Convenience alias for low-friction sandboxed automatic execution (network-disabled sandbox that can write to cwd and TMPDIR)
+    #[arg(long = "full-auto", default_value_t = false)]
+    pub full_auto: bool,
+
+    #[clap(flatten)]
+    pub sandbox_paths: SandboxPathArgs,
+
+    #[clap(skip)]
+    pub config_overrides: CliConfigOverrides,
+
+    /// Full command args to run under the sandbox.
+    #[arg(trailing_var_arg = true)]
+    pub command: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum SandboxBackendArg {
+    /// Seatbelt on macOS, Landlock + seccomp on Linux.
+    Platform,
+    /// podman, docker or bubblewrap.
+    Container,
+}
+
+/// Extra filesystem rules layered on top of the configured
+/// `[sandbox_current_dir_write]` settings. Either flag implies `--full-auto`.
+#[derive(Debug, Default, Args)]
+pub struct SandboxPathArgs {
+    /// Additional directory the command may write to (repeatable).
+    #[arg(long = "writable-root", value_name = "DIR")]
+    pub writable_roots: Vec<PathBuf>,
+
+    /// Path or glob the command may not read, e.g. `~/.ssh` or `.env` (repeatable).
+    #[arg(long = "deny-read", value_name = "PATTERN")]
+    pub deny_read: Vec<String>,
+}
+
+impl SandboxPathArgs {
+    pub fn is_empty(&self) -> bool {
+        self.writable_roots.is_empty() && self.deny_read.is_empty()
+    }
+}
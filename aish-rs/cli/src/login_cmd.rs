@@ -0,0 +1,131 @@
+use std::collections::BTreeSet;
+use std::io::IsTerminal;
+use std::io::Read;
+use std::io::Write;
+
+use aish_common::CliConfigOverrides;
+use aish_core::config::Config;
+use aish_core::provider_keys::ProviderKeyStorage;
+use aish_core::provider_keys::delete_provider_key;
+use aish_core::provider_keys::fallback_file_path;
+use aish_core::provider_keys::file_provider_ids;
+use aish_core::provider_keys::save_provider_key;
+use anyhow::Context;
+use anyhow::Result;
+use anyhow::bail;
+
+/// Store an API key for a model provider in the OS keyring.
+///
+/// The key is read from stdin, e.g. `printenv OPENROUTER_API_KEY | aish login
+/// --provider openrouter`; when stdin is a terminal you are prompted for it.
+#[derive(Debug, clap::Parser)]
+pub struct LoginCommand {
+    #[clap(flatten)]
+    pub config_overrides: CliConfigOverrides,
+
+    /// Id of the provider in `model_providers` (e.g. `openai`, `openrouter`).
+    #[arg(long, value_name = "ID")]
+    pub provider: String,
+}
+
+/// Remove API keys stored with `aish login`.
+#[derive(Debug, clap::Parser)]
+pub struct LogoutCommand {
+    #[clap(flatten)]
+    pub config_overrides: CliConfigOverrides,
+
+    /// Only remove the key for this provider. Removes every stored key when
+    /// omitted.
+    #[arg(long, value_name = "ID")]
+    pub provider: Option<String>,
+}
+
+impl LoginCommand {
+    pub async fn run(self) -> Result<()> {
+        let config = load_config(&self.config_overrides).await?;
+        let provider_id = self.provider;
+        if !config.model_providers.contains_key(&provider_id) {
+            bail!(
+                "No model provider named '{provider_id}' found. Known providers: {}",
+                known_providers(&config)
+            );
+        }
+
+        let api_key = read_api_key(&provider_id)?;
+        let storage = save_provider_key(&config.codex_home, &provider_id, &api_key)
+            .with_context(|| format!("failed to store API key for '{provider_id}'"))?;
+        match storage {
+            ProviderKeyStorage::Keyring => {
+                println!("Saved API key for '{provider_id}' in the OS keyring.");
+            }
+            ProviderKeyStorage::File => println!(
+                "OS keyring unavailable; saved API key for '{provider_id}' in {}.",
+                fallback_file_path(&config.codex_home).display()
+            ),
+        }
+        Ok(())
+    }
+}
+
+impl LogoutCommand {
+    pub async fn run(self) -> Result<()> {
+        let config = load_config(&self.config_overrides).await?;
+        let provider_ids: BTreeSet<String> = match self.provider {
+            Some(provider_id) => BTreeSet::from([provider_id]),
+            None => {
+                let mut ids: BTreeSet<String> = config.model_providers.keys().cloned().collect();
+                ids.extend(file_provider_ids(&config.codex_home)?);
+                ids
+            }
+        };
+
+        let mut removed = Vec::new();
+        for provider_id in provider_ids {
+            if delete_provider_key(&config.codex_home, &provider_id)
+                .with_context(|| format!("failed to remove API key for '{provider_id}'"))?
+            {
+                removed.push(provider_id);
+            }
+        }
+
+        if removed.is_empty() {
+            println!("No stored API keys to remove.");
+        } else {
+            println!("Removed stored API key for: {}", removed.join(", "));
+        }
+        Ok(())
+    }
+}
+
+async fn load_config(config_overrides: &CliConfigOverrides) -> Result<Config> {
+    let overrides = config_overrides
+        .parse_overrides()
+        .map_err(anyhow::Error::msg)?;
+    Config::load_with_cli_overrides(overrides)
+        .await
+        .context("failed to load configuration")
+}
+
+fn known_providers(config: &Config) -> String {
+    let mut ids: Vec<&str> = config.model_providers.keys().map(String::as_str).collect();
+    ids.sort_unstable();
+    ids.join(", ")
+}
+
+fn read_api_key(provider_id: &str) -> Result<String> {
+    let mut stdin = std::io::stdin();
+    let mut input = String::new();
+    if stdin.is_terminal() {
+        eprint!("API key for '{provider_id}': ");
+        std::io::stderr().flush()?;
+        stdin.read_line(&mut input)?;
+    } else {
+        stdin.read_to_string(&mut input)?;
+    }
+
+    let api_key = input.trim();
+    if api_key.is_empty() {
+        bail!("No API key provided on stdin.");
+    }
+    Ok(api_key.to_string())
+}
@@ -1,9 +1,10 @@
 use aish_arg0::arg0_dispatch_or_else;
 use aish_cli::LandlockCommand;
+use aish_cli::SandboxBackendCommand;
 use aish_cli::SeatbeltCommand;
 use aish_common::CliConfigOverrides;
 use aish_exec::Cli as ExecCli;
-use aish_execpolicy::ExecPolicyCheckCommand;
+use aish_exec::SuggestCli;
 use aish_tui::AppExitInfo;
 use aish_tui::Cli as TuiCli;
 use clap::CommandFactory;
@@ -14,13 +15,23 @@ use owo_colors::OwoColorize;
 use std::path::PathBuf;
 use supports_color::Stream;
 
+mod execpolicy_cmd;
+mod login_cmd;
 mod mcp_cmd;
+mod sessions_cmd;
 
+use crate::execpolicy_cmd::ExecpolicyCli;
+use crate::login_cmd::LoginCommand;
+use crate::login_cmd::LogoutCommand;
 use crate::mcp_cmd::McpCli;
+use crate::sessions_cmd::SessionsCli;
 
 use aish_core::config::Config;
 use aish_core::config::ConfigOverrides;
 use aish_core::features::is_known_feature_key;
+use aish_core::shell_integration::HookShell;
+use aish_core::shell_integration::LastShellCommand;
+use aish_core::shell_integration::hook_script;
 
 /// Aish CLI
 ///
@@ -57,6 +68,12 @@ enum Subcommand {
     #[clap(visible_alias = "e")]
     Exec(ExecCli),
 
+    /// Store a model provider's API key in the OS keyring.
+    Login(LoginCommand),
+
+    /// Remove API keys stored with `aish login`.
+    Logout(LogoutCommand),
+
     /// [experimental] Run Aish as an MCP server and manage MCP servers.
     Mcp(McpCli),
 
@@ -67,13 +84,25 @@ enum Subcommand {
     #[clap(visible_alias = "debug")]
     Sandbox(SandboxArgs),
 
-    /// Execpolicy tooling.
-    #[clap(hide = true)]
-    Execpolicy(ExecpolicyCommand),
+    /// Inspect, test and edit the execpolicy rules in `~/.aish/rules`.
+    Execpolicy(ExecpolicyCli),
 
     /// Resume a previous interactive session (picker by default; use --last to continue the most recent).
     Resume(ResumeCommand),
 
+    /// Search and manage saved sessions.
+    Sessions(SessionsCli),
+
+    /// Print a shell hook that records each command for `aish fix` and binds
+    /// Ctrl-X Ctrl-A to `aish suggest`.
+    ShellInit(ShellInitCommand),
+
+    /// Explain and fix the last command run in a shell set up with `aish shell-init`.
+    Fix(FixCommand),
+
+    /// Ask for a single shell command and print it instead of running it.
+    Suggest(SuggestCli),
+
     /// Internal: relay stdio to a Unix domain socket.
     #[clap(hide = true, name = "stdio-to-uds")]
     StdioToUds(StdioToUdsCommand),
@@ -100,14 +129,61 @@ struct ResumeCommand {
     #[arg(long = "last", default_value_t = false, conflicts_with = "session_id")]
     last: bool,
 
+    /// Branch the session at this user turn (0-based) instead of resuming it:
+    /// the turns before it are copied into a new session and the original is
+    /// left untouched.
+    #[arg(long = "fork-at", value_name = "TURN")]
+    fork_at: Option<usize>,
+
+    #[clap(flatten)]
+    config_overrides: TuiCli,
+}
+
+#[derive(Debug, Parser)]
+struct ShellInitCommand {
+    /// Shell to generate the hook for.
+    #[arg(value_enum)]
+    shell: HookShellArg,
+
+    /// Also tee each command's stderr into the state directory so `aish fix`
+    /// can include its tail. While a command runs its stderr is a pipe rather
+    /// than the terminal, so some programs turn off colors and progress bars.
+    /// Not supported by the fish hook.
+    #[arg(long = "capture-stderr", default_value_t = false)]
+    capture_stderr: bool,
+}
+
+#[derive(Debug, Clone, Copy, clap::ValueEnum)]
+enum HookShellArg {
+    Bash,
+    Zsh,
+    Fish,
+}
+
+impl From<HookShellArg> for HookShell {
+    fn from(value: HookShellArg) -> Self {
+        match value {
+            HookShellArg::Bash => HookShell::Bash,
+            HookShellArg::Zsh => HookShell::Zsh,
+            HookShellArg::Fish => HookShell::Fish,
+        }
+    }
+}
+
+#[derive(Debug, Parser)]
+struct FixCommand {
     #[clap(flatten)]
     config_overrides: TuiCli,
 }
 
 #[derive(Debug, Parser)]
+#[command(args_conflicts_with_subcommands = true)]
 struct SandboxArgs {
     #[command(subcommand)]
-    cmd: SandboxCommand,
+    cmd: Option<SandboxCommand>,
+
+    #[clap(flatten)]
+    run: SandboxBackendCommand,
 }
 
 #[derive(Debug, clap::Subcommand)]
@@ -121,19 +197,6 @@ enum SandboxCommand {
     Linux(LandlockCommand),
 }
 
-#[derive(Debug, Parser)]
-struct ExecpolicyCommand {
-    #[command(subcommand)]
-    sub: ExecpolicySubcommand,
-}
-
-#[derive(Debug, clap::Subcommand)]
-enum ExecpolicySubcommand {
-    /// Check execpolicy files against a command.
-    #[clap(name = "check")]
-    Check(ExecPolicyCheckCommand),
-}
-
 #[derive(Debug, Parser)]
 struct StdioToUdsCommand {
     /// Path to the Unix domain socket to connect to.
@@ -179,10 +242,6 @@ fn handle_app_exit(exit_info: AppExitInfo) -> anyhow::Result<()> {
     Ok(())
 }
 
-fn run_execpolicycheck(cmd: ExecPolicyCheckCommand) -> anyhow::Result<()> {
-    cmd.run()
-}
-
 #[derive(Debug, Default, Parser, Clone)]
 struct FeatureToggles {
     /// Enable a feature (repeatable). Equivalent to `-c features.<name>=true`.
@@ -283,14 +342,36 @@ async fn cli_main(aish_linux_sandbox_exe: Option<PathBuf>) -> anyhow::Result<()>
             );
             aish_exec::run_main(exec_cli, aish_linux_sandbox_exe).await?;
         }
+        Some(Subcommand::Login(mut login_cli)) => {
+            prepend_config_flags(
+                &mut login_cli.config_overrides,
+                root_config_overrides.clone(),
+            );
+            login_cli.run().await?;
+        }
+        Some(Subcommand::Logout(mut logout_cli)) => {
+            prepend_config_flags(
+                &mut logout_cli.config_overrides,
+                root_config_overrides.clone(),
+            );
+            logout_cli.run().await?;
+        }
         Some(Subcommand::Mcp(mut mcp_cli)) => {
             // Propagate any root-level config overrides (e.g. `-c key=value`).
             prepend_config_flags(&mut mcp_cli.config_overrides, root_config_overrides.clone());
-            mcp_cli.run().await?;
+            mcp_cli.run(aish_linux_sandbox_exe).await?;
+        }
+        Some(Subcommand::Sessions(mut sessions_cli)) => {
+            prepend_config_flags(
+                &mut sessions_cli.config_overrides,
+                root_config_overrides.clone(),
+            );
+            sessions_cli.run().await?;
         }
         Some(Subcommand::Resume(ResumeCommand {
             session_id,
             last,
+            fork_at,
             config_overrides,
         })) => {
             interactive = finalize_resume_interactive(
@@ -298,16 +379,57 @@ async fn cli_main(aish_linux_sandbox_exe: Option<PathBuf>) -> anyhow::Result<()>
                 root_config_overrides.clone(),
                 session_id,
                 last,
+                fork_at,
+                config_overrides,
+            );
+            let exit_info = run_interactive_tui(interactive, aish_linux_sandbox_exe).await?;
+            handle_app_exit(exit_info)?;
+        }
+        Some(Subcommand::ShellInit(ShellInitCommand {
+            shell,
+            capture_stderr,
+        })) => {
+            print!("{}", hook_script(shell.into(), capture_stderr));
+        }
+        Some(Subcommand::Fix(FixCommand { config_overrides })) => {
+            let Some(last_command) = LastShellCommand::load_from_env()? else {
+                anyhow::bail!(
+                    "No recorded shell command found. Enable shell integration first, e.g. add `eval \"$(aish shell-init bash)\"` to ~/.bashrc."
+                );
+            };
+            interactive = finalize_fix_interactive(
+                interactive,
+                root_config_overrides.clone(),
                 config_overrides,
+                &last_command,
             );
             let exit_info = run_interactive_tui(interactive, aish_linux_sandbox_exe).await?;
             handle_app_exit(exit_info)?;
         }
+        Some(Subcommand::Suggest(mut suggest_cli)) => {
+            prepend_config_flags(
+                &mut suggest_cli.config_overrides,
+                root_config_overrides.clone(),
+            );
+            aish_exec::run_suggest(suggest_cli, aish_linux_sandbox_exe).await?;
+        }
         Some(Subcommand::Completion(completion_cli)) => {
             print_completion(completion_cli);
         }
         Some(Subcommand::Sandbox(sandbox_args)) => match sandbox_args.cmd {
-            SandboxCommand::Macos(mut seatbelt_cli) => {
+            None => {
+                let mut backend_cli = sandbox_args.run;
+                prepend_config_flags(
+                    &mut backend_cli.config_overrides,
+                    root_config_overrides.clone(),
+                );
+                aish_cli::debug_sandbox::run_command_under_backend(
+                    backend_cli,
+                    aish_linux_sandbox_exe,
+                )
+                .await?;
+            }
+            Some(SandboxCommand::Macos(mut seatbelt_cli)) => {
                 prepend_config_flags(
                     &mut seatbelt_cli.config_overrides,
                     root_config_overrides.clone(),
@@ -318,7 +440,7 @@ async fn cli_main(aish_linux_sandbox_exe: Option<PathBuf>) -> anyhow::Result<()>
                 )
                 .await?;
             }
-            SandboxCommand::Linux(mut landlock_cli) => {
+            Some(SandboxCommand::Linux(mut landlock_cli)) => {
                 prepend_config_flags(
                     &mut landlock_cli.config_overrides,
                     root_config_overrides.clone(),
@@ -330,9 +452,13 @@ async fn cli_main(aish_linux_sandbox_exe: Option<PathBuf>) -> anyhow::Result<()>
                 .await?;
             }
         },
-        Some(Subcommand::Execpolicy(ExecpolicyCommand { sub })) => match sub {
-            ExecpolicySubcommand::Check(cmd) => run_execpolicycheck(cmd)?,
-        },
+        Some(Subcommand::Execpolicy(mut execpolicy_cli)) => {
+            prepend_config_flags(
+                &mut execpolicy_cli.config_overrides,
+                root_config_overrides.clone(),
+            );
+            execpolicy_cli.run().await?;
+        }
         Some(Subcommand::StdioToUds(cmd)) => {
             let socket_path = cmd.socket_path;
             tokio::task::spawn_blocking(move || aish_stdio_to_uds::run(socket_path.as_path()))
@@ -403,6 +529,7 @@ fn finalize_resume_interactive(
     root_config_overrides: CliConfigOverrides,
     session_id: Option<String>,
     last: bool,
+    fork_at: Option<usize>,
     resume_cli: TuiCli,
 ) -> TuiCli {
     // Start with the parsed interactive CLI so resume shares the same
@@ -411,6 +538,7 @@ fn finalize_resume_interactive(
     interactive.resume_picker = resume_session_id.is_none() && !last;
     interactive.resume_last = last;
     interactive.resume_session_id = resume_session_id;
+    interactive.resume_fork_at = fork_at;
 
     // Merge resume-scoped flags and overrides with highest precedence.
     merge_resume_cli_flags(&mut interactive, resume_cli);
@@ -421,6 +549,21 @@ fn finalize_resume_interactive(
     interactive
 }
 
+/// Build the final `TuiCli` for a `aish fix` invocation: the prompt given to
+/// `aish fix` (if any) becomes the question asked about the recorded command.
+fn finalize_fix_interactive(
+    mut interactive: TuiCli,
+    root_config_overrides: CliConfigOverrides,
+    mut fix_cli: TuiCli,
+    last_command: &LastShellCommand,
+) -> TuiCli {
+    let question = fix_cli.prompt.take();
+    merge_resume_cli_flags(&mut interactive, fix_cli);
+    interactive.prompt = Some(last_command.fix_prompt(question.as_deref()));
+    prepend_config_flags(&mut interactive.config_overrides, root_config_overrides);
+    interactive
+}
+
 /// Merge flags provided to `aish resume` so they take precedence over any
 /// root-level flags. Only overrides fields explicitly set on the resume-scoped
 /// CLI. Also appends `-c key=value` overrides with highest precedence.
@@ -494,13 +637,21 @@ mod tests {
         let Subcommand::Resume(ResumeCommand {
             session_id,
             last,
+            fork_at,
             config_overrides: resume_cli,
         }) = subcommand.expect("resume present")
         else {
             unreachable!()
         };
 
-        finalize_resume_interactive(interactive, root_overrides, session_id, last, resume_cli)
+        finalize_resume_interactive(
+            interactive,
+            root_overrides,
+            session_id,
+            last,
+            fork_at,
+            resume_cli,
+        )
     }
 
     fn sample_exit_info(conversation: Option<&str>) -> AppExitInfo {
@@ -581,6 +732,15 @@ mod tests {
         assert!(!interactive.resume_picker);
         assert!(!interactive.resume_last);
         assert_eq!(interactive.resume_session_id.as_deref(), Some("1234"));
+        assert_eq!(interactive.resume_fork_at, None);
+    }
+
+    #[test]
+    fn resume_fork_at_is_passed_through() {
+        let interactive = finalize_from_args(["aish", "resume", "1234", "--fork-at", "2"].as_ref());
+        assert!(!interactive.resume_picker);
+        assert_eq!(interactive.resume_session_id.as_deref(), Some("1234"));
+        assert_eq!(interactive.resume_fork_at, Some(2));
     }
 
     #[test]
@@ -656,6 +816,73 @@ mod tests {
         assert_eq!(interactive.resume_session_id, None);
     }
 
+    #[test]
+    fn fix_uses_prompt_as_question_about_last_command() {
+        let cli =
+            MultitoolCli::try_parse_from(["aish", "fix", "-m", "glm-test", "why?"]).expect("parse");
+        let Some(Subcommand::Fix(FixCommand { config_overrides })) = cli.subcommand else {
+            unreachable!()
+        };
+        let last_command = LastShellCommand {
+            command: "make".to_string(),
+            exit_code: 2,
+            cwd: PathBuf::from("/src"),
+            output: None,
+        };
+
+        let interactive = finalize_fix_interactive(
+            cli.interactive,
+            cli.config_overrides,
+            config_overrides,
+            &last_command,
+        );
+
+        assert_eq!(interactive.model.as_deref(), Some("glm-test"));
+        assert_eq!(
+            interactive.prompt,
+            Some(last_command.fix_prompt(Some("why?")))
+        );
+        assert!(!interactive.resume_picker);
+    }
+
+    #[test]
+    fn sandbox_backend_flag_runs_without_subcommand() {
+        let cli = MultitoolCli::try_parse_from([
+            "aish",
+            "sandbox",
+            "--backend",
+            "container",
+            "--",
+            "ls",
+            "-la",
+        ])
+        .expect("parse");
+        let Some(Subcommand::Sandbox(SandboxArgs { cmd, run })) = cli.subcommand else {
+            unreachable!()
+        };
+
+        assert!(cmd.is_none());
+        assert_eq!(run.backend, aish_cli::SandboxBackendArg::Container);
+        assert_eq!(run.command, vec!["ls".to_string(), "-la".to_string()]);
+    }
+
+    #[test]
+    fn sandbox_platform_subcommands_still_parse() {
+        let cli = MultitoolCli::try_parse_from(["aish", "sandbox", "linux", "--full-auto", "ls"])
+            .expect("parse");
+        let Some(Subcommand::Sandbox(SandboxArgs { cmd, .. })) = cli.subcommand else {
+            unreachable!()
+        };
+
+        assert_matches!(
+            cmd,
+            Some(SandboxCommand::Linux(LandlockCommand {
+                full_auto: true,
+                ..
+            }))
+        );
+    }
+
     #[test]
     fn feature_toggles_known_features_generate_overrides() {
         let toggles = FeatureToggles {
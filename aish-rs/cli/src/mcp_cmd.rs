@@ -1,4 +1,5 @@
 use std::collections::HashMap;
+use std::path::PathBuf;
 
 use aish_common::CliConfigOverrides;
 use aish_common::format_env_display::format_env_display;
@@ -36,6 +37,9 @@ pub struct McpCli {
 
 #[derive(Debug, clap::Subcommand)]
 pub enum McpSubcommand {
+    /// Run Aish as an MCP server on stdio, exposing the `aish_run` and
+    /// `aish_reply` tools.
+    Serve,
     List(ListArgs),
     Get(GetArgs),
     Add(AddArgs),
@@ -147,13 +151,16 @@ pub struct LogoutArgs {
 }
 
 impl McpCli {
-    pub async fn run(self) -> Result<()> {
+    pub async fn run(self, aish_linux_sandbox_exe: Option<PathBuf>) -> Result<()> {
         let McpCli {
             config_overrides,
             subcommand,
         } = self;
 
         match subcommand {
+            McpSubcommand::Serve => {
+                aish_mcp_server::run_main(aish_linux_sandbox_exe, config_overrides).await?;
+            }
             McpSubcommand::List(args) => {
                 run_list(&config_overrides, args).await?;
             }
@@ -0,0 +1,423 @@
+use std::path::PathBuf;
+
+use aish_common::CliConfigOverrides;
+use aish_core::ExportFormat;
+use aish_core::PruneReason;
+use aish_core::SessionSearchHit;
+use aish_core::config::Config;
+use aish_core::export_rollout;
+use aish_core::find_conversation_path_by_id_str;
+use aish_core::protocol::SessionMetadataUpdate;
+use aish_core::prune_sessions;
+use aish_core::read_session_metadata;
+use aish_core::search_sessions;
+use aish_core::star_session;
+use aish_core::starred_sessions;
+use aish_core::unstar_session;
+use aish_core::update_session_metadata;
+use anyhow::Context;
+use anyhow::Result;
+use anyhow::bail;
+use clap::ValueEnum;
+use uuid::Uuid;
+
+/// Subcommands:
+/// - `search` — full-text search over recorded sessions
+/// - `export` — render a session as Markdown, HTML or a shell script
+/// - `prune` — apply the `[sessions]` retention policy
+/// - `star` / `unstar` — protect a session from pruning
+/// - `tag` — name and tag a session
+#[derive(Debug, clap::Parser)]
+pub struct SessionsCli {
+    #[clap(flatten)]
+    pub config_overrides: CliConfigOverrides,
+
+    #[command(subcommand)]
+    pub subcommand: SessionsSubcommand,
+}
+
+#[derive(Debug, clap::Subcommand)]
+pub enum SessionsSubcommand {
+    /// Search user messages, agent messages and executed commands of every
+    /// saved session.
+    Search(SearchArgs),
+
+    /// Render a saved session for a ticket or runbook.
+    Export(ExportArgs),
+
+    /// Delete sessions beyond `[sessions] max_age_days` / `max_total_bytes`.
+    Prune(PruneArgs),
+
+    /// Star a session so pruning never deletes it. Lists starred sessions
+    /// when no id is given.
+    Star(StarArgs),
+
+    /// Remove the star from a session.
+    Unstar(UnstarArgs),
+
+    /// Name or tag a session. Shows its current name and tags when nothing
+    /// is changed.
+    Tag(TagArgs),
+}
+
+#[derive(Debug, clap::Parser)]
+pub struct SearchArgs {
+    /// Words that must all appear in a session (case-insensitive).
+    #[arg(value_name = "QUERY", required = true, num_args = 1..)]
+    pub query: Vec<String>,
+
+    /// Maximum number of sessions to show.
+    #[arg(long, default_value_t = 20)]
+    pub limit: usize,
+
+    /// Output the matches as JSON.
+    #[arg(long)]
+    pub json: bool,
+}
+
+#[derive(Debug, clap::Parser)]
+pub struct ExportArgs {
+    /// Session id (UUID) or path to a rollout `.jsonl` file.
+    #[arg(value_name = "SESSION_ID")]
+    pub session: String,
+
+    /// Output format.
+    #[arg(long, value_enum, default_value_t = ExportFormatArg::Md)]
+    pub format: ExportFormatArg,
+
+    /// Write to this file instead of stdout.
+    #[arg(long, short = 'o', value_name = "FILE")]
+    pub output: Option<PathBuf>,
+}
+
+#[derive(Debug, clap::Parser)]
+pub struct PruneArgs {
+    /// List the sessions that would be deleted without deleting them.
+    #[arg(long)]
+    pub dry_run: bool,
+}
+
+#[derive(Debug, clap::Parser)]
+pub struct StarArgs {
+    /// Session id (UUID). Name the session with `aish sessions tag --name`.
+    #[arg(value_name = "SESSION_ID")]
+    pub session: Option<String>,
+}
+
+#[derive(Debug, clap::Parser)]
+pub struct UnstarArgs {
+    /// Session id (UUID).
+    #[arg(value_name = "SESSION_ID")]
+    pub session: String,
+}
+
+#[derive(Debug, clap::Parser)]
+pub struct TagArgs {
+    /// Session id (UUID) or path to a rollout `.jsonl` file.
+    #[arg(value_name = "SESSION_ID")]
+    pub session: String,
+
+    /// Tags to add.
+    #[arg(value_name = "TAG")]
+    pub tags: Vec<String>,
+
+    /// Tags to remove.
+    #[arg(long = "remove", short = 'r', value_name = "TAG")]
+    pub remove: Vec<String>,
+
+    /// Rename the session; an empty name clears it.
+    #[arg(long, value_name = "NAME")]
+    pub name: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ExportFormatArg {
+    /// Markdown with prompts, messages, commands and diffs.
+    Md,
+    /// Standalone HTML page.
+    Html,
+    /// The executed commands, in order, as a commented shell script.
+    Sh,
+}
+
+impl From<ExportFormatArg> for ExportFormat {
+    fn from(format: ExportFormatArg) -> Self {
+        match format {
+            ExportFormatArg::Md => ExportFormat::Markdown,
+            ExportFormatArg::Html => ExportFormat::Html,
+            ExportFormatArg::Sh => ExportFormat::Shell,
+        }
+    }
+}
+
+impl SessionsCli {
+    pub async fn run(self) -> Result<()> {
+        let SessionsCli {
+            config_overrides,
+            subcommand,
+        } = self;
+        let overrides = config_overrides
+            .parse_overrides()
+            .map_err(anyhow::Error::msg)?;
+        let config = Config::load_with_cli_overrides(overrides)
+            .await
+            .context("failed to load configuration")?;
+
+        match subcommand {
+            SessionsSubcommand::Search(args) => run_search(&config, args).await,
+            SessionsSubcommand::Export(args) => run_export(&config, args).await,
+            SessionsSubcommand::Prune(args) => run_prune(&config, args).await,
+            SessionsSubcommand::Star(args) => run_star(&config, args).await,
+            SessionsSubcommand::Unstar(args) => run_unstar(&config, args),
+            SessionsSubcommand::Tag(args) => run_tag(&config, args).await,
+        }
+    }
+}
+
+async fn run_search(config: &Config, args: SearchArgs) -> Result<()> {
+    let query = args.query.join(" ");
+    let hits = search_sessions(
+        &config.codex_home,
+        &query,
+        args.limit,
+        &[],
+        None,
+        &config.model_provider_id,
+    )
+    .await
+    .context("failed to search sessions")?;
+
+    if args.json {
+        let json: Vec<serde_json::Value> = hits.iter().map(hit_to_json).collect();
+        println!("{}", serde_json::to_string_pretty(&json)?);
+        return Ok(());
+    }
+
+    if hits.is_empty() {
+        println!("No sessions match '{query}'.");
+        return Ok(());
+    }
+
+    for (idx, hit) in hits.iter().enumerate() {
+        if idx > 0 {
+            println!();
+        }
+        let when = hit
+            .updated_at
+            .as_deref()
+            .or(hit.created_at.as_deref())
+            .unwrap_or("-");
+        let cwd = hit
+            .cwd
+            .as_ref()
+            .map(|cwd| cwd.display().to_string())
+            .unwrap_or_else(|| "-".to_string());
+        println!("{}  {when}  {cwd}", hit.id);
+        if let Some(label) = metadata_label(hit.name.as_deref(), &hit.tags) {
+            println!("  {label}");
+        }
+        for m in &hit.matches {
+            println!("  {:<8} {}", format!("{}:", m.kind.label()), m.snippet);
+        }
+    }
+    println!();
+    println!("Resume with `aish resume <SESSION_ID>`.");
+    Ok(())
+}
+
+async fn run_export(config: &Config, args: ExportArgs) -> Result<()> {
+    let path = resolve_session_path(config, &args.session).await?;
+    let rendered = export_rollout(&path, args.format.into())
+        .with_context(|| format!("failed to read {}", path.display()))?;
+
+    match args.output {
+        Some(output) => {
+            std::fs::write(&output, rendered)
+                .with_context(|| format!("failed to write {}", output.display()))?;
+            #[cfg(unix)]
+            {
+                use std::os::unix::fs::PermissionsExt;
+                if args.format == ExportFormatArg::Sh {
+                    std::fs::set_permissions(&output, std::fs::Permissions::from_mode(0o755))?;
+                }
+            }
+            eprintln!("Wrote {}", output.display());
+        }
+        None => print!("{rendered}"),
+    }
+    Ok(())
+}
+
+async fn run_prune(config: &Config, args: PruneArgs) -> Result<()> {
+    if !config.sessions.is_enabled() {
+        println!(
+            "No retention limits configured. Set `max_age_days` or `max_total_bytes` under [sessions] in config.toml."
+        );
+        return Ok(());
+    }
+
+    let report = prune_sessions(
+        &config.codex_home,
+        &config.sessions,
+        Vec::new(),
+        args.dry_run,
+    )
+    .await
+    .context("failed to prune sessions")?;
+
+    for session in &report.pruned {
+        let id = session
+            .id
+            .map(|id| id.to_string())
+            .unwrap_or_else(|| session.path.display().to_string());
+        let reason = match session.reason {
+            PruneReason::MaxAge => "older than max_age_days",
+            PruneReason::MaxTotalBytes => "over max_total_bytes",
+        };
+        println!("{id}  {}  {reason}", format_bytes(session.bytes));
+    }
+    let verb = if args.dry_run {
+        "Would delete"
+    } else {
+        "Deleted"
+    };
+    println!(
+        "{verb} {} session(s), freeing {}; {} remain.",
+        report.pruned.len(),
+        format_bytes(report.freed_bytes()),
+        format_bytes(report.remaining_bytes)
+    );
+    if report.starred_or_named_kept > 0 {
+        println!(
+            "Kept {} starred or named session(s).",
+            report.starred_or_named_kept
+        );
+    }
+    Ok(())
+}
+
+async fn run_star(config: &Config, args: StarArgs) -> Result<()> {
+    let Some(session) = args.session else {
+        let starred = starred_sessions(&config.codex_home)?;
+        if starred.is_empty() {
+            println!("No starred sessions.");
+        }
+        for (id, entry) in starred {
+            let label = match find_conversation_path_by_id_str(&config.codex_home, &id.to_string())
+                .await?
+            {
+                Some(path) => read_session_metadata(&path)
+                    .ok()
+                    .and_then(|metadata| metadata_label(metadata.name.as_deref(), &metadata.tags)),
+                None => None,
+            };
+            println!(
+                "{id}  {}  {}",
+                entry.starred_at,
+                label.as_deref().unwrap_or("-")
+            );
+        }
+        return Ok(());
+    };
+
+    let id = parse_session_id(&session)?;
+    if find_conversation_path_by_id_str(&config.codex_home, &session)
+        .await?
+        .is_none()
+    {
+        bail!("No saved session found with id '{session}'.");
+    }
+    star_session(&config.codex_home, id).context("failed to star session")?;
+    println!("Starred {id}; pruning will keep it.");
+    Ok(())
+}
+
+fn run_unstar(config: &Config, args: UnstarArgs) -> Result<()> {
+    let id = parse_session_id(&args.session)?;
+    if unstar_session(&config.codex_home, id).context("failed to unstar session")? {
+        println!("Removed the star from {id}.");
+    } else {
+        println!("{id} was not starred.");
+    }
+    Ok(())
+}
+
+async fn run_tag(config: &Config, args: TagArgs) -> Result<()> {
+    let path = resolve_session_path(config, &args.session).await?;
+    let update = SessionMetadataUpdate {
+        name: args.name,
+        add_tags: args.tags,
+        remove_tags: args.remove,
+    };
+    let metadata = if update == SessionMetadataUpdate::default() {
+        read_session_metadata(&path)
+    } else {
+        update_session_metadata(&path, &update)
+    }
+    .with_context(|| format!("failed to update {}", path.display()))?;
+
+    match metadata_label(metadata.name.as_deref(), &metadata.tags) {
+        Some(label) => println!("{label}"),
+        None => println!("No name or tags."),
+    }
+    Ok(())
+}
+
+/// `"name" #tag1 #tag2`, or `None` when the session has neither.
+fn metadata_label(name: Option<&str>, tags: &[String]) -> Option<String> {
+    let parts: Vec<String> = name
+        .map(|name| format!("\"{name}\""))
+        .into_iter()
+        .chain(tags.iter().map(|tag| format!("#{tag}")))
+        .collect();
+    (!parts.is_empty()).then(|| parts.join(" "))
+}
+
+fn parse_session_id(session: &str) -> Result<Uuid> {
+    Uuid::parse_str(session).with_context(|| format!("'{session}' is not a session id"))
+}
+
+fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["B", "KiB", "MiB", "GiB"];
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{bytes} B")
+    } else {
+        format!("{value:.1} {}", UNITS[unit])
+    }
+}
+
+/// Accepts a session id or a path to a rollout file.
+async fn resolve_session_path(config: &Config, session: &str) -> Result<PathBuf> {
+    let as_path = PathBuf::from(session);
+    if as_path.is_file() {
+        return Ok(as_path);
+    }
+    match find_conversation_path_by_id_str(&config.codex_home, session).await? {
+        Some(path) => Ok(path),
+        None => bail!("No saved session found with id '{session}'."),
+    }
+}
+
+fn hit_to_json(hit: &SessionSearchHit) -> serde_json::Value {
+    serde_json::json!({
+        "id": hit.id.to_string(),
+        "path": hit.path,
+        "created_at": hit.created_at,
+        "updated_at": hit.updated_at,
+        "cwd": hit.cwd,
+        "preview": hit.preview,
+        "name": hit.name,
+        "tags": hit.tags,
+        "matches": hit
+            .matches
+            .iter()
+            .map(|m| serde_json::json!({ "kind": m.kind.label(), "snippet": m.snippet }))
+            .collect::<Vec<_>>(),
+    })
+}
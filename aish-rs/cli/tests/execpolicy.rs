@@ -59,3 +59,58 @@ prefix_rule(
 
     Ok(())
 }
+
+#[test]
+fn execpolicy_list_explain_and_remove_rules() -> Result<(), Box<dyn std::error::Error>> {
+    let codex_home = TempDir::new()?;
+    let rules_dir = codex_home.path().join("rules");
+    fs::create_dir_all(&rules_dir)?;
+    let policy_path = rules_dir.join("default.rules");
+    fs::write(
+        &policy_path,
+        r#"prefix_rule(pattern = ["git", "push"])
+flag_rule(
+    pattern = ["git", "push"],
+    flags = ["-f"],
+    decision = "prompt",
+)
+"#,
+    )?;
+    let aish = |args: &[&str]| -> Result<std::process::Output, Box<dyn std::error::Error>> {
+        Ok(Command::new(aish_utils_cargo_bin::cargo_bin("aish")?)
+            .env("AISH_HOME", codex_home.path())
+            .arg("execpolicy")
+            .args(args)
+            .output()?)
+    };
+
+    let output = aish(&["list", "--json"])?;
+    assert!(output.status.success());
+    let listed: serde_json::Value = serde_json::from_slice(&output.stdout)?;
+    assert_eq!(
+        listed
+            .as_array()
+            .expect("list --json prints an array")
+            .iter()
+            .map(|rule| (rule["line"].clone(), rule["decision"].clone()))
+            .collect::<Vec<_>>(),
+        vec![(json!(1), json!("allow")), (json!(2), json!("prompt"))]
+    );
+
+    let output = aish(&["explain", "bash", "-lc", "git push -f && ls"])?;
+    assert!(output.status.success());
+    let explained = String::from_utf8(output.stdout)?;
+    assert!(explained.contains("matched prefix `git push` and flag `-f`"));
+    assert!(explained.contains("=> prompt"));
+    assert!(explained.contains("$ ls\n  no rule matches"));
+
+    let location = format!("{}:3", policy_path.display());
+    let output = aish(&["remove", &location])?;
+    assert!(output.status.success());
+    assert_eq!(
+        fs::read_to_string(&policy_path)?,
+        "prefix_rule(pattern = [\"git\", \"push\"])\n"
+    );
+
+    Ok(())
+}
@@ -16,6 +16,9 @@ pub fn summarize_sandbox_policy(sandbox_policy: &SandboxPolicy) -> String {
             network_access,
             exclude_tmpdir_env_var,
             exclude_slash_tmp,
+            network_allowlist,
+            writable_roots,
+            deny_read,
         } => {
             let mut summary = "current-dir-write".to_string();
 
@@ -27,10 +30,23 @@ pub fn summarize_sandbox_policy(sandbox_policy: &SandboxPolicy) -> String {
             if !*exclude_tmpdir_env_var {
                 writable_entries.push("$TMPDIR".to_string());
             }
+            writable_entries.extend(
+                writable_roots
+                    .iter()
+                    .map(|root| root.to_string_lossy().to_string()),
+            );
 
             summary.push_str(&format!(" [{}]", writable_entries.join(", ")));
             if *network_access {
                 summary.push_str(" (network access enabled)");
+            } else if !network_allowlist.is_empty() {
+                summary.push_str(&format!(
+                    " (network allowlist: {})",
+                    network_allowlist.join(", ")
+                ));
+            }
+            if !deny_read.is_empty() {
+                summary.push_str(&format!(" (deny read: {})", deny_read.join(", ")));
             }
             summary
         }
@@ -40,6 +56,7 @@ pub fn summarize_sandbox_policy(sandbox_policy: &SandboxPolicy) -> String {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use aish_utils_absolute_path::AbsolutePathBuf;
     use pretty_assertions::assert_eq;
 
     #[test]
@@ -64,10 +81,45 @@ mod tests {
             network_access: true,
             exclude_tmpdir_env_var: true,
             exclude_slash_tmp: true,
+            network_allowlist: Vec::new(),
+            writable_roots: Vec::new(),
+            deny_read: Vec::new(),
         });
         assert_eq!(
             summary,
             "current-dir-write [workdir] (network access enabled)"
         );
     }
+
+    #[test]
+    fn current_dir_write_summary_lists_network_allowlist() {
+        let summary = summarize_sandbox_policy(&SandboxPolicy::CurrentDirWrite {
+            network_access: false,
+            exclude_tmpdir_env_var: true,
+            exclude_slash_tmp: true,
+            network_allowlist: vec!["pypi.org".to_string(), "10.0.0.0/8:5432".to_string()],
+            writable_roots: Vec::new(),
+            deny_read: Vec::new(),
+        });
+        assert_eq!(
+            summary,
+            "current-dir-write [workdir] (network allowlist: pypi.org, 10.0.0.0/8:5432)"
+        );
+    }
+
+    #[test]
+    fn current_dir_write_summary_lists_extra_roots_and_deny_read() {
+        let summary = summarize_sandbox_policy(&SandboxPolicy::CurrentDirWrite {
+            network_access: false,
+            exclude_tmpdir_env_var: true,
+            exclude_slash_tmp: true,
+            network_allowlist: Vec::new(),
+            writable_roots: vec![AbsolutePathBuf::try_from("/opt/cache").unwrap()],
+            deny_read: vec!["~/.ssh".to_string(), ".env".to_string()],
+        });
+        assert_eq!(
+            summary,
+            "current-dir-write [workdir, /opt/cache] (deny read: ~/.ssh, .env)"
+        );
+    }
 }
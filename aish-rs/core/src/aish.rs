@@ -1,16 +1,19 @@
 use std::collections::HashMap;
 use std::fmt::Debug;
+use std::path::Path;
 use std::path::PathBuf;
 use std::sync::Arc;
 use std::sync::atomic::AtomicU64;
 
 use crate::AuthManager;
 use crate::SandboxState;
+use crate::audit::AuditLog;
 use crate::compact;
 use crate::compact::run_inline_auto_compact_task;
 use crate::compact::should_use_remote_compact_task;
 use crate::compact_remote::run_inline_remote_auto_compact_task;
 use crate::exec_policy::ExecPolicyManager;
+use crate::exec_policy::exec_policy_commands;
 use crate::features::Feature;
 use crate::features::Features;
 use crate::models_manager::manager::ModelsManager;
@@ -32,6 +35,8 @@ use aish_protocol::protocol::ItemCompletedEvent;
 use aish_protocol::protocol::ItemStartedEvent;
 use aish_protocol::protocol::RawResponseItemEvent;
 use aish_protocol::protocol::RolloutItem;
+use aish_protocol::protocol::SessionMetadataItem;
+use aish_protocol::protocol::SessionMetadataUpdate;
 use aish_protocol::protocol::SessionSource;
 use aish_protocol::protocol::TaskStartedEvent;
 use aish_protocol::protocol::TurnAbortReason;
@@ -66,6 +71,7 @@ use tracing::trace_span;
 use tracing::warn;
 
 use crate::ModelProviderInfo;
+use crate::background_jobs::BackgroundJobManager;
 use crate::client::ModelClient;
 use crate::client_common::Prompt;
 use crate::client_common::ResponseEvent;
@@ -74,6 +80,7 @@ use crate::config::Config;
 use crate::config::Constrained;
 use crate::config::ConstraintResult;
 use crate::config::GhostSnapshotConfig;
+use crate::config::types::SandboxBackend;
 use crate::config::types::ShellEnvironmentPolicy;
 use crate::context_manager::ContextManager;
 use crate::environment_context::EnvironmentContext;
@@ -88,6 +95,7 @@ use crate::project_doc::get_user_instructions;
 use crate::protocol::AgentMessageContentDeltaEvent;
 use crate::protocol::AgentReasoningSectionBreakEvent;
 use crate::protocol::ApplyPatchApprovalRequestEvent;
+use crate::protocol::ApprovalGrant;
 use crate::protocol::AskForApproval;
 use crate::protocol::BackgroundEventEvent;
 use crate::protocol::DeprecationNoticeEvent;
@@ -95,6 +103,7 @@ use crate::protocol::ErrorEvent;
 use crate::protocol::Event;
 use crate::protocol::EventMsg;
 use crate::protocol::ExecApprovalRequestEvent;
+use crate::protocol::ModelProviderSwitchedEvent;
 use crate::protocol::Op;
 use crate::protocol::ReasoningContentDeltaEvent;
 use crate::protocol::ReasoningRawContentDeltaEvent;
@@ -113,7 +122,9 @@ use crate::protocol::WarningEvent;
 use crate::rollout::RolloutRecorder;
 use crate::rollout::RolloutRecorderParams;
 use crate::rollout::map_session_init_error;
+use crate::sandboxing::container::ContainerSandbox;
 use crate::shell;
+use crate::shell_timeouts::ShellTimeouts;
 use crate::skills::SkillError;
 use crate::skills::SkillInjections;
 use crate::skills::SkillMetadata;
@@ -129,9 +140,12 @@ use crate::tools::ToolRouter;
 use crate::tools::context::SharedTurnDiffTracker;
 use crate::tools::parallel::ToolCallRuntime;
 use crate::tools::sandboxing::ApprovalStore;
+use crate::tools::sandboxing::BypassSource;
+use crate::tools::sandboxing::ExecApprovalRequirement;
 use crate::tools::spec::ToolsConfig;
 use crate::tools::spec::ToolsConfigParams;
 use crate::turn_diff_tracker::TurnDiffTracker;
+use crate::unified_exec::UnifiedExecSessionManager;
 use crate::user_instructions::DeveloperInstructions;
 use crate::user_instructions::UserInstructions;
 use crate::user_notification::UserNotification;
@@ -166,6 +180,8 @@ pub struct AishSpawnOk {
 
 pub(crate) const INITIAL_SUBMIT_ID: &str = "";
 pub(crate) const SUBMISSION_CHANNEL_CAPACITY: usize = 64;
+/// Share of `session_budget` spent at which the session warns once.
+const SESSION_BUDGET_WARNING_RATIO: f64 = 0.8;
 
 impl Aish {
     /// Spawn a new [`Aish`] and initialize the session.
@@ -215,6 +231,7 @@ impl Aish {
             )
         })?;
         let session_configuration = SessionConfiguration {
+            model_provider_id: config.model_provider_id.clone(),
             provider: config.model_provider.clone(),
             model: model.clone(),
             model_reasoning_effort: config.model_reasoning_effort,
@@ -327,10 +344,13 @@ pub(crate) struct TurnContext {
     pub(crate) approval_policy: AskForApproval,
     pub(crate) sandbox_policy: SandboxPolicy,
     pub(crate) shell_environment_policy: ShellEnvironmentPolicy,
+    pub(crate) shell_timeouts: ShellTimeouts,
     pub(crate) tools_config: ToolsConfig,
     pub(crate) ghost_snapshot: GhostSnapshotConfig,
     pub(crate) final_output_json_schema: Option<Value>,
     pub(crate) aish_linux_sandbox_exe: Option<PathBuf>,
+    pub(crate) sandbox_backend: SandboxBackend,
+    pub(crate) sandbox_container: ContainerSandbox,
     pub(crate) tool_call_gate: Arc<ReadinessFlag>,
     pub(crate) truncation_policy: TruncationPolicy,
 }
@@ -351,6 +371,9 @@ impl TurnContext {
 
 #[derive(Clone)]
 pub(crate) struct SessionConfiguration {
+    /// Key of `provider` in the `model_providers` map.
+    model_provider_id: String,
+
     /// Provider identifier ("openai", "openrouter", ...).
     provider: ModelProviderInfo,
 
@@ -436,6 +459,8 @@ impl Session {
         let mut per_turn_config = (*config).clone();
         per_turn_config.model_reasoning_effort = session_configuration.model_reasoning_effort;
         per_turn_config.model_reasoning_summary = session_configuration.model_reasoning_summary;
+        per_turn_config.model_provider_id = session_configuration.model_provider_id.clone();
+        per_turn_config.model_provider = session_configuration.provider.clone();
         per_turn_config.features = config.features.clone();
         per_turn_config
     }
@@ -478,10 +503,13 @@ impl Session {
             approval_policy: session_configuration.approval_policy.value(),
             sandbox_policy: session_configuration.sandbox_policy.get().clone(),
             shell_environment_policy: per_turn_config.shell_environment_policy.clone(),
+            shell_timeouts: per_turn_config.shell_timeouts.clone(),
             tools_config,
             ghost_snapshot: per_turn_config.ghost_snapshot.clone(),
             final_output_json_schema: None,
             aish_linux_sandbox_exe: per_turn_config.aish_linux_sandbox_exe.clone(),
+            sandbox_backend: per_turn_config.sandbox_backend,
+            sandbox_container: per_turn_config.sandbox_container.clone(),
             tool_call_gate: Arc::new(ReadinessFlag::new()),
             truncation_policy: TruncationPolicy::new(
                 per_turn_config.as_ref(),
@@ -588,6 +616,9 @@ impl Session {
             models_manager: Arc::clone(&models_manager),
             tool_approvals: Mutex::new(ApprovalStore::default()),
             skills_manager,
+            unified_exec_manager: UnifiedExecSessionManager::default(),
+            background_jobs: BackgroundJobManager::new(tx_event.clone()),
+            audit: AuditLog::new(config.audit.clone()),
         };
 
         let sess = Arc::new(Session {
@@ -655,6 +686,10 @@ impl Session {
         self.tx_event.clone()
     }
 
+    pub(crate) fn conversation_id(&self) -> ConversationId {
+        self.conversation_id
+    }
+
     /// Ensure all rollout writes are durably flushed.
     pub(crate) async fn flush_rollout(&self) {
         let recorder = {
@@ -722,6 +757,13 @@ impl Session {
                     }
                 }
 
+                if let Some(metadata) = rollout_items.iter().rev().find_map(|item| match item {
+                    RolloutItem::SessionMetadata(metadata) => Some(metadata.clone()),
+                    _ => None,
+                }) {
+                    self.state.lock().await.session_metadata = metadata;
+                }
+
                 // Always add response items to conversation history
                 let reconstructed_history =
                     self.reconstruct_history_from_rollout(&turn_context, &rollout_items);
@@ -857,6 +899,60 @@ impl Session {
             .await
     }
 
+    /// Moves the session to the next provider of the profile's `fallback`
+    /// list after `turn_context`'s provider failed with `err`, and returns the
+    /// turn context to continue the turn with. Returns `None` once the list is
+    /// used up.
+    pub(crate) async fn switch_to_fallback_provider(
+        &self,
+        turn_context: &TurnContext,
+        err: &AishErr,
+    ) -> Option<Arc<TurnContext>> {
+        let config = turn_context.client.config();
+        let (previous_provider_id, session_configuration) = {
+            let mut state = self.state.lock().await;
+            let fallback = config
+                .model_provider_fallbacks
+                .get(state.model_provider_fallbacks_used)?
+                .clone();
+            state.model_provider_fallbacks_used += 1;
+            let configuration = &mut state.session_configuration;
+            let previous_provider_id =
+                std::mem::replace(&mut configuration.model_provider_id, fallback.provider_id);
+            configuration.provider = fallback.provider;
+            if let Some(model) = fallback.model {
+                configuration.model = model;
+            }
+            (previous_provider_id, state.session_configuration.clone())
+        };
+
+        let message = format!(
+            "Model provider `{previous_provider_id}` failed: {err}. Switching to `{}` ({}).",
+            session_configuration.model_provider_id, session_configuration.model
+        );
+        warn!("{message}");
+        self.send_event(turn_context, EventMsg::Warning(WarningEvent { message }))
+            .await;
+        self.send_event(
+            turn_context,
+            EventMsg::ModelProviderSwitched(ModelProviderSwitchedEvent {
+                model_provider_id: session_configuration.model_provider_id.clone(),
+                model: session_configuration.model.clone(),
+            }),
+        )
+        .await;
+
+        Some(
+            self.new_turn_from_configuration(
+                turn_context.sub_id.clone(),
+                session_configuration,
+                Some(turn_context.final_output_json_schema.clone()),
+                false,
+            )
+            .await,
+        )
+    }
+
     fn build_environment_update_item(
         &self,
         previous: Option<&Arc<TurnContext>>,
@@ -933,6 +1029,54 @@ impl Session {
         .await;
     }
 
+    /// Records `grant` for the rest of the session and returns every grant.
+    pub(crate) async fn add_approval_grant(&self, grant: ApprovalGrant) -> Vec<ApprovalGrant> {
+        let mut state = self.state.lock().await;
+        state.add_approval_grant(grant);
+        state.approval_grants.clone()
+    }
+
+    /// Removes `grant` and returns the remaining grants, or `None` if it was
+    /// not granted.
+    pub(crate) async fn revoke_approval_grant(
+        &self,
+        grant: &ApprovalGrant,
+    ) -> Option<Vec<ApprovalGrant>> {
+        let mut state = self.state.lock().await;
+        state
+            .revoke_approval_grant(grant)
+            .then(|| state.approval_grants.clone())
+    }
+
+    /// Lets `command` run without asking when approval grants cover every
+    /// command it runs in `cwd`. Like an execpolicy allow rule, a grant also
+    /// skips the sandbox; commands the policy forbids stay forbidden.
+    pub(crate) async fn apply_approval_grants(
+        &self,
+        requirement: ExecApprovalRequirement,
+        command: &[String],
+        cwd: &Path,
+    ) -> ExecApprovalRequirement {
+        if !matches!(requirement, ExecApprovalRequirement::NeedsApproval { .. }) {
+            return requirement;
+        }
+        let commands = exec_policy_commands(command);
+        if self
+            .state
+            .lock()
+            .await
+            .approval_grants_cover(&commands, cwd)
+        {
+            ExecApprovalRequirement::Skip {
+                bypass_sandbox: true,
+                bypass_source: Some(BypassSource::Grant),
+                proposed_execpolicy_amendment: None,
+            }
+        } else {
+            requirement
+        }
+    }
+
     /// Adds an execpolicy amendment to both the in-memory and on-disk policies so future
     /// commands can use the newly approved prefix.
     pub(crate) async fn persist_execpolicy_amendment(
@@ -1222,6 +1366,23 @@ impl Session {
         }
     }
 
+    /// Applies `update` to the session's name and tags and appends the
+    /// result to the rollout.
+    pub(crate) async fn update_session_metadata(
+        &self,
+        update: &SessionMetadataUpdate,
+    ) -> SessionMetadataItem {
+        let metadata = {
+            let mut state = self.state.lock().await;
+            state.session_metadata.apply(update);
+            state.session_metadata.clone()
+        };
+        self.persist_rollout_items(&[RolloutItem::SessionMetadata(metadata.clone())])
+            .await;
+        self.flush_rollout().await;
+        metadata
+    }
+
     pub(crate) async fn clone_history(&self) -> ContextManager {
         let state = self.state.lock().await;
         state.clone_history()
@@ -1239,11 +1400,50 @@ impl Session {
                     token_usage,
                     turn_context.client.get_model_context_window(),
                 );
+                if let Some(pricing) = turn_context
+                    .client
+                    .config()
+                    .model_pricing
+                    .get(&turn_context.client.get_model())
+                {
+                    state.add_cost(pricing.cost(token_usage));
+                }
             }
         }
         self.send_token_count_event(turn_context).await;
     }
 
+    /// Warns once the session's spend nears `session_budget`. Returns the
+    /// message to end the task with once the budget is used up.
+    pub(crate) async fn check_session_budget(&self, turn_context: &TurnContext) -> Option<String> {
+        let budget = turn_context.client.config().session_budget?;
+        let (spent, send_warning) = {
+            let mut state = self.state.lock().await;
+            let spent = state.cost.session;
+            let send_warning = spent < budget
+                && spent >= budget * SESSION_BUDGET_WARNING_RATIO
+                && !state.cost.budget_warning_sent;
+            if send_warning {
+                state.cost.budget_warning_sent = true;
+            }
+            (spent, send_warning)
+        };
+
+        if spent >= budget {
+            return Some(format!(
+                "Session budget of ${budget:.2} used up (${spent:.2} spent). Start a new session or raise `session_budget` to continue."
+            ));
+        }
+        if send_warning {
+            let message = format!(
+                "Session has spent ${spent:.2} of its ${budget:.2} budget. It stops once the budget is used up."
+            );
+            self.send_event(turn_context, EventMsg::Warning(WarningEvent { message }))
+                .await;
+        }
+        None
+    }
+
     pub(crate) async fn recompute_token_usage(&self, turn_context: &TurnContext) {
         let Some(estimated_total_tokens) = self
             .clone_history()
@@ -1278,11 +1478,12 @@ impl Session {
     }
 
     async fn send_token_count_event(&self, turn_context: &TurnContext) {
-        let info = {
+        let budget = turn_context.client.config().session_budget;
+        let (info, cost) = {
             let state = self.state.lock().await;
-            state.token_info()
+            (state.token_info(), state.token_cost(budget))
         };
-        let event = EventMsg::TokenCount(TokenCountEvent { info });
+        let event = EventMsg::TokenCount(TokenCountEvent { info, cost });
         self.send_event(turn_context, event).await;
     }
 
@@ -1540,6 +1741,22 @@ async fn submission_loop(sess: Arc<Session>, config: Arc<Config>, rx_sub: Receiv
             Op::ListCustomPrompts => {
                 handlers::list_custom_prompts(&sess, sub.id.clone()).await;
             }
+            Op::ListRecipes => {
+                handlers::list_recipes(&sess, sub.id.clone()).await;
+            }
+            Op::SaveRecipe { recipe } => {
+                handlers::save_recipe(&sess, sub.id.clone(), recipe).await;
+            }
+            Op::RunRecipe { name, commands } => {
+                handlers::run_recipe(&sess, sub.id.clone(), name, commands, &mut previous_context)
+                    .await;
+            }
+            Op::UpdateSessionMetadata { update } => {
+                handlers::update_session_metadata(&sess, sub.id.clone(), update).await;
+            }
+            Op::RevokeApprovalGrant { grant } => {
+                handlers::revoke_approval_grant(&sess, sub.id.clone(), grant).await;
+            }
             Op::ListSkills { force_reload } => {
                 handlers::list_skills(&sess, sub.id.clone(), force_reload).await;
             }
@@ -1587,20 +1804,27 @@ mod handlers {
     use crate::mcp::auth::compute_auth_statuses;
     use crate::mcp::collect_mcp_snapshot_from_manager;
     use crate::tasks::CompactTask;
+    use crate::tasks::RecipeTask;
     use crate::tasks::RegularTask;
     use crate::tasks::UndoTask;
     use crate::tasks::UserShellCommandTask;
     use aish_protocol::custom_prompts::CustomPrompt;
     use aish_protocol::protocol::AishErrorInfo;
+    use aish_protocol::protocol::ApprovalGrant;
+    use aish_protocol::protocol::ApprovalGrantsUpdatedEvent;
     use aish_protocol::protocol::ErrorEvent;
     use aish_protocol::protocol::Event;
     use aish_protocol::protocol::EventMsg;
     use aish_protocol::protocol::ListCustomPromptsResponseEvent;
+    use aish_protocol::protocol::ListRecipesResponseEvent;
     use aish_protocol::protocol::ListSkillsResponseEvent;
     use aish_protocol::protocol::Op;
     use aish_protocol::protocol::ReviewDecision;
+    use aish_protocol::protocol::RolloutItem;
+    use aish_protocol::protocol::SessionMetadataUpdate;
     use aish_protocol::protocol::TurnAbortReason;
     use aish_protocol::protocol::WarningEvent;
+    use aish_protocol::recipes::Recipe;
 
     use aish_protocol::user_input::UserInput;
     use aish_rmcp_client::ElicitationAction;
@@ -1699,6 +1923,23 @@ mod handlers {
         *previous_context = Some(turn_context);
     }
 
+    pub async fn run_recipe(
+        sess: &Arc<Session>,
+        sub_id: String,
+        name: String,
+        commands: Vec<String>,
+        previous_context: &mut Option<Arc<TurnContext>>,
+    ) {
+        let turn_context = sess.new_default_turn_with_sub_id(sub_id).await;
+        sess.spawn_task(
+            Arc::clone(&turn_context),
+            Vec::new(),
+            RecipeTask::new(name, commands),
+        )
+        .await;
+        *previous_context = Some(turn_context);
+    }
+
     pub async fn resolve_elicitation(
         sess: &Arc<Session>,
         server_name: String,
@@ -1728,6 +1969,14 @@ mod handlers {
     /// Propagate a user's exec approval decision to the session.
     /// Also optionally applies an execpolicy amendment.
     pub async fn exec_approval(sess: &Arc<Session>, id: String, decision: ReviewDecision) {
+        if let ReviewDecision::ApprovedWithGrant { grant } = &decision {
+            let grants = sess.add_approval_grant(grant.clone()).await;
+            sess.send_event_raw(Event {
+                id: id.clone(),
+                msg: EventMsg::ApprovalGrantsUpdated(ApprovalGrantsUpdatedEvent { grants }),
+            })
+            .await;
+        }
         if let ReviewDecision::ApprovedExecpolicyAmendment {
             proposed_execpolicy_amendment,
         } = &decision
@@ -1843,6 +2092,42 @@ mod handlers {
         sess.send_event_raw(event).await;
     }
 
+    pub async fn list_recipes(sess: &Session, sub_id: String) {
+        let recipes = if let Some(dir) = crate::recipes::default_recipes_dir() {
+            crate::recipes::discover_recipes_in(&dir).await
+        } else {
+            Vec::new()
+        };
+
+        let event = Event {
+            id: sub_id,
+            msg: EventMsg::ListRecipesResponse(ListRecipesResponseEvent { recipes }),
+        };
+        sess.send_event_raw(event).await;
+    }
+
+    pub async fn save_recipe(sess: &Session, sub_id: String, recipe: Recipe) {
+        let result = match crate::recipes::default_recipes_dir() {
+            Some(dir) => crate::recipes::save_recipe_in(&dir, &recipe).await,
+            None => Err(std::io::Error::new(
+                std::io::ErrorKind::NotFound,
+                "could not resolve AISH_HOME",
+            )),
+        };
+        if let Err(err) = result {
+            sess.send_event_raw(Event {
+                id: sub_id,
+                msg: EventMsg::Error(ErrorEvent {
+                    message: format!("failed to save recipe '{}': {err}", recipe.name),
+                    codex_error_info: Some(AishErrorInfo::Other),
+                }),
+            })
+            .await;
+            return;
+        }
+        list_recipes(sess, sub_id).await;
+    }
+
     pub async fn list_skills(sess: &Session, sub_id: String, force_reload: bool) {
         let (skills, errors) = if sess.enabled(Feature::Skills) {
             let skills_manager = &sess.services.skills_manager;
@@ -1880,10 +2165,54 @@ mod handlers {
         .await;
     }
 
+    pub async fn update_session_metadata(
+        sess: &Session,
+        sub_id: String,
+        update: SessionMetadataUpdate,
+    ) {
+        let metadata = sess.update_session_metadata(&update).await;
+        let event = Event {
+            id: sub_id,
+            msg: EventMsg::SessionMetadataUpdated(metadata),
+        };
+        sess.send_event_raw(event).await;
+    }
+
+    pub async fn revoke_approval_grant(sess: &Session, sub_id: String, grant: ApprovalGrant) {
+        match sess.revoke_approval_grant(&grant).await {
+            Some(grants) => {
+                sess.send_event_raw(Event {
+                    id: sub_id,
+                    msg: EventMsg::ApprovalGrantsUpdated(ApprovalGrantsUpdatedEvent { grants }),
+                })
+                .await;
+            }
+            None => {
+                let message = format!(
+                    "No approval is granted for `{}`; nothing to revoke.",
+                    grant.prefix.join(" ")
+                );
+                sess.send_event_raw(Event {
+                    id: sub_id,
+                    msg: EventMsg::Warning(WarningEvent { message }),
+                })
+                .await;
+            }
+        }
+    }
+
     pub async fn shutdown(sess: &Arc<Session>, sub_id: String) -> bool {
         sess.abort_all_tasks(TurnAbortReason::Interrupted).await;
         info!("Shutting down Aish instance");
 
+        // Repeat the name and tags at the end of the rollout: session listings
+        // only read the tail of long rollout files.
+        let metadata = sess.state.lock().await.session_metadata.clone();
+        if !metadata.is_empty() {
+            sess.persist_rollout_items(&[RolloutItem::SessionMetadata(metadata)])
+                .await;
+        }
+
         // Gracefully flush and shutdown rollout recorder on session end so tests
         // that inspect the rollout file do not race with the background writer.
         let recorder_opt = {
@@ -1952,7 +2281,7 @@ fn errors_to_info(errors: &[SkillError]) -> Vec<SkillErrorInfo> {
 ///
 pub(crate) async fn run_task(
     sess: Arc<Session>,
-    turn_context: Arc<TurnContext>,
+    mut turn_context: Arc<TurnContext>,
     input: Vec<UserInput>,
     cancellation_token: CancellationToken,
 ) -> Option<String> {
@@ -1974,6 +2303,16 @@ pub(crate) async fn run_task(
     });
     sess.send_event(&turn_context, event).await;
 
+    sess.state.lock().await.start_turn_cost();
+    if let Some(message) = sess.check_session_budget(&turn_context).await {
+        let event = EventMsg::Error(ErrorEvent {
+            message,
+            codex_error_info: None,
+        });
+        sess.send_event(&turn_context, event).await;
+        return None;
+    }
+
     let skills_outcome = sess
         .enabled(Feature::Skills)
         .then(|| sess.services.skills_manager.skills());
@@ -2045,6 +2384,16 @@ pub(crate) async fn run_task(
                     needs_follow_up,
                     last_agent_message: turn_last_agent_message,
                 } = turn_output;
+                if let Some(message) = sess.check_session_budget(&turn_context).await
+                    && needs_follow_up
+                {
+                    let event = EventMsg::Error(ErrorEvent {
+                        message,
+                        codex_error_info: None,
+                    });
+                    sess.send_event(&turn_context, event).await;
+                    break;
+                }
                 let total_usage_tokens = sess.get_total_token_usage().await;
                 let token_limit_reached = total_usage_tokens >= auto_compact_limit;
 
@@ -2080,6 +2429,13 @@ pub(crate) async fn run_task(
                 state.history.replace_last_turn_images("Invalid image");
             }
             Err(e) => {
+                if e.is_provider_failure()
+                    && let Some(next_turn_context) =
+                        sess.switch_to_fallback_provider(&turn_context, &e).await
+                {
+                    turn_context = next_turn_context;
+                    continue;
+                }
                 info!("Turn error: {e:#}");
                 let event = EventMsg::Error(e.to_error_event(None));
                 sess.send_event(&turn_context, event).await;
@@ -2545,6 +2901,83 @@ mod tests {
         assert_eq!(expected, actual);
     }
 
+    #[tokio::test]
+    async fn approval_grants_skip_approval_only_where_they_apply() {
+        let (session, _turn_context) = make_session_and_context().await;
+        let needs_approval = || ExecApprovalRequirement::NeedsApproval {
+            reason: None,
+            proposed_execpolicy_amendment: None,
+        };
+        let command =
+            |script: &str| vec!["bash".to_string(), "-lc".to_string(), script.to_string()];
+        let project = Path::new("/work/project");
+
+        session
+            .add_approval_grant(ApprovalGrant {
+                prefix: vec!["cargo".to_string(), "test".to_string()],
+                directory: Some(project.to_path_buf()),
+            })
+            .await;
+        let grants = session
+            .add_approval_grant(ApprovalGrant {
+                prefix: vec!["ls".to_string()],
+                directory: None,
+            })
+            .await;
+        assert_eq!(grants.len(), 2);
+
+        let skip = ExecApprovalRequirement::Skip {
+            bypass_sandbox: true,
+            bypass_source: Some(BypassSource::Grant),
+            proposed_execpolicy_amendment: None,
+        };
+        assert_eq!(
+            session
+                .apply_approval_grants(needs_approval(), &command("cargo test && ls"), project)
+                .await,
+            skip
+        );
+        assert_eq!(
+            session
+                .apply_approval_grants(
+                    needs_approval(),
+                    &command("cargo test"),
+                    &project.join("crates/../../other"),
+                )
+                .await,
+            needs_approval()
+        );
+        assert_eq!(
+            session
+                .apply_approval_grants(needs_approval(), &command("cargo test && rm x"), project)
+                .await,
+            needs_approval()
+        );
+        let forbidden = ExecApprovalRequirement::Forbidden {
+            reason: "no".to_string(),
+        };
+        assert_eq!(
+            session
+                .apply_approval_grants(forbidden.clone(), &command("ls"), project)
+                .await,
+            forbidden
+        );
+
+        let remaining = session
+            .revoke_approval_grant(&ApprovalGrant {
+                prefix: vec!["ls".to_string()],
+                directory: None,
+            })
+            .await;
+        assert_eq!(remaining.map(|grants| grants.len()), Some(1));
+        assert_eq!(
+            session
+                .apply_approval_grants(needs_approval(), &command("ls"), project)
+                .await,
+            needs_approval()
+        );
+    }
+
     #[test]
     fn prefers_structured_content_when_present() {
         let ctr = CallToolResult {
@@ -2587,7 +3020,7 @@ mod tests {
 
         assert_eq!(
             out,
-            "command timed out after 1000 milliseconds\nCommand output"
+            "command timed out after 1.0 s, partial output follows\nCommand output"
         );
     }
 
@@ -2675,6 +3108,7 @@ mod tests {
         let model = ModelsManager::get_model_offline(config.model.as_deref())
             .unwrap_or_else(|| "test-model".to_string());
         let session_configuration = SessionConfiguration {
+            model_provider_id: config.model_provider_id.clone(),
             provider: config.model_provider.clone(),
             model,
             model_reasoning_effort: config.model_reasoning_effort,
@@ -2710,6 +3144,9 @@ mod tests {
             models_manager,
             tool_approvals: Mutex::new(ApprovalStore::default()),
             skills_manager,
+            unified_exec_manager: UnifiedExecSessionManager::default(),
+            background_jobs: BackgroundJobManager::new(tx_event.clone()),
+            audit: AuditLog::new(config.audit.clone()),
         };
 
         let turn_context = Session::make_turn_context(
@@ -2754,6 +3191,7 @@ mod tests {
         let model = ModelsManager::get_model_offline(config.model.as_deref())
             .unwrap_or_else(|| "test-model".to_string());
         let session_configuration = SessionConfiguration {
+            model_provider_id: config.model_provider_id.clone(),
             provider: config.model_provider.clone(),
             model,
             model_reasoning_effort: config.model_reasoning_effort,
@@ -2789,6 +3227,9 @@ mod tests {
             models_manager,
             tool_approvals: Mutex::new(ApprovalStore::default()),
             skills_manager,
+            unified_exec_manager: UnifiedExecSessionManager::default(),
+            background_jobs: BackgroundJobManager::new(tx_event.clone()),
+            audit: AuditLog::new(config.audit.clone()),
         };
 
         let turn_context = Arc::new(Session::make_turn_context(
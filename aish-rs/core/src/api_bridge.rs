@@ -7,11 +7,11 @@ use http::HeaderMap;
 use serde::Deserialize;
 
 use crate::auth::AishAuth;
+use crate::auth::ProviderCredential;
 use crate::error::AishErr;
 use crate::error::RetryLimitReachedError;
 use crate::error::UnexpectedResponseError;
 use crate::error::UsageLimitReachedError;
-use crate::model_provider_info::ModelProviderInfo;
 use crate::token_data::PlanType;
 
 pub(crate) fn map_api_error(err: ApiError) -> AishErr {
@@ -96,18 +96,14 @@ fn extract_request_id(headers: Option<&HeaderMap>) -> Option<String> {
 
 pub(crate) async fn auth_provider_from_auth(
     auth: Option<AishAuth>,
-    provider: &ModelProviderInfo,
+    credential: Option<ProviderCredential>,
 ) -> crate::error::Result<CoreAuthProvider> {
-    if let Some(api_key) = provider.api_key()? {
+    if let Some(credential) = credential {
         return Ok(CoreAuthProvider {
-            token: Some(api_key),
+            token: Some(credential.api_key),
         });
     }
 
-    if let Some(token) = provider.experimental_bearer_token.clone() {
-        return Ok(CoreAuthProvider { token: Some(token) });
-    }
-
     if let Some(auth) = auth {
         let token = auth.get_token().await?;
         Ok(CoreAuthProvider { token: Some(token) })
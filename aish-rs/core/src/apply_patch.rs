@@ -1,5 +1,10 @@
 use crate::aish::Session;
 use crate::aish::TurnContext;
+use crate::audit::AuditApproval;
+use crate::audit::AuditOutcome;
+use crate::audit::AuditRecord;
+use crate::audit::AuditSubject;
+use crate::exec::SandboxType;
 use crate::function_tool::FunctionCallError;
 use crate::protocol::FileChange;
 use crate::protocol::ReviewDecision;
@@ -8,7 +13,9 @@ use crate::safety::assess_patch_safety;
 use aish_apply_patch::ApplyPatchAction;
 use aish_apply_patch::ApplyPatchFileChange;
 use std::collections::HashMap;
+use std::path::Path;
 use std::path::PathBuf;
+use std::time::Instant;
 
 pub const AISH_APPLY_PATCH_ARG1: &str = "--aish-run-as-apply-patch";
 
@@ -31,14 +38,22 @@ pub(crate) enum InternalApplyPatchInvocation {
 pub(crate) struct ApplyPatchExec {
     pub(crate) action: ApplyPatchAction,
     pub(crate) user_explicitly_approved_this_action: bool,
+    /// The user's answer when they were asked; `None` if the patch was
+    /// approved automatically.
+    pub(crate) user_decision: Option<ReviewDecision>,
 }
 
+/// Decides whether `action` may be applied, asking the user when the policy
+/// requires it. Rejected patches are recorded in the audit log here, since
+/// they never reach the orchestrator.
 pub(crate) async fn apply_patch(
     sess: &Session,
     turn_context: &TurnContext,
     call_id: &str,
+    tool_name: &str,
     action: ApplyPatchAction,
 ) -> InternalApplyPatchInvocation {
+    let started = Instant::now();
     match assess_patch_safety(
         &action,
         turn_context.approval_policy,
@@ -51,6 +66,7 @@ pub(crate) async fn apply_patch(
         } => InternalApplyPatchInvocation::DelegateToExec(ApplyPatchExec {
             action,
             user_explicitly_approved_this_action: user_explicitly_approved,
+            user_decision: None,
         }),
         SafetyCheck::AskUser => {
             // Compute a readable summary of path changes to include in the
@@ -69,25 +85,73 @@ pub(crate) async fn apply_patch(
                     None,
                 )
                 .await;
-            match rx_approve.await.unwrap_or_default() {
+            let decision = rx_approve.await.unwrap_or_default();
+            match decision {
                 ReviewDecision::Approved
                 | ReviewDecision::ApprovedExecpolicyAmendment { .. }
-                | ReviewDecision::ApprovedForSession => {
+                | ReviewDecision::ApprovedForSession
+                | ReviewDecision::ApprovedWithGrant { .. } => {
                     InternalApplyPatchInvocation::DelegateToExec(ApplyPatchExec {
                         action,
                         user_explicitly_approved_this_action: true,
+                        user_decision: Some(decision),
                     })
                 }
                 ReviewDecision::Denied | ReviewDecision::Abort => {
+                    let approval = AuditApproval::User { decision };
+                    record_rejection(sess, call_id, tool_name, &action, approval, started).await;
                     InternalApplyPatchInvocation::Output(Err(FunctionCallError::RespondToModel(
                         "patch rejected by user".to_string(),
                     )))
                 }
             }
         }
-        SafetyCheck::Reject { reason } => InternalApplyPatchInvocation::Output(Err(
-            FunctionCallError::RespondToModel(format!("patch rejected: {reason}")),
-        )),
+        SafetyCheck::Reject { reason } => {
+            let approval = AuditApproval::Policy {
+                reason: Some(reason.clone()),
+                rule: None,
+            };
+            record_rejection(sess, call_id, tool_name, &action, approval, started).await;
+            InternalApplyPatchInvocation::Output(Err(FunctionCallError::RespondToModel(format!(
+                "patch rejected: {reason}"
+            ))))
+        }
+    }
+}
+
+async fn record_rejection(
+    sess: &Session,
+    call_id: &str,
+    tool_name: &str,
+    action: &ApplyPatchAction,
+    approval: AuditApproval,
+    started: Instant,
+) {
+    let audit = &sess.services.audit;
+    if !audit.is_enabled() {
+        return;
+    }
+    let record = AuditRecord::new(
+        sess.conversation_id(),
+        call_id,
+        tool_name,
+        patch_audit_subject(&action.cwd, &touched_paths(action)),
+        SandboxType::None,
+        approval,
+        AuditOutcome::Rejected,
+        None,
+        started.elapsed(),
+    );
+    audit.record(record).await;
+}
+
+/// What the audit log records for a patch. The exec argv would carry the
+/// whole patch; the touched paths say more.
+pub(crate) fn patch_audit_subject(cwd: &Path, touched_paths: &[PathBuf]) -> AuditSubject {
+    AuditSubject {
+        argv: vec!["apply_patch".to_string()],
+        cwd: cwd.to_path_buf(),
+        touched_paths: touched_paths.to_vec(),
     }
 }
 
@@ -118,6 +182,27 @@ pub(crate) fn convert_apply_patch_to_protocol(
     result
 }
 
+/// Every path `action` writes to: added, deleted and updated files, plus the
+/// destination of moves. Sorted so audit records are stable.
+pub(crate) fn touched_paths(action: &ApplyPatchAction) -> Vec<PathBuf> {
+    let mut paths: Vec<PathBuf> = action
+        .changes()
+        .iter()
+        .flat_map(|(path, change)| {
+            let move_path = match change {
+                ApplyPatchFileChange::Update {
+                    move_path: Some(move_path),
+                    ..
+                } => Some(move_path.clone()),
+                _ => None,
+            };
+            std::iter::once(path.clone()).chain(move_path)
+        })
+        .collect();
+    paths.sort();
+    paths
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -0,0 +1,361 @@
+//! Append-only audit log of the commands and file changes that tools run.
+//!
+//! Unlike rollout files, which record the conversation, the audit log records
+//! one entry per tool execution driven by the orchestrator: what ran, where,
+//! under which sandbox, who approved it and how it ended. It is configured by
+//! the `[audit]` table and can be written to a JSONL file, the local syslog
+//! daemon and the systemd journal at the same time.
+//!
+//! File records are written with a single `write(2)` on a descriptor opened
+//! with `O_APPEND`, so concurrent sessions appending to the same file do not
+//! interleave lines. Failing to write a record is logged and never fails the
+//! tool call.
+
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::Path;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use aish_protocol::ConversationId;
+use aish_protocol::protocol::ReviewDecision;
+use chrono::SecondsFormat;
+use chrono::Utc;
+use serde::Serialize;
+use tracing::warn;
+
+use crate::config::types::AuditConfig;
+use crate::exec::SandboxType;
+
+#[cfg(unix)]
+use std::os::unix::fs::OpenOptionsExt;
+
+#[cfg(unix)]
+const SYSLOG_SOCKET: &str = "/dev/log";
+#[cfg(unix)]
+const JOURNALD_SOCKET: &str = "/run/systemd/journal/socket";
+
+/// `user.info`: facility 1 (user-level), severity 6 (informational).
+#[cfg(unix)]
+const SYSLOG_PRIORITY: u8 = 14;
+
+/// What an audited tool call runs, as reported by its runtime.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct AuditSubject {
+    pub argv: Vec<String>,
+    pub cwd: PathBuf,
+    pub touched_paths: Vec<PathBuf>,
+}
+
+/// Why the orchestrator let a call run (or refused it).
+#[derive(Serialize, Debug, Clone, PartialEq)]
+#[serde(tag = "source", rename_all = "snake_case")]
+pub(crate) enum AuditApproval {
+    /// No approval was needed under the approval and sandbox policy.
+    Auto,
+    /// An execpolicy rule allowed or forbade the call.
+    Policy {
+        #[serde(skip_serializing_if = "Option::is_none")]
+        reason: Option<String>,
+        /// `file:line` of the allow rule that matched, when it is known.
+        #[serde(skip_serializing_if = "Option::is_none")]
+        rule: Option<String>,
+    },
+    /// An approval grant made earlier in the session allowed the call.
+    Grant,
+    /// The user was asked (or a decision cached for the session was reused).
+    User { decision: ReviewDecision },
+}
+
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum AuditOutcome {
+    /// The command ran; see `exit_code`. Calls that leave a process running
+    /// (interactive sessions, background jobs) record no exit code.
+    Ran,
+    /// The command was not run because approval was refused.
+    Rejected,
+    /// The command could not be started or was killed (timeout, sandbox).
+    Failed,
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub(crate) struct AuditRecord {
+    /// RFC 3339 timestamp of when the call finished.
+    pub timestamp: String,
+    pub session_id: String,
+    pub call_id: String,
+    pub tool: String,
+    pub cwd: PathBuf,
+    pub argv: Vec<String>,
+    /// Sandbox of the last attempt: `none`, `macos-seatbelt`,
+    /// `linux-seccomp` or `container`.
+    pub sandbox: &'static str,
+    pub approval: AuditApproval,
+    pub outcome: AuditOutcome,
+    pub exit_code: Option<i32>,
+    pub duration_ms: u64,
+    pub touched_paths: Vec<PathBuf>,
+}
+
+impl AuditRecord {
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn new(
+        session_id: ConversationId,
+        call_id: &str,
+        tool: &str,
+        subject: AuditSubject,
+        sandbox: SandboxType,
+        approval: AuditApproval,
+        outcome: AuditOutcome,
+        exit_code: Option<i32>,
+        duration: Duration,
+    ) -> Self {
+        Self {
+            timestamp: Utc::now().to_rfc3339_opts(SecondsFormat::Millis, true),
+            session_id: session_id.to_string(),
+            call_id: call_id.to_string(),
+            tool: tool.to_string(),
+            cwd: subject.cwd,
+            argv: subject.argv,
+            sandbox: sandbox_name(sandbox),
+            approval,
+            outcome,
+            exit_code,
+            duration_ms: u64::try_from(duration.as_millis()).unwrap_or(u64::MAX),
+            touched_paths: subject.touched_paths,
+        }
+    }
+}
+
+/// Per-session handle on the configured audit outputs.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct AuditLog {
+    config: AuditConfig,
+}
+
+impl AuditLog {
+    pub(crate) fn new(config: AuditConfig) -> Self {
+        Self { config }
+    }
+
+    pub(crate) fn is_enabled(&self) -> bool {
+        self.config.is_enabled()
+    }
+
+    /// Writes `record` to every configured output.
+    pub(crate) async fn record(&self, record: AuditRecord) {
+        if !self.is_enabled() {
+            return;
+        }
+        let config = self.config.clone();
+        let result = tokio::task::spawn_blocking(move || write_record(&config, &record)).await;
+        if let Err(err) = result {
+            warn!("failed to join audit log task: {err}");
+        }
+    }
+}
+
+fn write_record(config: &AuditConfig, record: &AuditRecord) {
+    let json = match serde_json::to_string(record) {
+        Ok(json) => json,
+        Err(err) => {
+            warn!("failed to serialize audit record: {err}");
+            return;
+        }
+    };
+
+    if let Some(path) = &config.path
+        && let Err(err) = append_line(path, &json)
+    {
+        warn!("failed to write audit log {}: {err}", path.display());
+    }
+
+    #[cfg(unix)]
+    {
+        if config.syslog
+            && let Err(err) = send_datagram(SYSLOG_SOCKET, syslog_message(&json).as_bytes())
+        {
+            warn!("failed to send audit record to syslog: {err}");
+        }
+        if config.journald
+            && let Err(err) = send_datagram(JOURNALD_SOCKET, &journald_message(record, &json))
+        {
+            warn!("failed to send audit record to journald: {err}");
+        }
+    }
+    #[cfg(not(unix))]
+    {
+        if config.syslog || config.journald {
+            warn!("audit.syslog and audit.journald are only supported on Unix");
+        }
+    }
+}
+
+fn append_line(path: &Path, json: &str) -> std::io::Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let mut options = OpenOptions::new();
+    options.append(true).create(true);
+    #[cfg(unix)]
+    options.mode(0o600);
+    let mut file = options.open(path)?;
+    let mut line = String::with_capacity(json.len() + 1);
+    line.push_str(json);
+    line.push('\n');
+    file.write_all(line.as_bytes())
+}
+
+#[cfg(unix)]
+fn send_datagram(socket: &str, message: &[u8]) -> std::io::Result<()> {
+    let sock = std::os::unix::net::UnixDatagram::unbound()?;
+    sock.send_to(message, socket)?;
+    Ok(())
+}
+
+#[cfg(unix)]
+fn syslog_message(json: &str) -> String {
+    format!("<{SYSLOG_PRIORITY}>aish[{}]: {json}", std::process::id())
+}
+
+/// Encodes `record` in the journal's native datagram protocol. Values that
+/// contain a newline use the length-prefixed binary form.
+#[cfg(unix)]
+fn journald_message(record: &AuditRecord, json: &str) -> Vec<u8> {
+    let message = format!(
+        "{} {} in {}",
+        record.tool,
+        shlex::try_join(record.argv.iter().map(String::as_str))
+            .unwrap_or_else(|_| record.argv.join(" ")),
+        record.cwd.display()
+    );
+    let exit_code = record.exit_code.map(|code| code.to_string());
+    let fields = [
+        ("MESSAGE", Some(message.as_str())),
+        ("PRIORITY", Some("6")),
+        ("SYSLOG_IDENTIFIER", Some("aish")),
+        ("AISH_SESSION_ID", Some(record.session_id.as_str())),
+        ("AISH_CALL_ID", Some(record.call_id.as_str())),
+        ("AISH_TOOL", Some(record.tool.as_str())),
+        ("AISH_SANDBOX", Some(record.sandbox)),
+        ("AISH_EXIT_CODE", exit_code.as_deref()),
+        ("AISH_AUDIT", Some(json)),
+    ];
+
+    let mut buf = Vec::new();
+    for (key, value) in fields {
+        let Some(value) = value else {
+            continue;
+        };
+        buf.extend_from_slice(key.as_bytes());
+        if value.contains('\n') {
+            buf.push(b'\n');
+            buf.extend_from_slice(&(value.len() as u64).to_le_bytes());
+            buf.extend_from_slice(value.as_bytes());
+        } else {
+            buf.push(b'=');
+            buf.extend_from_slice(value.as_bytes());
+        }
+        buf.push(b'\n');
+    }
+    buf
+}
+
+fn sandbox_name(sandbox: SandboxType) -> &'static str {
+    match sandbox {
+        SandboxType::None => "none",
+        SandboxType::MacosSeatbelt => "macos-seatbelt",
+        SandboxType::LinuxSeccomp => "linux-seccomp",
+        SandboxType::Container => "container",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use tempfile::TempDir;
+
+    fn record() -> AuditRecord {
+        AuditRecord::new(
+            ConversationId::default(),
+            "call-1",
+            "shell",
+            AuditSubject {
+                argv: vec!["bash".to_string(), "-lc".to_string(), "ls\npwd".to_string()],
+                cwd: PathBuf::from("/repo"),
+                touched_paths: Vec::new(),
+            },
+            SandboxType::LinuxSeccomp,
+            AuditApproval::User {
+                decision: ReviewDecision::Approved,
+            },
+            AuditOutcome::Ran,
+            Some(0),
+            Duration::from_millis(42),
+        )
+    }
+
+    #[tokio::test]
+    async fn records_are_appended_as_json_lines() {
+        let dir = TempDir::new().expect("tempdir");
+        let path = dir.path().join("logs").join("audit.jsonl");
+        let log = AuditLog::new(AuditConfig {
+            path: Some(path.clone()),
+            ..Default::default()
+        });
+
+        log.record(record()).await;
+        log.record(record()).await;
+
+        let contents = std::fs::read_to_string(&path).expect("read audit log");
+        let lines: Vec<serde_json::Value> = contents
+            .lines()
+            .map(|line| serde_json::from_str(line).expect("valid json"))
+            .collect();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0]["argv"][2], "ls\npwd");
+        assert_eq!(lines[0]["sandbox"], "linux-seccomp");
+        assert_eq!(
+            lines[0]["approval"],
+            serde_json::json!({"source": "user", "decision": "approved"})
+        );
+        assert_eq!(lines[0]["exit_code"], 0);
+        assert_eq!(lines[0]["duration_ms"], 42);
+    }
+
+    #[test]
+    fn policy_bypasses_name_their_source() {
+        let approval = |approval: AuditApproval| serde_json::to_value(approval).expect("serialize");
+        assert_eq!(
+            approval(AuditApproval::Grant),
+            serde_json::json!({"source": "grant"})
+        );
+        assert_eq!(
+            approval(AuditApproval::Policy {
+                reason: None,
+                rule: Some("/home/me/.aish/rules/default.rules:3".to_string()),
+            }),
+            serde_json::json!({"source": "policy", "rule": "/home/me/.aish/rules/default.rules:3"})
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn journald_fields_with_newlines_are_length_prefixed() {
+        let record = record();
+        let json = serde_json::to_string(&record).expect("serialize");
+        let message = journald_message(&record, &json);
+
+        let mut expected_message = b"MESSAGE\n".to_vec();
+        let text = "shell bash -lc 'ls\npwd' in /repo";
+        expected_message.extend_from_slice(&(text.len() as u64).to_le_bytes());
+        expected_message.extend_from_slice(text.as_bytes());
+        expected_message.extend_from_slice(b"\nPRIORITY=6\n");
+        assert!(message.starts_with(&expected_message));
+
+        let json_field = format!("AISH_AUDIT={json}\n");
+        assert!(message.ends_with(json_field.as_bytes()));
+    }
+}
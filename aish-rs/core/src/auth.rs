@@ -1,3 +1,4 @@
+use std::collections::HashMap;
 use std::fmt::Debug;
 use std::path::Path;
 use std::path::PathBuf;
@@ -6,6 +7,12 @@ use std::sync::RwLock;
 
 pub use aish_protocol::config_types::AuthMode;
 
+use crate::error::AishErr;
+use crate::error::EnvVarError;
+use crate::model_provider_info::ModelProviderInfo;
+use crate::provider_keys::ProviderKeyStorage;
+use crate::provider_keys::load_provider_key;
+
 #[cfg(any(test, feature = "test-support"))]
 use once_cell::sync::Lazy;
 #[cfg(any(test, feature = "test-support"))]
@@ -61,6 +68,102 @@ fn load_auth(_enable_codex_api_key_env: bool) -> Option<AishAuth> {
 #[derive(Clone, Debug)]
 struct CachedAuth {
     auth: Option<AishAuth>,
+    /// Stored provider keys already looked up, so requests do not hit the
+    /// keyring every turn. Cleared by [`AuthManager::reload`].
+    provider_keys: HashMap<String, Option<(String, ProviderKeyStorage)>>,
+}
+
+/// Where the API key for a model provider came from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CredentialSource {
+    /// Saved with `aish login --provider` in the OS keyring.
+    Keyring,
+    /// Saved with `aish login --provider` in `AISH_HOME/provider_keys.json`
+    /// because the keyring was unavailable.
+    File,
+    /// Read from the provider's `env_key` variable.
+    Env(String),
+    /// `experimental_bearer_token` in the provider definition.
+    ConfigBearerToken,
+}
+
+impl std::fmt::Display for CredentialSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CredentialSource::Keyring => write!(f, "OS keyring"),
+            CredentialSource::File => write!(f, "provider_keys.json"),
+            CredentialSource::Env(var) => write!(f, "${var}"),
+            CredentialSource::ConfigBearerToken => write!(f, "config.toml bearer token"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProviderCredential {
+    pub api_key: String,
+    pub source: CredentialSource,
+}
+
+/// Resolves the API key for `provider_id`: a key stored with
+/// `aish login --provider` wins, then the provider's `env_key`, then its
+/// `experimental_bearer_token`. A provider that names an `env_key` but has no
+/// key anywhere is an error.
+pub fn resolve_provider_credential(
+    codex_home: &Path,
+    provider_id: &str,
+    provider: &ModelProviderInfo,
+) -> crate::error::Result<Option<ProviderCredential>> {
+    let stored = match load_provider_key(codex_home, provider_id) {
+        Ok(stored) => stored,
+        Err(err) => {
+            tracing::warn!("failed to read stored key for provider `{provider_id}`: {err}");
+            None
+        }
+    };
+    resolve_with_stored_key(stored, provider_id, provider)
+}
+
+fn resolve_with_stored_key(
+    stored: Option<(String, ProviderKeyStorage)>,
+    provider_id: &str,
+    provider: &ModelProviderInfo,
+) -> crate::error::Result<Option<ProviderCredential>> {
+    if let Some((api_key, storage)) = stored {
+        let source = match storage {
+            ProviderKeyStorage::Keyring => CredentialSource::Keyring,
+            ProviderKeyStorage::File => CredentialSource::File,
+        };
+        return Ok(Some(ProviderCredential { api_key, source }));
+    }
+    match provider.api_key() {
+        Ok(Some(api_key)) => {
+            let var = provider.env_key.clone().unwrap_or_default();
+            return Ok(Some(ProviderCredential {
+                api_key,
+                source: CredentialSource::Env(var),
+            }));
+        }
+        Ok(None) => {}
+        Err(AishErr::EnvVar(EnvVarError { var, instructions })) => {
+            let login_hint = format!("Or run `aish login --provider {provider_id}`.");
+            let instructions = match instructions {
+                Some(instructions) => format!("{instructions} {login_hint}"),
+                None => login_hint,
+            };
+            return Err(AishErr::EnvVar(EnvVarError {
+                var,
+                instructions: Some(instructions),
+            }));
+        }
+        Err(err) => return Err(err),
+    }
+    Ok(provider
+        .experimental_bearer_token
+        .clone()
+        .map(|api_key| ProviderCredential {
+            api_key,
+            source: CredentialSource::ConfigBearerToken,
+        }))
 }
 
 #[cfg(test)]
@@ -74,6 +177,60 @@ mod tests {
         assert_eq!(auth.mode, AuthMode::ApiKey);
         assert_eq!(auth.api_key, Some("sk-test-key".to_string()));
     }
+
+    fn provider_with_env_key(env_key: &str) -> ModelProviderInfo {
+        ModelProviderInfo {
+            env_key: Some(env_key.to_string()),
+            ..crate::model_provider_info::create_oss_provider_with_base_url(
+                "http://localhost:1234/v1",
+                crate::model_provider_info::WireApi::Chat,
+            )
+        }
+    }
+
+    #[test]
+    fn stored_key_wins_over_env() {
+        let provider = provider_with_env_key("PATH");
+
+        let credential = resolve_with_stored_key(
+            Some(("sk-stored".to_string(), ProviderKeyStorage::Keyring)),
+            "local",
+            &provider,
+        )
+        .expect("resolve");
+
+        assert_eq!(
+            credential,
+            Some(ProviderCredential {
+                api_key: "sk-stored".to_string(),
+                source: CredentialSource::Keyring,
+            })
+        );
+    }
+
+    #[test]
+    fn env_key_is_used_when_nothing_is_stored() {
+        // PATH is always set, which keeps this test free of env mutation.
+        let provider = provider_with_env_key("PATH");
+
+        let credential = resolve_with_stored_key(None, "local", &provider)
+            .expect("resolve")
+            .expect("credential");
+
+        assert_eq!(credential.source, CredentialSource::Env("PATH".to_string()));
+    }
+
+    #[test]
+    fn missing_key_error_mentions_login() {
+        let provider = provider_with_env_key("AISH_TEST_KEY_THAT_IS_NEVER_SET");
+
+        let err = resolve_with_stored_key(None, "local", &provider).expect_err("missing key");
+
+        assert!(
+            err.to_string().contains("aish login --provider local"),
+            "unexpected error: {err}"
+        );
+    }
 }
 
 /// Central manager providing a single source of truth for legacy
@@ -95,7 +252,10 @@ impl AuthManager {
         let auth = load_auth(enable_codex_api_key_env);
         Self {
             codex_home,
-            inner: RwLock::new(CachedAuth { auth }),
+            inner: RwLock::new(CachedAuth {
+                auth,
+                provider_keys: HashMap::new(),
+            }),
             enable_codex_api_key_env,
         }
     }
@@ -104,7 +264,10 @@ impl AuthManager {
     #[expect(clippy::expect_used)]
     /// Create an AuthManager with a specific AishAuth, for testing only.
     pub fn from_auth_for_testing(auth: AishAuth) -> Arc<Self> {
-        let cached = CachedAuth { auth: Some(auth) };
+        let cached = CachedAuth {
+            auth: Some(auth),
+            provider_keys: HashMap::new(),
+        };
         let temp_dir = tempfile::tempdir().expect("temp codex home");
         let codex_home = temp_dir.path().to_path_buf();
         TEST_AUTH_TEMP_DIRS
@@ -121,7 +284,10 @@ impl AuthManager {
     #[cfg(any(test, feature = "test-support"))]
     /// Create an AuthManager with a specific AishAuth and codex home, for testing only.
     pub fn from_auth_for_testing_with_home(auth: AishAuth, codex_home: PathBuf) -> Arc<Self> {
-        let cached = CachedAuth { auth: Some(auth) };
+        let cached = CachedAuth {
+            auth: Some(auth),
+            provider_keys: HashMap::new(),
+        };
         Arc::new(Self {
             codex_home,
             inner: RwLock::new(cached),
@@ -145,12 +311,48 @@ impl AuthManager {
         if let Ok(mut guard) = self.inner.write() {
             let changed = !AuthManager::auths_equal(&guard.auth, &new_auth);
             guard.auth = new_auth;
+            guard.provider_keys.clear();
             changed
         } else {
             false
         }
     }
 
+    /// Resolves the API key for a model provider; see
+    /// [`resolve_provider_credential`] for the lookup order.
+    pub fn provider_credential(
+        &self,
+        provider_id: &str,
+        provider: &ModelProviderInfo,
+    ) -> crate::error::Result<Option<ProviderCredential>> {
+        let cached = self
+            .inner
+            .read()
+            .ok()
+            .and_then(|c| c.provider_keys.get(provider_id).cloned());
+        let stored = match cached {
+            Some(stored) => stored,
+            None => {
+                let stored = match load_provider_key(&self.codex_home, provider_id) {
+                    Ok(stored) => stored,
+                    Err(err) => {
+                        tracing::warn!(
+                            "failed to read stored key for provider `{provider_id}`: {err}"
+                        );
+                        None
+                    }
+                };
+                if let Ok(mut guard) = self.inner.write() {
+                    guard
+                        .provider_keys
+                        .insert(provider_id.to_string(), stored.clone());
+                }
+                stored
+            }
+        };
+        resolve_with_stored_key(stored, provider_id, provider)
+    }
+
     fn auths_equal(a: &Option<AishAuth>, b: &Option<AishAuth>) -> bool {
         match (a, b) {
             (None, None) => true,
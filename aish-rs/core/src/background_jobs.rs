@@ -0,0 +1,429 @@
+//! Commands the model started with `background: true`.
+//!
+//! A job runs through the regular exec path (sandbox, process group, output
+//! capture) in its own task, so the tool call that started it returns right
+//! away with the job id. The model then polls it with `job_status` and
+//! `job_output` and can stop it with `job_kill`. Jobs live until they exit or
+//! the session ends; every state change is reported to clients with
+//! `EventMsg::BackgroundJobsUpdate`.
+
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::sync::Mutex as StdMutex;
+use std::sync::PoisonError;
+use std::sync::atomic::AtomicU64;
+use std::sync::atomic::Ordering;
+use std::time::Duration;
+use std::time::Instant;
+
+use async_channel::Sender;
+use thiserror::Error;
+use tokio::sync::watch;
+use tokio_util::sync::CancellationToken;
+
+use crate::error::AishErr;
+use crate::error::SandboxErr;
+use crate::exec::StdoutStream;
+use crate::protocol::BackgroundJobStatus;
+use crate::protocol::BackgroundJobSummary;
+use crate::protocol::BackgroundJobsUpdateEvent;
+use crate::protocol::Event;
+use crate::protocol::EventMsg;
+use crate::protocol::SandboxPolicy;
+use crate::sandboxing::ExecEnv;
+use crate::sandboxing::execute_env;
+use crate::unified_exec::OutputBuffer;
+use crate::unified_exec::OutputPage;
+
+/// Maximum number of jobs running at the same time.
+pub(crate) const MAX_RUNNING_JOBS: usize = 16;
+
+/// How long `job_kill` waits for the process group to go away.
+const KILL_WAIT: Duration = Duration::from_secs(5);
+
+const FIRST_JOB_ID: u64 = 1;
+
+#[derive(Debug, Error)]
+pub(crate) enum BackgroundJobError {
+    #[error("unknown job id {job_id}")]
+    UnknownJobId { job_id: u64 },
+    #[error("too many running background jobs (limit {limit}); wait for one to finish or kill it")]
+    TooManyJobs { limit: usize },
+}
+
+pub(crate) struct BackgroundJobManager {
+    next_job_id: AtomicU64,
+    jobs: Arc<StdMutex<BTreeMap<u64, Arc<BackgroundJob>>>>,
+    tx_event: Sender<Event>,
+}
+
+impl Drop for BackgroundJobManager {
+    fn drop(&mut self) {
+        for job in self.lock_jobs().values() {
+            job.cancel.cancel();
+        }
+    }
+}
+
+struct BackgroundJob {
+    command: Vec<String>,
+    cwd: PathBuf,
+    started_at: Instant,
+    cancel: CancellationToken,
+    output: StdMutex<OutputBuffer>,
+    state: watch::Sender<JobState>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum JobState {
+    Running,
+    Finished {
+        status: BackgroundJobStatus,
+        duration: Duration,
+    },
+}
+
+impl BackgroundJob {
+    fn status(&self) -> BackgroundJobStatus {
+        match &*self.state.borrow() {
+            JobState::Running => BackgroundJobStatus::Running,
+            JobState::Finished { status, .. } => status.clone(),
+        }
+    }
+
+    fn is_running(&self) -> bool {
+        matches!(*self.state.borrow(), JobState::Running)
+    }
+
+    fn summary(&self, job_id: u64) -> BackgroundJobSummary {
+        BackgroundJobSummary {
+            job_id,
+            command: self.command.clone(),
+            cwd: self.cwd.clone(),
+            status: self.status(),
+        }
+    }
+
+    /// One-line description used by every job tool.
+    fn describe(&self, job_id: u64) -> String {
+        let (status, duration) = match &*self.state.borrow() {
+            JobState::Running => ("running".to_string(), self.started_at.elapsed()),
+            JobState::Finished { status, duration } => {
+                let status = match status {
+                    BackgroundJobStatus::Running => "running".to_string(),
+                    BackgroundJobStatus::Exited { exit_code } => {
+                        format!("exited with code {exit_code}")
+                    }
+                    BackgroundJobStatus::Killed => "killed".to_string(),
+                    BackgroundJobStatus::Failed { error } => format!("failed: {error}"),
+                };
+                (status, *duration)
+            }
+        };
+        format!(
+            "Job {job_id}: {status} ({:.1} s) — {}",
+            duration.as_secs_f32(),
+            self.command.join(" ")
+        )
+    }
+
+    fn take_page(&self, max_bytes: usize) -> OutputPage {
+        self.output
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .take_page(max_bytes)
+    }
+}
+
+impl BackgroundJobManager {
+    pub(crate) fn new(tx_event: Sender<Event>) -> Self {
+        Self {
+            next_job_id: AtomicU64::new(FIRST_JOB_ID),
+            jobs: Arc::new(StdMutex::new(BTreeMap::new())),
+            tx_event,
+        }
+    }
+
+    /// Start `exec_env` as a background job. Its expiration must be the
+    /// `ExecExpiration::Cancellation` for `cancel` so that `job_kill` can
+    /// stop it.
+    pub(crate) async fn start(
+        &self,
+        sub_id: String,
+        command: Vec<String>,
+        cwd: PathBuf,
+        exec_env: ExecEnv,
+        sandbox_policy: SandboxPolicy,
+        cancel: CancellationToken,
+    ) -> Result<u64, BackgroundJobError> {
+        let job_id = {
+            let mut jobs = self.lock_jobs();
+            if jobs.values().filter(|job| job.is_running()).count() >= MAX_RUNNING_JOBS {
+                return Err(BackgroundJobError::TooManyJobs {
+                    limit: MAX_RUNNING_JOBS,
+                });
+            }
+            let job_id = self.next_job_id.fetch_add(1, Ordering::Relaxed);
+            let (state, _) = watch::channel(JobState::Running);
+            jobs.insert(
+                job_id,
+                Arc::new(BackgroundJob {
+                    command,
+                    cwd,
+                    started_at: Instant::now(),
+                    cancel,
+                    output: StdMutex::new(OutputBuffer::default()),
+                    state,
+                }),
+            );
+            job_id
+        };
+        let job = self.job(job_id)?;
+
+        // Output arrives as `ExecCommandOutputDelta` events on a private
+        // channel instead of the session's event stream.
+        let (delta_tx, delta_rx) = async_channel::unbounded::<Event>();
+        let collector = tokio::spawn({
+            let job = Arc::clone(&job);
+            async move {
+                while let Ok(event) = delta_rx.recv().await {
+                    if let EventMsg::ExecCommandOutputDelta(delta) = event.msg {
+                        job.output
+                            .lock()
+                            .unwrap_or_else(PoisonError::into_inner)
+                            .push(&delta.chunk);
+                    }
+                }
+            }
+        });
+        let stdout_stream = StdoutStream {
+            sub_id: sub_id.clone(),
+            call_id: format!("job-{job_id}"),
+            tx_event: delta_tx,
+        };
+
+        let jobs = Arc::clone(&self.jobs);
+        let tx_event = self.tx_event.clone();
+        tokio::spawn(async move {
+            let result = execute_env(exec_env, &sandbox_policy, Some(stdout_stream)).await;
+            // The stream's senders are gone once the exec returns; wait for the
+            // collector so no trailing output is missed.
+            let _ = collector.await;
+            let status = match result {
+                Ok(output) => BackgroundJobStatus::Exited {
+                    exit_code: output.exit_code,
+                },
+                Err(AishErr::Sandbox(SandboxErr::Timeout { .. })) => BackgroundJobStatus::Killed,
+                Err(AishErr::Sandbox(SandboxErr::Denied { output })) => {
+                    BackgroundJobStatus::Exited {
+                        exit_code: output.exit_code,
+                    }
+                }
+                Err(err) => BackgroundJobStatus::Failed {
+                    error: err.to_string(),
+                },
+            };
+            job.state.send_replace(JobState::Finished {
+                status,
+                duration: job.started_at.elapsed(),
+            });
+            send_update(&jobs, &tx_event, sub_id).await;
+        });
+
+        Ok(job_id)
+    }
+
+    /// Status of one job, or of every job when `job_id` is `None`.
+    pub(crate) fn status(&self, job_id: Option<u64>) -> Result<String, BackgroundJobError> {
+        if let Some(job_id) = job_id {
+            return Ok(self.job(job_id)?.describe(job_id));
+        }
+        let jobs = self.lock_jobs();
+        if jobs.is_empty() {
+            return Ok("No background jobs.".to_string());
+        }
+        Ok(jobs
+            .iter()
+            .map(|(job_id, job)| job.describe(*job_id))
+            .collect::<Vec<_>>()
+            .join("\n"))
+    }
+
+    /// Status plus the output produced since the previous call, at most
+    /// `max_bytes` of it.
+    pub(crate) fn output(
+        &self,
+        job_id: u64,
+        max_bytes: usize,
+    ) -> Result<String, BackgroundJobError> {
+        let job = self.job(job_id)?;
+        let page = job.take_page(max_bytes);
+        let mut sections = vec![job.describe(job_id)];
+        if page.dropped_bytes > 0 {
+            sections.push(format!(
+                "[{} bytes of earlier output were dropped because the buffer was full]",
+                page.dropped_bytes
+            ));
+        }
+        sections.push("Output:".to_string());
+        sections.push(page.text);
+        if page.pending_bytes > 0 {
+            sections.push(format!(
+                "[{} more bytes buffered; call job_output again to read them]",
+                page.pending_bytes
+            ));
+        }
+        Ok(sections.join("\n"))
+    }
+
+    /// Stop a job (SIGTERM, then SIGKILL for its whole process group) and
+    /// wait briefly for it to finish.
+    pub(crate) async fn kill(&self, job_id: u64) -> Result<String, BackgroundJobError> {
+        let job = self.job(job_id)?;
+        job.cancel.cancel();
+        let mut state = job.state.subscribe();
+        let _ = tokio::time::timeout(
+            KILL_WAIT,
+            state.wait_for(|state| !matches!(state, JobState::Running)),
+        )
+        .await;
+        Ok(job.describe(job_id))
+    }
+
+    /// Tell clients about the current jobs, e.g. right after one started.
+    pub(crate) async fn notify_clients(&self, sub_id: String) {
+        send_update(&self.jobs, &self.tx_event, sub_id).await;
+    }
+
+    fn job(&self, job_id: u64) -> Result<Arc<BackgroundJob>, BackgroundJobError> {
+        self.lock_jobs()
+            .get(&job_id)
+            .cloned()
+            .ok_or(BackgroundJobError::UnknownJobId { job_id })
+    }
+
+    fn lock_jobs(&self) -> std::sync::MutexGuard<'_, BTreeMap<u64, Arc<BackgroundJob>>> {
+        self.jobs.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+async fn send_update(
+    jobs: &StdMutex<BTreeMap<u64, Arc<BackgroundJob>>>,
+    tx_event: &Sender<Event>,
+    sub_id: String,
+) {
+    let jobs = jobs
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .iter()
+        .map(|(job_id, job)| job.summary(*job_id))
+        .collect();
+    let event = Event {
+        id: sub_id,
+        msg: EventMsg::BackgroundJobsUpdate(BackgroundJobsUpdateEvent { jobs }),
+    };
+    let _ = tx_event.send(event).await;
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use crate::exec::ExecExpiration;
+    use crate::exec::SandboxType;
+    use crate::sandboxing::SandboxPermissions;
+    use pretty_assertions::assert_eq;
+    use std::collections::HashMap;
+
+    fn exec_env(script: &str, cancel: &CancellationToken) -> ExecEnv {
+        ExecEnv {
+            command: vec!["/bin/sh".to_string(), "-c".to_string(), script.to_string()],
+            cwd: std::env::temp_dir(),
+            env: std::env::vars().collect::<HashMap<_, _>>(),
+            expiration: ExecExpiration::Cancellation(cancel.clone()),
+            sandbox: SandboxType::None,
+            sandbox_permissions: SandboxPermissions::UseDefault,
+            justification: None,
+            arg0: None,
+            container: None,
+        }
+    }
+
+    async fn start(manager: &BackgroundJobManager, script: &str) -> u64 {
+        let cancel = CancellationToken::new();
+        manager
+            .start(
+                "sub".to_string(),
+                vec![script.to_string()],
+                std::env::temp_dir(),
+                exec_env(script, &cancel),
+                SandboxPolicy::DangerFullAccess,
+                cancel,
+            )
+            .await
+            .expect("start job")
+    }
+
+    async fn wait_until_finished(manager: &BackgroundJobManager, job_id: u64) {
+        let mut state = manager.job(job_id).expect("job").state.subscribe();
+        tokio::time::timeout(
+            Duration::from_secs(10),
+            state.wait_for(|state| !matches!(state, JobState::Running)),
+        )
+        .await
+        .expect("job finished in time")
+        .expect("state channel open");
+    }
+
+    #[tokio::test]
+    async fn finished_job_reports_exit_code_and_output() {
+        let (tx_event, rx_event) = async_channel::unbounded();
+        let manager = BackgroundJobManager::new(tx_event);
+        let job_id = start(&manager, "echo hello; exit 3").await;
+        wait_until_finished(&manager, job_id).await;
+
+        let output = manager.output(job_id, 4096).expect("job output");
+        assert!(output.starts_with("Job 1: exited with code 3"), "{output}");
+        assert!(output.contains("hello"), "{output}");
+
+        // Output is handed out once.
+        let output = manager.output(job_id, 4096).expect("job output");
+        assert!(!output.contains("hello"), "{output}");
+
+        let event = rx_event.recv().await.expect("update event");
+        let EventMsg::BackgroundJobsUpdate(update) = event.msg else {
+            panic!("unexpected event {event:?}");
+        };
+        assert_eq!(
+            update.jobs[0].status,
+            BackgroundJobStatus::Exited { exit_code: 3 }
+        );
+    }
+
+    #[tokio::test]
+    async fn kill_stops_a_running_job() {
+        let (tx_event, _rx_event) = async_channel::unbounded();
+        let manager = BackgroundJobManager::new(tx_event);
+        let job_id = start(&manager, "echo started; sleep 30").await;
+
+        let status = manager.status(Some(job_id)).expect("job status");
+        assert!(status.starts_with("Job 1: running"), "{status}");
+
+        let status = manager.kill(job_id).await.expect("kill job");
+        assert!(status.starts_with("Job 1: killed"), "{status}");
+    }
+
+    #[tokio::test]
+    async fn unknown_job_is_an_error() {
+        let (tx_event, _rx_event) = async_channel::unbounded();
+        let manager = BackgroundJobManager::new(tx_event);
+        assert!(matches!(
+            manager.output(7, 4096),
+            Err(BackgroundJobError::UnknownJobId { job_id: 7 })
+        ));
+        assert_eq!(
+            manager.status(None).expect("job status"),
+            "No background jobs."
+        );
+    }
+}
@@ -6,6 +6,8 @@ use aish_api::AggregateStreamExt;
 use aish_api::ChatClient as ApiChatClient;
 use aish_api::CompactClient as ApiCompactClient;
 use aish_api::CompactionInput as ApiCompactionInput;
+use aish_api::MessagesClient as ApiMessagesClient;
+use aish_api::MessagesOptions as ApiMessagesOptions;
 use aish_api::Prompt as ApiPrompt;
 use aish_api::ReqwestTransport;
 use aish_api::ResponseStream as ApiResponseStream;
@@ -29,6 +31,8 @@ use tokio::sync::mpsc;
 use tracing::warn;
 
 use crate::AuthManager;
+use crate::auth::ProviderCredential;
+use crate::auth::resolve_provider_credential;
 use crate::client_common::Prompt;
 use crate::client_common::ResponseEvent;
 use crate::client_common::ResponseStream;
@@ -42,6 +46,7 @@ use crate::model_provider_info::ModelProviderInfo;
 use crate::model_provider_info::WireApi;
 use crate::models_manager::model_family::ModelFamily;
 use crate::tools::spec::create_tools_json_for_chat_completions_api;
+use crate::tools::spec::create_tools_json_for_messages_api;
 use crate::tools::spec::create_tools_json_for_responses_api;
 
 #[derive(Debug, Clone)]
@@ -96,8 +101,8 @@ impl ModelClient {
         &self.provider
     }
 
-    /// Streams a single model turn using either the Responses or Chat
-    /// Completions wire API, depending on the configured provider.
+    /// Streams a single model turn using the Responses, Chat Completions or
+    /// Messages wire API, depending on the configured provider.
     ///
     /// For Chat providers, the underlying stream is optionally aggregated
     /// based on the `show_raw_agent_reasoning` flag in the config.
@@ -113,6 +118,7 @@ impl ModelClient {
                     Ok(map_response_stream(api_stream.aggregate()))
                 }
             }
+            WireApi::Messages => self.stream_messages_api(prompt).await,
         }
     }
 
@@ -139,7 +145,8 @@ impl ModelClient {
         loop {
             let auth = auth_manager.as_ref().and_then(|m| m.auth());
             let api_provider = self.provider.to_api_provider()?;
-            let api_auth = auth_provider_from_auth(auth.clone(), &self.provider).await?;
+            let api_auth =
+                auth_provider_from_auth(auth.clone(), self.provider_credential()?).await?;
             let transport = ReqwestTransport::new(build_reqwest_client());
             let client = ApiChatClient::new(transport, api_provider, api_auth);
 
@@ -165,6 +172,60 @@ impl ModelClient {
         }
     }
 
+    /// Streams a turn via the Anthropic Messages API.
+    ///
+    /// The reasoning effort selects the extended thinking budget; like Chat
+    /// Completions, this path does not support `output_schema`.
+    async fn stream_messages_api(&self, prompt: &Prompt) -> Result<ResponseStream> {
+        if prompt.output_schema.is_some() {
+            return Err(AishErr::UnsupportedOperation(
+                "output_schema is not supported for Messages API".to_string(),
+            ));
+        }
+
+        let auth_manager = self.auth_manager.clone();
+        let model_family = self.get_model_family();
+        let instructions = prompt.get_full_instructions(&model_family).into_owned();
+        let tools_json = create_tools_json_for_messages_api(&prompt.tools)?;
+        let api_prompt = build_api_prompt(prompt, instructions, tools_json);
+        let thinking_budget =
+            thinking_budget_for_effort(self.effort.or(model_family.default_reasoning_effort));
+        let conversation_id = self.conversation_id.to_string();
+        let session_source = self.session_source.clone();
+
+        let mut refreshed = false;
+        loop {
+            let auth = auth_manager.as_ref().and_then(|m| m.auth());
+            let api_provider = self.provider.to_api_provider()?;
+            let api_auth =
+                auth_provider_from_auth(auth.clone(), self.provider_credential()?).await?;
+            let transport = ReqwestTransport::new(build_reqwest_client());
+            let client = ApiMessagesClient::new(transport, api_provider, api_auth);
+
+            let options = ApiMessagesOptions {
+                max_tokens: None,
+                thinking_budget,
+                conversation_id: Some(conversation_id.clone()),
+                session_source: Some(session_source.clone()),
+            };
+
+            let stream_result = client
+                .stream_prompt(&self.get_model(), &api_prompt, options)
+                .await;
+
+            match stream_result {
+                Ok(stream) => return Ok(map_response_stream(stream)),
+                Err(ApiError::Transport(TransportError::Http { status, .. }))
+                    if status == StatusCode::UNAUTHORIZED =>
+                {
+                    handle_unauthorized(status, &mut refreshed, &auth_manager, &auth).await?;
+                    continue;
+                }
+                Err(err) => return Err(map_api_error(err)),
+            }
+        }
+    }
+
     /// Streams a turn via the OpenAI Responses API.
     ///
     /// Handles SSE fixtures, reasoning summaries, verbosity, and the
@@ -224,7 +285,8 @@ impl ModelClient {
         loop {
             let auth = auth_manager.as_ref().and_then(|m| m.auth());
             let api_provider = self.provider.to_api_provider()?;
-            let api_auth = auth_provider_from_auth(auth.clone(), &self.provider).await?;
+            let api_auth =
+                auth_provider_from_auth(auth.clone(), self.provider_credential()?).await?;
             let transport = ReqwestTransport::new(build_reqwest_client());
             let client = ApiResponsesClient::new(transport, api_provider, api_auth);
 
@@ -286,6 +348,21 @@ impl ModelClient {
         self.summary
     }
 
+    /// API key for the configured provider, preferring a key saved with
+    /// `aish login --provider` over the provider's environment variable.
+    fn provider_credential(&self) -> Result<Option<ProviderCredential>> {
+        match self.auth_manager.as_ref() {
+            Some(manager) => {
+                manager.provider_credential(&self.config.model_provider_id, &self.provider)
+            }
+            None => resolve_provider_credential(
+                &self.config.codex_home,
+                &self.config.model_provider_id,
+                &self.provider,
+            ),
+        }
+    }
+
     pub fn get_auth_manager(&self) -> Option<Arc<AuthManager>> {
         self.auth_manager.clone()
     }
@@ -301,7 +378,7 @@ impl ModelClient {
         let auth_manager = self.auth_manager.clone();
         let auth = auth_manager.as_ref().and_then(|m| m.auth());
         let api_provider = self.provider.to_api_provider()?;
-        let api_auth = auth_provider_from_auth(auth.clone(), &self.provider).await?;
+        let api_auth = auth_provider_from_auth(auth.clone(), self.provider_credential()?).await?;
         let transport = ReqwestTransport::new(build_reqwest_client());
         let client = ApiCompactClient::new(transport, api_provider, api_auth);
 
@@ -347,6 +424,19 @@ fn build_api_prompt(prompt: &Prompt, instructions: String, tools_json: Vec<Value
     }
 }
 
+/// Extended thinking budget for the Messages API. Without an effort, or with
+/// `none`, thinking stays off.
+fn thinking_budget_for_effort(effort: Option<ReasoningEffortConfig>) -> Option<u64> {
+    match effort? {
+        ReasoningEffortConfig::None => None,
+        ReasoningEffortConfig::Minimal => Some(1_024),
+        ReasoningEffortConfig::Low => Some(4_096),
+        ReasoningEffortConfig::Medium => Some(8_192),
+        ReasoningEffortConfig::High => Some(16_384),
+        ReasoningEffortConfig::XHigh => Some(32_768),
+    }
+}
+
 fn beta_feature_headers(config: &Config) -> ApiHeaderMap {
     let enabled = FEATURES
         .iter()
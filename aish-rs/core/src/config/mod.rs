@@ -1,10 +1,18 @@
+use crate::config::types::AuditConfig;
 use crate::config::types::History;
 use crate::config::types::McpServerConfig;
+use crate::config::types::ModelPricing;
+use crate::config::types::ModelProviderFallback;
+use crate::config::types::ModelProviderFallbackToml;
 use crate::config::types::Notice;
 use crate::config::types::Notifications;
+use crate::config::types::SandboxBackend;
+use crate::config::types::SandboxContainerToml;
 use crate::config::types::SandboxCurrentDirWrite;
+use crate::config::types::SessionRetention;
 use crate::config::types::ShellEnvironmentPolicy;
 use crate::config::types::ShellEnvironmentPolicyToml;
+use crate::config::types::ShellTimeoutsToml;
 use crate::config::types::Tui;
 use crate::config::types::UriBasedFileOpener;
 use crate::config_loader::ConfigLayerStack;
@@ -19,9 +27,12 @@ use crate::model_provider_info::LMSTUDIO_OSS_PROVIDER_ID;
 use crate::model_provider_info::ModelProviderInfo;
 use crate::model_provider_info::OLLAMA_OSS_PROVIDER_ID;
 use crate::model_provider_info::built_in_model_providers;
+use crate::network_allowlist::NetworkAllowlist;
 use crate::project_doc::DEFAULT_INSTRUCTIONS_FILENAME;
 use crate::protocol::AskForApproval;
 use crate::protocol::SandboxPolicy;
+use crate::sandboxing::container::ContainerSandbox;
+use crate::shell_timeouts::ShellTimeouts;
 use aish_protocol::config_types::ReasoningSummary;
 use aish_protocol::config_types::SandboxMode;
 use aish_protocol::config_types::Verbosity;
@@ -94,6 +105,10 @@ pub struct Config {
     /// Info needed to make an API request to the model.
     pub model_provider: ModelProviderInfo,
 
+    /// Providers the session switches to, in order, after `model_provider`
+    /// keeps failing with retryable errors. Set by a profile's `fallback`.
+    pub model_provider_fallbacks: Vec<ModelProviderFallback>,
+
     /// Approval policy for executing commands.
     pub approval_policy: Constrained<AskForApproval>,
 
@@ -109,6 +124,17 @@ pub struct Config {
 
     pub shell_environment_policy: ShellEnvironmentPolicy,
 
+    /// How long shell tool calls may run when the model does not set a
+    /// timeout, from `[shell_timeouts]` merged with the active profile.
+    pub shell_timeouts: ShellTimeouts,
+
+    /// Mechanism used to enforce `sandbox_policy` for shell tool calls.
+    pub sandbox_backend: SandboxBackend,
+
+    /// `[sandbox_container]` merged with the active profile; used when
+    /// `sandbox_backend` is `container` and by `aish sandbox --backend container`.
+    pub sandbox_container: ContainerSandbox,
+
     /// When `true`, `AgentReasoning` events emitted by the backend will be
     /// suppressed from the frontend output. This can reduce visual noise when
     /// users are only interested in the final agent responses.
@@ -179,6 +205,13 @@ pub struct Config {
     /// Combined provider map (defaults merged with user-defined overrides).
     pub model_providers: HashMap<String, ModelProviderInfo>,
 
+    /// Prices from `[model_pricing.<model>]`, keyed by model slug. Usage of
+    /// models without an entry is not priced.
+    pub model_pricing: HashMap<String, ModelPricing>,
+
+    /// Spend (in USD) at which a session stops starting new model requests.
+    pub session_budget: Option<f64>,
+
     /// Maximum number of bytes to include from an AISH.md project doc file.
     pub project_doc_max_bytes: usize,
 
@@ -196,6 +229,12 @@ pub struct Config {
     /// Settings that govern if and what will be written to `~/.aish/history.jsonl`.
     pub history: History,
 
+    /// Retention policy for rollout files under `~/.aish/sessions`.
+    pub sessions: SessionRetention,
+
+    /// Where to write the audit log of executed commands and file changes.
+    pub audit: AuditConfig,
+
     /// Optional URI-based file opener. If set, citations to files in the model
     /// output will be hyperlinked using the specified URI scheme.
     pub file_opener: UriBasedFileOpener,
@@ -495,12 +534,23 @@ pub struct ConfigToml {
     #[serde(default)]
     pub shell_environment_policy: ShellEnvironmentPolicyToml,
 
+    /// Per-command timeouts for shell tool calls, in seconds.
+    #[serde(default)]
+    pub shell_timeouts: ShellTimeoutsToml,
+
     /// Sandbox mode to use.
     pub sandbox_mode: Option<SandboxMode>,
 
     /// Sandbox configuration to apply if `sandbox` is `CurrentDirWrite`.
     pub sandbox_current_dir_write: Option<SandboxCurrentDirWrite>,
 
+    /// How sandboxed commands are isolated (default: `platform`).
+    pub sandbox_backend: Option<SandboxBackend>,
+
+    /// Runtime and image for `sandbox_backend = "container"`.
+    #[serde(default)]
+    pub sandbox_container: SandboxContainerToml,
+
     /// Optional external command to spawn for end-user notifications.
     #[serde(default)]
     pub notify: Option<Vec<String>>,
@@ -531,6 +581,13 @@ pub struct ConfigToml {
     #[serde(default)]
     pub model_providers: HashMap<String, ModelProviderInfo>,
 
+    /// Per-model prices used to track what a session costs.
+    #[serde(default)]
+    pub model_pricing: HashMap<String, ModelPricing>,
+
+    /// Optional per-session spending limit, in USD.
+    pub session_budget: Option<f64>,
+
     /// Maximum number of bytes to include from an AISH.md project doc file.
     pub project_doc_max_bytes: Option<usize>,
 
@@ -551,6 +608,14 @@ pub struct ConfigToml {
     #[serde(default)]
     pub history: Option<History>,
 
+    /// Retention policy for rollout files under `~/.aish/sessions`.
+    #[serde(default)]
+    pub sessions: Option<SessionRetention>,
+
+    /// Audit log of executed commands and file changes.
+    #[serde(default)]
+    pub audit: Option<AuditConfig>,
+
     /// Optional URI-based file opener. If set, citations to files in the model
     /// output will be hyperlinked using the specified URI scheme.
     pub file_opener: Option<UriBasedFileOpener>,
@@ -653,10 +718,16 @@ impl ConfigToml {
                     network_access,
                     exclude_tmpdir_env_var,
                     exclude_slash_tmp,
+                    network_allowlist,
+                    writable_roots,
+                    deny_read,
                 }) => SandboxPolicy::CurrentDirWrite {
                     network_access: *network_access,
                     exclude_tmpdir_env_var: *exclude_tmpdir_env_var,
                     exclude_slash_tmp: *exclude_slash_tmp,
+                    network_allowlist: network_allowlist.clone(),
+                    writable_roots: writable_roots.clone(),
+                    deny_read: deny_read.clone(),
                 },
                 None => SandboxPolicy::new_current_dir_write_policy(),
             },
@@ -839,6 +910,8 @@ impl Config {
             policy: sandbox_policy,
             forced_auto_mode_downgraded_on_windows,
         } = cfg.derive_sandbox_policy(sandbox_mode, config_profile.sandbox_mode, &resolved_cwd);
+        NetworkAllowlist::parse(sandbox_policy.network_allowlist())
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidInput, e))?;
         let approval_policy = approval_policy_override
             .or(config_profile.approval_policy)
             .or(cfg.approval_policy)
@@ -875,11 +948,62 @@ impl Config {
                 )
             })?
             .clone();
+        let model_provider_fallbacks = config_profile
+            .fallback
+            .clone()
+            .unwrap_or_default()
+            .into_iter()
+            .map(|entry| {
+                let (provider_id, model) = match entry {
+                    ModelProviderFallbackToml::Provider(provider) => (provider, None),
+                    ModelProviderFallbackToml::ProviderModel { provider, model } => {
+                        (provider, model)
+                    }
+                };
+                let provider = model_providers.get(&provider_id).cloned().ok_or_else(|| {
+                    std::io::Error::new(
+                        std::io::ErrorKind::NotFound,
+                        format!("Fallback model provider `{provider_id}` not found"),
+                    )
+                })?;
+                Ok(ModelProviderFallback {
+                    provider_id,
+                    provider,
+                    model,
+                })
+            })
+            .collect::<std::io::Result<Vec<_>>>()?;
 
         let shell_environment_policy = cfg.shell_environment_policy.into();
+        let shell_timeouts: ShellTimeouts = cfg
+            .shell_timeouts
+            .merge(config_profile.shell_timeouts.clone().unwrap_or_default())
+            .into();
+
+        let sandbox_backend = config_profile
+            .sandbox_backend
+            .or(cfg.sandbox_backend)
+            .unwrap_or_default();
+        let sandbox_container: ContainerSandbox = cfg
+            .sandbox_container
+            .clone()
+            .merge(config_profile.sandbox_container.clone().unwrap_or_default())
+            .into();
+        if sandbox_backend == SandboxBackend::Container {
+            sandbox_container
+                .validate()
+                .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidInput, e))?;
+        }
 
         let history = cfg.history.unwrap_or_default();
 
+        let mut audit = cfg.audit.unwrap_or_default();
+        if let Some(path) = audit.path.as_mut()
+            && path.is_relative()
+        {
+            *path = codex_home.join(&*path);
+        }
+
         let ghost_snapshot = {
             let mut config = GhostSnapshotConfig::default();
             if let Some(ghost_snapshot) = cfg.ghost_snapshot.as_ref()
@@ -963,12 +1087,16 @@ impl Config {
             model_auto_compact_token_limit: cfg.model_auto_compact_token_limit,
             model_provider_id,
             model_provider,
+            model_provider_fallbacks,
             cwd: resolved_cwd,
             approval_policy: constrained_approval_policy,
             sandbox_policy: constrained_sandbox_policy,
             did_user_set_custom_approval_policy_or_sandbox_mode,
             forced_auto_mode_downgraded_on_windows,
             shell_environment_policy,
+            shell_timeouts,
+            sandbox_backend,
+            sandbox_container,
             notify: cfg.notify,
             user_instructions,
             base_instructions,
@@ -979,6 +1107,8 @@ impl Config {
             // is important in code to differentiate the mode from the store implementation.
             mcp_oauth_credentials_store_mode: cfg.mcp_oauth_credentials_store.unwrap_or_default(),
             model_providers,
+            model_pricing: cfg.model_pricing,
+            session_budget: cfg.session_budget,
             project_doc_max_bytes: cfg.project_doc_max_bytes.unwrap_or(PROJECT_DOC_MAX_BYTES),
             project_doc_fallback_filenames: cfg
                 .project_doc_fallback_filenames
@@ -997,6 +1127,8 @@ impl Config {
             codex_home,
             config_layer_stack,
             history,
+            sessions: cfg.sessions.unwrap_or_default(),
+            audit,
             file_opener: cfg.file_opener.unwrap_or(UriBasedFileOpener::VsCode),
             aish_linux_sandbox_exe,
 
@@ -1124,6 +1256,7 @@ mod tests {
     use crate::config::edit::ConfigEdit;
     use crate::config::edit::ConfigEditsBuilder;
     use crate::config::edit::apply_blocking;
+    use crate::config::types::ContainerRuntime;
     use crate::config::types::HistoryPersistence;
     use crate::config::types::McpServerTransportConfig;
     use crate::config::types::Notifications;
@@ -1167,6 +1300,54 @@ persistence = "none"
         );
     }
 
+    #[test]
+    fn sessions_retention_defaults_to_keeping_starred() {
+        let cfg = toml::from_str::<ConfigToml>(
+            r#"
+[sessions]
+max_age_days = 30
+"#,
+        )
+        .expect("TOML deserialization should succeed");
+        assert_eq!(
+            Some(SessionRetention {
+                max_age_days: Some(30),
+                max_total_bytes: None,
+                keep_starred: true,
+            }),
+            cfg.sessions
+        );
+    }
+
+    #[test]
+    fn audit_path_is_resolved_against_aish_home() -> std::io::Result<()> {
+        let codex_home = TempDir::new()?;
+        let cfg = toml::from_str::<ConfigToml>(
+            r#"
+[audit]
+path = "audit.jsonl"
+journald = true
+"#,
+        )
+        .expect("TOML deserialization should succeed");
+
+        let config = Config::load_from_base_config_with_overrides(
+            cfg,
+            ConfigOverrides::default(),
+            codex_home.path().to_path_buf(),
+        )?;
+
+        assert_eq!(
+            AuditConfig {
+                path: Some(codex_home.path().join("audit.jsonl")),
+                syslog: false,
+                journald: true,
+            },
+            config.audit
+        );
+        Ok(())
+    }
+
     #[test]
     fn tui_config_missing_notifications_field_defaults_to_enabled() {
         let cfg = r#"
@@ -1266,6 +1447,9 @@ exclude_slash_tmp = true
                         network_access: true,
                         exclude_tmpdir_env_var: true,
                         exclude_slash_tmp: true,
+                        network_allowlist: Vec::new(),
+                        writable_roots: Vec::new(),
+                        deny_read: Vec::new(),
                     },
                     forced_auto_mode_downgraded_on_windows: false,
                 }
@@ -1322,6 +1506,38 @@ exclude_slash_tmp = true
         Ok(())
     }
 
+    #[test]
+    fn invalid_network_allowlist_entry_is_rejected() -> std::io::Result<()> {
+        let temp_dir = TempDir::new()?;
+        let cfg = toml::from_str::<ConfigToml>(
+            r#"
+sandbox_mode = "current-dir-write"
+
+[sandbox_current_dir_write]
+network_allowlist = ["pypi.org", "10.0.0.0/40"]
+"#,
+        )
+        .expect("TOML deserialization should succeed");
+
+        let result = Config::load_from_base_config_with_overrides(
+            cfg,
+            ConfigOverrides {
+                cwd: Some(temp_dir.path().to_path_buf()),
+                ..Default::default()
+            },
+            temp_dir.path().to_path_buf(),
+        );
+        if cfg!(target_os = "windows") {
+            // current-dir-write is downgraded to read-only, which has no allowlist.
+            return Ok(());
+        }
+        let err = result.expect_err("allowlist entry with an out-of-range prefix should fail");
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+        assert!(err.to_string().contains("10.0.0.0/40"));
+
+        Ok(())
+    }
+
     #[test]
     fn config_defaults_to_file_cli_auth_store_mode() -> std::io::Result<()> {
         let codex_home = TempDir::new()?;
@@ -1383,6 +1599,205 @@ exclude_slash_tmp = true
         Ok(())
     }
 
+    #[test]
+    fn profile_shell_timeouts_layer_over_base() -> std::io::Result<()> {
+        let codex_home = TempDir::new()?;
+        let cfg: ConfigToml = toml::from_str(
+            r#"
+profile = "ci"
+
+[shell_timeouts]
+default = 120
+"cargo test" = 1800
+
+[profiles.ci.shell_timeouts]
+default = 600
+"#,
+        )
+        .expect("TOML deserialization should succeed");
+
+        let config = Config::load_from_base_config_with_overrides(
+            cfg,
+            ConfigOverrides::default(),
+            codex_home.path().to_path_buf(),
+        )?;
+
+        let command =
+            |args: &[&str]| -> Vec<String> { args.iter().map(ToString::to_string).collect() };
+        assert_eq!(
+            config
+                .shell_timeouts
+                .timeout_for(&command(&["cargo", "test"])),
+            Duration::from_secs(1800)
+        );
+        assert_eq!(
+            config.shell_timeouts.timeout_for(&command(&["make"])),
+            Duration::from_secs(600)
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn profile_fallback_resolves_providers() -> std::io::Result<()> {
+        let codex_home = TempDir::new()?;
+        let cfg: ConfigToml = toml::from_str(
+            r#"
+profile = "glm"
+
+[model_providers.zhipu]
+name = "Zhipu"
+base_url = "https://open.bigmodel.cn/api/paas/v4"
+
+[profiles.glm]
+model_provider = "openai"
+fallback = ["zhipu", { provider = "ollama", model = "qwen3:8b" }]
+"#,
+        )
+        .expect("TOML deserialization should succeed");
+
+        let config = Config::load_from_base_config_with_overrides(
+            cfg,
+            ConfigOverrides::default(),
+            codex_home.path().to_path_buf(),
+        )?;
+
+        let fallbacks = config
+            .model_provider_fallbacks
+            .iter()
+            .map(|fallback| (fallback.provider_id.as_str(), fallback.model.as_deref()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            fallbacks,
+            vec![("zhipu", None), ("ollama", Some("qwen3:8b"))]
+        );
+        assert_eq!(config.model_provider_fallbacks[0].provider.name, "Zhipu");
+
+        Ok(())
+    }
+
+    #[test]
+    fn model_pricing_and_session_budget_load() -> std::io::Result<()> {
+        let codex_home = TempDir::new()?;
+        let cfg: ConfigToml = toml::from_str(
+            r#"
+session_budget = 5.0
+
+[model_pricing."gpt-5.1"]
+input = 1.25
+cached_input = 0.125
+output = 10.0
+"#,
+        )
+        .expect("TOML deserialization should succeed");
+
+        let config = Config::load_from_base_config_with_overrides(
+            cfg,
+            ConfigOverrides::default(),
+            codex_home.path().to_path_buf(),
+        )?;
+
+        assert_eq!(config.session_budget, Some(5.0));
+        assert_eq!(
+            config.model_pricing.get("gpt-5.1"),
+            Some(&ModelPricing {
+                input: 1.25,
+                cached_input: Some(0.125),
+                output: 10.0,
+            })
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn profile_fallback_rejects_unknown_provider() {
+        let codex_home = TempDir::new().expect("tempdir");
+        let cfg: ConfigToml = toml::from_str(
+            r#"
+profile = "glm"
+
+[profiles.glm]
+fallback = ["missing"]
+"#,
+        )
+        .expect("TOML deserialization should succeed");
+
+        let err = Config::load_from_base_config_with_overrides(
+            cfg,
+            ConfigOverrides::default(),
+            codex_home.path().to_path_buf(),
+        )
+        .expect_err("unknown fallback provider should fail");
+        assert_eq!(
+            err.to_string(),
+            "Fallback model provider `missing` not found"
+        );
+    }
+
+    #[test]
+    fn profile_selects_container_backend_and_image() -> std::io::Result<()> {
+        let codex_home = TempDir::new()?;
+        let cfg: ConfigToml = toml::from_str(
+            r#"
+profile = "shared-host"
+
+[sandbox_container]
+runtime = "podman"
+image = "docker.io/library/ubuntu:24.04"
+
+[profiles.shared-host]
+sandbox_backend = "container"
+
+[profiles.shared-host.sandbox_container]
+image = "ghcr.io/example/build:latest"
+"#,
+        )
+        .expect("TOML deserialization should succeed");
+
+        let config = Config::load_from_base_config_with_overrides(
+            cfg,
+            ConfigOverrides::default(),
+            codex_home.path().to_path_buf(),
+        )?;
+
+        assert_eq!(config.sandbox_backend, SandboxBackend::Container);
+        assert_eq!(
+            config.sandbox_container,
+            ContainerSandbox {
+                runtime: ContainerRuntime::Podman,
+                image: Some("ghcr.io/example/build:latest".to_string()),
+                extra_args: Vec::new(),
+            }
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn container_backend_requires_an_image() -> std::io::Result<()> {
+        let codex_home = TempDir::new()?;
+        let cfg: ConfigToml = toml::from_str(
+            r#"
+sandbox_backend = "container"
+
+[sandbox_container]
+runtime = "docker"
+"#,
+        )
+        .expect("TOML deserialization should succeed");
+
+        let err = Config::load_from_base_config_with_overrides(
+            cfg,
+            ConfigOverrides::default(),
+            codex_home.path().to_path_buf(),
+        )
+        .expect_err("docker without an image should be rejected");
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+
+        Ok(())
+    }
+
     #[test]
     fn profile_sandbox_mode_overrides_base() -> std::io::Result<()> {
         let codex_home = TempDir::new()?;
@@ -2577,6 +2992,7 @@ model_verbosity = "high"
             base_url: Some("https://api.openai.com/v1".to_string()),
             env_key: Some("OPENAI_API_KEY".to_string()),
             wire_api: crate::WireApi::Chat,
+            prompt_cache: None,
             env_key_instructions: None,
             experimental_bearer_token: None,
             query_params: None,
@@ -2643,23 +3059,31 @@ model_verbosity = "high"
                 model_auto_compact_token_limit: None,
                 model_provider_id: "openai".to_string(),
                 model_provider: fixture.openai_provider.clone(),
+                model_provider_fallbacks: Vec::new(),
                 approval_policy: Constrained::allow_any(AskForApproval::Never),
                 sandbox_policy: Constrained::allow_any(SandboxPolicy::new_read_only_policy()),
                 did_user_set_custom_approval_policy_or_sandbox_mode: true,
                 forced_auto_mode_downgraded_on_windows: false,
                 shell_environment_policy: ShellEnvironmentPolicy::default(),
+                shell_timeouts: ShellTimeouts::default(),
+                sandbox_backend: SandboxBackend::default(),
+                sandbox_container: ContainerSandbox::default(),
                 user_instructions: None,
                 notify: None,
                 cwd: fixture.cwd(),
                 mcp_servers: HashMap::new(),
                 mcp_oauth_credentials_store_mode: Default::default(),
                 model_providers: fixture.model_provider_map.clone(),
+                model_pricing: HashMap::new(),
+                session_budget: None,
                 project_doc_max_bytes: PROJECT_DOC_MAX_BYTES,
                 project_doc_fallback_filenames: Vec::new(),
                 tool_output_token_limit: None,
                 codex_home: fixture.codex_home(),
                 config_layer_stack: Default::default(),
                 history: History::default(),
+                sessions: SessionRetention::default(),
+                audit: AuditConfig::default(),
                 file_opener: UriBasedFileOpener::VsCode,
                 aish_linux_sandbox_exe: None,
                 hide_agent_reasoning: false,
@@ -2707,23 +3131,31 @@ model_verbosity = "high"
             model_auto_compact_token_limit: None,
             model_provider_id: "openai-chat-completions".to_string(),
             model_provider: fixture.openai_chat_completions_provider.clone(),
+            model_provider_fallbacks: Vec::new(),
             approval_policy: Constrained::allow_any(AskForApproval::UnlessTrusted),
             sandbox_policy: Constrained::allow_any(SandboxPolicy::new_read_only_policy()),
             did_user_set_custom_approval_policy_or_sandbox_mode: true,
             forced_auto_mode_downgraded_on_windows: false,
             shell_environment_policy: ShellEnvironmentPolicy::default(),
+            shell_timeouts: ShellTimeouts::default(),
+            sandbox_backend: SandboxBackend::default(),
+            sandbox_container: ContainerSandbox::default(),
             user_instructions: None,
             notify: None,
             cwd: fixture.cwd(),
             mcp_servers: HashMap::new(),
             mcp_oauth_credentials_store_mode: Default::default(),
             model_providers: fixture.model_provider_map.clone(),
+            model_pricing: HashMap::new(),
+            session_budget: None,
             project_doc_max_bytes: PROJECT_DOC_MAX_BYTES,
             project_doc_fallback_filenames: Vec::new(),
             tool_output_token_limit: None,
             codex_home: fixture.codex_home(),
             config_layer_stack: Default::default(),
             history: History::default(),
+            sessions: SessionRetention::default(),
+            audit: AuditConfig::default(),
             file_opener: UriBasedFileOpener::VsCode,
             aish_linux_sandbox_exe: None,
             hide_agent_reasoning: false,
@@ -2786,23 +3218,31 @@ model_verbosity = "high"
             model_auto_compact_token_limit: None,
             model_provider_id: "openai".to_string(),
             model_provider: fixture.openai_provider.clone(),
+            model_provider_fallbacks: Vec::new(),
             approval_policy: Constrained::allow_any(AskForApproval::OnFailure),
             sandbox_policy: Constrained::allow_any(SandboxPolicy::new_read_only_policy()),
             did_user_set_custom_approval_policy_or_sandbox_mode: true,
             forced_auto_mode_downgraded_on_windows: false,
             shell_environment_policy: ShellEnvironmentPolicy::default(),
+            shell_timeouts: ShellTimeouts::default(),
+            sandbox_backend: SandboxBackend::default(),
+            sandbox_container: ContainerSandbox::default(),
             user_instructions: None,
             notify: None,
             cwd: fixture.cwd(),
             mcp_servers: HashMap::new(),
             mcp_oauth_credentials_store_mode: Default::default(),
             model_providers: fixture.model_provider_map.clone(),
+            model_pricing: HashMap::new(),
+            session_budget: None,
             project_doc_max_bytes: PROJECT_DOC_MAX_BYTES,
             project_doc_fallback_filenames: Vec::new(),
             tool_output_token_limit: None,
             codex_home: fixture.codex_home(),
             config_layer_stack: Default::default(),
             history: History::default(),
+            sessions: SessionRetention::default(),
+            audit: AuditConfig::default(),
             file_opener: UriBasedFileOpener::VsCode,
             aish_linux_sandbox_exe: None,
             hide_agent_reasoning: false,
@@ -2851,23 +3291,31 @@ model_verbosity = "high"
             model_auto_compact_token_limit: None,
             model_provider_id: "openai".to_string(),
             model_provider: fixture.openai_provider.clone(),
+            model_provider_fallbacks: Vec::new(),
             approval_policy: Constrained::allow_any(AskForApproval::OnFailure),
             sandbox_policy: Constrained::allow_any(SandboxPolicy::new_read_only_policy()),
             did_user_set_custom_approval_policy_or_sandbox_mode: true,
             forced_auto_mode_downgraded_on_windows: false,
             shell_environment_policy: ShellEnvironmentPolicy::default(),
+            shell_timeouts: ShellTimeouts::default(),
+            sandbox_backend: SandboxBackend::default(),
+            sandbox_container: ContainerSandbox::default(),
             user_instructions: None,
             notify: None,
             cwd: fixture.cwd(),
             mcp_servers: HashMap::new(),
             mcp_oauth_credentials_store_mode: Default::default(),
             model_providers: fixture.model_provider_map.clone(),
+            model_pricing: HashMap::new(),
+            session_budget: None,
             project_doc_max_bytes: PROJECT_DOC_MAX_BYTES,
             project_doc_fallback_filenames: Vec::new(),
             tool_output_token_limit: None,
             codex_home: fixture.codex_home(),
             config_layer_stack: Default::default(),
             history: History::default(),
+            sessions: SessionRetention::default(),
+            audit: AuditConfig::default(),
             file_opener: UriBasedFileOpener::VsCode,
             aish_linux_sandbox_exe: None,
             hide_agent_reasoning: false,
@@ -16,8 +16,14 @@ pub struct ConfigProfile {
     /// The key in the `model_providers` map identifying the
     /// [`ModelProviderInfo`] to use.
     pub model_provider: Option<String>,
+    /// Providers to switch to, in order, when `model_provider` keeps failing
+    /// with retryable errors after its retries run out.
+    pub fallback: Option<Vec<crate::config::types::ModelProviderFallbackToml>>,
     pub approval_policy: Option<AskForApproval>,
     pub sandbox_mode: Option<SandboxMode>,
+    pub sandbox_backend: Option<crate::config::types::SandboxBackend>,
+    /// Entries layered over the top-level `[sandbox_container]` table.
+    pub sandbox_container: Option<crate::config::types::SandboxContainerToml>,
     pub model_reasoning_effort: Option<ReasoningEffort>,
     pub model_reasoning_summary: Option<ReasoningSummary>,
     pub model_verbosity: Option<Verbosity>,
@@ -27,6 +33,8 @@ pub struct ConfigProfile {
     pub experimental_use_freeform_apply_patch: Option<bool>,
     pub tools_web_search: Option<bool>,
     pub tools_view_image: Option<bool>,
+    /// Entries layered over the top-level `[shell_timeouts]` table.
+    pub shell_timeouts: Option<crate::config::types::ShellTimeoutsToml>,
     /// Optional feature toggles scoped to this profile.
     #[serde(default)]
     pub features: Option<crate::features::FeaturesToml>,
@@ -3,6 +3,9 @@
 // Note this file should generally be restricted to simple struct/enum
 // definitions that do not contain business logic.
 
+use crate::model_provider_info::ModelProviderInfo;
+use aish_protocol::protocol::TokenUsage;
+use aish_utils_absolute_path::AbsolutePathBuf;
 use std::collections::HashMap;
 use std::path::PathBuf;
 use std::time::Duration;
@@ -259,6 +262,63 @@ pub struct History {
     pub max_bytes: Option<usize>,
 }
 
+/// Retention policy for rollout files under `~/.aish/sessions`, from the
+/// `[sessions]` table. Sessions are only pruned when at least one limit is set.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(default)]
+pub struct SessionRetention {
+    /// Delete sessions that have not been written to for this many days.
+    pub max_age_days: Option<u64>,
+
+    /// Delete the least recently used sessions until the sessions directory
+    /// is at most this many bytes.
+    pub max_total_bytes: Option<u64>,
+
+    /// Never delete sessions starred with `/star` or `aish sessions star`, or
+    /// named with `/rename`.
+    pub keep_starred: bool,
+}
+
+impl Default for SessionRetention {
+    fn default() -> Self {
+        Self {
+            max_age_days: None,
+            max_total_bytes: None,
+            keep_starred: true,
+        }
+    }
+}
+
+impl SessionRetention {
+    /// Whether any limit is configured.
+    pub fn is_enabled(&self) -> bool {
+        self.max_age_days.is_some() || self.max_total_bytes.is_some()
+    }
+}
+
+/// Append-only audit log of executed commands and file changes, from the
+/// `[audit]` table. Nothing is recorded unless at least one output is set.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+#[serde(default)]
+pub struct AuditConfig {
+    /// JSONL file that records are appended to. Relative paths are resolved
+    /// against `AISH_HOME`.
+    pub path: Option<PathBuf>,
+
+    /// Also send every record to the local syslog daemon (`/dev/log`).
+    pub syslog: bool,
+
+    /// Also send every record to the systemd journal.
+    pub journald: bool,
+}
+
+impl AuditConfig {
+    /// Whether any output is configured.
+    pub fn is_enabled(&self) -> bool {
+        self.path.is_some() || self.syslog || self.journald
+    }
+}
+
 #[derive(Serialize, Deserialize, Debug, Copy, Clone, PartialEq, Default)]
 #[serde(rename_all = "kebab-case")]
 pub enum HistoryPersistence {
@@ -346,6 +406,54 @@ pub struct SandboxCurrentDirWrite {
     pub exclude_tmpdir_env_var: bool,
     #[serde(default)]
     pub exclude_slash_tmp: bool,
+    #[serde(default)]
+    pub network_allowlist: Vec<String>,
+    #[serde(default)]
+    pub writable_roots: Vec<AbsolutePathBuf>,
+    #[serde(default)]
+    pub deny_read: Vec<String>,
+}
+
+/// Mechanism used to isolate sandboxed shell commands.
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, PartialEq, Eq, Default)]
+#[serde(rename_all = "kebab-case")]
+pub enum SandboxBackend {
+    /// Seatbelt on macOS, Landlock + seccomp on Linux.
+    #[default]
+    Platform,
+    /// Run each command in a throwaway container (see `[sandbox_container]`).
+    Container,
+}
+
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum ContainerRuntime {
+    #[default]
+    Podman,
+    Docker,
+    /// `bwrap`; reuses the host filesystem, so no image is needed.
+    #[serde(rename = "bwrap", alias = "bubblewrap")]
+    Bubblewrap,
+}
+
+/// `[sandbox_container]`: settings for `sandbox_backend = "container"`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+pub struct SandboxContainerToml {
+    pub runtime: Option<ContainerRuntime>,
+    /// Image to run commands in; required for podman and docker.
+    pub image: Option<String>,
+    /// Extra arguments passed to `<runtime> run` before the image.
+    pub extra_args: Option<Vec<String>>,
+}
+
+impl SandboxContainerToml {
+    /// Layer `overrides` (e.g. the active profile's table) on top of `self`.
+    pub fn merge(mut self, overrides: SandboxContainerToml) -> Self {
+        self.runtime = overrides.runtime.or(self.runtime);
+        self.image = overrides.image.or(self.image);
+        self.extra_args = overrides.extra_args.or(self.extra_args);
+        self
+    }
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
@@ -457,6 +565,72 @@ impl Default for ShellEnvironmentPolicy {
     }
 }
 
+/// `[shell_timeouts]`: how long a shell tool call may run when the model does
+/// not pass `timeout_ms`. Keys other than `default` are program names
+/// (`"apt"`) or command prefixes (`"cargo test"`); values are seconds.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+pub struct ShellTimeoutsToml {
+    /// Timeout for commands that match no entry.
+    pub default: Option<u64>,
+
+    #[serde(flatten)]
+    pub commands: HashMap<String, u64>,
+}
+
+impl ShellTimeoutsToml {
+    /// Layer `overrides` (e.g. the active profile's table) on top of `self`.
+    pub fn merge(mut self, overrides: ShellTimeoutsToml) -> Self {
+        self.default = overrides.default.or(self.default);
+        self.commands.extend(overrides.commands);
+        self
+    }
+}
+
+/// Entry of a profile's `fallback` list: either a provider id
+/// (`"ollama-local"`) or a table naming the model to use on that provider
+/// (`{ provider = "ollama-local", model = "qwen3:8b" }`).
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(untagged)]
+pub enum ModelProviderFallbackToml {
+    Provider(String),
+    ProviderModel {
+        provider: String,
+        model: Option<String>,
+    },
+}
+
+/// A provider the session switches to once the one in use keeps failing
+/// with retryable errors.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ModelProviderFallback {
+    /// Key into the `model_providers` map.
+    pub provider_id: String,
+    pub provider: ModelProviderInfo,
+    /// Model to request from `provider`. The session keeps its model when
+    /// unset.
+    pub model: Option<String>,
+}
+
+/// `[model_pricing.<model>]`: what a model costs, in USD per million tokens.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct ModelPricing {
+    pub input: f64,
+    /// Price of input tokens served from the prompt cache. Defaults to
+    /// `input`.
+    pub cached_input: Option<f64>,
+    pub output: f64,
+}
+
+impl ModelPricing {
+    /// Cost of `usage` in USD.
+    pub fn cost(&self, usage: &TokenUsage) -> f64 {
+        let cached_input = usage.cached_input() as f64 * self.cached_input.unwrap_or(self.input);
+        let input = usage.non_cached_input() as f64 * self.input;
+        let output = usage.output_tokens.max(0) as f64 * self.output;
+        (input + cached_input + output) / 1_000_000.0
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -729,4 +903,32 @@ mod tests {
             "unexpected error: {err}"
         );
     }
+
+    #[test]
+    fn model_pricing_charges_cached_input_separately() {
+        let pricing: ModelPricing = toml::from_str(
+            r#"
+            input = 2.0
+            cached_input = 0.5
+            output = 8.0
+        "#,
+        )
+        .expect("should deserialize model pricing");
+        let usage = TokenUsage {
+            input_tokens: 1_000_000,
+            cached_input_tokens: 400_000,
+            output_tokens: 100_000,
+            reasoning_output_tokens: 0,
+            total_tokens: 1_100_000,
+        };
+
+        // 600k uncached * $2 + 400k cached * $0.5 + 100k output * $8.
+        assert_eq!(pricing.cost(&usage), 2.2);
+
+        let without_cached_price = ModelPricing {
+            cached_input: None,
+            ..pricing
+        };
+        assert_eq!(without_cached_price.cost(&usage), 2.8);
+    }
 }
@@ -256,6 +256,9 @@ mod tests {
                     network_access: false,
                     exclude_tmpdir_env_var: false,
                     exclude_slash_tmp: false,
+                    network_allowlist: Vec::new(),
+                    writable_roots: Vec::new(),
+                    deny_read: Vec::new(),
                 })
                 .is_ok()
         );
@@ -0,0 +1,211 @@
+//! Resolves the `deny_read` patterns of a `current-dir-write` sandbox policy.
+//!
+//! Patterns may start with `~/`, are resolved against the cwd when relative,
+//! and may use `*` and `?` within a single path component. A pattern denies
+//! the paths it names and everything beneath them.
+
+use std::path::Component;
+use std::path::Path;
+use std::path::PathBuf;
+
+use aish_utils_absolute_path::AbsolutePathBuf;
+use wildmatch::WildMatch;
+
+use crate::protocol::SandboxPolicy;
+
+/// Expands `patterns` into the existing paths they currently name. Patterns
+/// that match nothing are skipped; files created later under a matching
+/// directory are still covered, new files matching a wildcard are not.
+pub fn resolve_deny_read_paths(patterns: &[String], cwd: &Path) -> Vec<AbsolutePathBuf> {
+    let mut resolved: Vec<AbsolutePathBuf> = Vec::new();
+    for pattern in patterns {
+        let Some(pattern) = absolute_pattern(pattern, cwd) else {
+            continue;
+        };
+        let mut candidates = vec![PathBuf::from("/")];
+        for component in pattern_components(&pattern) {
+            candidates = candidates
+                .into_iter()
+                .flat_map(|dir| expand_component(&dir, &component))
+                .collect();
+        }
+        for candidate in candidates {
+            if let Ok(path) = AbsolutePathBuf::from_absolute_path(&candidate)
+                && !resolved.contains(&path)
+            {
+                resolved.push(path);
+            }
+        }
+    }
+    resolved
+}
+
+/// Returns whether reading `path` is forbidden by the policy's `deny_read`
+/// patterns, checking both the path as given and its canonical form so a
+/// symlink cannot be used to reach a denied file.
+pub fn is_read_denied(sandbox_policy: &SandboxPolicy, cwd: &Path, path: &Path) -> bool {
+    let patterns = sandbox_policy.deny_read();
+    if patterns.is_empty() {
+        return false;
+    }
+    let Ok(path) = AbsolutePathBuf::resolve_path_against_base(path, cwd) else {
+        return false;
+    };
+    let mut paths = vec![path.to_path_buf()];
+    if let Ok(canonical) = path.as_path().canonicalize() {
+        paths.push(canonical);
+    }
+    patterns.iter().any(|pattern| {
+        absolute_pattern(pattern, cwd).is_some_and(|pattern| {
+            let pattern = pattern_components(&pattern);
+            paths.iter().any(|path| path_matches(&pattern, path))
+        })
+    })
+}
+
+fn absolute_pattern(pattern: &str, cwd: &Path) -> Option<PathBuf> {
+    let pattern = pattern.trim();
+    if pattern.is_empty() {
+        return None;
+    }
+    let expanded = match pattern.strip_prefix('~') {
+        Some(rest) if rest.is_empty() || rest.starts_with('/') => {
+            dirs::home_dir()?.join(rest.trim_start_matches('/'))
+        }
+        _ => PathBuf::from(pattern),
+    };
+    AbsolutePathBuf::resolve_path_against_base(expanded, cwd)
+        .ok()
+        .map(AbsolutePathBuf::into_path_buf)
+}
+
+fn pattern_components(pattern: &Path) -> Vec<String> {
+    pattern
+        .components()
+        .filter_map(|component| match component {
+            Component::Normal(name) => Some(name.to_string_lossy().into_owned()),
+            _ => None,
+        })
+        .collect()
+}
+
+fn has_wildcard(component: &str) -> bool {
+    component.contains(['*', '?'])
+}
+
+fn expand_component(dir: &Path, component: &str) -> Vec<PathBuf> {
+    if !has_wildcard(component) {
+        let path = dir.join(component);
+        return if path.symlink_metadata().is_ok() {
+            vec![path]
+        } else {
+            Vec::new()
+        };
+    }
+    let matcher = WildMatch::new(component);
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut matches: Vec<PathBuf> = entries
+        .flatten()
+        .filter(|entry| matcher.matches(&entry.file_name().to_string_lossy()))
+        .map(|entry| entry.path())
+        .collect();
+    matches.sort();
+    matches
+}
+
+/// A path is denied when its leading components match every component of
+/// the pattern.
+fn path_matches(pattern: &[String], path: &Path) -> bool {
+    let components = pattern_components(path);
+    components.len() >= pattern.len()
+        && pattern
+            .iter()
+            .zip(&components)
+            .all(|(pattern, name)| WildMatch::new(pattern).matches(name))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use tempfile::TempDir;
+
+    fn policy_denying(patterns: &[&str]) -> SandboxPolicy {
+        SandboxPolicy::CurrentDirWrite {
+            network_access: false,
+            exclude_tmpdir_env_var: true,
+            exclude_slash_tmp: true,
+            network_allowlist: Vec::new(),
+            writable_roots: Vec::new(),
+            deny_read: patterns.iter().map(ToString::to_string).collect(),
+        }
+    }
+
+    #[test]
+    fn resolves_relative_and_wildcard_patterns() -> std::io::Result<()> {
+        let dir = TempDir::new()?;
+        let cwd = dir.path().canonicalize()?;
+        std::fs::write(cwd.join(".env"), "TOKEN=1")?;
+        std::fs::create_dir(cwd.join("certs"))?;
+        std::fs::write(cwd.join("certs/a.pem"), "")?;
+        std::fs::write(cwd.join("certs/b.pem"), "")?;
+        std::fs::write(cwd.join("certs/readme.md"), "")?;
+
+        let resolved = resolve_deny_read_paths(
+            &[
+                ".env".to_string(),
+                "certs/*.pem".to_string(),
+                "missing".to_string(),
+            ],
+            &cwd,
+        );
+
+        let expected: Vec<PathBuf> = vec![
+            cwd.join(".env"),
+            cwd.join("certs/a.pem"),
+            cwd.join("certs/b.pem"),
+        ];
+        assert_eq!(
+            resolved
+                .into_iter()
+                .map(AbsolutePathBuf::into_path_buf)
+                .collect::<Vec<_>>(),
+            expected
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn read_is_denied_beneath_a_matching_path() -> std::io::Result<()> {
+        let dir = TempDir::new()?;
+        let cwd = dir.path().canonicalize()?;
+        let policy = policy_denying(&["secrets", "*.pem"]);
+
+        assert!(is_read_denied(&policy, &cwd, &cwd.join("secrets/api.key")));
+        assert!(is_read_denied(&policy, &cwd, Path::new("secrets")));
+        assert!(is_read_denied(&policy, &cwd, &cwd.join("server.pem")));
+        assert!(!is_read_denied(&policy, &cwd, &cwd.join("src/main.rs")));
+        assert!(!is_read_denied(
+            &SandboxPolicy::new_current_dir_write_policy(),
+            &cwd,
+            &cwd.join("secrets/api.key")
+        ));
+        Ok(())
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn symlinks_into_denied_paths_are_denied() -> std::io::Result<()> {
+        let dir = TempDir::new()?;
+        let cwd = dir.path().canonicalize()?;
+        std::fs::create_dir(cwd.join("secrets"))?;
+        std::fs::write(cwd.join("secrets/api.key"), "hunter2")?;
+        std::os::unix::fs::symlink(cwd.join("secrets/api.key"), cwd.join("innocent.txt"))?;
+
+        let policy = policy_denying(&["secrets"]);
+        assert!(is_read_denied(&policy, &cwd, &cwd.join("innocent.txt")));
+        Ok(())
+    }
+}
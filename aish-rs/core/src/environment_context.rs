@@ -20,7 +20,9 @@ pub(crate) struct EnvironmentContext {
     pub approval_policy: Option<AskForApproval>,
     pub sandbox_mode: Option<SandboxMode>,
     pub network_access: Option<NetworkAccess>,
+    pub network_allowlist: Option<Vec<String>>,
     pub writable_roots: Option<Vec<AbsolutePathBuf>>,
+    pub deny_read: Option<Vec<String>>,
     pub shell: Shell,
 }
 
@@ -54,11 +56,23 @@ impl EnvironmentContext {
                 }
                 None => None,
             },
-            writable_roots: match sandbox_policy {
-                // No writable_roots field anymore, cwd is always included
-                Some(SandboxPolicy::CurrentDirWrite { .. }) => None,
+            network_allowlist: sandbox_policy
+                .as_ref()
+                .map(|policy| policy.network_allowlist().to_vec())
+                .filter(|allowlist| !allowlist.is_empty()),
+            writable_roots: match sandbox_policy.as_ref() {
+                // cwd is always writable, so only the extra roots are listed.
+                Some(SandboxPolicy::CurrentDirWrite { writable_roots, .. })
+                    if !writable_roots.is_empty() =>
+                {
+                    Some(writable_roots.clone())
+                }
                 _ => None,
             },
+            deny_read: sandbox_policy
+                .as_ref()
+                .map(|policy| policy.deny_read().to_vec())
+                .filter(|deny_read| !deny_read.is_empty()),
             shell,
         }
     }
@@ -72,7 +86,9 @@ impl EnvironmentContext {
             approval_policy,
             sandbox_mode,
             network_access,
+            network_allowlist,
             writable_roots,
+            deny_read,
             // should compare all fields except shell
             shell: _,
         } = other;
@@ -81,7 +97,9 @@ impl EnvironmentContext {
             && self.approval_policy == *approval_policy
             && self.sandbox_mode == *sandbox_mode
             && self.network_access == *network_access
+            && self.network_allowlist == *network_allowlist
             && self.writable_roots == *writable_roots
+            && self.deny_read == *deny_read
     }
 
     pub fn diff(before: &TurnContext, after: &TurnContext, shell: &Shell) -> Self {
@@ -125,6 +143,8 @@ impl EnvironmentContext {
     ///   <sandbox_mode>...</sandbox_mode>
     ///   <writable_roots>...</writable_roots>
     ///   <network_access>...</network_access>
+    ///   <network_allowlist>...</network_allowlist>
+    ///   <deny_read>...</deny_read>
     ///   <shell>...</shell>
     /// </environment_context>
     /// ```
@@ -146,6 +166,13 @@ impl EnvironmentContext {
                 "  <network_access>{network_access}</network_access>"
             ));
         }
+        if let Some(network_allowlist) = self.network_allowlist {
+            lines.push("  <network_allowlist>".to_string());
+            for entry in network_allowlist {
+                lines.push(format!("    <host>{entry}</host>"));
+            }
+            lines.push("  </network_allowlist>".to_string());
+        }
         if let Some(writable_roots) = self.writable_roots {
             lines.push("  <writable_roots>".to_string());
             for writable_root in writable_roots {
@@ -156,6 +183,13 @@ impl EnvironmentContext {
             }
             lines.push("  </writable_roots>".to_string());
         }
+        if let Some(deny_read) = self.deny_read {
+            lines.push("  <deny_read>".to_string());
+            for pattern in deny_read {
+                lines.push(format!("    <path>{pattern}</path>"));
+            }
+            lines.push("  </deny_read>".to_string());
+        }
 
         let shell_name = self.shell.name();
         lines.push(format!("  <shell>{shell_name}</shell>"));
@@ -196,6 +230,9 @@ mod tests {
             network_access,
             exclude_tmpdir_env_var: false,
             exclude_slash_tmp: false,
+            network_allowlist: Vec::new(),
+            writable_roots: Vec::new(),
+            deny_read: Vec::new(),
         }
     }
 
@@ -223,6 +260,35 @@ mod tests {
         assert_eq!(context.serialize_to_xml(), expected);
     }
 
+    #[test]
+    fn serialize_network_allowlist_environment_context() {
+        let context = EnvironmentContext::new(
+            None,
+            None,
+            Some(SandboxPolicy::CurrentDirWrite {
+                network_access: false,
+                exclude_tmpdir_env_var: false,
+                exclude_slash_tmp: false,
+                network_allowlist: vec!["pypi.org".to_string(), "*.pythonhosted.org".to_string()],
+                writable_roots: Vec::new(),
+                deny_read: Vec::new(),
+            }),
+            fake_shell(),
+        );
+
+        let expected = r#"<environment_context>
+  <sandbox_mode>current-dir-write</sandbox_mode>
+  <network_access>restricted</network_access>
+  <network_allowlist>
+    <host>pypi.org</host>
+    <host>*.pythonhosted.org</host>
+  </network_allowlist>
+  <shell>bash</shell>
+</environment_context>"#;
+
+        assert_eq!(context.serialize_to_xml(), expected);
+    }
+
     #[test]
     fn serialize_read_only_environment_context() {
         let context = EnvironmentContext::new(
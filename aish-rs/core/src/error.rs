@@ -55,6 +55,18 @@ pub enum SandboxErr {
     #[error("Landlock is not available on this system")]
     Unavailable,
 
+    /// A `deny_read` path overlaps a writable root, which Landlock cannot
+    /// enforce without also hiding files created there later
+    #[cfg(target_os = "linux")]
+    #[error(
+        "deny_read path {} overlaps the writable root {}; Landlock cannot hide it without also hiding every file created there later, so deny only paths outside the writable roots",
+        .path.display(), .root.display()
+    )]
+    DenyReadInWritableRoot {
+        path: std::path::PathBuf,
+        root: std::path::PathBuf,
+    },
+
     /// Error from linux landlock
     #[error("Landlock was not able to fully enforce all sandbox rules")]
     LandlockRestrict,
@@ -462,6 +474,25 @@ impl AishErr {
         }
     }
 
+    /// Whether the provider failed to serve the request (unreachable,
+    /// overloaded or erroring) after its retries ran out, as opposed to
+    /// rejecting the request itself. A profile's `fallback` providers take
+    /// over after these.
+    pub fn is_provider_failure(&self) -> bool {
+        match self {
+            AishErr::Stream(..)
+            | AishErr::Timeout
+            | AishErr::ResponseStreamFailed(_)
+            | AishErr::ConnectionFailed(_)
+            | AishErr::InternalServerError
+            | AishErr::RetryLimit(_) => true,
+            AishErr::UnexpectedStatus(err) => {
+                err.status.is_server_error() || err.status == StatusCode::TOO_MANY_REQUESTS
+            }
+            _ => false,
+        }
+    }
+
     pub fn http_status_code_value(&self) -> Option<u16> {
         let http_status_code = match self {
             AishErr::RetryLimit(err) => Some(err.status),
@@ -545,6 +576,23 @@ mod tests {
         );
     }
 
+    #[test]
+    fn provider_failures_exclude_rejected_requests() {
+        let status = |status| {
+            AishErr::UnexpectedStatus(UnexpectedResponseError {
+                status,
+                body: String::new(),
+                request_id: None,
+            })
+        };
+        assert!(status(StatusCode::BAD_GATEWAY).is_provider_failure());
+        assert!(status(StatusCode::TOO_MANY_REQUESTS).is_provider_failure());
+        assert!(AishErr::Timeout.is_provider_failure());
+        assert!(!status(StatusCode::NOT_FOUND).is_provider_failure());
+        assert!(!AishErr::ContextWindowExceeded.is_provider_failure());
+        assert!(!AishErr::InvalidRequest("bad".to_string()).is_provider_failure());
+    }
+
     #[test]
     fn sandbox_denied_uses_aggregated_output_when_stderr_empty() {
         let output = ExecToolCallOutput {
@@ -29,6 +29,7 @@ use crate::sandboxing::CommandSpec;
 use crate::sandboxing::ExecEnv;
 use crate::sandboxing::SandboxManager;
 use crate::sandboxing::SandboxPermissions;
+use crate::sandboxing::container::RunningContainer;
 use crate::spawn::StdioPolicy;
 use crate::spawn::spawn_child_async;
 use crate::text_encoding::bytes_to_string_smart;
@@ -42,6 +43,11 @@ const TIMEOUT_CODE: i32 = 64;
 const EXIT_CODE_SIGNAL_BASE: i32 = 128; // conventional shell: 128 + signal
 const EXEC_TIMEOUT_EXIT_CODE: i32 = 124; // conventional timeout exit code
 
+/// How long a timed-out process group gets to exit after SIGTERM before it
+/// is sent SIGKILL.
+#[cfg(unix)]
+const TIMEOUT_KILL_GRACE_PERIOD: Duration = Duration::from_secs(2);
+
 // I/O buffer sizing
 const READ_CHUNK_SIZE: usize = 8192; // bytes per read
 const AGGREGATE_BUFFER_INITIAL_CAPACITY: usize = 8 * 1024; // 8 KiB
@@ -115,6 +121,9 @@ pub enum SandboxType {
 
     /// Only available on Linux.
     LinuxSeccomp,
+
+    /// podman, docker or bubblewrap, per `[sandbox_container]`.
+    Container,
 }
 
 #[derive(Clone)]
@@ -166,6 +175,10 @@ pub async fn process_exec_tool_call(
         justification,
     };
 
+    let call_id = stdout_stream
+        .as_ref()
+        .map(|stream| stream.call_id.clone())
+        .unwrap_or_default();
     let manager = SandboxManager::new();
     let exec_env = manager
         .transform(
@@ -174,6 +187,8 @@ pub async fn process_exec_tool_call(
             sandbox_type,
             sandbox_cwd,
             aish_linux_sandbox_exe.as_ref(),
+            None,
+            &call_id,
         )
         .map_err(AishErr::from)?;
 
@@ -195,6 +210,7 @@ pub(crate) async fn execute_exec_env(
         sandbox_permissions,
         justification,
         arg0,
+        container,
     } = env;
 
     let params = ExecParams {
@@ -208,7 +224,14 @@ pub(crate) async fn execute_exec_env(
     };
 
     let start = Instant::now();
-    let raw_output_result = exec(params, sandbox, sandbox_policy, stdout_stream).await;
+    let raw_output_result = exec(
+        params,
+        sandbox,
+        sandbox_policy,
+        stdout_stream,
+        container.as_ref(),
+    )
+    .await;
     let duration = start.elapsed();
     finalize_exec_result(raw_output_result, sandbox, duration)
 }
@@ -282,6 +305,9 @@ pub(crate) mod errors {
                 SandboxTransformError::MissingLinuxSandboxExecutable => {
                     AishErr::LandlockSandboxExecutableNotProvided
                 }
+                SandboxTransformError::InvalidContainerSandbox(message) => {
+                    AishErr::UnsupportedOperation(message)
+                }
                 #[cfg(not(target_os = "macos"))]
                 SandboxTransformError::SeatbeltUnavailable => AishErr::UnsupportedOperation(
                     "seatbelt sandbox is only available on macOS".to_string(),
@@ -420,6 +446,7 @@ async fn exec(
     sandbox: SandboxType,
     sandbox_policy: &SandboxPolicy,
     stdout_stream: Option<StdoutStream>,
+    container: Option<&RunningContainer>,
 ) -> Result<RawExecToolCallOutput> {
     #[cfg(target_os = "windows")]
     if sandbox == SandboxType::WindowsRestrictedToken
@@ -456,15 +483,17 @@ async fn exec(
         env,
     )
     .await?;
-    consume_truncated_output(child, expiration, stdout_stream).await
+    consume_truncated_output(child, expiration, stdout_stream, container).await
 }
 
 /// Consumes the output of a child process, truncating it so it is suitable for
-/// use as the output of a `shell` tool call. Also enforces specified timeout.
+/// use as the output of a `shell` tool call. Also enforces specified timeout,
+/// stopping `container` as well when the child runs one.
 async fn consume_truncated_output(
     mut child: Child,
     expiration: ExecExpiration,
     stdout_stream: Option<StdoutStream>,
+    container: Option<&RunningContainer>,
 ) -> Result<RawExecToolCallOutput> {
     // Both stdout and stderr were configured with `Stdio::piped()`
     // above, therefore `take()` should normally return `Some`.  If it doesn't
@@ -502,13 +531,14 @@ async fn consume_truncated_output(
             (exit_status, false)
         }
         _ = expiration.wait() => {
-            kill_child_process_group(&mut child)?;
-            child.start_kill()?;
+            terminate_child_process_group(&mut child).await?;
+            stop_container(container).await;
             (synthetic_exit_status(EXIT_CODE_SIGNAL_BASE + TIMEOUT_CODE), true)
         }
         _ = tokio::signal::ctrl_c() => {
             kill_child_process_group(&mut child)?;
             child.start_kill()?;
+            stop_container(container).await;
             (synthetic_exit_status(EXIT_CODE_SIGNAL_BASE + SIGKILL_CODE), false)
         }
     };
@@ -640,30 +670,44 @@ fn synthetic_exit_status(code: i32) -> ExitStatus {
     std::process::ExitStatus::from_raw(code)
 }
 
+/// Killing the runtime CLI leaves the container it started running.
+async fn stop_container(container: Option<&RunningContainer>) {
+    if let Some(container) = container {
+        container.kill_and_remove().await;
+    }
+}
+
+/// Stop a timed-out command: SIGTERM the whole process group so tools like
+/// `cargo` and `docker build` can clean up, then SIGKILL whatever is still
+/// running once [`TIMEOUT_KILL_GRACE_PERIOD`] has passed.
 #[cfg(unix)]
-fn kill_child_process_group(child: &mut Child) -> io::Result<()> {
-    use std::io::ErrorKind;
+async fn terminate_child_process_group(child: &mut Child) -> io::Result<()> {
+    let Some(pgid) = child_process_group(child)? else {
+        return Ok(());
+    };
+    signal_process_group(pgid, libc::SIGTERM)?;
+    let exited = tokio::time::timeout(TIMEOUT_KILL_GRACE_PERIOD, child.wait())
+        .await
+        .is_ok();
+    // Grandchildren can outlive the direct child, so the group always gets a
+    // final SIGKILL.
+    signal_process_group(pgid, libc::SIGKILL)?;
+    if !exited {
+        child.start_kill()?;
+    }
+    Ok(())
+}
 
-    if let Some(pid) = child.id() {
-        let pid = pid as libc::pid_t;
-        let pgid = unsafe { libc::getpgid(pid) };
-        if pgid == -1 {
-            let err = std::io::Error::last_os_error();
-            if err.kind() != ErrorKind::NotFound {
-                return Err(err);
-            }
-            return Ok(());
-        }
+#[cfg(not(unix))]
+async fn terminate_child_process_group(child: &mut Child) -> io::Result<()> {
+    child.start_kill()
+}
 
-        let result = unsafe { libc::killpg(pgid, libc::SIGKILL) };
-        if result == -1 {
-            let err = std::io::Error::last_os_error();
-            if err.kind() != ErrorKind::NotFound {
-                return Err(err);
-            }
-        }
+#[cfg(unix)]
+fn kill_child_process_group(child: &mut Child) -> io::Result<()> {
+    if let Some(pgid) = child_process_group(child)? {
+        signal_process_group(pgid, libc::SIGKILL)?;
     }
-
     Ok(())
 }
 
@@ -672,6 +716,40 @@ fn kill_child_process_group(_: &mut Child) -> io::Result<()> {
     Ok(())
 }
 
+/// The child's process group, or `None` if it has already been reaped.
+#[cfg(unix)]
+fn child_process_group(child: &Child) -> io::Result<Option<libc::pid_t>> {
+    use std::io::ErrorKind;
+
+    let Some(pid) = child.id() else {
+        return Ok(None);
+    };
+    let pgid = unsafe { libc::getpgid(pid as libc::pid_t) };
+    if pgid == -1 {
+        let err = std::io::Error::last_os_error();
+        if err.kind() != ErrorKind::NotFound && err.raw_os_error() != Some(libc::ESRCH) {
+            return Err(err);
+        }
+        return Ok(None);
+    }
+    Ok(Some(pgid))
+}
+
+#[cfg(unix)]
+fn signal_process_group(pgid: libc::pid_t, signal: libc::c_int) -> io::Result<()> {
+    use std::io::ErrorKind;
+
+    let result = unsafe { libc::killpg(pgid, signal) };
+    if result == -1 {
+        let err = std::io::Error::last_os_error();
+        // The group is already gone.
+        if err.kind() != ErrorKind::NotFound && err.raw_os_error() != Some(libc::ESRCH) {
+            return Err(err);
+        }
+    }
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -773,7 +851,14 @@ mod tests {
             arg0: None,
         };
 
-        let output = exec(params, SandboxType::None, &SandboxPolicy::ReadOnly, None).await?;
+        let output = exec(
+            params,
+            SandboxType::None,
+            &SandboxPolicy::ReadOnly,
+            None,
+            None,
+        )
+        .await?;
         assert!(output.timed_out);
 
         let stdout = output.stdout.from_utf8_lossy().text;
@@ -801,6 +886,85 @@ mod tests {
         Ok(())
     }
 
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn timeout_sends_sigterm_before_sigkill() -> Result<()> {
+        let command = vec![
+            "/bin/sh".to_string(),
+            "-c".to_string(),
+            "trap 'echo cleaned up; exit 0' TERM; echo started; sleep 30".to_string(),
+        ];
+        let env: HashMap<String, String> = std::env::vars().collect();
+        let params = ExecParams {
+            command,
+            cwd: std::env::current_dir()?,
+            expiration: 500.into(),
+            env,
+            sandbox_permissions: SandboxPermissions::UseDefault,
+            justification: None,
+            arg0: None,
+        };
+
+        let output = exec(
+            params,
+            SandboxType::None,
+            &SandboxPolicy::ReadOnly,
+            None,
+            None,
+        )
+        .await?;
+        assert!(output.timed_out);
+        let stdout = output.stdout.from_utf8_lossy().text;
+        assert!(stdout.contains("started"), "{stdout}");
+        assert!(stdout.contains("cleaned up"), "{stdout}");
+        Ok(())
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn timeout_kills_and_removes_the_container() -> Result<()> {
+        use std::os::unix::fs::PermissionsExt;
+
+        // Stands in for podman/docker and records how it was invoked.
+        let dir = tempfile::tempdir()?;
+        let calls = dir.path().join("calls");
+        let runtime = dir.path().join("runtime");
+        std::fs::write(
+            &runtime,
+            format!("#!/bin/sh\necho \"$@\" >> '{}'\n", calls.display()),
+        )?;
+        std::fs::set_permissions(&runtime, std::fs::Permissions::from_mode(0o755))?;
+        let container = RunningContainer {
+            program: runtime,
+            name: "aish-1-call_1".to_string(),
+        };
+        let params = ExecParams {
+            command: long_running_command(),
+            cwd: std::env::current_dir()?,
+            expiration: 500.into(),
+            env: std::env::vars().collect(),
+            sandbox_permissions: SandboxPermissions::UseDefault,
+            justification: None,
+            arg0: None,
+        };
+
+        let output = exec(
+            params,
+            SandboxType::Container,
+            &SandboxPolicy::ReadOnly,
+            None,
+            Some(&container),
+        )
+        .await?;
+
+        assert!(output.timed_out);
+        assert_eq!(
+            std::fs::read_to_string(calls)?,
+            "kill aish-1-call_1\nrm -f aish-1-call_1\n"
+        );
+        Ok(())
+    }
+
     #[tokio::test]
     async fn process_exec_tool_call_respects_cancellation_token() -> Result<()> {
         let command = long_running_command();
@@ -15,6 +15,16 @@ pub fn create_env(policy: &ShellEnvironmentPolicy) -> HashMap<String, String> {
     populate_env(std::env::vars(), policy)
 }
 
+/// Name patterns that are treated as secrets unless
+/// `ignore_default_excludes` is set.
+pub(crate) fn default_excludes() -> Vec<EnvironmentVariablePattern> {
+    vec![
+        EnvironmentVariablePattern::new_case_insensitive("*KEY*"),
+        EnvironmentVariablePattern::new_case_insensitive("*SECRET*"),
+        EnvironmentVariablePattern::new_case_insensitive("*TOKEN*"),
+    ]
+}
+
 fn populate_env<I>(vars: I, policy: &ShellEnvironmentPolicy) -> HashMap<String, String>
 where
     I: IntoIterator<Item = (String, String)>,
@@ -42,11 +52,7 @@ where
 
     // Step 2 – Apply the default exclude if not disabled.
     if !policy.ignore_default_excludes {
-        let default_excludes = vec![
-            EnvironmentVariablePattern::new_case_insensitive("*KEY*"),
-            EnvironmentVariablePattern::new_case_insensitive("*SECRET*"),
-            EnvironmentVariablePattern::new_case_insensitive("*TOKEN*"),
-        ];
+        let default_excludes = default_excludes();
         env_map.retain(|k, _| !matches_any(k, &default_excludes));
     }
 
@@ -12,8 +12,10 @@ use aish_execpolicy::AmendError;
 use aish_execpolicy::Decision;
 use aish_execpolicy::Error as ExecPolicyRuleError;
 use aish_execpolicy::Evaluation;
+use aish_execpolicy::MatchContext;
 use aish_execpolicy::Policy;
 use aish_execpolicy::PolicyParser;
+use aish_execpolicy::RuleDefinition;
 use aish_execpolicy::RuleMatch;
 use aish_execpolicy::blocking_append_allow_prefix_rule;
 use aish_protocol::approvals::ExecPolicyAmendment;
@@ -27,6 +29,7 @@ use crate::bash::parse_shell_lc_plain_commands;
 use crate::features::Feature;
 use crate::features::Features;
 use crate::sandboxing::SandboxPermissions;
+use crate::tools::sandboxing::BypassSource;
 use crate::tools::sandboxing::ExecApprovalRequirement;
 
 const FORBIDDEN_REASON: &str = "execpolicy forbids this command";
@@ -39,7 +42,9 @@ const DEFAULT_POLICY_FILE: &str = "default.rules";
 
 fn is_policy_match(rule_match: &RuleMatch) -> bool {
     match rule_match {
-        RuleMatch::PrefixRuleMatch { .. } => true,
+        RuleMatch::PrefixRuleMatch { .. }
+        | RuleMatch::FlagRuleMatch { .. }
+        | RuleMatch::PathRuleMatch { .. } => true,
         RuleMatch::HeuristicsRuleMatch { .. } => false,
     }
 }
@@ -85,12 +90,24 @@ pub enum ExecPolicyUpdateError {
 
 pub(crate) struct ExecPolicyManager {
     policy: ArcSwap<Policy>,
+    /// Where each rule of `policy` was written, to name the rule that let a
+    /// command bypass the sandbox in the audit log.
+    definitions: ArcSwap<Vec<RuleDefinition>>,
 }
 
 impl ExecPolicyManager {
     pub(crate) fn new(policy: Arc<Policy>) -> Self {
         Self {
             policy: ArcSwap::from(policy),
+            definitions: ArcSwap::from_pointee(Vec::new()),
+        }
+    }
+
+    pub(crate) fn from_parser(parser: PolicyParser) -> Self {
+        let (policy, definitions) = parser.build_with_definitions();
+        Self {
+            policy: ArcSwap::from_pointee(policy),
+            definitions: ArcSwap::from_pointee(definitions),
         }
     }
 
@@ -98,25 +115,42 @@ impl ExecPolicyManager {
         features: &Features,
         config_stack: &ConfigLayerStack,
     ) -> Result<Self, ExecPolicyError> {
-        let policy = load_exec_policy_for_features(features, config_stack).await?;
-        Ok(Self::new(Arc::new(policy)))
+        if !features.enabled(Feature::ExecPolicy) {
+            return Ok(Self::default());
+        }
+        let policy_paths = exec_policy_files(config_stack).await?;
+        let parser = parse_policy_files(&policy_paths).await?;
+        tracing::debug!("loaded execpolicy from {} files", policy_paths.len());
+        Ok(Self::from_parser(parser))
     }
 
     pub(crate) fn current(&self) -> Arc<Policy> {
         self.policy.load_full()
     }
 
+    /// `cwd` is where `command` runs and `workspace` the turn's working
+    /// directory, which `root = "."` path rules confine arguments to.
+    #[allow(clippy::too_many_arguments)]
     pub(crate) async fn create_exec_approval_requirement_for_command(
         &self,
         features: &Features,
         command: &[String],
+        cwd: &Path,
+        workspace: &Path,
         approval_policy: AskForApproval,
         sandbox_policy: &SandboxPolicy,
         sandbox_permissions: SandboxPermissions,
     ) -> ExecApprovalRequirement {
         let exec_policy = self.current();
-        let commands =
-            parse_shell_lc_plain_commands(command).unwrap_or_else(|| vec![command.to_vec()]);
+        let commands = exec_policy_commands(command);
+        let ctx = MatchContext {
+            cwd,
+            workspace,
+            cwd_may_change: commands.len() > 1
+                || commands
+                    .iter()
+                    .any(|cmd| matches!(cmd.first().map(String::as_str), Some("cd" | "pushd"))),
+        };
         let heuristics_fallback = |cmd: &[String]| {
             if requires_initial_appoval(approval_policy, sandbox_policy, cmd, sandbox_permissions) {
                 Decision::Prompt
@@ -124,7 +158,7 @@ impl ExecPolicyManager {
                 Decision::Allow
             }
         };
-        let evaluation = exec_policy.check_multiple(commands.iter(), &heuristics_fallback);
+        let evaluation = exec_policy.check_multiple_in(commands.iter(), &ctx, &heuristics_fallback);
 
         match evaluation.decision {
             Decision::Forbidden => ExecApprovalRequirement::Forbidden {
@@ -148,20 +182,41 @@ impl ExecPolicyManager {
                     }
                 }
             }
-            Decision::Allow => ExecApprovalRequirement::Skip {
+            Decision::Allow => {
                 // Bypass sandbox if execpolicy allows the command
-                bypass_sandbox: evaluation.matched_rules.iter().any(|rule_match| {
+                let bypass_sandbox = evaluation.matched_rules.iter().any(|rule_match| {
                     is_policy_match(rule_match) && rule_match.decision() == Decision::Allow
-                }),
-                proposed_execpolicy_amendment: if features.enabled(Feature::ExecPolicy) {
-                    try_derive_execpolicy_amendment_for_allow_rules(&evaluation.matched_rules)
-                } else {
-                    None
-                },
-            },
+                });
+                ExecApprovalRequirement::Skip {
+                    bypass_sandbox,
+                    bypass_source: bypass_sandbox
+                        .then(|| BypassSource::Rule(self.allow_rule_location(&commands, &ctx))),
+                    proposed_execpolicy_amendment: if features.enabled(Feature::ExecPolicy) {
+                        try_derive_execpolicy_amendment_for_allow_rules(&evaluation.matched_rules)
+                    } else {
+                        None
+                    },
+                }
+            }
         }
     }
 
+    /// `file:line` of the first allow rule matching one of `commands`.
+    fn allow_rule_location(&self, commands: &[Vec<String>], ctx: &MatchContext) -> Option<String> {
+        self.definitions
+            .load()
+            .iter()
+            .filter(|definition| definition.decision == Decision::Allow)
+            .find(|definition| {
+                definition.rules.iter().any(|rule| {
+                    commands
+                        .iter()
+                        .any(|cmd| rule.matches_in(cmd, ctx).is_some())
+                })
+            })
+            .map(RuleDefinition::location)
+    }
+
     pub(crate) async fn append_amendment_and_update(
         &self,
         codex_home: &Path,
@@ -177,13 +232,26 @@ impl ExecPolicyManager {
         .await
         .map_err(|source| ExecPolicyUpdateError::JoinBlockingTask { source })?
         .map_err(|source| ExecPolicyUpdateError::AppendRule {
-            path: policy_path,
+            path: policy_path.clone(),
             source,
         })?;
 
         let mut updated_policy = self.current().as_ref().clone();
-        updated_policy.add_prefix_rule(&prefix, Decision::Allow)?;
+        let rule = updated_policy.add_prefix_rule(&prefix, Decision::Allow)?;
         self.policy.store(Arc::new(updated_policy));
+        // The rule was appended to the file; its line is known once the
+        // policy is reloaded.
+        let mut definitions = self.definitions.load().as_ref().clone();
+        definitions.push(RuleDefinition {
+            source: policy_path.to_string_lossy().to_string(),
+            lines: None,
+            text: String::new(),
+            decision: Decision::Allow,
+            rules: vec![rule],
+            matches: Vec::new(),
+            not_matches: Vec::new(),
+        });
+        self.definitions.store(Arc::new(definitions));
         Ok(())
     }
 }
@@ -194,18 +262,31 @@ impl Default for ExecPolicyManager {
     }
 }
 
-async fn load_exec_policy_for_features(
-    features: &Features,
+pub async fn load_exec_policy(config_stack: &ConfigLayerStack) -> Result<Policy, ExecPolicyError> {
+    let policy_paths = exec_policy_files(config_stack).await?;
+    let parser = parse_policy_files(&policy_paths).await?;
+    let policy = parser.build();
+    tracing::debug!("loaded execpolicy from {} files", policy_paths.len());
+
+    Ok(policy)
+}
+
+/// Parses the same files as [`load_exec_policy`] and returns every rule with
+/// the file and lines that define it, for `aish execpolicy list` and `/policy`.
+pub async fn load_exec_policy_definitions(
     config_stack: &ConfigLayerStack,
-) -> Result<Policy, ExecPolicyError> {
-    if !features.enabled(Feature::ExecPolicy) {
-        Ok(Policy::empty())
-    } else {
-        load_exec_policy(config_stack).await
-    }
+) -> Result<Vec<RuleDefinition>, ExecPolicyError> {
+    let policy_paths = exec_policy_files(config_stack).await?;
+    let (_, definitions) = parse_policy_files(&policy_paths)
+        .await?
+        .build_with_definitions();
+    Ok(definitions)
 }
 
-pub async fn load_exec_policy(config_stack: &ConfigLayerStack) -> Result<Policy, ExecPolicyError> {
+/// The `*.rules` files of every config layer, lowest precedence first.
+pub async fn exec_policy_files(
+    config_stack: &ConfigLayerStack,
+) -> Result<Vec<PathBuf>, ExecPolicyError> {
     // Iterate the layers in increasing order of precedence, adding the *.rules
     // from each layer, so that higher-precedence layers can override
     // rules defined in lower-precedence ones.
@@ -218,9 +299,18 @@ pub async fn load_exec_policy(config_stack: &ConfigLayerStack) -> Result<Policy,
             policy_paths.extend(layer_policy_paths);
         }
     }
+    Ok(policy_paths)
+}
+
+/// The commands execpolicy evaluates for `command`: each plain command of a
+/// `bash -lc` script, or the command itself.
+pub fn exec_policy_commands(command: &[String]) -> Vec<Vec<String>> {
+    parse_shell_lc_plain_commands(command).unwrap_or_else(|| vec![command.to_vec()])
+}
 
+async fn parse_policy_files(policy_paths: &[PathBuf]) -> Result<PolicyParser, ExecPolicyError> {
     let mut parser = PolicyParser::new();
-    for policy_path in &policy_paths {
+    for policy_path in policy_paths {
         let contents =
             fs::read_to_string(policy_path)
                 .await
@@ -236,11 +326,7 @@ pub async fn load_exec_policy(config_stack: &ConfigLayerStack) -> Result<Policy,
                 source,
             })?;
     }
-
-    let policy = parser.build();
-    tracing::debug!("loaded execpolicy from {} files", policy_paths.len());
-
-    Ok(policy)
+    Ok(parser)
 }
 
 fn default_policy_path(codex_home: &Path) -> PathBuf {
@@ -570,6 +656,8 @@ prefix_rule(pattern=["rm"], decision="forbidden")
             .create_exec_approval_requirement_for_command(
                 &Features::with_defaults(),
                 &forbidden_script,
+                Path::new("/repo"),
+                Path::new("/repo"),
                 AskForApproval::OnRequest,
                 &SandboxPolicy::DangerFullAccess,
                 SandboxPermissions::UseDefault,
@@ -599,6 +687,8 @@ prefix_rule(pattern=["rm"], decision="forbidden")
             .create_exec_approval_requirement_for_command(
                 &Features::with_defaults(),
                 &command,
+                Path::new("/repo"),
+                Path::new("/repo"),
                 AskForApproval::OnRequest,
                 &SandboxPolicy::DangerFullAccess,
                 SandboxPermissions::UseDefault,
@@ -629,6 +719,8 @@ prefix_rule(pattern=["rm"], decision="forbidden")
             .create_exec_approval_requirement_for_command(
                 &Features::with_defaults(),
                 &command,
+                Path::new("/repo"),
+                Path::new("/repo"),
                 AskForApproval::Never,
                 &SandboxPolicy::DangerFullAccess,
                 SandboxPermissions::UseDefault,
@@ -652,6 +744,8 @@ prefix_rule(pattern=["rm"], decision="forbidden")
             .create_exec_approval_requirement_for_command(
                 &Features::with_defaults(),
                 &command,
+                Path::new("/repo"),
+                Path::new("/repo"),
                 AskForApproval::UnlessTrusted,
                 &SandboxPolicy::ReadOnly,
                 SandboxPermissions::UseDefault,
@@ -686,6 +780,8 @@ prefix_rule(pattern=["rm"], decision="forbidden")
                 .create_exec_approval_requirement_for_command(
                     &Features::with_defaults(),
                     &command,
+                    Path::new("/repo"),
+                    Path::new("/repo"),
                     AskForApproval::UnlessTrusted,
                     &SandboxPolicy::DangerFullAccess,
                     SandboxPermissions::UseDefault,
@@ -760,6 +856,8 @@ prefix_rule(pattern=["rm"], decision="forbidden")
             .create_exec_approval_requirement_for_command(
                 &Features::with_defaults(),
                 &command,
+                Path::new("/repo"),
+                Path::new("/repo"),
                 AskForApproval::UnlessTrusted,
                 &SandboxPolicy::ReadOnly,
                 SandboxPermissions::UseDefault,
@@ -787,6 +885,8 @@ prefix_rule(pattern=["rm"], decision="forbidden")
             .create_exec_approval_requirement_for_command(
                 &features,
                 &command,
+                Path::new("/repo"),
+                Path::new("/repo"),
                 AskForApproval::UnlessTrusted,
                 &SandboxPolicy::ReadOnly,
                 SandboxPermissions::UseDefault,
@@ -817,6 +917,8 @@ prefix_rule(pattern=["rm"], decision="forbidden")
             .create_exec_approval_requirement_for_command(
                 &Features::with_defaults(),
                 &command,
+                Path::new("/repo"),
+                Path::new("/repo"),
                 AskForApproval::OnRequest,
                 &SandboxPolicy::DangerFullAccess,
                 SandboxPermissions::UseDefault,
@@ -844,6 +946,8 @@ prefix_rule(pattern=["rm"], decision="forbidden")
             .create_exec_approval_requirement_for_command(
                 &Features::with_defaults(),
                 &command,
+                Path::new("/repo"),
+                Path::new("/repo"),
                 AskForApproval::UnlessTrusted,
                 &SandboxPolicy::ReadOnly,
                 SandboxPermissions::UseDefault,
@@ -882,6 +986,8 @@ prefix_rule(pattern=["rm"], decision="forbidden")
                 .create_exec_approval_requirement_for_command(
                     &Features::with_defaults(),
                     &command,
+                    Path::new("/repo"),
+                    Path::new("/repo"),
                     AskForApproval::UnlessTrusted,
                     &SandboxPolicy::ReadOnly,
                     SandboxPermissions::UseDefault,
@@ -905,6 +1011,8 @@ prefix_rule(pattern=["rm"], decision="forbidden")
             .create_exec_approval_requirement_for_command(
                 &Features::with_defaults(),
                 &command,
+                Path::new("/repo"),
+                Path::new("/repo"),
                 AskForApproval::OnRequest,
                 &SandboxPolicy::ReadOnly,
                 SandboxPermissions::UseDefault,
@@ -915,6 +1023,7 @@ prefix_rule(pattern=["rm"], decision="forbidden")
             requirement,
             ExecApprovalRequirement::Skip {
                 bypass_sandbox: false,
+                bypass_source: None,
                 proposed_execpolicy_amendment: Some(ExecPolicyAmendment::new(command)),
             }
         );
@@ -927,14 +1036,15 @@ prefix_rule(pattern=["rm"], decision="forbidden")
         parser
             .parse("test.rules", policy_src)
             .expect("parse policy");
-        let policy = Arc::new(parser.build());
         let command = vec!["echo".to_string(), "safe".to_string()];
 
-        let manager = ExecPolicyManager::new(policy);
+        let manager = ExecPolicyManager::from_parser(parser);
         let requirement = manager
             .create_exec_approval_requirement_for_command(
                 &Features::with_defaults(),
                 &command,
+                Path::new("/repo"),
+                Path::new("/repo"),
                 AskForApproval::OnRequest,
                 &SandboxPolicy::ReadOnly,
                 SandboxPermissions::UseDefault,
@@ -945,8 +1055,76 @@ prefix_rule(pattern=["rm"], decision="forbidden")
             requirement,
             ExecApprovalRequirement::Skip {
                 bypass_sandbox: true,
+                bypass_source: Some(BypassSource::Rule(Some("test.rules:1".to_string()))),
                 proposed_execpolicy_amendment: None,
             }
         );
     }
+
+    async fn rm_requirement(command: Vec<String>, cwd: &Path) -> ExecApprovalRequirement {
+        let policy_src = r#"path_rule(pattern=["rm"], root=".", decision="allow")"#;
+        let mut parser = PolicyParser::new();
+        parser
+            .parse("test.rules", policy_src)
+            .expect("parse policy");
+        ExecPolicyManager::new(Arc::new(parser.build()))
+            .create_exec_approval_requirement_for_command(
+                &Features::with_defaults(),
+                &command,
+                cwd,
+                Path::new("/repo"),
+                AskForApproval::OnRequest,
+                &SandboxPolicy::ReadOnly,
+                SandboxPermissions::UseDefault,
+            )
+            .await
+    }
+
+    fn bypasses_sandbox(requirement: &ExecApprovalRequirement) -> bool {
+        matches!(
+            requirement,
+            ExecApprovalRequirement::Skip {
+                bypass_sandbox: true,
+                ..
+            }
+        )
+    }
+
+    #[tokio::test]
+    async fn cwd_path_rule_allows_paths_inside_the_workspace() {
+        let command = vec!["rm".to_string(), "-rf".to_string(), "build".to_string()];
+        assert!(bypasses_sandbox(
+            &rm_requirement(command.clone(), Path::new("/repo")).await
+        ));
+        assert!(bypasses_sandbox(
+            &rm_requirement(command, Path::new("/repo/sub")).await
+        ));
+    }
+
+    #[tokio::test]
+    async fn cwd_path_rule_does_not_match_after_cd() {
+        let command = vec![
+            "bash".to_string(),
+            "-lc".to_string(),
+            "cd / && rm -rf etc".to_string(),
+        ];
+        assert!(!bypasses_sandbox(
+            &rm_requirement(command, Path::new("/repo")).await
+        ));
+    }
+
+    #[tokio::test]
+    async fn cwd_path_rule_resolves_arguments_against_the_workdir() {
+        let command = vec!["rm".to_string(), "-rf".to_string(), "etc".to_string()];
+        assert!(!bypasses_sandbox(
+            &rm_requirement(command.clone(), Path::new("/")).await
+        ));
+        assert!(!bypasses_sandbox(
+            &rm_requirement(
+                vec!["rm".to_string(), "../../etc".to_string()],
+                Path::new("/repo/sub")
+            )
+            .await
+        ));
+    }
 }
@@ -70,6 +70,8 @@ pub enum Feature {
     // Experimental
     /// Include the freeform apply_patch tool.
     ApplyPatchFreeform,
+    /// Use the PTY-backed exec_command/write_stdin tools instead of the shell tool.
+    UnifiedExec,
     /// Allow the model to request web searches.
     WebSearchRequest,
     /// Gate the execpolicy enforcement for shell/unified exec.
@@ -88,6 +90,10 @@ pub enum Feature {
     Skills,
     /// Enforce UTF8 output in Powershell.
     PowershellUtf8,
+    /// Include the read-only shell_history tool.
+    ShellHistoryTool,
+    /// Let shell tool calls run as background jobs (`background: true`).
+    BackgroundJobs,
 }
 
 impl Feature {
@@ -324,6 +330,12 @@ pub const FEATURES: &[FeatureSpec] = &[
         stage: Stage::Experimental,
         default_enabled: false,
     },
+    FeatureSpec {
+        id: Feature::UnifiedExec,
+        key: "unified_exec",
+        stage: Stage::Experimental,
+        default_enabled: false,
+    },
     FeatureSpec {
         id: Feature::ExecPolicy,
         key: "exec_policy",
@@ -360,4 +372,16 @@ pub const FEATURES: &[FeatureSpec] = &[
         stage: Stage::Experimental,
         default_enabled: false,
     },
+    FeatureSpec {
+        id: Feature::ShellHistoryTool,
+        key: "shell_history_tool",
+        stage: Stage::Experimental,
+        default_enabled: false,
+    },
+    FeatureSpec {
+        id: Feature::BackgroundJobs,
+        key: "background_jobs",
+        stage: Stage::Experimental,
+        default_enabled: false,
+    },
 ];
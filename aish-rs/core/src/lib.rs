@@ -9,7 +9,9 @@ pub mod aish;
 mod aish_conversation;
 pub mod api_bridge;
 mod apply_patch;
+mod audit;
 pub mod auth;
+mod background_jobs;
 pub mod bash;
 mod client;
 mod client_common;
@@ -20,6 +22,7 @@ pub mod config;
 pub mod config_loader;
 mod context_manager;
 pub mod custom_prompts;
+pub mod deny_read;
 pub mod env;
 mod environment_context;
 pub mod error;
@@ -39,6 +42,7 @@ pub use mcp_connection_manager::SandboxState;
 mod mcp_tool_call;
 mod message_history;
 mod model_provider_info;
+pub mod network_allowlist;
 pub mod parse_command;
 pub mod path_utils;
 pub mod powershell;
@@ -47,12 +51,14 @@ mod stream_events_utils;
 mod text_encoding;
 pub mod token_data;
 mod truncate;
+mod unified_exec;
 mod user_instructions;
 pub use model_provider_info::DEFAULT_LMSTUDIO_PORT;
 pub use model_provider_info::DEFAULT_OLLAMA_PORT;
 pub use model_provider_info::LMSTUDIO_OSS_PROVIDER_ID;
 pub use model_provider_info::ModelProviderInfo;
 pub use model_provider_info::OLLAMA_OSS_PROVIDER_ID;
+pub use model_provider_info::PromptCacheHints;
 pub use model_provider_info::WireApi;
 pub use model_provider_info::built_in_model_providers;
 pub use model_provider_info::create_oss_provider_with_base_url;
@@ -66,10 +72,14 @@ pub use auth::AishAuth;
 pub use auth::AuthManager;
 pub mod default_client;
 pub mod project_doc;
+pub mod provider_keys;
+pub mod recipes;
 mod rollout;
 pub(crate) mod safety;
 pub mod seatbelt;
 pub mod shell;
+pub mod shell_integration;
+pub mod shell_timeouts;
 pub mod skills;
 pub mod spawn;
 pub mod terminal;
@@ -80,12 +90,31 @@ pub use rollout::INTERACTIVE_SESSION_SOURCES;
 pub use rollout::RolloutRecorder;
 pub use rollout::SESSIONS_SUBDIR;
 pub use rollout::SessionMeta;
+pub use rollout::export::ExportFormat;
+pub use rollout::export::export_rollout;
 pub use rollout::find_conversation_path_by_id_str;
+pub use rollout::fork::ForkedSession;
+pub use rollout::fork::fork_rollout_at;
 pub use rollout::list::ConversationItem;
 pub use rollout::list::ConversationsPage;
 pub use rollout::list::Cursor;
 pub use rollout::list::parse_cursor;
 pub use rollout::list::read_head_for_summary;
+pub use rollout::metadata::read_session_metadata;
+pub use rollout::metadata::update_session_metadata;
+pub use rollout::prune::PruneReason;
+pub use rollout::prune::PruneReport;
+pub use rollout::prune::PrunedSession;
+pub use rollout::prune::prune_sessions;
+pub use rollout::search::SearchMatchKind;
+pub use rollout::search::SessionIndex;
+pub use rollout::search::SessionSearchHit;
+pub use rollout::search::SessionSearchMatch;
+pub use rollout::search::search_sessions;
+pub use rollout::starred::StarredSession;
+pub use rollout::starred::star_session;
+pub use rollout::starred::starred_sessions;
+pub use rollout::starred::unstar_session;
 mod function_tool;
 mod state;
 mod tasks;
@@ -97,7 +126,10 @@ pub use apply_patch::AISH_APPLY_PATCH_ARG1;
 pub use command_safety::is_dangerous_command;
 pub use command_safety::is_safe_command;
 pub use exec_policy::ExecPolicyError;
+pub use exec_policy::exec_policy_commands;
+pub use exec_policy::exec_policy_files;
 pub use exec_policy::load_exec_policy;
+pub use exec_policy::load_exec_policy_definitions;
 pub use safety::get_platform_sandbox;
 pub use safety::set_windows_sandbox_enabled;
 // Re-export the protocol types from the standalone `codex-protocol` crate so existing
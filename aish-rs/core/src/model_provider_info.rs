@@ -5,6 +5,7 @@
 //!   2. User-defined entries inside `~/.aish/config.toml` under the `model_providers`
 //!      key. These override or extend the defaults at runtime.
 
+use aish_api::PromptCacheHints as ApiPromptCacheHints;
 use aish_api::Provider as ApiProvider;
 use aish_api::WireApi as ApiWireApi;
 use aish_api::provider::RetryConfig as ApiRetryConfig;
@@ -43,6 +44,24 @@ pub enum WireApi {
     /// Regular Chat Completions compatible with `/v1/chat/completions`.
     #[default]
     Chat,
+
+    /// The Anthropic Messages API at `/v1/messages`, authenticated with an
+    /// `x-api-key` header.
+    Messages,
+}
+
+/// How Chat Completions requests help the provider reuse a cached prompt
+/// prefix. Providers that cache automatically (OpenAI, DeepSeek, ...) still
+/// report hits without any hint.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PromptCacheHints {
+    /// Anthropic-style `cache_control` breakpoints on the system prompt and
+    /// the latest message (OpenRouter, DashScope, ...).
+    CacheControl,
+
+    /// OpenAI-style `prompt_cache_key` set to the conversation id.
+    PromptCacheKey,
 }
 
 /// Serializable representation of a provider definition.
@@ -68,6 +87,10 @@ pub struct ModelProviderInfo {
     #[serde(default)]
     pub wire_api: WireApi,
 
+    /// Prompt caching hints to add to Chat Completions requests. Unset sends
+    /// none.
+    pub prompt_cache: Option<PromptCacheHints>,
+
     /// Optional query parameters to append to the base URL.
     pub query_params: Option<HashMap<String, String>>,
 
@@ -140,6 +163,12 @@ impl ModelProviderInfo {
             wire: match self.wire_api {
                 WireApi::Responses => ApiWireApi::Responses,
                 WireApi::Chat => ApiWireApi::Chat,
+                WireApi::Messages => ApiWireApi::Messages,
+            },
+            prompt_cache: match self.prompt_cache {
+                None => ApiPromptCacheHints::None,
+                Some(PromptCacheHints::CacheControl) => ApiPromptCacheHints::CacheControl,
+                Some(PromptCacheHints::PromptCacheKey) => ApiPromptCacheHints::PromptCacheKey,
             },
             headers,
             retry,
@@ -208,6 +237,7 @@ impl ModelProviderInfo {
             env_key_instructions: None,
             experimental_bearer_token: None,
             wire_api: WireApi::Responses,
+            prompt_cache: None,
             query_params: None,
             http_headers: Some(
                 [("version".to_string(), env!("CARGO_PKG_VERSION").to_string())]
@@ -295,6 +325,7 @@ pub fn create_oss_provider_with_base_url(base_url: &str, wire_api: WireApi) -> M
         env_key_instructions: None,
         experimental_bearer_token: None,
         wire_api,
+        prompt_cache: None,
         query_params: None,
         http_headers: None,
         env_http_headers: None,
@@ -322,6 +353,7 @@ base_url = "http://localhost:11434/v1"
             env_key_instructions: None,
             experimental_bearer_token: None,
             wire_api: WireApi::Chat,
+            prompt_cache: None,
             query_params: None,
             http_headers: None,
             env_http_headers: None,
@@ -349,6 +381,7 @@ query_params = { api-version = "2025-04-01-preview" }
             env_key_instructions: None,
             experimental_bearer_token: None,
             wire_api: WireApi::Chat,
+            prompt_cache: None,
             query_params: Some(maplit::hashmap! {
                 "api-version".to_string() => "2025-04-01-preview".to_string(),
             }),
@@ -379,6 +412,7 @@ env_http_headers = { "X-Example-Env-Header" = "EXAMPLE_ENV_VAR" }
             env_key_instructions: None,
             experimental_bearer_token: None,
             wire_api: WireApi::Chat,
+            prompt_cache: None,
             query_params: None,
             http_headers: Some(maplit::hashmap! {
                 "X-Example-Header".to_string() => "example-value".to_string(),
@@ -395,6 +429,20 @@ env_http_headers = { "X-Example-Env-Header" = "EXAMPLE_ENV_VAR" }
         assert_eq!(expected_provider, provider);
     }
 
+    #[test]
+    fn prompt_cache_hints_reach_api_provider() {
+        let provider_toml = r#"
+name = "OpenRouter"
+base_url = "https://openrouter.ai/api/v1"
+prompt_cache = "cache_control"
+        "#;
+        let provider: ModelProviderInfo = toml::from_str(provider_toml).unwrap();
+        assert_eq!(provider.prompt_cache, Some(PromptCacheHints::CacheControl));
+
+        let api = provider.to_api_provider().expect("api provider");
+        assert_eq!(api.prompt_cache, ApiPromptCacheHints::CacheControl);
+    }
+
     #[test]
     fn detects_azure_responses_base_urls() {
         let positive_cases = [
@@ -413,6 +461,7 @@ env_http_headers = { "X-Example-Env-Header" = "EXAMPLE_ENV_VAR" }
                 env_key_instructions: None,
                 experimental_bearer_token: None,
                 wire_api: WireApi::Responses,
+                prompt_cache: None,
                 query_params: None,
                 http_headers: None,
                 env_http_headers: None,
@@ -434,6 +483,7 @@ env_http_headers = { "X-Example-Env-Header" = "EXAMPLE_ENV_VAR" }
             env_key_instructions: None,
             experimental_bearer_token: None,
             wire_api: WireApi::Responses,
+            prompt_cache: None,
             query_params: None,
             http_headers: None,
             env_http_headers: None,
@@ -457,6 +507,7 @@ env_http_headers = { "X-Example-Env-Header" = "EXAMPLE_ENV_VAR" }
                 env_key_instructions: None,
                 experimental_bearer_token: None,
                 wire_api: WireApi::Responses,
+                prompt_cache: None,
                 query_params: None,
                 http_headers: None,
                 env_http_headers: None,
@@ -0,0 +1,348 @@
+//! Parses and matches the `network_allowlist` of a `current-dir-write`
+//! sandbox policy, and defines the denial records the Linux sandbox proxy
+//! reports when it blocks a connection.
+
+use std::net::IpAddr;
+
+use serde::Deserialize;
+use serde::Serialize;
+
+/// Prefix of the stderr line the sandbox proxy writes for every blocked
+/// connection. The remainder of the line is a JSON-encoded [`NetworkDenial`].
+pub const NETWORK_DENIAL_MARKER: &str = "aish-sandbox: network denied: ";
+
+/// Response header the sandbox proxy sets on the `403` it returns for a
+/// blocked request, so HTTP clients can tell it apart from an upstream 403.
+pub const NETWORK_DENIAL_HEADER: &str = "X-Aish-Sandbox-Denied";
+
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("invalid network allowlist entry `{entry}`: {reason}")]
+pub struct NetworkAllowlistError {
+    pub entry: String,
+    pub reason: String,
+}
+
+/// A parsed allowlist. Each entry names a host pattern and an optional port;
+/// a destination is allowed when any entry matches both.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct NetworkAllowlist {
+    entries: Vec<AllowlistEntry>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct AllowlistEntry {
+    host: HostPattern,
+    port: Option<u16>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum HostPattern {
+    /// `*` or a bare `:port`: any destination.
+    Any,
+    /// An exact hostname, lowercased and without a trailing dot.
+    Domain(String),
+    /// `*.example.com`: any subdomain of `example.com`, but not the apex.
+    Subdomains(String),
+    /// An IP address (`/32` or `/128`) or CIDR range.
+    Cidr { network: IpAddr, prefix_len: u8 },
+}
+
+/// A connection the sandbox refused to open.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NetworkDenial {
+    pub host: String,
+    pub port: u16,
+    pub reason: String,
+}
+
+impl NetworkDenial {
+    /// Renders the single stderr line the proxy writes for this denial.
+    pub fn to_marker_line(&self) -> String {
+        let json = serde_json::to_string(self).unwrap_or_default();
+        format!("{NETWORK_DENIAL_MARKER}{json}")
+    }
+}
+
+/// Extracts the denials reported by the sandbox proxy from command output.
+/// Lines that do not carry a well-formed marker are ignored, and repeated
+/// denials for the same destination are reported once.
+pub fn parse_network_denials(output: &str) -> Vec<NetworkDenial> {
+    let mut denials: Vec<NetworkDenial> = Vec::new();
+    for line in output.lines() {
+        let Some((_, json)) = line.split_once(NETWORK_DENIAL_MARKER) else {
+            continue;
+        };
+        let Ok(denial) = serde_json::from_str::<NetworkDenial>(json.trim()) else {
+            continue;
+        };
+        if !denials
+            .iter()
+            .any(|seen| seen.host == denial.host && seen.port == denial.port)
+        {
+            denials.push(denial);
+        }
+    }
+    denials
+}
+
+impl NetworkAllowlist {
+    pub fn parse<S: AsRef<str>>(entries: &[S]) -> Result<Self, NetworkAllowlistError> {
+        let entries = entries
+            .iter()
+            .map(|entry| parse_entry(entry.as_ref()))
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Self { entries })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Returns whether `host:port` is allowed by name alone. `host` may be a
+    /// hostname or an IP literal.
+    pub fn allows_host(&self, host: &str, port: u16) -> bool {
+        let host = normalize_host(host);
+        if let Ok(ip) = host.parse::<IpAddr>() {
+            return self.allows_ip(ip, port);
+        }
+        self.entries.iter().any(|entry| {
+            entry.port.is_none_or(|allowed| allowed == port)
+                && match &entry.host {
+                    HostPattern::Any => true,
+                    HostPattern::Domain(domain) => *domain == host,
+                    HostPattern::Subdomains(suffix) => host
+                        .strip_suffix(suffix.as_str())
+                        .is_some_and(|prefix| prefix.ends_with('.') && prefix.len() > 1),
+                    HostPattern::Cidr { .. } => false,
+                }
+        })
+    }
+
+    /// Returns whether a connection to the resolved address `ip:port` is
+    /// allowed by an address or CIDR entry (or a `*` entry).
+    pub fn allows_ip(&self, ip: IpAddr, port: u16) -> bool {
+        let ip = ip.to_canonical();
+        self.entries.iter().any(|entry| {
+            entry.port.is_none_or(|allowed| allowed == port)
+                && match &entry.host {
+                    HostPattern::Any => true,
+                    HostPattern::Cidr {
+                        network,
+                        prefix_len,
+                    } => cidr_contains(*network, *prefix_len, ip),
+                    HostPattern::Domain(_) | HostPattern::Subdomains(_) => false,
+                }
+        })
+    }
+}
+
+fn parse_entry(raw: &str) -> Result<AllowlistEntry, NetworkAllowlistError> {
+    let invalid = |reason: &str| NetworkAllowlistError {
+        entry: raw.to_string(),
+        reason: reason.to_string(),
+    };
+    let entry = raw.trim();
+    if entry.is_empty() {
+        return Err(invalid("entry is empty"));
+    }
+
+    let (host, port) = split_host_port(entry).map_err(|reason| invalid(&reason))?;
+    let port = match port {
+        Some(port) => Some(
+            port.parse::<u16>()
+                .ok()
+                .filter(|port| *port != 0)
+                .ok_or_else(|| invalid("port must be a number between 1 and 65535"))?,
+        ),
+        None => None,
+    };
+
+    let host = if host.is_empty() || host == "*" {
+        HostPattern::Any
+    } else if let Some((addr, prefix_len)) = host.split_once('/') {
+        let network = addr
+            .parse::<IpAddr>()
+            .map_err(|_| invalid("CIDR ranges must start with an IP address"))?
+            .to_canonical();
+        let max_len = if network.is_ipv4() { 32 } else { 128 };
+        let prefix_len = prefix_len
+            .parse::<u8>()
+            .ok()
+            .filter(|len| *len <= max_len)
+            .ok_or_else(|| invalid(&format!("prefix length must be between 0 and {max_len}")))?;
+        HostPattern::Cidr {
+            network,
+            prefix_len,
+        }
+    } else if let Ok(ip) = host.parse::<IpAddr>() {
+        let ip = ip.to_canonical();
+        HostPattern::Cidr {
+            network: ip,
+            prefix_len: if ip.is_ipv4() { 32 } else { 128 },
+        }
+    } else if let Some(suffix) = host.strip_prefix("*.") {
+        let suffix = normalize_host(suffix);
+        validate_hostname(&suffix).map_err(invalid)?;
+        HostPattern::Subdomains(suffix)
+    } else {
+        let domain = normalize_host(host);
+        validate_hostname(&domain).map_err(invalid)?;
+        HostPattern::Domain(domain)
+    };
+
+    Ok(AllowlistEntry { host, port })
+}
+
+/// Splits `host[:port]`, `[v6][:port]` and bare IPv6 addresses (which are
+/// never followed by a port unless bracketed or written as a CIDR range).
+fn split_host_port(entry: &str) -> Result<(&str, Option<&str>), String> {
+    if let Some(rest) = entry.strip_prefix('[') {
+        let (host, rest) = rest
+            .split_once(']')
+            .ok_or_else(|| "missing closing `]`".to_string())?;
+        return match rest {
+            "" => Ok((host, None)),
+            _ => rest
+                .strip_prefix(':')
+                .map(|port| (host, Some(port)))
+                .ok_or_else(|| "expected `:port` after `]`".to_string()),
+        };
+    }
+    if let Some((addr, rest)) = entry.split_once('/') {
+        return Ok(match rest.split_once(':') {
+            Some((prefix_len, port)) => (&entry[..addr.len() + 1 + prefix_len.len()], Some(port)),
+            None => (entry, None),
+        });
+    }
+    match entry.matches(':').count() {
+        0 => Ok((entry, None)),
+        1 => {
+            let (host, port) = entry.split_once(':').unwrap_or((entry, ""));
+            Ok((host, Some(port)))
+        }
+        _ => Ok((entry, None)),
+    }
+}
+
+fn validate_hostname(host: &str) -> Result<(), &'static str> {
+    if host.is_empty() || host.len() > 253 {
+        return Err("hostname must be between 1 and 253 characters");
+    }
+    let valid_label = |label: &str| {
+        !label.is_empty()
+            && label.len() <= 63
+            && label
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    };
+    if host.split('.').all(valid_label) {
+        Ok(())
+    } else {
+        Err(
+            "hostnames may only contain letters, digits, `-`, `_` and `.`; use `*.domain` for subdomains",
+        )
+    }
+}
+
+fn normalize_host(host: &str) -> String {
+    host.trim_start_matches('[')
+        .trim_end_matches(']')
+        .trim_end_matches('.')
+        .to_ascii_lowercase()
+}
+
+fn cidr_contains(network: IpAddr, prefix_len: u8, ip: IpAddr) -> bool {
+    match (network, ip) {
+        (IpAddr::V4(network), IpAddr::V4(ip)) => {
+            let mask = u32::MAX
+                .checked_shl(32 - u32::from(prefix_len))
+                .unwrap_or(0);
+            u32::from(network) & mask == u32::from(ip) & mask
+        }
+        (IpAddr::V6(network), IpAddr::V6(ip)) => {
+            let mask = u128::MAX
+                .checked_shl(128 - u32::from(prefix_len))
+                .unwrap_or(0);
+            u128::from(network) & mask == u128::from(ip) & mask
+        }
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn allowlist(entries: &[&str]) -> NetworkAllowlist {
+        NetworkAllowlist::parse(entries).expect("valid allowlist")
+    }
+
+    #[test]
+    fn domains_match_exactly_and_wildcards_match_subdomains_only() {
+        let list = allowlist(&["pypi.org", "*.githubusercontent.com"]);
+        assert!(list.allows_host("pypi.org", 443));
+        assert!(list.allows_host("PyPI.org.", 80));
+        assert!(!list.allows_host("files.pypi.org", 443));
+        assert!(list.allows_host("raw.githubusercontent.com", 443));
+        assert!(!list.allows_host("githubusercontent.com", 443));
+        assert!(!list.allows_host("evilgithubusercontent.com", 443));
+    }
+
+    #[test]
+    fn ports_restrict_matching_entries() {
+        let list = allowlist(&["registry.npmjs.org:443", ":8080", "[::1]:5432"]);
+        assert!(list.allows_host("registry.npmjs.org", 443));
+        assert!(!list.allows_host("registry.npmjs.org", 80));
+        assert!(list.allows_host("anything.example", 8080));
+        assert!(list.allows_ip("10.1.2.3".parse().unwrap(), 8080));
+        assert!(list.allows_host("::1", 5432));
+        assert!(!list.allows_host("::1", 5433));
+    }
+
+    #[test]
+    fn cidr_entries_match_resolved_addresses() {
+        let list = allowlist(&["10.0.0.0/8", "192.168.1.7", "fd00::/8:443"]);
+        assert!(list.allows_ip("10.20.30.40".parse().unwrap(), 22));
+        assert!(!list.allows_ip("11.0.0.1".parse().unwrap(), 22));
+        assert!(list.allows_ip("192.168.1.7".parse().unwrap(), 80));
+        assert!(!list.allows_ip("192.168.1.8".parse().unwrap(), 80));
+        assert!(list.allows_ip("::ffff:10.0.0.1".parse().unwrap(), 80));
+        assert!(list.allows_ip("fd12::1".parse().unwrap(), 443));
+        assert!(!list.allows_ip("fd12::1".parse().unwrap(), 80));
+        assert!(!list.allows_host("example.com", 80));
+    }
+
+    #[test]
+    fn invalid_entries_are_rejected_with_the_entry() {
+        for entry in [
+            "",
+            "example.com:0",
+            "example.com:http",
+            "10.0.0.0/33",
+            "exa mple.com",
+        ] {
+            let err = NetworkAllowlist::parse(&[entry]).expect_err(entry);
+            assert_eq!(err.entry, entry);
+        }
+    }
+
+    #[test]
+    fn denials_round_trip_through_marker_lines() {
+        let denial = NetworkDenial {
+            host: "example.com".to_string(),
+            port: 443,
+            reason: "not in network_allowlist".to_string(),
+        };
+        let output = format!(
+            "curl: (56) CONNECT tunnel failed, response 403\n{}\n{}\n",
+            denial.to_marker_line(),
+            denial.to_marker_line()
+        );
+        assert_eq!(parse_network_denials(&output), vec![denial]);
+        assert_eq!(
+            parse_network_denials("aish-sandbox: network denied: {"),
+            vec![]
+        );
+    }
+}
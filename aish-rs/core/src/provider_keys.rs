@@ -0,0 +1,307 @@
+//! Per-provider API keys saved by `aish login --provider <id>`.
+//!
+//! Keys live in the OS keyring under one account per provider id. When the
+//! keyring is unavailable (headless Linux without a secret service, CI, ...)
+//! they fall back to `AISH_HOME/provider_keys.json`, readable only by the
+//! current user.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::fs::OpenOptions;
+use std::io;
+use std::io::ErrorKind;
+use std::io::Write;
+#[cfg(unix)]
+use std::os::unix::fs::OpenOptionsExt;
+#[cfg(unix)]
+use std::os::unix::fs::PermissionsExt;
+use std::path::Path;
+use std::path::PathBuf;
+
+use aish_keyring_store::DefaultKeyringStore;
+use aish_keyring_store::KeyringStore;
+use tracing::warn;
+
+const KEYRING_SERVICE: &str = "Aish Provider Keys";
+const FALLBACK_FILENAME: &str = "provider_keys.json";
+
+/// Where a stored provider key was read from or written to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProviderKeyStorage {
+    Keyring,
+    File,
+}
+
+type FallbackFile = BTreeMap<String, String>;
+
+/// Stores `api_key` for `provider_id`, preferring the keyring and falling back
+/// to the credentials file when the keyring cannot be written.
+pub fn save_provider_key(
+    codex_home: &Path,
+    provider_id: &str,
+    api_key: &str,
+) -> io::Result<ProviderKeyStorage> {
+    save_provider_key_with_keyring(&DefaultKeyringStore, codex_home, provider_id, api_key)
+}
+
+/// Loads the stored key for `provider_id`, checking the keyring first.
+pub fn load_provider_key(
+    codex_home: &Path,
+    provider_id: &str,
+) -> io::Result<Option<(String, ProviderKeyStorage)>> {
+    load_provider_key_with_keyring(&DefaultKeyringStore, codex_home, provider_id)
+}
+
+/// Removes the key for `provider_id` from both the keyring and the file.
+/// Returns whether anything was removed.
+pub fn delete_provider_key(codex_home: &Path, provider_id: &str) -> io::Result<bool> {
+    delete_provider_key_with_keyring(&DefaultKeyringStore, codex_home, provider_id)
+}
+
+/// Provider ids with a key in the fallback file. Keyring entries cannot be
+/// enumerated portably, so callers that need every provider should also
+/// probe the ids they know about.
+pub fn file_provider_ids(codex_home: &Path) -> io::Result<Vec<String>> {
+    Ok(read_fallback_file(codex_home)?
+        .map(|store| store.into_keys().collect())
+        .unwrap_or_default())
+}
+
+pub(crate) fn save_provider_key_with_keyring<K: KeyringStore>(
+    keyring_store: &K,
+    codex_home: &Path,
+    provider_id: &str,
+    api_key: &str,
+) -> io::Result<ProviderKeyStorage> {
+    match keyring_store.save(KEYRING_SERVICE, provider_id, api_key) {
+        Ok(()) => {
+            if let Err(error) = delete_provider_key_from_file(codex_home, provider_id) {
+                warn!("failed to remove provider key from fallback storage: {error}");
+            }
+            Ok(ProviderKeyStorage::Keyring)
+        }
+        Err(error) => {
+            warn!(
+                "falling back to file storage for provider key: {}",
+                error.message()
+            );
+            let mut store = read_fallback_file(codex_home)?.unwrap_or_default();
+            store.insert(provider_id.to_string(), api_key.to_string());
+            write_fallback_file(codex_home, &store)?;
+            Ok(ProviderKeyStorage::File)
+        }
+    }
+}
+
+pub(crate) fn load_provider_key_with_keyring<K: KeyringStore>(
+    keyring_store: &K,
+    codex_home: &Path,
+    provider_id: &str,
+) -> io::Result<Option<(String, ProviderKeyStorage)>> {
+    match keyring_store.load(KEYRING_SERVICE, provider_id) {
+        Ok(Some(api_key)) if !api_key.trim().is_empty() => {
+            return Ok(Some((api_key, ProviderKeyStorage::Keyring)));
+        }
+        Ok(_) => {}
+        Err(error) => warn!(
+            "failed to read provider key from keyring: {}",
+            error.message()
+        ),
+    }
+    Ok(read_fallback_file(codex_home)?
+        .and_then(|mut store| store.remove(provider_id))
+        .filter(|api_key| !api_key.trim().is_empty())
+        .map(|api_key| (api_key, ProviderKeyStorage::File)))
+}
+
+pub(crate) fn delete_provider_key_with_keyring<K: KeyringStore>(
+    keyring_store: &K,
+    codex_home: &Path,
+    provider_id: &str,
+) -> io::Result<bool> {
+    // Without a keyring (the usual reason for the file fallback) the keyring
+    // delete fails, so it must not stop the file entry from being removed.
+    let keyring_removed = keyring_store
+        .delete(KEYRING_SERVICE, provider_id)
+        .map_err(|error| {
+            warn!(
+                "failed to delete provider key from keyring: {}",
+                error.message()
+            );
+            error
+        });
+    let file_removed = delete_provider_key_from_file(codex_home, provider_id);
+    match (keyring_removed, file_removed) {
+        (Err(keyring_error), Err(file_error)) => Err(io::Error::other(format!(
+            "failed to delete provider key from keyring ({}) and from {} ({file_error})",
+            keyring_error.message(),
+            fallback_file_path(codex_home).display()
+        ))),
+        (Ok(keyring_removed), Ok(file_removed)) => Ok(keyring_removed || file_removed),
+        (Ok(removed), Err(error)) => {
+            warn!("failed to remove provider key from fallback storage: {error}");
+            Ok(removed)
+        }
+        (Err(_), Ok(removed)) => Ok(removed),
+    }
+}
+
+fn delete_provider_key_from_file(codex_home: &Path, provider_id: &str) -> io::Result<bool> {
+    let Some(mut store) = read_fallback_file(codex_home)? else {
+        return Ok(false);
+    };
+    let removed = store.remove(provider_id).is_some();
+    if removed {
+        write_fallback_file(codex_home, &store)?;
+    }
+    Ok(removed)
+}
+
+pub fn fallback_file_path(codex_home: &Path) -> PathBuf {
+    codex_home.join(FALLBACK_FILENAME)
+}
+
+fn read_fallback_file(codex_home: &Path) -> io::Result<Option<FallbackFile>> {
+    let path = fallback_file_path(codex_home);
+    let contents = match fs::read_to_string(&path) {
+        Ok(contents) => contents,
+        Err(err) if err.kind() == ErrorKind::NotFound => return Ok(None),
+        Err(err) => return Err(err),
+    };
+    serde_json::from_str(&contents).map(Some).map_err(|err| {
+        io::Error::new(
+            ErrorKind::InvalidData,
+            format!("failed to parse {}: {err}", path.display()),
+        )
+    })
+}
+
+fn write_fallback_file(codex_home: &Path, store: &FallbackFile) -> io::Result<()> {
+    let path = fallback_file_path(codex_home);
+    if store.is_empty() {
+        if path.exists() {
+            fs::remove_file(path)?;
+        }
+        return Ok(());
+    }
+
+    fs::create_dir_all(codex_home)?;
+    let serialized = serde_json::to_string_pretty(store)?;
+    // Create the file 0600 so the keys are never readable with the umask's
+    // permissions, and tighten a file created by an older version.
+    let mut options = OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    options.mode(0o600);
+    let mut file = options.open(&path)?;
+    #[cfg(unix)]
+    file.set_permissions(fs::Permissions::from_mode(0o600))?;
+    file.write_all(serialized.as_bytes())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aish_keyring_store::tests::MockKeyringStore;
+    use keyring::Error as KeyringError;
+    use pretty_assertions::assert_eq;
+    use tempfile::TempDir;
+
+    #[test]
+    fn save_prefers_keyring_and_clears_file_copy() -> io::Result<()> {
+        let home = TempDir::new()?;
+        let store = MockKeyringStore::default();
+        write_fallback_file(
+            home.path(),
+            &FallbackFile::from([("openrouter".to_string(), "old".to_string())]),
+        )?;
+
+        let storage =
+            save_provider_key_with_keyring(&store, home.path(), "openrouter", "sk-or-new")?;
+
+        assert_eq!(storage, ProviderKeyStorage::Keyring);
+        assert_eq!(
+            store.saved_value("openrouter"),
+            Some("sk-or-new".to_string())
+        );
+        assert!(!fallback_file_path(home.path()).exists());
+        Ok(())
+    }
+
+    #[test]
+    fn save_falls_back_to_file_when_keyring_fails() -> io::Result<()> {
+        let home = TempDir::new()?;
+        let store = MockKeyringStore::default();
+        store.set_error("openrouter", KeyringError::Invalid("x".into(), "y".into()));
+
+        let storage =
+            save_provider_key_with_keyring(&store, home.path(), "openrouter", "sk-or-file")?;
+
+        assert_eq!(storage, ProviderKeyStorage::File);
+        assert_eq!(
+            load_provider_key_with_keyring(&store, home.path(), "openrouter")?,
+            Some(("sk-or-file".to_string(), ProviderKeyStorage::File))
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn delete_removes_keyring_and_file_entries() -> io::Result<()> {
+        let home = TempDir::new()?;
+        let store = MockKeyringStore::default();
+        save_provider_key_with_keyring(&store, home.path(), "mistral", "sk-m")?;
+        write_fallback_file(
+            home.path(),
+            &FallbackFile::from([("mistral".to_string(), "sk-m-file".to_string())]),
+        )?;
+
+        assert!(delete_provider_key_with_keyring(
+            &store,
+            home.path(),
+            "mistral"
+        )?);
+        assert_eq!(
+            load_provider_key_with_keyring(&store, home.path(), "mistral")?,
+            None
+        );
+        assert!(!delete_provider_key_with_keyring(
+            &store,
+            home.path(),
+            "mistral"
+        )?);
+        Ok(())
+    }
+
+    #[test]
+    fn delete_removes_file_entry_when_keyring_is_unavailable() -> io::Result<()> {
+        let home = TempDir::new()?;
+        let store = MockKeyringStore::default();
+        store.set_error("mistral", KeyringError::Invalid("x".into(), "y".into()));
+        save_provider_key_with_keyring(&store, home.path(), "mistral", "sk-m-file")?;
+        store.set_error("mistral", KeyringError::Invalid("x".into(), "y".into()));
+
+        assert!(delete_provider_key_with_keyring(
+            &store,
+            home.path(),
+            "mistral"
+        )?);
+        assert_eq!(file_provider_ids(home.path())?, Vec::<String>::new());
+        Ok(())
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn fallback_file_is_only_readable_by_owner() -> io::Result<()> {
+        let home = TempDir::new()?;
+        write_fallback_file(
+            home.path(),
+            &FallbackFile::from([("mistral".to_string(), "sk-m".to_string())]),
+        )?;
+
+        let mode = fs::metadata(fallback_file_path(home.path()))?
+            .permissions()
+            .mode();
+        assert_eq!(mode & 0o777, 0o600);
+        Ok(())
+    }
+}
@@ -0,0 +1,555 @@
+//! Saved recipes: named lists of shell commands with typed parameters, stored
+//! as TOML under `$AISH_HOME/recipes/` and replayed with `/recipe <name>`, which
+//! runs the rendered commands through the shell tool's approval flow.
+//!
+//! ```toml
+//! description = "Collect a k8s debug bundle"
+//! commands = [
+//!     "kubectl -n {namespace} get pods -o wide",
+//!     "kubectl -n {namespace} logs --since={since}m -l app={app}",
+//! ]
+//!
+//! [[params]]
+//! name = "namespace"
+//! default = "default"
+//!
+//! [[params]]
+//! name = "since"
+//! type = "integer"
+//! default = 30
+//!
+//! [[params]]
+//! name = "app"
+//! ```
+
+use std::path::Path;
+use std::path::PathBuf;
+
+use aish_protocol::recipes::Recipe;
+use aish_protocol::recipes::RecipeParam;
+use aish_protocol::recipes::RecipeParamType;
+use serde::Deserialize;
+use serde::Serialize;
+use thiserror::Error;
+use tokio::fs;
+use tracing::warn;
+
+const RECIPE_EXTENSION: &str = "toml";
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum RecipeError {
+    #[error(
+        "invalid recipe name '{name}': use letters, digits, '-' and '_' (for example `rotate-logs`)"
+    )]
+    InvalidName { name: String },
+    #[error("missing value for '{param}' (usage: {usage})")]
+    MissingArgument { param: String, usage: String },
+    #[error("too many arguments (usage: {usage})")]
+    TooManyArguments { usage: String },
+    #[error("'{param}' expects {expected}, got '{value}'")]
+    InvalidValue {
+        param: String,
+        expected: RecipeParamType,
+        value: String,
+    },
+    #[error("could not parse arguments: unbalanced quotes")]
+    UnbalancedQuotes,
+    #[error("'{binding}' is not a name=value pair")]
+    InvalidBinding { binding: String },
+    #[error("value '{value}' for '{param}' does not appear in any saved command")]
+    UnusedBinding { param: String, value: String },
+    #[error("no successful commands to save yet; run a turn first")]
+    NoCommands,
+}
+
+/// On-disk layout of a recipe file. The name comes from the file stem.
+#[derive(Debug, Serialize, Deserialize)]
+struct RecipeToml {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    description: Option<String>,
+    commands: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    params: Vec<RecipeParamToml>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct RecipeParamToml {
+    name: String,
+    #[serde(rename = "type", default)]
+    param_type: RecipeParamType,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    description: Option<String>,
+    /// Accepts `30` as well as `"30"` so defaults can be written naturally.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    default: Option<toml::Value>,
+}
+
+/// Return the default recipes directory: `$AISH_HOME/recipes`.
+/// If `AISH_HOME` cannot be resolved, returns `None`.
+pub fn default_recipes_dir() -> Option<PathBuf> {
+    crate::config::find_codex_home()
+        .ok()
+        .map(|home| home.join("recipes"))
+}
+
+/// `save` is reserved for `/recipe save`.
+pub fn is_valid_recipe_name(name: &str) -> bool {
+    !name.is_empty()
+        && name != "save"
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+/// Discover recipe files in `dir`, returning entries sorted by name. Files
+/// that fail to parse are skipped with a warning. A missing or unreadable
+/// directory yields an empty list.
+pub async fn discover_recipes_in(dir: &Path) -> Vec<Recipe> {
+    let mut out: Vec<Recipe> = Vec::new();
+    let mut entries = match fs::read_dir(dir).await {
+        Ok(entries) => entries,
+        Err(_) => return out,
+    };
+
+    while let Ok(Some(entry)) = entries.next_entry().await {
+        let path = entry.path();
+        let is_toml = path
+            .extension()
+            .and_then(|s| s.to_str())
+            .is_some_and(|ext| ext.eq_ignore_ascii_case(RECIPE_EXTENSION));
+        if !is_toml {
+            continue;
+        }
+        let Some(name) = path
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .filter(|name| is_valid_recipe_name(name))
+            .map(str::to_string)
+        else {
+            continue;
+        };
+        let Ok(content) = fs::read_to_string(&path).await else {
+            continue;
+        };
+        match toml::from_str::<RecipeToml>(&content) {
+            Ok(parsed) => out.push(recipe_from_toml(name, path, parsed)),
+            Err(err) => warn!("skipping recipe {}: {err}", path.display()),
+        }
+    }
+    out.sort_by(|a, b| a.name.cmp(&b.name));
+    out
+}
+
+/// Write `recipe` to `dir/<name>.toml`, creating `dir` if needed. Returns the
+/// path that was written.
+pub async fn save_recipe_in(dir: &Path, recipe: &Recipe) -> std::io::Result<PathBuf> {
+    if !is_valid_recipe_name(&recipe.name) {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidInput,
+            RecipeError::InvalidName {
+                name: recipe.name.clone(),
+            }
+            .to_string(),
+        ));
+    }
+    let serialized = toml::to_string_pretty(&recipe_to_toml(recipe))
+        .map_err(|err| std::io::Error::new(std::io::ErrorKind::InvalidData, err))?;
+    fs::create_dir_all(dir).await?;
+    let path = dir.join(format!("{}.{RECIPE_EXTENSION}", recipe.name));
+    fs::write(&path, serialized).await?;
+    Ok(path)
+}
+
+/// Build a recipe from the commands a turn ran. Each `name=value` binding
+/// turns every whole-word occurrence of `value` into a `{name}` parameter
+/// whose default is `value`; the parameter type is inferred from the value.
+pub fn recipe_from_commands(
+    name: &str,
+    commands: Vec<String>,
+    bindings: &[String],
+) -> Result<Recipe, RecipeError> {
+    if !is_valid_recipe_name(name) {
+        return Err(RecipeError::InvalidName {
+            name: name.to_string(),
+        });
+    }
+    if commands.is_empty() {
+        return Err(RecipeError::NoCommands);
+    }
+
+    let mut commands = commands;
+    let mut params = Vec::new();
+    for binding in bindings {
+        let Some((param, value)) = binding.split_once('=') else {
+            return Err(RecipeError::InvalidBinding {
+                binding: binding.clone(),
+            });
+        };
+        if !is_valid_recipe_name(param) || value.is_empty() {
+            return Err(RecipeError::InvalidBinding {
+                binding: binding.clone(),
+            });
+        }
+        let placeholder = format!("{{{param}}}");
+        let mut used = false;
+        for command in commands.iter_mut() {
+            if let Some(replaced) = replace_value(command, value, &placeholder) {
+                *command = replaced;
+                used = true;
+            }
+        }
+        if !used {
+            return Err(RecipeError::UnusedBinding {
+                param: param.to_string(),
+                value: value.to_string(),
+            });
+        }
+        params.push(RecipeParam {
+            name: param.to_string(),
+            param_type: infer_param_type(value),
+            description: None,
+            default: Some(value.to_string()),
+        });
+    }
+
+    Ok(Recipe {
+        name: name.to_string(),
+        path: PathBuf::from(format!("{name}.{RECIPE_EXTENSION}")),
+        description: None,
+        params,
+        commands,
+    })
+}
+
+/// Usage string such as `/recipe k8s-bundle <app> [namespace=default]`.
+pub fn recipe_usage(recipe: &Recipe) -> String {
+    let mut usage = format!("/recipe {}", recipe.name);
+    for param in &recipe.params {
+        match &param.default {
+            Some(default) => usage.push_str(&format!(" [{}={default}]", param.name)),
+            None => usage.push_str(&format!(" <{}>", param.name)),
+        }
+    }
+    usage
+}
+
+/// Bind `args` (positional values and/or `name=value` pairs, shell-quoted)
+/// to the recipe's parameters, validate them against their types, and return
+/// the commands with every `{param}` replaced by the shell-quoted value.
+pub fn render_recipe(recipe: &Recipe, args: &str) -> Result<Vec<String>, RecipeError> {
+    let tokens = shlex::split(args).ok_or(RecipeError::UnbalancedQuotes)?;
+    let mut values: Vec<Option<String>> = vec![None; recipe.params.len()];
+    let mut positional = Vec::new();
+    for token in tokens {
+        let named = token.split_once('=').and_then(|(key, value)| {
+            recipe
+                .params
+                .iter()
+                .position(|param| param.name == key)
+                .map(|idx| (idx, value.to_string()))
+        });
+        match named {
+            Some((idx, value)) => values[idx] = Some(value),
+            None => positional.push(token),
+        }
+    }
+
+    let mut positional = positional.into_iter();
+    for slot in values.iter_mut().filter(|slot| slot.is_none()) {
+        match positional.next() {
+            Some(value) => *slot = Some(value),
+            None => break,
+        }
+    }
+    if positional.next().is_some() {
+        return Err(RecipeError::TooManyArguments {
+            usage: recipe_usage(recipe),
+        });
+    }
+
+    let mut bound = Vec::with_capacity(recipe.params.len());
+    for (param, value) in recipe.params.iter().zip(values) {
+        let Some(value) = value.or_else(|| param.default.clone()) else {
+            return Err(RecipeError::MissingArgument {
+                param: param.name.clone(),
+                usage: recipe_usage(recipe),
+            });
+        };
+        bound.push((param.name.as_str(), validate_value(param, value)?));
+    }
+
+    Ok(recipe
+        .commands
+        .iter()
+        .map(|command| substitute(command, &bound))
+        .collect())
+}
+
+fn recipe_from_toml(name: String, path: PathBuf, parsed: RecipeToml) -> Recipe {
+    let params = parsed
+        .params
+        .into_iter()
+        .map(|param| RecipeParam {
+            name: param.name,
+            param_type: param.param_type,
+            description: param.description,
+            default: param.default.map(|value| match value {
+                toml::Value::String(s) => s,
+                other => other.to_string(),
+            }),
+        })
+        .collect();
+    Recipe {
+        name,
+        path,
+        description: parsed.description,
+        params,
+        commands: parsed.commands,
+    }
+}
+
+fn recipe_to_toml(recipe: &Recipe) -> RecipeToml {
+    let params = recipe
+        .params
+        .iter()
+        .map(|param| RecipeParamToml {
+            name: param.name.clone(),
+            param_type: param.param_type,
+            description: param.description.clone(),
+            default: param.default.as_ref().map(|default| {
+                match (
+                    param.param_type,
+                    default.parse::<i64>(),
+                    default.parse::<bool>(),
+                ) {
+                    (RecipeParamType::Integer, Ok(n), _) => toml::Value::Integer(n),
+                    (RecipeParamType::Boolean, _, Ok(b)) => toml::Value::Boolean(b),
+                    _ => toml::Value::String(default.clone()),
+                }
+            }),
+        })
+        .collect();
+    RecipeToml {
+        description: recipe.description.clone(),
+        commands: recipe.commands.clone(),
+        params,
+    }
+}
+
+fn infer_param_type(value: &str) -> RecipeParamType {
+    if value.parse::<i64>().is_ok() {
+        RecipeParamType::Integer
+    } else if value.parse::<bool>().is_ok() {
+        RecipeParamType::Boolean
+    } else if value.contains('/') || value.starts_with('~') {
+        RecipeParamType::Path
+    } else {
+        RecipeParamType::String
+    }
+}
+
+fn validate_value(param: &RecipeParam, value: String) -> Result<String, RecipeError> {
+    let invalid = || RecipeError::InvalidValue {
+        param: param.name.clone(),
+        expected: param.param_type,
+        value: value.clone(),
+    };
+    match param.param_type {
+        RecipeParamType::String => Ok(value),
+        RecipeParamType::Integer => value
+            .parse::<i64>()
+            .map(|n| n.to_string())
+            .map_err(|_| invalid()),
+        RecipeParamType::Boolean => match value.to_ascii_lowercase().as_str() {
+            "true" | "yes" | "1" => Ok("true".to_string()),
+            "false" | "no" | "0" => Ok("false".to_string()),
+            _ => Err(invalid()),
+        },
+        RecipeParamType::Path => {
+            if value.is_empty() {
+                return Err(invalid());
+            }
+            let expanded = value
+                .strip_prefix("~/")
+                .zip(dirs::home_dir())
+                .map(|(rest, home)| home.join(rest).to_string_lossy().into_owned());
+            Ok(expanded.unwrap_or(value))
+        }
+    }
+}
+
+/// Replace `{name}` for every declared parameter; other braces (`${VAR}`,
+/// `awk '{print $1}'`, brace expansion) are left untouched.
+fn substitute(command: &str, bound: &[(&str, String)]) -> String {
+    let mut out = command.to_string();
+    for (name, value) in bound {
+        let quoted = shlex::try_quote(value)
+            .map(|quoted| quoted.into_owned())
+            .unwrap_or_else(|_| value.clone());
+        out = out.replace(&format!("{{{name}}}"), &quoted);
+    }
+    out
+}
+
+/// Replace whole-word occurrences of `value` (optionally wrapped in quotes)
+/// with `placeholder`. Returns `None` when nothing matched.
+fn replace_value(command: &str, value: &str, placeholder: &str) -> Option<String> {
+    let mut out = String::with_capacity(command.len());
+    let mut rest = command;
+    let mut matched = false;
+    let candidates = [
+        format!("'{value}'"),
+        format!("\"{value}\""),
+        value.to_string(),
+    ];
+    'outer: while !rest.is_empty() {
+        for candidate in &candidates {
+            if rest.starts_with(candidate.as_str()) {
+                let before_ok = out.chars().next_back().is_none_or(|c| !is_word_char(c));
+                let after_ok = rest[candidate.len()..]
+                    .chars()
+                    .next()
+                    .is_none_or(|c| !is_word_char(c));
+                if before_ok && after_ok {
+                    out.push_str(placeholder);
+                    rest = &rest[candidate.len()..];
+                    matched = true;
+                    continue 'outer;
+                }
+            }
+        }
+        let Some(c) = rest.chars().next() else {
+            break;
+        };
+        out.push(c);
+        rest = &rest[c.len_utf8()..];
+    }
+    matched.then_some(out)
+}
+
+fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_' || c == '-' || c == '.'
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use tempfile::tempdir;
+
+    fn bundle_recipe() -> Recipe {
+        Recipe {
+            name: "k8s-bundle".to_string(),
+            path: PathBuf::from("k8s-bundle.toml"),
+            description: Some("Collect a k8s debug bundle".to_string()),
+            params: vec![
+                RecipeParam {
+                    name: "app".to_string(),
+                    param_type: RecipeParamType::String,
+                    description: None,
+                    default: None,
+                },
+                RecipeParam {
+                    name: "since".to_string(),
+                    param_type: RecipeParamType::Integer,
+                    description: None,
+                    default: Some("30".to_string()),
+                },
+            ],
+            commands: vec![
+                "kubectl logs --since={since}m -l app={app}".to_string(),
+                "awk '{print $1}' pods.txt".to_string(),
+            ],
+        }
+    }
+
+    #[test]
+    fn render_binds_positional_and_named_arguments() {
+        let recipe = bundle_recipe();
+        assert_eq!(
+            render_recipe(&recipe, "api since=5"),
+            Ok(vec![
+                "kubectl logs --since=5m -l app=api".to_string(),
+                "awk '{print $1}' pods.txt".to_string(),
+            ])
+        );
+        assert_eq!(
+            render_recipe(&recipe, "'my app'").map(|commands| commands[0].clone()),
+            Ok("kubectl logs --since=30m -l app='my app'".to_string())
+        );
+    }
+
+    #[test]
+    fn render_validates_types_and_arity() {
+        let recipe = bundle_recipe();
+        assert_eq!(
+            render_recipe(&recipe, ""),
+            Err(RecipeError::MissingArgument {
+                param: "app".to_string(),
+                usage: "/recipe k8s-bundle <app> [since=30]".to_string(),
+            })
+        );
+        assert_eq!(
+            render_recipe(&recipe, "api since=soon"),
+            Err(RecipeError::InvalidValue {
+                param: "since".to_string(),
+                expected: RecipeParamType::Integer,
+                value: "soon".to_string(),
+            })
+        );
+        assert!(matches!(
+            render_recipe(&recipe, "api 5 extra"),
+            Err(RecipeError::TooManyArguments { .. })
+        ));
+    }
+
+    #[test]
+    fn bindings_become_typed_parameters() {
+        let recipe = recipe_from_commands(
+            "rotate-logs",
+            vec![
+                "logrotate -f /etc/logrotate.d/api".to_string(),
+                "journalctl -u api --since '-30min' | tail -n 30".to_string(),
+            ],
+            &["service=api".to_string(), "lines=30".to_string()],
+        )
+        .expect("recipe");
+        assert_eq!(
+            recipe.commands,
+            vec![
+                "logrotate -f /etc/logrotate.d/{service}".to_string(),
+                "journalctl -u {service} --since '-30min' | tail -n {lines}".to_string(),
+            ]
+        );
+        assert_eq!(recipe.params[1].param_type, RecipeParamType::Integer);
+        assert_eq!(
+            recipe_from_commands("x", vec!["ls".to_string()], &["dir=src".to_string()]),
+            Err(RecipeError::UnusedBinding {
+                param: "dir".to_string(),
+                value: "src".to_string(),
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn saved_recipes_round_trip_through_discovery() {
+        let dir = tempdir().expect("create TempDir");
+        let recipe = bundle_recipe();
+        let path = save_recipe_in(dir.path(), &recipe)
+            .await
+            .expect("save recipe");
+        std::fs::write(dir.path().join("broken.toml"), "commands = 3").expect("write");
+
+        let found = discover_recipes_in(dir.path()).await;
+        assert_eq!(found, vec![Recipe { path, ..recipe }]);
+    }
+
+    #[tokio::test]
+    async fn empty_when_dir_missing() {
+        let dir = tempdir().expect("create TempDir");
+        assert_eq!(
+            discover_recipes_in(&dir.path().join("missing")).await,
+            Vec::new()
+        );
+    }
+}
@@ -0,0 +1,752 @@
+//! Render a recorded session as Markdown, HTML or a replayable shell script.
+//!
+//! The export walks the `RolloutItem`s of a rollout file and keeps what a
+//! reader of a ticket or runbook cares about: user prompts, agent messages,
+//! executed commands with their exit codes, and the diffs of applied patches.
+
+use std::collections::HashMap;
+use std::fmt::Write as _;
+use std::io;
+use std::path::Path;
+use std::path::PathBuf;
+
+use aish_apply_patch::Hunk;
+use aish_apply_patch::parse_patch;
+use aish_protocol::models::LocalShellAction;
+use aish_protocol::models::ResponseItem;
+use aish_protocol::protocol::RolloutItem;
+use aish_protocol::protocol::RolloutLine;
+use similar::ChangeTag;
+use similar::TextDiff;
+
+use crate::parse_command::extract_shell_command;
+use crate::parse_command::shlex_join;
+use crate::protocol::EventMsg;
+
+/// Tools whose `command`/`cmd` argument is an executed command.
+pub(super) const SHELL_TOOL_NAMES: &[&str] = &["shell", "shell_command", "exec_command"];
+const APPLY_PATCH_TOOL_NAME: &str = "apply_patch";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    Markdown,
+    Html,
+    Shell,
+}
+
+/// Reads the rollout file at `path` and renders it in `format`.
+pub fn export_rollout(path: &Path, format: ExportFormat) -> io::Result<String> {
+    let contents = std::fs::read_to_string(path)?;
+    let transcript = Transcript::from_jsonl(&contents);
+    Ok(match format {
+        ExportFormat::Markdown => render_markdown(&transcript),
+        ExportFormat::Html => render_html(&transcript),
+        ExportFormat::Shell => render_shell(&transcript),
+    })
+}
+
+#[derive(Debug, Default)]
+struct Transcript {
+    id: Option<String>,
+    started_at: Option<String>,
+    cwd: Option<PathBuf>,
+    model: Option<String>,
+    entries: Vec<Entry>,
+}
+
+#[derive(Debug)]
+enum Entry {
+    User(String),
+    Agent(String),
+    Command(CommandEntry),
+    Patch(Vec<PatchFile>),
+}
+
+#[derive(Debug)]
+struct CommandEntry {
+    command: String,
+    /// Working directory the command ran in.
+    cwd: Option<PathBuf>,
+    exit_code: Option<i32>,
+}
+
+#[derive(Debug, PartialEq)]
+struct PatchFile {
+    /// `A path`, `D path`, `M path` or `R from -> to`.
+    label: String,
+    diff: String,
+}
+
+impl Transcript {
+    fn from_jsonl(contents: &str) -> Self {
+        let mut transcript = Transcript::default();
+        let mut turn_cwd: Option<PathBuf> = None;
+        // Commands waiting for their output, keyed by call id.
+        let mut pending_commands: HashMap<String, usize> = HashMap::new();
+
+        for line in contents.lines() {
+            let trimmed = line.trim();
+            if trimmed.is_empty() {
+                continue;
+            }
+            let Ok(rollout_line) = serde_json::from_str::<RolloutLine>(trimmed) else {
+                continue;
+            };
+            match rollout_line.item {
+                RolloutItem::SessionMeta(meta_line) => {
+                    transcript
+                        .id
+                        .get_or_insert_with(|| meta_line.meta.id.to_string());
+                    transcript
+                        .started_at
+                        .get_or_insert(meta_line.meta.timestamp);
+                    transcript.cwd.get_or_insert(meta_line.meta.cwd);
+                }
+                RolloutItem::TurnContext(context) => {
+                    transcript.model.get_or_insert(context.model);
+                    turn_cwd = Some(context.cwd);
+                }
+                RolloutItem::EventMsg(EventMsg::UserMessage(ev)) => {
+                    transcript.entries.push(Entry::User(ev.message));
+                }
+                RolloutItem::EventMsg(EventMsg::AgentMessage(ev)) => {
+                    transcript.entries.push(Entry::Agent(ev.message));
+                }
+                RolloutItem::ResponseItem(item) => {
+                    let cwd = turn_cwd.as_ref().or(transcript.cwd.as_ref()).cloned();
+                    transcript.push_response_item(item, cwd, &mut pending_commands);
+                }
+                _ => {}
+            }
+        }
+        transcript
+    }
+
+    fn push_response_item(
+        &mut self,
+        item: ResponseItem,
+        cwd: Option<PathBuf>,
+        pending_commands: &mut HashMap<String, usize>,
+    ) {
+        match item {
+            ResponseItem::LocalShellCall {
+                call_id,
+                action: LocalShellAction::Exec(exec),
+                ..
+            } => {
+                let cwd = exec.working_directory.map(PathBuf::from).or(cwd);
+                self.push_command(call_id, command_text(&exec.command), cwd, pending_commands);
+            }
+            ResponseItem::FunctionCall {
+                name,
+                arguments,
+                call_id,
+                ..
+            } if SHELL_TOOL_NAMES.contains(&name.as_str()) => {
+                let Ok(args) = serde_json::from_str::<serde_json::Value>(&arguments) else {
+                    return;
+                };
+                if let Some(files) = apply_patch_from_shell_arguments(&args) {
+                    self.entries.push(Entry::Patch(files));
+                    return;
+                }
+                let Some(command) = command_text_from_arguments(&args) else {
+                    return;
+                };
+                let cwd = args
+                    .get("workdir")
+                    .and_then(serde_json::Value::as_str)
+                    .map(PathBuf::from)
+                    .or(cwd);
+                self.push_command(Some(call_id), command, cwd, pending_commands);
+            }
+            ResponseItem::FunctionCall {
+                name, arguments, ..
+            } if name == APPLY_PATCH_TOOL_NAME => {
+                let input = serde_json::from_str::<serde_json::Value>(&arguments)
+                    .ok()
+                    .and_then(|args| args.get("input")?.as_str().map(str::to_string));
+                if let Some(files) = input.as_deref().and_then(patch_files) {
+                    self.entries.push(Entry::Patch(files));
+                }
+            }
+            ResponseItem::CustomToolCall { name, input, .. } if name == APPLY_PATCH_TOOL_NAME => {
+                if let Some(files) = patch_files(&input) {
+                    self.entries.push(Entry::Patch(files));
+                }
+            }
+            ResponseItem::FunctionCallOutput { call_id, output } => {
+                if let Some(index) = pending_commands.remove(&call_id)
+                    && let Some(Entry::Command(command)) = self.entries.get_mut(index)
+                {
+                    command.exit_code = exit_code_from_output(&output.content);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn push_command(
+        &mut self,
+        call_id: Option<String>,
+        command: String,
+        cwd: Option<PathBuf>,
+        pending_commands: &mut HashMap<String, usize>,
+    ) {
+        if let Some(call_id) = call_id {
+            pending_commands.insert(call_id, self.entries.len());
+        }
+        self.entries.push(Entry::Command(CommandEntry {
+            command,
+            cwd,
+            exit_code: None,
+        }));
+    }
+
+    fn title(&self) -> String {
+        match self.id.as_deref() {
+            Some(id) => format!("Aish session {id}"),
+            None => "Aish session".to_string(),
+        }
+    }
+
+    /// `(label, value)` pairs describing the session.
+    fn details(&self) -> Vec<(&'static str, String)> {
+        let mut details = Vec::new();
+        if let Some(started_at) = self.started_at.as_ref() {
+            details.push(("Started", started_at.clone()));
+        }
+        if let Some(cwd) = self.cwd.as_ref() {
+            details.push(("Directory", cwd.display().to_string()));
+        }
+        if let Some(model) = self.model.as_ref() {
+            details.push(("Model", model.clone()));
+        }
+        details
+    }
+}
+
+/// Shell commands are recorded as argv; `bash -lc <script>` style wrappers
+/// are shown as the script the user would have typed.
+pub(super) fn command_text(argv: &[String]) -> String {
+    match extract_shell_command(argv) {
+        Some((_, script)) => script.to_string(),
+        None => shlex_join(argv),
+    }
+}
+
+/// Extracts the command from the JSON arguments of a shell tool call.
+pub(super) fn command_text_from_arguments(args: &serde_json::Value) -> Option<String> {
+    match args.get("command").or_else(|| args.get("cmd"))? {
+        serde_json::Value::String(command) => Some(command.clone()),
+        serde_json::Value::Array(parts) => {
+            let argv: Vec<String> = parts
+                .iter()
+                .filter_map(|part| part.as_str().map(str::to_string))
+                .collect();
+            Some(command_text(&argv))
+        }
+        _ => None,
+    }
+}
+
+/// `["apply_patch", "<patch>"]` sent through the shell tool.
+fn apply_patch_from_shell_arguments(args: &serde_json::Value) -> Option<Vec<PatchFile>> {
+    let parts = args.get("command")?.as_array()?;
+    match parts.as_slice() {
+        [program, patch] if matches!(program.as_str(), Some("apply_patch" | "applypatch")) => {
+            patch_files(patch.as_str()?)
+        }
+        _ => None,
+    }
+}
+
+fn exit_code_from_output(content: &str) -> Option<i32> {
+    if let Ok(value) = serde_json::from_str::<serde_json::Value>(content)
+        && let Some(code) = value
+            .pointer("/metadata/exit_code")
+            .and_then(serde_json::Value::as_i64)
+    {
+        return i32::try_from(code).ok();
+    }
+    content.lines().find_map(|line| {
+        line.strip_prefix("Exit code: ")
+            .or_else(|| line.strip_prefix("Process exited with code "))
+            .and_then(|code| code.trim().parse().ok())
+    })
+}
+
+/// Renders each file touched by `patch` as a unified diff.
+///
+/// `aish_apply_patch::unified_diff_from_chunks` needs the file as it was
+/// before the patch, which is gone by the time a session is exported, so
+/// each chunk is diffed on its own and labelled with its `@@` context.
+fn patch_files(patch: &str) -> Option<Vec<PatchFile>> {
+    let args = parse_patch(patch).ok()?;
+    Some(
+        args.hunks
+            .iter()
+            .map(|hunk| match hunk {
+                Hunk::AddFile { path, contents } => {
+                    let mut diff = format!("--- /dev/null\n+++ b/{}\n", path.display());
+                    for line in contents.lines() {
+                        let _ = writeln!(diff, "+{line}");
+                    }
+                    PatchFile {
+                        label: format!("A {}", path.display()),
+                        diff,
+                    }
+                }
+                Hunk::DeleteFile { path } => PatchFile {
+                    label: format!("D {}", path.display()),
+                    diff: format!("--- a/{}\n+++ /dev/null\n", path.display()),
+                },
+                Hunk::UpdateFile {
+                    path,
+                    move_path,
+                    chunks,
+                } => {
+                    let new_path = move_path.as_ref().unwrap_or(path);
+                    let mut diff =
+                        format!("--- a/{}\n+++ b/{}\n", path.display(), new_path.display());
+                    for chunk in chunks {
+                        match chunk.change_context.as_deref() {
+                            Some(context) => {
+                                let _ = writeln!(diff, "@@ {context}");
+                            }
+                            None => diff.push_str("@@\n"),
+                        }
+                        let old: Vec<&str> = chunk.old_lines.iter().map(String::as_str).collect();
+                        let new: Vec<&str> = chunk.new_lines.iter().map(String::as_str).collect();
+                        for change in TextDiff::from_slices(&old, &new).iter_all_changes() {
+                            let sign = match change.tag() {
+                                ChangeTag::Equal => ' ',
+                                ChangeTag::Delete => '-',
+                                ChangeTag::Insert => '+',
+                            };
+                            let _ = writeln!(diff, "{sign}{}", change.value());
+                        }
+                    }
+                    let label = match move_path {
+                        Some(to) => format!("R {} -> {}", path.display(), to.display()),
+                        None => format!("M {}", path.display()),
+                    };
+                    PatchFile { label, diff }
+                }
+            })
+            .collect(),
+    )
+}
+
+fn exit_code_label(exit_code: Option<i32>) -> String {
+    match exit_code {
+        Some(code) => format!("exit code {code}"),
+        None => "exit code unknown".to_string(),
+    }
+}
+
+/// A code fence longer than any backtick run in `text`.
+fn fence_for(text: &str) -> String {
+    let longest = text.split(|c| c != '`').map(str::len).max().unwrap_or(0);
+    "`".repeat(longest.max(2) + 1)
+}
+
+fn render_markdown(transcript: &Transcript) -> String {
+    let mut out = format!("# {}\n\n", transcript.title());
+    for (label, value) in transcript.details() {
+        let _ = writeln!(out, "- **{label}:** `{value}`");
+    }
+
+    for entry in &transcript.entries {
+        out.push('\n');
+        match entry {
+            Entry::User(text) => {
+                let _ = write!(out, "## User\n\n{}\n", text.trim());
+            }
+            Entry::Agent(text) => {
+                let _ = write!(out, "## Aish\n\n{}\n", text.trim());
+            }
+            Entry::Command(command) => {
+                let fence = fence_for(&command.command);
+                let _ = write!(out, "**Ran** ({})", exit_code_label(command.exit_code));
+                if let Some(cwd) = command.cwd.as_ref()
+                    && Some(cwd) != transcript.cwd.as_ref()
+                {
+                    let _ = write!(out, " in `{}`", cwd.display());
+                }
+                let _ = write!(out, "\n\n{fence}sh\n{}\n{fence}\n", command.command);
+            }
+            Entry::Patch(files) => {
+                for (idx, file) in files.iter().enumerate() {
+                    if idx > 0 {
+                        out.push('\n');
+                    }
+                    let fence = fence_for(&file.diff);
+                    let _ = write!(
+                        out,
+                        "**Patched** `{}`\n\n{fence}diff\n{}{fence}\n",
+                        file.label, file.diff
+                    );
+                }
+            }
+        }
+    }
+    out
+}
+
+fn escape_html(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+const HTML_STYLE: &str = "body{font-family:system-ui,sans-serif;max-width:60rem;margin:2rem auto;padding:0 1rem;line-height:1.5}\
+pre{background:#f6f8fa;padding:.75rem;overflow-x:auto}\
+.user{border-left:4px solid #0969da;padding-left:.75rem}\
+.agent{border-left:4px solid #8250df;padding-left:.75rem}\
+.meta{color:#57606a}\
+.add{color:#1a7f37}.del{color:#cf222e}.hunk{color:#0550ae}";
+
+fn render_html(transcript: &Transcript) -> String {
+    let title = escape_html(&transcript.title());
+    let mut out = format!(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{title}</title>\n<style>{HTML_STYLE}</style>\n</head>\n<body>\n<h1>{title}</h1>\n"
+    );
+    let details = transcript.details();
+    if !details.is_empty() {
+        out.push_str("<ul class=\"meta\">\n");
+        for (label, value) in details {
+            let _ = writeln!(
+                out,
+                "<li><strong>{label}:</strong> <code>{}</code></li>",
+                escape_html(&value)
+            );
+        }
+        out.push_str("</ul>\n");
+    }
+
+    for entry in &transcript.entries {
+        match entry {
+            Entry::User(text) => {
+                let _ = writeln!(
+                    out,
+                    "<section class=\"user\"><h2>User</h2><pre>{}</pre></section>",
+                    escape_html(text.trim())
+                );
+            }
+            Entry::Agent(text) => {
+                let _ = writeln!(
+                    out,
+                    "<section class=\"agent\"><h2>Aish</h2><pre>{}</pre></section>",
+                    escape_html(text.trim())
+                );
+            }
+            Entry::Command(command) => {
+                let mut meta = exit_code_label(command.exit_code);
+                if let Some(cwd) = command.cwd.as_ref()
+                    && Some(cwd) != transcript.cwd.as_ref()
+                {
+                    let _ = write!(meta, " in {}", cwd.display());
+                }
+                let _ = writeln!(
+                    out,
+                    "<section class=\"command\"><p class=\"meta\">Ran ({})</p><pre>$ {}</pre></section>",
+                    escape_html(&meta),
+                    escape_html(&command.command)
+                );
+            }
+            Entry::Patch(files) => {
+                for file in files {
+                    let _ = write!(
+                        out,
+                        "<section class=\"patch\"><p class=\"meta\">Patched <code>{}</code></p><pre>",
+                        escape_html(&file.label)
+                    );
+                    for line in file.diff.lines() {
+                        let class = if line.starts_with("@@") {
+                            Some("hunk")
+                        } else if line.starts_with('+') && !line.starts_with("+++") {
+                            Some("add")
+                        } else if line.starts_with('-') && !line.starts_with("---") {
+                            Some("del")
+                        } else {
+                            None
+                        };
+                        match class {
+                            Some(class) => {
+                                let _ = writeln!(
+                                    out,
+                                    "<span class=\"{class}\">{}</span>",
+                                    escape_html(line)
+                                );
+                            }
+                            None => {
+                                let _ = writeln!(out, "{}", escape_html(line));
+                            }
+                        }
+                    }
+                    out.push_str("</pre></section>\n");
+                }
+            }
+        }
+    }
+    out.push_str("</body>\n</html>\n");
+    out
+}
+
+fn render_shell(transcript: &Transcript) -> String {
+    let mut out = String::from("#!/usr/bin/env bash\n");
+    let _ = writeln!(out, "# Commands executed in {}.", transcript.title());
+    for (label, value) in transcript.details() {
+        let _ = writeln!(out, "# {label}: {value}");
+    }
+    out.push_str(
+        "# Commands are listed in the order they ran. Review before replaying;\n\
+         # the script does not stop when a command fails. Commands without a\n\
+         # recorded exit code (rejected, forbidden or interrupted) are commented out.\n",
+    );
+
+    let mut current_dir = transcript.cwd.clone();
+    if let Some(cwd) = current_dir.as_ref() {
+        let _ = write!(out, "\ncd {}\n", shell_quote(cwd));
+    }
+
+    let mut step = 0usize;
+    for entry in &transcript.entries {
+        match entry {
+            Entry::Command(command) => {
+                step += 1;
+                out.push('\n');
+                if command.exit_code.is_none() {
+                    let _ = writeln!(out, "# [{step}] not run (no exit code recorded)");
+                    for line in command.command.lines() {
+                        let _ = writeln!(out, "# {line}");
+                    }
+                    continue;
+                }
+                if let Some(cwd) = command.cwd.as_ref()
+                    && current_dir.as_ref() != Some(cwd)
+                {
+                    let _ = writeln!(out, "cd {}", shell_quote(cwd));
+                    current_dir = Some(cwd.clone());
+                }
+                let _ = writeln!(out, "# [{step}] {}", exit_code_label(command.exit_code));
+                let _ = writeln!(out, "{}", command.command);
+            }
+            Entry::Patch(files) => {
+                out.push('\n');
+                for file in files {
+                    let _ = writeln!(out, "# apply_patch (not replayed): {}", file.label);
+                }
+            }
+            Entry::User(_) | Entry::Agent(_) => {}
+        }
+    }
+    out
+}
+
+fn shell_quote(path: &Path) -> String {
+    let path = path.to_string_lossy();
+    shlex::try_quote(&path)
+        .map(|quoted| quoted.into_owned())
+        .unwrap_or_else(|_| path.into_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+
+    fn rollout() -> String {
+        let lines = [
+            json!({
+                "timestamp": "2025-01-03T12:00:00Z",
+                "type": "session_meta",
+                "payload": {
+                    "id": "00000000-0000-0000-0000-000000000003",
+                    "timestamp": "2025-01-03T12:00:00Z",
+                    "cwd": "/work/repo",
+                    "originator": "test",
+                    "cli_version": "0.0.0",
+                    "instructions": null,
+                    "source": "cli",
+                    "model_provider": "openai"
+                }
+            }),
+            json!({
+                "timestamp": "2025-01-03T12:00:01Z",
+                "type": "event_msg",
+                "payload": {"type": "user_message", "message": "fix the <build>"}
+            }),
+            json!({
+                "timestamp": "2025-01-03T12:00:02Z",
+                "type": "response_item",
+                "payload": {
+                    "type": "function_call",
+                    "name": "shell",
+                    "arguments": json!({"command": ["bash", "-lc", "cargo build"]}).to_string(),
+                    "call_id": "call-1"
+                }
+            }),
+            json!({
+                "timestamp": "2025-01-03T12:00:03Z",
+                "type": "response_item",
+                "payload": {
+                    "type": "function_call_output",
+                    "call_id": "call-1",
+                    "output": "Exit code: 101\nWall time: 1.2 seconds\nOutput:\nerror"
+                }
+            }),
+            json!({
+                "timestamp": "2025-01-03T12:00:04Z",
+                "type": "response_item",
+                "payload": {
+                    "type": "custom_tool_call",
+                    "name": "apply_patch",
+                    "input": "*** Begin Patch\n*** Update File: src/lib.rs\n@@ fn main\n-    broken();\n+    fixed();\n*** End Patch",
+                    "call_id": "call-2"
+                }
+            }),
+            json!({
+                "timestamp": "2025-01-03T12:00:05Z",
+                "type": "response_item",
+                "payload": {
+                    "type": "function_call",
+                    "name": "shell",
+                    "arguments": json!({"command": ["cargo", "test"], "workdir": "/work/repo/crate"}).to_string(),
+                    "call_id": "call-3"
+                }
+            }),
+            json!({
+                "timestamp": "2025-01-03T12:00:06Z",
+                "type": "response_item",
+                "payload": {
+                    "type": "function_call_output",
+                    "call_id": "call-3",
+                    "output": json!({"output": "ok", "metadata": {"exit_code": 0, "duration_seconds": 0.5}}).to_string()
+                }
+            }),
+            json!({
+                "timestamp": "2025-01-03T12:00:07Z",
+                "type": "event_msg",
+                "payload": {"type": "agent_message", "message": "Fixed the build."}
+            }),
+        ];
+        lines
+            .iter()
+            .map(serde_json::Value::to_string)
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    #[test]
+    fn shell_export_lists_commands_with_exit_codes() {
+        let transcript = Transcript::from_jsonl(&rollout());
+
+        assert_eq!(
+            render_shell(&transcript),
+            "#!/usr/bin/env bash\n\
+             # Commands executed in Aish session 00000000-0000-0000-0000-000000000003.\n\
+             # Started: 2025-01-03T12:00:00Z\n\
+             # Directory: /work/repo\n\
+             # Commands are listed in the order they ran. Review before replaying;\n\
+             # the script does not stop when a command fails. Commands without a\n\
+             # recorded exit code (rejected, forbidden or interrupted) are commented out.\n\
+             \n\
+             cd /work/repo\n\
+             \n\
+             # [1] exit code 101\n\
+             cargo build\n\
+             \n\
+             # apply_patch (not replayed): M src/lib.rs\n\
+             \n\
+             cd /work/repo/crate\n\
+             # [2] exit code 0\n\
+             cargo test\n"
+        );
+    }
+
+    #[test]
+    fn shell_export_comments_out_commands_that_did_not_run() {
+        let rejected = [
+            json!({
+                "timestamp": "2025-01-03T12:00:08Z",
+                "type": "response_item",
+                "payload": {
+                    "type": "function_call",
+                    "name": "shell",
+                    "arguments": json!({"command": ["bash", "-lc", "rm -rf /work/repo\necho done"], "workdir": "/tmp"}).to_string(),
+                    "call_id": "call-4"
+                }
+            }),
+            json!({
+                "timestamp": "2025-01-03T12:00:09Z",
+                "type": "response_item",
+                "payload": {
+                    "type": "function_call_output",
+                    "call_id": "call-4",
+                    "output": "exec command rejected by user"
+                }
+            }),
+        ];
+        let contents = std::iter::once(rollout())
+            .chain(rejected.iter().map(serde_json::Value::to_string))
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        let script = render_shell(&Transcript::from_jsonl(&contents));
+
+        assert!(
+            script.ends_with(
+                "cargo test\n\
+                 \n\
+                 # [3] not run (no exit code recorded)\n\
+                 # rm -rf /work/repo\n\
+                 # echo done\n"
+            ),
+            "{script}"
+        );
+        assert!(!script.contains("cd /tmp"), "{script}");
+    }
+
+    #[test]
+    fn markdown_export_includes_messages_commands_and_diffs() {
+        let transcript = Transcript::from_jsonl(&rollout());
+        let markdown = render_markdown(&transcript);
+
+        assert!(markdown.starts_with("# Aish session 00000000-0000-0000-0000-000000000003\n"));
+        assert!(markdown.contains("## User\n\nfix the <build>\n"));
+        assert!(markdown.contains("**Ran** (exit code 101)\n\n```sh\ncargo build\n```\n"));
+        assert!(markdown.contains(
+            "**Patched** `M src/lib.rs`\n\n```diff\n--- a/src/lib.rs\n+++ b/src/lib.rs\n@@ fn main\n-    broken();\n+    fixed();\n```\n"
+        ));
+        assert!(markdown.contains("**Ran** (exit code 0) in `/work/repo/crate`"));
+        assert!(markdown.ends_with("## Aish\n\nFixed the build.\n"));
+    }
+
+    #[test]
+    fn html_export_escapes_content() {
+        let transcript = Transcript::from_jsonl(&rollout());
+        let html = render_html(&transcript);
+
+        assert!(html.contains("<pre>fix the &lt;build&gt;</pre>"));
+        assert!(html.contains("<span class=\"add\">+    fixed();</span>"));
+        assert!(html.ends_with("</body>\n</html>\n"));
+    }
+
+    #[test]
+    fn fence_is_longer_than_embedded_backticks() {
+        assert_eq!(fence_for("plain"), "```");
+        assert_eq!(fence_for("has ``` inside"), "````");
+    }
+}
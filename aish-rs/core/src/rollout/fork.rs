@@ -0,0 +1,300 @@
+//! Branching a saved session at an earlier turn (`aish resume --fork-at`).
+//!
+//! The fork is a new rollout file holding a copy of the original up to the
+//! chosen turn, so the original session is left untouched and both can be
+//! resumed independently.
+
+use std::fs;
+use std::io;
+use std::io::BufRead;
+use std::io::BufReader;
+use std::io::ErrorKind;
+use std::io::Write;
+use std::path::Path;
+use std::path::PathBuf;
+
+use aish_protocol::ConversationId;
+use aish_protocol::items::TurnItem;
+use aish_protocol::models::ResponseItem;
+use aish_protocol::protocol::RolloutItem;
+use aish_protocol::protocol::RolloutLine;
+use aish_protocol::protocol::SessionMetaLine;
+use time::OffsetDateTime;
+use time::format_description::FormatItem;
+use time::macros::format_description;
+
+use super::recorder::new_rollout_path;
+use crate::event_mapping::parse_turn_item;
+
+/// A session created by [`fork_rollout_at`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct ForkedSession {
+    pub id: ConversationId,
+    pub path: PathBuf,
+    pub parent_id: ConversationId,
+    /// Number of user turns copied from the parent.
+    pub turns: usize,
+}
+
+/// Copies the rollout at `source` into a new session under `codex_home`,
+/// keeping everything before the user turn at `turn_index` (0-based). The
+/// new session's metadata records the original session as its parent; the
+/// original's name and tags are not carried over.
+pub async fn fork_rollout_at(
+    codex_home: &Path,
+    source: &Path,
+    turn_index: usize,
+) -> io::Result<ForkedSession> {
+    let codex_home = codex_home.to_path_buf();
+    let source = source.to_path_buf();
+    let now = OffsetDateTime::now_local().unwrap_or_else(|_| OffsetDateTime::now_utc());
+    tokio::task::spawn_blocking(move || fork_rollout_at_time(&codex_home, &source, turn_index, now))
+        .await
+        .map_err(io::Error::other)?
+}
+
+fn fork_rollout_at_time(
+    codex_home: &Path,
+    source: &Path,
+    turn_index: usize,
+    now: OffsetDateTime,
+) -> io::Result<ForkedSession> {
+    let reader = BufReader::new(fs::File::open(source)?);
+    let mut meta: Option<SessionMetaLine> = None;
+    let mut kept = Vec::new();
+    let mut turns = 0;
+    for line in reader.lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        // Same leniency as resuming: lines that do not parse are dropped.
+        let Ok(rollout_line) = serde_json::from_str::<RolloutLine>(&line) else {
+            continue;
+        };
+        match &rollout_line.item {
+            RolloutItem::SessionMeta(session_meta) => {
+                if meta.is_none() {
+                    meta = Some(session_meta.clone());
+                }
+            }
+            RolloutItem::SessionMetadata(_) => {}
+            RolloutItem::ResponseItem(item) if is_user_turn(item) => {
+                // Keep counting past the cut so errors can report the total.
+                turns += 1;
+                if turns <= turn_index {
+                    kept.push(rollout_line);
+                }
+            }
+            _ => {
+                if turns <= turn_index {
+                    kept.push(rollout_line);
+                }
+            }
+        }
+    }
+
+    let Some(mut meta) = meta else {
+        return Err(io::Error::new(
+            ErrorKind::InvalidData,
+            format!("{} has no session metadata", source.display()),
+        ));
+    };
+    if turn_index >= turns {
+        let plural = if turns == 1 { "" } else { "s" };
+        return Err(io::Error::new(
+            ErrorKind::InvalidInput,
+            format!("cannot fork at turn {turn_index}: the session has {turns} turn{plural}"),
+        ));
+    }
+
+    let parent_id = meta.meta.id;
+    let id = ConversationId::new();
+    let timestamp_format: &[FormatItem] =
+        format_description!("[year]-[month]-[day]T[hour]:[minute]:[second].[subsecond digits:3]Z");
+    let timestamp = now
+        .to_offset(time::UtcOffset::UTC)
+        .format(timestamp_format)
+        .map_err(|e| io::Error::other(format!("failed to format timestamp: {e}")))?;
+    meta.meta.id = id;
+    meta.meta.timestamp = timestamp.clone();
+    meta.meta.parent_id = Some(parent_id);
+
+    let path = new_rollout_path(codex_home, id, now)?;
+    let mut out = io::BufWriter::new(
+        fs::OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(&path)?,
+    );
+    let meta_line = RolloutLine {
+        timestamp,
+        item: RolloutItem::SessionMeta(meta),
+    };
+    for line in std::iter::once(&meta_line).chain(&kept) {
+        serde_json::to_writer(&mut out, line)?;
+        out.write_all(b"\n")?;
+    }
+    out.flush()?;
+
+    Ok(ForkedSession {
+        id,
+        path,
+        parent_id,
+        turns: turn_index,
+    })
+}
+
+fn is_user_turn(item: &ResponseItem) -> bool {
+    matches!(item, ResponseItem::Message { .. })
+        && matches!(parse_turn_item(item), Some(TurnItem::UserMessage(_)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aish_protocol::models::ContentItem;
+    use aish_protocol::protocol::SessionMeta;
+    use aish_protocol::protocol::SessionMetadataItem;
+    use pretty_assertions::assert_eq;
+    use tempfile::TempDir;
+
+    fn message(role: &str, text: &str) -> RolloutItem {
+        let content = if role == "user" {
+            ContentItem::InputText {
+                text: text.to_string(),
+            }
+        } else {
+            ContentItem::OutputText {
+                text: text.to_string(),
+            }
+        };
+        RolloutItem::ResponseItem(ResponseItem::Message {
+            id: None,
+            role: role.to_string(),
+            content: vec![content],
+        })
+    }
+
+    fn write_rollout(path: &Path, items: Vec<RolloutItem>) -> io::Result<()> {
+        let mut out = String::new();
+        for item in items {
+            out.push_str(&serde_json::to_string(&RolloutLine {
+                timestamp: "2025-01-01T00:00:00.000Z".to_string(),
+                item,
+            })?);
+            out.push('\n');
+        }
+        fs::write(path, out)
+    }
+
+    fn read_items(path: &Path) -> io::Result<Vec<RolloutItem>> {
+        fs::read_to_string(path)?
+            .lines()
+            .map(|line| {
+                serde_json::from_str::<RolloutLine>(line)
+                    .map(|line| line.item)
+                    .map_err(io::Error::other)
+            })
+            .collect()
+    }
+
+    fn texts(items: &[RolloutItem]) -> Vec<String> {
+        items
+            .iter()
+            .filter_map(|item| match item {
+                RolloutItem::ResponseItem(ResponseItem::Message { content, .. }) => {
+                    match content.first() {
+                        Some(
+                            ContentItem::InputText { text } | ContentItem::OutputText { text },
+                        ) => Some(text.clone()),
+                        _ => None,
+                    }
+                }
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn fork_copies_turns_before_index_and_links_parent() -> io::Result<()> {
+        let home = TempDir::new()?;
+        let source = home.path().join("source.jsonl");
+        let parent_id = ConversationId::new();
+        write_rollout(
+            &source,
+            vec![
+                RolloutItem::SessionMeta(SessionMetaLine {
+                    meta: SessionMeta {
+                        id: parent_id,
+                        ..Default::default()
+                    },
+                    git: None,
+                }),
+                message("user", "first"),
+                message("assistant", "first answer"),
+                RolloutItem::SessionMetadata(SessionMetadataItem {
+                    name: Some("original".to_string()),
+                    tags: Vec::new(),
+                }),
+                message("user", "second"),
+                message("assistant", "second answer"),
+            ],
+        )?;
+
+        let forked = fork_rollout_at_time(home.path(), &source, 1, OffsetDateTime::now_utc())?;
+
+        assert_eq!(forked.parent_id, parent_id);
+        assert_eq!(forked.turns, 1);
+        assert!(
+            forked
+                .path
+                .starts_with(home.path().join(super::super::SESSIONS_SUBDIR))
+        );
+        assert!(
+            forked
+                .path
+                .to_string_lossy()
+                .contains(&forked.id.to_string())
+        );
+        let items = read_items(&forked.path)?;
+        let RolloutItem::SessionMeta(meta) = &items[0] else {
+            panic!("first line should be session meta, got {:?}", items[0]);
+        };
+        assert_eq!(meta.meta.id, forked.id);
+        assert_eq!(meta.meta.parent_id, Some(parent_id));
+        assert_eq!(texts(&items), vec!["first", "first answer"]);
+        assert!(
+            !items
+                .iter()
+                .any(|item| matches!(item, RolloutItem::SessionMetadata(_)))
+        );
+        assert_eq!(texts(&read_items(&source)?).len(), 4);
+        Ok(())
+    }
+
+    #[test]
+    fn fork_past_the_last_turn_is_rejected() -> io::Result<()> {
+        let home = TempDir::new()?;
+        let source = home.path().join("source.jsonl");
+        write_rollout(
+            &source,
+            vec![
+                RolloutItem::SessionMeta(SessionMetaLine {
+                    meta: SessionMeta::default(),
+                    git: None,
+                }),
+                message("user", "only"),
+            ],
+        )?;
+
+        let err = fork_rollout_at_time(home.path(), &source, 1, OffsetDateTime::now_utc())
+            .expect_err("turn 1 does not exist");
+        assert_eq!(err.kind(), ErrorKind::InvalidInput);
+        assert_eq!(
+            err.to_string(),
+            "cannot fork at turn 1: the session has 1 turn"
+        );
+        Ok(())
+    }
+}
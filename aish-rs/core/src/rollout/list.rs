@@ -14,6 +14,7 @@ use time::macros::format_description;
 use uuid::Uuid;
 
 use super::SESSIONS_SUBDIR;
+use super::metadata::read_tail_session_metadata;
 use crate::protocol::EventMsg;
 use aish_file_search as file_search;
 use aish_protocol::protocol::RolloutItem;
@@ -44,6 +45,10 @@ pub struct ConversationItem {
     pub created_at: Option<String>,
     /// RFC3339 timestamp string for the most recent update (from file mtime).
     pub updated_at: Option<String>,
+    /// Name given with `/rename` or `aish sessions tag --name`.
+    pub name: Option<String>,
+    /// Tags given with `/tag` or `aish sessions tag`.
+    pub tags: Vec<String>,
 }
 
 #[derive(Default)]
@@ -232,11 +237,18 @@ async fn traverse_directories_for_paths(
                                 .unwrap_or(None)
                                 .or_else(|| created_at.clone());
                         }
+                        let metadata = read_tail_session_metadata(&path)
+                            .await
+                            .ok()
+                            .flatten()
+                            .unwrap_or_default();
                         items.push(ConversationItem {
                             path,
                             head,
                             created_at,
                             updated_at,
+                            name: metadata.name,
+                            tags: metadata.tags,
                         });
                     }
                 }
@@ -333,7 +345,7 @@ where
     Ok(collected)
 }
 
-fn parse_timestamp_uuid_from_filename(name: &str) -> Option<(OffsetDateTime, Uuid)> {
+pub(super) fn parse_timestamp_uuid_from_filename(name: &str) -> Option<(OffsetDateTime, Uuid)> {
     // Expected: rollout-YYYY-MM-DDThh-mm-ss-<uuid>.jsonl
     let core = name.strip_prefix("rollout-")?.strip_suffix(".jsonl")?;
 
@@ -350,13 +362,13 @@ fn parse_timestamp_uuid_from_filename(name: &str) -> Option<(OffsetDateTime, Uui
     Some((ts, uuid))
 }
 
-struct ProviderMatcher<'a> {
+pub(super) struct ProviderMatcher<'a> {
     filters: &'a [String],
     matches_default_provider: bool,
 }
 
 impl<'a> ProviderMatcher<'a> {
-    fn new(filters: &'a [String], default_provider: &'a str) -> Option<Self> {
+    pub(super) fn new(filters: &'a [String], default_provider: &'a str) -> Option<Self> {
         if filters.is_empty() {
             return None;
         }
@@ -368,7 +380,7 @@ impl<'a> ProviderMatcher<'a> {
         })
     }
 
-    fn matches(&self, session_provider: Option<&str>) -> bool {
+    pub(super) fn matches(&self, session_provider: Option<&str>) -> bool {
         match session_provider {
             Some(provider) => self.filters.iter().any(|candidate| candidate == provider),
             None => self.matches_default_provider,
@@ -420,7 +432,7 @@ async fn read_head_summary(path: &Path, head_limit: usize) -> io::Result<HeadTai
             RolloutItem::TurnContext(_) => {
                 // Not included in `head`; skip.
             }
-            RolloutItem::Compacted(_) => {
+            RolloutItem::Compacted(_) | RolloutItem::SessionMetadata(_) => {
                 // Not included in `head`; skip.
             }
             RolloutItem::EventMsg(ev) => {
@@ -0,0 +1,165 @@
+//! Reading and appending `SessionMetadata` records (session name and tags)
+//! in rollout files.
+
+use std::fs;
+use std::io;
+use std::io::BufRead;
+use std::io::BufReader;
+use std::io::SeekFrom;
+use std::io::Write;
+use std::path::Path;
+
+use aish_protocol::protocol::RolloutItem;
+use aish_protocol::protocol::RolloutLine;
+use aish_protocol::protocol::SessionMetadataItem;
+use aish_protocol::protocol::SessionMetadataUpdate;
+use time::OffsetDateTime;
+use time::format_description::FormatItem;
+use time::macros::format_description;
+
+/// How much of the end of a rollout listings read for the latest metadata
+/// record. Sessions repeat the record on shutdown so it stays in this window.
+const TAIL_SCAN_BYTES: u64 = 256 * 1024;
+
+/// Cheap pre-filter so only candidate lines are parsed.
+const METADATA_MARKER: &str = "\"session_metadata\"";
+
+/// Latest metadata record in the whole rollout at `path`.
+pub fn read_session_metadata(path: &Path) -> io::Result<SessionMetadataItem> {
+    let reader = BufReader::new(fs::File::open(path)?);
+    let mut latest = None;
+    for line in reader.lines() {
+        if let Some(metadata) = parse_metadata_line(&line?) {
+            latest = Some(metadata);
+        }
+    }
+    Ok(latest.unwrap_or_default())
+}
+
+/// Latest metadata record within the last [`TAIL_SCAN_BYTES`] of `path`.
+pub(crate) async fn read_tail_session_metadata(
+    path: &Path,
+) -> io::Result<Option<SessionMetadataItem>> {
+    use tokio::io::AsyncReadExt;
+    use tokio::io::AsyncSeekExt;
+
+    let mut file = tokio::fs::File::open(path).await?;
+    let len = file.metadata().await?.len();
+    let start = len.saturating_sub(TAIL_SCAN_BYTES);
+    file.seek(SeekFrom::Start(start)).await?;
+    let mut tail = Vec::new();
+    file.read_to_end(&mut tail).await?;
+    let tail = String::from_utf8_lossy(&tail);
+
+    let mut lines = tail.lines();
+    if start > 0 {
+        // The first line is most likely cut in half.
+        lines.next();
+    }
+    Ok(lines.rev().find_map(parse_metadata_line))
+}
+
+/// Applies `update` to the latest metadata of the rollout at `path` and
+/// appends the result as a new record.
+pub fn update_session_metadata(
+    path: &Path,
+    update: &SessionMetadataUpdate,
+) -> io::Result<SessionMetadataItem> {
+    let mut metadata = read_session_metadata(path)?;
+    metadata.apply(update);
+
+    let timestamp_format: &[FormatItem] =
+        format_description!("[year]-[month]-[day]T[hour]:[minute]:[second].[subsecond digits:3]Z");
+    let timestamp = OffsetDateTime::now_utc()
+        .format(timestamp_format)
+        .map_err(|e| io::Error::other(format!("failed to format timestamp: {e}")))?;
+    let mut json = serde_json::to_string(&RolloutLine {
+        timestamp,
+        item: RolloutItem::SessionMetadata(metadata.clone()),
+    })?;
+    json.push('\n');
+
+    // One `write_all` of a short line in append mode does not interleave
+    // with a running session writing to the same file.
+    let mut file = fs::OpenOptions::new().append(true).open(path)?;
+    file.write_all(json.as_bytes())?;
+    Ok(metadata)
+}
+
+fn parse_metadata_line(line: &str) -> Option<SessionMetadataItem> {
+    if !line.contains(METADATA_MARKER) {
+        return None;
+    }
+    match serde_json::from_str::<RolloutLine>(line.trim()).ok()?.item {
+        RolloutItem::SessionMetadata(metadata) => Some(metadata),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use tempfile::TempDir;
+
+    #[tokio::test]
+    async fn update_appends_record_that_tail_scan_finds() -> io::Result<()> {
+        let dir = TempDir::new()?;
+        let path = dir.path().join("rollout.jsonl");
+        let filler = serde_json::json!({
+            "timestamp": "2025-01-01T00:00:00.000Z",
+            "type": "event_msg",
+            "payload": {"type": "agent_message", "message": "x".repeat(1024)}
+        })
+        .to_string();
+        fs::write(&path, format!("{filler}\n"))?;
+
+        update_session_metadata(
+            &path,
+            &SessionMetadataUpdate {
+                name: Some("deploy fix".to_string()),
+                add_tags: vec!["prod".to_string()],
+                ..Default::default()
+            },
+        )?;
+        let metadata = update_session_metadata(
+            &path,
+            &SessionMetadataUpdate {
+                add_tags: vec!["#Hotfix".to_string()],
+                ..Default::default()
+            },
+        )?;
+
+        let expected = SessionMetadataItem {
+            name: Some("deploy fix".to_string()),
+            tags: vec!["prod".to_string(), "hotfix".to_string()],
+        };
+        assert_eq!(metadata, expected);
+        assert_eq!(read_session_metadata(&path)?, expected);
+        assert_eq!(read_tail_session_metadata(&path).await?, Some(expected));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn tail_scan_ignores_records_before_the_window() -> io::Result<()> {
+        let dir = TempDir::new()?;
+        let path = dir.path().join("rollout.jsonl");
+        fs::write(&path, "")?;
+        update_session_metadata(
+            &path,
+            &SessionMetadataUpdate {
+                name: Some("old".to_string()),
+                ..Default::default()
+            },
+        )?;
+        let mut file = fs::OpenOptions::new().append(true).open(&path)?;
+        let padding = format!("{}\n", " ".repeat(1024));
+        for _ in 0..=TAIL_SCAN_BYTES / 1024 {
+            file.write_all(padding.as_bytes())?;
+        }
+
+        assert_eq!(read_tail_session_metadata(&path).await?, None);
+        assert_eq!(read_session_metadata(&path)?.name.as_deref(), Some("old"));
+        Ok(())
+    }
+}
@@ -8,9 +8,15 @@ pub const INTERACTIVE_SESSION_SOURCES: &[SessionSource] =
     &[SessionSource::Cli, SessionSource::VSCode];
 
 pub(crate) mod error;
+pub mod export;
+pub mod fork;
 pub mod list;
+pub mod metadata;
 pub(crate) mod policy;
+pub mod prune;
 pub mod recorder;
+pub mod search;
+pub mod starred;
 
 pub use aish_protocol::protocol::SessionMeta;
 pub(crate) use error::map_session_init_error;
@@ -0,0 +1,418 @@
+//! Retention pruning for `~/.aish/sessions`, driven by the `[sessions]`
+//! config table.
+//!
+//! Sessions are ranked by when their rollout file was last written, so a
+//! session resumed yesterday is kept even if it was created months ago.
+//! With `keep_starred`, sessions that were starred or given a name are kept.
+
+use std::collections::HashSet;
+use std::fs;
+use std::io;
+use std::io::ErrorKind;
+use std::path::Path;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use time::OffsetDateTime;
+use uuid::Uuid;
+
+use super::SESSIONS_SUBDIR;
+use super::list::parse_timestamp_uuid_from_filename;
+use super::metadata::read_session_metadata;
+use super::search::collect_rollout_files;
+use super::starred::starred_sessions;
+use crate::config::types::SessionRetention;
+
+/// Rollouts written to within this window may belong to a session that is
+/// still running in another terminal and are never pruned.
+const ACTIVE_SESSION_GRACE: Duration = Duration::from_secs(60 * 60);
+
+const SECONDS_PER_DAY: u64 = 24 * 60 * 60;
+
+/// Which limit caused a session to be pruned.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PruneReason {
+    MaxAge,
+    MaxTotalBytes,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct PrunedSession {
+    pub path: PathBuf,
+    pub id: Option<Uuid>,
+    pub bytes: u64,
+    pub last_modified: OffsetDateTime,
+    pub reason: PruneReason,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PruneReport {
+    /// Sessions that were deleted, or would be with `dry_run`.
+    pub pruned: Vec<PrunedSession>,
+    /// Starred or named sessions that exceeded a limit but were kept.
+    pub starred_or_named_kept: usize,
+    /// Size of the remaining rollout files.
+    pub remaining_bytes: u64,
+}
+
+impl PruneReport {
+    pub fn freed_bytes(&self) -> u64 {
+        self.pruned.iter().map(|session| session.bytes).sum()
+    }
+}
+
+struct RolloutFile {
+    path: PathBuf,
+    id: Option<Uuid>,
+    bytes: u64,
+    last_modified: OffsetDateTime,
+}
+
+/// Applies `retention` to the sessions under `codex_home`. Rollout files in
+/// `protected` (e.g. the one being resumed) are never deleted. With
+/// `dry_run`, nothing is removed and the report lists what would be.
+pub async fn prune_sessions(
+    codex_home: &Path,
+    retention: &SessionRetention,
+    protected: Vec<PathBuf>,
+    dry_run: bool,
+) -> io::Result<PruneReport> {
+    let codex_home = codex_home.to_path_buf();
+    let retention = retention.clone();
+    tokio::task::spawn_blocking(move || {
+        prune_sessions_at(
+            &codex_home,
+            &retention,
+            &protected,
+            dry_run,
+            OffsetDateTime::now_utc(),
+        )
+    })
+    .await
+    .map_err(io::Error::other)?
+}
+
+fn prune_sessions_at(
+    codex_home: &Path,
+    retention: &SessionRetention,
+    protected: &[PathBuf],
+    dry_run: bool,
+    now: OffsetDateTime,
+) -> io::Result<PruneReport> {
+    let sessions_root = codex_home.join(SESSIONS_SUBDIR);
+    if !retention.is_enabled() || !sessions_root.exists() {
+        return Ok(PruneReport::default());
+    }
+
+    let starred: HashSet<Uuid> = if retention.keep_starred {
+        starred_sessions(codex_home)?.into_keys().collect()
+    } else {
+        HashSet::new()
+    };
+    // Names are only read for sessions that exceeded a limit.
+    let is_starred_or_named = |file: &RolloutFile| {
+        retention.keep_starred
+            && (file.id.is_some_and(|id| starred.contains(&id))
+                || read_session_metadata(&file.path).is_ok_and(|metadata| metadata.name.is_some()))
+    };
+    let mut starred_or_named_kept = HashSet::new();
+
+    let mut files = Vec::new();
+    for path in collect_rollout_files(&sessions_root)? {
+        if let Some(file) = read_rollout_file(path)? {
+            files.push(file);
+        }
+    }
+    files.sort_by_key(|file| file.last_modified);
+
+    let mut remaining_bytes: u64 = files.iter().map(|file| file.bytes).sum();
+    let mut report = PruneReport::default();
+    let mut kept = Vec::new();
+
+    let max_age = retention
+        .max_age_days
+        .map(|days| Duration::from_secs(days.saturating_mul(SECONDS_PER_DAY)));
+    for file in files {
+        let age = idle_for(&file, now);
+        let expired = max_age.is_some_and(|max_age| age > max_age);
+        if expired && !protected.contains(&file.path) && age > ACTIVE_SESSION_GRACE {
+            if is_starred_or_named(&file) {
+                starred_or_named_kept.insert(file.path.clone());
+                kept.push(file);
+            } else {
+                remaining_bytes -= file.bytes;
+                report.pruned.push(pruned(file, PruneReason::MaxAge));
+            }
+        } else {
+            kept.push(file);
+        }
+    }
+
+    if let Some(max_total_bytes) = retention.max_total_bytes {
+        for file in kept {
+            if remaining_bytes <= max_total_bytes {
+                break;
+            }
+            if protected.contains(&file.path) || idle_for(&file, now) <= ACTIVE_SESSION_GRACE {
+                continue;
+            }
+            if starred_or_named_kept.contains(&file.path) || is_starred_or_named(&file) {
+                starred_or_named_kept.insert(file.path.clone());
+                continue;
+            }
+            remaining_bytes -= file.bytes;
+            report.pruned.push(pruned(file, PruneReason::MaxTotalBytes));
+        }
+    }
+    report.remaining_bytes = remaining_bytes;
+    report.starred_or_named_kept = starred_or_named_kept.len();
+
+    if !dry_run {
+        for session in &report.pruned {
+            match fs::remove_file(&session.path) {
+                Ok(()) => remove_empty_day_dirs(&sessions_root, &session.path),
+                Err(err) if err.kind() == ErrorKind::NotFound => {}
+                Err(err) => return Err(err),
+            }
+        }
+    }
+    Ok(report)
+}
+
+fn read_rollout_file(path: PathBuf) -> io::Result<Option<RolloutFile>> {
+    let metadata = match fs::metadata(&path) {
+        Ok(metadata) => metadata,
+        Err(err) if err.kind() == ErrorKind::NotFound => return Ok(None),
+        Err(err) => return Err(err),
+    };
+    let parsed = path
+        .file_name()
+        .and_then(|name| name.to_str())
+        .and_then(parse_timestamp_uuid_from_filename);
+    let Some(last_modified) = metadata
+        .modified()
+        .ok()
+        .map(OffsetDateTime::from)
+        .or(parsed.map(|(created_at, _)| created_at))
+    else {
+        return Ok(None);
+    };
+    Ok(Some(RolloutFile {
+        path,
+        id: parsed.map(|(_, id)| id),
+        bytes: metadata.len(),
+        last_modified,
+    }))
+}
+
+fn idle_for(file: &RolloutFile, now: OffsetDateTime) -> Duration {
+    (now - file.last_modified).try_into().unwrap_or_default()
+}
+
+fn pruned(file: RolloutFile, reason: PruneReason) -> PrunedSession {
+    PrunedSession {
+        path: file.path,
+        id: file.id,
+        bytes: file.bytes,
+        last_modified: file.last_modified,
+        reason,
+    }
+}
+
+/// Removes the `YYYY/MM/DD` directories left empty by a deletion.
+fn remove_empty_day_dirs(sessions_root: &Path, removed: &Path) {
+    let mut dir = removed.parent();
+    while let Some(current) = dir {
+        if current == sessions_root || !current.starts_with(sessions_root) {
+            break;
+        }
+        // Fails (and stops) as soon as a directory is not empty.
+        if fs::remove_dir(current).is_err() {
+            break;
+        }
+        dir = current.parent();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rollout::metadata::update_session_metadata;
+    use crate::rollout::starred::star_session;
+    use aish_protocol::protocol::SessionMetadataUpdate;
+    use pretty_assertions::assert_eq;
+    use std::time::SystemTime;
+    use tempfile::TempDir;
+
+    const DAY: Duration = Duration::from_secs(SECONDS_PER_DAY);
+
+    fn write_rollout(home: &Path, day: &str, id: u128, bytes: usize, age: Duration) -> PathBuf {
+        let dir = home.join(SESSIONS_SUBDIR).join(day);
+        fs::create_dir_all(&dir).expect("create day dir");
+        let path = dir.join(format!(
+            "rollout-{}T12-00-00-{}.jsonl",
+            day.replace('/', "-"),
+            Uuid::from_u128(id)
+        ));
+        fs::write(&path, "x".repeat(bytes)).expect("write rollout");
+        set_age(&path, age);
+        path
+    }
+
+    fn set_age(path: &Path, age: Duration) {
+        let file = fs::File::options()
+            .write(true)
+            .open(path)
+            .expect("open rollout");
+        file.set_modified(SystemTime::now() - age)
+            .expect("set mtime");
+    }
+
+    fn retention(max_age_days: Option<u64>, max_total_bytes: Option<u64>) -> SessionRetention {
+        SessionRetention {
+            max_age_days,
+            max_total_bytes,
+            keep_starred: true,
+        }
+    }
+
+    #[test]
+    fn prunes_by_age_but_keeps_starred_and_protected() -> io::Result<()> {
+        let home = TempDir::new()?;
+        let old = write_rollout(home.path(), "2025/01/01", 1, 10, 40 * DAY);
+        let starred = write_rollout(home.path(), "2025/01/02", 2, 10, 40 * DAY);
+        let resumed = write_rollout(home.path(), "2025/01/03", 3, 10, 40 * DAY);
+        let recent = write_rollout(home.path(), "2025/02/01", 4, 10, DAY);
+        star_session(home.path(), Uuid::from_u128(2))?;
+
+        let report = prune_sessions_at(
+            home.path(),
+            &retention(Some(30), None),
+            std::slice::from_ref(&resumed),
+            false,
+            OffsetDateTime::now_utc(),
+        )?;
+
+        assert_eq!(
+            report
+                .pruned
+                .iter()
+                .map(|session| (session.path.clone(), session.reason))
+                .collect::<Vec<_>>(),
+            vec![(old.clone(), PruneReason::MaxAge)]
+        );
+        assert_eq!(report.starred_or_named_kept, 1);
+        assert_eq!(report.remaining_bytes, 30);
+        assert!(!old.exists());
+        assert!(
+            !home
+                .path()
+                .join(SESSIONS_SUBDIR)
+                .join("2025/01/01")
+                .exists()
+        );
+        assert!(starred.exists() && resumed.exists() && recent.exists());
+        Ok(())
+    }
+
+    #[test]
+    fn prunes_least_recently_used_until_under_size_limit() -> io::Result<()> {
+        let home = TempDir::new()?;
+        let oldest = write_rollout(home.path(), "2025/01/01", 1, 100, 3 * DAY);
+        let middle = write_rollout(home.path(), "2025/01/02", 2, 100, 2 * DAY);
+        let newest = write_rollout(home.path(), "2025/01/03", 3, 100, DAY);
+
+        let report = prune_sessions_at(
+            home.path(),
+            &retention(None, Some(150)),
+            &[],
+            false,
+            OffsetDateTime::now_utc(),
+        )?;
+
+        assert_eq!(
+            report
+                .pruned
+                .iter()
+                .map(|session| session.path.clone())
+                .collect::<Vec<_>>(),
+            vec![oldest.clone(), middle.clone()]
+        );
+        assert_eq!(report.freed_bytes(), 200);
+        assert_eq!(report.remaining_bytes, 100);
+        assert!(!oldest.exists() && !middle.exists() && newest.exists());
+        Ok(())
+    }
+
+    #[test]
+    fn keeps_named_sessions_unless_keep_starred_is_off() -> io::Result<()> {
+        let home = TempDir::new()?;
+        let unnamed = write_rollout(home.path(), "2025/01/01", 1, 100, 40 * DAY);
+        let named = write_rollout(home.path(), "2025/01/02", 2, 100, 40 * DAY);
+        update_session_metadata(
+            &named,
+            &SessionMetadataUpdate {
+                name: Some("release notes".to_string()),
+                ..Default::default()
+            },
+        )?;
+        set_age(&named, 40 * DAY);
+
+        let report = prune_sessions_at(
+            home.path(),
+            &retention(Some(30), Some(0)),
+            &[],
+            true,
+            OffsetDateTime::now_utc(),
+        )?;
+        assert_eq!(
+            report
+                .pruned
+                .iter()
+                .map(|session| session.path.clone())
+                .collect::<Vec<_>>(),
+            vec![unnamed.clone()]
+        );
+        assert_eq!(report.starred_or_named_kept, 1);
+
+        let report = prune_sessions_at(
+            home.path(),
+            &SessionRetention {
+                keep_starred: false,
+                ..retention(Some(30), None)
+            },
+            &[],
+            true,
+            OffsetDateTime::now_utc(),
+        )?;
+        assert_eq!(
+            report
+                .pruned
+                .iter()
+                .map(|session| session.path.clone())
+                .collect::<Vec<_>>(),
+            vec![unnamed, named]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn dry_run_and_active_sessions_delete_nothing() -> io::Result<()> {
+        let home = TempDir::new()?;
+        let old = write_rollout(home.path(), "2025/01/01", 1, 100, 40 * DAY);
+        let active = write_rollout(home.path(), "2025/01/02", 2, 100, Duration::from_secs(60));
+
+        let report = prune_sessions_at(
+            home.path(),
+            &retention(Some(30), Some(0)),
+            &[],
+            true,
+            OffsetDateTime::now_utc(),
+        )?;
+
+        assert_eq!(report.pruned.len(), 1);
+        assert_eq!(report.pruned[0].path, old);
+        assert!(old.exists() && active.exists());
+        Ok(())
+    }
+}
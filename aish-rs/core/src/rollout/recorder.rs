@@ -148,6 +148,7 @@ impl RolloutRecorder {
                         instructions,
                         source,
                         model_provider: Some(config.model_provider_id.clone()),
+                        parent_id: None,
                     }),
                 )
             }
@@ -251,6 +252,9 @@ impl RolloutRecorder {
                     RolloutItem::EventMsg(_ev) => {
                         items.push(RolloutItem::EventMsg(_ev));
                     }
+                    RolloutItem::SessionMetadata(item) => {
+                        items.push(RolloutItem::SessionMetadata(item));
+                    }
                 },
                 Err(e) => {
                     warn!("failed to parse rollout line: {v:?}, error: {e}");
@@ -312,10 +316,30 @@ fn create_log_file(
     config: &Config,
     conversation_id: ConversationId,
 ) -> std::io::Result<LogFileInfo> {
-    // Resolve ~/.aish/sessions/YYYY/MM/DD and create it if missing.
     let timestamp = OffsetDateTime::now_local()
         .map_err(|e| IoError::other(format!("failed to get local time: {e}")))?;
-    let mut dir = config.codex_home.clone();
+    let path = new_rollout_path(&config.codex_home, conversation_id, timestamp)?;
+    let file = std::fs::OpenOptions::new()
+        .append(true)
+        .create(true)
+        .open(&path)?;
+
+    Ok(LogFileInfo {
+        file,
+        path,
+        conversation_id,
+        timestamp,
+    })
+}
+
+/// Path for a new rollout of `conversation_id` created at `timestamp`, under
+/// `~/.aish/sessions/YYYY/MM/DD`. Creates the directory if missing.
+pub(super) fn new_rollout_path(
+    codex_home: &Path,
+    conversation_id: ConversationId,
+    timestamp: OffsetDateTime,
+) -> std::io::Result<PathBuf> {
+    let mut dir = codex_home.to_path_buf();
     dir.push(SESSIONS_SUBDIR);
     dir.push(timestamp.year().to_string());
     dir.push(format!("{:02}", u8::from(timestamp.month())));
@@ -331,19 +355,7 @@ fn create_log_file(
         .map_err(|e| IoError::other(format!("failed to format timestamp: {e}")))?;
 
     let filename = format!("rollout-{date_str}-{conversation_id}.jsonl");
-
-    let path = dir.join(filename);
-    let file = std::fs::OpenOptions::new()
-        .append(true)
-        .create(true)
-        .open(&path)?;
-
-    Ok(LogFileInfo {
-        file,
-        path,
-        conversation_id,
-        timestamp,
-    })
+    Ok(dir.join(filename))
 }
 
 async fn rollout_writer(
@@ -0,0 +1,700 @@
+//! Full-text search across recorded sessions.
+//!
+//! Every user message, agent message and executed command of each rollout
+//! file is kept in `~/.aish/sessions/search_index.json`. Rollout files are
+//! append-only, so the index remembers how many bytes of each file it has
+//! already read and only parses what was appended since the last refresh.
+
+use std::cmp::Reverse;
+use std::collections::BTreeMap;
+use std::collections::HashSet;
+use std::fs;
+use std::io;
+use std::io::ErrorKind;
+use std::io::Read;
+use std::io::Seek;
+use std::io::SeekFrom;
+use std::path::Path;
+use std::path::PathBuf;
+
+use aish_protocol::models::LocalShellAction;
+use aish_protocol::models::ResponseItem;
+use aish_protocol::protocol::RolloutItem;
+use aish_protocol::protocol::RolloutLine;
+use aish_protocol::protocol::SessionMetadataItem;
+use aish_protocol::protocol::SessionSource;
+use aish_protocol::protocol::normalize_session_tag;
+use serde::Deserialize;
+use serde::Serialize;
+use time::OffsetDateTime;
+use time::format_description::well_known::Rfc3339;
+use uuid::Uuid;
+
+use super::SESSIONS_SUBDIR;
+use super::export::SHELL_TOOL_NAMES;
+use super::export::command_text_from_arguments;
+use super::list::ProviderMatcher;
+use super::list::parse_timestamp_uuid_from_filename;
+use crate::protocol::EventMsg;
+
+const INDEX_FILENAME: &str = "search_index.json";
+/// Bump when the indexed content changes so stale indexes are rebuilt.
+const INDEX_VERSION: u32 = 2;
+const MAX_MATCHES_PER_SESSION: usize = 3;
+const SNIPPET_CONTEXT_BEFORE: usize = 30;
+const SNIPPET_CONTEXT_AFTER: usize = 70;
+
+/// What part of a session a search match came from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SearchMatchKind {
+    UserMessage,
+    AgentMessage,
+    Command,
+}
+
+impl SearchMatchKind {
+    pub fn label(self) -> &'static str {
+        match self {
+            SearchMatchKind::UserMessage => "user",
+            SearchMatchKind::AgentMessage => "agent",
+            SearchMatchKind::Command => "command",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SessionSearchMatch {
+    pub kind: SearchMatchKind,
+    /// Single-line excerpt around the first matching term.
+    pub snippet: String,
+}
+
+/// A session whose messages or commands contain every term of the query.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SessionSearchHit {
+    /// Absolute path to the rollout file.
+    pub path: PathBuf,
+    pub id: Uuid,
+    /// RFC3339 timestamp of the session meta line, if recorded.
+    pub created_at: Option<String>,
+    /// RFC3339 timestamp of the rollout file's last modification.
+    pub updated_at: Option<String>,
+    pub cwd: Option<PathBuf>,
+    /// First user message of the session.
+    pub preview: Option<String>,
+    /// Name given with `/rename` or `aish sessions tag --name`.
+    pub name: Option<String>,
+    pub tags: Vec<String>,
+    pub matches: Vec<SessionSearchMatch>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct IndexFile {
+    version: u32,
+    /// Keyed by rollout path relative to the sessions directory.
+    sessions: BTreeMap<String, IndexedSession>,
+}
+
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+struct IndexedSession {
+    /// Bytes of the rollout file consumed so far; always ends on a newline.
+    indexed_bytes: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    updated_at: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    created_at: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    source: Option<SessionSource>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    model_provider: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    cwd: Option<PathBuf>,
+    #[serde(default)]
+    entries: Vec<IndexedText>,
+    #[serde(default)]
+    metadata: SessionMetadataItem,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct IndexedText {
+    kind: SearchMatchKind,
+    text: String,
+}
+
+/// On-disk search index over `~/.aish/sessions`.
+#[derive(Debug)]
+pub struct SessionIndex {
+    sessions_root: PathBuf,
+    data: IndexFile,
+}
+
+impl SessionIndex {
+    /// Loads the index for `codex_home`. A missing, unreadable or outdated
+    /// index file yields an empty index that the next [`refresh`] rebuilds.
+    ///
+    /// [`refresh`]: SessionIndex::refresh
+    pub fn load(codex_home: &Path) -> Self {
+        let sessions_root = codex_home.join(SESSIONS_SUBDIR);
+        let data = fs::read_to_string(sessions_root.join(INDEX_FILENAME))
+            .ok()
+            .and_then(|contents| serde_json::from_str::<IndexFile>(&contents).ok())
+            .filter(|data| data.version == INDEX_VERSION)
+            .unwrap_or_else(|| IndexFile {
+                version: INDEX_VERSION,
+                sessions: BTreeMap::new(),
+            });
+        Self {
+            sessions_root,
+            data,
+        }
+    }
+
+    /// Indexes whatever was appended to rollout files since the last refresh,
+    /// drops deleted sessions and saves the index if anything changed.
+    pub fn refresh(&mut self) -> io::Result<()> {
+        if !self.sessions_root.exists() {
+            return Ok(());
+        }
+
+        let mut changed = false;
+        let mut present = HashSet::new();
+        for path in collect_rollout_files(&self.sessions_root)? {
+            let Some(key) = self.relative_key(&path) else {
+                continue;
+            };
+            let metadata = match fs::metadata(&path) {
+                Ok(metadata) => metadata,
+                Err(err) if err.kind() == ErrorKind::NotFound => continue,
+                Err(err) => return Err(err),
+            };
+            present.insert(key.clone());
+
+            let len = metadata.len();
+            let session = self.data.sessions.entry(key).or_default();
+            if len < session.indexed_bytes {
+                // The file was rewritten; start over.
+                *session = IndexedSession::default();
+            }
+            if len == session.indexed_bytes {
+                continue;
+            }
+            session.updated_at = metadata
+                .modified()
+                .ok()
+                .and_then(|modified| OffsetDateTime::from(modified).format(&Rfc3339).ok());
+            let before = session.indexed_bytes;
+            index_appended_lines(&path, session)?;
+            changed |= session.indexed_bytes != before;
+        }
+
+        let before = self.data.sessions.len();
+        self.data.sessions.retain(|key, _| present.contains(key));
+        changed |= self.data.sessions.len() != before;
+
+        if changed {
+            self.save()?;
+        }
+        Ok(())
+    }
+
+    /// Returns sessions that contain every whitespace-separated term of
+    /// `query` (case-insensitive) and carry every `#tag` term, newest first. Source and provider filters
+    /// behave like [`crate::RolloutRecorder::list_conversations`].
+    pub fn search(
+        &self,
+        query: &str,
+        limit: usize,
+        allowed_sources: &[SessionSource],
+        model_providers: Option<&[String]>,
+        default_provider: &str,
+    ) -> Vec<SessionSearchHit> {
+        let (tag_terms, terms): (Vec<&str>, Vec<&str>) = query
+            .split_whitespace()
+            .partition(|term| term.starts_with('#'));
+        let tags: Vec<String> = tag_terms
+            .into_iter()
+            .filter_map(normalize_session_tag)
+            .collect();
+        let terms: Vec<String> = terms.into_iter().map(str::to_lowercase).collect();
+        if (terms.is_empty() && tags.is_empty()) || limit == 0 {
+            return Vec::new();
+        }
+        let provider_matcher =
+            model_providers.and_then(|filters| ProviderMatcher::new(filters, default_provider));
+
+        let mut hits: Vec<(OffsetDateTime, SessionSearchHit)> = Vec::new();
+        for (key, session) in &self.data.sessions {
+            if !allowed_sources.is_empty()
+                && !session
+                    .source
+                    .as_ref()
+                    .is_some_and(|source| allowed_sources.contains(source))
+            {
+                continue;
+            }
+            if let Some(matcher) = provider_matcher.as_ref()
+                && !matcher.matches(session.model_provider.as_deref())
+            {
+                continue;
+            }
+            let path = self.sessions_root.join(key);
+            let Some((ts, id)) = path
+                .file_name()
+                .and_then(|name| name.to_str())
+                .and_then(parse_timestamp_uuid_from_filename)
+            else {
+                continue;
+            };
+            if !tags.iter().all(|tag| session.metadata.tags.contains(tag)) {
+                continue;
+            }
+            if let Some(matches) = match_session(session, &terms) {
+                hits.push((
+                    ts,
+                    SessionSearchHit {
+                        path,
+                        id,
+                        created_at: session.created_at.clone(),
+                        updated_at: session.updated_at.clone(),
+                        cwd: session.cwd.clone(),
+                        preview: session
+                            .entries
+                            .iter()
+                            .find(|entry| entry.kind == SearchMatchKind::UserMessage)
+                            .map(|entry| collapse_whitespace(&entry.text)),
+                        name: session.metadata.name.clone(),
+                        tags: session.metadata.tags.clone(),
+                        matches,
+                    },
+                ));
+            }
+        }
+
+        hits.sort_by_key(|(ts, hit)| (Reverse(*ts), Reverse(hit.id)));
+        hits.into_iter().take(limit).map(|(_, hit)| hit).collect()
+    }
+
+    fn relative_key(&self, path: &Path) -> Option<String> {
+        let relative = path.strip_prefix(&self.sessions_root).ok()?;
+        Some(
+            relative
+                .components()
+                .map(|component| component.as_os_str().to_string_lossy())
+                .collect::<Vec<_>>()
+                .join("/"),
+        )
+    }
+
+    fn save(&self) -> io::Result<()> {
+        let path = self.sessions_root.join(INDEX_FILENAME);
+        let tmp = self.sessions_root.join(format!("{INDEX_FILENAME}.tmp"));
+        fs::write(&tmp, serde_json::to_vec(&self.data)?)?;
+        fs::rename(tmp, path)
+    }
+}
+
+/// Refreshes the index under `codex_home` and searches it. See
+/// [`SessionIndex::search`] for the matching rules.
+pub async fn search_sessions(
+    codex_home: &Path,
+    query: &str,
+    limit: usize,
+    allowed_sources: &[SessionSource],
+    model_providers: Option<&[String]>,
+    default_provider: &str,
+) -> io::Result<Vec<SessionSearchHit>> {
+    let codex_home = codex_home.to_path_buf();
+    let query = query.to_string();
+    let allowed_sources = allowed_sources.to_vec();
+    let model_providers = model_providers.map(<[String]>::to_vec);
+    let default_provider = default_provider.to_string();
+    tokio::task::spawn_blocking(move || {
+        let mut index = SessionIndex::load(&codex_home);
+        index.refresh()?;
+        Ok(index.search(
+            &query,
+            limit,
+            &allowed_sources,
+            model_providers.as_deref(),
+            &default_provider,
+        ))
+    })
+    .await
+    .map_err(io::Error::other)?
+}
+
+/// Rollout files under `root/YYYY/MM/DD/`.
+pub(super) fn collect_rollout_files(root: &Path) -> io::Result<Vec<PathBuf>> {
+    fn numeric_dirs(parent: &Path) -> io::Result<Vec<PathBuf>> {
+        let mut dirs = Vec::new();
+        for entry in fs::read_dir(parent)? {
+            let entry = entry?;
+            if entry.file_type()?.is_dir()
+                && entry
+                    .file_name()
+                    .to_str()
+                    .is_some_and(|name| name.parse::<u16>().is_ok())
+            {
+                dirs.push(entry.path());
+            }
+        }
+        Ok(dirs)
+    }
+
+    let mut files = Vec::new();
+    for year in numeric_dirs(root)? {
+        for month in numeric_dirs(&year)? {
+            for day in numeric_dirs(&month)? {
+                for entry in fs::read_dir(&day)? {
+                    let entry = entry?;
+                    let is_rollout = entry.file_name().to_str().is_some_and(|name| {
+                        name.starts_with("rollout-") && name.ends_with(".jsonl")
+                    });
+                    if is_rollout && entry.file_type()?.is_file() {
+                        files.push(entry.path());
+                    }
+                }
+            }
+        }
+    }
+    Ok(files)
+}
+
+/// Parses the complete lines appended to `path` after
+/// `session.indexed_bytes`. A trailing partial line is left for the next
+/// refresh.
+fn index_appended_lines(path: &Path, session: &mut IndexedSession) -> io::Result<()> {
+    let mut file = fs::File::open(path)?;
+    file.seek(SeekFrom::Start(session.indexed_bytes))?;
+    let mut appended = Vec::new();
+    file.read_to_end(&mut appended)?;
+    let Some(last_newline) = appended.iter().rposition(|byte| *byte == b'\n') else {
+        return Ok(());
+    };
+    let complete = &appended[..=last_newline];
+
+    for line in String::from_utf8_lossy(complete).lines() {
+        let trimmed = line.trim();
+        if trimmed.is_empty() {
+            continue;
+        }
+        let Ok(rollout_line) = serde_json::from_str::<RolloutLine>(trimmed) else {
+            continue;
+        };
+        index_rollout_item(session, rollout_line);
+    }
+    session.indexed_bytes += complete.len() as u64;
+    Ok(())
+}
+
+fn index_rollout_item(session: &mut IndexedSession, line: RolloutLine) {
+    let (kind, text) = match line.item {
+        RolloutItem::SessionMeta(meta_line) => {
+            session.created_at.get_or_insert(line.timestamp);
+            session.source = Some(meta_line.meta.source);
+            session.model_provider = meta_line.meta.model_provider;
+            session.cwd = Some(meta_line.meta.cwd);
+            return;
+        }
+        RolloutItem::SessionMetadata(metadata) => {
+            session.metadata = metadata;
+            return;
+        }
+        RolloutItem::EventMsg(EventMsg::UserMessage(ev)) => {
+            (SearchMatchKind::UserMessage, ev.message)
+        }
+        RolloutItem::EventMsg(EventMsg::AgentMessage(ev)) => {
+            (SearchMatchKind::AgentMessage, ev.message)
+        }
+        RolloutItem::ResponseItem(ResponseItem::LocalShellCall {
+            action: LocalShellAction::Exec(exec),
+            ..
+        }) => (SearchMatchKind::Command, command_text(&exec.command)),
+        RolloutItem::ResponseItem(ResponseItem::FunctionCall {
+            name, arguments, ..
+        }) if SHELL_TOOL_NAMES.contains(&name.as_str()) => {
+            let Some(command) = serde_json::from_str::<serde_json::Value>(&arguments)
+                .ok()
+                .and_then(|args| command_text_from_arguments(&args))
+            else {
+                return;
+            };
+            (SearchMatchKind::Command, command)
+        }
+        _ => return,
+    };
+    if !text.trim().is_empty() {
+        session.entries.push(IndexedText { kind, text });
+    }
+}
+
+/// Returns up to [`MAX_MATCHES_PER_SESSION`] excerpts when every term occurs
+/// somewhere in the session or its name, `None` otherwise.
+fn match_session(session: &IndexedSession, terms: &[String]) -> Option<Vec<SessionSearchMatch>> {
+    let lowered: Vec<String> = session
+        .entries
+        .iter()
+        .map(|entry| entry.text.to_lowercase())
+        .collect();
+    let name = session
+        .metadata
+        .name
+        .as_deref()
+        .map(str::to_lowercase)
+        .unwrap_or_default();
+    let all_terms_present = terms.iter().all(|term| {
+        name.contains(term.as_str()) || lowered.iter().any(|text| text.contains(term.as_str()))
+    });
+    if !all_terms_present {
+        return None;
+    }
+
+    let matches = session
+        .entries
+        .iter()
+        .zip(&lowered)
+        .filter_map(|(entry, text)| {
+            let term = terms.iter().find(|term| text.contains(term.as_str()))?;
+            Some(SessionSearchMatch {
+                kind: entry.kind,
+                snippet: snippet_around(&entry.text, term),
+            })
+        })
+        .take(MAX_MATCHES_PER_SESSION)
+        .collect();
+    Some(matches)
+}
+
+fn collapse_whitespace(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Single-line excerpt of `text` centred on the first case-insensitive
+/// occurrence of `term` (already lowercased).
+fn snippet_around(text: &str, term: &str) -> String {
+    let chars: Vec<char> = collapse_whitespace(text).chars().collect();
+    let lowered: Vec<char> = chars
+        .iter()
+        .map(|c| c.to_lowercase().next().unwrap_or(*c))
+        .collect();
+    let needle: Vec<char> = term.chars().collect();
+    let start_of_match = lowered
+        .windows(needle.len().max(1))
+        .position(|window| window == needle.as_slice())
+        .unwrap_or(0);
+
+    let start = start_of_match.saturating_sub(SNIPPET_CONTEXT_BEFORE);
+    let end = (start_of_match + needle.len() + SNIPPET_CONTEXT_AFTER).min(chars.len());
+    let mut snippet = String::new();
+    if start > 0 {
+        snippet.push('…');
+    }
+    snippet.extend(&chars[start..end]);
+    if end < chars.len() {
+        snippet.push('…');
+    }
+    snippet
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use std::io::Write;
+    use tempfile::TempDir;
+
+    const ROLLOUT_NAME: &str =
+        "rollout-2025-01-03T12-00-00-00000000-0000-0000-0000-000000000003.jsonl";
+
+    fn write_lines(path: &Path, lines: &[serde_json::Value]) {
+        let mut file = fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .expect("open rollout");
+        for line in lines {
+            writeln!(file, "{line}").expect("write line");
+        }
+    }
+
+    fn session_meta() -> serde_json::Value {
+        serde_json::json!({
+            "timestamp": "2025-01-03T12:00:00Z",
+            "type": "session_meta",
+            "payload": {
+                "id": "00000000-0000-0000-0000-000000000003",
+                "timestamp": "2025-01-03T12:00:00Z",
+                "cwd": "/work/repo",
+                "originator": "test",
+                "cli_version": "0.0.0",
+                "instructions": null,
+                "source": "cli",
+                "model_provider": "openai"
+            }
+        })
+    }
+
+    fn event(kind: &str, message: &str) -> serde_json::Value {
+        serde_json::json!({
+            "timestamp": "2025-01-03T12:00:01Z",
+            "type": "event_msg",
+            "payload": {"type": kind, "message": message}
+        })
+    }
+
+    fn shell_call(command: &[&str]) -> serde_json::Value {
+        serde_json::json!({
+            "timestamp": "2025-01-03T12:00:02Z",
+            "type": "response_item",
+            "payload": {
+                "type": "function_call",
+                "name": "shell",
+                "arguments": serde_json::json!({"command": command}).to_string(),
+                "call_id": "call-1"
+            }
+        })
+    }
+
+    fn rollout_path(home: &Path) -> PathBuf {
+        let dir = home.join(SESSIONS_SUBDIR).join("2025/01/03");
+        fs::create_dir_all(&dir).expect("create day dir");
+        dir.join(ROLLOUT_NAME)
+    }
+
+    #[test]
+    fn search_matches_messages_and_commands_beyond_the_first_message() {
+        let home = TempDir::new().expect("tempdir");
+        let path = rollout_path(home.path());
+        write_lines(
+            &path,
+            &[
+                session_meta(),
+                event("user_message", "set up the project"),
+                event("agent_message", "Installing the toolchain now."),
+                shell_call(&["cargo", "install", "ripgrep"]),
+            ],
+        );
+
+        let mut index = SessionIndex::load(home.path());
+        index.refresh().expect("refresh");
+        let hits = index.search("RIPGREP toolchain", 10, &[], None, "openai");
+
+        assert_eq!(hits.len(), 1);
+        let hit = &hits[0];
+        assert_eq!(hit.path, path);
+        assert_eq!(hit.preview.as_deref(), Some("set up the project"));
+        assert_eq!(hit.cwd, Some(PathBuf::from("/work/repo")));
+        assert_eq!(
+            hit.matches,
+            vec![
+                SessionSearchMatch {
+                    kind: SearchMatchKind::AgentMessage,
+                    snippet: "Installing the toolchain now.".to_string(),
+                },
+                SessionSearchMatch {
+                    kind: SearchMatchKind::Command,
+                    snippet: "cargo install ripgrep".to_string(),
+                },
+            ]
+        );
+        assert!(
+            index
+                .search("ripgrep missing", 10, &[], None, "openai")
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn search_matches_session_name_and_tag_terms() {
+        let home = TempDir::new().expect("tempdir");
+        let path = rollout_path(home.path());
+        write_lines(
+            &path,
+            &[
+                session_meta(),
+                event("user_message", "why is the build red"),
+                serde_json::json!({
+                    "timestamp": "2025-01-03T12:00:03Z",
+                    "type": "session_metadata",
+                    "payload": {"name": "Nightly triage", "tags": ["ci"]}
+                }),
+            ],
+        );
+
+        let mut index = SessionIndex::load(home.path());
+        index.refresh().expect("refresh");
+
+        let hits = index.search("#CI nightly", 10, &[], None, "openai");
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].name.as_deref(), Some("Nightly triage"));
+        assert_eq!(hits[0].tags, vec!["ci".to_string()]);
+        assert_eq!(hits[0].matches, Vec::new());
+        assert_eq!(index.search("#ci", 10, &[], None, "openai").len(), 1);
+        assert!(index.search("#infra", 10, &[], None, "openai").is_empty());
+    }
+
+    #[test]
+    fn refresh_only_reads_appended_lines_and_persists() {
+        let home = TempDir::new().expect("tempdir");
+        let path = rollout_path(home.path());
+        write_lines(&path, &[session_meta(), event("user_message", "first")]);
+
+        let mut index = SessionIndex::load(home.path());
+        index.refresh().expect("refresh");
+        let indexed_bytes = fs::metadata(&path).expect("metadata").len();
+
+        write_lines(&path, &[event("user_message", "second question")]);
+        // A partial line must not be indexed until it is complete.
+        fs::OpenOptions::new()
+            .append(true)
+            .open(&path)
+            .and_then(|mut file| file.write_all(b"{\"timestamp\""))
+            .expect("append partial");
+
+        let mut reloaded = SessionIndex::load(home.path());
+        let session = reloaded.data.sessions.values().next().expect("session");
+        assert_eq!(session.indexed_bytes, indexed_bytes);
+
+        reloaded.refresh().expect("refresh");
+        let hits = reloaded.search("second", 10, &[], None, "openai");
+        assert_eq!(hits.len(), 1);
+        let session = reloaded.data.sessions.values().next().expect("session");
+        assert_eq!(session.entries.len(), 2);
+    }
+
+    #[test]
+    fn refresh_drops_deleted_sessions_and_filters_by_source() {
+        let home = TempDir::new().expect("tempdir");
+        let path = rollout_path(home.path());
+        write_lines(&path, &[session_meta(), event("user_message", "needle")]);
+
+        let mut index = SessionIndex::load(home.path());
+        index.refresh().expect("refresh");
+        assert_eq!(
+            index
+                .search("needle", 10, &[SessionSource::Exec], None, "openai")
+                .len(),
+            0
+        );
+        assert_eq!(
+            index
+                .search("needle", 10, &[SessionSource::Cli], None, "openai")
+                .len(),
+            1
+        );
+
+        fs::remove_file(&path).expect("remove rollout");
+        index.refresh().expect("refresh");
+        assert!(index.search("needle", 10, &[], None, "openai").is_empty());
+    }
+
+    #[test]
+    fn snippet_is_centred_on_the_match() {
+        let text = format!("{} needle {}", "a".repeat(50), "b".repeat(100));
+        let snippet = snippet_around(&text, "needle");
+        assert!(snippet.starts_with('…'));
+        assert!(snippet.ends_with('…'));
+        assert!(snippet.contains("needle"));
+    }
+}
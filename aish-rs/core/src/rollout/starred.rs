@@ -0,0 +1,127 @@
+//! Starred sessions, kept in `~/.aish/sessions/starred.json`.
+//!
+//! Starring a session protects its rollout file from retention pruning (see
+//! [`super::prune`]). The list lives next to the rollouts rather than inside
+//! them so that starring an old session does not rewrite its file. A
+//! session's name lives in its rollout (see `SessionMetadataUpdate`), not here.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::io::ErrorKind;
+use std::path::Path;
+use std::path::PathBuf;
+
+use serde::Deserialize;
+use serde::Serialize;
+use time::OffsetDateTime;
+use time::format_description::well_known::Rfc3339;
+use uuid::Uuid;
+
+use super::SESSIONS_SUBDIR;
+
+const STARRED_FILENAME: &str = "starred.json";
+
+/// When a session was starred.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StarredSession {
+    pub starred_at: String,
+}
+
+/// Reads the starred sessions. A missing file means nothing is starred.
+pub fn starred_sessions(codex_home: &Path) -> io::Result<BTreeMap<Uuid, StarredSession>> {
+    let path = starred_file_path(codex_home);
+    let contents = match fs::read_to_string(&path) {
+        Ok(contents) => contents,
+        Err(err) if err.kind() == ErrorKind::NotFound => return Ok(BTreeMap::new()),
+        Err(err) => return Err(err),
+    };
+    serde_json::from_str(&contents).map_err(|err| {
+        io::Error::new(
+            ErrorKind::InvalidData,
+            format!("failed to parse {}: {err}", path.display()),
+        )
+    })
+}
+
+/// Stars `id`. Starring an already starred session keeps its original time.
+pub fn star_session(codex_home: &Path, id: Uuid) -> io::Result<()> {
+    let mut starred = starred_sessions(codex_home)?;
+    if starred.contains_key(&id) {
+        return Ok(());
+    }
+    let starred_at = OffsetDateTime::now_utc()
+        .format(&Rfc3339)
+        .map_err(io::Error::other)?;
+    starred.insert(id, StarredSession { starred_at });
+    write_starred(codex_home, &starred)
+}
+
+/// Removes the star from `id`. Returns whether it was starred.
+pub fn unstar_session(codex_home: &Path, id: Uuid) -> io::Result<bool> {
+    let mut starred = starred_sessions(codex_home)?;
+    if starred.remove(&id).is_none() {
+        return Ok(false);
+    }
+    write_starred(codex_home, &starred)?;
+    Ok(true)
+}
+
+fn starred_file_path(codex_home: &Path) -> PathBuf {
+    codex_home.join(SESSIONS_SUBDIR).join(STARRED_FILENAME)
+}
+
+fn write_starred(codex_home: &Path, starred: &BTreeMap<Uuid, StarredSession>) -> io::Result<()> {
+    let path = starred_file_path(codex_home);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let tmp = path.with_extension("json.tmp");
+    fs::write(&tmp, serde_json::to_string_pretty(starred)?)?;
+    fs::rename(tmp, path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use tempfile::TempDir;
+
+    #[test]
+    fn star_and_unstar_round_trip() -> io::Result<()> {
+        let home = TempDir::new()?;
+        let id = Uuid::from_u128(7);
+
+        star_session(home.path(), id)?;
+        let first = starred_sessions(home.path())?;
+        star_session(home.path(), id)?;
+        let starred = starred_sessions(home.path())?;
+        assert_eq!(starred.len(), 1);
+        assert_eq!(starred, first);
+
+        assert!(unstar_session(home.path(), id)?);
+        assert!(!unstar_session(home.path(), id)?);
+        assert!(starred_sessions(home.path())?.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn reads_stars_written_with_a_name() -> io::Result<()> {
+        let home = TempDir::new()?;
+        let path = starred_file_path(home.path());
+        fs::create_dir_all(path.parent().unwrap())?;
+        fs::write(
+            &path,
+            r#"{"00000000-0000-0000-0000-000000000007":{"name":"old","starred_at":"2025-01-01T00:00:00Z"}}"#,
+        )?;
+
+        let starred = starred_sessions(home.path())?;
+        assert_eq!(
+            starred[&Uuid::from_u128(7)],
+            StarredSession {
+                starred_at: "2025-01-01T00:00:00Z".to_string()
+            }
+        );
+        Ok(())
+    }
+}
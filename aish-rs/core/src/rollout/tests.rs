@@ -25,6 +25,7 @@ use aish_protocol::protocol::RolloutItem;
 use aish_protocol::protocol::RolloutLine;
 use aish_protocol::protocol::SessionMeta;
 use aish_protocol::protocol::SessionMetaLine;
+use aish_protocol::protocol::SessionMetadataUpdate;
 use aish_protocol::protocol::SessionSource;
 use aish_protocol::protocol::UserMessageEvent;
 use anyhow::Result;
@@ -234,18 +235,24 @@ async fn test_list_conversations_latest_first() {
                 head: head_3,
                 created_at: Some("2025-01-03T12-00-00".into()),
                 updated_at: updated_times.first().cloned().flatten(),
+                name: None,
+                tags: Vec::new(),
             },
             ConversationItem {
                 path: p2,
                 head: head_2,
                 created_at: Some("2025-01-02T12-00-00".into()),
                 updated_at: updated_times.get(1).cloned().flatten(),
+                name: None,
+                tags: Vec::new(),
             },
             ConversationItem {
                 path: p3,
                 head: head_1,
                 created_at: Some("2025-01-01T12-00-00".into()),
                 updated_at: updated_times.get(2).cloned().flatten(),
+                name: None,
+                tags: Vec::new(),
             },
         ],
         next_cursor: None,
@@ -364,12 +371,16 @@ async fn test_pagination_cursor() {
                 head: head_5,
                 created_at: Some("2025-03-05T09-00-00".into()),
                 updated_at: updated_page1.first().cloned().flatten(),
+                name: None,
+                tags: Vec::new(),
             },
             ConversationItem {
                 path: p4,
                 head: head_4,
                 created_at: Some("2025-03-04T09-00-00".into()),
                 updated_at: updated_page1.get(1).cloned().flatten(),
+                name: None,
+                tags: Vec::new(),
             },
         ],
         next_cursor: Some(expected_cursor1.clone()),
@@ -431,12 +442,16 @@ async fn test_pagination_cursor() {
                 head: head_3,
                 created_at: Some("2025-03-03T09-00-00".into()),
                 updated_at: updated_page2.first().cloned().flatten(),
+                name: None,
+                tags: Vec::new(),
             },
             ConversationItem {
                 path: p2,
                 head: head_2,
                 created_at: Some("2025-03-02T09-00-00".into()),
                 updated_at: updated_page2.get(1).cloned().flatten(),
+                name: None,
+                tags: Vec::new(),
             },
         ],
         next_cursor: Some(expected_cursor2.clone()),
@@ -479,6 +494,8 @@ async fn test_pagination_cursor() {
             head: head_1,
             created_at: Some("2025-03-01T09-00-00".into()),
             updated_at: updated_page3.first().cloned().flatten(),
+            name: None,
+            tags: Vec::new(),
         }],
         next_cursor: None,
         num_scanned_files: 5, // scanned 05, 04 (anchor), 03, 02 (anchor), 01
@@ -534,6 +551,8 @@ async fn test_get_conversation_contents() {
             head: expected_head,
             created_at: Some(ts.into()),
             updated_at: page.items[0].updated_at.clone(),
+            name: None,
+            tags: Vec::new(),
         }],
         next_cursor: None,
         num_scanned_files: 1,
@@ -592,6 +611,7 @@ async fn test_updated_at_uses_file_mtime() -> Result<()> {
                 cli_version: "test_version".into(),
                 source: SessionSource::VSCode,
                 model_provider: Some("test-provider".into()),
+                parent_id: None,
             },
             git: None,
         }),
@@ -708,12 +728,16 @@ async fn test_stable_ordering_same_second_pagination() {
                 head: head(u3),
                 created_at: Some(ts.to_string()),
                 updated_at: updated_page1.first().cloned().flatten(),
+                name: None,
+                tags: Vec::new(),
             },
             ConversationItem {
                 path: p2,
                 head: head(u2),
                 created_at: Some(ts.to_string()),
                 updated_at: updated_page1.get(1).cloned().flatten(),
+                name: None,
+                tags: Vec::new(),
             },
         ],
         next_cursor: Some(expected_cursor1.clone()),
@@ -746,6 +770,8 @@ async fn test_stable_ordering_same_second_pagination() {
             head: head(u1),
             created_at: Some(ts.to_string()),
             updated_at: updated_page2.first().cloned().flatten(),
+            name: None,
+            tags: Vec::new(),
         }],
         next_cursor: None,
         num_scanned_files: 3, // scanned u3, u2 (anchor), u1
@@ -916,3 +942,36 @@ async fn test_model_provider_filter_selects_only_matching_sessions() -> Result<(
 
     Ok(())
 }
+
+#[tokio::test]
+async fn test_conversation_items_include_latest_name_and_tags() -> Result<()> {
+    let temp = TempDir::new().unwrap();
+    let home = temp.path();
+    let id = Uuid::from_u128(5);
+    write_session_file(home, "2025-03-01T09-00-00", id, 1, Some(SessionSource::Cli))?;
+    let path = home
+        .join("sessions/2025/03/01")
+        .join(format!("rollout-2025-03-01T09-00-00-{id}.jsonl"));
+
+    crate::rollout::metadata::update_session_metadata(
+        &path,
+        &SessionMetadataUpdate {
+            name: Some("nightly build".to_string()),
+            add_tags: vec!["ci".to_string(), "flaky".to_string()],
+            ..Default::default()
+        },
+    )?;
+    crate::rollout::metadata::update_session_metadata(
+        &path,
+        &SessionMetadataUpdate {
+            remove_tags: vec!["flaky".to_string()],
+            ..Default::default()
+        },
+    )?;
+
+    let page = get_conversations(home, 10, None, NO_SOURCE_FILTER, None, TEST_PROVIDER).await?;
+    assert_eq!(page.items.len(), 1);
+    assert_eq!(page.items[0].name.as_deref(), Some("nightly build"));
+    assert_eq!(page.items[0].tags, vec!["ci".to_string()]);
+    Ok(())
+}
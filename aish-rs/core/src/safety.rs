@@ -227,6 +227,9 @@ mod tests {
             network_access: false,
             exclude_tmpdir_env_var: true,
             exclude_slash_tmp: true,
+            network_allowlist: Vec::new(),
+            writable_roots: Vec::new(),
+            deny_read: Vec::new(),
         };
 
         assert!(is_write_patch_constrained_to_writable_paths(
@@ -0,0 +1,594 @@
+//! Container sandbox backend: runs each command in a throwaway rootless
+//! podman/docker container, or under bubblewrap, with the filesystem exposed
+//! according to the [`SandboxPolicy`].
+//!
+//! Unlike the platform sandboxes this does not depend on Landlock or Seatbelt
+//! being available on the host, only on the configured runtime.
+
+use std::collections::HashMap;
+use std::path::Path;
+use std::path::PathBuf;
+use std::process::Stdio;
+
+use tokio::process::Child;
+use tokio::process::Command;
+
+use crate::config::types::ContainerRuntime;
+use crate::config::types::SandboxContainerToml;
+use crate::deny_read::resolve_deny_read_paths;
+use crate::protocol::SandboxPolicy;
+use crate::spawn::StdioPolicy;
+use crate::spawn::spawn_child_async;
+
+/// Variables that describe the host rather than the command and would break
+/// the container's own userland if forwarded into an image.
+const HOST_ONLY_ENV_VARS: [&str; 9] = [
+    "HOME", "HOSTNAME", "LOGNAME", "OLDPWD", "PATH", "PWD", "SHELL", "TMPDIR", "USER",
+];
+
+/// Effective `[sandbox_container]` settings, merged with the active profile.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct ContainerSandbox {
+    pub runtime: ContainerRuntime,
+    pub image: Option<String>,
+    pub extra_args: Vec<String>,
+}
+
+impl From<SandboxContainerToml> for ContainerSandbox {
+    fn from(toml: SandboxContainerToml) -> Self {
+        Self {
+            runtime: toml.runtime.unwrap_or_default(),
+            image: toml.image.filter(|image| !image.trim().is_empty()),
+            extra_args: toml.extra_args.unwrap_or_default(),
+        }
+    }
+}
+
+impl ContainerSandbox {
+    /// Executable that is spawned for this runtime.
+    pub fn program(&self) -> &'static str {
+        match self.runtime {
+            ContainerRuntime::Podman => "podman",
+            ContainerRuntime::Docker => "docker",
+            ContainerRuntime::Bubblewrap => "bwrap",
+        }
+    }
+
+    /// Checks that the settings are complete enough to run a command.
+    pub fn validate(&self) -> Result<(), String> {
+        match self.runtime {
+            ContainerRuntime::Podman | ContainerRuntime::Docker if self.image.is_none() => {
+                Err(format!(
+                    "`[sandbox_container] image` is required when the container sandbox uses {}",
+                    self.program()
+                ))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// The container that runs under `name`, for runtimes whose containers
+    /// outlive the CLI process; bubblewrap dies with its parent.
+    pub fn running_container(&self, name: &str) -> Option<RunningContainer> {
+        match self.runtime {
+            ContainerRuntime::Podman | ContainerRuntime::Docker => Some(RunningContainer {
+                program: PathBuf::from(self.program()),
+                name: name.to_string(),
+            }),
+            ContainerRuntime::Bubblewrap => None,
+        }
+    }
+
+    /// Builds the arguments (excluding the program itself) that run `command`
+    /// in `command_cwd` under `sandbox_policy`, in a container called
+    /// `container_name` (see [`container_name`]).
+    pub fn create_command_args(
+        &self,
+        command: Vec<String>,
+        command_cwd: &Path,
+        sandbox_policy: &SandboxPolicy,
+        sandbox_policy_cwd: &Path,
+        env: &HashMap<String, String>,
+        container_name: &str,
+    ) -> Vec<String> {
+        let mounts = Mounts::for_policy(sandbox_policy, sandbox_policy_cwd, command_cwd);
+        let network = sandbox_policy.has_full_network_access();
+        match self.runtime {
+            ContainerRuntime::Podman | ContainerRuntime::Docker => {
+                self.create_run_args(command, command_cwd, &mounts, network, env, container_name)
+            }
+            ContainerRuntime::Bubblewrap => {
+                self.create_bwrap_args(command, command_cwd, &mounts, network)
+            }
+        }
+    }
+
+    fn create_run_args(
+        &self,
+        command: Vec<String>,
+        command_cwd: &Path,
+        mounts: &Mounts,
+        network: bool,
+        env: &HashMap<String, String>,
+        container_name: &str,
+    ) -> Vec<String> {
+        let mut args: Vec<String> = vec![
+            "run".to_string(),
+            format!("--name={container_name}"),
+            "--rm".to_string(),
+            "--interactive".to_string(),
+            "--init".to_string(),
+            "--cap-drop=ALL".to_string(),
+            "--security-opt=no-new-privileges".to_string(),
+        ];
+        if !network {
+            args.push("--network=none".to_string());
+        }
+        match self.runtime {
+            // Keep the host uid inside the user namespace so files written to
+            // bind mounts are owned by the user, not by a subordinate id.
+            ContainerRuntime::Podman => args.push("--userns=keep-id".to_string()),
+            #[cfg(unix)]
+            ContainerRuntime::Docker => {
+                // SAFETY: getuid/getgid cannot fail.
+                let (uid, gid) = unsafe { (libc::getuid(), libc::getgid()) };
+                args.push(format!("--user={uid}:{gid}"));
+            }
+            _ => {}
+        }
+
+        for path in &mounts.read_only {
+            args.push(mount_arg("bind", Some(path), path, true));
+        }
+        for path in &mounts.writable {
+            args.push(mount_arg("bind", Some(path), path, false));
+        }
+        for path in &mounts.read_only_subpaths {
+            args.push(mount_arg("bind", Some(path), path, true));
+        }
+        // Paths outside every mount are already absent from the container.
+        for path in mounts.hidden.iter().filter(|path| mounts.is_visible(path)) {
+            if path.is_dir() {
+                args.push(mount_arg("tmpfs", None, path, false));
+            } else {
+                args.push(mount_arg("bind", Some(Path::new("/dev/null")), path, true));
+            }
+        }
+
+        // `--env NAME` forwards the value from the runtime's own environment,
+        // which is `env`, so values never appear on the command line.
+        let mut names: Vec<&String> = env
+            .keys()
+            .filter(|name| !HOST_ONLY_ENV_VARS.contains(&name.as_str()))
+            .collect();
+        names.sort();
+        for name in names {
+            args.push("--env".to_string());
+            args.push(name.clone());
+        }
+
+        args.push(format!("--workdir={}", command_cwd.display()));
+        args.extend(self.extra_args.iter().cloned());
+        if let Some(image) = &self.image {
+            args.push(image.clone());
+        }
+        args.extend(command);
+        args
+    }
+
+    fn create_bwrap_args(
+        &self,
+        command: Vec<String>,
+        command_cwd: &Path,
+        mounts: &Mounts,
+        network: bool,
+    ) -> Vec<String> {
+        let mut args: Vec<String> = vec![
+            "--die-with-parent".to_string(),
+            "--new-session".to_string(),
+            "--unshare-pid".to_string(),
+            "--unshare-ipc".to_string(),
+        ];
+        if !network {
+            args.push("--unshare-net".to_string());
+        }
+        // bubblewrap shares the host's userland, so the whole filesystem is
+        // readable just like with the platform sandboxes.
+        args.extend(["--ro-bind", "/", "/", "--dev", "/dev", "--proc", "/proc"].map(String::from));
+        for path in &mounts.writable {
+            push_path_pair(&mut args, "--bind", path);
+        }
+        for path in &mounts.read_only_subpaths {
+            push_path_pair(&mut args, "--ro-bind", path);
+        }
+        for path in &mounts.hidden {
+            if path.is_dir() {
+                args.push("--tmpfs".to_string());
+                args.push(path.to_string_lossy().to_string());
+            } else {
+                args.push("--ro-bind".to_string());
+                args.push("/dev/null".to_string());
+                args.push(path.to_string_lossy().to_string());
+            }
+        }
+        args.push("--chdir".to_string());
+        args.push(command_cwd.to_string_lossy().to_string());
+        args.extend(self.extra_args.iter().cloned());
+        args.push("--".to_string());
+        args.extend(command);
+        args
+    }
+}
+
+/// Formats a `--mount` option. podman and docker parse its value as a CSV
+/// record, so a field whose path contains a comma or a quote is quoted rather
+/// than split into separate (and reinterpreted) options.
+/// Name of the container that runs tool call `call_id`. The process id keeps
+/// concurrent aish processes apart, as providers may reuse call ids.
+pub fn container_name(call_id: &str) -> String {
+    let call_id: String = call_id
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || matches!(c, '_' | '.' | '-') {
+                c
+            } else {
+                '-'
+            }
+        })
+        .collect();
+    format!("aish-{}-{call_id}", std::process::id())
+}
+
+/// A podman/docker container started for one command. Killing the runtime
+/// CLI on a timeout or `job_kill` leaves the container itself running, so it
+/// is stopped by name as well.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RunningContainer {
+    pub program: PathBuf,
+    pub name: String,
+}
+
+impl RunningContainer {
+    /// Runs `<runtime> kill <name>` and then `<runtime> rm -f <name>`.
+    /// Failures are ignored: the container may already have exited.
+    pub async fn kill_and_remove(&self) {
+        for args in [
+            vec!["kill", self.name.as_str()],
+            vec!["rm", "-f", self.name.as_str()],
+        ] {
+            let status = Command::new(&self.program)
+                .args(&args)
+                .stdin(Stdio::null())
+                .stdout(Stdio::null())
+                .stderr(Stdio::null())
+                .status()
+                .await;
+            if let Err(err) = status {
+                tracing::warn!("failed to run {} {args:?}: {err}", self.program.display());
+            }
+        }
+    }
+}
+
+fn mount_arg(kind: &str, src: Option<&Path>, dst: &Path, readonly: bool) -> String {
+    let mut fields = vec![format!("type={kind}")];
+    if let Some(src) = src {
+        fields.push(csv_field(&format!("src={}", src.display())));
+    }
+    fields.push(csv_field(&format!("dst={}", dst.display())));
+    if readonly {
+        fields.push("readonly".to_string());
+    }
+    format!("--mount={}", fields.join(","))
+}
+
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+fn push_path_pair(args: &mut Vec<String>, flag: &str, path: &Path) {
+    let path = path.to_string_lossy().to_string();
+    args.push(flag.to_string());
+    args.push(path.clone());
+    args.push(path);
+}
+
+/// Host paths exposed to the command, grouped by how they are mounted.
+#[derive(Debug, Default, PartialEq)]
+struct Mounts {
+    /// Read-only views of the working directory for policies that do not
+    /// make it writable. Only used by image-based runtimes.
+    read_only: Vec<PathBuf>,
+    writable: Vec<PathBuf>,
+    /// Paths beneath a writable root that must stay read-only (e.g. `.git`).
+    read_only_subpaths: Vec<PathBuf>,
+    /// `deny_read` matches, shadowed by an empty tmpfs or `/dev/null`.
+    hidden: Vec<PathBuf>,
+}
+
+impl Mounts {
+    fn for_policy(
+        sandbox_policy: &SandboxPolicy,
+        sandbox_policy_cwd: &Path,
+        command_cwd: &Path,
+    ) -> Self {
+        let mut mounts = Mounts::default();
+        for root in sandbox_policy.get_writable_roots_with_cwd(sandbox_policy_cwd) {
+            // Containers bring their own /dev; sharing the host's shm
+            // segments would defeat the isolation.
+            if root.root.as_path().starts_with("/dev") {
+                continue;
+            }
+            mounts.writable.push(root.root.to_path_buf());
+            mounts.read_only_subpaths.extend(
+                root.read_only_subpaths
+                    .iter()
+                    .map(|subpath| subpath.to_path_buf()),
+            );
+        }
+        for dir in [sandbox_policy_cwd, command_cwd] {
+            if !mounts.is_visible(dir) && !mounts.read_only.iter().any(|path| path == dir) {
+                mounts.read_only.push(dir.to_path_buf());
+            }
+        }
+        mounts.hidden = resolve_deny_read_paths(sandbox_policy.deny_read(), sandbox_policy_cwd)
+            .into_iter()
+            .map(|path| path.into_path_buf())
+            .collect();
+        mounts
+    }
+
+    fn is_visible(&self, path: &Path) -> bool {
+        self.writable
+            .iter()
+            .chain(&self.read_only)
+            .any(|root| path.starts_with(root))
+    }
+}
+
+/// Spawn a command under the container sandbox, the counterpart of
+/// [`crate::landlock::spawn_command_under_linux_sandbox`].
+pub async fn spawn_command_under_container(
+    container_sandbox: &ContainerSandbox,
+    command: Vec<String>,
+    command_cwd: PathBuf,
+    sandbox_policy: &SandboxPolicy,
+    sandbox_policy_cwd: &Path,
+    stdio_policy: StdioPolicy,
+    env: HashMap<String, String>,
+    container_name: &str,
+) -> std::io::Result<Child> {
+    container_sandbox
+        .validate()
+        .map_err(|message| std::io::Error::new(std::io::ErrorKind::InvalidInput, message))?;
+    let args = container_sandbox.create_command_args(
+        command,
+        &command_cwd,
+        sandbox_policy,
+        sandbox_policy_cwd,
+        &env,
+        container_name,
+    );
+    spawn_child_async(
+        PathBuf::from(container_sandbox.program()),
+        args,
+        None,
+        command_cwd,
+        sandbox_policy,
+        stdio_policy,
+        env,
+    )
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aish_utils_absolute_path::AbsolutePathBuf;
+    use pretty_assertions::assert_eq;
+    use tempfile::TempDir;
+
+    fn current_dir_write(cwd_extra: Vec<AbsolutePathBuf>, deny_read: Vec<String>) -> SandboxPolicy {
+        SandboxPolicy::CurrentDirWrite {
+            network_access: false,
+            exclude_tmpdir_env_var: true,
+            exclude_slash_tmp: true,
+            network_allowlist: Vec::new(),
+            writable_roots: cwd_extra,
+            deny_read,
+        }
+    }
+
+    fn podman() -> ContainerSandbox {
+        ContainerSandbox {
+            runtime: ContainerRuntime::Podman,
+            image: Some("ubuntu:24.04".to_string()),
+            extra_args: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn podman_mounts_cwd_writable_and_disables_network() -> std::io::Result<()> {
+        let dir = TempDir::new()?;
+        let cwd = dir.path().canonicalize()?;
+        let env = HashMap::from([
+            ("PATH".to_string(), "/usr/bin".to_string()),
+            ("RUST_LOG".to_string(), "info".to_string()),
+        ]);
+
+        let args = podman().create_command_args(
+            vec!["ls".to_string()],
+            &cwd,
+            &current_dir_write(Vec::new(), Vec::new()),
+            &cwd,
+            &env,
+            "aish-1-call_1",
+        );
+
+        let cwd = cwd.display();
+        assert_eq!(
+            args,
+            vec![
+                "run".to_string(),
+                "--name=aish-1-call_1".to_string(),
+                "--rm".to_string(),
+                "--interactive".to_string(),
+                "--init".to_string(),
+                "--cap-drop=ALL".to_string(),
+                "--security-opt=no-new-privileges".to_string(),
+                "--network=none".to_string(),
+                "--userns=keep-id".to_string(),
+                format!("--mount=type=bind,src={cwd},dst={cwd}"),
+                "--env".to_string(),
+                "RUST_LOG".to_string(),
+                format!("--workdir={cwd}"),
+                "ubuntu:24.04".to_string(),
+                "ls".to_string(),
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn mount_paths_with_commas_are_quoted() {
+        assert_eq!(
+            mount_arg(
+                "bind",
+                Some(Path::new("/work/a,b")),
+                Path::new("/work/a,b"),
+                true
+            ),
+            r#"--mount=type=bind,"src=/work/a,b","dst=/work/a,b",readonly"#
+        );
+        assert_eq!(
+            mount_arg("tmpfs", None, Path::new(r#"/work/say "hi""#), false),
+            r#"--mount=type=tmpfs,"dst=/work/say ""hi""""#
+        );
+        assert_eq!(
+            mount_arg(
+                "bind",
+                Some(Path::new("/work/plain")),
+                Path::new("/work/plain"),
+                false
+            ),
+            "--mount=type=bind,src=/work/plain,dst=/work/plain"
+        );
+    }
+
+    #[test]
+    fn read_only_policy_mounts_cwd_read_only() -> std::io::Result<()> {
+        let dir = TempDir::new()?;
+        let cwd = dir.path().canonicalize()?;
+
+        let mounts = Mounts::for_policy(&SandboxPolicy::ReadOnly, &cwd, &cwd);
+
+        assert_eq!(
+            mounts,
+            Mounts {
+                read_only: vec![cwd],
+                ..Default::default()
+            }
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn git_dir_and_denied_paths_are_shadowed() -> std::io::Result<()> {
+        let dir = TempDir::new()?;
+        let cwd = dir.path().canonicalize()?;
+        std::fs::create_dir(cwd.join(".git"))?;
+        std::fs::write(cwd.join(".env"), "TOKEN=1")?;
+        let extra = TempDir::new()?;
+        let extra = AbsolutePathBuf::from_absolute_path(extra.path().canonicalize()?)?;
+
+        let mounts = Mounts::for_policy(
+            &current_dir_write(vec![extra.clone()], vec![".env".to_string()]),
+            &cwd,
+            &cwd,
+        );
+
+        assert_eq!(
+            mounts,
+            Mounts {
+                read_only: Vec::new(),
+                writable: vec![cwd.clone(), extra.into_path_buf()],
+                read_only_subpaths: vec![cwd.join(".git")],
+                hidden: vec![cwd.join(".env")],
+            }
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn bwrap_binds_host_root_read_only() -> std::io::Result<()> {
+        let dir = TempDir::new()?;
+        let cwd = dir.path().canonicalize()?;
+        let sandbox = ContainerSandbox {
+            runtime: ContainerRuntime::Bubblewrap,
+            image: None,
+            extra_args: Vec::new(),
+        };
+        assert_eq!(sandbox.validate(), Ok(()));
+
+        let args = sandbox.create_command_args(
+            vec!["true".to_string()],
+            &cwd,
+            &current_dir_write(Vec::new(), Vec::new()),
+            &cwd,
+            &HashMap::new(),
+            "aish-1-call_1",
+        );
+
+        let cwd = cwd.to_string_lossy().to_string();
+        let expected: Vec<String> = [
+            "--die-with-parent",
+            "--new-session",
+            "--unshare-pid",
+            "--unshare-ipc",
+            "--unshare-net",
+            "--ro-bind",
+            "/",
+            "/",
+            "--dev",
+            "/dev",
+            "--proc",
+            "/proc",
+            "--bind",
+            &cwd,
+            &cwd,
+            "--chdir",
+            &cwd,
+            "--",
+            "true",
+        ]
+        .map(String::from)
+        .to_vec();
+        assert_eq!(args, expected);
+        Ok(())
+    }
+
+    #[test]
+    fn image_is_required_for_podman_and_docker() {
+        let sandbox = ContainerSandbox {
+            runtime: ContainerRuntime::Docker,
+            image: None,
+            extra_args: Vec::new(),
+        };
+        assert!(sandbox.validate().is_err());
+        assert_eq!(podman().validate(), Ok(()));
+    }
+
+    #[test]
+    fn container_names_are_valid_for_any_call_id() {
+        let pid = std::process::id();
+        assert_eq!(container_name("call_Ab1"), format!("aish-{pid}-call_Ab1"));
+        assert_eq!(
+            container_name("tool call/0"),
+            format!("aish-{pid}-tool-call-0")
+        );
+    }
+}
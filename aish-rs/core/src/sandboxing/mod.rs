@@ -6,6 +6,9 @@ sandbox placement and transformation of portable CommandSpec into a
 ready‑to‑spawn environment.
 */
 
+pub mod container;
+
+use crate::config::types::SandboxBackend;
 use crate::exec::ExecExpiration;
 use crate::exec::ExecToolCallOutput;
 use crate::exec::SandboxType;
@@ -22,6 +25,9 @@ use crate::spawn::AISH_SANDBOX_ENV_VAR;
 use crate::spawn::AISH_SANDBOX_NETWORK_DISABLED_ENV_VAR;
 use crate::tools::sandboxing::SandboxablePreference;
 pub use aish_protocol::models::SandboxPermissions;
+use container::ContainerSandbox;
+use container::RunningContainer;
+use container::container_name;
 use std::collections::HashMap;
 use std::path::Path;
 use std::path::PathBuf;
@@ -47,6 +53,8 @@ pub struct ExecEnv {
     pub sandbox_permissions: SandboxPermissions,
     pub justification: Option<String>,
     pub arg0: Option<String>,
+    /// Container to stop by name when the command times out or is killed.
+    pub container: Option<RunningContainer>,
 }
 
 pub enum SandboxPreference {
@@ -59,6 +67,8 @@ pub enum SandboxPreference {
 pub(crate) enum SandboxTransformError {
     #[error("missing aish-linux-sandbox executable path")]
     MissingLinuxSandboxExecutable,
+    #[error("{0}")]
+    InvalidContainerSandbox(String),
     #[cfg(not(target_os = "macos"))]
     #[error("seatbelt sandbox is only available on macOS")]
     SeatbeltUnavailable,
@@ -76,23 +86,31 @@ impl SandboxManager {
         &self,
         policy: &SandboxPolicy,
         pref: SandboxablePreference,
+        backend: SandboxBackend,
     ) -> SandboxType {
-        match pref {
-            SandboxablePreference::Forbid => SandboxType::None,
-            SandboxablePreference::Require => {
-                // Require a platform sandbox when available; on Windows this
-                // respects the experimental_windows_sandbox feature.
+        let restricted = || match backend {
+            SandboxBackend::Container => SandboxType::Container,
+            SandboxBackend::Platform => {
                 crate::safety::get_platform_sandbox().unwrap_or(SandboxType::None)
             }
+        };
+        match pref {
+            SandboxablePreference::Forbid => SandboxType::None,
+            // Require a sandbox when available; on Windows this respects the
+            // experimental_windows_sandbox feature.
+            SandboxablePreference::Require => restricted(),
             SandboxablePreference::Auto => match policy {
                 SandboxPolicy::DangerFullAccess | SandboxPolicy::ExternalSandbox { .. } => {
                     SandboxType::None
                 }
-                _ => crate::safety::get_platform_sandbox().unwrap_or(SandboxType::None),
+                _ => restricted(),
             },
         }
     }
 
+    /// `call_id` names the container when `sandbox` is
+    /// [`SandboxType::Container`].
+    #[allow(clippy::too_many_arguments)]
     pub(crate) fn transform(
         &self,
         mut spec: CommandSpec,
@@ -100,6 +118,8 @@ impl SandboxManager {
         sandbox: SandboxType,
         sandbox_policy_cwd: &Path,
         aish_linux_sandbox_exe: Option<&PathBuf>,
+        container_sandbox: Option<&ContainerSandbox>,
+        call_id: &str,
     ) -> Result<ExecEnv, SandboxTransformError> {
         let mut env = spec.env;
         if !policy.has_full_network_access() {
@@ -113,6 +133,7 @@ impl SandboxManager {
         command.push(spec.program);
         command.append(&mut spec.args);
 
+        let mut container = None;
         let (command, sandbox_env, arg0_override) = match sandbox {
             SandboxType::None => (command, HashMap::new(), None),
             #[cfg(target_os = "macos")]
@@ -142,6 +163,31 @@ impl SandboxManager {
                     Some("aish-linux-sandbox".to_string()),
                 )
             }
+            SandboxType::Container => {
+                let container_sandbox = container_sandbox.ok_or_else(|| {
+                    SandboxTransformError::InvalidContainerSandbox(
+                        "container sandbox selected without `[sandbox_container]` settings"
+                            .to_string(),
+                    )
+                })?;
+                container_sandbox
+                    .validate()
+                    .map_err(SandboxTransformError::InvalidContainerSandbox)?;
+                let name = container_name(call_id);
+                let mut args = container_sandbox.create_command_args(
+                    command,
+                    &spec.cwd,
+                    policy,
+                    sandbox_policy_cwd,
+                    &env,
+                    &name,
+                );
+                container = container_sandbox.running_container(&name);
+                let mut full_command = Vec::with_capacity(1 + args.len());
+                full_command.push(container_sandbox.program().to_string());
+                full_command.append(&mut args);
+                (full_command, HashMap::new(), None)
+            }
         };
 
         env.extend(sandbox_env);
@@ -155,6 +201,7 @@ impl SandboxManager {
             sandbox_permissions: spec.sandbox_permissions,
             justification: spec.justification,
             arg0: arg0_override,
+            container,
         })
     }
 
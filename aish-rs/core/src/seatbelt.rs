@@ -6,6 +6,7 @@ use std::path::Path;
 use std::path::PathBuf;
 use tokio::process::Child;
 
+use crate::deny_read::resolve_deny_read_paths;
 use crate::protocol::SandboxPolicy;
 use crate::spawn::AISH_SANDBOX_ENV_VAR;
 use crate::spawn::StdioPolicy;
@@ -105,12 +106,32 @@ pub(crate) fn create_seatbelt_command_args(
         }
     };
 
-    let file_read_policy = if sandbox_policy.has_full_disk_read_access() {
-        "; allow read-only file operations\n(allow file-read*)"
+    let mut file_read_policy = if sandbox_policy.has_full_disk_read_access() {
+        "; allow read-only file operations\n(allow file-read*)".to_string()
     } else {
-        ""
+        String::new()
     };
 
+    // Later rules take precedence, so this must follow the blanket allow.
+    let mut deny_read_params = Vec::new();
+    let deny_read_paths = resolve_deny_read_paths(sandbox_policy.deny_read(), sandbox_policy_cwd);
+    if !deny_read_paths.is_empty() {
+        let mut denied_subpaths = Vec::new();
+        for (index, path) in deny_read_paths.iter().enumerate() {
+            let canonical_path = path
+                .as_path()
+                .canonicalize()
+                .unwrap_or_else(|_| path.to_path_buf());
+            let param = format!("DENY_READ_{index}");
+            denied_subpaths.push(format!("(subpath (param \"{param}\"))"));
+            deny_read_params.push((param, canonical_path));
+        }
+        file_read_policy.push_str(&format!(
+            "\n(deny file-read-data {})",
+            denied_subpaths.join(" ")
+        ));
+    }
+
     // TODO(mbolin): apply_patch calls must also honor the SandboxPolicy.
     let network_policy = if sandbox_policy.has_full_network_access() {
         MACOS_SEATBELT_NETWORK_POLICY
@@ -122,7 +143,7 @@ pub(crate) fn create_seatbelt_command_args(
         "{MACOS_SEATBELT_BASE_POLICY}\n{file_read_policy}\n{file_write_policy}\n{network_policy}"
     );
 
-    let dir_params = [file_write_dir_params, macos_dir_params()].concat();
+    let dir_params = [file_write_dir_params, deny_read_params, macos_dir_params()].concat();
 
     let mut seatbelt_args: Vec<String> = vec!["-p".to_string(), full_policy];
     let definition_args = dir_params
@@ -191,6 +212,9 @@ mod tests {
             network_access: false,
             exclude_tmpdir_env_var: true,
             exclude_slash_tmp: true,
+            network_allowlist: Vec::new(),
+            writable_roots: Vec::new(),
+            deny_read: Vec::new(),
         };
 
         // Create the Seatbelt command to wrap a shell command that tries to
@@ -288,6 +312,9 @@ mod tests {
             network_access: false,
             exclude_tmpdir_env_var: false,
             exclude_slash_tmp: false,
+            network_allowlist: Vec::new(),
+            writable_roots: Vec::new(),
+            deny_read: Vec::new(),
         };
 
         let shell_command: Vec<String> = [
@@ -0,0 +1,266 @@
+//! Shell integration hooks used by `aish shell-init` and `aish fix`.
+//!
+//! The hook scripts record the last command the user ran in their own shell
+//! (command line, exit status, working directory and, with `--capture-stderr`,
+//! a tail of its stderr) into a per-shell state directory. `aish fix` reads
+//! that record back and seeds a new turn with it, using the same
+//! `<user_shell_command>` envelope that `!` commands are recorded with.
+//!
+//! The bash, zsh and fish hooks also bind Ctrl-X Ctrl-A to a widget that runs
+//! `aish suggest` on the current command line and replaces it with the
+//! suggested command.
+
+use std::io;
+use std::path::Path;
+use std::path::PathBuf;
+
+use crate::user_shell_command::USER_SHELL_COMMAND_CLOSE;
+use crate::user_shell_command::USER_SHELL_COMMAND_OPEN;
+
+/// Environment variable exported by the hook scripts that points at the
+/// per-shell state directory.
+pub const SHELL_STATE_DIR_ENV_VAR: &str = "AISH_SHELL_STATE_DIR";
+
+/// Maximum number of trailing output bytes the hooks keep for a command.
+pub const SHELL_OUTPUT_TAIL_BYTES: usize = 16 * 1024;
+
+const BASH_HOOK: &str = include_str!("../templates/shell_integration/bash.sh");
+const ZSH_HOOK: &str = include_str!("../templates/shell_integration/zsh.sh");
+const FISH_HOOK: &str = include_str!("../templates/shell_integration/fish.fish");
+
+const COMMAND_FILE: &str = "command";
+const STATUS_FILE: &str = "status";
+const CWD_FILE: &str = "cwd";
+const OUTPUT_FILE: &str = "output";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HookShell {
+    Bash,
+    Zsh,
+    Fish,
+}
+
+/// Render the hook script for `shell`. When `capture_output` is false the
+/// hook only records the command line, exit status and working directory.
+pub fn hook_script(shell: HookShell, capture_output: bool) -> String {
+    let template = match shell {
+        HookShell::Bash => BASH_HOOK,
+        HookShell::Zsh => ZSH_HOOK,
+        HookShell::Fish => FISH_HOOK,
+    };
+    template
+        .replace(
+            "@AISH_CAPTURE_OUTPUT@",
+            if capture_output { "1" } else { "" },
+        )
+        .replace(
+            "@AISH_OUTPUT_TAIL_BYTES@",
+            &SHELL_OUTPUT_TAIL_BYTES.to_string(),
+        )
+}
+
+/// The last command recorded by the shell hook.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LastShellCommand {
+    pub command: String,
+    pub exit_code: i32,
+    pub cwd: PathBuf,
+    /// Tail of the command's output, when the hook captured any.
+    pub output: Option<String>,
+}
+
+impl LastShellCommand {
+    /// Read the record from the state directory advertised by the hook via
+    /// [`SHELL_STATE_DIR_ENV_VAR`]. Returns `Ok(None)` when no hook is active
+    /// or nothing has been recorded yet.
+    pub fn load_from_env() -> io::Result<Option<Self>> {
+        match std::env::var_os(SHELL_STATE_DIR_ENV_VAR) {
+            Some(dir) if !dir.is_empty() => Self::load(Path::new(&dir)),
+            _ => Ok(None),
+        }
+    }
+
+    /// Read the record stored in `state_dir`. Returns `Ok(None)` when the
+    /// hook has not recorded a command yet.
+    pub fn load(state_dir: &Path) -> io::Result<Option<Self>> {
+        let Some(command) = read_optional(&state_dir.join(COMMAND_FILE))? else {
+            return Ok(None);
+        };
+        let command = command.trim_end().to_string();
+        if command.is_empty() {
+            return Ok(None);
+        }
+
+        let status = read_optional(&state_dir.join(STATUS_FILE))?.unwrap_or_default();
+        let exit_code = status.trim().parse::<i32>().map_err(|err| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("invalid exit status {status:?}: {err}"),
+            )
+        })?;
+        let cwd = read_optional(&state_dir.join(CWD_FILE))?
+            .map(|cwd| PathBuf::from(cwd.trim_end_matches('\n')))
+            .unwrap_or_default();
+        let output = read_optional(&state_dir.join(OUTPUT_FILE))?
+            .map(|output| tail_on_char_boundary(&output, SHELL_OUTPUT_TAIL_BYTES).to_string())
+            .filter(|output| !output.trim().is_empty());
+
+        Ok(Some(Self {
+            command,
+            exit_code,
+            cwd,
+            output,
+        }))
+    }
+
+    /// Format the record with the same envelope used for `!` commands so the
+    /// model treats it as something the user ran themselves.
+    pub fn format_record(&self) -> String {
+        let mut sections = vec![
+            USER_SHELL_COMMAND_OPEN.to_string(),
+            "<command>".to_string(),
+            self.command.clone(),
+            "</command>".to_string(),
+            "<result>".to_string(),
+            format!("Exit code: {}", self.exit_code),
+            format!("Working directory: {}", self.cwd.display()),
+        ];
+        match &self.output {
+            Some(output) => {
+                sections.push("Output (tail of stderr):".to_string());
+                sections.push(output.trim_end().to_string());
+            }
+            None => sections.push("Output: not captured".to_string()),
+        }
+        sections.push("</result>".to_string());
+        sections.push(USER_SHELL_COMMAND_CLOSE.to_string());
+        sections.join("\n")
+    }
+
+    /// Build the prompt for `aish fix`: the user's question (or a default one
+    /// based on the exit status) followed by the recorded command.
+    pub fn fix_prompt(&self, question: Option<&str>) -> String {
+        let question = match question.map(str::trim).filter(|q| !q.is_empty()) {
+            Some(question) => question.to_string(),
+            None if self.exit_code == 0 => {
+                "Explain the output of the last command I ran in my shell.".to_string()
+            }
+            None => "The last command I ran in my shell failed. Explain why and suggest a fix."
+                .to_string(),
+        };
+        format!("{question}\n\n{}", self.format_record())
+    }
+}
+
+fn read_optional(path: &Path) -> io::Result<Option<String>> {
+    match std::fs::read(path) {
+        Ok(bytes) => Ok(Some(String::from_utf8_lossy(&bytes).into_owned())),
+        Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(None),
+        Err(err) => Err(err),
+    }
+}
+
+fn tail_on_char_boundary(text: &str, max_bytes: usize) -> &str {
+    if text.len() <= max_bytes {
+        return text;
+    }
+    let mut start = text.len() - max_bytes;
+    while !text.is_char_boundary(start) {
+        start += 1;
+    }
+    &text[start..]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use tempfile::TempDir;
+
+    fn write_record(dir: &Path, command: &str, status: &str, output: Option<&str>) {
+        std::fs::write(dir.join(COMMAND_FILE), command).unwrap();
+        std::fs::write(dir.join(STATUS_FILE), status).unwrap();
+        std::fs::write(dir.join(CWD_FILE), "/work/project").unwrap();
+        if let Some(output) = output {
+            std::fs::write(dir.join(OUTPUT_FILE), output).unwrap();
+        }
+    }
+
+    #[test]
+    fn hook_script_substitutes_placeholders() {
+        for shell in [HookShell::Bash, HookShell::Zsh, HookShell::Fish] {
+            let script = hook_script(shell, true);
+            assert!(!script.contains("@AISH_"), "{shell:?} left a placeholder");
+            assert!(script.contains(SHELL_STATE_DIR_ENV_VAR));
+        }
+        let bash = hook_script(HookShell::Bash, false);
+        assert!(bash.contains("__aish_capture_output=\"\""));
+        for shell in [HookShell::Bash, HookShell::Zsh] {
+            let script = hook_script(shell, true);
+            assert!(
+                !script.contains("exec 2> >("),
+                "{shell:?} redirects the shell's own stderr"
+            );
+        }
+    }
+
+    #[test]
+    fn load_returns_none_without_record() {
+        let dir = TempDir::new().unwrap();
+        assert_eq!(LastShellCommand::load(dir.path()).unwrap(), None);
+    }
+
+    #[test]
+    fn load_reads_record() {
+        let dir = TempDir::new().unwrap();
+        write_record(
+            dir.path(),
+            "cargo build\n",
+            "101",
+            Some("error: could not compile\n"),
+        );
+        let record = LastShellCommand::load(dir.path()).unwrap();
+        assert_eq!(
+            record,
+            Some(LastShellCommand {
+                command: "cargo build".to_string(),
+                exit_code: 101,
+                cwd: PathBuf::from("/work/project"),
+                output: Some("error: could not compile\n".to_string()),
+            })
+        );
+    }
+
+    #[test]
+    fn load_rejects_invalid_status() {
+        let dir = TempDir::new().unwrap();
+        write_record(dir.path(), "ls", "nope", None);
+        let err = LastShellCommand::load(dir.path()).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn fix_prompt_wraps_record_in_user_shell_command_envelope() {
+        let record = LastShellCommand {
+            command: "make".to_string(),
+            exit_code: 2,
+            cwd: PathBuf::from("/src"),
+            output: None,
+        };
+        assert_eq!(
+            record.fix_prompt(None),
+            "The last command I ran in my shell failed. Explain why and suggest a fix.\n\n<user_shell_command>\n<command>\nmake\n</command>\n<result>\nExit code: 2\nWorking directory: /src\nOutput: not captured\n</result>\n</user_shell_command>"
+        );
+        assert!(
+            record
+                .fix_prompt(Some("why is make unhappy?"))
+                .starts_with("why is make unhappy?\n\n<user_shell_command>")
+        );
+    }
+
+    #[test]
+    fn tail_respects_char_boundaries() {
+        assert_eq!(tail_on_char_boundary("héllo", 4), "llo");
+        assert_eq!(tail_on_char_boundary("abc", 10), "abc");
+    }
+}
@@ -0,0 +1,205 @@
+//! Resolves how long a shell tool call may run when the model does not pass
+//! an explicit `timeout_ms`, based on the `[shell_timeouts]` config table.
+
+use std::path::Path;
+use std::time::Duration;
+
+use crate::bash::extract_bash_command;
+use crate::bash::parse_shell_lc_plain_commands;
+use crate::config::types::ShellTimeoutsToml;
+use crate::exec::DEFAULT_EXEC_COMMAND_TIMEOUT_MS;
+
+/// Effective `[shell_timeouts]` policy. Each entry is a command prefix (a
+/// single program name is a one-token prefix) that is matched against the
+/// start of every command the shell tool runs; the longest matching prefix
+/// wins.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ShellTimeouts {
+    pub default: Duration,
+    rules: Vec<ShellTimeoutRule>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct ShellTimeoutRule {
+    prefix: Vec<String>,
+    timeout: Duration,
+}
+
+impl Default for ShellTimeouts {
+    fn default() -> Self {
+        Self {
+            default: Duration::from_millis(DEFAULT_EXEC_COMMAND_TIMEOUT_MS),
+            rules: Vec::new(),
+        }
+    }
+}
+
+impl From<ShellTimeoutsToml> for ShellTimeouts {
+    fn from(toml: ShellTimeoutsToml) -> Self {
+        let default = toml.default.map_or(
+            Duration::from_millis(DEFAULT_EXEC_COMMAND_TIMEOUT_MS),
+            Duration::from_secs,
+        );
+        let mut rules: Vec<ShellTimeoutRule> = toml
+            .commands
+            .into_iter()
+            .filter_map(|(pattern, secs)| {
+                let prefix = shlex::split(&pattern)
+                    .unwrap_or_else(|| pattern.split_whitespace().map(str::to_string).collect());
+                (!prefix.is_empty()).then(|| ShellTimeoutRule {
+                    prefix,
+                    timeout: Duration::from_secs(secs),
+                })
+            })
+            .collect();
+        // Keep the order stable so equal-length matches resolve the same way
+        // on every run regardless of HashMap iteration order.
+        rules.sort_by(|a, b| a.prefix.cmp(&b.prefix));
+        Self { default, rules }
+    }
+}
+
+impl ShellTimeouts {
+    /// Timeout for `command` as the shell tool would run it. Scripts passed to
+    /// `bash -lc` are split into their individual commands and the longest
+    /// timeout among them is used, so `cd app && cargo test` gets the
+    /// `cargo test` budget.
+    pub fn timeout_for(&self, command: &[String]) -> Duration {
+        if self.rules.is_empty() {
+            return self.default;
+        }
+        command_words(command)
+            .iter()
+            .filter_map(|words| self.matching_rule(words))
+            .map(|rule| rule.timeout)
+            .max()
+            .unwrap_or(self.default)
+    }
+
+    fn matching_rule(&self, words: &[String]) -> Option<&ShellTimeoutRule> {
+        self.rules
+            .iter()
+            .filter(|rule| prefix_matches(&rule.prefix, words))
+            .max_by_key(|rule| rule.prefix.len())
+    }
+}
+
+fn command_words(command: &[String]) -> Vec<Vec<String>> {
+    if let Some(commands) = parse_shell_lc_plain_commands(command) {
+        return commands;
+    }
+    if let Some((_, script)) = extract_bash_command(command) {
+        // Not a plain command sequence (redirects, substitutions, ...): fall
+        // back to the leading words of the script.
+        let words = shlex::split(script)
+            .unwrap_or_else(|| script.split_whitespace().map(str::to_string).collect());
+        return vec![words];
+    }
+    vec![command.to_vec()]
+}
+
+/// The first token matches on the program's file name so that
+/// `/usr/bin/apt` is covered by an `apt` entry.
+fn prefix_matches(prefix: &[String], words: &[String]) -> bool {
+    let Some((program, rest)) = prefix.split_first() else {
+        return false;
+    };
+    let Some((word, word_rest)) = words.split_first() else {
+        return false;
+    };
+    let word_program = Path::new(word)
+        .file_name()
+        .and_then(|name| name.to_str())
+        .unwrap_or(word);
+    (word == program || word_program == program)
+        && word_rest.len() >= rest.len()
+        && rest.iter().zip(word_rest).all(|(a, b)| a == b)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use std::collections::HashMap;
+
+    fn timeouts() -> ShellTimeouts {
+        ShellTimeoutsToml {
+            default: Some(60),
+            commands: HashMap::from([
+                ("apt".to_string(), 900),
+                ("cargo".to_string(), 300),
+                ("cargo test".to_string(), 1800),
+                ("docker build".to_string(), 3600),
+            ]),
+        }
+        .into()
+    }
+
+    fn argv(args: &[&str]) -> Vec<String> {
+        args.iter().map(ToString::to_string).collect()
+    }
+
+    #[test]
+    fn longest_prefix_wins() {
+        let timeouts = timeouts();
+        assert_eq!(
+            timeouts.timeout_for(&argv(&["cargo", "test", "--all"])),
+            Duration::from_secs(1800)
+        );
+        assert_eq!(
+            timeouts.timeout_for(&argv(&["cargo", "build"])),
+            Duration::from_secs(300)
+        );
+        assert_eq!(
+            timeouts.timeout_for(&argv(&["/usr/bin/apt", "install", "jq"])),
+            Duration::from_secs(900)
+        );
+        assert_eq!(
+            timeouts.timeout_for(&argv(&["docker", "run", "alpine"])),
+            Duration::from_secs(60)
+        );
+    }
+
+    #[test]
+    fn shell_scripts_use_the_longest_matching_command() {
+        let timeouts = timeouts();
+        assert_eq!(
+            timeouts.timeout_for(&argv(&["bash", "-lc", "cd app && cargo test -p core"])),
+            Duration::from_secs(1800)
+        );
+        assert_eq!(
+            timeouts.timeout_for(&argv(&["bash", "-lc", "docker build . > build.log"])),
+            Duration::from_secs(3600)
+        );
+        assert_eq!(
+            timeouts.timeout_for(&argv(&["bash", "-lc", "ls -la"])),
+            Duration::from_secs(60)
+        );
+    }
+
+    #[test]
+    fn profile_table_overrides_base_entries() {
+        let base = ShellTimeoutsToml {
+            default: Some(60),
+            commands: HashMap::from([("cargo".to_string(), 300)]),
+        };
+        let profile = ShellTimeoutsToml {
+            default: None,
+            commands: HashMap::from([("cargo".to_string(), 30)]),
+        };
+        let timeouts: ShellTimeouts = base.merge(profile).into();
+        assert_eq!(timeouts.default, Duration::from_secs(60));
+        assert_eq!(
+            timeouts.timeout_for(&argv(&["cargo", "check"])),
+            Duration::from_secs(30)
+        );
+    }
+
+    #[test]
+    fn unset_table_keeps_the_builtin_default() {
+        assert_eq!(
+            ShellTimeouts::from(ShellTimeoutsToml::default()).timeout_for(&argv(&["sleep", "1"])),
+            Duration::from_millis(DEFAULT_EXEC_COMMAND_TIMEOUT_MS)
+        );
+    }
+}
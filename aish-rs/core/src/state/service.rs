@@ -2,11 +2,14 @@ use std::sync::Arc;
 
 use crate::AuthManager;
 use crate::RolloutRecorder;
+use crate::audit::AuditLog;
+use crate::background_jobs::BackgroundJobManager;
 use crate::exec_policy::ExecPolicyManager;
 use crate::mcp_connection_manager::McpConnectionManager;
 use crate::models_manager::manager::ModelsManager;
 use crate::skills::SkillsManager;
 use crate::tools::sandboxing::ApprovalStore;
+use crate::unified_exec::UnifiedExecSessionManager;
 use crate::user_notification::UserNotifier;
 use tokio::sync::Mutex;
 use tokio::sync::RwLock;
@@ -24,4 +27,7 @@ pub(crate) struct SessionServices {
     pub(crate) models_manager: Arc<ModelsManager>,
     pub(crate) tool_approvals: Mutex<ApprovalStore>,
     pub(crate) skills_manager: Arc<SkillsManager>,
+    pub(crate) unified_exec_manager: UnifiedExecSessionManager,
+    pub(crate) background_jobs: BackgroundJobManager,
+    pub(crate) audit: AuditLog,
 }
@@ -1,9 +1,14 @@
 //! Session-wide mutable state.
 
+use std::path::Path;
+
 use aish_protocol::models::ResponseItem;
+use aish_protocol::protocol::ApprovalGrant;
+use aish_protocol::protocol::SessionMetadataItem;
 
 use crate::aish::SessionConfiguration;
 use crate::context_manager::ContextManager;
+use crate::protocol::TokenCost;
 use crate::protocol::TokenUsage;
 use crate::protocol::TokenUsageInfo;
 use crate::truncate::TruncationPolicy;
@@ -12,6 +17,27 @@ use crate::truncate::TruncationPolicy;
 pub(crate) struct SessionState {
     pub(crate) session_configuration: SessionConfiguration,
     pub(crate) history: ContextManager,
+    /// Name and tags set with `Op::UpdateSessionMetadata`, restored on resume.
+    pub(crate) session_metadata: SessionMetadataItem,
+    /// Approvals granted with `ReviewDecision::ApprovedWithGrant`. They are
+    /// never written to disk and end with the session.
+    pub(crate) approval_grants: Vec<ApprovalGrant>,
+    /// How many entries of `Config::model_provider_fallbacks` the session has
+    /// switched through.
+    pub(crate) model_provider_fallbacks_used: usize,
+    /// What the session has spent, priced with `Config::model_pricing`.
+    pub(crate) cost: SessionCost,
+}
+
+/// Running cost of the session and of its current turn, in USD.
+#[derive(Debug, Default, Clone, Copy)]
+pub(crate) struct SessionCost {
+    pub(crate) turn: f64,
+    pub(crate) session: f64,
+    /// Whether any usage has been priced. No cost is reported until then.
+    pub(crate) priced: bool,
+    /// Whether the warning for nearing `Config::session_budget` was sent.
+    pub(crate) budget_warning_sent: bool,
 }
 
 impl SessionState {
@@ -21,6 +47,10 @@ impl SessionState {
         Self {
             session_configuration,
             history,
+            session_metadata: SessionMetadataItem::default(),
+            approval_grants: Vec::new(),
+            model_provider_fallbacks_used: 0,
+            cost: SessionCost::default(),
         }
     }
 
@@ -45,6 +75,31 @@ impl SessionState {
         self.history.set_token_info(info);
     }
 
+    // Approval grant helpers
+    pub(crate) fn add_approval_grant(&mut self, grant: ApprovalGrant) {
+        if !self.approval_grants.contains(&grant) {
+            self.approval_grants.push(grant);
+        }
+    }
+
+    /// Returns whether `grant` was present.
+    pub(crate) fn revoke_approval_grant(&mut self, grant: &ApprovalGrant) -> bool {
+        let before = self.approval_grants.len();
+        self.approval_grants.retain(|existing| existing != grant);
+        self.approval_grants.len() != before
+    }
+
+    /// Whether every command in `commands`, run in `cwd`, is covered by a
+    /// grant.
+    pub(crate) fn approval_grants_cover(&self, commands: &[Vec<String>], cwd: &Path) -> bool {
+        !commands.is_empty()
+            && commands.iter().all(|command| {
+                self.approval_grants
+                    .iter()
+                    .any(|grant| grant.covers(command, cwd))
+            })
+    }
+
     // Token/rate limit helpers
     pub(crate) fn update_token_info_from_usage(
         &mut self,
@@ -65,4 +120,23 @@ impl SessionState {
     pub(crate) fn get_total_token_usage(&self) -> i64 {
         self.history.get_total_token_usage()
     }
+
+    // Cost helpers
+    pub(crate) fn start_turn_cost(&mut self) {
+        self.cost.turn = 0.0;
+    }
+
+    pub(crate) fn add_cost(&mut self, cost: f64) {
+        self.cost.turn += cost;
+        self.cost.session += cost;
+        self.cost.priced = true;
+    }
+
+    pub(crate) fn token_cost(&self, budget: Option<f64>) -> Option<TokenCost> {
+        self.cost.priced.then_some(TokenCost {
+            turn: self.cost.turn,
+            session: self.cost.session,
+            budget,
+        })
+    }
 }
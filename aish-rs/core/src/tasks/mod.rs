@@ -1,5 +1,6 @@
 mod compact;
 mod ghost_snapshot;
+mod recipe;
 mod regular;
 mod undo;
 mod user_shell;
@@ -28,6 +29,7 @@ use aish_protocol::user_input::UserInput;
 
 pub(crate) use compact::CompactTask;
 pub(crate) use ghost_snapshot::GhostSnapshotTask;
+pub(crate) use recipe::RecipeTask;
 pub(crate) use regular::RegularTask;
 pub(crate) use undo::UndoTask;
 pub(crate) use user_shell::UserShellCommandTask;
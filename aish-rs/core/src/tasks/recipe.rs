@@ -0,0 +1,145 @@
+use std::sync::Arc;
+
+use aish_async_utils::OrCancelExt;
+use aish_protocol::user_input::UserInput;
+use async_trait::async_trait;
+use tokio_util::sync::CancellationToken;
+use uuid::Uuid;
+
+use crate::aish::TurnContext;
+use crate::error::AishErr;
+use crate::error::SandboxErr;
+use crate::protocol::EventMsg;
+use crate::protocol::ExecCommandSource;
+use crate::protocol::TaskStartedEvent;
+use crate::protocol::WarningEvent;
+use crate::state::TaskKind;
+use crate::tools::events::ToolEmitter;
+use crate::tools::events::ToolEventCtx;
+use crate::tools::handlers::ShellCommandHandler;
+use crate::tools::handlers::run_shell_request;
+use crate::tools::sandboxing::ToolError;
+use crate::user_shell_command::user_shell_command_record_item;
+
+use super::SessionTask;
+use super::SessionTaskContext;
+
+/// Tool name recorded in the audit log for recipe commands.
+const RECIPE_TOOL_NAME: &str = "recipe";
+
+/// Runs the rendered commands of a saved recipe in order, without a model
+/// turn. Each command goes through the same exec policy, approval and sandbox
+/// path as a `shell_command` call; the first command that fails, is rejected
+/// or is interrupted stops the recipe.
+#[derive(Clone)]
+pub(crate) struct RecipeTask {
+    name: String,
+    commands: Vec<String>,
+}
+
+impl RecipeTask {
+    pub(crate) fn new(name: String, commands: Vec<String>) -> Self {
+        Self { name, commands }
+    }
+}
+
+#[async_trait]
+impl SessionTask for RecipeTask {
+    fn kind(&self) -> TaskKind {
+        TaskKind::Regular
+    }
+
+    async fn run(
+        self: Arc<Self>,
+        session: Arc<SessionTaskContext>,
+        turn_context: Arc<TurnContext>,
+        _input: Vec<UserInput>,
+        cancellation_token: CancellationToken,
+    ) -> Option<String> {
+        let event = EventMsg::TaskStarted(TaskStartedEvent {
+            model_context_window: turn_context.client.get_model_context_window(),
+        });
+        let session = session.clone_session();
+        session.send_event(turn_context.as_ref(), event).await;
+
+        let total = self.commands.len();
+        for (idx, raw_command) in self.commands.iter().enumerate() {
+            let exec_params = ShellCommandHandler::exec_params_for_command(
+                session.as_ref(),
+                turn_context.as_ref(),
+                raw_command,
+            );
+            let call_id = Uuid::new_v4().to_string();
+            let emitter = ToolEmitter::shell(
+                exec_params.command.clone(),
+                exec_params.cwd.clone(),
+                ExecCommandSource::UserShell,
+                true,
+            );
+            emitter
+                .begin(ToolEventCtx::new(
+                    session.as_ref(),
+                    turn_context.as_ref(),
+                    &call_id,
+                    None,
+                ))
+                .await;
+
+            let Ok(out) = run_shell_request(
+                &exec_params,
+                session.as_ref(),
+                turn_context.as_ref(),
+                &call_id,
+                RECIPE_TOOL_NAME,
+            )
+            .or_cancel(&cancellation_token)
+            .await
+            else {
+                // The interrupt handler reports the aborted turn.
+                return None;
+            };
+
+            let output = match &out {
+                Ok(output) => Some(output.clone()),
+                Err(ToolError::Codex(AishErr::Sandbox(
+                    SandboxErr::Denied { output } | SandboxErr::Timeout { output },
+                ))) => Some(output.as_ref().clone()),
+                Err(_) => None,
+            };
+            if let Some(output) = output {
+                let items = [user_shell_command_record_item(
+                    raw_command,
+                    &output,
+                    &turn_context,
+                )];
+                session
+                    .record_conversation_items(turn_context.as_ref(), &items)
+                    .await;
+            }
+
+            let event_ctx =
+                ToolEventCtx::new(session.as_ref(), turn_context.as_ref(), &call_id, None);
+            if emitter.finish(event_ctx, out).await.is_err() {
+                let remaining = total - idx - 1;
+                let mut message = format!(
+                    "Recipe `{}` stopped at command {} of {total}.",
+                    self.name,
+                    idx + 1
+                );
+                if remaining > 0 {
+                    message.push_str(&format!(
+                        " The remaining {remaining} command(s) were not run."
+                    ));
+                }
+                session
+                    .send_event(
+                        turn_context.as_ref(),
+                        EventMsg::Warning(WarningEvent { message }),
+                    )
+                    .await;
+                break;
+            }
+        }
+        None
+    }
+}
@@ -96,6 +96,14 @@ pub(crate) enum ToolEmitter {
         changes: HashMap<PathBuf, FileChange>,
         auto_approved: bool,
     },
+    UnifiedExec {
+        command: Vec<String>,
+        cwd: PathBuf,
+        source: ExecCommandSource,
+        parsed_cmd: Vec<ParsedCommand>,
+        interaction_input: Option<String>,
+        process_id: Option<String>,
+    },
 }
 
 impl ToolEmitter {
@@ -122,6 +130,24 @@ impl ToolEmitter {
         }
     }
 
+    pub fn unified_exec(
+        command: Vec<String>,
+        cwd: PathBuf,
+        source: ExecCommandSource,
+        interaction_input: Option<String>,
+        process_id: Option<String>,
+    ) -> Self {
+        let parsed_cmd = parse_command(&command);
+        Self::UnifiedExec {
+            command,
+            cwd,
+            source,
+            parsed_cmd,
+            interaction_input,
+            process_id,
+        }
+    }
+
     pub async fn emit(&self, ctx: ToolEventCtx<'_>, stage: ToolEventStage) {
         match (self, stage) {
             (
@@ -141,6 +167,31 @@ impl ToolEmitter {
                 )
                 .await;
             }
+            (
+                Self::UnifiedExec {
+                    command,
+                    cwd,
+                    source,
+                    parsed_cmd,
+                    interaction_input,
+                    process_id,
+                },
+                stage,
+            ) => {
+                emit_exec_stage(
+                    ctx,
+                    ExecCommandInput::new(
+                        command,
+                        cwd.as_path(),
+                        parsed_cmd,
+                        *source,
+                        interaction_input.as_deref(),
+                        process_id.as_deref(),
+                    ),
+                    stage,
+                )
+                .await;
+            }
 
             (
                 Self::ApplyPatch {
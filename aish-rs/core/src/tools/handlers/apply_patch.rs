@@ -6,6 +6,7 @@ use crate::aish::TurnContext;
 use crate::apply_patch;
 use crate::apply_patch::InternalApplyPatchInvocation;
 use crate::apply_patch::convert_apply_patch_to_protocol;
+use crate::apply_patch::touched_paths;
 use crate::client_common::tools::FreeformTool;
 use crate::client_common::tools::FreeformToolFormat;
 use crate::client_common::tools::ResponsesApiTool;
@@ -81,8 +82,14 @@ impl ToolHandler for ApplyPatchHandler {
         let command = vec!["apply_patch".to_string(), patch_input.clone()];
         match aish_apply_patch::maybe_parse_apply_patch_verified(&command, &cwd) {
             aish_apply_patch::MaybeApplyPatchVerified::Body(changes) => {
-                match apply_patch::apply_patch(session.as_ref(), turn.as_ref(), &call_id, changes)
-                    .await
+                match apply_patch::apply_patch(
+                    session.as_ref(),
+                    turn.as_ref(),
+                    &call_id,
+                    &tool_name,
+                    changes,
+                )
+                .await
                 {
                     InternalApplyPatchInvocation::Output(item) => {
                         let content = item?;
@@ -110,7 +117,9 @@ impl ToolHandler for ApplyPatchHandler {
                             cwd: apply.action.cwd.clone(),
                             timeout_ms: None,
                             user_explicitly_approved: apply.user_explicitly_approved_this_action,
+                            user_decision: apply.user_decision.clone(),
                             codex_exe: turn.aish_linux_sandbox_exe.clone(),
+                            touched_paths: touched_paths(&apply.action),
                         };
 
                         let mut orchestrator = ToolOrchestrator::new();
@@ -178,7 +187,7 @@ pub(crate) async fn intercept_apply_patch(
                     turn,
                 )
                 .await;
-            match apply_patch::apply_patch(session, turn, call_id, changes).await {
+            match apply_patch::apply_patch(session, turn, call_id, tool_name, changes).await {
                 InternalApplyPatchInvocation::Output(item) => {
                     let content = item?;
                     Ok(Some(ToolOutput::Function {
@@ -201,7 +210,9 @@ pub(crate) async fn intercept_apply_patch(
                         cwd: apply.action.cwd.clone(),
                         timeout_ms,
                         user_explicitly_approved: apply.user_explicitly_approved_this_action,
+                        user_decision: apply.user_decision.clone(),
                         codex_exe: turn.aish_linux_sandbox_exe.clone(),
+                        touched_paths: touched_paths(&apply.action),
                     };
 
                     let mut orchestrator = ToolOrchestrator::new();
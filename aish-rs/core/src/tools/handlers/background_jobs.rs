@@ -0,0 +1,98 @@
+use async_trait::async_trait;
+use serde::Deserialize;
+
+use crate::function_tool::FunctionCallError;
+use crate::tools::context::ToolInvocation;
+use crate::tools::context::ToolOutput;
+use crate::tools::context::ToolPayload;
+use crate::tools::registry::ToolHandler;
+use crate::tools::registry::ToolKind;
+use crate::truncate::TruncationPolicy;
+
+/// Handles `job_status`, `job_output` and `job_kill` for jobs started with
+/// `background: true`.
+pub struct BackgroundJobsHandler;
+
+#[derive(Debug, Deserialize)]
+struct JobStatusArgs {
+    #[serde(default)]
+    job_id: Option<u64>,
+}
+
+#[derive(Debug, Deserialize)]
+struct JobOutputArgs {
+    job_id: u64,
+    #[serde(default)]
+    max_output_tokens: Option<usize>,
+}
+
+#[derive(Debug, Deserialize)]
+struct JobKillArgs {
+    job_id: u64,
+}
+
+fn parse_arguments<T: for<'de> Deserialize<'de>>(arguments: &str) -> Result<T, FunctionCallError> {
+    serde_json::from_str(arguments).map_err(|err| {
+        FunctionCallError::RespondToModel(format!("failed to parse function arguments: {err:?}"))
+    })
+}
+
+#[async_trait]
+impl ToolHandler for BackgroundJobsHandler {
+    fn kind(&self) -> ToolKind {
+        ToolKind::Function
+    }
+
+    async fn is_mutating(&self, invocation: &ToolInvocation) -> bool {
+        invocation.tool_name == "job_kill"
+    }
+
+    async fn handle(&self, invocation: ToolInvocation) -> Result<ToolOutput, FunctionCallError> {
+        let ToolInvocation {
+            session,
+            turn,
+            tool_name,
+            payload,
+            ..
+        } = invocation;
+
+        let ToolPayload::Function { arguments } = payload else {
+            return Err(FunctionCallError::RespondToModel(format!(
+                "unsupported payload for background jobs handler: {tool_name}"
+            )));
+        };
+
+        let jobs = &session.services.background_jobs;
+        let result = match tool_name.as_str() {
+            "job_status" => {
+                let args: JobStatusArgs = parse_arguments(&arguments)?;
+                jobs.status(args.job_id)
+            }
+            "job_output" => {
+                let args: JobOutputArgs = parse_arguments(&arguments)?;
+                let max_bytes = args
+                    .max_output_tokens
+                    .map(TruncationPolicy::Tokens)
+                    .unwrap_or(turn.truncation_policy)
+                    .byte_budget();
+                jobs.output(args.job_id, max_bytes)
+            }
+            "job_kill" => {
+                let args: JobKillArgs = parse_arguments(&arguments)?;
+                jobs.kill(args.job_id).await
+            }
+            other => {
+                return Err(FunctionCallError::RespondToModel(format!(
+                    "unsupported background job tool: {other}"
+                )));
+            }
+        };
+        let content = result.map_err(|err| FunctionCallError::RespondToModel(err.to_string()))?;
+
+        Ok(ToolOutput::Function {
+            content,
+            content_items: None,
+            success: Some(true),
+        })
+    }
+}
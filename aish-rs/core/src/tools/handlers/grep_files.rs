@@ -6,6 +6,7 @@ use serde::Deserialize;
 use tokio::process::Command;
 use tokio::time::timeout;
 
+use crate::deny_read::is_read_denied;
 use crate::function_tool::FunctionCallError;
 use crate::tools::context::ToolInvocation;
 use crate::tools::context::ToolOutput;
@@ -73,6 +74,12 @@ impl ToolHandler for GrepFilesHandler {
 
         let limit = args.limit.min(MAX_LIMIT);
         let search_path = turn.resolve_path(args.path.clone());
+        if is_read_denied(&turn.sandbox_policy, &turn.cwd, &search_path) {
+            return Err(FunctionCallError::RespondToModel(format!(
+                "searching `{}` is blocked by the sandbox deny_read setting",
+                search_path.display()
+            )));
+        }
 
         verify_path_exists(&search_path).await?;
 
@@ -84,8 +91,19 @@ impl ToolHandler for GrepFilesHandler {
             }
         });
 
-        let search_results =
-            run_rg_search(pattern, include.as_deref(), &search_path, limit, &turn.cwd).await?;
+        // Searching a directory still reads the denied files beneath it, and
+        // the list of matching files would reveal their contents pattern by
+        // pattern, so drop them from the results.
+        let is_denied = |path: &Path| is_read_denied(&turn.sandbox_policy, &turn.cwd, path);
+        let search_results = run_rg_search(
+            pattern,
+            include.as_deref(),
+            &search_path,
+            limit,
+            &turn.cwd,
+            &is_denied,
+        )
+        .await?;
 
         if search_results.is_empty() {
             Ok(ToolOutput::Function {
@@ -116,6 +134,7 @@ async fn run_rg_search(
     search_path: &Path,
     limit: usize,
     cwd: &Path,
+    is_denied: &dyn Fn(&Path) -> bool,
 ) -> Result<Vec<String>, FunctionCallError> {
     let mut command = Command::new("rg");
     command
@@ -144,7 +163,7 @@ async fn run_rg_search(
         })?;
 
     match output.status.code() {
-        Some(0) => Ok(parse_results(&output.stdout, limit)),
+        Some(0) => Ok(parse_results(&output.stdout, limit, is_denied)),
         Some(1) => Ok(Vec::new()),
         _ => {
             let stderr = String::from_utf8_lossy(&output.stderr);
@@ -155,14 +174,14 @@ async fn run_rg_search(
     }
 }
 
-fn parse_results(stdout: &[u8], limit: usize) -> Vec<String> {
+fn parse_results(stdout: &[u8], limit: usize, is_denied: &dyn Fn(&Path) -> bool) -> Vec<String> {
     let mut results = Vec::new();
     for line in stdout.split(|byte| *byte == b'\n') {
         if line.is_empty() {
             continue;
         }
         if let Ok(text) = std::str::from_utf8(line) {
-            if text.is_empty() {
+            if text.is_empty() || is_denied(Path::new(text)) {
                 continue;
             }
             results.push(text.to_string());
@@ -177,13 +196,14 @@ fn parse_results(stdout: &[u8], limit: usize) -> Vec<String> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::protocol::SandboxPolicy;
     use std::process::Command as StdCommand;
     use tempfile::tempdir;
 
     #[test]
     fn parses_basic_results() {
         let stdout = b"/tmp/file_a.rs\n/tmp/file_b.rs\n";
-        let parsed = parse_results(stdout, 10);
+        let parsed = parse_results(stdout, 10, &|_| false);
         assert_eq!(
             parsed,
             vec!["/tmp/file_a.rs".to_string(), "/tmp/file_b.rs".to_string()]
@@ -193,7 +213,7 @@ mod tests {
     #[test]
     fn parse_truncates_after_limit() {
         let stdout = b"/tmp/file_a.rs\n/tmp/file_b.rs\n/tmp/file_c.rs\n";
-        let parsed = parse_results(stdout, 2);
+        let parsed = parse_results(stdout, 2, &|_| false);
         assert_eq!(
             parsed,
             vec!["/tmp/file_a.rs".to_string(), "/tmp/file_b.rs".to_string()]
@@ -211,7 +231,7 @@ mod tests {
         std::fs::write(dir.join("match_two.txt"), "alpha delta").unwrap();
         std::fs::write(dir.join("other.txt"), "omega").unwrap();
 
-        let results = run_rg_search("alpha", None, dir, 10, dir).await?;
+        let results = run_rg_search("alpha", None, dir, 10, dir, &|_| false).await?;
         assert_eq!(results.len(), 2);
         assert!(results.iter().any(|path| path.ends_with("match_one.txt")));
         assert!(results.iter().any(|path| path.ends_with("match_two.txt")));
@@ -228,7 +248,7 @@ mod tests {
         std::fs::write(dir.join("match_one.rs"), "alpha beta gamma").unwrap();
         std::fs::write(dir.join("match_two.txt"), "alpha delta").unwrap();
 
-        let results = run_rg_search("alpha", Some("*.rs"), dir, 10, dir).await?;
+        let results = run_rg_search("alpha", Some("*.rs"), dir, 10, dir, &|_| false).await?;
         assert_eq!(results.len(), 1);
         assert!(results.iter().all(|path| path.ends_with("match_one.rs")));
         Ok(())
@@ -245,7 +265,7 @@ mod tests {
         std::fs::write(dir.join("two.txt"), "alpha two").unwrap();
         std::fs::write(dir.join("three.txt"), "alpha three").unwrap();
 
-        let results = run_rg_search("alpha", None, dir, 2, dir).await?;
+        let results = run_rg_search("alpha", None, dir, 2, dir, &|_| false).await?;
         assert_eq!(results.len(), 2);
         Ok(())
     }
@@ -259,11 +279,48 @@ mod tests {
         let dir = temp.path();
         std::fs::write(dir.join("one.txt"), "omega").unwrap();
 
-        let results = run_rg_search("alpha", None, dir, 5, dir).await?;
+        let results = run_rg_search("alpha", None, dir, 5, dir, &|_| false).await?;
         assert!(results.is_empty());
         Ok(())
     }
 
+    #[tokio::test]
+    async fn run_search_drops_read_denied_files() -> anyhow::Result<()> {
+        if !rg_available() {
+            return Ok(());
+        }
+        let temp = tempdir().expect("create temp dir");
+        let dir = temp.path();
+        std::fs::write(dir.join(".env"), "API_KEY=alpha-secret").unwrap();
+        std::fs::write(dir.join("credentials.txt"), "API_KEY=alpha-secret").unwrap();
+        std::fs::write(dir.join("config.txt"), "alpha").unwrap();
+        let policy = SandboxPolicy::CurrentDirWrite {
+            network_access: false,
+            exclude_tmpdir_env_var: true,
+            exclude_slash_tmp: true,
+            network_allowlist: Vec::new(),
+            writable_roots: Vec::new(),
+            deny_read: vec![".env".to_string(), "credentials.txt".to_string()],
+        };
+        let is_denied = |path: &Path| is_read_denied(&policy, dir, path);
+
+        let results = run_rg_search("alpha", None, Path::new("."), 10, dir, &is_denied).await?;
+        assert_eq!(results, vec!["./config.txt".to_string()]);
+        let results = run_rg_search("API_KEY=", None, Path::new("."), 10, dir, &is_denied).await?;
+        assert!(results.is_empty(), "denied files leaked: {results:?}");
+        let results = run_rg_search(
+            "API_KEY=",
+            Some(".env"),
+            Path::new("."),
+            10,
+            dir,
+            &is_denied,
+        )
+        .await?;
+        assert!(results.is_empty(), "denied files leaked: {results:?}");
+        Ok(())
+    }
+
     fn rg_available() -> bool {
         StdCommand::new("rg")
             .arg("--version")
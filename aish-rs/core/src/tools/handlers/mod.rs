@@ -1,4 +1,5 @@
 pub mod apply_patch;
+mod background_jobs;
 mod grep_files;
 mod list_dir;
 mod mcp;
@@ -6,12 +7,15 @@ mod mcp_resource;
 mod plan;
 mod read_file;
 mod shell;
+mod shell_history;
 mod test_sync;
+mod unified_exec;
 mod view_image;
 
 pub use plan::PLAN_TOOL;
 
 pub use apply_patch::ApplyPatchHandler;
+pub use background_jobs::BackgroundJobsHandler;
 pub use grep_files::GrepFilesHandler;
 pub use list_dir::ListDirHandler;
 pub use mcp::McpHandler;
@@ -20,5 +24,8 @@ pub use plan::PlanHandler;
 pub use read_file::ReadFileHandler;
 pub use shell::ShellCommandHandler;
 pub use shell::ShellHandler;
+pub(crate) use shell::run_shell_request;
+pub use shell_history::ShellHistoryHandler;
 pub use test_sync::TestSyncHandler;
+pub use unified_exec::UnifiedExecHandler;
 pub use view_image::ViewImageHandler;
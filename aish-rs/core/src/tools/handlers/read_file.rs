@@ -5,6 +5,7 @@ use aish_utils_string::take_bytes_at_char_boundary;
 use async_trait::async_trait;
 use serde::Deserialize;
 
+use crate::deny_read::is_read_denied;
 use crate::function_tool::FunctionCallError;
 use crate::tools::context::ToolInvocation;
 use crate::tools::context::ToolOutput;
@@ -96,7 +97,7 @@ impl ToolHandler for ReadFileHandler {
     }
 
     async fn handle(&self, invocation: ToolInvocation) -> Result<ToolOutput, FunctionCallError> {
-        let ToolInvocation { payload, .. } = invocation;
+        let ToolInvocation { payload, turn, .. } = invocation;
 
         let arguments = match payload {
             ToolPayload::Function { arguments } => arguments,
@@ -139,6 +140,11 @@ impl ToolHandler for ReadFileHandler {
                 "file_path must be an absolute path".to_string(),
             ));
         }
+        if is_read_denied(&turn.sandbox_policy, &turn.cwd, &path) {
+            return Err(FunctionCallError::RespondToModel(format!(
+                "reading `{file_path}` is blocked by the sandbox deny_read setting"
+            )));
+        }
 
         let collected = match mode {
             ReadMode::Slice => slice::read(&path, offset, limit).await?,
@@ -2,9 +2,15 @@ use aish_protocol::models::ShellCommandToolCallParams;
 use aish_protocol::models::ShellToolCallParams;
 use async_trait::async_trait;
 use std::sync::Arc;
+use std::time::Duration;
+use tokio_util::sync::CancellationToken;
 
+use crate::aish::Session;
 use crate::aish::TurnContext;
+use crate::exec::ExecExpiration;
 use crate::exec::ExecParams;
+use crate::exec::ExecToolCallOutput;
+use crate::exec::StreamOutput;
 use crate::exec_env::create_env;
 use crate::function_tool::FunctionCallError;
 use crate::is_safe_command::is_known_safe_command;
@@ -19,9 +25,13 @@ use crate::tools::handlers::apply_patch::intercept_apply_patch;
 use crate::tools::orchestrator::ToolOrchestrator;
 use crate::tools::registry::ToolHandler;
 use crate::tools::registry::ToolKind;
+use crate::tools::runtimes::background_job::BackgroundJobRequest;
+use crate::tools::runtimes::background_job::BackgroundJobRuntime;
 use crate::tools::runtimes::shell::ShellRequest;
 use crate::tools::runtimes::shell::ShellRuntime;
+use crate::tools::sandboxing::ExecApprovalRequirement;
 use crate::tools::sandboxing::ToolCtx;
+use crate::tools::sandboxing::ToolError;
 
 pub struct ShellHandler;
 
@@ -29,10 +39,11 @@ pub struct ShellCommandHandler;
 
 impl ShellHandler {
     fn to_exec_params(params: ShellToolCallParams, turn_context: &TurnContext) -> ExecParams {
+        let expiration = resolve_expiration(params.timeout_ms, &params.command, turn_context);
         ExecParams {
             command: params.command,
             cwd: turn_context.resolve_path(params.workdir.clone()),
-            expiration: params.timeout_ms.into(),
+            expiration,
             env: create_env(&turn_context.shell_environment_policy),
             sandbox_permissions: params.sandbox_permissions.unwrap_or_default(),
             justification: params.justification,
@@ -47,18 +58,40 @@ impl ShellCommandHandler {
         shell.derive_exec_args(command, use_login_shell)
     }
 
+    /// Exec params for running `command` through the user's login shell with
+    /// the configured timeout, as `shell_command` does without overrides.
+    pub(crate) fn exec_params_for_command(
+        session: &Session,
+        turn_context: &TurnContext,
+        command: &str,
+    ) -> ExecParams {
+        Self::to_exec_params(
+            ShellCommandToolCallParams {
+                command: command.to_string(),
+                workdir: None,
+                login: None,
+                timeout_ms: None,
+                sandbox_permissions: None,
+                justification: None,
+                background: None,
+            },
+            session,
+            turn_context,
+        )
+    }
+
     fn to_exec_params(
         params: ShellCommandToolCallParams,
-        session: &crate::aish::Session,
+        session: &Session,
         turn_context: &TurnContext,
     ) -> ExecParams {
         let shell = session.user_shell();
         let command = Self::base_command(shell.as_ref(), &params.command, params.login);
 
         ExecParams {
+            expiration: resolve_expiration(params.timeout_ms, &command, turn_context),
             command,
             cwd: turn_context.resolve_path(params.workdir.clone()),
-            expiration: params.timeout_ms.into(),
             env: create_env(&turn_context.shell_environment_policy),
             sandbox_permissions: params.sandbox_permissions.unwrap_or_default(),
             justification: params.justification,
@@ -67,6 +100,19 @@ impl ShellCommandHandler {
     }
 }
 
+/// An explicit `timeout_ms` from the model wins; otherwise the command's
+/// `[shell_timeouts]` entry (or the configured default) applies.
+fn resolve_expiration(
+    timeout_ms: Option<u64>,
+    command: &[String],
+    turn_context: &TurnContext,
+) -> ExecExpiration {
+    ExecExpiration::Timeout(timeout_ms.map_or_else(
+        || turn_context.shell_timeouts.timeout_for(command),
+        Duration::from_millis,
+    ))
+}
+
 #[async_trait]
 impl ToolHandler for ShellHandler {
     fn kind(&self) -> ToolKind {
@@ -110,6 +156,7 @@ impl ToolHandler for ShellHandler {
                             "failed to parse function arguments: {e:?}"
                         ))
                     })?;
+                let background = params.background.unwrap_or(false);
                 let exec_params = Self::to_exec_params(params, turn.as_ref());
                 Self::run_exec_like(
                     tool_name.as_str(),
@@ -119,6 +166,7 @@ impl ToolHandler for ShellHandler {
                     tracker,
                     call_id,
                     false,
+                    background,
                 )
                 .await
             }
@@ -132,6 +180,7 @@ impl ToolHandler for ShellHandler {
                     tracker,
                     call_id,
                     false,
+                    false,
                 )
                 .await
             }
@@ -185,6 +234,7 @@ impl ToolHandler for ShellCommandHandler {
         let params: ShellCommandToolCallParams = serde_json::from_str(&arguments).map_err(|e| {
             FunctionCallError::RespondToModel(format!("failed to parse function arguments: {e:?}"))
         })?;
+        let background = params.background.unwrap_or(false);
         let exec_params = Self::to_exec_params(params, session.as_ref(), turn.as_ref());
         ShellHandler::run_exec_like(
             tool_name.as_str(),
@@ -194,12 +244,14 @@ impl ToolHandler for ShellCommandHandler {
             tracker,
             call_id,
             true,
+            background,
         )
         .await
     }
 }
 
 impl ShellHandler {
+    #[allow(clippy::too_many_arguments)]
     async fn run_exec_like(
         tool_name: &str,
         exec_params: ExecParams,
@@ -208,6 +260,7 @@ impl ShellHandler {
         tracker: crate::tools::context::SharedTurnDiffTracker,
         call_id: String,
         freeform: bool,
+        background: bool,
     ) -> Result<ToolOutput, FunctionCallError> {
         // Approval policy guard for explicit escalation in non-OnRequest modes.
         if exec_params
@@ -250,39 +303,78 @@ impl ShellHandler {
         let event_ctx = ToolEventCtx::new(session.as_ref(), turn.as_ref(), &call_id, None);
         emitter.begin(event_ctx).await;
 
-        let features = session.features();
-        let exec_approval_requirement = session
-            .services
-            .exec_policy
-            .create_exec_approval_requirement_for_command(
-                &features,
-                &exec_params.command,
-                turn.approval_policy,
-                &turn.sandbox_policy,
-                exec_params.sandbox_permissions,
-            )
-            .await;
-
-        let req = ShellRequest {
-            command: exec_params.command.clone(),
-            cwd: exec_params.cwd.clone(),
-            timeout_ms: exec_params.expiration.timeout_ms(),
-            env: exec_params.env.clone(),
-            sandbox_permissions: exec_params.sandbox_permissions,
-            justification: exec_params.justification.clone(),
-            exec_approval_requirement,
-        };
-        let mut orchestrator = ToolOrchestrator::new();
-        let mut runtime = ShellRuntime::new();
-        let tool_ctx = ToolCtx {
-            session: session.as_ref(),
-            turn: turn.as_ref(),
-            call_id: call_id.clone(),
-            tool_name: tool_name.to_string(),
-        };
-        let out = orchestrator
-            .run(&mut runtime, &req, &tool_ctx, &turn, turn.approval_policy)
-            .await;
+        // Background jobs are ignored (and run in the foreground) unless the
+        // feature that advertises `background` to the model is enabled.
+        if background && turn.tools_config.background_jobs {
+            let exec_approval_requirement =
+                exec_approval_requirement(session.as_ref(), turn.as_ref(), &exec_params).await;
+            let tool_ctx = ToolCtx {
+                session: session.as_ref(),
+                turn: turn.as_ref(),
+                call_id: call_id.clone(),
+                tool_name: tool_name.to_string(),
+            };
+            let mut orchestrator = ToolOrchestrator::new();
+            let cancel = CancellationToken::new();
+            let req = BackgroundJobRequest {
+                command: exec_params.command.clone(),
+                cwd: exec_params.cwd.clone(),
+                env: exec_params.env.clone(),
+                sandbox_permissions: exec_params.sandbox_permissions,
+                justification: exec_params.justification.clone(),
+                exec_approval_requirement,
+                cancel: cancel.clone(),
+            };
+            let out = match orchestrator
+                .run(
+                    &mut BackgroundJobRuntime::new(),
+                    &req,
+                    &tool_ctx,
+                    &turn,
+                    turn.approval_policy,
+                )
+                .await
+            {
+                Ok(exec_env) => session
+                    .services
+                    .background_jobs
+                    .start(
+                        turn.sub_id.clone(),
+                        exec_params.command.clone(),
+                        exec_params.cwd.clone(),
+                        exec_env,
+                        turn.sandbox_policy.clone(),
+                        cancel,
+                    )
+                    .await
+                    .map(background_job_started_output)
+                    .map_err(|err| ToolError::Rejected(err.to_string())),
+                Err(err) => Err(err),
+            };
+            if out.is_ok() {
+                session
+                    .services
+                    .background_jobs
+                    .notify_clients(turn.sub_id.clone())
+                    .await;
+            }
+            let event_ctx = ToolEventCtx::new(session.as_ref(), turn.as_ref(), &call_id, None);
+            let content = emitter.finish(event_ctx, out).await?;
+            return Ok(ToolOutput::Function {
+                content,
+                content_items: None,
+                success: Some(true),
+            });
+        }
+
+        let out = run_shell_request(
+            &exec_params,
+            session.as_ref(),
+            turn.as_ref(),
+            &call_id,
+            tool_name,
+        )
+        .await;
         let event_ctx = ToolEventCtx::new(session.as_ref(), turn.as_ref(), &call_id, None);
         let content = emitter.finish(event_ctx, out).await?;
         Ok(ToolOutput::Function {
@@ -293,6 +385,80 @@ impl ShellHandler {
     }
 }
 
+/// Run `exec_params` in the foreground through the exec policy, approval
+/// grants and the orchestrator. Callers emit the begin/end events.
+pub(crate) async fn run_shell_request(
+    exec_params: &ExecParams,
+    session: &Session,
+    turn: &TurnContext,
+    call_id: &str,
+    tool_name: &str,
+) -> Result<ExecToolCallOutput, ToolError> {
+    let req = ShellRequest {
+        command: exec_params.command.clone(),
+        cwd: exec_params.cwd.clone(),
+        timeout_ms: exec_params.expiration.timeout_ms(),
+        env: exec_params.env.clone(),
+        sandbox_permissions: exec_params.sandbox_permissions,
+        justification: exec_params.justification.clone(),
+        exec_approval_requirement: exec_approval_requirement(session, turn, exec_params).await,
+    };
+    let tool_ctx = ToolCtx {
+        session,
+        turn,
+        call_id: call_id.to_string(),
+        tool_name: tool_name.to_string(),
+    };
+    ToolOrchestrator::new()
+        .run(
+            &mut ShellRuntime::new(),
+            &req,
+            &tool_ctx,
+            turn,
+            turn.approval_policy,
+        )
+        .await
+}
+
+async fn exec_approval_requirement(
+    session: &Session,
+    turn: &TurnContext,
+    exec_params: &ExecParams,
+) -> ExecApprovalRequirement {
+    let features = session.features();
+    let requirement = session
+        .services
+        .exec_policy
+        .create_exec_approval_requirement_for_command(
+            &features,
+            &exec_params.command,
+            &exec_params.cwd,
+            &turn.cwd,
+            turn.approval_policy,
+            &turn.sandbox_policy,
+            exec_params.sandbox_permissions,
+        )
+        .await;
+    session
+        .apply_approval_grants(requirement, &exec_params.command, &exec_params.cwd)
+        .await
+}
+
+/// What the model and clients see when a background job has started.
+fn background_job_started_output(job_id: u64) -> ExecToolCallOutput {
+    let message = format!(
+        "Started background job {job_id}. Use job_status, job_output or job_kill with job_id {job_id}."
+    );
+    ExecToolCallOutput {
+        exit_code: 0,
+        stdout: StreamOutput::new(message.clone()),
+        stderr: StreamOutput::new(String::new()),
+        aggregated_output: StreamOutput::new(message),
+        duration: Duration::ZERO,
+        timed_out: false,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use std::path::PathBuf;
@@ -301,6 +467,7 @@ mod tests {
     use pretty_assertions::assert_eq;
 
     use crate::aish::make_session_and_context;
+    use crate::config::types::ShellTimeoutsToml;
     use crate::exec_env::create_env;
     use crate::is_safe_command::is_known_safe_command;
     use crate::powershell::try_find_powershell_executable_blocking;
@@ -375,6 +542,7 @@ mod tests {
             timeout_ms,
             sandbox_permissions: Some(sandbox_permissions),
             justification: justification.clone(),
+            background: None,
         };
 
         let exec_params = ShellCommandHandler::to_exec_params(params, &session, &turn_context);
@@ -389,6 +557,37 @@ mod tests {
         assert_eq!(exec_params.arg0, None);
     }
 
+    #[tokio::test]
+    async fn shell_command_handler_falls_back_to_configured_timeout() {
+        let (session, mut turn_context) = make_session_and_context().await;
+        turn_context.shell_timeouts = ShellTimeoutsToml {
+            default: Some(42),
+            commands: [("cargo test".to_string(), 900)].into_iter().collect(),
+        }
+        .into();
+
+        let params = |command: &str| ShellCommandToolCallParams {
+            command: command.to_string(),
+            workdir: None,
+            login: None,
+            timeout_ms: None,
+            sandbox_permissions: None,
+            justification: None,
+            background: None,
+        };
+
+        let exec_params = ShellCommandHandler::to_exec_params(
+            params("cargo test -p core"),
+            &session,
+            &turn_context,
+        );
+        assert_eq!(exec_params.expiration.timeout_ms(), Some(900_000));
+
+        let exec_params =
+            ShellCommandHandler::to_exec_params(params("ls"), &session, &turn_context);
+        assert_eq!(exec_params.expiration.timeout_ms(), Some(42_000));
+    }
+
     #[test]
     fn shell_command_handler_respects_explicit_login_flag() {
         let shell = Shell {
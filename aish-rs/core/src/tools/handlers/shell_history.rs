@@ -0,0 +1,609 @@
+use std::collections::HashSet;
+use std::path::Path;
+use std::path::PathBuf;
+
+use async_trait::async_trait;
+use chrono::DateTime;
+use chrono::Utc;
+use regex_lite::Captures;
+use regex_lite::Regex;
+use serde::Deserialize;
+
+use crate::config::types::EnvironmentVariablePattern;
+use crate::config::types::ShellEnvironmentPolicy;
+use crate::exec_env::default_excludes;
+use crate::function_tool::FunctionCallError;
+use crate::tools::context::ToolInvocation;
+use crate::tools::context::ToolOutput;
+use crate::tools::context::ToolPayload;
+use crate::tools::registry::ToolHandler;
+use crate::tools::registry::ToolKind;
+
+pub struct ShellHistoryHandler;
+
+const DEFAULT_LIMIT: usize = 50;
+const MAX_LIMIT: usize = 500;
+const MAX_ENTRY_LENGTH: usize = 1000;
+const REDACTED: &str = "[REDACTED]";
+
+/// zsh escapes special bytes in its history file as `0x83` followed by the
+/// byte xor 0x20.
+const ZSH_META: u8 = 0x83;
+
+fn default_limit() -> usize {
+    DEFAULT_LIMIT
+}
+
+#[derive(Deserialize)]
+struct ShellHistoryArgs {
+    #[serde(default)]
+    query: Option<String>,
+    #[serde(default)]
+    regex: bool,
+    #[serde(default)]
+    shell: Option<HistoryShell>,
+    #[serde(default)]
+    since: Option<String>,
+    #[serde(default)]
+    until: Option<String>,
+    #[serde(default = "default_limit")]
+    limit: usize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum HistoryShell {
+    Bash,
+    Zsh,
+    Fish,
+}
+
+impl HistoryShell {
+    fn name(self) -> &'static str {
+        match self {
+            HistoryShell::Bash => "bash",
+            HistoryShell::Zsh => "zsh",
+            HistoryShell::Fish => "fish",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct HistoryEntry {
+    shell: HistoryShell,
+    /// Seconds since the Unix epoch, when the history format records it.
+    timestamp: Option<i64>,
+    command: String,
+}
+
+enum Matcher {
+    All,
+    Substring(String),
+    Regex(Regex),
+}
+
+impl Matcher {
+    fn matches(&self, command: &str) -> bool {
+        match self {
+            Matcher::All => true,
+            Matcher::Substring(needle) => command.to_lowercase().contains(needle),
+            Matcher::Regex(regex) => regex.is_match(command),
+        }
+    }
+}
+
+#[async_trait]
+impl ToolHandler for ShellHistoryHandler {
+    fn kind(&self) -> ToolKind {
+        ToolKind::Function
+    }
+
+    async fn handle(&self, invocation: ToolInvocation) -> Result<ToolOutput, FunctionCallError> {
+        let ToolInvocation { payload, turn, .. } = invocation;
+
+        let arguments = match payload {
+            ToolPayload::Function { arguments } => arguments,
+            _ => {
+                return Err(FunctionCallError::RespondToModel(
+                    "shell_history handler received unsupported payload".to_string(),
+                ));
+            }
+        };
+
+        let args: ShellHistoryArgs = serde_json::from_str(&arguments).map_err(|err| {
+            FunctionCallError::RespondToModel(format!(
+                "failed to parse function arguments: {err:?}"
+            ))
+        })?;
+
+        if args.limit == 0 {
+            return Err(FunctionCallError::RespondToModel(
+                "limit must be greater than zero".to_string(),
+            ));
+        }
+        let limit = args.limit.min(MAX_LIMIT);
+
+        let matcher = match args.query.as_deref().map(str::trim) {
+            None | Some("") => Matcher::All,
+            Some(query) if args.regex => Matcher::Regex(Regex::new(query).map_err(|err| {
+                FunctionCallError::RespondToModel(format!("invalid regex `{query}`: {err}"))
+            })?),
+            Some(query) => Matcher::Substring(query.to_lowercase()),
+        };
+        let since = parse_time_bound("since", args.since.as_deref())?;
+        let until = parse_time_bound("until", args.until.as_deref())?;
+
+        let shells = match args.shell {
+            Some(shell) => vec![shell],
+            None => vec![HistoryShell::Bash, HistoryShell::Zsh, HistoryShell::Fish],
+        };
+        let mut entries = Vec::new();
+        for shell in shells {
+            let Some(path) = default_history_path(shell) else {
+                continue;
+            };
+            entries.extend(read_history_file(shell, &path).await);
+        }
+
+        let redactor = SecretRedactor::new(&turn.shell_environment_policy);
+        let selected = select_entries(entries, &redactor, &matcher, since, until, limit);
+        if selected.is_empty() {
+            return Ok(ToolOutput::Function {
+                content: "No matching history entries found.".to_string(),
+                content_items: None,
+                success: Some(false),
+            });
+        }
+
+        let lines: Vec<String> = selected.iter().map(format_entry).collect();
+        Ok(ToolOutput::Function {
+            content: lines.join("\n"),
+            content_items: None,
+            success: Some(true),
+        })
+    }
+}
+
+fn parse_time_bound(name: &str, value: Option<&str>) -> Result<Option<i64>, FunctionCallError> {
+    let Some(value) = value.map(str::trim).filter(|value| !value.is_empty()) else {
+        return Ok(None);
+    };
+    DateTime::parse_from_rfc3339(value)
+        .map(|time| Some(time.timestamp()))
+        .map_err(|err| {
+            FunctionCallError::RespondToModel(format!(
+                "{name} must be an RFC 3339 timestamp (got `{value}`): {err}"
+            ))
+        })
+}
+
+fn default_history_path(shell: HistoryShell) -> Option<PathBuf> {
+    let home = dirs::home_dir()?;
+    match shell {
+        HistoryShell::Bash => Some(home.join(".bash_history")),
+        HistoryShell::Zsh => {
+            let dir = std::env::var_os("ZDOTDIR")
+                .map(PathBuf::from)
+                .unwrap_or(home);
+            Some(dir.join(".zsh_history"))
+        }
+        HistoryShell::Fish => {
+            let data_home = std::env::var_os("XDG_DATA_HOME")
+                .map(PathBuf::from)
+                .unwrap_or_else(|| home.join(".local").join("share"));
+            Some(data_home.join("fish").join("fish_history"))
+        }
+    }
+}
+
+async fn read_history_file(shell: HistoryShell, path: &Path) -> Vec<HistoryEntry> {
+    let Ok(bytes) = tokio::fs::read(path).await else {
+        return Vec::new();
+    };
+    match shell {
+        HistoryShell::Bash => parse_bash_history(&String::from_utf8_lossy(&bytes)),
+        HistoryShell::Zsh => parse_zsh_history(&String::from_utf8_lossy(&unmetafy(&bytes))),
+        HistoryShell::Fish => parse_fish_history(&String::from_utf8_lossy(&bytes)),
+    }
+}
+
+/// Plain bash history, optionally with `#<epoch>` lines written when
+/// `HISTTIMEFORMAT` is set.
+fn parse_bash_history(contents: &str) -> Vec<HistoryEntry> {
+    let mut entries = Vec::new();
+    let mut timestamp = None;
+    for line in contents.lines() {
+        if let Some(epoch) = line.strip_prefix('#')
+            && let Ok(epoch) = epoch.trim().parse::<i64>()
+        {
+            timestamp = Some(epoch);
+            continue;
+        }
+        if line.trim().is_empty() {
+            continue;
+        }
+        entries.push(HistoryEntry {
+            shell: HistoryShell::Bash,
+            timestamp: timestamp.take(),
+            command: line.to_string(),
+        });
+    }
+    entries
+}
+
+fn unmetafy(bytes: &[u8]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut iter = bytes.iter();
+    while let Some(&byte) = iter.next() {
+        if byte == ZSH_META {
+            if let Some(&next) = iter.next() {
+                out.push(next ^ 0x20);
+            }
+        } else {
+            out.push(byte);
+        }
+    }
+    out
+}
+
+/// zsh history in either the plain or the `EXTENDED_HISTORY` format
+/// (`: <start>:<elapsed>;<command>`). Multi-line commands continue with a
+/// trailing backslash.
+fn parse_zsh_history(contents: &str) -> Vec<HistoryEntry> {
+    let mut entries = Vec::new();
+    let mut lines = contents.lines();
+    while let Some(line) = lines.next() {
+        let (timestamp, first) = match parse_zsh_extended_prefix(line) {
+            Some((timestamp, command)) => (Some(timestamp), command),
+            None => (None, line),
+        };
+        let mut command = first.to_string();
+        while command.ends_with('\\') {
+            command.pop();
+            command.push('\n');
+            match lines.next() {
+                Some(next) => command.push_str(next),
+                None => break,
+            }
+        }
+        if command.trim().is_empty() {
+            continue;
+        }
+        entries.push(HistoryEntry {
+            shell: HistoryShell::Zsh,
+            timestamp,
+            command,
+        });
+    }
+    entries
+}
+
+fn parse_zsh_extended_prefix(line: &str) -> Option<(i64, &str)> {
+    let rest = line.strip_prefix(": ")?;
+    let (meta, command) = rest.split_once(';')?;
+    let (start, _elapsed) = meta.split_once(':')?;
+    let start = start.trim().parse::<i64>().ok()?;
+    Some((start, command))
+}
+
+/// fish history is a YAML-like list of `- cmd:` / `  when:` records.
+fn parse_fish_history(contents: &str) -> Vec<HistoryEntry> {
+    let mut entries: Vec<HistoryEntry> = Vec::new();
+    for line in contents.lines() {
+        if let Some(command) = line.strip_prefix("- cmd: ") {
+            entries.push(HistoryEntry {
+                shell: HistoryShell::Fish,
+                timestamp: None,
+                command: unescape_fish(command),
+            });
+        } else if let Some(when) = line.trim_start().strip_prefix("when: ")
+            && let Some(entry) = entries.last_mut()
+        {
+            entry.timestamp = when.trim().parse::<i64>().ok();
+        }
+    }
+    entries
+}
+
+fn unescape_fish(command: &str) -> String {
+    let mut out = String::with_capacity(command.len());
+    let mut chars = command.chars();
+    while let Some(ch) = chars.next() {
+        if ch != '\\' {
+            out.push(ch);
+            continue;
+        }
+        match chars.next() {
+            Some('n') => out.push('\n'),
+            Some('\\') => out.push('\\'),
+            Some(other) => {
+                out.push('\\');
+                out.push(other);
+            }
+            None => out.push('\\'),
+        }
+    }
+    out
+}
+
+/// Redact, filter, order (most recent first) and de-duplicate entries.
+/// Matching runs on the redacted command so a query cannot probe a secret
+/// one prefix at a time. Entries without a timestamp are excluded whenever a
+/// time window is requested.
+fn select_entries(
+    entries: Vec<HistoryEntry>,
+    redactor: &SecretRedactor,
+    matcher: &Matcher,
+    since: Option<i64>,
+    until: Option<i64>,
+    limit: usize,
+) -> Vec<HistoryEntry> {
+    let windowed = since.is_some() || until.is_some();
+    let mut matching: Vec<HistoryEntry> = entries
+        .into_iter()
+        .rev()
+        .filter(|entry| match entry.timestamp {
+            Some(ts) => {
+                since.is_none_or(|since| ts >= since) && until.is_none_or(|until| ts <= until)
+            }
+            None => !windowed,
+        })
+        .map(|entry| HistoryEntry {
+            command: redactor.redact(&entry.command),
+            ..entry
+        })
+        .filter(|entry| matcher.matches(&entry.command))
+        .collect();
+    matching.sort_by_key(|entry| std::cmp::Reverse(entry.timestamp.unwrap_or(i64::MIN)));
+
+    let mut seen = HashSet::new();
+    matching
+        .into_iter()
+        .filter(|entry| seen.insert(entry.command.clone()))
+        .take(limit)
+        .collect()
+}
+
+fn format_entry(entry: &HistoryEntry) -> String {
+    let when = entry
+        .timestamp
+        .and_then(|ts| DateTime::<Utc>::from_timestamp(ts, 0))
+        .map(|time| time.format("%Y-%m-%d %H:%M:%S UTC").to_string())
+        .unwrap_or_else(|| "unknown time".to_string());
+    let mut command = entry.command.clone();
+    if command.len() > MAX_ENTRY_LENGTH {
+        let cut = aish_utils_string::take_bytes_at_char_boundary(&command, MAX_ENTRY_LENGTH).len();
+        command.truncate(cut);
+        command.push_str("...");
+    }
+    format!("[{when}] {}: {command}", entry.shell.name())
+}
+
+/// Masks values assigned to names that the shell environment policy treats
+/// as sensitive (`*KEY*`, `*SECRET*`, `*TOKEN*` plus any configured
+/// excludes), e.g. `export API_TOKEN=abc` or `--api-key abc`.
+struct SecretRedactor {
+    patterns: Vec<EnvironmentVariablePattern>,
+    assignment: Regex,
+    flag_value: Regex,
+}
+
+impl SecretRedactor {
+    #[allow(clippy::expect_used)]
+    fn new(policy: &ShellEnvironmentPolicy) -> Self {
+        let mut patterns = default_excludes();
+        patterns.extend(policy.exclude.iter().cloned());
+        Self {
+            patterns,
+            assignment: Regex::new(
+                r#"(?P<name>-{0,2}[A-Za-z_][A-Za-z0-9_-]*)=(?P<value>'[^']*'|"[^"]*"|\S+)"#,
+            )
+            .expect("valid assignment regex"),
+            flag_value: Regex::new(
+                r#"(?P<name>--?[A-Za-z][A-Za-z0-9_-]*)(?P<sep>\s+)(?P<value>'[^']*'|"[^"]*"|[^\s-]\S*)"#,
+            )
+            .expect("valid flag regex"),
+        }
+    }
+
+    fn is_sensitive(&self, name: &str) -> bool {
+        let name = name.trim_start_matches('-').replace('-', "_");
+        self.patterns.iter().any(|pattern| pattern.matches(&name))
+    }
+
+    fn redact(&self, command: &str) -> String {
+        let command = self.assignment.replace_all(command, |caps: &Captures| {
+            if self.is_sensitive(&caps["name"]) {
+                format!("{}={REDACTED}", &caps["name"])
+            } else {
+                caps[0].to_string()
+            }
+        });
+        self.flag_value
+            .replace_all(&command, |caps: &Captures| {
+                if self.is_sensitive(&caps["name"]) {
+                    format!("{}{}{REDACTED}", &caps["name"], &caps["sep"])
+                } else {
+                    caps[0].to_string()
+                }
+            })
+            .into_owned()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn entry(shell: HistoryShell, timestamp: Option<i64>, command: &str) -> HistoryEntry {
+        HistoryEntry {
+            shell,
+            timestamp,
+            command: command.to_string(),
+        }
+    }
+
+    #[test]
+    fn parses_bash_history_with_and_without_timestamps() {
+        let entries = parse_bash_history("ls\n#1700000000\ndocker build .\n\ngit status\n");
+        assert_eq!(
+            entries,
+            vec![
+                entry(HistoryShell::Bash, None, "ls"),
+                entry(HistoryShell::Bash, Some(1_700_000_000), "docker build ."),
+                entry(HistoryShell::Bash, None, "git status"),
+            ]
+        );
+    }
+
+    #[test]
+    fn parses_zsh_extended_and_multiline_history() {
+        let entries = parse_zsh_history(
+            ": 1700000000:0;echo one\n: 1700000100:3;for f in *; do\\\n  echo $f\\\ndone\nplain\n",
+        );
+        assert_eq!(
+            entries,
+            vec![
+                entry(HistoryShell::Zsh, Some(1_700_000_000), "echo one"),
+                entry(
+                    HistoryShell::Zsh,
+                    Some(1_700_000_100),
+                    "for f in *; do\n  echo $f\ndone"
+                ),
+                entry(HistoryShell::Zsh, None, "plain"),
+            ]
+        );
+    }
+
+    #[test]
+    fn unmetafies_zsh_bytes() {
+        let bytes = [b'e', b'c', b'h', b'o', b' ', ZSH_META, 0xc3 ^ 0x20, 0xa9];
+        assert_eq!(String::from_utf8(unmetafy(&bytes)).unwrap(), "echo é");
+    }
+
+    #[test]
+    fn parses_fish_history() {
+        let entries = parse_fish_history(
+            "- cmd: cargo test\n  when: 1700000000\n- cmd: echo a\\nb\n  when: 1700000005\n  paths:\n    - a\n",
+        );
+        assert_eq!(
+            entries,
+            vec![
+                entry(HistoryShell::Fish, Some(1_700_000_000), "cargo test"),
+                entry(HistoryShell::Fish, Some(1_700_000_005), "echo a\nb"),
+            ]
+        );
+    }
+
+    #[test]
+    fn select_orders_most_recent_first_and_dedupes() {
+        let entries = vec![
+            entry(HistoryShell::Zsh, Some(10), "docker build -t app ."),
+            entry(HistoryShell::Zsh, Some(20), "ls"),
+            entry(HistoryShell::Zsh, Some(30), "docker build -t app ."),
+            entry(HistoryShell::Bash, None, "docker ps"),
+        ];
+        let selected = select_entries(
+            entries,
+            &SecretRedactor::new(&ShellEnvironmentPolicy::default()),
+            &Matcher::Substring("docker".to_string()),
+            None,
+            None,
+            10,
+        );
+        assert_eq!(
+            selected,
+            vec![
+                entry(HistoryShell::Zsh, Some(30), "docker build -t app ."),
+                entry(HistoryShell::Bash, None, "docker ps"),
+            ]
+        );
+    }
+
+    #[test]
+    fn select_applies_time_window() {
+        let entries = vec![
+            entry(HistoryShell::Zsh, Some(10), "old"),
+            entry(HistoryShell::Zsh, Some(20), "mid"),
+            entry(HistoryShell::Zsh, Some(30), "new"),
+            entry(HistoryShell::Bash, None, "undated"),
+        ];
+        let selected = select_entries(
+            entries,
+            &SecretRedactor::new(&ShellEnvironmentPolicy::default()),
+            &Matcher::All,
+            Some(15),
+            Some(25),
+            10,
+        );
+        assert_eq!(selected, vec![entry(HistoryShell::Zsh, Some(20), "mid")]);
+    }
+
+    #[test]
+    fn select_matches_only_the_redacted_command() {
+        let entries = vec![
+            entry(HistoryShell::Zsh, Some(10), "export API_TOKEN=ghp_abc"),
+            entry(HistoryShell::Zsh, Some(20), "export API_TOKEN=ghp_xyz"),
+        ];
+        let redactor = SecretRedactor::new(&ShellEnvironmentPolicy::default());
+        let probe = select_entries(
+            entries.clone(),
+            &redactor,
+            &Matcher::Substring("token=ghp_a".to_string()),
+            None,
+            None,
+            10,
+        );
+        assert_eq!(probe, Vec::new());
+
+        let selected = select_entries(
+            entries,
+            &redactor,
+            &Matcher::Substring("api_token".to_string()),
+            None,
+            None,
+            10,
+        );
+        assert_eq!(
+            selected,
+            vec![entry(
+                HistoryShell::Zsh,
+                Some(20),
+                &format!("export API_TOKEN={REDACTED}")
+            )]
+        );
+    }
+
+    #[test]
+    fn redacts_sensitive_assignments_and_flags() {
+        let redactor = SecretRedactor::new(&ShellEnvironmentPolicy::default());
+        assert_eq!(
+            redactor.redact("export GITHUB_TOKEN=ghp_abc && PATH=/bin make"),
+            "export GITHUB_TOKEN=[REDACTED] && PATH=/bin make"
+        );
+        assert_eq!(
+            redactor.redact("curl --api-key 'abc def' --header x https://example.com"),
+            "curl --api-key [REDACTED] --header x https://example.com"
+        );
+        assert_eq!(
+            redactor.redact("tool --client-secret=s3cr3t"),
+            "tool --client-secret=[REDACTED]"
+        );
+    }
+
+    #[test]
+    fn redacts_configured_excludes() {
+        let policy = ShellEnvironmentPolicy {
+            exclude: vec![EnvironmentVariablePattern::new_case_insensitive(
+                "*PASSWORD*",
+            )],
+            ..Default::default()
+        };
+        let redactor = SecretRedactor::new(&policy);
+        assert_eq!(
+            redactor.redact("DB_PASSWORD=hunter2 psql"),
+            "DB_PASSWORD=[REDACTED] psql"
+        );
+    }
+}
@@ -0,0 +1,308 @@
+use std::sync::Arc;
+use std::time::Instant;
+
+use async_trait::async_trait;
+use serde::Deserialize;
+
+use crate::aish::Session;
+use crate::aish::TurnContext;
+use crate::exec::ExecToolCallOutput;
+use crate::exec::StreamOutput;
+use crate::exec_env::create_env;
+use crate::function_tool::FunctionCallError;
+use crate::is_safe_command::is_known_safe_command;
+use crate::protocol::ExecCommandSource;
+use crate::sandboxing::SandboxPermissions;
+use crate::tools::context::ToolInvocation;
+use crate::tools::context::ToolOutput;
+use crate::tools::context::ToolPayload;
+use crate::tools::events::ToolEmitter;
+use crate::tools::events::ToolEventCtx;
+use crate::tools::events::ToolEventStage;
+use crate::tools::orchestrator::ToolOrchestrator;
+use crate::tools::registry::ToolHandler;
+use crate::tools::registry::ToolKind;
+use crate::tools::runtimes::unified_exec::UnifiedExecRequest;
+use crate::tools::runtimes::unified_exec::UnifiedExecRuntime;
+use crate::tools::sandboxing::ToolCtx;
+use crate::tools::sandboxing::ToolError;
+use crate::truncate::TruncationPolicy;
+use crate::unified_exec::DEFAULT_EXEC_COMMAND_YIELD_TIME_MS;
+use crate::unified_exec::DEFAULT_WRITE_STDIN_YIELD_TIME_MS;
+use crate::unified_exec::UnifiedExecResponse;
+use crate::unified_exec::clamp_yield_time;
+
+/// Handles both `exec_command` and `write_stdin`.
+pub struct UnifiedExecHandler;
+
+#[derive(Debug, Deserialize)]
+struct ExecCommandArgs {
+    cmd: String,
+    #[serde(default)]
+    workdir: Option<String>,
+    #[serde(default = "default_login")]
+    login: bool,
+    #[serde(default)]
+    yield_time_ms: Option<u64>,
+    #[serde(default)]
+    max_output_tokens: Option<usize>,
+    #[serde(default)]
+    sandbox_permissions: Option<SandboxPermissions>,
+    #[serde(default)]
+    justification: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct WriteStdinArgs {
+    session_id: i32,
+    #[serde(default)]
+    chars: String,
+    #[serde(default)]
+    yield_time_ms: Option<u64>,
+    #[serde(default)]
+    max_output_tokens: Option<usize>,
+}
+
+fn default_login() -> bool {
+    true
+}
+
+fn parse_arguments<T: for<'de> Deserialize<'de>>(arguments: &str) -> Result<T, FunctionCallError> {
+    serde_json::from_str(arguments).map_err(|err| {
+        FunctionCallError::RespondToModel(format!("failed to parse function arguments: {err:?}"))
+    })
+}
+
+/// Size of one output page: the caller's token budget when given, otherwise
+/// the turn's tool output truncation policy.
+fn page_bytes(max_output_tokens: Option<usize>, turn: &TurnContext) -> usize {
+    max_output_tokens
+        .map(TruncationPolicy::Tokens)
+        .unwrap_or(turn.truncation_policy)
+        .byte_budget()
+}
+
+#[async_trait]
+impl ToolHandler for UnifiedExecHandler {
+    fn kind(&self) -> ToolKind {
+        ToolKind::Function
+    }
+
+    async fn is_mutating(&self, invocation: &ToolInvocation) -> bool {
+        let ToolPayload::Function { arguments } = &invocation.payload else {
+            return true;
+        };
+        if invocation.tool_name != "exec_command" {
+            // Input sent to a running process can do anything.
+            return true;
+        }
+        serde_json::from_str::<ExecCommandArgs>(arguments)
+            .map(|args| {
+                let shell = invocation.session.user_shell();
+                !is_known_safe_command(&shell.derive_exec_args(&args.cmd, args.login))
+            })
+            .unwrap_or(true)
+    }
+
+    async fn handle(&self, invocation: ToolInvocation) -> Result<ToolOutput, FunctionCallError> {
+        let ToolInvocation {
+            session,
+            turn,
+            call_id,
+            tool_name,
+            payload,
+            ..
+        } = invocation;
+
+        let ToolPayload::Function { arguments } = payload else {
+            return Err(FunctionCallError::RespondToModel(format!(
+                "unsupported payload for unified exec handler: {tool_name}"
+            )));
+        };
+
+        let content = match tool_name.as_str() {
+            "exec_command" => {
+                let args: ExecCommandArgs = parse_arguments(&arguments)?;
+                exec_command(args, session, turn, call_id, &tool_name).await?
+            }
+            "write_stdin" => {
+                let args: WriteStdinArgs = parse_arguments(&arguments)?;
+                write_stdin(args, session, turn, call_id).await?
+            }
+            other => {
+                return Err(FunctionCallError::RespondToModel(format!(
+                    "unsupported unified exec tool: {other}"
+                )));
+            }
+        };
+
+        Ok(ToolOutput::Function {
+            content,
+            content_items: None,
+            success: Some(true),
+        })
+    }
+}
+
+async fn exec_command(
+    args: ExecCommandArgs,
+    session: Arc<Session>,
+    turn: Arc<TurnContext>,
+    call_id: String,
+    tool_name: &str,
+) -> Result<String, FunctionCallError> {
+    let started_at = Instant::now();
+    let sandbox_permissions = args.sandbox_permissions.unwrap_or_default();
+    // Approval policy guard for explicit escalation in non-OnRequest modes.
+    if sandbox_permissions.requires_escalated_permissions()
+        && !matches!(
+            turn.approval_policy,
+            aish_protocol::protocol::AskForApproval::OnRequest
+        )
+    {
+        return Err(FunctionCallError::RespondToModel(format!(
+            "approval policy is {policy:?}; reject command — you should not ask for escalated permissions if the approval policy is {policy:?}",
+            policy = turn.approval_policy
+        )));
+    }
+
+    let command = session.user_shell().derive_exec_args(&args.cmd, args.login);
+    let cwd = turn.resolve_path(args.workdir.clone());
+
+    let features = session.features();
+    let exec_approval_requirement = session
+        .services
+        .exec_policy
+        .create_exec_approval_requirement_for_command(
+            &features,
+            &command,
+            &cwd,
+            &turn.cwd,
+            turn.approval_policy,
+            &turn.sandbox_policy,
+            sandbox_permissions,
+        )
+        .await;
+    let exec_approval_requirement = session
+        .apply_approval_grants(exec_approval_requirement, &command, &cwd)
+        .await;
+    let req = UnifiedExecRequest {
+        command: command.clone(),
+        cwd: cwd.clone(),
+        env: create_env(&turn.shell_environment_policy),
+        sandbox_permissions,
+        justification: args.justification.clone(),
+        exec_approval_requirement,
+    };
+    let tool_ctx = ToolCtx {
+        session: session.as_ref(),
+        turn: turn.as_ref(),
+        call_id: call_id.clone(),
+        tool_name: tool_name.to_string(),
+    };
+    let spawned = ToolOrchestrator::new()
+        .run(
+            &mut UnifiedExecRuntime::new(),
+            &req,
+            &tool_ctx,
+            &turn,
+            turn.approval_policy,
+        )
+        .await
+        .map_err(|err| match err {
+            ToolError::Rejected(msg) if msg == "rejected by user" => {
+                FunctionCallError::RespondToModel("exec command rejected by user".to_string())
+            }
+            ToolError::Rejected(msg) => FunctionCallError::RespondToModel(msg),
+            ToolError::Codex(err) => {
+                FunctionCallError::RespondToModel(format!("execution error: {err:?}"))
+            }
+        })?;
+
+    let manager = &session.services.unified_exec_manager;
+    let session_id = manager
+        .open_session(spawned, command.clone(), cwd.clone())
+        .await
+        .map_err(|err| FunctionCallError::RespondToModel(err.to_string()))?;
+
+    let emitter = ToolEmitter::unified_exec(
+        command,
+        cwd,
+        ExecCommandSource::UnifiedExecStartup,
+        None,
+        Some(session_id.to_string()),
+    );
+    let event_ctx = ToolEventCtx::new(session.as_ref(), turn.as_ref(), &call_id, None);
+    emitter.begin(event_ctx).await;
+
+    let response = manager
+        .collect_output(
+            session_id,
+            started_at,
+            clamp_yield_time(args.yield_time_ms, DEFAULT_EXEC_COMMAND_YIELD_TIME_MS),
+            page_bytes(args.max_output_tokens, &turn),
+        )
+        .await
+        .map_err(|err| FunctionCallError::RespondToModel(err.to_string()))?;
+    emitter
+        .emit(event_ctx, ToolEventStage::Success(exec_output(&response)))
+        .await;
+    Ok(response.format_for_model())
+}
+
+async fn write_stdin(
+    args: WriteStdinArgs,
+    session: Arc<Session>,
+    turn: Arc<TurnContext>,
+    call_id: String,
+) -> Result<String, FunctionCallError> {
+    let started_at = Instant::now();
+    let manager = &session.services.unified_exec_manager;
+    let (command, cwd) = manager
+        .session_command(args.session_id)
+        .await
+        .map_err(|err| FunctionCallError::RespondToModel(err.to_string()))?;
+    if !args.chars.is_empty() {
+        manager
+            .write_stdin(args.session_id, &args.chars)
+            .await
+            .map_err(|err| FunctionCallError::RespondToModel(err.to_string()))?;
+    }
+
+    let emitter = ToolEmitter::unified_exec(
+        command,
+        cwd,
+        ExecCommandSource::UnifiedExecInteraction,
+        Some(args.chars.clone()),
+        Some(args.session_id.to_string()),
+    );
+    let event_ctx = ToolEventCtx::new(session.as_ref(), turn.as_ref(), &call_id, None);
+    emitter.begin(event_ctx).await;
+
+    let response = manager
+        .collect_output(
+            args.session_id,
+            started_at,
+            clamp_yield_time(args.yield_time_ms, DEFAULT_WRITE_STDIN_YIELD_TIME_MS),
+            page_bytes(args.max_output_tokens, &turn),
+        )
+        .await
+        .map_err(|err| FunctionCallError::RespondToModel(err.to_string()))?;
+    emitter
+        .emit(event_ctx, ToolEventStage::Success(exec_output(&response)))
+        .await;
+    Ok(response.format_for_model())
+}
+
+/// Event payload for a page of PTY output. Running processes report exit
+/// code 0 so the UI does not render them as failures.
+fn exec_output(response: &UnifiedExecResponse) -> ExecToolCallOutput {
+    ExecToolCallOutput {
+        exit_code: response.exit_code.unwrap_or(0),
+        stdout: StreamOutput::new(response.output.clone()),
+        stderr: StreamOutput::new(String::new()),
+        aggregated_output: StreamOutput::new(response.output.clone()),
+        duration: response.wall_time,
+        timed_out: false,
+    }
+}
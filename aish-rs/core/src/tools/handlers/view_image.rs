@@ -2,6 +2,7 @@ use async_trait::async_trait;
 use serde::Deserialize;
 use tokio::fs;
 
+use crate::deny_read::is_read_denied;
 use crate::function_tool::FunctionCallError;
 use crate::protocol::EventMsg;
 use crate::protocol::ViewImageToolCallEvent;
@@ -48,6 +49,12 @@ impl ToolHandler for ViewImageHandler {
         })?;
 
         let abs_path = turn.resolve_path(Some(args.path));
+        if is_read_denied(&turn.sandbox_policy, &turn.cwd, &abs_path) {
+            return Err(FunctionCallError::RespondToModel(format!(
+                "reading `{}` is blocked by the sandbox deny_read setting",
+                abs_path.display()
+            )));
+        }
 
         let metadata = fs::metadata(&abs_path).await.map_err(|error| {
             FunctionCallError::RespondToModel(format!(
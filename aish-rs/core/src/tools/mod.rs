@@ -10,6 +10,8 @@ pub mod sandboxing;
 pub mod spec;
 
 use crate::exec::ExecToolCallOutput;
+use crate::network_allowlist::NetworkDenial;
+use crate::network_allowlist::parse_network_denials;
 use crate::truncate::TruncationPolicy;
 use crate::truncate::formatted_truncate_text;
 use crate::truncate::truncate_text;
@@ -17,7 +19,8 @@ pub use router::ToolRouter;
 use serde::Serialize;
 
 /// Format the combined exec output for sending back to the model.
-/// Includes exit code and duration metadata; truncates large bodies safely.
+/// Includes exit code and duration metadata, plus any connections the sandbox
+/// network allowlist blocked; truncates large bodies safely.
 pub fn format_exec_output_for_model_structured(
     exec_output: &ExecToolCallOutput,
     truncation_policy: TruncationPolicy,
@@ -32,6 +35,8 @@ pub fn format_exec_output_for_model_structured(
     struct ExecMetadata {
         exit_code: i32,
         duration_seconds: f32,
+        #[serde(skip_serializing_if = "Vec::is_empty")]
+        sandbox_network_denials: Vec<NetworkDenial>,
     }
 
     #[derive(Serialize)]
@@ -50,6 +55,7 @@ pub fn format_exec_output_for_model_structured(
         metadata: ExecMetadata {
             exit_code: *exit_code,
             duration_seconds,
+            sandbox_network_denials: parse_network_denials(&exec_output.stderr.text),
         },
     };
 
@@ -77,6 +83,15 @@ pub fn format_exec_output_for_model_freeform(
     if total_lines != formatted_output.lines().count() {
         sections.push(format!("Total output lines: {total_lines}"));
     }
+    let denials = parse_network_denials(&exec_output.stderr.text);
+    if !denials.is_empty() {
+        sections.push("Blocked by sandbox network allowlist:".to_string());
+        sections.extend(
+            denials
+                .iter()
+                .map(|denial| format!("- {}:{} ({})", denial.host, denial.port, denial.reason)),
+        );
+    }
 
     sections.push("Output:".to_string());
     sections.push(formatted_output);
@@ -98,8 +113,8 @@ pub fn format_exec_output_str(
 fn build_content_with_timeout(exec_output: &ExecToolCallOutput) -> String {
     if exec_output.timed_out {
         format!(
-            "command timed out after {} milliseconds\n{}",
-            exec_output.duration.as_millis(),
+            "command timed out after {:.1} s, partial output follows\n{}",
+            exec_output.duration.as_secs_f32(),
             exec_output.aggregated_output.text
         )
     } else {
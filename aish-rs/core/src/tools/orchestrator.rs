@@ -3,13 +3,24 @@ Module: orchestrator
 
 Central place for approvals + sandbox selection + retry semantics. Drives a
 simple sequence for any ToolRuntime: approval → select sandbox → attempt →
-retry without sandbox on denial (no re‑approval thanks to caching).
+retry without sandbox on denial (no re‑approval thanks to caching). Every
+call that a runtime describes with `audit_subject` is recorded in the audit
+log once it finishes or is refused.
 */
+use std::time::Duration;
+use std::time::Instant;
+
+use crate::audit::AuditApproval;
+use crate::audit::AuditOutcome;
+use crate::audit::AuditRecord;
 use crate::error::AishErr;
 use crate::error::SandboxErr;
 use crate::exec::ExecToolCallOutput;
+use crate::exec::SandboxType;
+use crate::network_allowlist::parse_network_denials;
 use crate::sandboxing::SandboxManager;
 use crate::tools::sandboxing::ApprovalCtx;
+use crate::tools::sandboxing::BypassSource;
 use crate::tools::sandboxing::ExecApprovalRequirement;
 use crate::tools::sandboxing::SandboxAttempt;
 use crate::tools::sandboxing::SandboxOverride;
@@ -24,6 +35,12 @@ pub(crate) struct ToolOrchestrator {
     sandbox: SandboxManager,
 }
 
+/// How a call was approved and sandboxed, for its audit record.
+struct AuditTrail {
+    approval: AuditApproval,
+    sandbox: SandboxType,
+}
+
 impl ToolOrchestrator {
     pub fn new() -> Self {
         Self {
@@ -39,6 +56,30 @@ impl ToolOrchestrator {
         turn_ctx: &crate::aish::TurnContext,
         approval_policy: AskForApproval,
     ) -> Result<Out, ToolError>
+    where
+        T: ToolRuntime<Rq, Out>,
+    {
+        let started = Instant::now();
+        let mut trail = AuditTrail {
+            approval: tool.audit_approval(req).unwrap_or(AuditApproval::Auto),
+            sandbox: SandboxType::None,
+        };
+        let result = self
+            .run_attempts(tool, req, tool_ctx, turn_ctx, approval_policy, &mut trail)
+            .await;
+        record_audit(tool, req, tool_ctx, trail, &result, started.elapsed()).await;
+        result
+    }
+
+    async fn run_attempts<Rq, Out, T>(
+        &mut self,
+        tool: &mut T,
+        req: &Rq,
+        tool_ctx: &ToolCtx<'_>,
+        turn_ctx: &crate::aish::TurnContext,
+        approval_policy: AskForApproval,
+        trail: &mut AuditTrail,
+    ) -> Result<Out, ToolError>
     where
         T: ToolRuntime<Rq, Out>,
     {
@@ -49,8 +90,22 @@ impl ToolOrchestrator {
             default_exec_approval_requirement(approval_policy, &turn_ctx.sandbox_policy)
         });
         match requirement {
-            ExecApprovalRequirement::Skip { .. } => {}
+            ExecApprovalRequirement::Skip { bypass_source, .. } => {
+                // Only an execpolicy allow rule or an approval grant lets a
+                // call skip both the prompt and the sandbox.
+                match bypass_source {
+                    Some(BypassSource::Grant) => trail.approval = AuditApproval::Grant,
+                    Some(BypassSource::Rule(rule)) => {
+                        trail.approval = AuditApproval::Policy { reason: None, rule };
+                    }
+                    None => {}
+                }
+            }
             ExecApprovalRequirement::Forbidden { reason } => {
+                trail.approval = AuditApproval::Policy {
+                    reason: Some(reason.clone()),
+                    rule: None,
+                };
                 return Err(ToolError::Rejected(reason));
             }
             ExecApprovalRequirement::NeedsApproval { reason, .. } => {
@@ -61,6 +116,11 @@ impl ToolOrchestrator {
                     retry_reason: reason,
                 };
                 let decision = tool.start_approval_async(req, approval_ctx).await;
+                if tool.audit_approval(req).is_none() {
+                    trail.approval = AuditApproval::User {
+                        decision: decision.clone(),
+                    };
+                }
 
                 match decision {
                     ReviewDecision::Denied | ReviewDecision::Abort => {
@@ -68,7 +128,8 @@ impl ToolOrchestrator {
                     }
                     ReviewDecision::Approved
                     | ReviewDecision::ApprovedExecpolicyAmendment { .. }
-                    | ReviewDecision::ApprovedForSession => {}
+                    | ReviewDecision::ApprovedForSession
+                    | ReviewDecision::ApprovedWithGrant { .. } => {}
                 }
                 already_approved = true;
             }
@@ -77,9 +138,11 @@ impl ToolOrchestrator {
         // 2) First attempt under the selected sandbox.
         let initial_sandbox = match tool.sandbox_mode_for_first_attempt(req) {
             SandboxOverride::BypassSandboxFirstAttempt => crate::exec::SandboxType::None,
-            SandboxOverride::NoOverride => self
-                .sandbox
-                .select_initial(&turn_ctx.sandbox_policy, tool.sandbox_preference()),
+            SandboxOverride::NoOverride => self.sandbox.select_initial(
+                &turn_ctx.sandbox_policy,
+                tool.sandbox_preference(),
+                turn_ctx.sandbox_backend,
+            ),
         };
 
         // Platform-specific flag gating is handled by SandboxManager::select_initial
@@ -90,8 +153,12 @@ impl ToolOrchestrator {
             manager: &self.sandbox,
             sandbox_cwd: &turn_ctx.cwd,
             aish_linux_sandbox_exe: turn_ctx.aish_linux_sandbox_exe.as_ref(),
+            container_sandbox: Some(&turn_ctx.sandbox_container),
+            call_id: &tool_ctx.call_id,
         };
 
+        trail.sandbox = initial_sandbox;
+
         match tool.run(req, &initial_attempt, tool_ctx).await {
             Ok(out) => {
                 // We have a successful initial result
@@ -122,6 +189,9 @@ impl ToolOrchestrator {
                     };
 
                     let decision = tool.start_approval_async(req, approval_ctx).await;
+                    trail.approval = AuditApproval::User {
+                        decision: decision.clone(),
+                    };
 
                     match decision {
                         ReviewDecision::Denied | ReviewDecision::Abort => {
@@ -129,7 +199,8 @@ impl ToolOrchestrator {
                         }
                         ReviewDecision::Approved
                         | ReviewDecision::ApprovedExecpolicyAmendment { .. }
-                        | ReviewDecision::ApprovedForSession => {}
+                        | ReviewDecision::ApprovedForSession
+                        | ReviewDecision::ApprovedWithGrant { .. } => {}
                     }
                 }
 
@@ -139,9 +210,12 @@ impl ToolOrchestrator {
                     manager: &self.sandbox,
                     sandbox_cwd: &turn_ctx.cwd,
                     aish_linux_sandbox_exe: None,
+                    container_sandbox: None,
+                    call_id: &tool_ctx.call_id,
                 };
 
                 // Second attempt.
+                trail.sandbox = escalated_attempt.sandbox;
                 (*tool).run(req, &escalated_attempt, tool_ctx).await
             }
             other => other,
@@ -149,8 +223,58 @@ impl ToolOrchestrator {
     }
 }
 
-fn build_denial_reason_from_output(_output: &ExecToolCallOutput) -> String {
+async fn record_audit<Rq, Out, T>(
+    tool: &T,
+    req: &Rq,
+    tool_ctx: &ToolCtx<'_>,
+    trail: AuditTrail,
+    result: &Result<Out, ToolError>,
+    duration: Duration,
+) where
+    T: ToolRuntime<Rq, Out>,
+{
+    let audit = &tool_ctx.session.services.audit;
+    if !audit.is_enabled() {
+        return;
+    }
+    let Some(subject) = tool.audit_subject(req) else {
+        return;
+    };
+    let (outcome, exit_code) = match result {
+        Ok(out) => (AuditOutcome::Ran, tool.audit_exit_code(out)),
+        Err(ToolError::Rejected(_)) => (AuditOutcome::Rejected, None),
+        Err(ToolError::Codex(AishErr::Sandbox(
+            SandboxErr::Denied { output } | SandboxErr::Timeout { output },
+        ))) => (AuditOutcome::Failed, Some(output.exit_code)),
+        Err(ToolError::Codex(_)) => (AuditOutcome::Failed, None),
+    };
+    let record = AuditRecord::new(
+        tool_ctx.session.conversation_id(),
+        &tool_ctx.call_id,
+        &tool_ctx.tool_name,
+        subject,
+        trail.sandbox,
+        trail.approval,
+        outcome,
+        exit_code,
+        duration,
+    );
+    audit.record(record).await;
+}
+
+fn build_denial_reason_from_output(output: &ExecToolCallOutput) -> String {
     // Keep approval reason terse and stable for UX/tests, but accept the
     // output so we can evolve heuristics later without touching call sites.
-    "command failed; retry without sandbox?".to_string()
+    let denials = parse_network_denials(&output.stderr.text);
+    if denials.is_empty() {
+        return "command failed; retry without sandbox?".to_string();
+    }
+    let destinations = denials
+        .iter()
+        .map(|denial| format!("{}:{}", denial.host, denial.port))
+        .collect::<Vec<_>>()
+        .join(", ");
+    format!(
+        "network access to {destinations} is not in the sandbox allowlist; retry without sandbox?"
+    )
 }
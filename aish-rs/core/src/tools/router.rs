@@ -113,6 +113,7 @@ impl ToolRouter {
                             timeout_ms: exec.timeout_ms,
                             sandbox_permissions: Some(SandboxPermissions::UseDefault),
                             justification: None,
+                            background: None,
                         };
                         Ok(Some(ToolCall {
                             tool_name: "local_shell".to_string(),
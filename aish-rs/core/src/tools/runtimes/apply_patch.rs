@@ -5,6 +5,9 @@
 //! `aish --aish-run-as-apply-patch`, and runs under the current
 //! `SandboxAttempt` with a minimal environment.
 use crate::AISH_APPLY_PATCH_ARG1;
+use crate::apply_patch::patch_audit_subject;
+use crate::audit::AuditApproval;
+use crate::audit::AuditSubject;
 use crate::exec::ExecToolCallOutput;
 use crate::sandboxing::CommandSpec;
 use crate::sandboxing::SandboxPermissions;
@@ -30,7 +33,12 @@ pub struct ApplyPatchRequest {
     pub cwd: PathBuf,
     pub timeout_ms: Option<u64>,
     pub user_explicitly_approved: bool,
+    /// What the user answered when `apply_patch` asked before delegating
+    /// here; `None` when the patch was approved automatically.
+    pub user_decision: Option<ReviewDecision>,
     pub codex_exe: Option<PathBuf>,
+    /// Files the patch adds, deletes, updates or moves to, for the audit log.
+    pub touched_paths: Vec<PathBuf>,
 }
 
 #[derive(Default)]
@@ -152,4 +160,23 @@ impl ToolRuntime<ApplyPatchRequest, ExecToolCallOutput> for ApplyPatchRuntime {
             .map_err(ToolError::Codex)?;
         Ok(out)
     }
+
+    fn audit_subject(&self, req: &ApplyPatchRequest) -> Option<AuditSubject> {
+        Some(patch_audit_subject(&req.cwd, &req.touched_paths))
+    }
+
+    fn audit_approval(&self, req: &ApplyPatchRequest) -> Option<AuditApproval> {
+        // `apply_patch` already decided before delegating; the orchestrator's
+        // own approval step only replays that decision without asking.
+        Some(match &req.user_decision {
+            Some(decision) => AuditApproval::User {
+                decision: decision.clone(),
+            },
+            None => AuditApproval::Auto,
+        })
+    }
+
+    fn audit_exit_code(&self, out: &ExecToolCallOutput) -> Option<i32> {
+        Some(out.exit_code)
+    }
 }
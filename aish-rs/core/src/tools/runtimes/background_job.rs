@@ -0,0 +1,169 @@
+/*
+Runtime: background job
+
+Prepares `background: true` shell commands under the orchestrator: asks for
+approval when needed, builds a CommandSpec and transforms it for the selected
+sandbox. The resulting ExecEnv is started by the BackgroundJobManager, so the
+tool call returns without waiting for the command.
+*/
+use crate::audit::AuditSubject;
+use crate::exec::ExecExpiration;
+use crate::sandboxing::ExecEnv;
+use crate::sandboxing::SandboxPermissions;
+use crate::tools::runtimes::build_command_spec;
+use crate::tools::sandboxing::Approvable;
+use crate::tools::sandboxing::ApprovalCtx;
+use crate::tools::sandboxing::ExecApprovalRequirement;
+use crate::tools::sandboxing::SandboxAttempt;
+use crate::tools::sandboxing::SandboxOverride;
+use crate::tools::sandboxing::Sandboxable;
+use crate::tools::sandboxing::SandboxablePreference;
+use crate::tools::sandboxing::ToolCtx;
+use crate::tools::sandboxing::ToolError;
+use crate::tools::sandboxing::ToolRuntime;
+use crate::tools::sandboxing::with_cached_approval;
+use aish_protocol::protocol::ReviewDecision;
+use futures::future::BoxFuture;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use tokio_util::sync::CancellationToken;
+
+#[derive(Clone, Debug)]
+pub struct BackgroundJobRequest {
+    pub command: Vec<String>,
+    pub cwd: PathBuf,
+    pub env: HashMap<String, String>,
+    pub sandbox_permissions: SandboxPermissions,
+    pub justification: Option<String>,
+    pub exec_approval_requirement: ExecApprovalRequirement,
+    /// Stops the job; cancelled by `job_kill`.
+    pub cancel: CancellationToken,
+}
+
+#[derive(Default)]
+pub struct BackgroundJobRuntime;
+
+#[derive(serde::Serialize, Clone, Debug, Eq, PartialEq, Hash)]
+pub(crate) struct BackgroundJobApprovalKey {
+    command: Vec<String>,
+    cwd: PathBuf,
+    sandbox_permissions: SandboxPermissions,
+}
+
+impl BackgroundJobRuntime {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Sandboxable for BackgroundJobRuntime {
+    fn sandbox_preference(&self) -> SandboxablePreference {
+        SandboxablePreference::Auto
+    }
+
+    // The job has not run yet when this runtime returns, so sandbox denials
+    // cannot be detected and retried here.
+    fn escalate_on_failure(&self) -> bool {
+        false
+    }
+}
+
+impl Approvable<BackgroundJobRequest> for BackgroundJobRuntime {
+    type ApprovalKey = BackgroundJobApprovalKey;
+
+    fn approval_key(&self, req: &BackgroundJobRequest) -> Self::ApprovalKey {
+        BackgroundJobApprovalKey {
+            command: req.command.clone(),
+            cwd: req.cwd.clone(),
+            sandbox_permissions: req.sandbox_permissions,
+        }
+    }
+
+    fn start_approval_async<'a>(
+        &'a mut self,
+        req: &'a BackgroundJobRequest,
+        ctx: ApprovalCtx<'a>,
+    ) -> BoxFuture<'a, ReviewDecision> {
+        let key = self.approval_key(req);
+        let command = req.command.clone();
+        let cwd = req.cwd.clone();
+        let reason = ctx
+            .retry_reason
+            .clone()
+            .or_else(|| req.justification.clone());
+        let session = ctx.session;
+        let turn = ctx.turn;
+        let call_id = ctx.call_id.to_string();
+        Box::pin(async move {
+            with_cached_approval(&session.services, key, move || async move {
+                session
+                    .request_command_approval(
+                        turn,
+                        call_id,
+                        command,
+                        cwd,
+                        reason,
+                        req.exec_approval_requirement
+                            .proposed_execpolicy_amendment()
+                            .cloned(),
+                    )
+                    .await
+            })
+            .await
+        })
+    }
+
+    fn exec_approval_requirement(
+        &self,
+        req: &BackgroundJobRequest,
+    ) -> Option<ExecApprovalRequirement> {
+        Some(req.exec_approval_requirement.clone())
+    }
+
+    fn sandbox_mode_for_first_attempt(&self, req: &BackgroundJobRequest) -> SandboxOverride {
+        if req.sandbox_permissions.requires_escalated_permissions()
+            || matches!(
+                req.exec_approval_requirement,
+                ExecApprovalRequirement::Skip {
+                    bypass_sandbox: true,
+                    ..
+                }
+            )
+        {
+            SandboxOverride::BypassSandboxFirstAttempt
+        } else {
+            SandboxOverride::NoOverride
+        }
+    }
+}
+
+impl ToolRuntime<BackgroundJobRequest, ExecEnv> for BackgroundJobRuntime {
+    async fn run(
+        &mut self,
+        req: &BackgroundJobRequest,
+        attempt: &SandboxAttempt<'_>,
+        _ctx: &ToolCtx<'_>,
+    ) -> Result<ExecEnv, ToolError> {
+        // Background jobs are not bound by the exec timeout; they run until
+        // they exit, are killed with `job_kill`, or the session ends.
+        let spec = build_command_spec(
+            &req.command,
+            &req.cwd,
+            &req.env,
+            ExecExpiration::Cancellation(req.cancel.clone()),
+            req.sandbox_permissions,
+            req.justification.clone(),
+        )?;
+        attempt
+            .env_for(spec)
+            .map_err(|err| ToolError::Codex(err.into()))
+    }
+
+    fn audit_subject(&self, req: &BackgroundJobRequest) -> Option<AuditSubject> {
+        Some(AuditSubject {
+            argv: req.command.clone(),
+            cwd: req.cwd.clone(),
+            touched_paths: Vec::new(),
+        })
+    }
+}
@@ -4,6 +4,7 @@ Runtime: shell
 Executes shell requests under the orchestrator: asks for approval when needed,
 builds a CommandSpec, and runs it under the current SandboxAttempt.
 */
+use crate::audit::AuditSubject;
 use crate::exec::ExecToolCallOutput;
 use crate::features::Feature;
 use crate::powershell::prefix_powershell_script_with_utf8;
@@ -170,4 +171,16 @@ impl ToolRuntime<ShellRequest, ExecToolCallOutput> for ShellRuntime {
             .map_err(ToolError::Codex)?;
         Ok(out)
     }
+
+    fn audit_subject(&self, req: &ShellRequest) -> Option<AuditSubject> {
+        Some(AuditSubject {
+            argv: req.command.clone(),
+            cwd: req.cwd.clone(),
+            touched_paths: Vec::new(),
+        })
+    }
+
+    fn audit_exit_code(&self, out: &ExecToolCallOutput) -> Option<i32> {
+        Some(out.exit_code)
+    }
 }
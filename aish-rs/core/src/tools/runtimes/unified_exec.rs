@@ -0,0 +1,179 @@
+/*
+Runtime: unified exec
+
+Starts `exec_command` processes inside a PTY under the orchestrator: asks for
+approval when needed, builds a CommandSpec, transforms it for the selected
+sandbox and spawns it with `aish-utils-pty`. The session itself is handed to
+the UnifiedExecSessionManager by the caller.
+*/
+use crate::audit::AuditSubject;
+use crate::error::AishErr;
+use crate::exec::ExecExpiration;
+use crate::sandboxing::SandboxPermissions;
+use crate::tools::runtimes::build_command_spec;
+use crate::tools::sandboxing::Approvable;
+use crate::tools::sandboxing::ApprovalCtx;
+use crate::tools::sandboxing::ExecApprovalRequirement;
+use crate::tools::sandboxing::SandboxAttempt;
+use crate::tools::sandboxing::SandboxOverride;
+use crate::tools::sandboxing::Sandboxable;
+use crate::tools::sandboxing::SandboxablePreference;
+use crate::tools::sandboxing::ToolCtx;
+use crate::tools::sandboxing::ToolError;
+use crate::tools::sandboxing::ToolRuntime;
+use crate::tools::sandboxing::with_cached_approval;
+use aish_protocol::protocol::ReviewDecision;
+use aish_utils_pty::SpawnedPty;
+use aish_utils_pty::spawn_pty_process;
+use futures::future::BoxFuture;
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+#[derive(Clone, Debug)]
+pub struct UnifiedExecRequest {
+    pub command: Vec<String>,
+    pub cwd: PathBuf,
+    pub env: HashMap<String, String>,
+    pub sandbox_permissions: SandboxPermissions,
+    pub justification: Option<String>,
+    pub exec_approval_requirement: ExecApprovalRequirement,
+}
+
+#[derive(Default)]
+pub struct UnifiedExecRuntime;
+
+#[derive(serde::Serialize, Clone, Debug, Eq, PartialEq, Hash)]
+pub(crate) struct UnifiedExecApprovalKey {
+    command: Vec<String>,
+    cwd: PathBuf,
+    sandbox_permissions: SandboxPermissions,
+}
+
+impl UnifiedExecRuntime {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Sandboxable for UnifiedExecRuntime {
+    fn sandbox_preference(&self) -> SandboxablePreference {
+        SandboxablePreference::Auto
+    }
+
+    // A PTY session has no final output to inspect when it starts, so sandbox
+    // denials cannot be detected and retried here.
+    fn escalate_on_failure(&self) -> bool {
+        false
+    }
+}
+
+impl Approvable<UnifiedExecRequest> for UnifiedExecRuntime {
+    type ApprovalKey = UnifiedExecApprovalKey;
+
+    fn approval_key(&self, req: &UnifiedExecRequest) -> Self::ApprovalKey {
+        UnifiedExecApprovalKey {
+            command: req.command.clone(),
+            cwd: req.cwd.clone(),
+            sandbox_permissions: req.sandbox_permissions,
+        }
+    }
+
+    fn start_approval_async<'a>(
+        &'a mut self,
+        req: &'a UnifiedExecRequest,
+        ctx: ApprovalCtx<'a>,
+    ) -> BoxFuture<'a, ReviewDecision> {
+        let key = self.approval_key(req);
+        let command = req.command.clone();
+        let cwd = req.cwd.clone();
+        let reason = ctx
+            .retry_reason
+            .clone()
+            .or_else(|| req.justification.clone());
+        let session = ctx.session;
+        let turn = ctx.turn;
+        let call_id = ctx.call_id.to_string();
+        Box::pin(async move {
+            with_cached_approval(&session.services, key, move || async move {
+                session
+                    .request_command_approval(
+                        turn,
+                        call_id,
+                        command,
+                        cwd,
+                        reason,
+                        req.exec_approval_requirement
+                            .proposed_execpolicy_amendment()
+                            .cloned(),
+                    )
+                    .await
+            })
+            .await
+        })
+    }
+
+    fn exec_approval_requirement(
+        &self,
+        req: &UnifiedExecRequest,
+    ) -> Option<ExecApprovalRequirement> {
+        Some(req.exec_approval_requirement.clone())
+    }
+
+    fn sandbox_mode_for_first_attempt(&self, req: &UnifiedExecRequest) -> SandboxOverride {
+        if req.sandbox_permissions.requires_escalated_permissions()
+            || matches!(
+                req.exec_approval_requirement,
+                ExecApprovalRequirement::Skip {
+                    bypass_sandbox: true,
+                    ..
+                }
+            )
+        {
+            SandboxOverride::BypassSandboxFirstAttempt
+        } else {
+            SandboxOverride::NoOverride
+        }
+    }
+}
+
+impl ToolRuntime<UnifiedExecRequest, SpawnedPty> for UnifiedExecRuntime {
+    async fn run(
+        &mut self,
+        req: &UnifiedExecRequest,
+        attempt: &SandboxAttempt<'_>,
+        _ctx: &ToolCtx<'_>,
+    ) -> Result<SpawnedPty, ToolError> {
+        // PTY sessions are not bound by the exec timeout; they live until the
+        // process exits or the conversation ends.
+        let spec = build_command_spec(
+            &req.command,
+            &req.cwd,
+            &req.env,
+            ExecExpiration::DefaultTimeout,
+            req.sandbox_permissions,
+            req.justification.clone(),
+        )?;
+        let env = attempt
+            .env_for(spec)
+            .map_err(|err| ToolError::Codex(err.into()))?;
+        let (program, args) = env
+            .command
+            .split_first()
+            .ok_or_else(|| ToolError::Rejected("command args are empty".to_string()))?;
+        spawn_pty_process(program, args, &env.cwd, &env.env, &env.arg0)
+            .await
+            .map_err(|err| {
+                ToolError::Codex(AishErr::Io(std::io::Error::other(format!(
+                    "failed to start PTY session: {err}"
+                ))))
+            })
+    }
+
+    fn audit_subject(&self, req: &UnifiedExecRequest) -> Option<AuditSubject> {
+        Some(AuditSubject {
+            argv: req.command.clone(),
+            cwd: req.cwd.clone(),
+            touched_paths: Vec::new(),
+        })
+    }
+}
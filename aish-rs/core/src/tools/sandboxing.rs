@@ -6,6 +6,8 @@
 
 use crate::aish::Session;
 use crate::aish::TurnContext;
+use crate::audit::AuditApproval;
+use crate::audit::AuditSubject;
 use crate::error::AishErr;
 use crate::protocol::SandboxPolicy;
 use crate::sandboxing::CommandSpec;
@@ -84,6 +86,15 @@ pub(crate) struct ApprovalCtx<'a> {
     pub retry_reason: Option<String>,
 }
 
+/// What let a call skip both the approval prompt and the sandbox.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) enum BypassSource {
+    /// An approval grant made earlier in the session.
+    Grant,
+    /// An execpolicy allow rule, as `file:line` when it is known.
+    Rule(Option<String>),
+}
+
 // Specifies what tool orchestrator should do with a given tool call.
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub(crate) enum ExecApprovalRequirement {
@@ -92,6 +103,8 @@ pub(crate) enum ExecApprovalRequirement {
         /// The first attempt should skip sandboxing (e.g., when explicitly
         /// greenlit by policy).
         bypass_sandbox: bool,
+        /// What greenlit the call when `bypass_sandbox` is set, for the audit log.
+        bypass_source: Option<BypassSource>,
         /// Proposed execpolicy amendment to skip future approvals for similar commands
         /// Only applies if the command fails to run in sandbox and codex prompts the user to run outside the sandbox.
         proposed_execpolicy_amendment: Option<ExecPolicyAmendment>,
@@ -147,6 +160,7 @@ pub(crate) fn default_exec_approval_requirement(
     } else {
         ExecApprovalRequirement::Skip {
             bypass_sandbox: false,
+            bypass_source: None,
             proposed_execpolicy_amendment: None,
         }
     }
@@ -216,7 +230,6 @@ pub(crate) struct ToolCtx<'a> {
     pub session: &'a Session,
     pub turn: &'a TurnContext,
     pub call_id: String,
-    #[allow(dead_code)]
     pub tool_name: String,
 }
 
@@ -233,6 +246,24 @@ pub(crate) trait ToolRuntime<Req, Out>: Approvable<Req> + Sandboxable {
         attempt: &SandboxAttempt<'_>,
         ctx: &ToolCtx,
     ) -> Result<Out, ToolError>;
+
+    /// What to record in the audit log for `req`. Defaults to `None`, which
+    /// keeps the call out of the log.
+    fn audit_subject(&self, _req: &Req) -> Option<AuditSubject> {
+        None
+    }
+
+    /// Approval settled before the orchestrator ran, recorded in place of
+    /// the orchestrator's own first approval step. Prompts for retrying
+    /// without the sandbox are still recorded as they happen.
+    fn audit_approval(&self, _req: &Req) -> Option<AuditApproval> {
+        None
+    }
+
+    /// Exit code to record for a call that returned `out`.
+    fn audit_exit_code(&self, _out: &Out) -> Option<i32> {
+        None
+    }
 }
 
 pub(crate) struct SandboxAttempt<'a> {
@@ -241,6 +272,8 @@ pub(crate) struct SandboxAttempt<'a> {
     pub(crate) manager: &'a SandboxManager,
     pub(crate) sandbox_cwd: &'a Path,
     pub aish_linux_sandbox_exe: Option<&'a std::path::PathBuf>,
+    pub container_sandbox: Option<&'a crate::sandboxing::container::ContainerSandbox>,
+    pub(crate) call_id: &'a str,
 }
 
 impl<'a> SandboxAttempt<'a> {
@@ -254,6 +287,8 @@ impl<'a> SandboxAttempt<'a> {
             self.sandbox,
             self.sandbox_cwd,
             self.aish_linux_sandbox_exe,
+            self.container_sandbox,
+            self.call_id,
         )
     }
 }
@@ -275,6 +310,7 @@ mod tests {
             ),
             ExecApprovalRequirement::Skip {
                 bypass_sandbox: false,
+                bypass_source: None,
                 proposed_execpolicy_amendment: None,
             }
         );
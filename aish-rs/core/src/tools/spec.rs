@@ -22,6 +22,8 @@ pub(crate) struct ToolsConfig {
     pub apply_patch_tool_type: Option<ApplyPatchToolType>,
     pub web_search_request: bool,
     pub include_view_image_tool: bool,
+    pub include_shell_history_tool: bool,
+    pub background_jobs: bool,
     pub experimental_supported_tools: Vec<String>,
 }
 
@@ -39,13 +41,23 @@ impl ToolsConfig {
         let include_apply_patch_tool = features.enabled(Feature::ApplyPatchFreeform);
         let include_web_search_request = features.enabled(Feature::WebSearchRequest);
         let include_view_image_tool = features.enabled(Feature::ViewImageTool);
+        let include_shell_history_tool = features.enabled(Feature::ShellHistoryTool);
 
         let shell_type = if !features.enabled(Feature::ShellTool) {
             ConfigShellToolType::Disabled
+        } else if features.enabled(Feature::UnifiedExec) {
+            ConfigShellToolType::UnifiedExec
         } else {
             model_family.shell_type
         };
 
+        // Only the `shell` and `shell_command` tools take a `background` flag.
+        let background_jobs = features.enabled(Feature::BackgroundJobs)
+            && matches!(
+                shell_type,
+                ConfigShellToolType::Default | ConfigShellToolType::ShellCommand
+            );
+
         let apply_patch_tool_type = match model_family.apply_patch_tool_type {
             Some(ApplyPatchToolType::Freeform) => Some(ApplyPatchToolType::Freeform),
             Some(ApplyPatchToolType::Function) => Some(ApplyPatchToolType::Function),
@@ -63,6 +75,8 @@ impl ToolsConfig {
             apply_patch_tool_type,
             web_search_request: include_web_search_request,
             include_view_image_tool,
+            include_shell_history_tool,
+            background_jobs,
             experimental_supported_tools: model_family.experimental_supported_tools.clone(),
         }
     }
@@ -124,7 +138,18 @@ impl From<JsonSchema> for AdditionalProperties {
     }
 }
 
-fn create_shell_tool() -> ToolSpec {
+fn create_background_param() -> JsonSchema {
+    JsonSchema::Boolean {
+        description: Some(
+            "Start the command as a background job and return its job id immediately instead of \
+             waiting for it. Use this for servers, watchers and long builds; check on it with \
+             job_status and job_output."
+                .to_string(),
+        ),
+    }
+}
+
+fn create_shell_tool(include_background: bool) -> ToolSpec {
     let mut properties = BTreeMap::new();
     properties.insert(
         "command".to_string(),
@@ -159,6 +184,10 @@ fn create_shell_tool() -> ToolSpec {
         },
     );
 
+    if include_background {
+        properties.insert("background".to_string(), create_background_param());
+    }
+
     let description  = if cfg!(windows) {
         r#"Runs a Powershell command (Windows) and returns its output. Arguments to `shell` will be passed to CreateProcessW(). Most commands should be prefixed with ["powershell.exe", "-Command"].
         
@@ -188,7 +217,7 @@ Examples of valid command strings:
     })
 }
 
-fn create_shell_command_tool() -> ToolSpec {
+fn create_shell_command_tool(include_background: bool) -> ToolSpec {
     let mut properties = BTreeMap::new();
     properties.insert(
         "command".to_string(),
@@ -232,6 +261,10 @@ fn create_shell_command_tool() -> ToolSpec {
         },
     );
 
+    if include_background {
+        properties.insert("background".to_string(), create_background_param());
+    }
+
     let description = if cfg!(windows) {
         r#"Runs a Powershell command (Windows) and returns its output.
         
@@ -396,6 +429,272 @@ fn create_grep_files_tool() -> ToolSpec {
     })
 }
 
+fn create_exec_command_tool() -> ToolSpec {
+    let mut properties = BTreeMap::new();
+    properties.insert(
+        "cmd".to_string(),
+        JsonSchema::String {
+            description: Some("Shell command to execute.".to_string()),
+        },
+    );
+    properties.insert(
+        "workdir".to_string(),
+        JsonSchema::String {
+            description: Some(
+                "The working directory to run the command in. Defaults to the turn cwd."
+                    .to_string(),
+            ),
+        },
+    );
+    properties.insert(
+        "login".to_string(),
+        JsonSchema::Boolean {
+            description: Some(
+                "Whether to run the shell with -l/-i semantics. Defaults to true.".to_string(),
+            ),
+        },
+    );
+    properties.insert(
+        "yield_time_ms".to_string(),
+        JsonSchema::Number {
+            description: Some(
+                "How long to wait (in milliseconds) for the command to finish before returning \
+                 with a session ID (defaults to 10000, max 30000)."
+                    .to_string(),
+            ),
+        },
+    );
+    properties.insert(
+        "max_output_tokens".to_string(),
+        JsonSchema::Number {
+            description: Some(
+                "Maximum number of output tokens to return per call. Remaining output stays \
+                 buffered for the next write_stdin call."
+                    .to_string(),
+            ),
+        },
+    );
+    properties.insert(
+        "sandbox_permissions".to_string(),
+        JsonSchema::String {
+            description: Some("Sandbox permissions for the command. Set to \"require_escalated\" to request running without sandbox restrictions; defaults to \"use_default\".".to_string()),
+        },
+    );
+    properties.insert(
+        "justification".to_string(),
+        JsonSchema::String {
+            description: Some("Only set if sandbox_permissions is \"require_escalated\". 1-sentence explanation of why we want to run this command.".to_string()),
+        },
+    );
+
+    ToolSpec::Function(ResponsesApiTool {
+        name: "exec_command".to_string(),
+        description: "Runs a command in a PTY. Returns the output and exit code when it finishes \
+                      within yield_time_ms; otherwise returns a session ID that stays open \
+                      across turns and can be driven with write_stdin. Use it for long-running \
+                      and interactive programs such as REPLs, debuggers or `tail -f`."
+            .to_string(),
+        strict: false,
+        parameters: JsonSchema::Object {
+            properties,
+            required: Some(vec!["cmd".to_string()]),
+            additional_properties: Some(false.into()),
+        },
+    })
+}
+
+fn create_write_stdin_tool() -> ToolSpec {
+    let mut properties = BTreeMap::new();
+    properties.insert(
+        "session_id".to_string(),
+        JsonSchema::Number {
+            description: Some("Identifier of the running session.".to_string()),
+        },
+    );
+    properties.insert(
+        "chars".to_string(),
+        JsonSchema::String {
+            description: Some(
+                "Bytes to write to stdin (may be empty to just poll for output). Include \\n to \
+                 submit a line."
+                    .to_string(),
+            ),
+        },
+    );
+    properties.insert(
+        "yield_time_ms".to_string(),
+        JsonSchema::Number {
+            description: Some(
+                "How long to wait (in milliseconds) for output before returning (defaults to \
+                 250, max 30000)."
+                    .to_string(),
+            ),
+        },
+    );
+    properties.insert(
+        "max_output_tokens".to_string(),
+        JsonSchema::Number {
+            description: Some(
+                "Maximum number of output tokens to return. Remaining output stays buffered."
+                    .to_string(),
+            ),
+        },
+    );
+
+    ToolSpec::Function(ResponsesApiTool {
+        name: "write_stdin".to_string(),
+        description: "Writes characters to a session started by exec_command and returns the \
+                      output produced since the last call, or the next page of buffered output."
+            .to_string(),
+        strict: false,
+        parameters: JsonSchema::Object {
+            properties,
+            required: Some(vec!["session_id".to_string()]),
+            additional_properties: Some(false.into()),
+        },
+    })
+}
+
+fn create_job_status_tool() -> ToolSpec {
+    let mut properties = BTreeMap::new();
+    properties.insert(
+        "job_id".to_string(),
+        JsonSchema::Number {
+            description: Some("Job to report on. Omit to list every background job.".to_string()),
+        },
+    );
+
+    ToolSpec::Function(ResponsesApiTool {
+        name: "job_status".to_string(),
+        description: "Reports whether background jobs are still running, and their exit codes \
+                      once they have finished."
+            .to_string(),
+        strict: false,
+        parameters: JsonSchema::Object {
+            properties,
+            required: None,
+            additional_properties: Some(false.into()),
+        },
+    })
+}
+
+fn create_job_output_tool() -> ToolSpec {
+    let mut properties = BTreeMap::new();
+    properties.insert(
+        "job_id".to_string(),
+        JsonSchema::Number {
+            description: Some("Identifier of the background job.".to_string()),
+        },
+    );
+    properties.insert(
+        "max_output_tokens".to_string(),
+        JsonSchema::Number {
+            description: Some(
+                "Maximum number of output tokens to return. Remaining output stays buffered."
+                    .to_string(),
+            ),
+        },
+    );
+
+    ToolSpec::Function(ResponsesApiTool {
+        name: "job_output".to_string(),
+        description: "Returns the status of a background job and the output it produced since \
+                      the previous job_output call."
+            .to_string(),
+        strict: false,
+        parameters: JsonSchema::Object {
+            properties,
+            required: Some(vec!["job_id".to_string()]),
+            additional_properties: Some(false.into()),
+        },
+    })
+}
+
+fn create_job_kill_tool() -> ToolSpec {
+    let mut properties = BTreeMap::new();
+    properties.insert(
+        "job_id".to_string(),
+        JsonSchema::Number {
+            description: Some("Identifier of the background job.".to_string()),
+        },
+    );
+
+    ToolSpec::Function(ResponsesApiTool {
+        name: "job_kill".to_string(),
+        description: "Stops a background job and every process it started.".to_string(),
+        strict: false,
+        parameters: JsonSchema::Object {
+            properties,
+            required: Some(vec!["job_id".to_string()]),
+            additional_properties: Some(false.into()),
+        },
+    })
+}
+
+fn create_shell_history_tool() -> ToolSpec {
+    let mut properties = BTreeMap::new();
+    properties.insert(
+        "query".to_string(),
+        JsonSchema::String {
+            description: Some(
+                "Optional text to look for in past commands (case-insensitive substring match)."
+                    .to_string(),
+            ),
+        },
+    );
+    properties.insert(
+        "regex".to_string(),
+        JsonSchema::Boolean {
+            description: Some("Treat query as a regular expression.".to_string()),
+        },
+    );
+    properties.insert(
+        "shell".to_string(),
+        JsonSchema::String {
+            description: Some(
+                "Only search this shell's history: \"bash\", \"zsh\" or \"fish\". Defaults to all \
+                 of them."
+                    .to_string(),
+            ),
+        },
+    );
+    properties.insert(
+        "since".to_string(),
+        JsonSchema::String {
+            description: Some(
+                "Only include commands run at or after this RFC 3339 timestamp.".to_string(),
+            ),
+        },
+    );
+    properties.insert(
+        "until".to_string(),
+        JsonSchema::String {
+            description: Some(
+                "Only include commands run at or before this RFC 3339 timestamp.".to_string(),
+            ),
+        },
+    );
+    properties.insert(
+        "limit".to_string(),
+        JsonSchema::Number {
+            description: Some("Maximum number of commands to return (defaults to 50).".to_string()),
+        },
+    );
+
+    ToolSpec::Function(ResponsesApiTool {
+        name: "shell_history".to_string(),
+        description: "Searches the user's bash, zsh and fish command history, most recent first. \
+                      Values that look like secrets are redacted."
+            .to_string(),
+        strict: false,
+        parameters: JsonSchema::Object {
+            properties,
+            required: None,
+            additional_properties: Some(false.into()),
+        },
+    })
+}
+
 fn create_read_file_tool() -> ToolSpec {
     let mut properties = BTreeMap::new();
     properties.insert(
@@ -697,6 +996,28 @@ pub(crate) fn create_tools_json_for_chat_completions_api(
     Ok(tools_json)
 }
 
+/// Returns JSON values that are compatible with tool use in the Messages API:
+/// https://docs.anthropic.com/en/docs/agents-and-tools/tool-use/overview
+pub(crate) fn create_tools_json_for_messages_api(
+    tools: &[ToolSpec],
+) -> crate::error::Result<Vec<serde_json::Value>> {
+    // Like the chat completions format, this is derived from the Responses API
+    // JSON and only function tools carry over.
+    let responses_api_tools_json = create_tools_json_for_responses_api(tools)?;
+    let tools_json = responses_api_tools_json
+        .into_iter()
+        .filter(|tool| tool.get("type").and_then(serde_json::Value::as_str) == Some("function"))
+        .map(|tool| {
+            json!({
+                "name": tool.get("name"),
+                "description": tool.get("description"),
+                "input_schema": tool.get("parameters"),
+            })
+        })
+        .collect::<Vec<serde_json::Value>>();
+    Ok(tools_json)
+}
+
 pub(crate) fn mcp_tool_to_openai_tool(
     fully_qualified_name: String,
     tool: mcp_types::Tool,
@@ -849,6 +1170,7 @@ pub(crate) fn build_specs(
     mcp_tools: Option<HashMap<String, mcp_types::Tool>>,
 ) -> ToolRegistryBuilder {
     use crate::tools::handlers::ApplyPatchHandler;
+    use crate::tools::handlers::BackgroundJobsHandler;
     use crate::tools::handlers::GrepFilesHandler;
     use crate::tools::handlers::ListDirHandler;
     use crate::tools::handlers::McpHandler;
@@ -857,7 +1179,9 @@ pub(crate) fn build_specs(
     use crate::tools::handlers::ReadFileHandler;
     use crate::tools::handlers::ShellCommandHandler;
     use crate::tools::handlers::ShellHandler;
+    use crate::tools::handlers::ShellHistoryHandler;
     use crate::tools::handlers::TestSyncHandler;
+    use crate::tools::handlers::UnifiedExecHandler;
     use crate::tools::handlers::ViewImageHandler;
     use std::sync::Arc;
 
@@ -870,22 +1194,26 @@ pub(crate) fn build_specs(
     let mcp_handler = Arc::new(McpHandler);
     let mcp_resource_handler = Arc::new(McpResourceHandler);
     let shell_command_handler = Arc::new(ShellCommandHandler);
+    let unified_exec_handler = Arc::new(UnifiedExecHandler);
 
     match &config.shell_type {
         ConfigShellToolType::Default => {
-            builder.push_spec(create_shell_tool());
+            builder.push_spec(create_shell_tool(config.background_jobs));
         }
         ConfigShellToolType::Local => {
             builder.push_spec(ToolSpec::LocalShell {});
         }
         ConfigShellToolType::UnifiedExec => {
-            // UnifiedExec is removed.
+            builder.push_spec(create_exec_command_tool());
+            builder.push_spec(create_write_stdin_tool());
+            builder.register_handler("exec_command", unified_exec_handler.clone());
+            builder.register_handler("write_stdin", unified_exec_handler);
         }
         ConfigShellToolType::Disabled => {
             // Do nothing.
         }
         ConfigShellToolType::ShellCommand => {
-            builder.push_spec(create_shell_command_tool());
+            builder.push_spec(create_shell_command_tool(config.background_jobs));
         }
     }
 
@@ -956,6 +1284,21 @@ pub(crate) fn build_specs(
         builder.register_handler("test_sync_tool", test_sync_handler);
     }
 
+    if config.background_jobs {
+        let background_jobs_handler = Arc::new(BackgroundJobsHandler);
+        builder.push_spec_with_parallel_support(create_job_status_tool(), true);
+        builder.push_spec_with_parallel_support(create_job_output_tool(), true);
+        builder.push_spec(create_job_kill_tool());
+        builder.register_handler("job_status", background_jobs_handler.clone());
+        builder.register_handler("job_output", background_jobs_handler.clone());
+        builder.register_handler("job_kill", background_jobs_handler);
+    }
+
+    if config.include_shell_history_tool {
+        builder.push_spec_with_parallel_support(create_shell_history_tool(), true);
+        builder.register_handler("shell_history", Arc::new(ShellHistoryHandler));
+    }
+
     if config.web_search_request {
         builder.push_spec(ToolSpec::WebSearch {});
     }
@@ -1031,7 +1374,7 @@ mod tests {
         match config.shell_type {
             ConfigShellToolType::Default => Some("shell"),
             ConfigShellToolType::Local => Some("local_shell"),
-            ConfigShellToolType::UnifiedExec => None,
+            ConfigShellToolType::UnifiedExec => Some("exec_command"),
             ConfigShellToolType::Disabled => None,
             ConfigShellToolType::ShellCommand => Some("shell_command"),
         }
@@ -1118,6 +1461,85 @@ mod tests {
         assert!(find_tool(&tools, "read_file").supports_parallel_tool_calls);
     }
 
+    #[test]
+    fn test_unified_exec_feature_replaces_shell_tool() {
+        let mut features = Features::with_defaults();
+        features.enable(Feature::UnifiedExec);
+        assert_model_tools(
+            "test-model",
+            &features,
+            &[
+                "exec_command",
+                "write_stdin",
+                "list_mcp_resources",
+                "list_mcp_resource_templates",
+                "read_mcp_resource",
+                "update_plan",
+                "apply_patch",
+                "grep_files",
+                "read_file",
+                "list_dir",
+                "test_sync_tool",
+                "view_image",
+            ],
+        );
+    }
+
+    #[test]
+    fn test_background_jobs_feature_adds_job_tools() {
+        let config = test_config();
+        let model_family = ModelsManager::construct_model_family_offline("test-model", &config);
+        let mut features = Features::with_defaults();
+        features.enable(Feature::BackgroundJobs);
+        let tools_config = ToolsConfig::new(&ToolsConfigParams {
+            model_family: &model_family,
+            features: &features,
+        });
+        let (tools, _) = build_specs(&tools_config, None).build();
+
+        let ToolSpec::Function(ResponsesApiTool {
+            parameters: JsonSchema::Object { properties, .. },
+            ..
+        }) = &find_tool(&tools, "shell_command").spec
+        else {
+            panic!("shell_command should be a function tool");
+        };
+        assert!(properties.contains_key("background"));
+        assert!(find_tool(&tools, "job_status").supports_parallel_tool_calls);
+        assert!(find_tool(&tools, "job_output").supports_parallel_tool_calls);
+        assert!(!find_tool(&tools, "job_kill").supports_parallel_tool_calls);
+
+        // exec_command sessions already run in the background.
+        features.enable(Feature::UnifiedExec);
+        let tools_config = ToolsConfig::new(&ToolsConfigParams {
+            model_family: &model_family,
+            features: &features,
+        });
+        let (tools, _) = build_specs(&tools_config, None).build();
+        assert!(!tools.iter().any(|t| t.spec.name() == "job_status"));
+    }
+
+    #[test]
+    fn test_shell_history_tool_behind_feature() {
+        let config = test_config();
+        let model_family = ModelsManager::construct_model_family_offline("test-model", &config);
+        let mut features = Features::with_defaults();
+        let tools_config = ToolsConfig::new(&ToolsConfigParams {
+            model_family: &model_family,
+            features: &features,
+        });
+        let (tools, _) = build_specs(&tools_config, None).build();
+        assert!(!tools.iter().any(|t| t.spec.name() == "shell_history"));
+
+        features.enable(Feature::ShellHistoryTool);
+        let tools_config = ToolsConfig::new(&ToolsConfigParams {
+            model_family: &model_family,
+            features: &features,
+        });
+        let (tools, _) = build_specs(&tools_config, None).build();
+        assert!(find_tool(&tools, "shell_history").supports_parallel_tool_calls);
+    }
+
     #[test]
     fn test_build_specs_mcp_tools_converted() {
         let config = test_config();
@@ -1505,7 +1927,7 @@ mod tests {
 
     #[test]
     fn test_shell_tool() {
-        let tool = super::create_shell_tool();
+        let tool = super::create_shell_tool(false);
         let ToolSpec::Function(ResponsesApiTool {
             description, name, ..
         }) = &tool
@@ -1535,7 +1957,7 @@ Examples of valid command strings:
 
     #[test]
     fn test_shell_command_tool() {
-        let tool = super::create_shell_command_tool();
+        let tool = super::create_shell_command_tool(false);
         let ToolSpec::Function(ResponsesApiTool {
             description, name, ..
         }) = &tool
@@ -1728,4 +2150,32 @@ Examples of valid command strings:
             })]
         );
     }
+
+    #[test]
+    fn messages_tools_use_input_schema() {
+        let tools = vec![ToolSpec::Function(ResponsesApiTool {
+            name: "demo".to_string(),
+            description: "A demo tool".to_string(),
+            strict: false,
+            parameters: JsonSchema::Object {
+                properties: BTreeMap::new(),
+                required: None,
+                additional_properties: None,
+            },
+        })];
+
+        let tools_json = create_tools_json_for_messages_api(&tools).unwrap();
+
+        assert_eq!(
+            tools_json,
+            vec![json!({
+                "name": "demo",
+                "description": "A demo tool",
+                "input_schema": {
+                    "type": "object",
+                    "properties": {},
+                },
+            })]
+        );
+    }
 }
@@ -0,0 +1,13 @@
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub(crate) enum UnifiedExecError {
+    #[error("unknown session id {session_id}")]
+    UnknownSessionId { session_id: i32 },
+    #[error("session {session_id} has exited; its stdin is closed")]
+    SessionExited { session_id: i32 },
+    #[error("failed to write to stdin of session {session_id}")]
+    WriteToStdin { session_id: i32 },
+    #[error("too many open sessions (limit {limit}); finish or exit an existing session first")]
+    TooManySessions { limit: usize },
+}
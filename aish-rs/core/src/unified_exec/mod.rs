@@ -0,0 +1,138 @@
+//! Interactive PTY sessions backing the `exec_command` / `write_stdin` tools.
+//!
+//! `exec_command` starts a process inside a PTY (via `aish-utils-pty`) and
+//! waits up to `yield_time_ms` for it to finish. Processes that are still
+//! running keep their session: they survive the end of the turn and can be
+//! driven later with `write_stdin`, which sends input and returns whatever
+//! output arrived since the previous call. Output that does not fit in the
+//! caller's token budget stays buffered and is handed out page by page.
+
+use std::time::Duration;
+
+mod errors;
+mod session_manager;
+
+pub(crate) use errors::UnifiedExecError;
+pub(crate) use session_manager::OutputBuffer;
+pub(crate) use session_manager::OutputPage;
+pub(crate) use session_manager::UnifiedExecSessionManager;
+
+pub(crate) const DEFAULT_EXEC_COMMAND_YIELD_TIME_MS: u64 = 10_000;
+pub(crate) const DEFAULT_WRITE_STDIN_YIELD_TIME_MS: u64 = 250;
+pub(crate) const MIN_YIELD_TIME_MS: u64 = 250;
+pub(crate) const MAX_YIELD_TIME_MS: u64 = 30_000;
+
+/// Maximum number of sessions kept open at the same time.
+pub(crate) const MAX_SESSIONS: usize = 64;
+
+/// Unread output kept per session; older bytes are dropped first.
+pub(crate) const OUTPUT_BUFFER_MAX_BYTES: usize = 1024 * 1024;
+
+pub(crate) fn clamp_yield_time(yield_time_ms: Option<u64>, default_ms: u64) -> Duration {
+    Duration::from_millis(
+        yield_time_ms
+            .unwrap_or(default_ms)
+            .clamp(MIN_YIELD_TIME_MS, MAX_YIELD_TIME_MS),
+    )
+}
+
+/// Result of a single `exec_command` or `write_stdin` call.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct UnifiedExecResponse {
+    pub session_id: i32,
+    /// `None` while the process is still running.
+    pub exit_code: Option<i32>,
+    /// The page of output returned by this call.
+    pub output: String,
+    /// Bytes still buffered after this page.
+    pub pending_bytes: usize,
+    /// Bytes dropped since the previous call because nobody read them.
+    pub dropped_bytes: usize,
+    pub wall_time: Duration,
+}
+
+impl UnifiedExecResponse {
+    /// Text returned to the model.
+    pub(crate) fn format_for_model(&self) -> String {
+        let mut sections = vec![format!(
+            "Wall time: {:.1} seconds",
+            self.wall_time.as_secs_f32()
+        )];
+        match self.exit_code {
+            Some(exit_code) => sections.push(format!("Process exited with code {exit_code}")),
+            None => sections.push(format!(
+                "Process running with session ID {}",
+                self.session_id
+            )),
+        }
+        if self.dropped_bytes > 0 {
+            sections.push(format!(
+                "Note: {} bytes of earlier output were dropped because they were not read in time.",
+                self.dropped_bytes
+            ));
+        }
+        sections.push("Output:".to_string());
+        sections.push(self.output.clone());
+        if self.pending_bytes > 0 {
+            sections.push(format!(
+                "[{} more bytes of output are buffered; call write_stdin with session_id {} and empty chars to read the next page]",
+                self.pending_bytes, self.session_id
+            ));
+        }
+        sections.join("\n")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn clamps_yield_time() {
+        assert_eq!(
+            clamp_yield_time(None, DEFAULT_WRITE_STDIN_YIELD_TIME_MS),
+            Duration::from_millis(250)
+        );
+        assert_eq!(
+            clamp_yield_time(Some(0), DEFAULT_EXEC_COMMAND_YIELD_TIME_MS),
+            Duration::from_millis(MIN_YIELD_TIME_MS)
+        );
+        assert_eq!(
+            clamp_yield_time(Some(600_000), DEFAULT_EXEC_COMMAND_YIELD_TIME_MS),
+            Duration::from_millis(MAX_YIELD_TIME_MS)
+        );
+    }
+
+    #[test]
+    fn formats_running_session_with_pending_output() {
+        let response = UnifiedExecResponse {
+            session_id: 1000,
+            exit_code: None,
+            output: ">>> ".to_string(),
+            pending_bytes: 42,
+            dropped_bytes: 0,
+            wall_time: Duration::from_millis(1500),
+        };
+        assert_eq!(
+            response.format_for_model(),
+            "Wall time: 1.5 seconds\nProcess running with session ID 1000\nOutput:\n>>> \n[42 more bytes of output are buffered; call write_stdin with session_id 1000 and empty chars to read the next page]"
+        );
+    }
+
+    #[test]
+    fn formats_exited_session() {
+        let response = UnifiedExecResponse {
+            session_id: 1001,
+            exit_code: Some(0),
+            output: "hello\n".to_string(),
+            pending_bytes: 0,
+            dropped_bytes: 7,
+            wall_time: Duration::from_millis(100),
+        };
+        assert_eq!(
+            response.format_for_model(),
+            "Wall time: 0.1 seconds\nProcess exited with code 0\nNote: 7 bytes of earlier output were dropped because they were not read in time.\nOutput:\nhello\n"
+        );
+    }
+}
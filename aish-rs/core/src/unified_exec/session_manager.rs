@@ -0,0 +1,402 @@
+use std::collections::HashMap;
+use std::collections::VecDeque;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::sync::Mutex as StdMutex;
+use std::sync::PoisonError;
+use std::sync::atomic::AtomicI32;
+use std::sync::atomic::Ordering;
+use std::time::Duration;
+
+use aish_utils_pty::ExecCommandSession;
+use aish_utils_pty::SpawnedPty;
+use tokio::sync::Mutex;
+use tokio::sync::broadcast::error::RecvError;
+use tokio::sync::watch;
+use tokio::task::JoinHandle;
+use tokio::time::Instant;
+
+use super::MAX_SESSIONS;
+use super::OUTPUT_BUFFER_MAX_BYTES;
+use super::UnifiedExecError;
+use super::UnifiedExecResponse;
+
+const FIRST_SESSION_ID: i32 = 1000;
+
+/// After a process exits, keep reading until its output stops growing (or
+/// this much time passes) so the tail is not lost to the PTY reader.
+const EXIT_DRAIN_MAX: Duration = Duration::from_millis(500);
+const EXIT_DRAIN_POLL: Duration = Duration::from_millis(25);
+
+/// Owns every PTY session started by `exec_command` for the lifetime of the
+/// conversation. Dropping the manager terminates the remaining processes.
+pub(crate) struct UnifiedExecSessionManager {
+    next_session_id: AtomicI32,
+    sessions: Mutex<HashMap<i32, Arc<ManagedSession>>>,
+}
+
+impl Default for UnifiedExecSessionManager {
+    fn default() -> Self {
+        Self {
+            next_session_id: AtomicI32::new(FIRST_SESSION_ID),
+            sessions: Mutex::new(HashMap::new()),
+        }
+    }
+}
+
+struct ManagedSession {
+    session: ExecCommandSession,
+    command: Vec<String>,
+    cwd: PathBuf,
+    output: Arc<StdMutex<OutputBuffer>>,
+    exit_code: watch::Receiver<Option<i32>>,
+    reader_task: JoinHandle<()>,
+}
+
+impl Drop for ManagedSession {
+    fn drop(&mut self) {
+        // `ExecCommandSession` kills the process in its own `Drop`.
+        self.reader_task.abort();
+    }
+}
+
+impl ManagedSession {
+    fn exit_code(&self) -> Option<i32> {
+        *self.exit_code.borrow()
+    }
+
+    fn bytes_received(&self) -> u64 {
+        self.output
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .received
+    }
+
+    fn take_page(&self, max_bytes: usize) -> OutputPage {
+        self.output
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .take_page(max_bytes)
+    }
+
+    async fn wait_for_output_to_settle(&self) {
+        let deadline = Instant::now() + EXIT_DRAIN_MAX;
+        let mut last_received = self.bytes_received();
+        while Instant::now() < deadline {
+            tokio::time::sleep(EXIT_DRAIN_POLL).await;
+            let received = self.bytes_received();
+            if received == last_received {
+                break;
+            }
+            last_received = received;
+        }
+    }
+}
+
+impl UnifiedExecSessionManager {
+    /// Take ownership of a freshly spawned PTY process and return its
+    /// session id.
+    pub(crate) async fn open_session(
+        &self,
+        spawned: SpawnedPty,
+        command: Vec<String>,
+        cwd: PathBuf,
+    ) -> Result<i32, UnifiedExecError> {
+        let mut sessions = self.sessions.lock().await;
+        if sessions.len() >= MAX_SESSIONS {
+            sessions.retain(|_, session| session.exit_code().is_none());
+        }
+        if sessions.len() >= MAX_SESSIONS {
+            return Err(UnifiedExecError::TooManySessions {
+                limit: MAX_SESSIONS,
+            });
+        }
+
+        let SpawnedPty {
+            session,
+            mut output_rx,
+            exit_rx,
+        } = spawned;
+
+        let output = Arc::new(StdMutex::new(OutputBuffer::default()));
+        let reader_task = tokio::spawn({
+            let output = Arc::clone(&output);
+            async move {
+                loop {
+                    match output_rx.recv().await {
+                        Ok(chunk) => output
+                            .lock()
+                            .unwrap_or_else(PoisonError::into_inner)
+                            .push(&chunk),
+                        Err(RecvError::Lagged(_)) => continue,
+                        Err(RecvError::Closed) => break,
+                    }
+                }
+            }
+        });
+
+        let (exit_tx, exit_code) = watch::channel(None);
+        tokio::spawn(async move {
+            let code = exit_rx.await.unwrap_or(-1);
+            let _ = exit_tx.send(Some(code));
+        });
+
+        let session_id = self.next_session_id.fetch_add(1, Ordering::Relaxed);
+        sessions.insert(
+            session_id,
+            Arc::new(ManagedSession {
+                session,
+                command,
+                cwd,
+                output,
+                exit_code,
+                reader_task,
+            }),
+        );
+        Ok(session_id)
+    }
+
+    /// The command line and working directory a session was started with.
+    pub(crate) async fn session_command(
+        &self,
+        session_id: i32,
+    ) -> Result<(Vec<String>, PathBuf), UnifiedExecError> {
+        let session = self.session(session_id).await?;
+        Ok((session.command.clone(), session.cwd.clone()))
+    }
+
+    pub(crate) async fn write_stdin(
+        &self,
+        session_id: i32,
+        chars: &str,
+    ) -> Result<(), UnifiedExecError> {
+        let session = self.session(session_id).await?;
+        if session.exit_code().is_some() {
+            return Err(UnifiedExecError::SessionExited { session_id });
+        }
+        session
+            .session
+            .writer_sender()
+            .send(chars.as_bytes().to_vec())
+            .await
+            .map_err(|_| UnifiedExecError::WriteToStdin { session_id })
+    }
+
+    /// Wait up to `yield_time` for the process to exit, then return the next
+    /// page of at most `max_bytes` of buffered output. Sessions are forgotten
+    /// once their process has exited and all output has been read.
+    pub(crate) async fn collect_output(
+        &self,
+        session_id: i32,
+        started_at: std::time::Instant,
+        yield_time: Duration,
+        max_bytes: usize,
+    ) -> Result<UnifiedExecResponse, UnifiedExecError> {
+        let session = self.session(session_id).await?;
+
+        let mut exit_code = session.exit_code.clone();
+        let exited = tokio::select! {
+            result = exit_code.wait_for(Option::is_some) => result.is_ok(),
+            _ = tokio::time::sleep(yield_time) => false,
+        };
+        if exited {
+            session.wait_for_output_to_settle().await;
+        }
+
+        let page = session.take_page(max_bytes);
+        let exit_code = session.exit_code();
+        if exit_code.is_some() && page.pending_bytes == 0 {
+            self.sessions.lock().await.remove(&session_id);
+        }
+
+        Ok(UnifiedExecResponse {
+            session_id,
+            exit_code,
+            output: page.text,
+            pending_bytes: page.pending_bytes,
+            dropped_bytes: page.dropped_bytes,
+            wall_time: started_at.elapsed(),
+        })
+    }
+
+    async fn session(&self, session_id: i32) -> Result<Arc<ManagedSession>, UnifiedExecError> {
+        self.sessions
+            .lock()
+            .await
+            .get(&session_id)
+            .cloned()
+            .ok_or(UnifiedExecError::UnknownSessionId { session_id })
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) struct OutputPage {
+    pub(crate) text: String,
+    pub(crate) pending_bytes: usize,
+    pub(crate) dropped_bytes: usize,
+}
+
+/// Unread output for one session (also used for background jobs).
+#[derive(Debug, Default)]
+pub(crate) struct OutputBuffer {
+    bytes: VecDeque<u8>,
+    /// Total bytes ever received; used to detect when output stops growing.
+    received: u64,
+    /// Bytes discarded since the last page because the buffer was full.
+    dropped: usize,
+}
+
+impl OutputBuffer {
+    pub(crate) fn push(&mut self, chunk: &[u8]) {
+        self.received += chunk.len() as u64;
+        self.bytes.extend(chunk);
+        let overflow = self.bytes.len().saturating_sub(OUTPUT_BUFFER_MAX_BYTES);
+        if overflow > 0 {
+            self.bytes.drain(..overflow);
+            self.dropped += overflow;
+        }
+    }
+
+    pub(crate) fn take_page(&mut self, max_bytes: usize) -> OutputPage {
+        let mut end = self.bytes.len().min(max_bytes.max(1));
+        if end < self.bytes.len() {
+            // Do not split a UTF-8 sequence across two pages.
+            let mut boundary = end;
+            while boundary > 0 && is_utf8_continuation(self.bytes[boundary]) {
+                boundary -= 1;
+            }
+            if boundary > 0 {
+                end = boundary;
+            }
+        }
+        let page: Vec<u8> = self.bytes.drain(..end).collect();
+        OutputPage {
+            text: String::from_utf8_lossy(&page).into_owned(),
+            pending_bytes: self.bytes.len(),
+            dropped_bytes: std::mem::take(&mut self.dropped),
+        }
+    }
+}
+
+fn is_utf8_continuation(byte: u8) -> bool {
+    byte & 0b1100_0000 == 0b1000_0000
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn pages_do_not_split_utf8_sequences() {
+        let mut buffer = OutputBuffer::default();
+        buffer.push("abé".as_bytes());
+        assert_eq!(
+            buffer.take_page(3),
+            OutputPage {
+                text: "ab".to_string(),
+                pending_bytes: 2,
+                dropped_bytes: 0,
+            }
+        );
+        assert_eq!(
+            buffer.take_page(3),
+            OutputPage {
+                text: "é".to_string(),
+                pending_bytes: 0,
+                dropped_bytes: 0,
+            }
+        );
+    }
+
+    #[test]
+    fn full_buffer_drops_oldest_output() {
+        let mut buffer = OutputBuffer::default();
+        buffer.push(&vec![b'a'; OUTPUT_BUFFER_MAX_BYTES]);
+        buffer.push(b"tail");
+        let page = buffer.take_page(OUTPUT_BUFFER_MAX_BYTES);
+        assert_eq!(page.dropped_bytes, 4);
+        assert!(page.text.ends_with("tail"));
+        assert_eq!(buffer.take_page(10).dropped_bytes, 0);
+    }
+
+    #[cfg(unix)]
+    async fn spawn(program: &str, args: &[&str]) -> SpawnedPty {
+        let args: Vec<String> = args.iter().map(ToString::to_string).collect();
+        let env: HashMap<String, String> = std::env::vars().collect();
+        aish_utils_pty::spawn_pty_process(program, &args, &std::env::temp_dir(), &env, &None)
+            .await
+            .expect("spawn pty process")
+    }
+
+    #[cfg(unix)]
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn short_command_reports_exit_code_and_output() {
+        let manager = UnifiedExecSessionManager::default();
+        let spawned = spawn("/bin/sh", &["-c", "echo hello; exit 3"]).await;
+        let session_id = manager
+            .open_session(
+                spawned,
+                vec!["echo hello".to_string()],
+                std::env::temp_dir(),
+            )
+            .await
+            .expect("open session");
+
+        let response = manager
+            .collect_output(
+                session_id,
+                std::time::Instant::now(),
+                Duration::from_secs(10),
+                4096,
+            )
+            .await
+            .expect("collect output");
+        assert_eq!(response.exit_code, Some(3));
+        assert!(response.output.contains("hello"), "{response:?}");
+
+        // Fully read, exited sessions are forgotten.
+        assert!(matches!(
+            manager.write_stdin(session_id, "x").await,
+            Err(UnifiedExecError::UnknownSessionId { .. })
+        ));
+    }
+
+    #[cfg(unix)]
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn sessions_stay_open_for_interaction() {
+        let manager = UnifiedExecSessionManager::default();
+        let spawned = spawn("/bin/cat", &[]).await;
+        let session_id = manager
+            .open_session(spawned, vec!["cat".to_string()], std::env::temp_dir())
+            .await
+            .expect("open session");
+
+        let response = manager
+            .collect_output(
+                session_id,
+                std::time::Instant::now(),
+                Duration::from_millis(250),
+                4096,
+            )
+            .await
+            .expect("collect output");
+        assert_eq!(response.exit_code, None);
+
+        manager
+            .write_stdin(session_id, "ping\n")
+            .await
+            .expect("write stdin");
+        let response = manager
+            .collect_output(
+                session_id,
+                std::time::Instant::now(),
+                Duration::from_millis(500),
+                4096,
+            )
+            .await
+            .expect("collect output");
+        assert_eq!(response.exit_code, None);
+        assert!(response.output.contains("ping"), "{response:?}");
+    }
+}
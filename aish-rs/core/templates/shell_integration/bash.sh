@@ -0,0 +1,94 @@
+# aish shell integration for bash.
+#
+# Records the last command line, its exit status and working directory so
+# that `aish fix` can explain what went wrong, and binds Ctrl-X Ctrl-A to
+# `aish suggest`. With `--capture-stderr`, each command's stderr is also
+# teed into the state directory while it runs; the shell's own stderr
+# (prompt and line editor) is never redirected.
+# Enable with: eval "$(aish shell-init bash)"
+
+__aish_state_dir="${AISH_HOME:-$HOME/.aish}/shell/$$"
+export AISH_SHELL_STATE_DIR="$__aish_state_dir"
+mkdir -p "$__aish_state_dir"
+
+__aish_capture_output="@AISH_CAPTURE_OUTPUT@"
+__aish_pending=""
+__aish_stderr_fd=""
+__aish_at_prompt=0
+__aish_exit=0
+__aish_last_histnum="$(HISTTIMEFORMAT='' builtin history 1 | sed -n '1s/^ *\([0-9]*\).*/\1/p')"
+
+# A single tee for the lifetime of the shell; preexec points fd 2 at it for
+# the duration of each command and precmd restores the terminal.
+if [ -n "$__aish_capture_output" ]; then
+    : >"$__aish_state_dir/stderr"
+    exec {__aish_tee_fd}> >(tee -a "$__aish_state_dir/stderr" >&2)
+fi
+
+# `aish fix` and `aish suggest` are about the previous command; recording
+# them would replace it.
+__aish_is_own_command() {
+    [[ $1 =~ ^[[:space:]]*aish[[:space:]]+(fix|suggest)([[:space:]]|$) ]]
+}
+
+__aish_preexec() {
+    [ -n "$COMP_LINE" ] && return
+    [ -n "${READLINE_LINE+set}" ] && return
+    [ "$__aish_at_prompt" = 1 ] || return
+    __aish_at_prompt=0
+    # PROMPT_COMMAND starts with this assignment. Reaching it at the prompt
+    # means the line was empty, and nothing in PROMPT_COMMAND is the user's.
+    [ "$BASH_COMMAND" = "__aish_exit=\$?" ] && return
+    local __aish_entry __aish_histnum
+    __aish_entry="$(HISTTIMEFORMAT='' builtin history 1)"
+    __aish_histnum="$(printf '%s' "$__aish_entry" | sed -n '1s/^ *\([0-9]*\).*/\1/p')"
+    if [ -n "$__aish_histnum" ] && [ "$__aish_histnum" != "$__aish_last_histnum" ]; then
+        __aish_last_histnum="$__aish_histnum"
+        __aish_pending="$(printf '%s' "$__aish_entry" | sed '1s/^ *[0-9]*\*\{0,1\} *//')"
+    else
+        # The line was not added to history (HISTCONTROL, HISTIGNORE), so
+        # the last entry belongs to an earlier command.
+        __aish_pending="$BASH_COMMAND"
+    fi
+    if __aish_is_own_command "$__aish_pending"; then
+        __aish_pending=""
+        return
+    fi
+    if [ -n "$__aish_capture_output" ]; then
+        # Rotate the previous command's stderr now rather than in precmd so
+        # tee has had time to flush it.
+        tail -c @AISH_OUTPUT_TAIL_BYTES@ "$__aish_state_dir/stderr" >"$__aish_state_dir/output" 2>/dev/null
+        : >"$__aish_state_dir/stderr"
+        exec {__aish_stderr_fd}>&2 2>&"$__aish_tee_fd"
+    fi
+}
+
+__aish_precmd() {
+    if [ -n "$__aish_stderr_fd" ]; then
+        exec 2>&"$__aish_stderr_fd" {__aish_stderr_fd}>&-
+        __aish_stderr_fd=""
+    fi
+    if [ -n "$__aish_pending" ]; then
+        printf '%s' "$__aish_pending" >"$__aish_state_dir/command"
+        printf '%s' "$__aish_exit" >"$__aish_state_dir/status"
+        printf '%s' "$PWD" >"$__aish_state_dir/cwd"
+    fi
+    __aish_pending=""
+    __aish_at_prompt=1
+}
+
+trap '__aish_preexec' DEBUG
+PROMPT_COMMAND="__aish_exit=\$?;${PROMPT_COMMAND:+$PROMPT_COMMAND;}__aish_precmd"
+trap 'rm -rf "$__aish_state_dir"' EXIT
+
+# Ctrl-X Ctrl-A: replace the current line with the command `aish suggest`
+# proposes for it. The explanation is printed above the prompt.
+__aish_suggest_widget() {
+    [ -n "$READLINE_LINE" ] || return
+    local __aish_suggestion
+    printf '\n' >&2
+    __aish_suggestion="$(aish suggest -- "$READLINE_LINE")" || return
+    READLINE_LINE="$__aish_suggestion"
+    READLINE_POINT=${#READLINE_LINE}
+}
+bind -x '"\C-x\C-a": __aish_suggest_widget'
@@ -0,0 +1,41 @@
+# aish shell integration for fish.
+#
+# Records the last command line, its exit status and working directory so
+# that `aish fix` can explain what went wrong. fish cannot redirect its own
+# stderr, so command output is not captured. Also binds Ctrl-X Ctrl-A to
+# `aish suggest`.
+# Enable with: aish shell-init fish | source
+
+if set -q AISH_HOME
+    set -gx AISH_SHELL_STATE_DIR $AISH_HOME/shell/$fish_pid
+else
+    set -gx AISH_SHELL_STATE_DIR $HOME/.aish/shell/$fish_pid
+end
+mkdir -p $AISH_SHELL_STATE_DIR
+
+function __aish_postexec --on-event fish_postexec
+    set -l __aish_exit $status
+    # Empty lines ran nothing, and `aish fix` and `aish suggest` are about
+    # the previous command; recording them would replace it.
+    string match -qr '^\s*$' -- $argv[1]; and return
+    string match -qr '^\s*aish\s+(fix|suggest)(\s|$)' -- $argv[1]; and return
+    printf '%s' $argv[1] >$AISH_SHELL_STATE_DIR/command
+    printf '%s' $__aish_exit >$AISH_SHELL_STATE_DIR/status
+    printf '%s' $PWD >$AISH_SHELL_STATE_DIR/cwd
+end
+
+function __aish_cleanup --on-event fish_exit
+    rm -rf $AISH_SHELL_STATE_DIR
+end
+
+# Ctrl-X Ctrl-A: replace the command line with the command `aish suggest`
+# proposes for it. The explanation is printed above the prompt.
+function __aish_suggest_widget
+    set -l __aish_line (commandline)
+    test -n "$__aish_line"; or return
+    echo >&2
+    set -l __aish_suggestion (aish suggest -- "$__aish_line"); or return
+    commandline -r -- $__aish_suggestion
+    commandline -f repaint
+end
+bind \cx\ca __aish_suggest_widget
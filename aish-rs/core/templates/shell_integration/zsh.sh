@@ -0,0 +1,77 @@
+# aish shell integration for zsh.
+#
+# Records the last command line, its exit status and working directory so
+# that `aish fix` can explain what went wrong, and binds Ctrl-X Ctrl-A to
+# `aish suggest`. With `--capture-stderr`, each command's stderr is also
+# teed into the state directory while it runs; the shell's own stderr
+# (prompt and line editor) is never redirected.
+# Enable with: eval "$(aish shell-init zsh)"
+
+__aish_state_dir="${AISH_HOME:-$HOME/.aish}/shell/$$"
+export AISH_SHELL_STATE_DIR="$__aish_state_dir"
+mkdir -p "$__aish_state_dir"
+
+__aish_capture_output="@AISH_CAPTURE_OUTPUT@"
+__aish_pending=""
+__aish_stderr_fd=""
+
+# A single tee for the lifetime of the shell; preexec points fd 2 at it for
+# the duration of each command and precmd restores the terminal.
+if [[ -n "$__aish_capture_output" ]]; then
+    : >|"$__aish_state_dir/stderr"
+    exec {__aish_tee_fd}> >(tee -a "$__aish_state_dir/stderr" >&2)
+fi
+
+# `aish fix` and `aish suggest` are about the previous command; recording
+# them would replace it.
+__aish_is_own_command() {
+    [[ $1 =~ '^[[:space:]]*aish[[:space:]]+(fix|suggest)([[:space:]]|$)' ]]
+}
+
+__aish_preexec() {
+    __aish_is_own_command "$1" && return
+    __aish_pending="$1"
+    if [[ -n "$__aish_capture_output" ]]; then
+        # Rotate the previous command's stderr now rather than in precmd so
+        # tee has had time to flush it.
+        tail -c @AISH_OUTPUT_TAIL_BYTES@ "$__aish_state_dir/stderr" >|"$__aish_state_dir/output" 2>/dev/null
+        : >|"$__aish_state_dir/stderr"
+        exec {__aish_stderr_fd}>&2 2>&"$__aish_tee_fd"
+    fi
+}
+
+__aish_precmd() {
+    local __aish_exit=$?
+    if [[ -n "$__aish_stderr_fd" ]]; then
+        exec 2>&"$__aish_stderr_fd" {__aish_stderr_fd}>&-
+        __aish_stderr_fd=""
+    fi
+    if [[ -n "$__aish_pending" ]]; then
+        printf '%s' "$__aish_pending" >|"$__aish_state_dir/command"
+        printf '%s' "$__aish_exit" >|"$__aish_state_dir/status"
+        printf '%s' "$PWD" >|"$__aish_state_dir/cwd"
+    fi
+    __aish_pending=""
+}
+
+__aish_cleanup() {
+    rm -rf "$__aish_state_dir"
+}
+
+autoload -Uz add-zsh-hook
+add-zsh-hook preexec __aish_preexec
+add-zsh-hook precmd __aish_precmd
+add-zsh-hook zshexit __aish_cleanup
+
+# Ctrl-X Ctrl-A: replace the current buffer with the command `aish suggest`
+# proposes for it. The explanation is printed above the prompt.
+__aish_suggest_widget() {
+    [[ -n "$BUFFER" ]] || return
+    local __aish_suggestion
+    zle -I
+    __aish_suggestion="$(aish suggest -- "$BUFFER")" || return
+    BUFFER="$__aish_suggestion"
+    CURSOR=${#BUFFER}
+}
+zle -N __aish_suggest_widget
+bindkey '^X^A' __aish_suggest_widget
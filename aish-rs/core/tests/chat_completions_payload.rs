@@ -50,6 +50,7 @@ async fn run_request(input: Vec<ResponseItem>) -> Value {
         env_key_instructions: None,
         experimental_bearer_token: None,
         wire_api: WireApi::Chat,
+        prompt_cache: None,
         query_params: None,
         http_headers: None,
         env_http_headers: None,
@@ -48,6 +48,7 @@ async fn run_stream_with_bytes(sse_body: &[u8]) -> Vec<ResponseEvent> {
         env_key_instructions: None,
         experimental_bearer_token: None,
         wire_api: WireApi::Chat,
+        prompt_cache: None,
         query_params: None,
         http_headers: None,
         env_http_headers: None,
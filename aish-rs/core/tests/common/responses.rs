@@ -593,6 +593,14 @@ fn compact_mock() -> (MockBuilder, ResponseMock) {
     (mock, response_mock)
 }
 
+fn messages_mock() -> (MockBuilder, ResponseMock) {
+    let response_mock = ResponseMock::new();
+    let mock = Mock::given(method("POST"))
+        .and(path_regex(".*/messages$"))
+        .and(response_mock.clone());
+    (mock, response_mock)
+}
+
 fn models_mock() -> (MockBuilder, ModelsMock) {
     let models_mock = ModelsMock::new();
     let mock = Mock::given(method("GET"))
@@ -751,6 +759,32 @@ pub async fn mount_function_call_agent_response(
 /// POST to `/v1/responses`. Panics if more requests are received than bodies
 /// provided. Also asserts the exact number of expected calls.
 pub async fn mount_sse_sequence(server: &MockServer, bodies: Vec<String>) -> ResponseMock {
+    mount_sequence(server, base_mock(), bodies).await
+}
+
+/// Like [`mount_sse_sequence`], for POSTs to the Messages API at
+/// `/v1/messages`. Build the bodies with [`messages_sse`].
+pub async fn mount_messages_sse_sequence(server: &MockServer, bodies: Vec<String>) -> ResponseMock {
+    mount_sequence(server, messages_mock(), bodies).await
+}
+
+/// Build a Messages API SSE stream body. Unlike [`sse`], every event carries
+/// its JSON as `data`, including single-field ones like `message_stop`.
+pub fn messages_sse(events: Vec<Value>) -> String {
+    use std::fmt::Write as _;
+    let mut out = String::new();
+    for ev in events {
+        let kind = ev.get("type").and_then(|v| v.as_str()).unwrap();
+        write!(&mut out, "event: {kind}\ndata: {ev}\n\n").unwrap();
+    }
+    out
+}
+
+async fn mount_sequence(
+    server: &MockServer,
+    (mock, response_mock): (MockBuilder, ResponseMock),
+    bodies: Vec<String>,
+) -> ResponseMock {
     use std::sync::atomic::AtomicUsize;
     use std::sync::atomic::Ordering;
 
@@ -777,7 +811,6 @@ pub async fn mount_sse_sequence(server: &MockServer, bodies: Vec<String>) -> Res
         responses: bodies,
     };
 
-    let (mock, response_mock) = base_mock();
     mock.respond_with(responder)
         .up_to_n_times(num_calls as u64)
         .expect(num_calls as u64)
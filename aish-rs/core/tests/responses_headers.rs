@@ -42,6 +42,7 @@ async fn responses_stream_includes_subagent_header_on_my_task_alias() {
         env_key_instructions: None,
         experimental_bearer_token: None,
         wire_api: WireApi::Responses,
+        prompt_cache: None,
         query_params: None,
         http_headers: None,
         env_http_headers: None,
@@ -123,6 +124,7 @@ async fn responses_stream_includes_subagent_header_on_other() {
         env_key_instructions: None,
         experimental_bearer_token: None,
         wire_api: WireApi::Responses,
+        prompt_cache: None,
         query_params: None,
         http_headers: None,
         env_http_headers: None,
@@ -199,6 +201,7 @@ async fn responses_respects_model_family_overrides_from_config() {
         env_key_instructions: None,
         experimental_bearer_token: None,
         wire_api: WireApi::Responses,
+        prompt_cache: None,
         query_params: None,
         http_headers: None,
         env_http_headers: None,
@@ -566,6 +566,9 @@ fn scenarios() -> Vec<ScenarioSpec> {
         network_access,
         exclude_tmpdir_env_var: false,
         exclude_slash_tmp: false,
+        network_allowlist: Vec::new(),
+        writable_roots: Vec::new(),
+        deny_read: Vec::new(),
     };
 
     vec![
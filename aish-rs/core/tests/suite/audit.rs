@@ -0,0 +1,120 @@
+#![allow(clippy::unwrap_used, clippy::expect_used)]
+
+use aish_core::config::Constrained;
+use aish_core::protocol::AskForApproval;
+use aish_core::protocol::EventMsg;
+use aish_core::protocol::Op;
+use aish_core::protocol::SandboxPolicy;
+use aish_protocol::config_types::ReasoningSummary;
+use aish_protocol::protocol::ReviewDecision;
+use aish_protocol::user_input::UserInput;
+use anyhow::Result;
+use core_test_support::responses::ev_apply_patch_function_call;
+use core_test_support::responses::ev_assistant_message;
+use core_test_support::responses::ev_completed;
+use core_test_support::responses::ev_response_created;
+use core_test_support::responses::mount_sse_once;
+use core_test_support::responses::sse;
+use core_test_support::responses::start_mock_server;
+use core_test_support::test_aish::test_aish;
+use core_test_support::wait_for_event;
+use pretty_assertions::assert_eq;
+use serde_json::Value;
+use serde_json::json;
+use std::fs;
+
+/// Has the model propose a patch under `UnlessTrusted`, answers the approval
+/// prompt with `decision` and returns the audit records written.
+async fn audit_patch_with_decision(decision: ReviewDecision) -> Result<Vec<Value>> {
+    let server = start_mock_server().await;
+    let test = test_aish()
+        .with_model("test-model")
+        .with_config(|config| {
+            config.approval_policy = Constrained::allow_any(AskForApproval::UnlessTrusted);
+            config.sandbox_policy = Constrained::allow_any(SandboxPolicy::DangerFullAccess);
+            config.audit.path = Some(config.codex_home.join("audit.jsonl"));
+        })
+        .build(&server)
+        .await?;
+
+    let patch = "*** Begin Patch\n*** Add File: audited.txt\n+hello\n*** End Patch\n";
+    mount_sse_once(
+        &server,
+        sse(vec![
+            ev_response_created("resp-1"),
+            ev_apply_patch_function_call("patch-call", patch),
+            ev_completed("resp-1"),
+        ]),
+    )
+    .await;
+    mount_sse_once(
+        &server,
+        sse(vec![
+            ev_assistant_message("msg-1", "done"),
+            ev_completed("resp-2"),
+        ]),
+    )
+    .await;
+
+    test.codex
+        .submit(Op::UserTurn {
+            items: vec![UserInput::Text {
+                text: "add a file".into(),
+            }],
+            final_output_json_schema: None,
+            cwd: test.cwd.path().to_path_buf(),
+            approval_policy: AskForApproval::UnlessTrusted,
+            sandbox_policy: SandboxPolicy::DangerFullAccess,
+            model: test.session_configured.model.clone(),
+            effort: None,
+            summary: ReasoningSummary::Auto,
+        })
+        .await?;
+    wait_for_event(&test.codex, |event| {
+        matches!(event, EventMsg::ApplyPatchApprovalRequest(_))
+    })
+    .await;
+    test.codex
+        .submit(Op::PatchApproval {
+            id: "0".into(),
+            decision,
+        })
+        .await?;
+    wait_for_event(&test.codex, |event| {
+        matches!(event, EventMsg::TaskComplete(_))
+    })
+    .await;
+
+    let log = fs::read_to_string(test.codex_home_path().join("audit.jsonl"))?;
+    Ok(log
+        .lines()
+        .map(|line| serde_json::from_str(line).expect("audit record"))
+        .collect())
+}
+
+#[tokio::test]
+async fn approved_patch_records_the_users_decision() -> Result<()> {
+    let records = audit_patch_with_decision(ReviewDecision::Approved).await?;
+
+    assert_eq!(records.len(), 1);
+    assert_eq!(
+        records[0]["approval"],
+        json!({"source": "user", "decision": "approved"})
+    );
+    assert_eq!(records[0]["outcome"], json!("ran"));
+    Ok(())
+}
+
+#[tokio::test]
+async fn rejected_patch_is_recorded() -> Result<()> {
+    let records = audit_patch_with_decision(ReviewDecision::Denied).await?;
+
+    assert_eq!(records.len(), 1);
+    assert_eq!(
+        records[0]["approval"],
+        json!({"source": "user", "decision": "denied"})
+    );
+    assert_eq!(records[0]["outcome"], json!("rejected"));
+    assert_eq!(records[0]["argv"], json!(["apply_patch"]));
+    Ok(())
+}
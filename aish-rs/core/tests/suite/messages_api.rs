@@ -0,0 +1,181 @@
+//! Runs a tool-using turn against a provider configured with
+//! `wire_api = "messages"`.
+
+use aish_core::ModelProviderInfo;
+use aish_core::WireApi;
+use aish_core::protocol::EventMsg;
+use aish_core::protocol::Op;
+use aish_protocol::openai_models::ReasoningEffort;
+use aish_protocol::user_input::UserInput;
+use core_test_support::responses::messages_sse;
+use core_test_support::responses::mount_messages_sse_sequence;
+use core_test_support::responses::start_mock_server;
+use core_test_support::skip_if_no_network;
+use core_test_support::test_aish::TestAish;
+use core_test_support::test_aish::test_aish;
+use core_test_support::wait_for_event;
+use pretty_assertions::assert_eq;
+use serde_json::Value;
+use serde_json::json;
+
+fn messages_provider(server_uri: &str) -> ModelProviderInfo {
+    ModelProviderInfo {
+        name: "anthropic".into(),
+        base_url: Some(format!("{server_uri}/v1")),
+        // Any variable that is set works as the API key.
+        env_key: Some("PATH".into()),
+        env_key_instructions: None,
+        experimental_bearer_token: None,
+        wire_api: WireApi::Messages,
+        prompt_cache: None,
+        query_params: None,
+        http_headers: None,
+        env_http_headers: None,
+        request_max_retries: Some(0),
+        stream_max_retries: Some(0),
+        stream_idle_timeout_ms: Some(2000),
+    }
+}
+
+fn message_start(id: &str, usage: Value) -> Value {
+    json!({"type": "message_start", "message": {"id": id, "usage": usage}})
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn messages_turn_replays_thinking_and_tool_results() -> anyhow::Result<()> {
+    skip_if_no_network!(Ok(()));
+
+    let server = start_mock_server().await;
+
+    let plan_args = json!({
+        "plan": [{"step": "Inspect workspace", "status": "in_progress"}],
+    });
+    let tool_turn = messages_sse(vec![
+        message_start("msg_1", json!({"input_tokens": 500, "output_tokens": 1})),
+        json!({"type": "content_block_start", "index": 0,
+               "content_block": {"type": "thinking", "thinking": ""}}),
+        json!({"type": "content_block_delta", "index": 0,
+               "delta": {"type": "thinking_delta", "thinking": "plan first"}}),
+        json!({"type": "content_block_delta", "index": 0,
+               "delta": {"type": "signature_delta", "signature": "sig-1"}}),
+        json!({"type": "content_block_stop", "index": 0}),
+        json!({"type": "content_block_start", "index": 1,
+               "content_block": {"type": "tool_use", "id": "toolu_1", "name": "update_plan", "input": {}}}),
+        json!({"type": "content_block_delta", "index": 1,
+               "delta": {"type": "input_json_delta", "partial_json": plan_args.to_string()}}),
+        json!({"type": "content_block_stop", "index": 1}),
+        json!({"type": "message_delta", "delta": {"stop_reason": "tool_use"},
+               "usage": {"output_tokens": 40}}),
+        json!({"type": "message_stop"}),
+    ]);
+    let final_turn = messages_sse(vec![
+        message_start(
+            "msg_2",
+            json!({"input_tokens": 10, "cache_read_input_tokens": 540, "output_tokens": 1}),
+        ),
+        json!({"type": "content_block_start", "index": 0,
+               "content_block": {"type": "text", "text": ""}}),
+        json!({"type": "content_block_delta", "index": 0,
+               "delta": {"type": "text_delta", "text": "plan updated"}}),
+        json!({"type": "content_block_stop", "index": 0}),
+        json!({"type": "message_delta", "delta": {"stop_reason": "end_turn"},
+               "usage": {"output_tokens": 5}}),
+        json!({"type": "message_stop"}),
+    ]);
+    let mock = mount_messages_sse_sequence(&server, vec![tool_turn, final_turn]).await;
+
+    let model_provider = messages_provider(&server.uri());
+    let TestAish { codex, .. } = test_aish()
+        .with_config(move |config| {
+            config.model_provider = model_provider;
+            config.model_reasoning_effort = Some(ReasoningEffort::Low);
+        })
+        .build(&server)
+        .await?;
+
+    codex
+        .submit(Op::UserInput {
+            items: vec![UserInput::Text {
+                text: "make a plan".into(),
+            }],
+        })
+        .await?;
+
+    let token_event = wait_for_event(&codex, |event| {
+        matches!(
+            event,
+            EventMsg::TokenCount(payload)
+                if payload
+                    .info
+                    .as_ref()
+                    .is_some_and(|info| info.last_token_usage.cached_input_tokens > 0)
+        )
+    })
+    .await;
+    let EventMsg::TokenCount(token_payload) = token_event else {
+        unreachable!("wait_for_event returned unexpected event");
+    };
+    let last_usage = token_payload
+        .info
+        .expect("token usage info")
+        .last_token_usage;
+    assert_eq!(last_usage.input_tokens, 550);
+    assert_eq!(last_usage.cached_input_tokens, 540);
+    assert_eq!(last_usage.output_tokens, 5);
+
+    wait_for_event(&codex, |event| matches!(event, EventMsg::TaskComplete(_))).await;
+
+    let requests = mock.requests();
+    assert_eq!(requests.len(), 2);
+    let request = &requests[1];
+    assert_eq!(request.path(), "/v1/messages");
+    assert_eq!(request.header("x-api-key"), std::env::var("PATH").ok());
+    assert_eq!(
+        request.header("anthropic-version").as_deref(),
+        Some("2023-06-01")
+    );
+
+    let body = request.body_json();
+    assert_eq!(
+        body["thinking"],
+        json!({"type": "enabled", "budget_tokens": 4096})
+    );
+    assert_eq!(
+        body["system"][0]["cache_control"],
+        json!({"type": "ephemeral"})
+    );
+    assert!(
+        body["tools"]
+            .as_array()
+            .and_then(|tools| tools.last())
+            .is_some_and(|tool| tool.get("cache_control").is_some()),
+        "expected a cache breakpoint on the last tool: {body}"
+    );
+
+    let messages = body["messages"].as_array().expect("messages array");
+    let [.., assistant, tool_results] = messages.as_slice() else {
+        panic!("expected at least two messages: {body}");
+    };
+    assert_eq!(
+        assistant,
+        &json!({
+            "role": "assistant",
+            "content": [
+                {"type": "thinking", "thinking": "plan first", "signature": "sig-1"},
+                {"type": "tool_use", "id": "toolu_1", "name": "update_plan", "input": plan_args},
+            ],
+        })
+    );
+    assert_eq!(tool_results["role"], "user");
+    assert_eq!(
+        tool_results["content"],
+        json!([{
+            "type": "tool_result",
+            "tool_use_id": "toolu_1",
+            "content": "Plan updated",
+            "cache_control": {"type": "ephemeral"},
+        }])
+    );
+
+    Ok(())
+}
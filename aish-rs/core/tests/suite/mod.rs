@@ -18,6 +18,7 @@ mod abort_tasks;
 mod apply_patch_cli;
 #[cfg(not(target_os = "windows"))]
 mod approvals;
+mod audit;
 mod cli_stream;
 mod client;
 mod compact;
@@ -32,17 +33,21 @@ mod json_result;
 mod list_dir;
 mod list_models;
 mod live_cli;
+mod messages_api;
 mod model_overrides;
 mod model_tools;
 
 mod prompt_caching;
+mod provider_fallback;
 mod quota_exceeded;
 mod read_file;
+mod recipes;
 mod resume;
 mod resume_warning;
 mod rmcp_client;
 mod rollout_list_find;
 mod seatbelt;
+mod session_cost;
 mod shell_command;
 mod shell_serialization;
 mod skills;
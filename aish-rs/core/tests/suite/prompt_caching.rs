@@ -318,6 +318,9 @@ async fn overrides_turn_context_but_keeps_cached_prefix_and_key_constant() -> an
                 network_access: true,
                 exclude_tmpdir_env_var: true,
                 exclude_slash_tmp: true,
+                network_allowlist: Vec::new(),
+                writable_roots: Vec::new(),
+                deny_read: Vec::new(),
             }),
             model: Some("test-model".to_string()),
             effort: Some(Some(ReasoningEffort::High)),
@@ -514,6 +517,9 @@ async fn per_turn_overrides_keep_cached_prefix_and_key_constant() -> anyhow::Res
                 network_access: true,
                 exclude_tmpdir_env_var: true,
                 exclude_slash_tmp: true,
+                network_allowlist: Vec::new(),
+                writable_roots: Vec::new(),
+                deny_read: Vec::new(),
             },
             model: "test-model".to_string(),
             effort: Some(ReasoningEffort::High),
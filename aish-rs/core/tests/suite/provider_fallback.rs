@@ -0,0 +1,115 @@
+//! Moves a turn to the next provider in the profile's fallback chain once
+//! the primary provider keeps failing.
+
+use aish_core::ModelProviderInfo;
+use aish_core::WireApi;
+use aish_core::config::types::ModelProviderFallback;
+use aish_core::protocol::EventMsg;
+use aish_core::protocol::Op;
+use aish_protocol::user_input::UserInput;
+use core_test_support::responses::ev_assistant_message;
+use core_test_support::responses::ev_completed;
+use core_test_support::responses::ev_response_created;
+use core_test_support::responses::mount_sse_once;
+use core_test_support::responses::sse;
+use core_test_support::responses::start_mock_server;
+use core_test_support::skip_if_no_network;
+use core_test_support::test_aish::TestAish;
+use core_test_support::test_aish::test_aish;
+use core_test_support::wait_for_event;
+use pretty_assertions::assert_eq;
+use wiremock::Mock;
+use wiremock::ResponseTemplate;
+use wiremock::matchers::method;
+use wiremock::matchers::path;
+
+fn provider(name: &str, server_uri: &str) -> ModelProviderInfo {
+    ModelProviderInfo {
+        name: name.into(),
+        base_url: Some(format!("{server_uri}/v1")),
+        // Any variable that is set works as the API key.
+        env_key: Some("PATH".into()),
+        env_key_instructions: None,
+        experimental_bearer_token: None,
+        wire_api: WireApi::Responses,
+        prompt_cache: None,
+        query_params: None,
+        http_headers: None,
+        env_http_headers: None,
+        request_max_retries: Some(0),
+        stream_max_retries: Some(0),
+        stream_idle_timeout_ms: Some(2_000),
+    }
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn failing_provider_switches_to_fallback() -> anyhow::Result<()> {
+    skip_if_no_network!(Ok(()));
+
+    let primary = start_mock_server().await;
+    let backup = start_mock_server().await;
+
+    Mock::given(method("POST"))
+        .and(path("/v1/responses"))
+        .respond_with(ResponseTemplate::new(500))
+        .expect(1)
+        .mount(&primary)
+        .await;
+    let backup_mock = mount_sse_once(
+        &backup,
+        sse(vec![
+            ev_response_created("resp-1"),
+            ev_assistant_message("msg-1", "served by backup"),
+            ev_completed("resp-1"),
+        ]),
+    )
+    .await;
+
+    let primary_provider = provider("primary", &primary.uri());
+    let backup_provider = provider("backup", &backup.uri());
+    let TestAish { codex, .. } = test_aish()
+        .with_config(move |config| {
+            config.model_provider = primary_provider;
+            config.model_provider_fallbacks = vec![ModelProviderFallback {
+                provider_id: "backup".to_string(),
+                provider: backup_provider,
+                model: Some("backup-model".to_string()),
+            }];
+        })
+        .build(&primary)
+        .await?;
+
+    codex
+        .submit(Op::UserInput {
+            items: vec![UserInput::Text {
+                text: "hello".into(),
+            }],
+        })
+        .await?;
+
+    wait_for_event(&codex, |event| {
+        matches!(
+            event,
+            EventMsg::Warning(warning)
+                if warning.message.contains("Switching to `backup` (backup-model)")
+        )
+    })
+    .await;
+
+    let switched = wait_for_event(&codex, |event| {
+        matches!(event, EventMsg::ModelProviderSwitched(_))
+    })
+    .await;
+    let EventMsg::ModelProviderSwitched(switched) = switched else {
+        unreachable!("wait_for_event returned unexpected event");
+    };
+    assert_eq!(switched.model_provider_id, "backup");
+    assert_eq!(switched.model, "backup-model");
+
+    wait_for_event(&codex, |event| matches!(event, EventMsg::TaskComplete(_))).await;
+
+    let request = backup_mock.single_request();
+    assert_eq!(request.body_json()["model"], "backup-model");
+
+    Ok(())
+}
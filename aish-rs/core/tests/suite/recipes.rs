@@ -0,0 +1,115 @@
+#![allow(clippy::unwrap_used, clippy::expect_used)]
+
+use aish_core::config::Constrained;
+use aish_core::protocol::AskForApproval;
+use aish_core::protocol::EventMsg;
+use aish_core::protocol::ExecCommandEndEvent;
+use aish_core::protocol::ExecCommandSource;
+use aish_core::protocol::Op;
+use aish_core::protocol::SandboxPolicy;
+use anyhow::Result;
+use core_test_support::responses::start_mock_server;
+use core_test_support::test_aish::TestAish;
+use core_test_support::test_aish::TestAishBuilder;
+use core_test_support::test_aish::test_aish;
+use core_test_support::wait_for_event;
+use std::fs;
+
+fn unrestricted(builder: TestAishBuilder) -> TestAishBuilder {
+    builder.with_config(|config| {
+        config.approval_policy = Constrained::allow_any(AskForApproval::Never);
+        config.sandbox_policy = Constrained::allow_any(SandboxPolicy::DangerFullAccess);
+    })
+}
+
+/// Submit a recipe and collect its `ExecCommandEnd` and `Warning` events
+/// until the task completes.
+async fn run_recipe(
+    test: &TestAish,
+    commands: &[&str],
+) -> Result<(Vec<ExecCommandEndEvent>, Vec<String>)> {
+    test.codex
+        .submit(Op::RunRecipe {
+            name: "chores".to_string(),
+            commands: commands.iter().map(ToString::to_string).collect(),
+        })
+        .await?;
+
+    let mut ends = Vec::new();
+    let mut warnings = Vec::new();
+    loop {
+        match wait_for_event(&test.codex, |_| true).await {
+            EventMsg::ExecCommandEnd(end) => ends.push(end),
+            EventMsg::Warning(warning) => warnings.push(warning.message),
+            EventMsg::TaskComplete(_) => break,
+            _ => {}
+        }
+    }
+    Ok((ends, warnings))
+}
+
+#[tokio::test]
+async fn recipe_runs_commands_in_order_and_stops_at_first_failure() -> Result<()> {
+    if cfg!(windows) {
+        return Ok(());
+    }
+
+    let server = start_mock_server().await;
+    let test = unrestricted(test_aish()).build(&server).await?;
+
+    let (ends, warnings) = run_recipe(&test, &["echo one", "exit 3", "echo three"]).await?;
+
+    assert_eq!(ends.len(), 2);
+    assert_eq!(ends[0].exit_code, 0);
+    assert_eq!(ends[0].stdout.trim(), "one");
+    assert_eq!(ends[0].source, ExecCommandSource::UserShell);
+    assert_eq!(ends[1].exit_code, 3);
+    assert_eq!(
+        warnings,
+        vec![
+            "Recipe `chores` stopped at command 2 of 3. The remaining 1 command(s) were not run."
+                .to_string()
+        ]
+    );
+
+    let requests = server.received_requests().await.unwrap_or_default();
+    assert!(
+        requests.is_empty(),
+        "replaying a recipe must not call the model"
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn recipe_commands_go_through_execpolicy() -> Result<()> {
+    if cfg!(windows) {
+        return Ok(());
+    }
+
+    let builder = unrestricted(test_aish()).with_config(|config| {
+        let policy_path = config.codex_home.join("rules").join("policy.rules");
+        fs::create_dir_all(policy_path.parent().unwrap()).unwrap();
+        fs::write(
+            &policy_path,
+            r#"prefix_rule(pattern=["rm"], decision="forbidden")"#,
+        )
+        .unwrap();
+    });
+    let server = start_mock_server().await;
+    let test = builder.build(&server).await?;
+
+    let (ends, warnings) = run_recipe(&test, &["rm -rf build", "echo after"]).await?;
+
+    assert_eq!(ends.len(), 1);
+    assert!(
+        ends[0]
+            .aggregated_output
+            .contains("execpolicy forbids this command"),
+        "unexpected output: {}",
+        ends[0].aggregated_output
+    );
+    assert_eq!(warnings.len(), 1);
+
+    Ok(())
+}
@@ -81,6 +81,9 @@ async fn if_cwd_is_repo_root_then_dot_git_folder_is_read_only() {
         network_access: false,
         exclude_tmpdir_env_var: true,
         exclude_slash_tmp: true,
+        network_allowlist: Vec::new(),
+        writable_roots: Vec::new(),
+        deny_read: Vec::new(),
     };
 
     test_scenario
@@ -0,0 +1,94 @@
+//! Prices usage with `model_pricing` and enforces `session_budget`.
+
+use aish_core::config::types::ModelPricing;
+use aish_core::protocol::EventMsg;
+use aish_core::protocol::Op;
+use aish_protocol::user_input::UserInput;
+use core_test_support::responses::ev_assistant_message;
+use core_test_support::responses::ev_completed_with_tokens;
+use core_test_support::responses::ev_response_created;
+use core_test_support::responses::mount_sse_once;
+use core_test_support::responses::sse;
+use core_test_support::responses::start_mock_server;
+use core_test_support::skip_if_no_network;
+use core_test_support::test_aish::TestAish;
+use core_test_support::test_aish::test_aish;
+use core_test_support::wait_for_event;
+use pretty_assertions::assert_eq;
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn exhausted_budget_refuses_next_turn() -> anyhow::Result<()> {
+    skip_if_no_network!(Ok(()));
+
+    let server = start_mock_server().await;
+    let mock = mount_sse_once(
+        &server,
+        sse(vec![
+            ev_response_created("resp-1"),
+            ev_assistant_message("msg-1", "done"),
+            ev_completed_with_tokens("resp-1", 1_000_000),
+        ]),
+    )
+    .await;
+
+    let TestAish { codex, .. } = test_aish()
+        .with_config(|config| {
+            let model = config.model.clone().expect("test model");
+            config.model_pricing.insert(
+                model,
+                ModelPricing {
+                    input: 1.0,
+                    cached_input: None,
+                    output: 1.0,
+                },
+            );
+            config.session_budget = Some(1.0);
+        })
+        .build(&server)
+        .await?;
+
+    codex
+        .submit(Op::UserInput {
+            items: vec![UserInput::Text {
+                text: "first".into(),
+            }],
+        })
+        .await?;
+    let token_event = wait_for_event(
+        &codex,
+        |event| matches!(event, EventMsg::TokenCount(payload) if payload.cost.is_some()),
+    )
+    .await;
+    let EventMsg::TokenCount(token_payload) = token_event else {
+        unreachable!("wait_for_event returned unexpected event");
+    };
+    let cost = token_payload.cost.expect("cost");
+    assert_eq!(
+        (cost.turn, cost.session, cost.budget),
+        (1.0, 1.0, Some(1.0))
+    );
+    wait_for_event(&codex, |event| matches!(event, EventMsg::TaskComplete(_))).await;
+
+    codex
+        .submit(Op::UserInput {
+            items: vec![UserInput::Text {
+                text: "second".into(),
+            }],
+        })
+        .await?;
+    let error = wait_for_event(&codex, |event| matches!(event, EventMsg::Error(_))).await;
+    let EventMsg::Error(error) = error else {
+        unreachable!("wait_for_event returned unexpected event");
+    };
+    assert!(
+        error.message.starts_with("Session budget of $1.00 used up"),
+        "unexpected error: {}",
+        error.message
+    );
+    wait_for_event(&codex, |event| matches!(event, EventMsg::TaskComplete(_))).await;
+
+    // The second turn never reached the model.
+    assert_eq!(mock.requests().len(), 1);
+
+    Ok(())
+}
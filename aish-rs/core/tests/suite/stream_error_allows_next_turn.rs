@@ -66,6 +66,7 @@ async fn continue_after_stream_error() {
         env_key_instructions: None,
         experimental_bearer_token: None,
         wire_api: WireApi::Responses,
+        prompt_cache: None,
         query_params: None,
         http_headers: None,
         env_http_headers: None,
@@ -73,6 +73,7 @@ async fn retries_on_early_close() {
         env_key_instructions: None,
         experimental_bearer_token: None,
         wire_api: WireApi::Responses,
+        prompt_cache: None,
         query_params: None,
         http_headers: None,
         env_http_headers: None,
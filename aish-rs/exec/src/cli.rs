@@ -65,6 +65,11 @@ pub struct Cli {
     #[clap(skip)]
     pub config_overrides: CliConfigOverrides,
 
+    /// Internal: set by the top-level `aish suggest` wrapper. The model must
+    /// answer with a single command that is printed on stdout, not run.
+    #[clap(skip)]
+    pub suggest: bool,
+
     /// Specifies color settings for use in the output.
     #[arg(long = "color", value_enum, default_value_t = Color::Auto)]
     pub color: Color,
@@ -83,6 +88,68 @@ pub struct Cli {
     pub prompt: Option<String>,
 }
 
+/// Arguments for `aish suggest`.
+#[derive(Parser, Debug)]
+pub struct SuggestCli {
+    /// Model the agent should use.
+    #[arg(long, short = 'm')]
+    pub model: Option<String>,
+
+    /// Use open-source provider.
+    #[arg(long = "oss", default_value_t = false)]
+    pub oss: bool,
+
+    /// Configuration profile from config.toml to specify default options.
+    #[arg(long = "profile", short = 'p')]
+    pub config_profile: Option<String>,
+
+    /// Tell the agent to use the specified directory as its working root.
+    #[clap(long = "cd", short = 'C', value_name = "DIR")]
+    pub cwd: Option<PathBuf>,
+
+    #[clap(skip)]
+    pub config_overrides: CliConfigOverrides,
+
+    /// Describe the command you need. If not provided as an argument (or if
+    /// `-` is used), the request is read from stdin.
+    #[arg(value_name = "PROMPT", value_hint = clap::ValueHint::Other)]
+    pub prompt: Option<String>,
+}
+
+impl From<SuggestCli> for Cli {
+    fn from(value: SuggestCli) -> Self {
+        let SuggestCli {
+            model,
+            oss,
+            config_profile,
+            cwd,
+            config_overrides,
+            prompt,
+        } = value;
+        Self {
+            command: None,
+            images: Vec::new(),
+            model,
+            oss,
+            oss_provider: None,
+            // The model may look around but must never change anything.
+            sandbox_mode: Some(aish_common::SandboxModeCliArg::ReadOnly),
+            config_profile,
+            full_auto: false,
+            dangerously_bypass_approvals_and_sandbox: false,
+            cwd,
+            add_dir: Vec::new(),
+            output_schema: None,
+            config_overrides,
+            suggest: true,
+            color: Color::Never,
+            json: false,
+            last_message_file: None,
+            prompt,
+        }
+    }
+}
+
 #[derive(Debug, clap::Subcommand)]
 pub enum Command {
     /// Resume a previous session by id or pick the most recent with --last.
@@ -100,6 +167,12 @@ pub struct ResumeArgs {
     #[arg(long = "last", default_value_t = false)]
     pub last: bool,
 
+    /// Branch the session at this user turn (0-based) instead of resuming it:
+    /// the turns before it are copied into a new session and the original is
+    /// left untouched.
+    #[arg(long = "fork-at", value_name = "TURN")]
+    pub fork_at: Option<usize>,
+
     /// Prompt to send after resuming the session. If `-` is used, read from stdin.
     #[arg(value_name = "PROMPT", value_hint = clap::ValueHint::Other)]
     pub prompt: Option<String>,
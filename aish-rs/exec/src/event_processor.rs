@@ -22,7 +22,11 @@ pub(crate) trait EventProcessor {
     /// Handle a single event emitted by the agent.
     fn process_event(&mut self, event: Event) -> CodexStatus;
 
-    fn print_final_output(&mut self) {}
+    /// Print the run's final output. Returns `false` when the run produced
+    /// nothing usable, which makes `aish exec` exit with a non-zero status.
+    fn print_final_output(&mut self) -> bool {
+        true
+    }
 }
 
 pub(crate) fn handle_last_message(last_agent_message: Option<&str>, output_file: &Path) {
@@ -573,6 +573,10 @@ impl EventProcessor for EventProcessorWithHumanOutput {
             | EventMsg::GetHistoryEntryResponse(_)
             | EventMsg::McpListToolsResponse(_)
             | EventMsg::ListCustomPromptsResponse(_)
+            | EventMsg::ListRecipesResponse(_)
+            | EventMsg::SessionMetadataUpdated(_)
+            | EventMsg::ApprovalGrantsUpdated(_)
+            | EventMsg::ModelProviderSwitched(_)
             | EventMsg::ListSkillsResponse(_)
             | EventMsg::RawResponseItem(_)
             | EventMsg::UserMessage(_)
@@ -585,13 +589,14 @@ impl EventProcessor for EventProcessorWithHumanOutput {
             | EventMsg::ReasoningContentDelta(_)
             | EventMsg::ReasoningRawContentDelta(_)
             | EventMsg::SkillsUpdateAvailable
+            | EventMsg::BackgroundJobsUpdate(_)
             | EventMsg::UndoCompleted(_)
             | EventMsg::UndoStarted(_) => {}
         }
         CodexStatus::Running
     }
 
-    fn print_final_output(&mut self) {
+    fn print_final_output(&mut self) -> bool {
         if let Some(usage_info) = &self.last_total_token_usage {
             eprintln!(
                 "{}\n{}",
@@ -612,6 +617,7 @@ impl EventProcessor for EventProcessorWithHumanOutput {
                 println!("{message}");
             }
         }
+        true
     }
 }
 
@@ -32,6 +32,7 @@ use crate::exec_events::TurnCompletedEvent;
 use crate::exec_events::TurnFailedEvent;
 use crate::exec_events::TurnStartedEvent;
 use crate::exec_events::Usage;
+use crate::exec_events::UsageCost;
 use crate::exec_events::WebSearchItem;
 use aish_core::config::Config;
 use aish_core::protocol::AgentMessageEvent;
@@ -65,6 +66,7 @@ pub struct EventProcessorWithJsonOutput {
     // Tracks the todo list for the current turn (at most one per turn).
     running_todo_list: Option<RunningTodoList>,
     last_total_token_usage: Option<aish_core::protocol::TokenUsage>,
+    last_cost: Option<aish_core::protocol::TokenCost>,
     running_mcp_tool_calls: HashMap<String, RunningMcpToolCall>,
     last_critical_error: Option<ThreadErrorEvent>,
 }
@@ -99,6 +101,7 @@ impl EventProcessorWithJsonOutput {
             running_patch_applies: HashMap::new(),
             running_todo_list: None,
             last_total_token_usage: None,
+            last_cost: None,
             running_mcp_tool_calls: HashMap::new(),
             last_critical_error: None,
         }
@@ -125,6 +128,7 @@ impl EventProcessorWithJsonOutput {
                 if let Some(info) = &ev.info {
                     self.last_total_token_usage = Some(info.total_token_usage.clone());
                 }
+                self.last_cost = ev.cost;
                 Vec::new()
             }
             EventMsg::TaskStarted(ev) => self.handle_task_started(ev),
@@ -465,14 +469,22 @@ impl EventProcessorWithJsonOutput {
     }
 
     fn handle_task_complete(&mut self) -> Vec<ThreadEvent> {
+        let cost = self.last_cost.map(|cost| UsageCost {
+            turn: cost.turn,
+            session: cost.session,
+        });
         let usage = if let Some(u) = &self.last_total_token_usage {
             Usage {
                 input_tokens: u.input_tokens,
                 cached_input_tokens: u.cached_input_tokens,
                 output_tokens: u.output_tokens,
+                cost,
             }
         } else {
-            Usage::default()
+            Usage {
+                cost,
+                ..Usage::default()
+            }
         };
 
         let mut items = Vec::new();
@@ -64,6 +64,20 @@ pub struct Usage {
     pub cached_input_tokens: i64,
     /// The number of output tokens used during the turn.
     pub output_tokens: i64,
+    /// What the turn and the session cost, when `model_pricing` covers the
+    /// model.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub cost: Option<UsageCost>,
+}
+
+/// Cost in USD, priced with the `model_pricing` config.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, TS)]
+pub struct UsageCost {
+    /// Cost of this turn.
+    pub turn: f64,
+    /// Cost of the whole thread so far.
+    pub session: f64,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, TS)]
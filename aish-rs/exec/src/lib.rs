@@ -9,6 +9,7 @@ mod event_processor;
 mod event_processor_with_human_output;
 pub mod event_processor_with_jsonl_output;
 pub mod exec_events;
+mod suggest;
 
 use aish_common::oss::ensure_oss_provider_ready;
 use aish_common::oss::get_default_model_for_oss_provider;
@@ -33,6 +34,7 @@ use aish_protocol::user_input::UserInput;
 use aish_utils_absolute_path::AbsolutePathBuf;
 pub use cli::Cli;
 pub use cli::Command;
+pub use cli::SuggestCli;
 use event_processor_with_human_output::EventProcessorWithHumanOutput;
 use event_processor_with_jsonl_output::EventProcessorWithJsonOutput;
 use serde_json::Value;
@@ -49,8 +51,12 @@ use tracing_subscriber::prelude::*;
 use crate::cli::Command as ExecCommand;
 use crate::event_processor::CodexStatus;
 use crate::event_processor::EventProcessor;
+use crate::suggest::EventProcessorForSuggestion;
+use crate::suggest::suggestion_output_schema;
+use crate::suggest::suggestion_prompt;
 use aish_core::default_client::set_default_originator;
 use aish_core::find_conversation_path_by_id_str;
+use aish_core::fork_rollout_at;
 
 enum InitialOperation {
     UserTurn {
@@ -59,6 +65,15 @@ enum InitialOperation {
     },
 }
 
+/// Run `aish suggest`: a headless, read-only turn whose final answer is a
+/// single shell command printed on stdout.
+pub async fn run_suggest(
+    cli: SuggestCli,
+    aish_linux_sandbox_exe: Option<PathBuf>,
+) -> anyhow::Result<()> {
+    run_main(cli.into(), aish_linux_sandbox_exe).await
+}
+
 pub async fn run_main(cli: Cli, aish_linux_sandbox_exe: Option<PathBuf>) -> anyhow::Result<()> {
     if let Err(err) = set_default_originator("aish_exec".to_string()) {
         tracing::warn!(?err, "Failed to set aish exec originator override {err:?}");
@@ -82,6 +97,7 @@ pub async fn run_main(cli: Cli, aish_linux_sandbox_exe: Option<PathBuf>) -> anyh
         prompt,
         output_schema: output_schema_path,
         config_overrides,
+        suggest,
     } = cli;
 
     let (stdout_with_ansi, stderr_with_ansi) = match color {
@@ -209,13 +225,16 @@ pub async fn run_main(cli: Cli, aish_linux_sandbox_exe: Option<PathBuf>) -> anyh
 
     let _ = tracing_subscriber::registry().with(fmt_layer).try_init();
 
-    let mut event_processor: Box<dyn EventProcessor> = match json_mode {
-        true => Box::new(EventProcessorWithJsonOutput::new(last_message_file.clone())),
-        _ => Box::new(EventProcessorWithHumanOutput::create_with_ansi(
+    let mut event_processor: Box<dyn EventProcessor> = if suggest {
+        Box::new(EventProcessorForSuggestion::new())
+    } else if json_mode {
+        Box::new(EventProcessorWithJsonOutput::new(last_message_file.clone()))
+    } else {
+        Box::new(EventProcessorWithHumanOutput::create_with_ansi(
             stdout_with_ansi,
             &config,
             last_message_file.clone(),
-        )),
+        ))
     };
 
     if oss {
@@ -258,7 +277,14 @@ pub async fn run_main(cli: Cli, aish_linux_sandbox_exe: Option<PathBuf>) -> anyh
         conversation,
         session_configured,
     } = if let Some(ExecCommand::Resume(args)) = command.as_ref() {
-        let resume_path = resolve_resume_path(&config, args).await?;
+        let mut resume_path = resolve_resume_path(&config, args).await?;
+        if let Some(turn_index) = args.fork_at {
+            let Some(path) = resume_path else {
+                anyhow::bail!("--fork-at requires a saved session to fork");
+            };
+            let forked = fork_rollout_at(&config.codex_home, &path, turn_index).await?;
+            resume_path = Some(forked.path);
+        }
 
         if let Some(path) = resume_path {
             conversation_manager
@@ -304,6 +330,18 @@ pub async fn run_main(cli: Cli, aish_linux_sandbox_exe: Option<PathBuf>) -> anyh
                 prompt_text,
             )
         }
+        (None, root_prompt, _) if suggest => {
+            let prompt_text = suggestion_prompt(&resolve_prompt(root_prompt));
+            (
+                InitialOperation::UserTurn {
+                    items: vec![UserInput::Text {
+                        text: prompt_text.clone(),
+                    }],
+                    output_schema: Some(suggestion_output_schema()),
+                },
+                prompt_text,
+            )
+        }
         (None, root_prompt, imgs) => {
             let prompt_text = resolve_prompt(root_prompt);
             let mut items: Vec<UserInput> = imgs
@@ -420,8 +458,8 @@ pub async fn run_main(cli: Cli, aish_linux_sandbox_exe: Option<PathBuf>) -> anyh
             }
         }
     }
-    event_processor.print_final_output();
-    if error_seen {
+    let output_ok = event_processor.print_final_output();
+    if error_seen || !output_ok {
         std::process::exit(1);
     }
 
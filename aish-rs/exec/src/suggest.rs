@@ -0,0 +1,164 @@
+//! `aish suggest`: ask the model for exactly one shell command without running
+//! it. The command is printed alone on stdout so shell widgets can insert it
+//! into the edit buffer; the explanation goes to stderr.
+
+use aish_core::config::Config;
+use aish_core::parse_command::parse_command;
+use aish_core::protocol::ErrorEvent;
+use aish_core::protocol::Event;
+use aish_core::protocol::EventMsg;
+use aish_core::protocol::SessionConfiguredEvent;
+use aish_core::protocol::TaskCompleteEvent;
+use aish_protocol::parse_command::ParsedCommand;
+use serde::Deserialize;
+use serde_json::Value;
+use serde_json::json;
+
+use crate::event_processor::CodexStatus;
+use crate::event_processor::EventProcessor;
+
+/// The structured response the model must produce in suggest mode.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub(crate) struct SuggestedCommand {
+    pub command: String,
+    pub explanation: String,
+}
+
+/// JSON Schema passed as the turn's `final_output_json_schema`.
+pub(crate) fn suggestion_output_schema() -> Value {
+    json!({
+        "type": "object",
+        "properties": {
+            "command": {
+                "type": "string",
+                "description": "A single shell command line the user can run as-is."
+            },
+            "explanation": {
+                "type": "string",
+                "description": "One or two sentences explaining what the command does."
+            }
+        },
+        "required": ["command", "explanation"],
+        "additionalProperties": false
+    })
+}
+
+/// Wrap the user's request with the instructions for suggest mode.
+pub(crate) fn suggestion_prompt(request: &str) -> String {
+    format!(
+        "Propose exactly one shell command for the request below. Do not run it and do not \
+         modify any files; you may inspect the environment with read-only commands if needed. \
+         Reply with the command and a short explanation.\n\nRequest: {}",
+        request.trim()
+    )
+}
+
+/// One line per parsed sub-command, using the same labels as the TUI's
+/// exec cells.
+pub(crate) fn summarize_command(command: &str) -> Vec<String> {
+    let argv = vec!["bash".to_string(), "-lc".to_string(), command.to_string()];
+    parse_command(&argv)
+        .into_iter()
+        .map(|parsed| match parsed {
+            ParsedCommand::Read { name, .. } => format!("Read {name}"),
+            ParsedCommand::ListFiles { cmd, path } => format!("List {}", path.unwrap_or(cmd)),
+            ParsedCommand::Search { cmd, query, path } => match (query, path) {
+                (Some(query), Some(path)) => format!("Search {query} in {path}"),
+                (Some(query), None) => format!("Search {query}"),
+                _ => format!("Search {cmd}"),
+            },
+            ParsedCommand::Unknown { cmd } => format!("Run {cmd}"),
+        })
+        .collect()
+}
+
+/// Event processor for suggest mode: stays quiet while the turn runs and only
+/// prints the final suggestion.
+pub(crate) struct EventProcessorForSuggestion {
+    suggestion: Option<SuggestedCommand>,
+}
+
+impl EventProcessorForSuggestion {
+    pub(crate) fn new() -> Self {
+        Self { suggestion: None }
+    }
+}
+
+impl EventProcessor for EventProcessorForSuggestion {
+    fn print_config_summary(&mut self, _: &Config, _: &str, _: &SessionConfiguredEvent) {}
+
+    fn process_event(&mut self, event: Event) -> CodexStatus {
+        match event.msg {
+            EventMsg::Error(ErrorEvent { message, .. }) => {
+                eprintln!("ERROR: {message}");
+            }
+            EventMsg::TaskComplete(TaskCompleteEvent { last_agent_message }) => {
+                match last_agent_message.as_deref().map(serde_json::from_str) {
+                    Some(Ok(suggestion)) => self.suggestion = Some(suggestion),
+                    Some(Err(err)) => eprintln!("ERROR: malformed suggestion from model: {err}"),
+                    None => eprintln!("ERROR: the model did not suggest a command"),
+                }
+                return CodexStatus::InitiateShutdown;
+            }
+            EventMsg::ShutdownComplete => return CodexStatus::Shutdown,
+            _ => {}
+        }
+        CodexStatus::Running
+    }
+
+    fn print_final_output(&mut self) -> bool {
+        let Some(SuggestedCommand {
+            command,
+            explanation,
+        }) = self.suggestion.take()
+        else {
+            return false;
+        };
+
+        eprintln!("{}", explanation.trim());
+        for line in summarize_command(&command) {
+            eprintln!("  {line}");
+        }
+        #[allow(clippy::print_stdout)]
+        {
+            println!("{}", command.trim());
+        }
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn suggestion_round_trips_through_schema_shape() {
+        let suggestion: SuggestedCommand =
+            serde_json::from_str(r#"{"command":"ls -la","explanation":"List all files."}"#)
+                .expect("valid suggestion");
+        assert_eq!(
+            suggestion,
+            SuggestedCommand {
+                command: "ls -la".to_string(),
+                explanation: "List all files.".to_string(),
+            }
+        );
+        assert_eq!(
+            suggestion_output_schema()["required"],
+            json!(["command", "explanation"])
+        );
+    }
+
+    #[test]
+    fn summarize_command_uses_parsed_command_labels() {
+        assert_eq!(
+            summarize_command("cat Cargo.toml"),
+            vec!["Read Cargo.toml".to_string()]
+        );
+        assert_eq!(
+            summarize_command("cargo build --release"),
+            vec!["Run cargo build --release".to_string()]
+        );
+    }
+}
@@ -43,6 +43,7 @@ use aish_exec::exec_events::TurnCompletedEvent;
 use aish_exec::exec_events::TurnFailedEvent;
 use aish_exec::exec_events::TurnStartedEvent;
 use aish_exec::exec_events::Usage;
+use aish_exec::exec_events::UsageCost;
 use aish_exec::exec_events::WebSearchItem;
 use aish_protocol::plan_tool::PlanItemArg;
 use aish_protocol::plan_tool::StepStatus;
@@ -1050,7 +1051,10 @@ fn task_complete_produces_turn_completed_with_usage() {
     };
     let token_count_event = event(
         "e1",
-        EventMsg::TokenCount(aish_core::protocol::TokenCountEvent { info: Some(info) }),
+        EventMsg::TokenCount(aish_core::protocol::TokenCountEvent {
+            info: Some(info),
+            cost: None,
+        }),
     );
     assert!(ep.collect_thread_events(&token_count_event).is_empty());
 
@@ -1069,6 +1073,47 @@ fn task_complete_produces_turn_completed_with_usage() {
                 input_tokens: 1200,
                 cached_input_tokens: 200,
                 output_tokens: 345,
+                cost: None,
+            },
+        })]
+    );
+}
+
+#[test]
+fn task_complete_reports_cost_when_priced() {
+    let mut ep = EventProcessorWithJsonOutput::new(None);
+
+    let token_count_event = event(
+        "e1",
+        EventMsg::TokenCount(aish_core::protocol::TokenCountEvent {
+            info: None,
+            cost: Some(aish_core::protocol::TokenCost {
+                turn: 0.25,
+                session: 1.5,
+                budget: Some(5.0),
+            }),
+        }),
+    );
+    assert!(ep.collect_thread_events(&token_count_event).is_empty());
+
+    let complete_event = event(
+        "e2",
+        EventMsg::TaskComplete(aish_core::protocol::TaskCompleteEvent {
+            last_agent_message: None,
+        }),
+    );
+    let out = ep.collect_thread_events(&complete_event);
+    assert_eq!(
+        out,
+        vec![ThreadEvent::TurnCompleted(TurnCompletedEvent {
+            usage: Usage {
+                input_tokens: 0,
+                cached_input_tokens: 0,
+                output_tokens: 0,
+                cost: Some(UsageCost {
+                    turn: 0.25,
+                    session: 1.5,
+                }),
             },
         })]
     );
@@ -63,6 +63,9 @@ async fn python_multiprocessing_lock_works_under_sandbox() {
         network_access: false,
         exclude_tmpdir_env_var: false,
         exclude_slash_tmp: false,
+        network_allowlist: Vec::new(),
+        writable_roots: Vec::new(),
+        deny_read: Vec::new(),
     };
 
     let python_code = r#"import multiprocessing
@@ -159,6 +162,9 @@ async fn sandbox_distinguishes_command_and_policy_cwds() {
         network_access: false,
         exclude_tmpdir_env_var: true,
         exclude_slash_tmp: true,
+        network_allowlist: Vec::new(),
+        writable_roots: Vec::new(),
+        deny_read: Vec::new(),
     };
 
     // Attempt to write inside the command cwd, which is outside of the sandbox policy cwd.
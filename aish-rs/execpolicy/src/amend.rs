@@ -9,6 +9,8 @@ use std::path::PathBuf;
 use serde_json;
 use thiserror::Error;
 
+use crate::definition::RuleDefinition;
+
 #[derive(Debug, Error)]
 pub enum AmendError {
     #[error("prefix rule requires at least one token")]
@@ -52,6 +54,10 @@ pub enum AmendError {
         path: PathBuf,
         source: std::io::Error,
     },
+    #[error("the location of the rule in {path} is unknown")]
+    UnknownRuleLocation { path: PathBuf },
+    #[error("{path} changed since it was loaded; run the command again")]
+    PolicyFileChanged { path: PathBuf },
 }
 
 /// Note this thread uses advisory file locking and performs blocking I/O, so it should be used with
@@ -145,6 +151,65 @@ fn append_locked_line(policy_path: &Path, line: &str) -> Result<(), AmendError>
     Ok(())
 }
 
+/// Deletes the lines of `definition` from `policy_path`, refusing if the file
+/// no longer has the rule at those lines.
+///
+/// Like [`blocking_append_allow_prefix_rule`], this locks the file and performs
+/// blocking I/O.
+pub fn blocking_remove_rule(
+    policy_path: &Path,
+    definition: &RuleDefinition,
+) -> Result<(), AmendError> {
+    let Some(lines) = definition.lines.clone() else {
+        return Err(AmendError::UnknownRuleLocation {
+            path: policy_path.to_path_buf(),
+        });
+    };
+    let mut file = OpenOptions::new()
+        .read(true)
+        .write(true)
+        .open(policy_path)
+        .map_err(|source| AmendError::OpenPolicyFile {
+            path: policy_path.to_path_buf(),
+            source,
+        })?;
+    file.lock().map_err(|source| AmendError::LockPolicyFile {
+        path: policy_path.to_path_buf(),
+        source,
+    })?;
+
+    let mut contents = String::new();
+    file.read_to_string(&mut contents)
+        .map_err(|source| AmendError::ReadPolicyFile {
+            path: policy_path.to_path_buf(),
+            source,
+        })?;
+    let all_lines: Vec<&str> = contents.lines().collect();
+    let (start, end) = (lines.start() - 1, *lines.end());
+    if end > all_lines.len() || all_lines[start..end].join("\n") != definition.text {
+        return Err(AmendError::PolicyFileChanged {
+            path: policy_path.to_path_buf(),
+        });
+    }
+
+    let mut remaining = [&all_lines[..start], &all_lines[end..]].concat().join("\n");
+    if !remaining.is_empty() {
+        remaining.push('\n');
+    }
+    let write_err = |source| AmendError::WritePolicyFile {
+        path: policy_path.to_path_buf(),
+        source,
+    };
+    file.set_len(0).map_err(write_err)?;
+    file.seek(SeekFrom::Start(0))
+        .map_err(|source| AmendError::SeekPolicyFile {
+            path: policy_path.to_path_buf(),
+            source,
+        })?;
+    file.write_all(remaining.as_bytes()).map_err(write_err)?;
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -222,4 +287,36 @@ prefix_rule(pattern=["echo", "Hello, world!"], decision="allow")
 "#
         );
     }
+
+    #[test]
+    fn removes_rule_lines_and_keeps_the_rest() {
+        let tmp = tempdir().expect("create temp dir");
+        let policy_path = tmp.path().join("default.rules");
+        let contents = r#"prefix_rule(pattern=["ls"], decision="allow")
+prefix_rule(
+    pattern = ["git", "push"],
+    decision = "prompt",
+)
+prefix_rule(pattern=["echo"], decision="allow")
+"#;
+        std::fs::write(&policy_path, contents).expect("write policy");
+        let mut parser = crate::PolicyParser::new();
+        parser
+            .parse(&policy_path.to_string_lossy(), contents)
+            .expect("parse policy");
+        let (_, definitions) = parser.build_with_definitions();
+        assert_eq!(definitions[1].lines, Some(2..=5));
+
+        blocking_remove_rule(&policy_path, &definitions[1]).expect("remove rule");
+
+        assert_eq!(
+            std::fs::read_to_string(&policy_path).expect("read policy"),
+            r#"prefix_rule(pattern=["ls"], decision="allow")
+prefix_rule(pattern=["echo"], decision="allow")
+"#
+        );
+        let err =
+            blocking_remove_rule(&policy_path, &definitions[1]).expect_err("rule is already gone");
+        assert!(matches!(err, AmendError::PolicyFileChanged { .. }));
+    }
 }
@@ -24,4 +24,13 @@ impl Decision {
             other => Err(Error::InvalidDecision(other.to_string())),
         }
     }
+
+    /// The spelling used in policy files.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Allow => "allow",
+            Self::Prompt => "prompt",
+            Self::Forbidden => "forbidden",
+        }
+    }
 }
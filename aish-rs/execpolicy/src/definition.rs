@@ -0,0 +1,199 @@
+//! Where each rule of a policy was written, so rules can be listed, explained
+//! and removed by the file and line that defines them.
+
+use std::ops::RangeInclusive;
+
+use crate::decision::Decision;
+use crate::error::Result;
+use crate::rule::RuleMatch;
+use crate::rule::RuleRef;
+use crate::rule::validate_match_examples;
+use crate::rule::validate_not_match_examples;
+
+/// One rule builtin call, e.g. `prefix_rule(...)`, and the rules it produced
+/// (one per alternative of the first pattern token).
+#[derive(Clone, Debug)]
+pub struct RuleDefinition {
+    /// Identifier the policy was parsed with, usually the file path.
+    pub source: String,
+    /// 1-based lines spanned by the call, when Starlark reports them.
+    pub lines: Option<RangeInclusive<usize>>,
+    /// The call as written in the policy file.
+    pub text: String,
+    pub decision: Decision,
+    pub rules: Vec<RuleRef>,
+    pub matches: Vec<Vec<String>>,
+    pub not_matches: Vec<Vec<String>>,
+}
+
+impl RuleDefinition {
+    /// `source:line`, the form `aish execpolicy remove` accepts.
+    pub fn location(&self) -> String {
+        match &self.lines {
+            Some(lines) => format!("{}:{}", self.source, lines.start()),
+            None => self.source.clone(),
+        }
+    }
+
+    /// The first match of `cmd` against any of this definition's rules.
+    pub fn matches(&self, cmd: &[String]) -> Option<RuleMatch> {
+        self.rules.iter().find_map(|rule| rule.matches(cmd))
+    }
+
+    /// Re-runs the `match` / `not_match` examples, which the parser already
+    /// checks when the policy is loaded.
+    pub fn check_examples(&self) -> Result<()> {
+        validate_not_match_examples(&self.rules, &self.not_matches)?;
+        validate_match_examples(&self.rules, &self.matches)
+    }
+
+    /// The call on a single line with comments removed.
+    pub fn summary(&self) -> String {
+        let mut summary = String::new();
+        for line in self.text.lines() {
+            let line = strip_comment(line).trim();
+            if line.is_empty() {
+                continue;
+            }
+            if !summary.is_empty()
+                && !summary.ends_with(['(', '['])
+                && !line.starts_with([')', ']'])
+            {
+                summary.push(' ');
+            }
+            summary.push_str(line);
+        }
+        summary
+    }
+}
+
+/// Drops a trailing `# comment`, ignoring `#` inside string literals.
+fn strip_comment(line: &str) -> &str {
+    let mut quote = None;
+    let mut escaped = false;
+    for (idx, ch) in line.char_indices() {
+        match quote {
+            Some(_) if escaped => escaped = false,
+            Some(_) if ch == '\\' => escaped = true,
+            Some(open) if ch == open => quote = None,
+            Some(_) => {}
+            None if ch == '"' || ch == '\'' => quote = Some(ch),
+            None if ch == '#' => return &line[..idx],
+            None => {}
+        }
+    }
+    line
+}
+
+/// Every definition with a rule matching `cmd`, in policy order, with the
+/// match it produced.
+pub fn explain<'a>(
+    definitions: &'a [RuleDefinition],
+    cmd: &[String],
+) -> Vec<(&'a RuleDefinition, RuleMatch)> {
+    definitions
+        .iter()
+        .filter_map(|definition| {
+            definition
+                .matches(cmd)
+                .map(|rule_match| (definition, rule_match))
+        })
+        .collect()
+}
+
+/// `definitions` grouped by file, one line per rule: `line  decision  call`.
+pub fn render_definitions(definitions: &[RuleDefinition]) -> String {
+    let mut out = String::new();
+    let mut current_source = None;
+    for definition in definitions {
+        if current_source != Some(definition.source.as_str()) {
+            if current_source.is_some() {
+                out.push('\n');
+            }
+            out.push_str(&definition.source);
+            out.push('\n');
+            current_source = Some(definition.source.as_str());
+        }
+        let line = definition
+            .lines
+            .as_ref()
+            .map(|lines| lines.start().to_string())
+            .unwrap_or_else(|| "?".to_string());
+        out.push_str(&format!(
+            "  {line:>4}  {:<9}  {}\n",
+            definition.decision.as_str(),
+            definition.summary()
+        ));
+    }
+    out
+}
+
+/// Which rules match each of `commands` and the decision they add up to.
+pub fn render_explanation(definitions: &[RuleDefinition], commands: &[Vec<String>]) -> String {
+    let mut out = String::new();
+    for cmd in commands {
+        out.push_str(&format!("$ {}\n", join_command(cmd)));
+        let matched = explain(definitions, cmd);
+        if matched.is_empty() {
+            out.push_str("  no rule matches; the default approval behavior applies\n");
+            continue;
+        }
+        for (definition, rule_match) in &matched {
+            out.push_str(&format!(
+                "  {:<9}  {}  {}\n",
+                rule_match.decision().as_str(),
+                definition.location(),
+                describe_match(rule_match)
+            ));
+        }
+        if let Some(decision) = matched
+            .iter()
+            .map(|(_, rule_match)| rule_match.decision())
+            .max()
+        {
+            out.push_str(&format!("  => {}\n", decision.as_str()));
+        }
+    }
+    out
+}
+
+/// Why a rule matched, e.g. "matched prefix `git push` and flag `-f`".
+pub fn describe_match(rule_match: &RuleMatch) -> String {
+    match rule_match {
+        RuleMatch::PrefixRuleMatch { matched_prefix, .. } => {
+            format!("matched prefix `{}`", join_command(matched_prefix))
+        }
+        RuleMatch::FlagRuleMatch {
+            matched_prefix,
+            matched_flag,
+            ..
+        } => format!(
+            "matched prefix `{}` and flag `{matched_flag}`",
+            join_command(matched_prefix)
+        ),
+        RuleMatch::PathRuleMatch {
+            matched_prefix,
+            root,
+            paths,
+            ..
+        } if paths.is_empty() => format!(
+            "matched prefix `{}` with no path arguments (root `{root}`)",
+            join_command(matched_prefix)
+        ),
+        RuleMatch::PathRuleMatch {
+            matched_prefix,
+            root,
+            paths,
+            ..
+        } => format!(
+            "matched prefix `{}`; `{}` within `{root}`",
+            join_command(matched_prefix),
+            paths.join("`, `")
+        ),
+        RuleMatch::HeuristicsRuleMatch { .. } => "no rule matched".to_string(),
+    }
+}
+
+fn join_command(cmd: &[String]) -> String {
+    shlex::try_join(cmd.iter().map(String::as_str)).unwrap_or_else(|_| cmd.join(" "))
+}
@@ -1,5 +1,6 @@
 pub mod amend;
 pub mod decision;
+pub mod definition;
 pub mod error;
 pub mod execpolicycheck;
 pub mod parser;
@@ -8,13 +9,16 @@ pub mod rule;
 
 pub use amend::AmendError;
 pub use amend::blocking_append_allow_prefix_rule;
+pub use amend::blocking_remove_rule;
 pub use decision::Decision;
+pub use definition::RuleDefinition;
 pub use error::Error;
 pub use error::Result;
 pub use execpolicycheck::ExecPolicyCheckCommand;
 pub use parser::PolicyParser;
 pub use policy::Evaluation;
 pub use policy::Policy;
+pub use rule::MatchContext;
 pub use rule::Rule;
 pub use rule::RuleMatch;
 pub use rule::RuleRef;
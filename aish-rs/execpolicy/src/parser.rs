@@ -7,20 +7,30 @@ use starlark::eval::Evaluator;
 use starlark::starlark_module;
 use starlark::syntax::AstModule;
 use starlark::syntax::Dialect;
+use starlark::values::Heap;
 use starlark::values::Value;
+use starlark::values::dict::AllocDict;
+use starlark::values::dict::DictRef;
 use starlark::values::list::ListRef;
 use starlark::values::list::UnpackList;
 use starlark::values::none::NoneType;
 use std::cell::RefCell;
 use std::cell::RefMut;
+use std::ops::RangeInclusive;
 use std::sync::Arc;
 
 use crate::decision::Decision;
+use crate::definition::RuleDefinition;
 use crate::error::Error;
 use crate::error::Result;
+use crate::rule::FlagRule;
+use crate::rule::GlobToken;
+use crate::rule::PathRoot;
+use crate::rule::PathRule;
 use crate::rule::PatternToken;
 use crate::rule::PrefixPattern;
 use crate::rule::PrefixRule;
+use crate::rule::RegexToken;
 use crate::rule::RuleRef;
 use crate::rule::validate_match_examples;
 use crate::rule::validate_not_match_examples;
@@ -55,6 +65,10 @@ impl PolicyParser {
         .map_err(Error::Starlark)?;
         let globals = GlobalsBuilder::standard().with(policy_builtins).build();
         let module = Module::new();
+        self.builder.borrow_mut().source = Some(PolicySource {
+            identifier: policy_identifier.to_string(),
+            contents: policy_file_contents.to_string(),
+        });
         {
             let mut eval = Evaluator::new(&module);
             eval.extra = Some(&self.builder);
@@ -66,17 +80,36 @@ impl PolicyParser {
     pub fn build(self) -> crate::policy::Policy {
         self.builder.into_inner().build()
     }
+
+    /// Like [`Self::build`], also returning every rule builtin call in the
+    /// order it was evaluated.
+    pub fn build_with_definitions(self) -> (crate::policy::Policy, Vec<RuleDefinition>) {
+        let mut builder = self.builder.into_inner();
+        let definitions = std::mem::take(&mut builder.definitions);
+        (builder.build(), definitions)
+    }
+}
+
+#[derive(Debug)]
+struct PolicySource {
+    identifier: String,
+    contents: String,
 }
 
 #[derive(Debug, ProvidesStaticType)]
 struct PolicyBuilder {
     rules_by_program: MultiMap<String, RuleRef>,
+    definitions: Vec<RuleDefinition>,
+    /// The policy currently being parsed.
+    source: Option<PolicySource>,
 }
 
 impl PolicyBuilder {
     fn new() -> Self {
         Self {
             rules_by_program: MultiMap::new(),
+            definitions: Vec::new(),
+            source: None,
         }
     }
 
@@ -85,6 +118,46 @@ impl PolicyBuilder {
             .insert(rule.program().to_string(), rule);
     }
 
+    fn add_definition(
+        &mut self,
+        lines: Option<RangeInclusive<usize>>,
+        decision: Decision,
+        rules: Vec<RuleRef>,
+        matches: Vec<Vec<String>>,
+        not_matches: Vec<Vec<String>>,
+    ) {
+        for rule in &rules {
+            self.add_rule(rule.clone());
+        }
+        let (source, text) = match &self.source {
+            Some(source) => (
+                source.identifier.clone(),
+                lines
+                    .as_ref()
+                    .map(|lines| {
+                        source
+                            .contents
+                            .lines()
+                            .skip(lines.start() - 1)
+                            .take(lines.end() + 1 - lines.start())
+                            .collect::<Vec<_>>()
+                            .join("\n")
+                    })
+                    .unwrap_or_default(),
+            ),
+            None => (String::new(), String::new()),
+        };
+        self.definitions.push(RuleDefinition {
+            source,
+            lines,
+            text,
+            decision,
+            rules,
+            matches,
+            not_matches,
+        });
+    }
+
     fn build(self) -> crate::policy::Policy {
         crate::policy::Policy::new(self.rules_by_program)
     }
@@ -103,9 +176,34 @@ fn parse_pattern<'v>(pattern: UnpackList<Value<'v>>) -> Result<Vec<PatternToken>
     }
 }
 
+/// Expands `pattern` into one prefix pattern per alternative of its first token, since rules are
+/// keyed by program. The first token must therefore be literal.
+fn parse_prefix_patterns<'v>(pattern: UnpackList<Value<'v>>) -> Result<Vec<PrefixPattern>> {
+    let pattern_tokens = parse_pattern(pattern)?;
+    let (first_token, remaining_tokens) = pattern_tokens
+        .split_first()
+        .ok_or_else(|| Error::InvalidPattern("pattern cannot be empty".to_string()))?;
+    let programs = first_token.alternatives().ok_or_else(|| {
+        Error::InvalidPattern(format!(
+            "first pattern element must name the program, not {first_token:?}"
+        ))
+    })?;
+
+    let rest: Arc<[PatternToken]> = remaining_tokens.to_vec().into();
+    Ok(programs
+        .iter()
+        .map(|program| PrefixPattern {
+            first: Arc::from(program.as_str()),
+            rest: rest.clone(),
+        })
+        .collect())
+}
+
 fn parse_pattern_token<'v>(value: Value<'v>) -> Result<PatternToken> {
     if let Some(s) = value.unpack_str() {
         Ok(PatternToken::Single(s.to_string()))
+    } else if let Some(dict) = DictRef::from_value(value) {
+        parse_matcher_token(&dict)
     } else if let Some(list) = ListRef::from_value(value) {
         let tokens: Vec<String> = list
             .content()
@@ -132,12 +230,59 @@ fn parse_pattern_token<'v>(value: Value<'v>) -> Result<PatternToken> {
         }
     } else {
         Err(Error::InvalidPattern(format!(
-            "pattern element must be a string or list of strings (got {})",
+            "pattern element must be a string, list of strings, regex() or glob() (got {})",
             value.get_type()
         )))
     }
 }
 
+/// Parses the `{"regex": ...}` / `{"glob": ...}` values built by the `regex()` and `glob()`
+/// builtins.
+fn parse_matcher_token(dict: &DictRef<'_>) -> Result<PatternToken> {
+    let entries: Vec<(Value<'_>, Value<'_>)> = dict.iter().collect();
+    let [(kind, source)] = entries.as_slice() else {
+        return Err(Error::InvalidPattern(
+            "pattern matcher must have exactly one of `regex` or `glob`".to_string(),
+        ));
+    };
+    let source = source.unpack_str().ok_or_else(|| {
+        Error::InvalidPattern(format!(
+            "pattern matcher source must be a string (got {})",
+            source.get_type()
+        ))
+    })?;
+    match kind.unpack_str() {
+        Some("regex") => RegexToken::new(source).map(PatternToken::Regex),
+        Some("glob") => Ok(PatternToken::Glob(GlobToken::new(source))),
+        _ => Err(Error::InvalidPattern(format!(
+            "unknown pattern matcher `{kind}`; expected `regex` or `glob`"
+        ))),
+    }
+}
+
+fn parse_flags<'v>(flags: UnpackList<&'v str>) -> Result<Arc<[String]>> {
+    let flags: Vec<String> = flags.items.into_iter().map(str::to_string).collect();
+    if flags.is_empty() {
+        return Err(Error::InvalidPattern("flags cannot be empty".to_string()));
+    }
+    if let Some(flag) = flags
+        .iter()
+        .find(|flag| !flag.starts_with('-') || *flag == "-")
+    {
+        return Err(Error::InvalidPattern(format!(
+            "flags must start with `-` (got {flag})"
+        )));
+    }
+    Ok(flags.into())
+}
+
+fn parse_decision(decision: Option<&str>) -> Result<Decision> {
+    match decision {
+        Some(raw) => Decision::parse(raw),
+        None => Ok(Decision::Allow),
+    }
+}
+
 fn parse_examples<'v>(examples: UnpackList<Value<'v>>) -> Result<Vec<Vec<String>>> {
     examples.items.into_iter().map(parse_example).collect()
 }
@@ -205,6 +350,34 @@ fn policy_builder<'v, 'a>(eval: &Evaluator<'v, 'a, '_>) -> RefMut<'a, PolicyBuil
         .borrow_mut()
 }
 
+/// 1-based lines of the builtin call being evaluated.
+fn call_lines(eval: &Evaluator<'_, '_, '_>) -> Option<RangeInclusive<usize>> {
+    let span = eval.call_stack_top_location()?.resolve_span();
+    Some(span.begin.line + 1..=span.end.line + 1)
+}
+
+/// Checks the `match` / `not_match` examples against `rules` and adds them to the policy.
+fn add_validated_rules<'v>(
+    eval: &mut Evaluator<'v, '_, '_>,
+    decision: Decision,
+    rules: Vec<RuleRef>,
+    r#match: Option<UnpackList<Value<'v>>>,
+    not_match: Option<UnpackList<Value<'v>>>,
+) -> Result<()> {
+    let matches: Vec<Vec<String>> = r#match.map(parse_examples).transpose()?.unwrap_or_default();
+    let not_matches: Vec<Vec<String>> = not_match
+        .map(parse_examples)
+        .transpose()?
+        .unwrap_or_default();
+
+    validate_not_match_examples(&rules, &not_matches)?;
+    validate_match_examples(&rules, &matches)?;
+
+    let lines = call_lines(eval);
+    policy_builder(eval).add_definition(lines, decision, rules, matches, not_matches);
+    Ok(())
+}
+
 #[starlark_module]
 fn policy_builtins(builder: &mut GlobalsBuilder) {
     fn prefix_rule<'v>(
@@ -214,46 +387,76 @@ fn policy_builtins(builder: &mut GlobalsBuilder) {
         not_match: Option<UnpackList<Value<'v>>>,
         eval: &mut Evaluator<'v, '_, '_>,
     ) -> anyhow::Result<NoneType> {
-        let decision = match decision {
-            Some(raw) => Decision::parse(raw)?,
-            None => Decision::Allow,
-        };
-
-        let pattern_tokens = parse_pattern(pattern)?;
-
-        let matches: Vec<Vec<String>> =
-            r#match.map(parse_examples).transpose()?.unwrap_or_default();
-        let not_matches: Vec<Vec<String>> = not_match
-            .map(parse_examples)
-            .transpose()?
-            .unwrap_or_default();
+        let decision = parse_decision(decision)?;
+        let rules: Vec<RuleRef> = parse_prefix_patterns(pattern)?
+            .into_iter()
+            .map(|pattern| Arc::new(PrefixRule { pattern, decision }) as RuleRef)
+            .collect();
 
-        let mut builder = policy_builder(eval);
+        add_validated_rules(eval, decision, rules, r#match, not_match)?;
+        Ok(NoneType)
+    }
 
-        let (first_token, remaining_tokens) = pattern_tokens
-            .split_first()
-            .ok_or_else(|| Error::InvalidPattern("pattern cannot be empty".to_string()))?;
+    /// Matches commands starting with `pattern` that pass any of `flags` after it.
+    fn flag_rule<'v>(
+        pattern: UnpackList<Value<'v>>,
+        flags: UnpackList<&'v str>,
+        decision: Option<&'v str>,
+        r#match: Option<UnpackList<Value<'v>>>,
+        not_match: Option<UnpackList<Value<'v>>>,
+        eval: &mut Evaluator<'v, '_, '_>,
+    ) -> anyhow::Result<NoneType> {
+        let decision = parse_decision(decision)?;
+        let flags = parse_flags(flags)?;
+        let rules: Vec<RuleRef> = parse_prefix_patterns(pattern)?
+            .into_iter()
+            .map(|pattern| {
+                Arc::new(FlagRule {
+                    pattern,
+                    flags: flags.clone(),
+                    decision,
+                }) as RuleRef
+            })
+            .collect();
 
-        let rest: Arc<[PatternToken]> = remaining_tokens.to_vec().into();
+        add_validated_rules(eval, decision, rules, r#match, not_match)?;
+        Ok(NoneType)
+    }
 
-        let rules: Vec<RuleRef> = first_token
-            .alternatives()
-            .iter()
-            .map(|head| {
-                Arc::new(PrefixRule {
-                    pattern: PrefixPattern {
-                        first: Arc::from(head.as_str()),
-                        rest: rest.clone(),
-                    },
+    /// Matches commands starting with `pattern` whose path arguments all lie within `root`.
+    fn path_rule<'v>(
+        pattern: UnpackList<Value<'v>>,
+        root: &'v str,
+        decision: Option<&'v str>,
+        r#match: Option<UnpackList<Value<'v>>>,
+        not_match: Option<UnpackList<Value<'v>>>,
+        eval: &mut Evaluator<'v, '_, '_>,
+    ) -> anyhow::Result<NoneType> {
+        let decision = parse_decision(decision)?;
+        let root = PathRoot::parse(root)?;
+        let rules: Vec<RuleRef> = parse_prefix_patterns(pattern)?
+            .into_iter()
+            .map(|pattern| {
+                Arc::new(PathRule {
+                    pattern,
+                    root: root.clone(),
                     decision,
                 }) as RuleRef
             })
             .collect();
 
-        validate_not_match_examples(&rules, &not_matches)?;
-        validate_match_examples(&rules, &matches)?;
-
-        rules.into_iter().for_each(|rule| builder.add_rule(rule));
+        add_validated_rules(eval, decision, rules, r#match, not_match)?;
         Ok(NoneType)
     }
+
+    /// Pattern element matching tokens that fully match the regular expression `source`.
+    fn regex<'v>(source: &'v str, heap: &'v Heap) -> anyhow::Result<Value<'v>> {
+        RegexToken::new(source)?;
+        Ok(heap.alloc(AllocDict([("regex", source)])))
+    }
+
+    /// Pattern element matching tokens that fully match the glob `source` (`*` and `?`).
+    fn glob<'v>(source: &'v str, heap: &'v Heap) -> anyhow::Result<Value<'v>> {
+        Ok(heap.alloc(AllocDict([("glob", source)])))
+    }
 }
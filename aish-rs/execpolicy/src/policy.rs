@@ -1,6 +1,7 @@
 use crate::decision::Decision;
 use crate::error::Error;
 use crate::error::Result;
+use crate::rule::MatchContext;
 use crate::rule::PatternToken;
 use crate::rule::PrefixPattern;
 use crate::rule::PrefixRule;
@@ -31,7 +32,8 @@ impl Policy {
         &self.rules_by_program
     }
 
-    pub fn add_prefix_rule(&mut self, prefix: &[String], decision: Decision) -> Result<()> {
+    /// Adds a rule matching commands that start with `prefix` and returns it.
+    pub fn add_prefix_rule(&mut self, prefix: &[String], decision: Decision) -> Result<RuleRef> {
         let (first_token, rest) = prefix
             .split_first()
             .ok_or_else(|| Error::InvalidPattern("prefix cannot be empty".to_string()))?;
@@ -48,8 +50,9 @@ impl Policy {
             decision,
         });
 
-        self.rules_by_program.insert(first_token.clone(), rule);
-        Ok(())
+        self.rules_by_program
+            .insert(first_token.clone(), Arc::clone(&rule));
+        Ok(rule)
     }
 
     pub fn check<F>(&self, cmd: &[String], heuristics_fallback: &F) -> Evaluation
@@ -80,16 +83,56 @@ impl Policy {
         Evaluation::from_matches(matched_rules)
     }
 
+    /// Like [`Policy::check_multiple`], for commands that run as described by `ctx`. Path rules
+    /// resolve relative arguments against the directory the commands run in.
+    pub fn check_multiple_in<Commands, F>(
+        &self,
+        commands: Commands,
+        ctx: &MatchContext<'_>,
+        heuristics_fallback: &F,
+    ) -> Evaluation
+    where
+        Commands: IntoIterator,
+        Commands::Item: AsRef<[String]>,
+        F: Fn(&[String]) -> Decision,
+    {
+        let matched_rules: Vec<RuleMatch> = commands
+            .into_iter()
+            .flat_map(|command| {
+                self.matches_for_command_in(command.as_ref(), Some(ctx), Some(heuristics_fallback))
+            })
+            .collect();
+
+        Evaluation::from_matches(matched_rules)
+    }
+
     pub fn matches_for_command(
         &self,
         cmd: &[String],
         heuristics_fallback: HeuristicsFallback<'_>,
+    ) -> Vec<RuleMatch> {
+        self.matches_for_command_in(cmd, None, heuristics_fallback)
+    }
+
+    fn matches_for_command_in(
+        &self,
+        cmd: &[String],
+        ctx: Option<&MatchContext<'_>>,
+        heuristics_fallback: HeuristicsFallback<'_>,
     ) -> Vec<RuleMatch> {
         let mut matched_rules: Vec<RuleMatch> = match cmd.first() {
             Some(first) => self
                 .rules_by_program
                 .get_vec(first)
-                .map(|rules| rules.iter().filter_map(|rule| rule.matches(cmd)).collect())
+                .map(|rules| {
+                    rules
+                        .iter()
+                        .filter_map(|rule| match ctx {
+                            Some(ctx) => rule.matches_in(cmd, ctx),
+                            None => rule.matches(cmd),
+                        })
+                        .collect()
+                })
                 .unwrap_or_default(),
             None => Vec::new(),
         };
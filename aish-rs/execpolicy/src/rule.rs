@@ -1,18 +1,27 @@
 use crate::decision::Decision;
 use crate::error::Error;
 use crate::error::Result;
+use regex_lite::Regex;
 use serde::Deserialize;
 use serde::Serialize;
 use shlex::try_join;
 use std::any::Any;
+use std::fmt;
 use std::fmt::Debug;
+use std::path::Component;
+use std::path::Path;
+use std::path::PathBuf;
 use std::sync::Arc;
+use wildmatch::WildMatch;
 
-/// Matches a single command token, either a fixed string or one of several allowed alternatives.
+/// Matches a single command token: a fixed string, one of several allowed alternatives, a
+/// regular expression, or a glob.
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub enum PatternToken {
     Single(String),
     Alts(Vec<String>),
+    Regex(RegexToken),
+    Glob(GlobToken),
 }
 
 impl PatternToken {
@@ -20,17 +29,91 @@ impl PatternToken {
         match self {
             Self::Single(expected) => expected == token,
             Self::Alts(alternatives) => alternatives.iter().any(|alt| alt == token),
+            Self::Regex(regex) => regex.regex.is_match(token),
+            Self::Glob(glob) => glob.glob.matches(token),
         }
     }
 
-    pub fn alternatives(&self) -> &[String] {
+    /// The literal tokens this matches, or `None` for regex and glob tokens.
+    pub fn alternatives(&self) -> Option<&[String]> {
         match self {
-            Self::Single(expected) => std::slice::from_ref(expected),
-            Self::Alts(alternatives) => alternatives,
+            Self::Single(expected) => Some(std::slice::from_ref(expected)),
+            Self::Alts(alternatives) => Some(alternatives),
+            Self::Regex(_) | Self::Glob(_) => None,
         }
     }
 }
 
+/// A regular expression that must match a whole token.
+#[derive(Clone)]
+pub struct RegexToken {
+    source: String,
+    regex: Regex,
+}
+
+impl RegexToken {
+    pub fn new(source: &str) -> Result<Self> {
+        let regex = Regex::new(&format!("^(?:{source})$"))
+            .map_err(|err| Error::InvalidPattern(format!("invalid regex `{source}`: {err}")))?;
+        Ok(Self {
+            source: source.to_string(),
+            regex,
+        })
+    }
+
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+}
+
+impl PartialEq for RegexToken {
+    fn eq(&self, other: &Self) -> bool {
+        self.source == other.source
+    }
+}
+
+impl Eq for RegexToken {}
+
+impl Debug for RegexToken {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "regex({:?})", self.source)
+    }
+}
+
+/// A shell-style glob (`*`, `?`) that must match a whole token.
+#[derive(Clone)]
+pub struct GlobToken {
+    source: String,
+    glob: WildMatch,
+}
+
+impl GlobToken {
+    pub fn new(source: &str) -> Self {
+        Self {
+            source: source.to_string(),
+            glob: WildMatch::new(source),
+        }
+    }
+
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+}
+
+impl PartialEq for GlobToken {
+    fn eq(&self, other: &Self) -> bool {
+        self.source == other.source
+    }
+}
+
+impl Eq for GlobToken {}
+
+impl Debug for GlobToken {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "glob({:?})", self.source)
+    }
+}
+
 /// Prefix matcher for commands with support for alternative match tokens.
 /// First token is fixed since we key by the first token in policy.
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -64,6 +147,20 @@ pub enum RuleMatch {
         matched_prefix: Vec<String>,
         decision: Decision,
     },
+    FlagRuleMatch {
+        #[serde(rename = "matchedPrefix")]
+        matched_prefix: Vec<String>,
+        #[serde(rename = "matchedFlag")]
+        matched_flag: String,
+        decision: Decision,
+    },
+    PathRuleMatch {
+        #[serde(rename = "matchedPrefix")]
+        matched_prefix: Vec<String>,
+        root: String,
+        paths: Vec<String>,
+        decision: Decision,
+    },
     HeuristicsRuleMatch {
         command: Vec<String>,
         decision: Decision,
@@ -74,6 +171,8 @@ impl RuleMatch {
     pub fn decision(&self) -> Decision {
         match self {
             Self::PrefixRuleMatch { decision, .. } => *decision,
+            Self::FlagRuleMatch { decision, .. } => *decision,
+            Self::PathRuleMatch { decision, .. } => *decision,
             Self::HeuristicsRuleMatch { decision, .. } => *decision,
         }
     }
@@ -85,10 +184,28 @@ pub struct PrefixRule {
     pub decision: Decision,
 }
 
+/// Where a command runs, for rules whose match depends on it.
+#[derive(Clone, Copy, Debug)]
+pub struct MatchContext<'a> {
+    /// Directory the command runs in.
+    pub cwd: &'a Path,
+    /// Directory that `root = "."` path rules confine arguments to: the session's working
+    /// directory.
+    pub workspace: &'a Path,
+    /// Whether an earlier part of the script may have changed directory (it has several commands
+    /// or runs `cd`/`pushd`), so `cwd` cannot be trusted.
+    pub cwd_may_change: bool,
+}
+
 pub trait Rule: Any + Debug + Send + Sync {
     fn program(&self) -> &str;
 
     fn matches(&self, cmd: &[String]) -> Option<RuleMatch>;
+
+    /// Like [`Rule::matches`], for a command known to run as described by `ctx`.
+    fn matches_in(&self, cmd: &[String], _ctx: &MatchContext<'_>) -> Option<RuleMatch> {
+        self.matches(cmd)
+    }
 }
 
 pub type RuleRef = Arc<dyn Rule>;
@@ -108,6 +225,219 @@ impl Rule for PrefixRule {
     }
 }
 
+/// Matches commands starting with `pattern` in which any of `flags` appears after the prefix,
+/// e.g. `git push` with `--force`. Combined with a laxer prefix rule this expresses "allow
+/// unless": the strictest matching decision wins.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct FlagRule {
+    pub pattern: PrefixPattern,
+    pub flags: Arc<[String]>,
+    pub decision: Decision,
+}
+
+impl FlagRule {
+    fn find_flag(&self, args: &[String]) -> Option<&str> {
+        args.iter()
+            .take_while(|arg| arg.as_str() != "--")
+            .find_map(|arg| {
+                self.flags
+                    .iter()
+                    .find(|flag| flag_matches(flag, arg))
+                    .map(String::as_str)
+            })
+    }
+}
+
+/// Whether the argument `arg` sets `flag`. Long flags also match their `--flag=value` form and
+/// single-letter short flags also match when grouped, as in `-fv`.
+fn flag_matches(flag: &str, arg: &str) -> bool {
+    if flag == arg {
+        return true;
+    }
+    if flag.starts_with("--") {
+        return arg
+            .strip_prefix(flag)
+            .is_some_and(|value| value.starts_with('='));
+    }
+    match (flag.strip_prefix('-'), arg.strip_prefix('-')) {
+        (Some(letter), Some(group)) if letter.chars().count() == 1 && !group.starts_with('-') => {
+            group.contains(letter)
+        }
+        _ => false,
+    }
+}
+
+impl Rule for FlagRule {
+    fn program(&self) -> &str {
+        self.pattern.first.as_ref()
+    }
+
+    fn matches(&self, cmd: &[String]) -> Option<RuleMatch> {
+        let matched_prefix = self.pattern.matches_prefix(cmd)?;
+        let matched_flag = self.find_flag(&cmd[matched_prefix.len()..])?.to_string();
+        Some(RuleMatch::FlagRuleMatch {
+            matched_prefix,
+            matched_flag,
+            decision: self.decision,
+        })
+    }
+}
+
+/// Directory that the path arguments of a [`PathRule`] must stay within.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum PathRoot {
+    /// The command's working directory: arguments must be relative paths that do not climb out
+    /// of it with `..`.
+    Cwd,
+    /// An absolute directory: arguments must be absolute paths inside it.
+    Absolute(PathBuf),
+}
+
+impl PathRoot {
+    pub fn parse(raw: &str) -> Result<Self> {
+        let path = Path::new(raw);
+        if raw == "." || raw == "./" {
+            Ok(Self::Cwd)
+        } else if path.is_absolute() {
+            normalize_path(path)
+                .map(Self::Absolute)
+                .ok_or_else(|| Error::InvalidPattern(format!("invalid path root: {raw}")))
+        } else {
+            Err(Error::InvalidPattern(format!(
+                "path root must be \".\" or an absolute path (got {raw})"
+            )))
+        }
+    }
+
+    /// Like [`PathRoot::contains`], for a command running as described by `ctx`: relative
+    /// arguments are resolved against `ctx.cwd`, and a `.` root means `ctx.workspace`.
+    pub fn contains_in(&self, arg: &str, ctx: &MatchContext<'_>) -> bool {
+        match self {
+            Self::Cwd => {
+                !arg.starts_with('~')
+                    && normalize_path(&ctx.cwd.join(arg)).is_some_and(|path| {
+                        normalize_path(ctx.workspace).is_some_and(|root| path.starts_with(root))
+                    })
+            }
+            Self::Absolute(_) => self.contains(arg),
+        }
+    }
+
+    /// Whether `arg` names the root or something inside it. Paths are compared lexically, so
+    /// symlinks are not followed.
+    pub fn contains(&self, arg: &str) -> bool {
+        let path = Path::new(arg);
+        match self {
+            Self::Cwd => {
+                !path.is_absolute() && !arg.starts_with('~') && normalize_path(path).is_some()
+            }
+            Self::Absolute(root) => {
+                path.is_absolute()
+                    && normalize_path(path).is_some_and(|path| path.starts_with(root))
+            }
+        }
+    }
+}
+
+impl fmt::Display for PathRoot {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Cwd => f.write_str("."),
+            Self::Absolute(root) => write!(f, "{}", root.display()),
+        }
+    }
+}
+
+/// Resolves `.` and `..` components without touching the filesystem. Returns `None` when `..`
+/// would climb above the start of `path`.
+fn normalize_path(path: &Path) -> Option<PathBuf> {
+    let mut normalized = PathBuf::new();
+    let mut depth = 0usize;
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                depth = depth.checked_sub(1)?;
+                normalized.pop();
+            }
+            Component::Normal(part) => {
+                depth += 1;
+                normalized.push(part);
+            }
+            Component::RootDir | Component::Prefix(_) => normalized.push(component),
+        }
+    }
+    Some(normalized)
+}
+
+/// Matches commands starting with `pattern` whose path arguments (every argument after the
+/// prefix that is not a flag, plus the values of `--flag=value` arguments) all lie within `root`,
+/// e.g. `rm` limited to the working directory. Short flags with a joined value such as `-t/etc`
+/// cannot be split reliably, so the rule does not match them.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PathRule {
+    pub pattern: PrefixPattern,
+    pub root: PathRoot,
+    pub decision: Decision,
+}
+
+impl Rule for PathRule {
+    fn program(&self) -> &str {
+        self.pattern.first.as_ref()
+    }
+
+    fn matches(&self, cmd: &[String]) -> Option<RuleMatch> {
+        self.matches_where(cmd, |path| self.root.contains(path))
+    }
+
+    fn matches_in(&self, cmd: &[String], ctx: &MatchContext<'_>) -> Option<RuleMatch> {
+        if self.root == PathRoot::Cwd && ctx.cwd_may_change {
+            return None;
+        }
+        self.matches_where(cmd, |path| self.root.contains_in(path, ctx))
+    }
+}
+
+impl PathRule {
+    fn matches_where(
+        &self,
+        cmd: &[String],
+        within_root: impl Fn(&str) -> bool,
+    ) -> Option<RuleMatch> {
+        let matched_prefix = self.pattern.matches_prefix(cmd)?;
+        let paths = path_arguments(&cmd[matched_prefix.len()..])?;
+        if !paths.iter().all(|path| within_root(path)) {
+            return None;
+        }
+        Some(RuleMatch::PathRuleMatch {
+            matched_prefix,
+            root: self.root.to_string(),
+            paths,
+            decision: self.decision,
+        })
+    }
+}
+
+/// Arguments that are not flags and the values of `--flag=value` arguments; everything after
+/// `--` counts as a path. Returns `None` for a short flag group that carries a joined value
+/// (anything but letters and digits after the `-`), since its value could be any suffix.
+fn path_arguments(args: &[String]) -> Option<Vec<String>> {
+    let mut paths = Vec::new();
+    let mut options_ended = false;
+    for arg in args {
+        if options_ended || !arg.starts_with('-') || arg == "-" {
+            paths.push(arg.clone());
+        } else if arg == "--" {
+            options_ended = true;
+        } else if let Some(long) = arg.strip_prefix("--") {
+            paths.extend(long.split_once('=').map(|(_, value)| value.to_string()));
+        } else if !arg[1..].chars().all(|c| c.is_ascii_alphanumeric()) {
+            return None;
+        }
+    }
+    Some(paths)
+}
+
 /// Count how many rules match each provided example and error if any example is unmatched.
 pub(crate) fn validate_match_examples(rules: &[RuleRef], matches: &[Vec<String>]) -> Result<()> {
     let mut unmatched_examples = Vec::new();
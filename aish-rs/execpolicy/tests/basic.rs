@@ -8,6 +8,7 @@ use aish_execpolicy::Policy;
 use aish_execpolicy::PolicyParser;
 use aish_execpolicy::RuleMatch;
 use aish_execpolicy::RuleRef;
+use aish_execpolicy::definition::explain;
 use aish_execpolicy::rule::PatternToken;
 use aish_execpolicy::rule::PrefixPattern;
 use aish_execpolicy::rule::PrefixRule;
@@ -452,3 +453,224 @@ fn heuristics_match_is_returned_when_no_policy_matches() {
         evaluation
     );
 }
+
+#[test]
+fn flag_rule_matches_flags_anywhere_after_prefix() -> Result<()> {
+    let policy_src = r#"
+prefix_rule(pattern = ["git", "push"])
+flag_rule(
+    pattern = ["git", "push"],
+    flags = ["--force", "-f"],
+    decision = "prompt",
+    match = ["git push origin main --force", "git push -fu origin main"],
+    not_match = ["git push --force-with-lease", "git push origin -- -f"],
+)
+    "#;
+    let mut parser = PolicyParser::new();
+    parser.parse("test.rules", policy_src)?;
+    let policy = parser.build();
+
+    let forced = policy.check(
+        &tokens(&["git", "push", "origin", "--force=yes"]),
+        &allow_all,
+    );
+    assert_eq!(
+        Evaluation {
+            decision: Decision::Prompt,
+            matched_rules: vec![
+                RuleMatch::PrefixRuleMatch {
+                    matched_prefix: tokens(&["git", "push"]),
+                    decision: Decision::Allow,
+                },
+                RuleMatch::FlagRuleMatch {
+                    matched_prefix: tokens(&["git", "push"]),
+                    matched_flag: "--force".to_string(),
+                    decision: Decision::Prompt,
+                },
+            ],
+        },
+        forced
+    );
+
+    let plain = policy.check(&tokens(&["git", "push", "origin", "main"]), &prompt_all);
+    assert_eq!(Decision::Allow, plain.decision);
+    Ok(())
+}
+
+#[test]
+fn regex_and_glob_tokens_match_whole_tokens() -> Result<()> {
+    let policy_src = r#"
+prefix_rule(
+    pattern = ["git", "checkout", regex("release/v[0-9]+")],
+    match = ["git checkout release/v12"],
+    not_match = ["git checkout release/v12-rc", "git checkout xrelease/v1"],
+)
+prefix_rule(
+    pattern = ["cat", glob("*.log")],
+    decision = "forbidden",
+    match = ["cat server.log"],
+    not_match = ["cat server.log.txt"],
+)
+    "#;
+    let mut parser = PolicyParser::new();
+    parser.parse("test.rules", policy_src)?;
+    let policy = parser.build();
+
+    let checkout = policy.check(
+        &tokens(&["git", "checkout", "release/v3", "--", "file"]),
+        &prompt_all,
+    );
+    assert_eq!(
+        Evaluation {
+            decision: Decision::Allow,
+            matched_rules: vec![RuleMatch::PrefixRuleMatch {
+                matched_prefix: tokens(&["git", "checkout", "release/v3"]),
+                decision: Decision::Allow,
+            }],
+        },
+        checkout
+    );
+    assert_eq!(
+        Decision::Forbidden,
+        policy
+            .check(&tokens(&["cat", "app.log"]), &allow_all)
+            .decision
+    );
+    Ok(())
+}
+
+#[test]
+fn path_rule_limits_arguments_to_root() -> Result<()> {
+    let policy_src = r#"
+path_rule(
+    pattern = ["rm"],
+    root = ".",
+    match = ["rm -rf build ./target/debug", "rm -- -weird-name"],
+    not_match = ["rm -rf /", "rm ../sibling", "rm a/../../b", "rm ~/notes"],
+)
+path_rule(
+    pattern = ["cp"],
+    root = ".",
+    match = ["cp --target-directory=out a b", "cp -rv a b"],
+    not_match = [
+        "cp --target-directory=/etc x",
+        "cp --target-directory=../up x",
+        "cp -t/etc x",
+        "cp -vt/etc x",
+        "cp -t../up x",
+    ],
+)
+path_rule(
+    pattern = ["touch"],
+    root = "/tmp/scratch",
+    match = ["touch /tmp/scratch/a /tmp/scratch/b/../c"],
+    not_match = ["touch /tmp/scratch/../etc/passwd", "touch relative"],
+)
+    "#;
+    let mut parser = PolicyParser::new();
+    parser.parse("test.rules", policy_src)?;
+    let policy = parser.build();
+
+    let evaluation = policy.check(&tokens(&["rm", "-r", "src/old"]), &prompt_all);
+    assert_eq!(
+        Evaluation {
+            decision: Decision::Allow,
+            matched_rules: vec![RuleMatch::PathRuleMatch {
+                matched_prefix: tokens(&["rm"]),
+                root: ".".to_string(),
+                paths: tokens(&["src/old"]),
+                decision: Decision::Allow,
+            }],
+        },
+        evaluation
+    );
+    assert_eq!(
+        Decision::Prompt,
+        policy
+            .check(&tokens(&["rm", "-r", "src", "/etc"]), &prompt_all)
+            .decision
+    );
+    assert_eq!(
+        Decision::Prompt,
+        policy
+            .check(
+                &tokens(&["cp", "--target-directory=/etc", "x"]),
+                &prompt_all
+            )
+            .decision
+    );
+    assert_eq!(
+        Decision::Prompt,
+        policy
+            .check(&tokens(&["cp", "-t/etc", "x"]), &prompt_all)
+            .decision
+    );
+    Ok(())
+}
+
+#[test]
+fn new_rule_kinds_reject_invalid_arguments() {
+    for policy_src in [
+        r#"prefix_rule(pattern = [regex("g.t"), "status"])"#,
+        r#"prefix_rule(pattern = ["git", regex("(")])"#,
+        r#"flag_rule(pattern = ["git", "push"], flags = [])"#,
+        r#"flag_rule(pattern = ["git", "push"], flags = ["force"])"#,
+        r#"path_rule(pattern = ["rm"], root = "relative/dir")"#,
+        r#"flag_rule(pattern = ["git", "push"], flags = ["-f"], not_match = ["git push -f"])"#,
+    ] {
+        let mut parser = PolicyParser::new();
+        assert!(
+            parser.parse("test.rules", policy_src).is_err(),
+            "expected an error for {policy_src}"
+        );
+    }
+}
+
+#[test]
+fn definitions_record_source_lines_and_explain_matches() -> Result<()> {
+    let policy_src = r#"
+prefix_rule(pattern = ["git", "push"])  # pushing is fine
+
+flag_rule(
+    pattern = ["git", "push"],
+    flags = ["-f"],
+    decision = "prompt",
+)
+"#;
+    let mut parser = PolicyParser::new();
+    parser.parse("test.rules", policy_src)?;
+    let (_, definitions) = parser.build_with_definitions();
+
+    assert_eq!(
+        definitions
+            .iter()
+            .map(|definition| (definition.location(), definition.summary()))
+            .collect::<Vec<_>>(),
+        vec![
+            (
+                "test.rules:2".to_string(),
+                r#"prefix_rule(pattern = ["git", "push"])"#.to_string()
+            ),
+            (
+                "test.rules:4".to_string(),
+                r#"flag_rule(pattern = ["git", "push"], flags = ["-f"], decision = "prompt",)"#
+                    .to_string()
+            ),
+        ]
+    );
+    assert_eq!(definitions[1].lines, Some(4..=8));
+
+    let explained = explain(&definitions, &tokens(&["git", "push", "-f"]));
+    assert_eq!(
+        explained
+            .iter()
+            .map(|(definition, rule_match)| (definition.location(), rule_match.decision()))
+            .collect::<Vec<_>>(),
+        vec![
+            ("test.rules:2".to_string(), Decision::Allow),
+            ("test.rules:4".to_string(), Decision::Prompt),
+        ]
+    );
+    assert!(explain(&definitions, &tokens(&["git", "status"])).is_empty());
+    Ok(())
+}
@@ -1,6 +1,8 @@
 use std::collections::BTreeMap;
 use std::path::Path;
+use std::path::PathBuf;
 
+use aish_core::deny_read::resolve_deny_read_paths;
 use aish_core::error::AishErr;
 use aish_core::error::Result;
 use aish_core::error::SandboxErr;
@@ -10,6 +12,7 @@ use aish_utils_absolute_path::AbsolutePathBuf;
 use landlock::ABI;
 use landlock::Access;
 use landlock::AccessFs;
+use landlock::BitFlags;
 use landlock::CompatLevel;
 use landlock::Compatible;
 use landlock::Ruleset;
@@ -43,7 +46,14 @@ pub(crate) fn apply_sandbox_policy_to_current_thread(
     cwd: &Path,
 ) -> Result<()> {
     if !sandbox_policy.has_full_network_access() {
-        install_network_seccomp_filter_on_current_thread()?;
+        if sandbox_policy.network_allowlist().is_empty() {
+            install_network_seccomp_filter_on_current_thread()?;
+        } else {
+            // The network namespace set up by `network_proxy` already confines
+            // sockets to loopback; only block ways of reaching the proxy's
+            // processes outside it.
+            install_seccomp_deny_rules_on_current_thread(process_tampering_rules())?;
+        }
     }
 
     if !sandbox_policy.has_full_disk_write_access() {
@@ -52,7 +62,8 @@ pub(crate) fn apply_sandbox_policy_to_current_thread(
             .into_iter()
             .map(|writable_root| writable_root.root)
             .collect();
-        install_filesystem_landlock_rules_on_current_thread(writable_roots)?;
+        let deny_read = resolve_deny_read_paths(sandbox_policy.deny_read(), cwd);
+        install_filesystem_landlock_rules_on_current_thread(writable_roots, &deny_read)?;
     }
 
     // TODO(ragona): Add appropriate restrictions if
@@ -63,12 +74,15 @@ pub(crate) fn apply_sandbox_policy_to_current_thread(
 
 /// Installs Landlock file-system rules on the current thread allowing read
 /// access to the entire file-system while restricting write access to
-/// `/dev/null` and the provided list of `writable_roots`.
+/// `/dev/null` and the provided list of `writable_roots`. Files under
+/// `deny_read` keep every right except `ReadFile`.
 ///
 /// # Errors
-/// Returns [`CodexErr::Sandbox`] variants when the ruleset fails to apply.
+/// Returns [`CodexErr::Sandbox`] variants when the ruleset fails to apply,
+/// or when a `deny_read` path overlaps a writable root.
 fn install_filesystem_landlock_rules_on_current_thread(
     writable_roots: Vec<AbsolutePathBuf>,
+    deny_read: &[AbsolutePathBuf],
 ) -> Result<()> {
     if !is_landlock_supported() {
         return Err(AishErr::Sandbox(SandboxErr::Unavailable));
@@ -77,16 +91,32 @@ fn install_filesystem_landlock_rules_on_current_thread(
     let access_rw = AccessFs::from_all(abi);
     let access_ro = AccessFs::from_read(abi);
 
+    // Landlock rules only ever add access, so a denied path is carved out of
+    // a grant by granting the grant's siblings individually instead.
+    let denied = denied_paths_with_canonical_forms(deny_read);
+    if let Some((path, root)) = denied_path_in_writable_root(&denied, &writable_roots) {
+        return Err(AishErr::Sandbox(SandboxErr::DenyReadInWritableRoot {
+            path,
+            root,
+        }));
+    }
+    let mut grants = Vec::new();
+    collect_grants_excluding_denied(Path::new("/"), access_ro, &denied, &mut grants);
+    for root in &writable_roots {
+        collect_grants_excluding_denied(root.as_path(), access_rw, &denied, &mut grants);
+    }
+
     let mut ruleset = Ruleset::default()
         .set_compatibility(CompatLevel::BestEffort)
         .handle_access(access_rw)?
         .create()?
-        .add_rules(landlock::path_beneath_rules(&["/"], access_ro))?
         .add_rules(landlock::path_beneath_rules(&["/dev/null"], access_rw))?
         .set_no_new_privs(true);
 
-    if !writable_roots.is_empty() {
-        ruleset = ruleset.add_rules(landlock::path_beneath_rules(&writable_roots, access_rw))?;
+    for (path, access) in grants {
+        // A path that vanished since it was listed simply gets no access.
+        ruleset = ruleset
+            .add_rules(landlock::path_beneath_rules(&[path], access).filter(|rule| rule.is_ok()))?;
     }
 
     let status = ruleset.restrict_self()?;
@@ -98,6 +128,93 @@ fn install_filesystem_landlock_rules_on_current_thread(
     Ok(())
 }
 
+/// Denied paths plus their canonical forms, so a symlinked secret (for
+/// example a dotfiles checkout) is protected at its real location too.
+fn denied_paths_with_canonical_forms(deny_read: &[AbsolutePathBuf]) -> Vec<PathBuf> {
+    let mut denied: Vec<PathBuf> = Vec::new();
+    for path in deny_read {
+        let path = path.to_path_buf();
+        if let Ok(canonical) = path.canonicalize()
+            && canonical != path
+        {
+            denied.push(canonical);
+        }
+        denied.push(path);
+    }
+    denied
+}
+
+/// The first denied path that overlaps a writable root, with that root.
+/// Directories above a denied path cannot keep `ReadFile` (it would reach the
+/// denied path too), so a file the command creates in one of them later
+/// could not be read back; inside a writable root that breaks ordinary
+/// builds. A denied path at or above a writable root would be read through
+/// the root's own grant. Either way the entry is refused.
+fn denied_path_in_writable_root(
+    denied: &[PathBuf],
+    writable_roots: &[AbsolutePathBuf],
+) -> Option<(PathBuf, PathBuf)> {
+    denied.iter().find_map(|path| {
+        writable_roots
+            .iter()
+            .find(|root| path.starts_with(root.as_path()) || root.as_path().starts_with(path))
+            .map(|root| (path.clone(), root.to_path_buf()))
+    })
+}
+
+/// Pushes the rules that grant `access` beneath `root` except `ReadFile`
+/// beneath any `denied` path. Directories on the way to a denied path keep
+/// every other right, including `ReadDir`, but lose `ReadFile`: only entries
+/// that exist now get their own grants, so a file created in one of them
+/// later cannot be read. Callers keep writable roots off that path (see
+/// [`denied_path_in_writable_root`]).
+fn collect_grants_excluding_denied(
+    root: &Path,
+    access: BitFlags<AccessFs>,
+    denied: &[PathBuf],
+    grants: &mut Vec<(PathBuf, BitFlags<AccessFs>)>,
+) {
+    let related = |path: &Path| {
+        denied
+            .iter()
+            .any(|denied| denied.starts_with(path) || path.starts_with(denied))
+    };
+    if !related(root) {
+        grants.push((root.to_path_buf(), access));
+        return;
+    }
+
+    let mut without_read = access;
+    without_read.remove(AccessFs::ReadFile);
+    grants.push((root.to_path_buf(), without_read));
+    if denied.iter().any(|denied| root.starts_with(denied)) {
+        return;
+    }
+
+    let Ok(entries) = std::fs::read_dir(root) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let child = entry.path();
+        let is_symlink = entry
+            .file_type()
+            .is_ok_and(|file_type| file_type.is_symlink());
+        if is_symlink {
+            // A rule follows the link, so never grant one that leads into
+            // (or above) a denied path; the target is reached on its own.
+            let leads_to_denied = child
+                .canonicalize()
+                .ok()
+                .is_none_or(|target| related(&target));
+            if !leads_to_denied {
+                grants.push((child, access));
+            }
+            continue;
+        }
+        collect_grants_excluding_denied(&child, access, denied, grants);
+    }
+}
+
 /// Installs a seccomp filter that blocks outbound network access except for
 /// AF_UNIX domain sockets.
 fn install_network_seccomp_filter_on_current_thread() -> std::result::Result<(), SandboxErr> {
@@ -138,6 +255,27 @@ fn install_network_seccomp_filter_on_current_thread() -> std::result::Result<(),
     rules.insert(libc::SYS_socket, vec![unix_only_rule.clone()]);
     rules.insert(libc::SYS_socketpair, vec![unix_only_rule]); // always deny (Unix can use socketpair but fine, keep open?)
 
+    install_seccomp_deny_rules_on_current_thread(rules)
+}
+
+/// Rules for allowlist mode: the command shares a user namespace with the
+/// bridge process, so it must not be able to attach to it or write its memory.
+fn process_tampering_rules() -> BTreeMap<i64, Vec<SeccompRule>> {
+    [
+        libc::SYS_ptrace,
+        libc::SYS_process_vm_writev,
+        libc::SYS_setns,
+    ]
+    .into_iter()
+    .map(|nr| (nr, Vec::new()))
+    .collect()
+}
+
+/// Installs a seccomp filter that fails every syscall matching `rules` with
+/// `EPERM` and allows everything else.
+fn install_seccomp_deny_rules_on_current_thread(
+    rules: BTreeMap<i64, Vec<SeccompRule>>,
+) -> std::result::Result<(), SandboxErr> {
     let filter = SeccompFilter::new(
         rules,
         SeccompAction::Allow,                     // default – allow
@@ -2,6 +2,8 @@
 mod landlock;
 #[cfg(target_os = "linux")]
 mod linux_run_main;
+#[cfg(target_os = "linux")]
+mod network_proxy;
 
 #[cfg(target_os = "linux")]
 pub use landlock::is_landlock_supported;
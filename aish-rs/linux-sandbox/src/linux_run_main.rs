@@ -3,6 +3,7 @@ use std::ffi::CString;
 use std::path::PathBuf;
 
 use crate::landlock::apply_sandbox_policy_to_current_thread;
+use crate::network_proxy::enter_network_allowlist_sandbox;
 
 #[derive(Debug, Parser)]
 pub struct LandlockCommand {
@@ -26,6 +27,13 @@ pub fn run_main() -> ! {
         command,
     } = LandlockCommand::parse();
 
+    // Allowlist mode forks a proxy supervisor and a namespace bridge; only
+    // the process that will run the command returns here.
+    let network_allowlist = sandbox_policy.network_allowlist();
+    if !network_allowlist.is_empty() {
+        enter_network_allowlist_sandbox(network_allowlist);
+    }
+
     if let Err(e) = apply_sandbox_policy_to_current_thread(&sandbox_policy, &sandbox_policy_cwd) {
         panic!("error running landlock: {e:?}");
     }
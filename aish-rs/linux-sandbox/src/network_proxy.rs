@@ -0,0 +1,542 @@
+//! Network-allowlist mode for `current-dir-write` policies.
+//!
+//! The seccomp network filter is all-or-nothing, so when the policy carries a
+//! `network_allowlist` the command instead runs in a fresh user + network
+//! namespace whose only interface is loopback. Three processes cooperate:
+//!
+//! - the supervisor (this process) stays in the host network namespace and
+//!   serves an HTTP proxy on a Unix socket in a private temp dir, checking
+//!   every destination against the allowlist;
+//! - the bridge unshares the namespaces, listens on `127.0.0.1` inside them
+//!   and forwards each connection to the supervisor's socket;
+//! - the command itself, started with `HTTP(S)_PROXY`/`ALL_PROXY` pointing at
+//!   the bridge and `NO_PROXY` cleared. Loopback inside the namespace is
+//!   empty, so `localhost` targets go through the proxy as well and reach the
+//!   host's services when the allowlist names them.
+//!
+//! Blocked destinations get a `403` carrying [`NETWORK_DENIAL_HEADER`] and a
+//! [`NETWORK_DENIAL_MARKER`](aish_core::network_allowlist::NETWORK_DENIAL_MARKER)
+//! line on stderr, which core turns into a structured denial for the model.
+//! Only hostnames that are themselves allowed are ever resolved, so DNS cannot
+//! be used to smuggle data out.
+
+use std::ffi::CString;
+use std::io;
+use std::io::Read;
+use std::io::Write;
+use std::net::Ipv4Addr;
+use std::net::Shutdown;
+use std::net::TcpListener;
+use std::net::TcpStream;
+use std::net::ToSocketAddrs;
+use std::os::fd::AsRawFd;
+use std::os::fd::FromRawFd;
+use std::os::fd::OwnedFd;
+use std::os::unix::ffi::OsStrExt;
+use std::os::unix::net::UnixListener;
+use std::os::unix::net::UnixStream;
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+use aish_core::network_allowlist::NETWORK_DENIAL_HEADER;
+use aish_core::network_allowlist::NetworkAllowlist;
+use aish_core::network_allowlist::NetworkDenial;
+
+const MAX_REQUEST_HEAD_BYTES: usize = 16 * 1024;
+const REQUEST_HEAD_TIMEOUT: Duration = Duration::from_secs(30);
+const UPSTREAM_CONNECT_TIMEOUT: Duration = Duration::from_secs(30);
+const DENIAL_REASON: &str = "not in network_allowlist";
+
+const PROXY_ENV_VARS: [&str; 6] = [
+    "HTTP_PROXY",
+    "HTTPS_PROXY",
+    "ALL_PROXY",
+    "http_proxy",
+    "https_proxy",
+    "all_proxy",
+];
+const NO_PROXY_ENV_VARS: [&str; 2] = ["NO_PROXY", "no_proxy"];
+
+/// Sets up the proxy, namespaces and bridge described in the module docs.
+/// Returns only in the process that should go on to apply the rest of the
+/// sandbox policy and exec the command; every other process exits with the
+/// command's status.
+pub(crate) fn enter_network_allowlist_sandbox(entries: &[String]) {
+    let allowlist = NetworkAllowlist::parse(entries).unwrap_or_else(|err| fail(&err.to_string()));
+    let socket_dir = create_private_dir()
+        .unwrap_or_else(|err| fail(&format!("failed to create proxy socket dir: {err}")));
+    let socket_path = socket_dir.join("proxy.sock");
+    let listener = UnixListener::bind(&socket_path)
+        .unwrap_or_else(|err| fail(&format!("failed to bind network proxy socket: {err}")));
+
+    // Fork before any thread exists so the child may unshare its user
+    // namespace (which requires a single-threaded process).
+    match unsafe { libc::fork() } {
+        -1 => fail(&format!("fork failed: {}", io::Error::last_os_error())),
+        0 => {
+            drop(listener);
+            run_isolated_bridge(&socket_path);
+        }
+        child => {
+            let allowlist = Arc::new(allowlist);
+            thread::spawn(move || serve_proxy(listener, allowlist));
+            let status = wait_for(child);
+            let _ = std::fs::remove_dir_all(&socket_dir);
+            exit_with_status(status);
+        }
+    }
+}
+
+/// Runs in the forked child: moves into new user and network namespaces and
+/// forks once more. Returns in the grandchild, which will run the command.
+fn run_isolated_bridge(socket_path: &Path) {
+    set_parent_death_signal();
+    let uid = unsafe { libc::geteuid() };
+    let gid = unsafe { libc::getegid() };
+    if unsafe { libc::unshare(libc::CLONE_NEWUSER | libc::CLONE_NEWNET) } != 0 {
+        fail(&format!(
+            "network_allowlist requires unprivileged user namespaces: {}",
+            io::Error::last_os_error()
+        ));
+    }
+    write_id_maps(uid, gid)
+        .unwrap_or_else(|err| fail(&format!("failed to map user namespace ids: {err}")));
+    bring_up_loopback()
+        .unwrap_or_else(|err| fail(&format!("failed to bring up loopback interface: {err}")));
+    let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0))
+        .unwrap_or_else(|err| fail(&format!("failed to bind proxy bridge: {err}")));
+    let port = listener
+        .local_addr()
+        .unwrap_or_else(|err| fail(&format!("failed to read proxy bridge address: {err}")))
+        .port();
+
+    match unsafe { libc::fork() } {
+        -1 => fail(&format!("fork failed: {}", io::Error::last_os_error())),
+        0 => {
+            set_parent_death_signal();
+            let proxy_url = format!("http://127.0.0.1:{port}");
+            // SAFETY: the grandchild is single-threaded; nothing else can be
+            // reading the environment concurrently.
+            unsafe {
+                for name in PROXY_ENV_VARS {
+                    std::env::set_var(name, &proxy_url);
+                }
+                for name in NO_PROXY_ENV_VARS {
+                    std::env::remove_var(name);
+                }
+            }
+        }
+        child => {
+            let socket_path = socket_path.to_path_buf();
+            thread::spawn(move || bridge_connections(listener, socket_path));
+            let status = wait_for(child);
+            exit_with_status(status);
+        }
+    }
+}
+
+/// Maps the caller's uid/gid to themselves inside the new user namespace so
+/// file ownership (and Landlock rules) behave as they do outside it.
+fn write_id_maps(uid: libc::uid_t, gid: libc::gid_t) -> io::Result<()> {
+    std::fs::write("/proc/self/setgroups", "deny")?;
+    std::fs::write("/proc/self/uid_map", format!("{uid} {uid} 1"))?;
+    std::fs::write("/proc/self/gid_map", format!("{gid} {gid} 1"))?;
+    Ok(())
+}
+
+fn bring_up_loopback() -> io::Result<()> {
+    let fd = unsafe { libc::socket(libc::AF_INET, libc::SOCK_DGRAM | libc::SOCK_CLOEXEC, 0) };
+    if fd < 0 {
+        return Err(io::Error::last_os_error());
+    }
+    // SAFETY: `fd` was just returned by `socket` and is owned by nobody else.
+    let fd = unsafe { OwnedFd::from_raw_fd(fd) };
+
+    let mut request: libc::ifreq = unsafe { std::mem::zeroed() };
+    for (dst, src) in request.ifr_name.iter_mut().zip(b"lo") {
+        *dst = *src as libc::c_char;
+    }
+    if unsafe {
+        libc::ioctl(
+            fd.as_raw_fd(),
+            libc::SIOCGIFFLAGS,
+            &mut request as *mut libc::ifreq,
+        )
+    } < 0
+    {
+        return Err(io::Error::last_os_error());
+    }
+    unsafe {
+        request.ifr_ifru.ifru_flags |= (libc::IFF_UP | libc::IFF_RUNNING) as libc::c_short;
+    }
+    if unsafe {
+        libc::ioctl(
+            fd.as_raw_fd(),
+            libc::SIOCSIFFLAGS,
+            &request as *const libc::ifreq,
+        )
+    } < 0
+    {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(())
+}
+
+fn create_private_dir() -> io::Result<PathBuf> {
+    let template = std::env::temp_dir().join("aish-net-XXXXXX");
+    let template = CString::new(template.as_os_str().as_bytes())
+        .map_err(|err| io::Error::new(io::ErrorKind::InvalidInput, err))?;
+    let mut template = template.into_bytes_with_nul();
+    // mkdtemp creates the directory with mode 0700.
+    if unsafe { libc::mkdtemp(template.as_mut_ptr().cast()) }.is_null() {
+        return Err(io::Error::last_os_error());
+    }
+    template.pop();
+    Ok(PathBuf::from(std::ffi::OsStr::from_bytes(&template)))
+}
+
+fn serve_proxy(listener: UnixListener, allowlist: Arc<NetworkAllowlist>) {
+    for stream in listener.incoming() {
+        let Ok(stream) = stream else {
+            continue;
+        };
+        let allowlist = Arc::clone(&allowlist);
+        thread::spawn(move || {
+            let _ = handle_proxy_client(stream, &allowlist);
+        });
+    }
+}
+
+fn bridge_connections(listener: TcpListener, socket_path: PathBuf) {
+    for stream in listener.incoming() {
+        let Ok(stream) = stream else {
+            continue;
+        };
+        let socket_path = socket_path.clone();
+        thread::spawn(move || {
+            if let Ok(proxy) = UnixStream::connect(&socket_path) {
+                relay(stream, proxy);
+            }
+        });
+    }
+}
+
+/// A parsed proxy request: either a `CONNECT host:port` tunnel or a plain
+/// HTTP request in absolute form, rewritten to origin form for the upstream.
+#[derive(Debug, PartialEq, Eq)]
+struct ProxyRequest {
+    host: String,
+    port: u16,
+    forwarded_head: Option<String>,
+}
+
+impl ProxyRequest {
+    fn parse(head: &[u8]) -> Option<Self> {
+        let head = std::str::from_utf8(head).ok()?;
+        let mut lines = head.split("\r\n");
+        let mut request_line = lines.next()?.split(' ');
+        let method = request_line.next()?;
+        let target = request_line.next()?;
+        let version = request_line.next()?;
+
+        if method.eq_ignore_ascii_case("CONNECT") {
+            let (host, port) = split_authority(target, None)?;
+            return Some(Self {
+                host,
+                port,
+                forwarded_head: None,
+            });
+        }
+
+        let rest = target.strip_prefix("http://")?;
+        let (authority, path) = match rest.find('/') {
+            Some(idx) => rest.split_at(idx),
+            None => (rest, "/"),
+        };
+        let (host, port) = split_authority(authority, Some(80))?;
+
+        // Force one request per connection so a client cannot reuse an
+        // allowed upstream connection for a request aimed at another host.
+        let mut forwarded_head = format!("{method} {path} {version}\r\n");
+        for line in lines.filter(|line| !line.is_empty()) {
+            let name = line.split(':').next().unwrap_or_default().trim();
+            if ["connection", "proxy-connection", "proxy-authorization"]
+                .iter()
+                .any(|skip| name.eq_ignore_ascii_case(skip))
+            {
+                continue;
+            }
+            forwarded_head.push_str(line);
+            forwarded_head.push_str("\r\n");
+        }
+        forwarded_head.push_str("Connection: close\r\n\r\n");
+
+        Some(Self {
+            host,
+            port,
+            forwarded_head: Some(forwarded_head),
+        })
+    }
+}
+
+/// Splits `[userinfo@]host[:port]`, accepting bracketed IPv6 hosts.
+fn split_authority(authority: &str, default_port: Option<u16>) -> Option<(String, u16)> {
+    let authority = authority
+        .rsplit_once('@')
+        .map_or(authority, |(_, host)| host);
+    let (host, port) = if let Some(rest) = authority.strip_prefix('[') {
+        let (host, rest) = rest.split_once(']')?;
+        (host, rest.strip_prefix(':'))
+    } else {
+        match authority.rsplit_once(':') {
+            Some((host, port)) => (host, Some(port)),
+            None => (authority, None),
+        }
+    };
+    let port = match port {
+        Some(port) => port.parse().ok()?,
+        None => default_port?,
+    };
+    (!host.is_empty()).then(|| (host.to_string(), port))
+}
+
+fn handle_proxy_client(mut client: UnixStream, allowlist: &NetworkAllowlist) -> io::Result<()> {
+    client.set_read_timeout(Some(REQUEST_HEAD_TIMEOUT))?;
+    let (head, leftover) = read_request_head(&mut client)?;
+    let Some(request) = ProxyRequest::parse(&head) else {
+        return respond(
+            &mut client,
+            "400 Bad Request",
+            &[],
+            "aish sandbox proxy: expected CONNECT or an absolute http:// request\n",
+        );
+    };
+
+    if !allowlist.allows_host(&request.host, request.port) {
+        let denial = NetworkDenial {
+            host: request.host,
+            port: request.port,
+            reason: DENIAL_REASON.to_string(),
+        };
+        eprintln!("{}", denial.to_marker_line());
+        return respond(
+            &mut client,
+            "403 Forbidden",
+            &[(NETWORK_DENIAL_HEADER, "network-allowlist")],
+            &format!(
+                "aish sandbox: connection to {}:{} blocked ({})\n",
+                denial.host, denial.port, denial.reason
+            ),
+        );
+    }
+
+    let mut upstream = match connect_upstream(&request.host, request.port) {
+        Ok(upstream) => upstream,
+        Err(err) => {
+            return respond(
+                &mut client,
+                "502 Bad Gateway",
+                &[],
+                &format!(
+                    "aish sandbox proxy: could not connect to {}:{}: {err}\n",
+                    request.host, request.port
+                ),
+            );
+        }
+    };
+    client.set_read_timeout(None)?;
+    match &request.forwarded_head {
+        Some(head) => upstream.write_all(head.as_bytes())?,
+        None => client.write_all(b"HTTP/1.1 200 Connection Established\r\n\r\n")?,
+    }
+    upstream.write_all(&leftover)?;
+    relay(client, upstream);
+    Ok(())
+}
+
+/// Reads up to the blank line that ends the request head. Returns the head
+/// (including the terminator) and any body bytes read past it.
+fn read_request_head(stream: &mut impl Read) -> io::Result<(Vec<u8>, Vec<u8>)> {
+    let mut buf = Vec::new();
+    let mut chunk = [0u8; 4096];
+    loop {
+        let n = stream.read(&mut chunk)?;
+        if n == 0 {
+            return Err(io::ErrorKind::UnexpectedEof.into());
+        }
+        buf.extend_from_slice(&chunk[..n]);
+        if let Some(end) = buf.windows(4).position(|window| window == b"\r\n\r\n") {
+            let leftover = buf.split_off(end + 4);
+            return Ok((buf, leftover));
+        }
+        if buf.len() > MAX_REQUEST_HEAD_BYTES {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "request head too large",
+            ));
+        }
+    }
+}
+
+fn connect_upstream(host: &str, port: u16) -> io::Result<TcpStream> {
+    let mut last_err = io::Error::new(io::ErrorKind::NotFound, "host did not resolve");
+    for addr in (host, port).to_socket_addrs()? {
+        match TcpStream::connect_timeout(&addr, UPSTREAM_CONNECT_TIMEOUT) {
+            Ok(stream) => return Ok(stream),
+            Err(err) => last_err = err,
+        }
+    }
+    Err(last_err)
+}
+
+fn respond(
+    stream: &mut UnixStream,
+    status: &str,
+    headers: &[(&str, &str)],
+    body: &str,
+) -> io::Result<()> {
+    let mut response = format!("HTTP/1.1 {status}\r\n");
+    for (name, value) in headers {
+        response.push_str(&format!("{name}: {value}\r\n"));
+    }
+    response.push_str(&format!(
+        "Content-Type: text/plain\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+        body.len()
+    ));
+    stream.write_all(response.as_bytes())
+}
+
+/// A bidirectional stream that can be split across two relay threads.
+trait Duplex: Read + Write + Send + Sized + 'static {
+    fn try_clone(&self) -> io::Result<Self>;
+    fn shutdown_write(&self);
+}
+
+impl Duplex for TcpStream {
+    fn try_clone(&self) -> io::Result<Self> {
+        TcpStream::try_clone(self)
+    }
+
+    fn shutdown_write(&self) {
+        let _ = self.shutdown(Shutdown::Write);
+    }
+}
+
+impl Duplex for UnixStream {
+    fn try_clone(&self) -> io::Result<Self> {
+        UnixStream::try_clone(self)
+    }
+
+    fn shutdown_write(&self) {
+        let _ = self.shutdown(Shutdown::Write);
+    }
+}
+
+/// Copies bytes both ways until each side has closed its half.
+fn relay<A: Duplex, B: Duplex>(mut a: A, mut b: B) {
+    let (Ok(mut a_read), Ok(mut b_write)) = (a.try_clone(), b.try_clone()) else {
+        return;
+    };
+    let forward = thread::spawn(move || {
+        let _ = io::copy(&mut a_read, &mut b_write);
+        b_write.shutdown_write();
+    });
+    let _ = io::copy(&mut b, &mut a);
+    a.shutdown_write();
+    let _ = forward.join();
+}
+
+fn set_parent_death_signal() {
+    unsafe {
+        libc::prctl(libc::PR_SET_PDEATHSIG, libc::SIGKILL);
+    }
+}
+
+fn wait_for(pid: libc::pid_t) -> libc::c_int {
+    let mut status = 0;
+    loop {
+        if unsafe { libc::waitpid(pid, &mut status, 0) } != -1 {
+            return status;
+        }
+        let err = io::Error::last_os_error();
+        if err.kind() != io::ErrorKind::Interrupted {
+            fail(&format!("waitpid failed: {err}"));
+        }
+    }
+}
+
+/// Exits the way the waited-for child did, re-raising its signal if it was
+/// killed by one.
+fn exit_with_status(status: libc::c_int) -> ! {
+    if libc::WIFEXITED(status) {
+        std::process::exit(libc::WEXITSTATUS(status));
+    }
+    if libc::WIFSIGNALED(status) {
+        let signal = libc::WTERMSIG(status);
+        unsafe {
+            libc::signal(signal, libc::SIG_DFL);
+            libc::raise(signal);
+        }
+        std::process::exit(128 + signal);
+    }
+    std::process::exit(1);
+}
+
+/// Aborts sandbox setup. The message names the sandbox so core classifies
+/// the failure as a sandbox denial rather than a command error.
+fn fail(message: &str) -> ! {
+    eprintln!("aish-sandbox: {message}");
+    std::process::exit(1);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn connect_requests_require_a_port() {
+        let request =
+            ProxyRequest::parse(b"CONNECT pypi.org:443 HTTP/1.1\r\nHost: pypi.org\r\n\r\n");
+        assert_eq!(
+            request,
+            Some(ProxyRequest {
+                host: "pypi.org".to_string(),
+                port: 443,
+                forwarded_head: None,
+            })
+        );
+        assert_eq!(
+            ProxyRequest::parse(b"CONNECT pypi.org HTTP/1.1\r\n\r\n"),
+            None
+        );
+    }
+
+    #[test]
+    fn absolute_requests_are_rewritten_to_origin_form() {
+        let request = ProxyRequest::parse(
+            b"GET http://[::1]:8080/simple/?q=1 HTTP/1.1\r\nHost: [::1]:8080\r\nProxy-Connection: keep-alive\r\nConnection: keep-alive\r\n\r\n",
+        )
+        .expect("request should parse");
+        assert_eq!(request.host, "::1");
+        assert_eq!(request.port, 8080);
+        assert_eq!(
+            request.forwarded_head.as_deref(),
+            Some("GET /simple/?q=1 HTTP/1.1\r\nHost: [::1]:8080\r\nConnection: close\r\n\r\n")
+        );
+    }
+
+    #[test]
+    fn non_http_targets_are_rejected() {
+        assert_eq!(
+            ProxyRequest::parse(b"GET /index.html HTTP/1.1\r\n\r\n"),
+            None
+        );
+        assert_eq!(
+            ProxyRequest::parse(b"GET ftp://example.com/ HTTP/1.1\r\n\r\n"),
+            None
+        );
+    }
+}
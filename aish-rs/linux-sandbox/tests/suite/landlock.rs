@@ -7,6 +7,7 @@ use aish_core::exec::process_exec_tool_call;
 use aish_core::exec_env::create_env;
 use aish_core::protocol::SandboxPolicy;
 use aish_core::sandboxing::SandboxPermissions;
+use aish_utils_absolute_path::AbsolutePathBuf;
 use std::collections::HashMap;
 use std::path::PathBuf;
 use tempfile::NamedTempFile;
@@ -43,8 +44,28 @@ async fn run_cmd(cmd: &[&str], timeout_ms: u64) {
     .await;
 }
 
-#[expect(clippy::print_stdout, clippy::unwrap_used)]
 async fn run_cmd_in_cwd(cmd: &[&str], cwd: PathBuf, timeout_ms: u64) {
+    let sandbox_policy = SandboxPolicy::CurrentDirWrite {
+        network_access: false,
+        // Exclude tmp-related folders from writable roots because we need a
+        // folder that is writable by tests but that we intentionally disallow
+        // writing to in the sandbox.
+        exclude_tmpdir_env_var: true,
+        exclude_slash_tmp: true,
+        network_allowlist: Vec::new(),
+        writable_roots: Vec::new(),
+        deny_read: Vec::new(),
+    };
+    run_cmd_with_policy(cmd, cwd, &sandbox_policy, timeout_ms).await;
+}
+
+#[expect(clippy::print_stdout, clippy::unwrap_used)]
+async fn run_cmd_with_policy(
+    cmd: &[&str],
+    cwd: PathBuf,
+    sandbox_policy: &SandboxPolicy,
+    timeout_ms: u64,
+) {
     let sandbox_cwd = cwd.clone();
     let params = ExecParams {
         command: cmd.iter().copied().map(str::to_owned).collect(),
@@ -56,19 +77,11 @@ async fn run_cmd_in_cwd(cmd: &[&str], cwd: PathBuf, timeout_ms: u64) {
         arg0: None,
     };
 
-    let sandbox_policy = SandboxPolicy::CurrentDirWrite {
-        network_access: false,
-        // Exclude tmp-related folders from writable roots because we need a
-        // folder that is writable by tests but that we intentionally disallow
-        // writing to in the sandbox.
-        exclude_tmpdir_env_var: true,
-        exclude_slash_tmp: true,
-    };
     let sandbox_program = env!("CARGO_BIN_EXE_aish-linux-sandbox");
     let aish_linux_sandbox_exe = Some(PathBuf::from(sandbox_program));
     let res = process_exec_tool_call(
         params,
-        &sandbox_policy,
+        sandbox_policy,
         sandbox_cwd.as_path(),
         &aish_linux_sandbox_exe,
         None,
@@ -130,6 +143,108 @@ async fn test_writable_root() {
     .await;
 }
 
+fn deny_read_policy(writable_roots: Vec<AbsolutePathBuf>, deny_read: &[&str]) -> SandboxPolicy {
+    SandboxPolicy::CurrentDirWrite {
+        network_access: false,
+        exclude_tmpdir_env_var: true,
+        exclude_slash_tmp: true,
+        network_allowlist: Vec::new(),
+        writable_roots,
+        deny_read: deny_read.iter().map(ToString::to_string).collect(),
+    }
+}
+
+#[tokio::test]
+async fn test_additional_writable_root() {
+    let cwd = tempfile::tempdir().unwrap();
+    let extra = tempfile::tempdir().unwrap();
+    let policy = deny_read_policy(vec![AbsolutePathBuf::try_from(extra.path()).unwrap()], &[]);
+    let file_path = extra.path().join("test");
+    run_cmd_with_policy(
+        &[
+            "bash",
+            "-lc",
+            &format!("echo blah > {}", file_path.to_string_lossy()),
+        ],
+        cwd.path().to_path_buf(),
+        &policy,
+        LONG_TIMEOUT_MS,
+    )
+    .await;
+}
+
+/// A directory outside the writable roots holding `.env` and `README.md`,
+/// plus a `deny_read` policy for its `.env`.
+fn secrets_dir() -> (tempfile::TempDir, SandboxPolicy) {
+    let secrets = tempfile::tempdir().unwrap();
+    std::fs::write(secrets.path().join(".env"), "TOKEN=secret").unwrap();
+    std::fs::write(secrets.path().join("README.md"), "hello").unwrap();
+    let env_path = secrets.path().join(".env");
+    let policy = deny_read_policy(Vec::new(), &[&env_path.to_string_lossy()]);
+    (secrets, policy)
+}
+
+#[tokio::test]
+async fn test_deny_read_leaves_siblings_readable() {
+    let cwd = tempfile::tempdir().unwrap();
+    let (secrets, policy) = secrets_dir();
+    let dir = secrets.path().to_string_lossy();
+    run_cmd_with_policy(
+        &[
+            "bash",
+            "-lc",
+            &format!("cat {dir}/README.md && ls -a {dir} | grep -q .env"),
+        ],
+        cwd.path().to_path_buf(),
+        &policy,
+        LONG_TIMEOUT_MS,
+    )
+    .await;
+}
+
+#[tokio::test]
+#[should_panic(expected = "Sandbox(Denied")]
+async fn test_deny_read_blocks_contents() {
+    let cwd = tempfile::tempdir().unwrap();
+    let (secrets, policy) = secrets_dir();
+    let env_path = secrets.path().join(".env");
+    run_cmd_with_policy(
+        &["cat", &env_path.to_string_lossy()],
+        cwd.path().to_path_buf(),
+        &policy,
+        LONG_TIMEOUT_MS,
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn test_deny_read_keeps_new_files_readable() {
+    let cwd = tempfile::tempdir().unwrap();
+    let (_secrets, policy) = secrets_dir();
+    run_cmd_with_policy(
+        &["bash", "-lc", "echo built > out.txt && cat out.txt"],
+        cwd.path().to_path_buf(),
+        &policy,
+        LONG_TIMEOUT_MS,
+    )
+    .await;
+}
+
+#[tokio::test]
+#[should_panic]
+async fn test_deny_read_inside_writable_root_is_refused() {
+    let cwd = tempfile::tempdir().unwrap();
+    std::fs::write(cwd.path().join(".env"), "TOKEN=secret").unwrap();
+    let policy = deny_read_policy(Vec::new(), &[".env"]);
+    run_cmd_with_policy(
+        &["true"],
+        cwd.path().to_path_buf(),
+        &policy,
+        LONG_TIMEOUT_MS,
+    )
+    .await;
+}
+
 #[tokio::test]
 #[should_panic(expected = "Sandbox(Timeout")]
 async fn test_timeout() {
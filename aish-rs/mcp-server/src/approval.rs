@@ -0,0 +1,290 @@
+//! Turns approval requests from a running session into MCP elicitations.
+//!
+//! Every elicitation carries a `decision` field in its requested schema. A
+//! client that only knows plain MCP can accept (approve once), decline (deny)
+//! or cancel (deny and stop the turn); one that wants more can answer with a
+//! `decision` such as `approved_for_session`. `_meta` holds the originating
+//! `tools/call` request id and the full event, for clients that render Aish
+//! approvals themselves.
+
+use std::sync::Arc;
+
+use aish_core::AishConversation;
+use aish_core::parse_command::shlex_join;
+use aish_protocol::approvals::ApplyPatchApprovalRequestEvent;
+use aish_protocol::approvals::ElicitationAction;
+use aish_protocol::approvals::ElicitationRequestEvent;
+use aish_protocol::approvals::ExecApprovalRequestEvent;
+use aish_protocol::protocol::EventMsg;
+use aish_protocol::protocol::Op;
+use aish_protocol::protocol::ReviewDecision;
+use mcp_types::ElicitRequest;
+use mcp_types::ElicitRequestParamsRequestedSchema;
+use mcp_types::ElicitResult;
+use mcp_types::RequestId;
+use serde::Serialize;
+use tracing::error;
+
+use crate::outgoing_message::OutgoingMessageSender;
+
+/// Field of the requested schema that carries an explicit decision.
+const DECISION_FIELD: &str = "decision";
+
+#[derive(Debug, Serialize)]
+struct AishElicitRequestParams {
+    message: String,
+    #[serde(rename = "requestedSchema")]
+    requested_schema: ElicitRequestParamsRequestedSchema,
+    #[serde(rename = "_meta")]
+    meta: serde_json::Value,
+}
+
+pub(crate) async fn handle_exec_approval_request(
+    event_id: String,
+    event: ExecApprovalRequestEvent,
+    conversation: Arc<AishConversation>,
+    outgoing: Arc<OutgoingMessageSender>,
+    tool_call_id: RequestId,
+) {
+    let command = shlex_join(&event.command);
+    let mut message = format!("Allow Aish to run `{command}` in {}?", event.cwd.display());
+    if let Some(reason) = &event.reason {
+        message.push_str(&format!("\n\n{reason}"));
+    }
+    let mut decisions = vec!["approved", "approved_for_session", "denied", "abort"];
+    let amendment = event.proposed_execpolicy_amendment.clone();
+    if amendment.is_some() {
+        decisions.insert(2, "approved_execpolicy_amendment");
+    }
+
+    let meta = serde_json::json!({
+        "requestId": tool_call_id,
+        "event": EventMsg::ExecApprovalRequest(event),
+    });
+    let result = elicit(&outgoing, message, approval_schema(&decisions), meta).await;
+    let decision = review_decision(result, |name| match name {
+        "approved_execpolicy_amendment" => amendment.clone().map(|proposed_execpolicy_amendment| {
+            ReviewDecision::ApprovedExecpolicyAmendment {
+                proposed_execpolicy_amendment,
+            }
+        }),
+        _ => None,
+    });
+
+    if let Err(err) = conversation
+        .submit(Op::ExecApproval {
+            id: event_id,
+            decision,
+        })
+        .await
+    {
+        error!("failed to submit exec approval: {err}");
+    }
+}
+
+pub(crate) async fn handle_patch_approval_request(
+    event_id: String,
+    event: ApplyPatchApprovalRequestEvent,
+    conversation: Arc<AishConversation>,
+    outgoing: Arc<OutgoingMessageSender>,
+    tool_call_id: RequestId,
+) {
+    let mut files: Vec<String> = event
+        .changes
+        .keys()
+        .map(|path| path.display().to_string())
+        .collect();
+    files.sort();
+    let mut message = format!("Allow Aish to apply changes to {}?", files.join(", "));
+    if let Some(reason) = &event.reason {
+        message.push_str(&format!("\n\n{reason}"));
+    }
+
+    let meta = serde_json::json!({
+        "requestId": tool_call_id,
+        "event": EventMsg::ApplyPatchApprovalRequest(event),
+    });
+    let decisions = ["approved", "approved_for_session", "denied", "abort"];
+    let result = elicit(&outgoing, message, approval_schema(&decisions), meta).await;
+    let decision = review_decision(result, |_| None);
+
+    if let Err(err) = conversation
+        .submit(Op::PatchApproval {
+            id: event_id,
+            decision,
+        })
+        .await
+    {
+        error!("failed to submit patch approval: {err}");
+    }
+}
+
+/// Relays an elicitation from an MCP server that the session itself uses.
+pub(crate) async fn handle_mcp_elicitation_request(
+    event: ElicitationRequestEvent,
+    conversation: Arc<AishConversation>,
+    outgoing: Arc<OutgoingMessageSender>,
+    tool_call_id: RequestId,
+) {
+    let ElicitationRequestEvent {
+        server_name,
+        id,
+        message,
+    } = event;
+    let meta = serde_json::json!({
+        "requestId": tool_call_id,
+        "serverName": server_name,
+    });
+    let schema = ElicitRequestParamsRequestedSchema {
+        properties: serde_json::json!({}),
+        required: None,
+        r#type: "object".to_string(),
+    };
+    let decision = match elicit(&outgoing, message, schema, meta).await {
+        Some(result) if result.action == "accept" => ElicitationAction::Accept,
+        Some(result) if result.action == "cancel" => ElicitationAction::Cancel,
+        _ => ElicitationAction::Decline,
+    };
+
+    if let Err(err) = conversation
+        .submit(Op::ResolveElicitation {
+            server_name,
+            request_id: id,
+            decision,
+        })
+        .await
+    {
+        error!("failed to resolve elicitation: {err}");
+    }
+}
+
+fn approval_schema(decisions: &[&str]) -> ElicitRequestParamsRequestedSchema {
+    ElicitRequestParamsRequestedSchema {
+        properties: serde_json::json!({
+            DECISION_FIELD: {
+                "type": "string",
+                "enum": decisions,
+                "description": "Defaults to `approved` when accepted.",
+            }
+        }),
+        required: None,
+        r#type: "object".to_string(),
+    }
+}
+
+/// Sends an elicitation and waits for the client. `None` means the client
+/// answered with an error or the server is shutting down.
+async fn elicit(
+    outgoing: &OutgoingMessageSender,
+    message: String,
+    requested_schema: ElicitRequestParamsRequestedSchema,
+    meta: serde_json::Value,
+) -> Option<ElicitResult> {
+    let params = AishElicitRequestParams {
+        message,
+        requested_schema,
+        meta,
+    };
+    let params = match serde_json::to_value(&params) {
+        Ok(params) => params,
+        Err(err) => {
+            error!("failed to serialize elicitation: {err}");
+            return None;
+        }
+    };
+    let response = outgoing.send_request::<ElicitRequest>(params).await.await;
+    match response.map(serde_json::from_value::<ElicitResult>) {
+        Ok(Ok(result)) => Some(result),
+        Ok(Err(err)) => {
+            error!("failed to parse elicitation result: {err}");
+            None
+        }
+        Err(_) => None,
+    }
+}
+
+/// Maps an elicitation result onto a review decision. Anything the client
+/// did not clearly approve is denied.
+fn review_decision(
+    result: Option<ElicitResult>,
+    extra: impl Fn(&str) -> Option<ReviewDecision>,
+) -> ReviewDecision {
+    let Some(ElicitResult { action, content }) = result else {
+        return ReviewDecision::Denied;
+    };
+    match action.as_str() {
+        "accept" => {
+            let name = content
+                .as_ref()
+                .and_then(|content| content.get(DECISION_FIELD))
+                .and_then(serde_json::Value::as_str)
+                .unwrap_or("approved");
+            match name {
+                "approved" => ReviewDecision::Approved,
+                "approved_for_session" => ReviewDecision::ApprovedForSession,
+                "abort" => ReviewDecision::Abort,
+                other => extra(other).unwrap_or(ReviewDecision::Denied),
+            }
+        }
+        "cancel" => ReviewDecision::Abort,
+        _ => ReviewDecision::Denied,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn result(action: &str, content: Option<serde_json::Value>) -> Option<ElicitResult> {
+        Some(ElicitResult {
+            action: action.to_string(),
+            content,
+        })
+    }
+
+    #[test]
+    fn plain_elicitation_actions_map_to_decisions() {
+        assert_eq!(
+            review_decision(result("accept", None), |_| None),
+            ReviewDecision::Approved
+        );
+        assert_eq!(
+            review_decision(result("decline", None), |_| None),
+            ReviewDecision::Denied
+        );
+        assert_eq!(
+            review_decision(result("cancel", None), |_| None),
+            ReviewDecision::Abort
+        );
+        assert_eq!(review_decision(None, |_| None), ReviewDecision::Denied);
+    }
+
+    #[test]
+    fn accepted_content_selects_the_decision() {
+        assert_eq!(
+            review_decision(
+                result(
+                    "accept",
+                    Some(serde_json::json!({"decision": "approved_for_session"}))
+                ),
+                |_| None
+            ),
+            ReviewDecision::ApprovedForSession
+        );
+        assert_eq!(
+            review_decision(
+                result("accept", Some(serde_json::json!({"decision": "denied"}))),
+                |_| None
+            ),
+            ReviewDecision::Denied
+        );
+        assert_eq!(
+            review_decision(
+                result("accept", Some(serde_json::json!({"decision": "bogus"}))),
+                |_| None
+            ),
+            ReviewDecision::Denied
+        );
+    }
+}
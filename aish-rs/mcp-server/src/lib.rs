@@ -0,0 +1,136 @@
+//! `aish mcp serve`: runs Aish as an MCP server on stdio.
+//!
+//! The server exposes two tools. `aish_run` starts a conversation and
+//! `aish_reply` continues one. While a call runs, every event of the session
+//! is streamed to the client as an `aish/event` notification, and approval
+//! requests become `elicitation/create` requests. The call's result is the
+//! final agent message plus the `conversation_id` to reply to.
+//!
+//! Messages are newline-delimited JSON-RPC, read from stdin and written to
+//! stdout. Logs go to stderr.
+
+#![deny(clippy::print_stdout, clippy::print_stderr)]
+
+use std::io::Result as IoResult;
+use std::path::PathBuf;
+
+use aish_common::CliConfigOverrides;
+use aish_core::AuthManager;
+use aish_core::ConversationManager;
+use aish_core::config::find_codex_home;
+use aish_protocol::protocol::SessionSource;
+use mcp_types::JSONRPCMessage;
+use tokio::io::AsyncBufReadExt;
+use tokio::io::AsyncWriteExt;
+use tokio::io::BufReader;
+use tokio::io::{self};
+use tokio::sync::mpsc;
+use tracing::debug;
+use tracing::error;
+use tracing::info;
+use tracing_subscriber::EnvFilter;
+
+mod approval;
+mod error_code;
+mod message_processor;
+mod outgoing_message;
+mod tool_config;
+mod tool_runner;
+
+use crate::message_processor::MessageProcessor;
+use crate::outgoing_message::OutgoingMessage;
+use crate::outgoing_message::OutgoingMessageSender;
+
+pub use crate::tool_config::AishReplyParams;
+pub use crate::tool_config::AishRunParams;
+
+/// Size of the bounded channel between the stdin reader and the processor.
+const CHANNEL_CAPACITY: usize = 128;
+
+pub async fn run_main(
+    aish_linux_sandbox_exe: Option<PathBuf>,
+    cli_config_overrides: CliConfigOverrides,
+) -> IoResult<()> {
+    // Stdout carries the protocol, so logs must go to stderr.
+    let _ = tracing_subscriber::fmt()
+        .with_writer(std::io::stderr)
+        .with_env_filter(EnvFilter::from_default_env())
+        .try_init();
+
+    let cli_overrides = cli_config_overrides.parse_overrides().map_err(|err| {
+        std::io::Error::new(
+            std::io::ErrorKind::InvalidInput,
+            format!("error parsing -c overrides: {err}"),
+        )
+    })?;
+    let codex_home = find_codex_home()?;
+    let conversation_manager =
+        ConversationManager::new(AuthManager::shared(codex_home, true), SessionSource::Mcp);
+
+    let (incoming_tx, mut incoming_rx) = mpsc::channel::<JSONRPCMessage>(CHANNEL_CAPACITY);
+    let (outgoing_tx, mut outgoing_rx) = mpsc::unbounded_channel::<OutgoingMessage>();
+
+    let stdin_reader_handle = tokio::spawn(async move {
+        let mut lines = BufReader::new(io::stdin()).lines();
+        while let Some(line) = lines.next_line().await.unwrap_or_default() {
+            match serde_json::from_str::<JSONRPCMessage>(&line) {
+                Ok(message) => {
+                    if incoming_tx.send(message).await.is_err() {
+                        break;
+                    }
+                }
+                Err(err) => error!("failed to deserialize JSON-RPC message: {err}"),
+            }
+        }
+        debug!("stdin reader finished (EOF)");
+    });
+
+    let processor_handle = tokio::spawn(async move {
+        let mut processor = MessageProcessor::new(
+            OutgoingMessageSender::new(outgoing_tx),
+            conversation_manager,
+            cli_overrides,
+            aish_linux_sandbox_exe,
+        );
+        while let Some(message) = incoming_rx.recv().await {
+            match message {
+                JSONRPCMessage::Request(request) => processor.process_request(request).await,
+                JSONRPCMessage::Response(response) => processor.process_response(response).await,
+                JSONRPCMessage::Notification(notification) => {
+                    processor.process_notification(notification).await
+                }
+                JSONRPCMessage::Error(error) => processor.process_error(error).await,
+            }
+        }
+        info!("processor task exited (channel closed)");
+    });
+
+    let stdout_writer_handle = tokio::spawn(async move {
+        let mut stdout = io::stdout();
+        while let Some(message) = outgoing_rx.recv().await {
+            let message = JSONRPCMessage::from(message);
+            match serde_json::to_string(&message) {
+                Ok(mut json) => {
+                    json.push('\n');
+                    if let Err(err) = stdout.write_all(json.as_bytes()).await {
+                        error!("failed to write to stdout: {err}");
+                        break;
+                    }
+                    if let Err(err) = stdout.flush().await {
+                        error!("failed to flush stdout: {err}");
+                        break;
+                    }
+                }
+                Err(err) => error!("failed to serialize JSON-RPC message: {err}"),
+            }
+        }
+        info!("stdout writer exited (channel closed)");
+    });
+
+    // The client is gone once stdin closes; turns still running have nobody
+    // to answer their elicitations, so do not wait for them.
+    let _ = tokio::join!(stdin_reader_handle, processor_handle);
+    stdout_writer_handle.abort();
+
+    Ok(())
+}
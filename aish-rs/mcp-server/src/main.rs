@@ -0,0 +1,20 @@
+//! Entry-point for the `aish-mcp-server` binary, which is also reachable as
+//! `aish mcp serve`.
+use aish_arg0::arg0_dispatch_or_else;
+use aish_common::CliConfigOverrides;
+use aish_mcp_server::run_main;
+use clap::Parser;
+
+#[derive(Parser, Debug)]
+struct TopCli {
+    #[clap(flatten)]
+    config_overrides: CliConfigOverrides,
+}
+
+fn main() -> anyhow::Result<()> {
+    arg0_dispatch_or_else(|aish_linux_sandbox_exe| async move {
+        let top_cli = TopCli::parse();
+        run_main(aish_linux_sandbox_exe, top_cli.config_overrides).await?;
+        Ok(())
+    })
+}
@@ -0,0 +1,291 @@
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use aish_core::ConversationManager;
+use aish_protocol::protocol::Op;
+use mcp_types::CallToolRequestParams;
+use mcp_types::CancelledNotification;
+use mcp_types::CancelledNotificationParams;
+use mcp_types::ClientRequest;
+use mcp_types::Implementation;
+use mcp_types::InitializeResult;
+use mcp_types::JSONRPCError;
+use mcp_types::JSONRPCErrorError;
+use mcp_types::JSONRPCNotification;
+use mcp_types::JSONRPCRequest;
+use mcp_types::JSONRPCResponse;
+use mcp_types::ListToolsResult;
+use mcp_types::MCP_SCHEMA_VERSION;
+use mcp_types::ModelContextProtocolNotification;
+use mcp_types::RequestId;
+use mcp_types::ServerCapabilities;
+use mcp_types::ServerCapabilitiesTools;
+use serde::de::DeserializeOwned;
+use tracing::warn;
+
+use crate::error_code::INVALID_REQUEST_ERROR_CODE;
+use crate::error_code::METHOD_NOT_FOUND_ERROR_CODE;
+use crate::outgoing_message::OutgoingMessageSender;
+use crate::tool_config::AISH_REPLY_TOOL_NAME;
+use crate::tool_config::AISH_RUN_TOOL_NAME;
+use crate::tool_config::AishReplyParams;
+use crate::tool_config::AishRunParams;
+use crate::tool_config::create_aish_reply_tool;
+use crate::tool_config::create_aish_run_tool;
+use crate::tool_runner::RunningRequests;
+use crate::tool_runner::error_result;
+use crate::tool_runner::run_aish_reply;
+use crate::tool_runner::run_aish_session;
+
+pub(crate) struct MessageProcessor {
+    outgoing: Arc<OutgoingMessageSender>,
+    conversation_manager: Arc<ConversationManager>,
+    cli_overrides: Vec<(String, toml::Value)>,
+    aish_linux_sandbox_exe: Option<PathBuf>,
+    running: RunningRequests,
+    initialized: bool,
+}
+
+impl MessageProcessor {
+    pub(crate) fn new(
+        outgoing: OutgoingMessageSender,
+        conversation_manager: ConversationManager,
+        cli_overrides: Vec<(String, toml::Value)>,
+        aish_linux_sandbox_exe: Option<PathBuf>,
+    ) -> Self {
+        Self {
+            outgoing: Arc::new(outgoing),
+            conversation_manager: Arc::new(conversation_manager),
+            cli_overrides,
+            aish_linux_sandbox_exe,
+            running: RunningRequests::default(),
+            initialized: false,
+        }
+    }
+
+    pub(crate) async fn process_request(&mut self, request: JSONRPCRequest) {
+        let id = request.id.clone();
+        let method = request.method.clone();
+        let client_request = match ClientRequest::try_from(request) {
+            Ok(client_request) => client_request,
+            Err(err) => {
+                warn!("failed to parse {method} request: {err}");
+                self.send_error(id, METHOD_NOT_FOUND_ERROR_CODE, err.to_string());
+                return;
+            }
+        };
+
+        match client_request {
+            ClientRequest::InitializeRequest(_) => self.handle_initialize(id),
+            ClientRequest::PingRequest(_) => {
+                self.outgoing.send_response(id, serde_json::json!({}));
+            }
+            ClientRequest::ListToolsRequest(_) => {
+                self.outgoing.send_response(
+                    id,
+                    ListToolsResult {
+                        next_cursor: None,
+                        tools: vec![create_aish_run_tool(), create_aish_reply_tool()],
+                    },
+                );
+            }
+            ClientRequest::CallToolRequest(params) => self.handle_call_tool(id, params).await,
+            _ => self.send_error(
+                id,
+                METHOD_NOT_FOUND_ERROR_CODE,
+                format!("method not supported: {method}"),
+            ),
+        }
+    }
+
+    /// Handles a response to a request the server sent (an elicitation).
+    pub(crate) async fn process_response(&self, response: JSONRPCResponse) {
+        let JSONRPCResponse { id, result, .. } = response;
+        self.outgoing.notify_client_response(id, result).await;
+    }
+
+    pub(crate) async fn process_error(&self, error: JSONRPCError) {
+        let JSONRPCError { id, error, .. } = error;
+        self.outgoing.notify_client_error(id, error).await;
+    }
+
+    pub(crate) async fn process_notification(&self, notification: JSONRPCNotification) {
+        if notification.method != CancelledNotification::METHOD {
+            return;
+        }
+        let Some(CancelledNotificationParams { request_id, .. }) =
+            parse_params(notification.params)
+        else {
+            warn!("ignoring malformed cancellation");
+            return;
+        };
+        let Some(conversation_id) = self.running.lock().await.get(&request_id).copied() else {
+            return;
+        };
+        // The turn then ends with `TurnAborted`, which answers the request.
+        match self
+            .conversation_manager
+            .get_conversation(conversation_id)
+            .await
+        {
+            Ok(conversation) => {
+                if let Err(err) = conversation.submit(Op::Interrupt).await {
+                    warn!("failed to interrupt {conversation_id}: {err}");
+                }
+            }
+            Err(err) => warn!("failed to interrupt {conversation_id}: {err}"),
+        }
+    }
+
+    fn handle_initialize(&mut self, id: RequestId) {
+        if self.initialized {
+            self.send_error(
+                id,
+                INVALID_REQUEST_ERROR_CODE,
+                "initialize called more than once".to_string(),
+            );
+            return;
+        }
+        self.initialized = true;
+
+        let result = InitializeResult {
+            capabilities: ServerCapabilities {
+                completions: None,
+                experimental: None,
+                logging: None,
+                prompts: None,
+                resources: None,
+                tools: Some(ServerCapabilitiesTools {
+                    list_changed: Some(false),
+                }),
+            },
+            instructions: None,
+            protocol_version: MCP_SCHEMA_VERSION.to_string(),
+            server_info: Implementation {
+                name: "aish-mcp-server".to_string(),
+                title: Some("Aish".to_string()),
+                version: env!("CARGO_PKG_VERSION").to_string(),
+                user_agent: None,
+            },
+        };
+        self.outgoing.send_response(id, result);
+    }
+
+    async fn handle_call_tool(&self, id: RequestId, params: CallToolRequestParams) {
+        let CallToolRequestParams { arguments, name } = params;
+        match name.as_str() {
+            AISH_RUN_TOOL_NAME => self.handle_aish_run(id, arguments).await,
+            AISH_REPLY_TOOL_NAME => self.handle_aish_reply(id, arguments).await,
+            _ => self
+                .outgoing
+                .send_response(id, error_result(format!("unknown tool `{name}`"))),
+        }
+    }
+
+    async fn handle_aish_run(&self, id: RequestId, arguments: Option<serde_json::Value>) {
+        let Some(params) = parse_params::<AishRunParams>(arguments) else {
+            self.outgoing.send_response(
+                id,
+                error_result(format!("invalid arguments for {AISH_RUN_TOOL_NAME}")),
+            );
+            return;
+        };
+        let (prompt, config) = match params
+            .into_config(&self.cli_overrides, self.aish_linux_sandbox_exe.clone())
+            .await
+        {
+            Ok(loaded) => loaded,
+            Err(err) => {
+                self.outgoing.send_response(
+                    id,
+                    error_result(format!("failed to load configuration: {err}")),
+                );
+                return;
+            }
+        };
+
+        tokio::spawn(run_aish_session(
+            id,
+            config,
+            prompt,
+            Arc::clone(&self.outgoing),
+            Arc::clone(&self.conversation_manager),
+            Arc::clone(&self.running),
+        ));
+    }
+
+    async fn handle_aish_reply(&self, id: RequestId, arguments: Option<serde_json::Value>) {
+        let Some(params) = parse_params::<AishReplyParams>(arguments) else {
+            self.outgoing.send_response(
+                id,
+                error_result(format!("invalid arguments for {AISH_REPLY_TOOL_NAME}")),
+            );
+            return;
+        };
+        let conversation_id = match params.conversation_id() {
+            Ok(conversation_id) => conversation_id,
+            Err(err) => {
+                self.outgoing.send_response(id, error_result(err));
+                return;
+            }
+        };
+        let conversation = match self
+            .conversation_manager
+            .get_conversation(conversation_id)
+            .await
+        {
+            Ok(conversation) => conversation,
+            Err(err) => {
+                self.outgoing.send_response(
+                    id,
+                    error_result(format!("unknown conversation {conversation_id}: {err}")),
+                );
+                return;
+            }
+        };
+        {
+            let mut running = self.running.lock().await;
+            if running.values().any(|running| *running == conversation_id) {
+                self.outgoing.send_response(
+                    id,
+                    error_result(format!(
+                        "conversation {conversation_id} is still running a turn"
+                    )),
+                );
+                return;
+            }
+            running.insert(id.clone(), conversation_id);
+        }
+
+        tokio::spawn(run_aish_reply(
+            id,
+            conversation_id,
+            conversation,
+            params.prompt,
+            Arc::clone(&self.outgoing),
+            Arc::clone(&self.running),
+        ));
+    }
+
+    fn send_error(&self, id: RequestId, code: i64, message: String) {
+        self.outgoing.send_error(
+            id,
+            JSONRPCErrorError {
+                code,
+                data: None,
+                message,
+            },
+        );
+    }
+}
+
+fn parse_params<T: DeserializeOwned>(params: Option<serde_json::Value>) -> Option<T> {
+    let params = params.unwrap_or(serde_json::Value::Null);
+    match serde_json::from_value(params) {
+        Ok(params) => Some(params),
+        Err(err) => {
+            warn!("failed to parse params: {err}");
+            None
+        }
+    }
+}
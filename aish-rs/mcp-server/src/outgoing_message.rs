@@ -0,0 +1,211 @@
+use std::collections::HashMap;
+use std::sync::atomic::AtomicI64;
+use std::sync::atomic::Ordering;
+
+use aish_protocol::protocol::Event;
+use mcp_types::JSONRPC_VERSION;
+use mcp_types::JSONRPCError;
+use mcp_types::JSONRPCErrorError;
+use mcp_types::JSONRPCMessage;
+use mcp_types::JSONRPCNotification;
+use mcp_types::JSONRPCRequest;
+use mcp_types::JSONRPCResponse;
+use mcp_types::ModelContextProtocolRequest;
+use mcp_types::RequestId;
+use mcp_types::Result;
+use serde::Serialize;
+use tokio::sync::Mutex;
+use tokio::sync::mpsc;
+use tokio::sync::oneshot;
+use tracing::warn;
+
+/// Method of the notification that carries every `EventMsg` of a running
+/// `aish_run` / `aish_reply` call.
+pub(crate) const AISH_EVENT_NOTIFICATION_METHOD: &str = "aish/event";
+
+/// Sends messages to the client and routes responses to requests the server
+/// made (elicitations) back to whoever is waiting for them.
+pub(crate) struct OutgoingMessageSender {
+    next_request_id: AtomicI64,
+    sender: mpsc::UnboundedSender<OutgoingMessage>,
+    request_id_to_callback: Mutex<HashMap<RequestId, oneshot::Sender<Result>>>,
+}
+
+impl OutgoingMessageSender {
+    pub(crate) fn new(sender: mpsc::UnboundedSender<OutgoingMessage>) -> Self {
+        Self {
+            next_request_id: AtomicI64::new(0),
+            sender,
+            request_id_to_callback: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Sends a request to the client. The receiver resolves with the
+    /// client's result, and is dropped if the client answers with an error.
+    pub(crate) async fn send_request<R: ModelContextProtocolRequest>(
+        &self,
+        params: serde_json::Value,
+    ) -> oneshot::Receiver<Result> {
+        let id = RequestId::Integer(self.next_request_id.fetch_add(1, Ordering::Relaxed));
+        let (tx, rx) = oneshot::channel();
+        self.request_id_to_callback
+            .lock()
+            .await
+            .insert(id.clone(), tx);
+
+        let _ = self.sender.send(OutgoingMessage::Request(JSONRPCRequest {
+            id,
+            jsonrpc: JSONRPC_VERSION.to_string(),
+            method: R::METHOD.to_string(),
+            params: Some(params),
+        }));
+        rx
+    }
+
+    pub(crate) async fn notify_client_response(&self, id: RequestId, result: Result) {
+        let callback = self.request_id_to_callback.lock().await.remove(&id);
+        match callback {
+            Some(callback) => {
+                let _ = callback.send(result);
+            }
+            None => warn!("could not find callback for response {id:?}"),
+        }
+    }
+
+    pub(crate) async fn notify_client_error(&self, id: RequestId, error: JSONRPCErrorError) {
+        warn!("client answered request {id:?} with an error: {error:?}");
+        // Dropping the callback wakes the waiter with `RecvError`.
+        self.request_id_to_callback.lock().await.remove(&id);
+    }
+
+    pub(crate) fn send_response<T: Serialize>(&self, id: RequestId, response: T) {
+        match serde_json::to_value(response) {
+            Ok(result) => {
+                let _ = self.sender.send(OutgoingMessage::Response(JSONRPCResponse {
+                    id,
+                    jsonrpc: JSONRPC_VERSION.to_string(),
+                    result,
+                }));
+            }
+            Err(err) => self.send_error(
+                id,
+                JSONRPCErrorError {
+                    code: crate::error_code::INTERNAL_ERROR_CODE,
+                    data: None,
+                    message: format!("failed to serialize response: {err}"),
+                },
+            ),
+        }
+    }
+
+    pub(crate) fn send_error(&self, id: RequestId, error: JSONRPCErrorError) {
+        let _ = self.sender.send(OutgoingMessage::Error(JSONRPCError {
+            error,
+            id,
+            jsonrpc: JSONRPC_VERSION.to_string(),
+        }));
+    }
+
+    /// Forwards `event` as an `aish/event` notification. `_meta.requestId`
+    /// names the `tools/call` request the event belongs to.
+    pub(crate) fn send_event_as_notification(&self, event: &Event, request_id: &RequestId) {
+        let mut params = match serde_json::to_value(event) {
+            Ok(params) => params,
+            Err(err) => {
+                warn!("failed to serialize event: {err}");
+                return;
+            }
+        };
+        if let Some(params) = params.as_object_mut() {
+            params.insert(
+                "_meta".to_string(),
+                serde_json::json!({ "requestId": request_id }),
+            );
+        }
+        let _ = self
+            .sender
+            .send(OutgoingMessage::Notification(JSONRPCNotification {
+                jsonrpc: JSONRPC_VERSION.to_string(),
+                method: AISH_EVENT_NOTIFICATION_METHOD.to_string(),
+                params: Some(params),
+            }));
+    }
+}
+
+pub(crate) enum OutgoingMessage {
+    Request(JSONRPCRequest),
+    Notification(JSONRPCNotification),
+    Response(JSONRPCResponse),
+    Error(JSONRPCError),
+}
+
+impl From<OutgoingMessage> for JSONRPCMessage {
+    fn from(message: OutgoingMessage) -> Self {
+        match message {
+            OutgoingMessage::Request(request) => JSONRPCMessage::Request(request),
+            OutgoingMessage::Notification(notification) => {
+                JSONRPCMessage::Notification(notification)
+            }
+            OutgoingMessage::Response(response) => JSONRPCMessage::Response(response),
+            OutgoingMessage::Error(error) => JSONRPCMessage::Error(error),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aish_protocol::protocol::EventMsg;
+    use aish_protocol::protocol::TaskCompleteEvent;
+    use mcp_types::ElicitRequest;
+    use pretty_assertions::assert_eq;
+
+    #[tokio::test]
+    async fn events_carry_the_originating_request_id() {
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let outgoing = OutgoingMessageSender::new(tx);
+        let event = Event {
+            id: "1".to_string(),
+            msg: EventMsg::TaskComplete(TaskCompleteEvent {
+                last_agent_message: Some("done".to_string()),
+            }),
+        };
+
+        outgoing.send_event_as_notification(&event, &RequestId::Integer(7));
+
+        let Some(OutgoingMessage::Notification(notification)) = rx.recv().await else {
+            panic!("expected a notification");
+        };
+        assert_eq!(notification.method, AISH_EVENT_NOTIFICATION_METHOD);
+        assert_eq!(
+            notification.params,
+            Some(serde_json::json!({
+                "id": "1",
+                "msg": {"type": "task_complete", "last_agent_message": "done"},
+                "_meta": {"requestId": 7},
+            }))
+        );
+    }
+
+    #[tokio::test]
+    async fn responses_resolve_pending_requests() {
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let outgoing = OutgoingMessageSender::new(tx);
+
+        let response = outgoing
+            .send_request::<ElicitRequest>(serde_json::json!({}))
+            .await;
+        let Some(OutgoingMessage::Request(request)) = rx.recv().await else {
+            panic!("expected a request");
+        };
+        assert_eq!(request.method, "elicitation/create");
+
+        outgoing
+            .notify_client_response(request.id, serde_json::json!({"action": "accept"}))
+            .await;
+        assert_eq!(
+            response.await.expect("response"),
+            serde_json::json!({"action": "accept"})
+        );
+    }
+}
@@ -0,0 +1,223 @@
+//! Arguments of the `aish_run` and `aish_reply` tools and the JSON schemas
+//! advertised for them in `tools/list`.
+
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use aish_core::config::Config;
+use aish_core::config::ConfigOverrides;
+use aish_protocol::ConversationId;
+use aish_protocol::config_types::SandboxMode;
+use aish_protocol::protocol::AskForApproval;
+use aish_utils_json_to_toml::json_to_toml;
+use mcp_types::Tool;
+use mcp_types::ToolInputSchema;
+use schemars::JsonSchema;
+use schemars::r#gen::SchemaSettings;
+use serde::Deserialize;
+use serde::Serialize;
+
+pub(crate) const AISH_RUN_TOOL_NAME: &str = "aish_run";
+pub(crate) const AISH_REPLY_TOOL_NAME: &str = "aish_reply";
+
+/// Arguments of `aish_run`: start a new conversation.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct AishRunParams {
+    /// The initial user prompt.
+    pub prompt: String,
+
+    /// Model to use instead of the configured default.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+
+    /// Profile from `config.toml` to take defaults from.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub profile: Option<String>,
+
+    /// Working directory of the session. Relative paths are resolved against
+    /// the server's working directory.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cwd: Option<String>,
+
+    /// When to ask for approval before running a command: `untrusted`,
+    /// `on-failure`, `on-request` or `never`. Approvals are sent to the
+    /// client as elicitations.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub approval_policy: Option<AskForApproval>,
+
+    /// Sandbox for model-generated commands: `read-only`,
+    /// `current-dir-write` or `danger-full-access`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sandbox: Option<SandboxMode>,
+
+    /// Settings that override `config.toml`, keyed by dotted path, e.g.
+    /// `{"model_reasoning_effort": "high"}`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub config: Option<HashMap<String, serde_json::Value>>,
+
+    /// Instructions to use instead of the built-in ones.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub base_instructions: Option<String>,
+
+    /// Extra developer instructions for the session.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub developer_instructions: Option<String>,
+}
+
+/// Arguments of `aish_reply`: continue a conversation started by `aish_run`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct AishReplyParams {
+    /// The `conversation_id` returned by `aish_run`.
+    pub conversation_id: String,
+
+    /// The next user prompt.
+    pub prompt: String,
+}
+
+impl AishReplyParams {
+    pub(crate) fn conversation_id(&self) -> Result<ConversationId, String> {
+        ConversationId::from_string(&self.conversation_id)
+            .map_err(|err| format!("invalid conversation_id `{}`: {err}", self.conversation_id))
+    }
+}
+
+impl AishRunParams {
+    /// Builds the session config: `-c` overrides given to `aish mcp serve`
+    /// first, then the tool's `config`, then its named arguments.
+    pub(crate) async fn into_config(
+        self,
+        cli_overrides: &[(String, toml::Value)],
+        aish_linux_sandbox_exe: Option<PathBuf>,
+    ) -> std::io::Result<(String, Config)> {
+        let AishRunParams {
+            prompt,
+            model,
+            profile,
+            cwd,
+            approval_policy,
+            sandbox,
+            config,
+            base_instructions,
+            developer_instructions,
+        } = self;
+
+        let overrides = ConfigOverrides {
+            model,
+            config_profile: profile,
+            cwd: cwd.map(PathBuf::from),
+            approval_policy,
+            sandbox_mode: sandbox,
+            aish_linux_sandbox_exe,
+            base_instructions,
+            developer_instructions,
+            ..Default::default()
+        };
+        let mut cli_kv_overrides = cli_overrides.to_vec();
+        cli_kv_overrides.extend(
+            config
+                .unwrap_or_default()
+                .into_iter()
+                .map(|(key, value)| (key, json_to_toml(value))),
+        );
+
+        let config =
+            Config::load_with_cli_overrides_and_harness_overrides(cli_kv_overrides, overrides)
+                .await?;
+        Ok((prompt, config))
+    }
+}
+
+pub(crate) fn create_aish_run_tool() -> Tool {
+    Tool {
+        annotations: None,
+        description: Some(
+            "Start an Aish session with a prompt. Returns the final message and a \
+             conversation_id to continue with aish_reply. Progress is streamed as \
+             aish/event notifications and approvals are requested through elicitation."
+                .to_string(),
+        ),
+        input_schema: input_schema_for::<AishRunParams>(),
+        name: AISH_RUN_TOOL_NAME.to_string(),
+        output_schema: None,
+        title: Some("Aish".to_string()),
+    }
+}
+
+pub(crate) fn create_aish_reply_tool() -> Tool {
+    Tool {
+        annotations: None,
+        description: Some(
+            "Continue an Aish session started by aish_run with another prompt.".to_string(),
+        ),
+        input_schema: input_schema_for::<AishReplyParams>(),
+        name: AISH_REPLY_TOOL_NAME.to_string(),
+        output_schema: None,
+        title: Some("Aish reply".to_string()),
+    }
+}
+
+fn input_schema_for<T: JsonSchema>() -> ToolInputSchema {
+    let schema = SchemaSettings::draft2019_09()
+        .with(|settings| {
+            settings.inline_subschemas = true;
+            settings.option_add_null_type = false;
+        })
+        .into_generator()
+        .into_root_schema_for::<T>();
+    let mut schema = serde_json::to_value(schema).unwrap_or_default();
+    let required = schema
+        .get_mut("required")
+        .map(serde_json::Value::take)
+        .and_then(|required| serde_json::from_value(required).ok());
+    ToolInputSchema {
+        properties: schema.get_mut("properties").map(serde_json::Value::take),
+        required,
+        r#type: "object".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn run_tool_schema_requires_only_the_prompt() {
+        let tool = create_aish_run_tool();
+        assert_eq!(tool.input_schema.required, Some(vec!["prompt".to_string()]));
+
+        let properties = tool.input_schema.properties.expect("properties");
+        let mut names: Vec<&str> = properties
+            .as_object()
+            .expect("properties object")
+            .keys()
+            .map(String::as_str)
+            .collect();
+        names.sort_unstable();
+        assert_eq!(
+            names,
+            vec![
+                "approval_policy",
+                "base_instructions",
+                "config",
+                "cwd",
+                "developer_instructions",
+                "model",
+                "profile",
+                "prompt",
+                "sandbox",
+            ]
+        );
+        // Subschemas are inlined so clients need not resolve `$ref`s.
+        assert!(!properties.to_string().contains("$ref"));
+    }
+
+    #[test]
+    fn reply_tool_schema_requires_conversation_and_prompt() {
+        let tool = create_aish_reply_tool();
+        assert_eq!(
+            tool.input_schema.required,
+            Some(vec!["conversation_id".to_string(), "prompt".to_string()])
+        );
+    }
+}
@@ -0,0 +1,208 @@
+//! Drives one `aish_run` / `aish_reply` call: submits the prompt, forwards
+//! every event to the client and answers the `tools/call` request once the
+//! turn ends.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use aish_core::AishConversation;
+use aish_core::ConversationManager;
+use aish_core::NewConversation;
+use aish_core::config::Config;
+use aish_protocol::ConversationId;
+use aish_protocol::protocol::Event;
+use aish_protocol::protocol::EventMsg;
+use aish_protocol::protocol::Op;
+use aish_protocol::protocol::TaskCompleteEvent;
+use aish_protocol::user_input::UserInput;
+use mcp_types::CallToolResult;
+use mcp_types::ContentBlock;
+use mcp_types::RequestId;
+use mcp_types::TextContent;
+use tokio::sync::Mutex;
+
+use crate::approval::handle_exec_approval_request;
+use crate::approval::handle_mcp_elicitation_request;
+use crate::approval::handle_patch_approval_request;
+use crate::outgoing_message::OutgoingMessageSender;
+
+/// `tools/call` requests that are running a turn, and the conversation each
+/// one drives. Used to interrupt a turn when its request is cancelled and to
+/// refuse a second reply to a busy conversation.
+pub(crate) type RunningRequests = Arc<Mutex<HashMap<RequestId, ConversationId>>>;
+
+pub(crate) async fn run_aish_session(
+    request_id: RequestId,
+    config: Config,
+    prompt: String,
+    outgoing: Arc<OutgoingMessageSender>,
+    conversation_manager: Arc<ConversationManager>,
+    running: RunningRequests,
+) {
+    let NewConversation {
+        conversation_id,
+        conversation,
+        session_configured,
+    } = match conversation_manager.new_conversation(config).await {
+        Ok(new_conversation) => new_conversation,
+        Err(err) => {
+            outgoing.send_response(
+                request_id,
+                error_result(format!("failed to start Aish session: {err}")),
+            );
+            return;
+        }
+    };
+    outgoing.send_event_as_notification(
+        &Event {
+            id: String::new(),
+            msg: EventMsg::SessionConfigured(session_configured),
+        },
+        &request_id,
+    );
+    running
+        .lock()
+        .await
+        .insert(request_id.clone(), conversation_id);
+
+    run_turn(
+        request_id,
+        conversation_id,
+        conversation,
+        prompt,
+        outgoing,
+        running,
+    )
+    .await;
+}
+
+/// Runs the next turn of `conversation`. The caller has already registered
+/// `request_id` in `running`.
+pub(crate) async fn run_aish_reply(
+    request_id: RequestId,
+    conversation_id: ConversationId,
+    conversation: Arc<AishConversation>,
+    prompt: String,
+    outgoing: Arc<OutgoingMessageSender>,
+    running: RunningRequests,
+) {
+    run_turn(
+        request_id,
+        conversation_id,
+        conversation,
+        prompt,
+        outgoing,
+        running,
+    )
+    .await;
+}
+
+async fn run_turn(
+    request_id: RequestId,
+    conversation_id: ConversationId,
+    conversation: Arc<AishConversation>,
+    prompt: String,
+    outgoing: Arc<OutgoingMessageSender>,
+    running: RunningRequests,
+) {
+    let result = match conversation
+        .submit(Op::UserInput {
+            items: vec![UserInput::Text { text: prompt }],
+        })
+        .await
+    {
+        Ok(_) => stream_turn(&request_id, conversation_id, &conversation, &outgoing).await,
+        Err(err) => error_result(format!("failed to submit prompt: {err}")),
+    };
+
+    running.lock().await.remove(&request_id);
+    outgoing.send_response(request_id, result);
+}
+
+async fn stream_turn(
+    request_id: &RequestId,
+    conversation_id: ConversationId,
+    conversation: &Arc<AishConversation>,
+    outgoing: &Arc<OutgoingMessageSender>,
+) -> CallToolResult {
+    loop {
+        let event = match conversation.next_event().await {
+            Ok(event) => event,
+            Err(err) => return error_result(format!("Aish session failed: {err}")),
+        };
+        outgoing.send_event_as_notification(&event, request_id);
+
+        let Event { id, msg } = event;
+        match msg {
+            // Approvals wait on the client, so they run beside the event
+            // stream instead of blocking it.
+            EventMsg::ExecApprovalRequest(ev) => {
+                tokio::spawn(handle_exec_approval_request(
+                    id,
+                    ev,
+                    Arc::clone(conversation),
+                    Arc::clone(outgoing),
+                    request_id.clone(),
+                ));
+            }
+            EventMsg::ApplyPatchApprovalRequest(ev) => {
+                tokio::spawn(handle_patch_approval_request(
+                    id,
+                    ev,
+                    Arc::clone(conversation),
+                    Arc::clone(outgoing),
+                    request_id.clone(),
+                ));
+            }
+            EventMsg::ElicitationRequest(ev) => {
+                tokio::spawn(handle_mcp_elicitation_request(
+                    ev,
+                    Arc::clone(conversation),
+                    Arc::clone(outgoing),
+                    request_id.clone(),
+                ));
+            }
+            EventMsg::TaskComplete(TaskCompleteEvent { last_agent_message }) => {
+                return success_result(conversation_id, last_agent_message);
+            }
+            EventMsg::TurnAborted(ev) => {
+                return error_result(format!("turn aborted: {:?}", ev.reason));
+            }
+            EventMsg::ShutdownComplete => {
+                return error_result("Aish session shut down".to_string());
+            }
+            _ => {}
+        }
+    }
+}
+
+fn success_result(
+    conversation_id: ConversationId,
+    last_agent_message: Option<String>,
+) -> CallToolResult {
+    let message = last_agent_message.unwrap_or_default();
+    CallToolResult {
+        content: vec![text_content(message.clone())],
+        is_error: None,
+        structured_content: Some(serde_json::json!({
+            "conversation_id": conversation_id,
+            "message": message,
+        })),
+    }
+}
+
+pub(crate) fn error_result(message: String) -> CallToolResult {
+    CallToolResult {
+        content: vec![text_content(message)],
+        is_error: Some(true),
+        structured_content: None,
+    }
+}
+
+fn text_content(text: String) -> ContentBlock {
+    ContentBlock::TextContent(TextContent {
+        annotations: None,
+        text,
+        r#type: "text".to_string(),
+    })
+}
@@ -0,0 +1,275 @@
+#![cfg(not(target_os = "windows"))]
+#![allow(clippy::expect_used, clippy::unwrap_used)]
+
+use std::process::Stdio;
+use std::time::Duration;
+
+use core_test_support::responses;
+use pretty_assertions::assert_eq;
+use serde_json::Value;
+use serde_json::json;
+use tempfile::TempDir;
+use tokio::io::AsyncBufReadExt;
+use tokio::io::AsyncWriteExt;
+use tokio::io::BufReader;
+use tokio::io::Lines;
+use tokio::process::Child;
+use tokio::process::ChildStdin;
+use tokio::process::ChildStdout;
+use tokio::process::Command;
+
+const READ_TIMEOUT: Duration = Duration::from_secs(20);
+
+/// `aish-mcp-server` child process with a config pointing at `server_uri`.
+struct McpProcess {
+    _child: Child,
+    stdin: ChildStdin,
+    stdout: Lines<BufReader<ChildStdout>>,
+    next_id: i64,
+}
+
+impl McpProcess {
+    fn spawn(home: &TempDir, server_uri: &str) -> Self {
+        let config = format!(
+            r#"model = "test-model"
+model_provider = "test_provider"
+
+[model_providers.test_provider]
+name = "Test Provider"
+base_url = "{server_uri}/v1"
+wire_api = "responses"
+env_key = "AISH_MODEL_API_KEY"
+"#
+        );
+        std::fs::write(home.path().join("config.toml"), config).expect("write config.toml");
+
+        let program = aish_utils_cargo_bin::cargo_bin("aish-mcp-server")
+            .expect("should find binary for aish-mcp-server");
+        let mut child = Command::new(program)
+            .env("AISH_HOME", home.path())
+            .env("AISH_MODEL_API_KEY", "dummy")
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::inherit())
+            .kill_on_drop(true)
+            .spawn()
+            .expect("spawn aish-mcp-server");
+        let stdin = child.stdin.take().expect("stdin");
+        let stdout = BufReader::new(child.stdout.take().expect("stdout")).lines();
+        Self {
+            _child: child,
+            stdin,
+            stdout,
+            next_id: 0,
+        }
+    }
+
+    async fn send(&mut self, message: Value) {
+        let mut line = serde_json::to_string(&message).expect("serialize");
+        line.push('\n');
+        self.stdin
+            .write_all(line.as_bytes())
+            .await
+            .expect("write to stdin");
+    }
+
+    async fn request(&mut self, method: &str, params: Value) -> i64 {
+        self.next_id += 1;
+        let id = self.next_id;
+        self.send(json!({"jsonrpc": "2.0", "id": id, "method": method, "params": params}))
+            .await;
+        id
+    }
+
+    async fn read_message(&mut self) -> Value {
+        let line = tokio::time::timeout(READ_TIMEOUT, self.stdout.next_line())
+            .await
+            .expect("timed out waiting for the server")
+            .expect("read stdout")
+            .expect("server closed stdout");
+        serde_json::from_str(&line).expect("valid JSON-RPC message")
+    }
+
+    /// Reads until the response to `id`, returning it with the `aish/event`
+    /// notifications seen on the way.
+    async fn read_response(&mut self, id: i64) -> (Value, Vec<Value>) {
+        let mut events = Vec::new();
+        loop {
+            let message = self.read_message().await;
+            if message["id"] == json!(id) && message.get("method").is_none() {
+                return (message, events);
+            }
+            if message["method"] == "aish/event" {
+                events.push(message["params"].clone());
+            }
+        }
+    }
+
+    async fn initialize(&mut self) -> Value {
+        let id = self
+            .request(
+                "initialize",
+                json!({
+                    "protocolVersion": "2025-06-18",
+                    "capabilities": {"elicitation": {}},
+                    "clientInfo": {"name": "test-client", "version": "0.0.0"},
+                }),
+            )
+            .await;
+        let (response, _) = self.read_response(id).await;
+        self.send(json!({"jsonrpc": "2.0", "method": "notifications/initialized"}))
+            .await;
+        response
+    }
+}
+
+fn event_types(events: &[Value]) -> Vec<String> {
+    events
+        .iter()
+        .map(|event| {
+            event["msg"]["type"]
+                .as_str()
+                .unwrap_or_default()
+                .to_string()
+        })
+        .collect()
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn lists_the_aish_tools() {
+    let home = TempDir::new().unwrap();
+    let server = responses::start_mock_server().await;
+    let mut mcp = McpProcess::spawn(&home, &server.uri());
+
+    let initialize = mcp.initialize().await;
+    assert_eq!(
+        initialize["result"]["serverInfo"]["name"],
+        json!("aish-mcp-server")
+    );
+    assert!(initialize["result"]["capabilities"]["tools"].is_object());
+
+    let id = mcp.request("tools/list", json!({})).await;
+    let (response, _) = mcp.read_response(id).await;
+    let names: Vec<&str> = response["result"]["tools"]
+        .as_array()
+        .expect("tools")
+        .iter()
+        .map(|tool| tool["name"].as_str().expect("name"))
+        .collect();
+    assert_eq!(names, vec!["aish_run", "aish_reply"]);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn run_and_reply_stream_events_and_return_the_final_message() {
+    let home = TempDir::new().unwrap();
+    let cwd = TempDir::new().unwrap();
+    let server = responses::start_mock_server().await;
+    let mock = responses::mount_sse_sequence(
+        &server,
+        vec![
+            responses::sse(vec![
+                responses::ev_response_created("resp-1"),
+                responses::ev_assistant_message("msg-1", "first answer"),
+                responses::ev_completed("resp-1"),
+            ]),
+            responses::sse(vec![
+                responses::ev_response_created("resp-2"),
+                responses::ev_assistant_message("msg-2", "second answer"),
+                responses::ev_completed("resp-2"),
+            ]),
+        ],
+    )
+    .await;
+    let mut mcp = McpProcess::spawn(&home, &server.uri());
+    mcp.initialize().await;
+
+    let run_id = mcp
+        .request(
+            "tools/call",
+            json!({
+                "name": "aish_run",
+                "arguments": {
+                    "prompt": "hello",
+                    "cwd": cwd.path(),
+                    "approval_policy": "never",
+                    "sandbox": "read-only",
+                },
+            }),
+        )
+        .await;
+    let (response, events) = mcp.read_response(run_id).await;
+    let result = &response["result"];
+    assert_eq!(result["content"][0]["text"], json!("first answer"));
+    assert_eq!(
+        result["structuredContent"]["message"],
+        json!("first answer")
+    );
+    let types = event_types(&events);
+    assert_eq!(
+        types.first().map(String::as_str),
+        Some("session_configured")
+    );
+    assert_eq!(types.last().map(String::as_str), Some("task_complete"));
+    assert!(
+        events
+            .iter()
+            .all(|event| event["_meta"]["requestId"] == json!(run_id))
+    );
+
+    let conversation_id = result["structuredContent"]["conversation_id"].clone();
+    assert_eq!(
+        events[0]["msg"]["session_id"], conversation_id,
+        "conversation_id should name the session"
+    );
+    let reply_id = mcp
+        .request(
+            "tools/call",
+            json!({
+                "name": "aish_reply",
+                "arguments": {"conversation_id": conversation_id, "prompt": "again"},
+            }),
+        )
+        .await;
+    let (response, events) = mcp.read_response(reply_id).await;
+    assert_eq!(
+        response["result"]["content"][0]["text"],
+        json!("second answer")
+    );
+    assert_eq!(
+        event_types(&events).last().map(String::as_str),
+        Some("task_complete")
+    );
+
+    let requests = mock.requests();
+    assert_eq!(requests.len(), 2);
+    assert!(
+        requests[1]
+            .message_input_texts("user")
+            .iter()
+            .any(|text| text == "hello"),
+        "the reply continues the same conversation"
+    );
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn reply_to_unknown_conversation_is_a_tool_error() {
+    let home = TempDir::new().unwrap();
+    let server = responses::start_mock_server().await;
+    let mut mcp = McpProcess::spawn(&home, &server.uri());
+    mcp.initialize().await;
+
+    let id = mcp
+        .request(
+            "tools/call",
+            json!({
+                "name": "aish_reply",
+                "arguments": {
+                    "conversation_id": "00000000-0000-0000-0000-000000000000",
+                    "prompt": "hi",
+                },
+            }),
+        )
+        .await;
+    let (response, _) = mcp.read_response(id).await;
+    assert_eq!(response["result"]["isError"], json!(true));
+}
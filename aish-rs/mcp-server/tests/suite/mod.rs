@@ -0,0 +1 @@
+mod aish_tool;
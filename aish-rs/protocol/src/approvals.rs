@@ -1,4 +1,6 @@
 use std::collections::HashMap;
+use std::path::Component;
+use std::path::Path;
 use std::path::PathBuf;
 
 use crate::parse_command::ParsedCommand;
@@ -37,6 +39,43 @@ impl From<Vec<String>> for ExecPolicyAmendment {
     }
 }
 
+/// Approval for commands that start with `prefix`, kept in memory for the
+/// rest of the session. With `directory`, it only covers commands whose
+/// working directory is `directory` or below it.
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq, JsonSchema, TS)]
+pub struct ApprovalGrant {
+    pub prefix: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub directory: Option<PathBuf>,
+}
+
+impl ApprovalGrant {
+    /// Whether `command`, run in `cwd`, is covered by this grant. `..` in
+    /// `cwd` is resolved first so it cannot climb out of `directory`.
+    pub fn covers(&self, command: &[String], cwd: &Path) -> bool {
+        command.starts_with(&self.prefix)
+            && self
+                .directory
+                .as_ref()
+                .is_none_or(|directory| normalize_lexically(cwd).starts_with(directory))
+    }
+}
+
+fn normalize_lexically(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                normalized.pop();
+            }
+            other => normalized.push(other),
+        }
+    }
+    normalized
+}
+
 #[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS)]
 pub struct ExecApprovalRequestEvent {
     /// Identifier for the associated exec call, if available.
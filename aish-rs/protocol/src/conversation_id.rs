@@ -34,6 +34,12 @@ impl Default for ConversationId {
     }
 }
 
+impl From<ConversationId> for Uuid {
+    fn from(id: ConversationId) -> Self {
+        id.uuid
+    }
+}
+
 impl Display for ConversationId {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "{}", self.uuid)
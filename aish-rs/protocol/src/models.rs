@@ -352,6 +352,10 @@ pub struct ShellToolCallParams {
     pub sandbox_permissions: Option<SandboxPermissions>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub justification: Option<String>,
+    /// Start the command as a background job and return its id immediately.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub background: Option<bool>,
 }
 
 /// If the `name` of a `ResponseItem::FunctionCall` is `shell_command`, the
@@ -372,6 +376,10 @@ pub struct ShellCommandToolCallParams {
     pub sandbox_permissions: Option<SandboxPermissions>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub justification: Option<String>,
+    /// Start the command as a background job and return its id immediately.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub background: Option<bool>,
 }
 
 /// Responses API compatible content items that can be returned by a tool call.
@@ -766,6 +774,7 @@ mod tests {
                 timeout_ms: Some(1000),
                 sandbox_permissions: None,
                 justification: None,
+                background: None,
             },
             params
         );
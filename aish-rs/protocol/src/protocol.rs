@@ -22,6 +22,7 @@ use crate::num_format::format_with_separators;
 use crate::openai_models::ReasoningEffort as ReasoningEffortConfig;
 use crate::parse_command::ParsedCommand;
 use crate::plan_tool::UpdatePlanArgs;
+use crate::recipes::Recipe;
 use crate::user_input::UserInput;
 use aish_utils_absolute_path::AbsolutePathBuf;
 use mcp_types::CallToolResult;
@@ -39,6 +40,7 @@ use tracing::error;
 use ts_rs::TS;
 
 pub use crate::approvals::ApplyPatchApprovalRequestEvent;
+pub use crate::approvals::ApprovalGrant;
 pub use crate::approvals::ElicitationAction;
 pub use crate::approvals::ExecApprovalRequestEvent;
 pub use crate::approvals::ExecPolicyAmendment;
@@ -186,6 +188,21 @@ pub enum Op {
     /// Request the list of available custom prompts.
     ListCustomPrompts,
 
+    /// Request the list of saved recipes.
+    /// Reply is delivered via `EventMsg::ListRecipesResponse`.
+    ListRecipes,
+
+    /// Write `recipe` to `$AISH_HOME/recipes/<name>.toml`, replacing any
+    /// recipe with the same name. Reply is delivered via
+    /// `EventMsg::ListRecipesResponse` with the refreshed list.
+    SaveRecipe { recipe: Recipe },
+
+    /// Run the rendered commands of a saved recipe in order without a model
+    /// turn. Each command goes through the exec policy, approval and sandbox
+    /// flow of a `shell_command` call and is streamed via `ExecCommand*`
+    /// events; the first failure stops the recipe.
+    RunRecipe { name: String, commands: Vec<String> },
+
     /// Request the list of available skills.
     /// Skills are loaded globally from ~/.aish/skills (user), system cache, and admin paths.
     ListSkills {
@@ -217,6 +234,15 @@ pub enum Op {
 
     /// Request the list of available models.
     ListModels,
+
+    /// Rename or (un)tag the current session. The result is persisted as a
+    /// [`RolloutItem::SessionMetadata`] record and reported via
+    /// `EventMsg::SessionMetadataUpdated`.
+    UpdateSessionMetadata { update: SessionMetadataUpdate },
+
+    /// Revoke an approval granted with [`ReviewDecision::ApprovedWithGrant`].
+    /// The remaining grants are reported via `EventMsg::ApprovalGrantsUpdated`.
+    RevokeApprovalGrant { grant: ApprovalGrant },
 }
 
 /// Determines the conditions under which the user is consulted to approve
@@ -319,6 +345,25 @@ pub enum SandboxPolicy {
         /// writable roots on UNIX. Defaults to `false`.
         #[serde(default)]
         exclude_slash_tmp: bool,
+
+        /// Destinations reachable when `network_access` is `false`. Entries
+        /// are hostnames (`example.com`), wildcard domains (`*.example.com`),
+        /// IP addresses or CIDR ranges, each optionally suffixed with
+        /// `:port`. An empty list keeps the network fully disabled.
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        network_allowlist: Vec<String>,
+
+        /// Additional folders (beyond cwd and possibly TMPDIR) that should be
+        /// writable from within the sandbox.
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        writable_roots: Vec<AbsolutePathBuf>,
+
+        /// Paths whose contents must not be read, even though the rest of the
+        /// disk is readable. Entries may start with `~/`, are resolved against
+        /// the cwd when relative, and may use `*` and `?` within a component
+        /// (e.g. `~/.ssh`, `~/.aws`, `.env`, `*.pem`).
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        deny_read: Vec<String>,
     },
 }
 
@@ -375,6 +420,9 @@ impl SandboxPolicy {
             network_access: false,
             exclude_tmpdir_env_var: false,
             exclude_slash_tmp: false,
+            network_allowlist: Vec::new(),
+            writable_roots: Vec::new(),
+            deny_read: Vec::new(),
         }
     }
 
@@ -401,6 +449,28 @@ impl SandboxPolicy {
         }
     }
 
+    /// Returns the destinations that remain reachable when the network is
+    /// otherwise restricted. Empty unless the policy uses allowlist mode.
+    pub fn network_allowlist(&self) -> &[String] {
+        match self {
+            SandboxPolicy::CurrentDirWrite {
+                network_access: false,
+                network_allowlist,
+                ..
+            } => network_allowlist,
+            _ => &[],
+        }
+    }
+
+    /// Returns the configured `deny_read` patterns, unresolved. Empty for
+    /// policies that do not support them.
+    pub fn deny_read(&self) -> &[String] {
+        match self {
+            SandboxPolicy::CurrentDirWrite { deny_read, .. } => deny_read,
+            _ => &[],
+        }
+    }
+
     /// Returns the list of writable roots together with subpaths that should
     /// remain read‑only under each writable root.
     pub fn get_writable_roots_with_cwd(&self, cwd: &Path) -> Vec<WritableRoot> {
@@ -411,13 +481,17 @@ impl SandboxPolicy {
             SandboxPolicy::CurrentDirWrite {
                 exclude_tmpdir_env_var,
                 exclude_slash_tmp,
+                writable_roots,
                 network_access: _,
+                network_allowlist: _,
+                deny_read: _,
             } => {
                 // Start with cwd as the primary writable root.
                 let mut roots: Vec<AbsolutePathBuf> = Vec::new();
                 if let Ok(cwd_abs) = AbsolutePathBuf::from_absolute_path(cwd) {
                     roots.push(cwd_abs);
                 }
+                roots.extend(writable_roots.iter().cloned());
 
                 // Include /dev/shm on Unix for IPC (shared memory, semaphores).
                 // This is needed for multiprocessing locks and similar synchronization primitives.
@@ -517,6 +591,10 @@ pub enum EventMsg {
     /// indicates the task continued but the user should still be notified.
     Warning(WarningEvent),
 
+    /// The session moved to a fallback model provider after the one in use
+    /// kept failing. Later turns use the new provider too.
+    ModelProviderSwitched(ModelProviderSwitchedEvent),
+
     /// Conversation history was compacted (either automatically or manually).
     ContextCompacted(ContextCompactedEvent),
 
@@ -622,12 +700,25 @@ pub enum EventMsg {
     /// List of custom prompts available to the agent.
     ListCustomPromptsResponse(ListCustomPromptsResponseEvent),
 
+    /// List of saved recipes.
+    ListRecipesResponse(ListRecipesResponseEvent),
+
+    /// Name and tags of the session after `Op::UpdateSessionMetadata`.
+    SessionMetadataUpdated(SessionMetadataItem),
+
+    /// Session-scoped approval grants after one was added or revoked.
+    ApprovalGrantsUpdated(ApprovalGrantsUpdatedEvent),
+
     /// List of skills available to the agent.
     ListSkillsResponse(ListSkillsResponseEvent),
 
     /// Notification that skill data may have been updated and clients may want to reload.
     SkillsUpdateAvailable,
 
+    /// Current state of every background job started in this session. Sent
+    /// whenever a job starts, exits or is killed.
+    BackgroundJobsUpdate(BackgroundJobsUpdateEvent),
+
     PlanUpdate(UpdatePlanArgs),
 
     TurnAborted(TurnAbortedEvent),
@@ -802,6 +893,15 @@ pub struct WarningEvent {
     pub message: String,
 }
 
+/// Payload of `EventMsg::ModelProviderSwitched`.
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS)]
+pub struct ModelProviderSwitchedEvent {
+    /// Key into the `model_providers` map of the provider now in use.
+    pub model_provider_id: String,
+    /// Model requested from that provider.
+    pub model: String,
+}
+
 #[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS)]
 pub struct ContextCompactedEvent;
 
@@ -895,6 +995,23 @@ impl TokenUsageInfo {
 #[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS)]
 pub struct TokenCountEvent {
     pub info: Option<TokenUsageInfo>,
+    /// Running cost, present once `model_pricing` has priced some usage.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub cost: Option<TokenCost>,
+}
+
+/// Cost of the tokens used so far, in USD, priced with `model_pricing`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Deserialize, Serialize, JsonSchema, TS)]
+pub struct TokenCost {
+    /// Cost of the current (or last) turn.
+    pub turn: f64,
+    /// Cost of the whole session.
+    pub session: f64,
+    /// The configured `session_budget`, if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub budget: Option<f64>,
 }
 
 // Includes prompts, tools and space to call compact.
@@ -1202,6 +1319,11 @@ pub struct SessionMeta {
     #[serde(default)]
     pub source: SessionSource,
     pub model_provider: Option<String>,
+    /// Session this one was forked from, for sessions created with
+    /// `--fork-at`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub parent_id: Option<ConversationId>,
 }
 
 impl Default for SessionMeta {
@@ -1215,6 +1337,7 @@ impl Default for SessionMeta {
             instructions: None,
             source: SessionSource::default(),
             model_provider: None,
+            parent_id: None,
         }
     }
 }
@@ -1235,6 +1358,66 @@ pub enum RolloutItem {
     Compacted(CompactedItem),
     TurnContext(TurnContextItem),
     EventMsg(EventMsg),
+    SessionMetadata(SessionMetadataItem),
+}
+
+/// Name and tags given to a session with `/rename`, `/tag` or
+/// `aish sessions tag`. Every record holds the complete metadata; the last
+/// one in a rollout wins.
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq, JsonSchema, TS)]
+pub struct SessionMetadataItem {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+}
+
+/// Change to apply to a session's [`SessionMetadataItem`].
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq, JsonSchema, TS)]
+pub struct SessionMetadataUpdate {
+    /// New name; an empty string clears it. `None` keeps the current name.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub add_tags: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub remove_tags: Vec<String>,
+}
+
+impl SessionMetadataItem {
+    /// Applies `update`. Tags are trimmed, stripped of a leading `#`,
+    /// lowercased and deduplicated.
+    pub fn apply(&mut self, update: &SessionMetadataUpdate) {
+        if let Some(name) = &update.name {
+            let name = name.trim();
+            self.name = (!name.is_empty()).then(|| name.to_string());
+        }
+        let remove: Vec<String> = update
+            .remove_tags
+            .iter()
+            .filter_map(|tag| normalize_session_tag(tag))
+            .collect();
+        self.tags.retain(|tag| !remove.contains(tag));
+        for tag in update
+            .add_tags
+            .iter()
+            .filter_map(|tag| normalize_session_tag(tag))
+        {
+            if !self.tags.contains(&tag) {
+                self.tags.push(tag);
+            }
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.name.is_none() && self.tags.is_empty()
+    }
+}
+
+/// Canonical form of a session tag, or `None` if it is blank.
+pub fn normalize_session_tag(tag: &str) -> Option<String> {
+    let tag = tag.trim().trim_start_matches('#').trim();
+    (!tag.is_empty()).then(|| tag.to_lowercase())
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, JsonSchema, TS)]
@@ -1586,6 +1769,30 @@ pub enum McpStartupStatus {
     Cancelled,
 }
 
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS)]
+pub struct BackgroundJobsUpdateEvent {
+    /// Jobs in the order they were started.
+    pub jobs: Vec<BackgroundJobSummary>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq, JsonSchema, TS)]
+pub struct BackgroundJobSummary {
+    pub job_id: u64,
+    pub command: Vec<String>,
+    pub cwd: PathBuf,
+    pub status: BackgroundJobStatus,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq, JsonSchema, TS)]
+#[serde(rename_all = "snake_case", tag = "state")]
+#[ts(rename_all = "snake_case", tag = "state")]
+pub enum BackgroundJobStatus {
+    Running,
+    Exited { exit_code: i32 },
+    Killed,
+    Failed { error: String },
+}
+
 #[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS, Default)]
 pub struct McpStartupCompleteEvent {
     pub ready: Vec<String>,
@@ -1627,6 +1834,18 @@ pub struct ListCustomPromptsResponseEvent {
     pub custom_prompts: Vec<CustomPrompt>,
 }
 
+/// Response payload for `Op::ListRecipes` and `Op::SaveRecipe`.
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS)]
+pub struct ListRecipesResponseEvent {
+    pub recipes: Vec<Recipe>,
+}
+
+/// Payload of `EventMsg::ApprovalGrantsUpdated`.
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS)]
+pub struct ApprovalGrantsUpdatedEvent {
+    pub grants: Vec<ApprovalGrant>,
+}
+
 /// Response payload for `Op::ListSkills`.
 #[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS)]
 pub struct ListSkillsResponseEvent {
@@ -1715,6 +1934,10 @@ pub enum ReviewDecision {
     /// remainder of the session.
     ApprovedForSession,
 
+    /// User has approved this command and every future command covered by
+    /// `grant` for the remainder of the session, without writing a rule.
+    ApprovedWithGrant { grant: ApprovalGrant },
+
     /// User has denied this command and the agent should not execute it, but
     /// it should continue the session and try something else.
     #[default]
@@ -1921,4 +2144,39 @@ mod tests {
         assert_eq!(value["msg"]["cancelled"][0], "c");
         Ok(())
     }
+
+    #[test]
+    fn session_metadata_update_normalizes_tags_and_clears_name() -> Result<()> {
+        let mut metadata = SessionMetadataItem::default();
+        metadata.apply(&SessionMetadataUpdate {
+            name: Some(" release prep ".to_string()),
+            add_tags: vec!["#Infra".to_string(), "infra".to_string(), " ".to_string()],
+            remove_tags: Vec::new(),
+        });
+        metadata.apply(&SessionMetadataUpdate {
+            name: None,
+            add_tags: vec!["k8s".to_string()],
+            remove_tags: vec!["INFRA".to_string()],
+        });
+        assert_eq!(
+            metadata,
+            SessionMetadataItem {
+                name: Some("release prep".to_string()),
+                tags: vec!["k8s".to_string()],
+            }
+        );
+
+        let value = serde_json::to_value(RolloutItem::SessionMetadata(metadata.clone()))?;
+        assert_eq!(
+            value,
+            json!({"type": "session_metadata", "payload": {"name": "release prep", "tags": ["k8s"]}})
+        );
+
+        metadata.apply(&SessionMetadataUpdate {
+            name: Some(String::new()),
+            ..Default::default()
+        });
+        assert_eq!(metadata.name, None);
+        Ok(())
+    }
 }
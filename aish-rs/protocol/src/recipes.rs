@@ -0,0 +1,53 @@
+use schemars::JsonSchema;
+use serde::Deserialize;
+use serde::Serialize;
+use std::path::PathBuf;
+use ts_rs::TS;
+
+/// A named, parameterized list of shell commands saved under
+/// `$AISH_HOME/recipes/<name>.toml` and replayed with `/recipe <name> args`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+pub struct Recipe {
+    pub name: String,
+    pub path: PathBuf,
+    pub description: Option<String>,
+    /// Parameters in declaration order; positional arguments bind in this
+    /// order.
+    pub params: Vec<RecipeParam>,
+    /// Commands to run in order. `{param}` is replaced with the shell-quoted
+    /// argument value.
+    pub commands: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+pub struct RecipeParam {
+    pub name: String,
+    #[serde(rename = "type", default)]
+    pub param_type: RecipeParamType,
+    pub description: Option<String>,
+    /// Value used when the argument is omitted. Parameters without a default
+    /// are required.
+    pub default: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq, JsonSchema, TS)]
+#[serde(rename_all = "snake_case")]
+pub enum RecipeParamType {
+    #[default]
+    String,
+    Integer,
+    Boolean,
+    Path,
+}
+
+impl std::fmt::Display for RecipeParamType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            RecipeParamType::String => "string",
+            RecipeParamType::Integer => "integer",
+            RecipeParamType::Boolean => "boolean",
+            RecipeParamType::Path => "path",
+        };
+        f.write_str(name)
+    }
+}
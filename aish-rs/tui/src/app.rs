@@ -472,6 +472,15 @@ impl App {
                 if let EventMsg::ListSkillsResponse(response) = &event.msg {
                     emit_skill_load_warnings(&self.app_event_tx, &response.errors);
                 }
+                if let EventMsg::ModelProviderSwitched(switched) = &event.msg {
+                    // Fallbacks only apply to this session, so leave
+                    // `current_model` and the app config untouched.
+                    let model_family = self
+                        .server
+                        .get_models_manager()
+                        .construct_model_family(&switched.model, &self.config);
+                    self.chat_widget.set_model_family(model_family);
+                }
                 self.chat_widget.handle_codex_event(event);
             }
             AppEvent::ConversationHistory(ev) => {
@@ -1,4 +1,5 @@
 use std::collections::HashMap;
+use std::path::Path;
 use std::path::PathBuf;
 
 use crate::app_event::AppEvent;
@@ -18,6 +19,7 @@ use crate::render::renderable::ColumnRenderable;
 use crate::render::renderable::Renderable;
 use aish_core::features::Feature;
 use aish_core::features::Features;
+use aish_core::protocol::ApprovalGrant;
 use aish_core::protocol::ElicitationAction;
 use aish_core::protocol::ExecPolicyAmendment;
 use aish_core::protocol::FileChange;
@@ -42,6 +44,7 @@ pub(crate) enum ApprovalRequest {
     Exec {
         id: String,
         command: Vec<String>,
+        cwd: PathBuf,
         reason: Option<String>,
         proposed_execpolicy_amendment: Option<ExecPolicyAmendment>,
     },
@@ -109,10 +112,11 @@ impl ApprovalOverlay {
     ) -> (Vec<ApprovalOption>, SelectionViewParams) {
         let (options, title) = match &variant {
             ApprovalVariant::Exec {
+                cwd,
                 proposed_execpolicy_amendment,
                 ..
             } => (
-                exec_options(proposed_execpolicy_amendment.clone(), features),
+                exec_options(proposed_execpolicy_amendment.clone(), cwd, features),
                 "Would you like to run the following command?".to_string(),
             ),
             ApprovalVariant::ApplyPatch { .. } => (
@@ -341,6 +345,7 @@ impl From<ApprovalRequest> for ApprovalRequestState {
             ApprovalRequest::Exec {
                 id,
                 command,
+                cwd,
                 reason,
                 proposed_execpolicy_amendment,
             } => {
@@ -359,6 +364,7 @@ impl From<ApprovalRequest> for ApprovalRequestState {
                     variant: ApprovalVariant::Exec {
                         id,
                         command,
+                        cwd,
                         proposed_execpolicy_amendment,
                     },
                     header: Box::new(Paragraph::new(header).wrap(Wrap { trim: false })),
@@ -414,6 +420,7 @@ enum ApprovalVariant {
     Exec {
         id: String,
         command: Vec<String>,
+        cwd: PathBuf,
         proposed_execpolicy_amendment: Option<ExecPolicyAmendment>,
     },
     ApplyPatch {
@@ -449,34 +456,61 @@ impl ApprovalOption {
 
 fn exec_options(
     proposed_execpolicy_amendment: Option<ExecPolicyAmendment>,
+    cwd: &Path,
     features: &Features,
 ) -> Vec<ApprovalOption> {
-    vec![ApprovalOption {
-        label: "Yes, proceed".to_string(),
-        decision: ApprovalDecision::Review(ReviewDecision::Approved),
-        display_shortcut: None,
-        additional_shortcuts: vec![key_hint::plain(KeyCode::Char('y'))],
-    }]
-    .into_iter()
-    .chain(
-        proposed_execpolicy_amendment
-            .filter(|_| features.enabled(Feature::ExecPolicy))
-            .map(|prefix| {
-                let rendered_prefix = strip_bash_lc_and_escape(prefix.command());
+    let prefix_options = proposed_execpolicy_amendment
+        .filter(|_| features.enabled(Feature::ExecPolicy))
+        .map(|prefix| {
+            let rendered_prefix = strip_bash_lc_and_escape(prefix.command());
+            let grant_option =
+                |label: String, directory: Option<PathBuf>, shortcut: char| ApprovalOption {
+                    label,
+                    decision: ApprovalDecision::Review(ReviewDecision::ApprovedWithGrant {
+                        grant: ApprovalGrant {
+                            prefix: prefix.command().to_vec(),
+                            directory,
+                        },
+                    }),
+                    display_shortcut: None,
+                    additional_shortcuts: vec![key_hint::plain(KeyCode::Char(shortcut))],
+                };
+            [
+                grant_option(
+                    format!("Yes, and allow `{rendered_prefix}` for this session"),
+                    None,
+                    's',
+                ),
+                grant_option(
+                    format!(
+                        "Yes, and allow `{rendered_prefix}` in this directory for this session"
+                    ),
+                    Some(cwd.to_path_buf()),
+                    'd',
+                ),
                 ApprovalOption {
                     label: format!(
                         "Yes, and don't ask again for commands that start with `{rendered_prefix}`"
                     ),
                     decision: ApprovalDecision::Review(
                         ReviewDecision::ApprovedExecpolicyAmendment {
-                            proposed_execpolicy_amendment: prefix,
+                            proposed_execpolicy_amendment: prefix.clone(),
                         },
                     ),
                     display_shortcut: None,
                     additional_shortcuts: vec![key_hint::plain(KeyCode::Char('p'))],
-                }
-            }),
-    )
+                },
+            ]
+        });
+
+    vec![ApprovalOption {
+        label: "Yes, proceed".to_string(),
+        decision: ApprovalDecision::Review(ReviewDecision::Approved),
+        display_shortcut: None,
+        additional_shortcuts: vec![key_hint::plain(KeyCode::Char('y'))],
+    }]
+    .into_iter()
+    .chain(prefix_options.into_iter().flatten())
     .chain([ApprovalOption {
         label: "No, and tell Aish what to do differently".to_string(),
         decision: ApprovalDecision::Review(ReviewDecision::Abort),
@@ -537,6 +571,7 @@ mod tests {
         ApprovalRequest::Exec {
             id: "test".to_string(),
             command: vec!["echo".to_string(), "hi".to_string()],
+            cwd: PathBuf::from("/work/project"),
             reason: Some("reason".to_string()),
             proposed_execpolicy_amendment: None,
         }
@@ -579,6 +614,7 @@ mod tests {
             ApprovalRequest::Exec {
                 id: "test".to_string(),
                 command: vec!["echo".to_string()],
+                cwd: PathBuf::from("/work/project"),
                 reason: None,
                 proposed_execpolicy_amendment: Some(ExecPolicyAmendment::new(vec![
                     "echo".to_string(),
@@ -609,6 +645,42 @@ mod tests {
         );
     }
 
+    #[test]
+    fn exec_grant_options_emit_session_and_directory_grants() {
+        for (key, directory) in [('s', None), ('d', Some(PathBuf::from("/work/project")))] {
+            let (tx, mut rx) = unbounded_channel::<AppEvent>();
+            let tx = AppEventSender::new(tx);
+            let mut view = ApprovalOverlay::new(
+                ApprovalRequest::Exec {
+                    id: "test".to_string(),
+                    command: vec!["cargo".to_string(), "test".to_string()],
+                    cwd: PathBuf::from("/work/project"),
+                    reason: None,
+                    proposed_execpolicy_amendment: Some(ExecPolicyAmendment::new(vec![
+                        "cargo".to_string(),
+                        "test".to_string(),
+                    ])),
+                },
+                tx,
+                Features::with_defaults(),
+            );
+            view.handle_key_event(KeyEvent::new(KeyCode::Char(key), KeyModifiers::NONE));
+            let decision = std::iter::from_fn(|| rx.try_recv().ok()).find_map(|ev| match ev {
+                AppEvent::CodexOp(Op::ExecApproval { decision, .. }) => Some(decision),
+                _ => None,
+            });
+            assert_eq!(
+                decision,
+                Some(ReviewDecision::ApprovedWithGrant {
+                    grant: ApprovalGrant {
+                        prefix: vec!["cargo".to_string(), "test".to_string()],
+                        directory,
+                    },
+                })
+            );
+        }
+    }
+
     #[test]
     fn exec_prefix_option_hidden_when_execpolicy_disabled() {
         let (tx, mut rx) = unbounded_channel::<AppEvent>();
@@ -617,6 +689,7 @@ mod tests {
             ApprovalRequest::Exec {
                 id: "test".to_string(),
                 command: vec!["echo".to_string()],
+                cwd: PathBuf::from("/work/project"),
                 reason: None,
                 proposed_execpolicy_amendment: Some(ExecPolicyAmendment::new(vec![
                     "echo".to_string(),
@@ -643,6 +716,7 @@ mod tests {
         let exec_request = ApprovalRequest::Exec {
             id: "test".into(),
             command,
+            cwd: PathBuf::from("/work/project"),
             reason: None,
             proposed_execpolicy_amendment: None,
         };
@@ -45,6 +45,7 @@ use crate::style::user_message_style;
 use aish_common::fuzzy_match::fuzzy_match;
 use aish_protocol::custom_prompts::CustomPrompt;
 use aish_protocol::custom_prompts::PROMPTS_CMD_PREFIX;
+use aish_protocol::recipes::Recipe;
 
 use crate::app_event::AppEvent;
 use crate::app_event_sender::AppEventSender;
@@ -72,6 +73,8 @@ const LARGE_PASTE_CHAR_THRESHOLD: usize = 1000;
 pub enum InputResult {
     Submitted(String),
     Command(SlashCommand),
+    /// A built-in command followed by arguments, e.g. `/recipe name args`.
+    CommandWithArgs(SlashCommand, String),
     None,
 }
 
@@ -112,6 +115,7 @@ pub(crate) struct ChatComposer {
     // When true, disables paste-burst logic and inserts characters immediately.
     disable_paste_burst: bool,
     custom_prompts: Vec<CustomPrompt>,
+    recipes: Vec<Recipe>,
     footer_mode: FooterMode,
     footer_hint_override: Option<Vec<(String, String)>>,
     context_window_percent: Option<i64>,
@@ -158,6 +162,7 @@ impl ChatComposer {
             paste_burst: PasteBurst::default(),
             disable_paste_burst: false,
             custom_prompts: Vec::new(),
+            recipes: Vec::new(),
             footer_mode: FooterMode::ShortcutSummary,
             footer_hint_override: None,
             context_window_percent: None,
@@ -592,6 +597,14 @@ impl ChatComposer {
                                 }
                             }
                         }
+                        CommandItem::Recipe(idx) => {
+                            if let Some(recipe) = popup.recipe(idx) {
+                                let text =
+                                    format!("/{} {} ", SlashCommand::Recipe.command(), recipe.name);
+                                cursor_target = Some(text.len());
+                                self.textarea.set_text(&text);
+                            }
+                        }
                     }
                     if let Some(pos) = cursor_target {
                         self.textarea.set_cursor(pos);
@@ -621,9 +634,37 @@ impl ChatComposer {
                 if let Some(sel) = popup.selected_item() {
                     match sel {
                         CommandItem::Builtin(cmd) => {
+                            if let Some((_, rest)) = parse_slash_name(first_line)
+                                && !rest.is_empty()
+                                && cmd.accepts_args()
+                            {
+                                let args = rest.to_string();
+                                self.textarea.set_text("");
+                                return (InputResult::CommandWithArgs(cmd, args), true);
+                            }
                             self.textarea.set_text("");
                             return (InputResult::Command(cmd), true);
                         }
+                        CommandItem::Recipe(idx) => {
+                            let Some(recipe) = popup.recipe(idx) else {
+                                return (InputResult::None, true);
+                            };
+                            if recipe.params.iter().all(|param| param.default.is_some()) {
+                                let name = recipe.name.clone();
+                                self.textarea.set_text("");
+                                return (
+                                    InputResult::CommandWithArgs(SlashCommand::Recipe, name),
+                                    true,
+                                );
+                            }
+                            // Leave the cursor after the name so the user can
+                            // fill in the required arguments.
+                            let text =
+                                format!("/{} {} ", SlashCommand::Recipe.command(), recipe.name);
+                            self.textarea.set_text(&text);
+                            self.textarea.set_cursor(text.len());
+                            return (InputResult::None, true);
+                        }
                         CommandItem::UserPrompt(idx) => {
                             if let Some(prompt) = popup.prompt(idx) {
                                 match prompt_selection_action(
@@ -1176,13 +1217,19 @@ impl ChatComposer {
                 // literal text.
                 let first_line = self.textarea.text().lines().next().unwrap_or("");
                 if let Some((name, rest)) = parse_slash_name(first_line)
-                    && rest.is_empty()
                     && let Some((_n, cmd)) = built_in_slash_commands()
                         .into_iter()
                         .find(|(n, _)| *n == name)
                 {
-                    self.textarea.set_text("");
-                    return (InputResult::Command(cmd), true);
+                    if rest.is_empty() {
+                        self.textarea.set_text("");
+                        return (InputResult::Command(cmd), true);
+                    }
+                    if cmd.accepts_args() {
+                        let args = rest.trim_end().to_string();
+                        self.textarea.set_text("");
+                        return (InputResult::CommandWithArgs(cmd, args), true);
+                    }
                 }
                 // If we're in a paste-like burst capture, treat Enter as part of the burst
                 // and accumulate it rather than submitting or inserting immediately.
@@ -1725,6 +1772,10 @@ impl ChatComposer {
         self.custom_prompts
             .iter()
             .any(|p| fuzzy_match(&format!("{prompt_prefix}{}", p.name), name).is_some())
+            || self
+                .recipes
+                .iter()
+                .any(|r| fuzzy_match(&r.name, name).is_some())
     }
 
     /// Synchronize `self.command_popup` with the current text in the
@@ -1770,6 +1821,7 @@ impl ChatComposer {
                     let skills_enabled = self.skills_enabled();
                     let mut command_popup =
                         CommandPopup::new(self.custom_prompts.clone(), skills_enabled);
+                    command_popup.set_recipes(self.recipes.clone());
                     command_popup.on_composer_text_change(first_line.to_string());
                     self.active_popup = ActivePopup::Command(command_popup);
                 }
@@ -1777,6 +1829,13 @@ impl ChatComposer {
         }
     }
 
+    pub(crate) fn set_recipes(&mut self, recipes: Vec<Recipe>) {
+        self.recipes = recipes.clone();
+        if let ActivePopup::Command(popup) = &mut self.active_popup {
+            popup.set_recipes(recipes);
+        }
+    }
+
     pub(crate) fn set_custom_prompts(&mut self, prompts: Vec<CustomPrompt>) {
         self.custom_prompts = prompts.clone();
         if let ActivePopup::Command(popup) = &mut self.active_popup {
@@ -2624,7 +2683,7 @@ mod tests {
                 Some(CommandItem::Builtin(cmd)) => {
                     assert_eq!(cmd.command(), "resume")
                 }
-                Some(CommandItem::UserPrompt(_)) => {
+                Some(CommandItem::UserPrompt(_) | CommandItem::Recipe(_)) => {
                     panic!("unexpected prompt selected for '/res'")
                 }
                 None => panic!("no selected command for '/res'"),
@@ -2672,6 +2731,12 @@ mod tests {
                 panic!("expected command dispatch, but composer submitted literal text: {text}")
             }
             InputResult::None => panic!("expected Command result for '/new'"),
+            InputResult::CommandWithArgs(cmd, args) => {
+                panic!(
+                    "expected bare command for '/new', got /{} {args}",
+                    cmd.command()
+                )
+            }
         }
         assert!(composer.textarea.is_empty(), "composer should be cleared");
     }
@@ -2733,10 +2798,40 @@ mod tests {
                 panic!("expected command dispatch after Tab completion, got literal submit: {text}")
             }
             InputResult::None => panic!("expected Command result for '/exit'"),
+            InputResult::CommandWithArgs(cmd, args) => {
+                panic!(
+                    "expected bare command for '/exit', got /{} {args}",
+                    cmd.command()
+                )
+            }
         }
         assert!(composer.textarea.is_empty());
     }
 
+    #[test]
+    fn slash_recipe_with_args_dispatches_command_with_args() {
+        use crossterm::event::KeyCode;
+        use crossterm::event::KeyEvent;
+        use crossterm::event::KeyModifiers;
+
+        let (tx, _rx) = unbounded_channel::<AppEvent>();
+        let sender = AppEventSender::new(tx);
+        let mut composer = ChatComposer::new(true, sender, false, false);
+
+        composer.textarea.set_text("/recipe k8s-bundle api since=5");
+        let (result, _needs_redraw) =
+            composer.handle_key_event(KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE));
+
+        assert_eq!(
+            result,
+            InputResult::CommandWithArgs(
+                SlashCommand::Recipe,
+                "k8s-bundle api since=5".to_string()
+            )
+        );
+        assert!(composer.textarea.is_empty());
+    }
+
     #[test]
     fn slash_mention_dispatches_command_and_inserts_at() {
         use crossterm::event::KeyCode;
@@ -2760,6 +2855,12 @@ mod tests {
                 panic!("expected command dispatch, but composer submitted literal text: {text}")
             }
             InputResult::None => panic!("expected Command result for '/mention'"),
+            InputResult::CommandWithArgs(cmd, args) => {
+                panic!(
+                    "expected bare command for '/mention', got /{} {args}",
+                    cmd.command()
+                )
+            }
         }
         assert!(composer.textarea.is_empty(), "composer should be cleared");
         composer.insert_str("@");
@@ -13,20 +13,25 @@ use crate::slash_command::built_in_slash_commands;
 use aish_common::fuzzy_match::fuzzy_match;
 use aish_protocol::custom_prompts::CustomPrompt;
 use aish_protocol::custom_prompts::PROMPTS_CMD_PREFIX;
+use aish_protocol::recipes::Recipe;
 use std::collections::HashSet;
 
-/// A selectable item in the popup: either a built-in command or a user prompt.
+/// A selectable item in the popup: a built-in command, a user prompt or a
+/// saved recipe.
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub(crate) enum CommandItem {
     Builtin(SlashCommand),
     // Index into `prompts`
     UserPrompt(usize),
+    // Index into `recipes`
+    Recipe(usize),
 }
 
 pub(crate) struct CommandPopup {
     command_filter: String,
     builtins: Vec<(&'static str, SlashCommand)>,
     prompts: Vec<CustomPrompt>,
+    recipes: Vec<Recipe>,
     state: ScrollState,
 }
 
@@ -44,6 +49,7 @@ impl CommandPopup {
             command_filter: String::new(),
             builtins,
             prompts,
+            recipes: Vec::new(),
             state: ScrollState::new(),
         }
     }
@@ -63,6 +69,15 @@ impl CommandPopup {
         self.prompts.get(idx)
     }
 
+    pub(crate) fn set_recipes(&mut self, mut recipes: Vec<Recipe>) {
+        recipes.sort_by(|a, b| a.name.cmp(&b.name));
+        self.recipes = recipes;
+    }
+
+    pub(crate) fn recipe(&self, idx: usize) -> Option<&Recipe> {
+        self.recipes.get(idx)
+    }
+
     /// Update the filter string based on the current composer text. The text
     /// passed in is expected to start with a leading '/'. Everything after the
     /// *first* '/" on the *first* line becomes the active filter that is used
@@ -118,6 +133,10 @@ impl CommandPopup {
             for idx in 0..self.prompts.len() {
                 out.push((CommandItem::UserPrompt(idx), None, 0));
             }
+            // Then recipes, already sorted by name.
+            for idx in 0..self.recipes.len() {
+                out.push((CommandItem::Recipe(idx), None, 0));
+            }
             return out;
         }
 
@@ -135,16 +154,25 @@ impl CommandPopup {
                 out.push((CommandItem::UserPrompt(idx), Some(indices), score));
             }
         }
+        // Typing a recipe name surfaces "/recipe name".
+        for (idx, recipe) in self.recipes.iter().enumerate() {
+            let display = format!("{} {}", SlashCommand::Recipe.command(), recipe.name);
+            if let Some((indices, score)) = fuzzy_match(&display, filter) {
+                out.push((CommandItem::Recipe(idx), Some(indices), score));
+            }
+        }
         // When filtering, sort by ascending score and then by name for stability.
         out.sort_by(|a, b| {
             a.2.cmp(&b.2).then_with(|| {
                 let an = match a.0 {
                     CommandItem::Builtin(c) => c.command(),
                     CommandItem::UserPrompt(i) => &self.prompts[i].name,
+                    CommandItem::Recipe(i) => &self.recipes[i].name,
                 };
                 let bn = match b.0 {
                     CommandItem::Builtin(c) => c.command(),
                     CommandItem::UserPrompt(i) => &self.prompts[i].name,
+                    CommandItem::Recipe(i) => &self.recipes[i].name,
                 };
                 an.cmp(bn)
             })
@@ -178,6 +206,17 @@ impl CommandPopup {
                             description,
                         )
                     }
+                    CommandItem::Recipe(i) => {
+                        let recipe = &self.recipes[i];
+                        let description = recipe
+                            .description
+                            .clone()
+                            .unwrap_or_else(|| "run saved recipe".to_string());
+                        (
+                            format!("/{} {}", SlashCommand::Recipe.command(), recipe.name),
+                            description,
+                        )
+                    }
                 };
                 GenericDisplayRow {
                     name,
@@ -246,7 +285,7 @@ mod tests {
         let matches = popup.filtered_items();
         let has_new = matches.iter().any(|item| match item {
             CommandItem::Builtin(cmd) => cmd.command() == "new",
-            CommandItem::UserPrompt(_) => false,
+            CommandItem::UserPrompt(_) | CommandItem::Recipe(_) => false,
         });
         assert!(has_new, "expected '/new' to appear among filtered commands");
     }
@@ -261,7 +300,9 @@ mod tests {
         let selected = popup.selected_item();
         match selected {
             Some(CommandItem::Builtin(cmd)) => assert_eq!(cmd.command(), "new"),
-            Some(CommandItem::UserPrompt(_)) => panic!("unexpected prompt selected for '/new'"),
+            Some(CommandItem::UserPrompt(_) | CommandItem::Recipe(_)) => {
+                panic!("unexpected prompt selected for '/new'")
+            }
             None => panic!("expected a selected command for exact match"),
         }
     }
@@ -368,7 +409,7 @@ mod tests {
             .into_iter()
             .filter_map(|item| match item {
                 CommandItem::Builtin(cmd) => Some(cmd.command()),
-                CommandItem::UserPrompt(_) => None,
+                CommandItem::UserPrompt(_) | CommandItem::Recipe(_) => None,
             })
             .collect();
         assert_eq!(
@@ -377,4 +418,22 @@ mod tests {
             "expected fuzzy search for '/ac' to match only compact, got {cmds:?}"
         );
     }
+
+    #[test]
+    fn recipes_are_listed_as_recipe_subcommands() {
+        let mut popup = CommandPopup::new(Vec::new(), false);
+        popup.set_recipes(vec![Recipe {
+            name: "k8s-bundle".to_string(),
+            path: "/tmp/k8s-bundle.toml".into(),
+            description: None,
+            params: Vec::new(),
+            commands: vec!["kubectl get pods".to_string()],
+        }]);
+        popup.on_composer_text_change("/k8s".to_string());
+
+        assert_eq!(popup.selected_item(), Some(CommandItem::Recipe(0)));
+        let rows = popup.rows_from_matches(popup.filtered());
+        assert_eq!(rows[0].name, "/recipe k8s-bundle");
+        assert_eq!(rows[0].description.as_deref(), Some("run saved recipe"));
+    }
 }
@@ -44,6 +44,7 @@ pub(crate) enum CancellationEvent {
 }
 
 use aish_protocol::custom_prompts::CustomPrompt;
+use aish_protocol::recipes::Recipe;
 pub(crate) use chat_composer::ChatComposer;
 pub(crate) use chat_composer::InputResult;
 
@@ -400,6 +401,12 @@ impl BottomPane {
         self.request_redraw();
     }
 
+    /// Update saved recipes listed in the slash popup.
+    pub(crate) fn set_recipes(&mut self, recipes: Vec<Recipe>) {
+        self.composer.set_recipes(recipes);
+        self.request_redraw();
+    }
+
     /// Update custom prompts available for the slash popup.
     pub(crate) fn set_custom_prompts(&mut self, prompts: Vec<CustomPrompt>) {
         self.composer.set_custom_prompts(prompts);
@@ -586,6 +593,7 @@ mod tests {
         ApprovalRequest::Exec {
             id: "1".to_string(),
             command: vec!["echo".into(), "ok".into()],
+            cwd: PathBuf::from("/work/project"),
             reason: None,
             proposed_execpolicy_amendment: None,
         }
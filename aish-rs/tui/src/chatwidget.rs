@@ -8,7 +8,17 @@ use aish_core::config::Config;
 use aish_core::config::ConstraintResult;
 use aish_core::config::types::Notifications;
 
+use aish_core::exec_policy_commands;
+use aish_core::load_exec_policy_definitions;
 use aish_core::models_manager::model_family::ModelFamily;
+use aish_core::protocol::SessionMetadataItem;
+use aish_core::protocol::SessionMetadataUpdate;
+use aish_core::read_session_metadata;
+use aish_core::recipes;
+use aish_core::star_session;
+use aish_core::unstar_session;
+use aish_execpolicy::definition::render_definitions;
+use aish_execpolicy::definition::render_explanation;
 
 use aish_core::protocol::AgentMessageDeltaEvent;
 use aish_core::protocol::AgentMessageEvent;
@@ -17,7 +27,9 @@ use aish_core::protocol::AgentReasoningEvent;
 use aish_core::protocol::AgentReasoningRawContentDeltaEvent;
 use aish_core::protocol::AgentReasoningRawContentEvent;
 use aish_core::protocol::ApplyPatchApprovalRequestEvent;
+use aish_core::protocol::ApprovalGrant;
 use aish_core::protocol::BackgroundEventEvent;
+use aish_core::protocol::BackgroundJobSummary;
 use aish_core::protocol::DeprecationNoticeEvent;
 use aish_core::protocol::ErrorEvent;
 use aish_core::protocol::Event;
@@ -27,6 +39,7 @@ use aish_core::protocol::ExecCommandBeginEvent;
 use aish_core::protocol::ExecCommandEndEvent;
 use aish_core::protocol::ExecCommandSource;
 use aish_core::protocol::ListCustomPromptsResponseEvent;
+use aish_core::protocol::ListRecipesResponseEvent;
 use aish_core::protocol::ListSkillsResponseEvent;
 use aish_core::protocol::McpListToolsResponseEvent;
 use aish_core::protocol::McpStartupCompleteEvent;
@@ -34,11 +47,13 @@ use aish_core::protocol::McpStartupStatus;
 use aish_core::protocol::McpStartupUpdateEvent;
 use aish_core::protocol::McpToolCallBeginEvent;
 use aish_core::protocol::McpToolCallEndEvent;
+use aish_core::protocol::ModelProviderSwitchedEvent;
 use aish_core::protocol::Op;
 use aish_core::protocol::PatchApplyBeginEvent;
 use aish_core::protocol::StreamErrorEvent;
 use aish_core::protocol::TaskCompleteEvent;
 use aish_core::protocol::TerminalInteractionEvent;
+use aish_core::protocol::TokenCost;
 use aish_core::protocol::TokenUsage;
 use aish_core::protocol::TokenUsageInfo;
 use aish_core::protocol::TurnAbortReason;
@@ -55,6 +70,7 @@ use aish_protocol::ConversationId;
 use aish_protocol::account::PlanType;
 use aish_protocol::approvals::ElicitationRequestEvent;
 use aish_protocol::parse_command::ParsedCommand;
+use aish_protocol::recipes::Recipe;
 use aish_protocol::user_input::UserInput;
 use crossterm::event::KeyCode;
 use crossterm::event::KeyEvent;
@@ -86,6 +102,7 @@ use crate::diff_render::display_path_for;
 use crate::exec_cell::CommandOutput;
 use crate::exec_cell::ExecCell;
 use crate::exec_cell::new_active_exec_command;
+use crate::exec_command::strip_bash_lc_and_escape;
 use crate::history_cell;
 use crate::history_cell::HistoryCell;
 use crate::history_cell::McpToolCallCell;
@@ -97,6 +114,7 @@ use crate::render::renderable::Renderable;
 use crate::render::renderable::RenderableExt;
 use crate::render::renderable::RenderableItem;
 use crate::slash_command::SlashCommand;
+use crate::status::format_directory_display;
 use crate::text_formatting::truncate_text;
 use crate::tui::FrameRequester;
 mod interrupts;
@@ -134,13 +152,6 @@ fn is_unified_exec_source(source: ExecCommandSource) -> bool {
     )
 }
 
-fn is_standard_tool_call(parsed_cmd: &[ParsedCommand]) -> bool {
-    !parsed_cmd.is_empty()
-        && parsed_cmd
-            .iter()
-            .all(|parsed| !matches!(parsed, ParsedCommand::Unknown { .. }))
-}
-
 #[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
 pub(crate) enum ExternalEditorState {
     #[default]
@@ -172,7 +183,18 @@ pub(crate) struct ChatWidget {
     session_header: SessionHeader,
     initial_user_message: Option<UserMessage>,
     token_info: Option<TokenUsageInfo>,
+    // Running cost reported with token counts, when the model is priced.
+    token_cost: Option<TokenCost>,
     plan_type: Option<PlanType>,
+    // Latest snapshot of shell commands running in the background.
+    background_jobs: Vec<BackgroundJobSummary>,
+    recipes: Vec<Recipe>,
+    // Session-scoped approvals, listed and revoked from `/approvals`.
+    approval_grants: Vec<ApprovalGrant>,
+    // Commands that succeeded in the current turn and in the last completed
+    // turn; the latter is what `/recipe save` records.
+    turn_commands: Vec<String>,
+    last_turn_commands: Vec<String>,
     // Stream lifecycle controller
     stream_controller: Option<StreamController>,
     running_commands: HashMap<String, RunningCommand>,
@@ -288,6 +310,7 @@ impl ChatWidget {
         }
         // Ask codex-core to enumerate custom prompts for this session.
         self.submit_op(Op::ListCustomPrompts);
+        self.submit_op(Op::ListRecipes);
         self.submit_op(Op::ListSkills {
             force_reload: false,
         });
@@ -378,10 +401,14 @@ impl ChatWidget {
         self.set_status_header(String::from("Working"));
         self.full_reasoning_buffer.clear();
         self.reasoning_buffer.clear();
+        self.turn_commands.clear();
         self.request_redraw();
     }
 
     fn on_task_complete(&mut self, last_agent_message: Option<String>) {
+        if !self.turn_commands.is_empty() {
+            self.last_turn_commands = std::mem::take(&mut self.turn_commands);
+        }
         // If a stream is currently active, finalize it.
         self.flush_answer_stream_with_separator();
         self.flush_wait_cell();
@@ -445,6 +472,22 @@ impl ChatWidget {
         self.model_family.clone()
     }
 
+    pub(crate) fn set_model_family(&mut self, model_family: ModelFamily) {
+        self.model_family = model_family;
+    }
+
+    /// The session moved to a fallback provider; keep `/status` and the
+    /// header in sync with the provider actually serving requests.
+    fn on_model_provider_switched(&mut self, ev: ModelProviderSwitchedEvent) {
+        if let Some(provider) = self.config.model_providers.get(&ev.model_provider_id) {
+            self.config.model_provider = provider.clone();
+        }
+        self.config.model_provider_id = ev.model_provider_id;
+        self.session_header.set_model(&ev.model);
+        self.config.model = Some(ev.model);
+        self.request_redraw();
+    }
+
     fn on_error(&mut self, message: String) {
         self.finalize_turn();
         self.add_to_history(history_cell::new_error_event(message));
@@ -595,12 +638,6 @@ impl ChatWidget {
 
     fn on_exec_command_begin(&mut self, ev: ExecCommandBeginEvent) {
         self.flush_answer_stream_with_separator();
-        if is_unified_exec_source(ev.source) {
-            // Unified exec removed
-            if !is_standard_tool_call(&ev.parsed_cmd) {
-                return;
-            }
-        }
         let ev2 = ev.clone();
         self.defer_or_handle(|q| q.push_exec_begin(ev), |s| s.handle_exec_begin_now(ev2));
     }
@@ -641,11 +678,19 @@ impl ChatWidget {
     }
 
     fn on_exec_command_end(&mut self, ev: ExecCommandEndEvent) {
-        if is_unified_exec_source(ev.source) {
-            // Unified exec removed
-            if !self.bottom_pane.is_task_running() {
-                return;
-            }
+        // PTY sessions outlive their turn; ignore late output once the task
+        // has finished.
+        if is_unified_exec_source(ev.source) && !self.bottom_pane.is_task_running() {
+            return;
+        }
+        if ev.exit_code == 0
+            && matches!(
+                ev.source,
+                ExecCommandSource::Agent | ExecCommandSource::UnifiedExecStartup
+            )
+        {
+            self.turn_commands
+                .push(strip_bash_lc_and_escape(&ev.command));
         }
         let ev2 = ev.clone();
         self.defer_or_handle(|q| q.push_exec_end(ev), |s| s.handle_exec_end_now(ev2));
@@ -880,6 +925,7 @@ impl ChatWidget {
         let request = ApprovalRequest::Exec {
             id,
             command: ev.command,
+            cwd: ev.cwd,
             reason: ev.reason,
             proposed_execpolicy_amendment: ev.proposed_execpolicy_amendment,
         };
@@ -1060,7 +1106,13 @@ impl ChatWidget {
                 initial_images,
             ),
             token_info: None,
+            token_cost: None,
             plan_type: None,
+            background_jobs: Vec::new(),
+            recipes: Vec::new(),
+            approval_grants: Vec::new(),
+            turn_commands: Vec::new(),
+            last_turn_commands: Vec::new(),
             stream_controller: None,
             running_commands: HashMap::new(),
             task_complete_pending: false,
@@ -1126,7 +1178,13 @@ impl ChatWidget {
                 initial_images,
             ),
             token_info: None,
+            token_cost: None,
             plan_type: None,
+            background_jobs: Vec::new(),
+            recipes: Vec::new(),
+            approval_grants: Vec::new(),
+            turn_commands: Vec::new(),
+            last_turn_commands: Vec::new(),
             stream_controller: None,
             running_commands: HashMap::new(),
             task_complete_pending: false,
@@ -1218,6 +1276,9 @@ impl ChatWidget {
                     InputResult::Command(cmd) => {
                         self.dispatch_command(cmd);
                     }
+                    InputResult::CommandWithArgs(cmd, args) => {
+                        self.dispatch_command_with_args(cmd, args);
+                    }
                     InputResult::None => {}
                 }
             }
@@ -1307,11 +1368,34 @@ impl ChatWidget {
             SlashCommand::Mcp => {
                 self.add_mcp_output();
             }
+            SlashCommand::Recipe => {
+                self.add_to_history(history_cell::new_recipes_output(&self.recipes));
+            }
+            SlashCommand::Jobs => {
+                self.add_to_history(history_cell::new_background_jobs_output(
+                    &self.background_jobs,
+                ));
+            }
+            SlashCommand::Policy => {
+                self.show_exec_policy(None);
+            }
+            SlashCommand::Rename | SlashCommand::Tag => {
+                self.show_session_metadata();
+            }
+            SlashCommand::Star => {
+                self.star_current_session();
+            }
+            SlashCommand::Unstar => {
+                self.unstar_current_session();
+            }
             SlashCommand::Rollout => {
                 if let Some(path) = self.rollout_path() {
+                    let hint = self.conversation_id.map(|id| {
+                        format!("Export it with `aish sessions export {id} --format md|html|sh`.")
+                    });
                     self.add_info_message(
                         format!("Current rollout path: {}", path.display()),
-                        None,
+                        hint,
                     );
                 } else {
                     self.add_info_message("Rollout path is not available yet.".to_string(), None);
@@ -1407,6 +1491,155 @@ impl ChatWidget {
         self.app_event_tx.send(AppEvent::InsertHistoryCell(cell));
     }
 
+    fn dispatch_command_with_args(&mut self, cmd: SlashCommand, args: String) {
+        if !cmd.available_during_task() && self.bottom_pane.is_task_running() {
+            self.dispatch_command(cmd);
+            return;
+        }
+        match cmd {
+            SlashCommand::Recipe => self.run_recipe_command(&args),
+            SlashCommand::Rename => self.submit_op(Op::UpdateSessionMetadata {
+                update: SessionMetadataUpdate {
+                    name: Some(args.trim().to_string()),
+                    ..Default::default()
+                },
+            }),
+            SlashCommand::Tag => self.submit_op(Op::UpdateSessionMetadata {
+                update: parse_tag_args(&args),
+            }),
+            SlashCommand::Policy => self.show_exec_policy(Some(args.trim())),
+            _ => self.dispatch_command(cmd),
+        }
+    }
+
+    /// `/policy [command]`: lists the execpolicy rules, or shows which of
+    /// them decide `command`, read as the script of a `bash -lc` call.
+    fn show_exec_policy(&mut self, command: Option<&str>) {
+        let command = command
+            .filter(|command| !command.is_empty())
+            .map(str::to_string);
+        let config_stack = self.config.config_layer_stack.clone();
+        let app_event_tx = self.app_event_tx.clone();
+        tokio::spawn(async move {
+            let cell = match load_exec_policy_definitions(&config_stack).await {
+                Ok(definitions) => {
+                    let body = match &command {
+                        Some(command) => render_explanation(
+                            &definitions,
+                            &exec_policy_commands(&[
+                                "bash".to_string(),
+                                "-lc".to_string(),
+                                command.clone(),
+                            ]),
+                        ),
+                        None => render_definitions(&definitions),
+                    };
+                    history_cell::new_policy_output(command.as_deref(), &body)
+                }
+                Err(err) => {
+                    history_cell::new_error_event(format!("Failed to load execpolicy rules: {err}"))
+                }
+            };
+            app_event_tx.send(AppEvent::InsertHistoryCell(Box::new(cell)));
+        });
+    }
+
+    /// `/rename` or `/tag` without arguments: shows the current name and tags.
+    fn show_session_metadata(&mut self) {
+        let metadata = self
+            .rollout_path()
+            .and_then(|path| read_session_metadata(&path).ok())
+            .unwrap_or_default();
+        self.add_info_message(
+            session_metadata_summary(&metadata),
+            Some(
+                "Rename with /rename <name>; tag with /tag <tag> or untag with /tag -<tag>."
+                    .to_string(),
+            ),
+        );
+    }
+
+    fn on_session_metadata_updated(&mut self, metadata: SessionMetadataItem) {
+        self.add_info_message(session_metadata_summary(&metadata), None);
+    }
+
+    /// Handles `/star`: protects the current session from `[sessions]`
+    /// retention pruning. Naming it is `/rename`'s job.
+    fn star_current_session(&mut self) {
+        let Some(conversation_id) = self.conversation_id else {
+            self.add_error_message("Session has not started yet; nothing to star.".to_string());
+            return;
+        };
+        match star_session(&self.config.codex_home, conversation_id.into()) {
+            Ok(()) => {
+                self.add_info_message(
+                    "Starred this session.".to_string(),
+                    Some("Pruning will never delete it. Remove the star with /unstar.".to_string()),
+                );
+            }
+            Err(err) => self.add_error_message(format!("Failed to star session: {err}")),
+        }
+    }
+
+    fn unstar_current_session(&mut self) {
+        let Some(conversation_id) = self.conversation_id else {
+            self.add_error_message("Session has not started yet; nothing to unstar.".to_string());
+            return;
+        };
+        match unstar_session(&self.config.codex_home, conversation_id.into()) {
+            Ok(true) => {
+                self.add_info_message("Removed the star from this session.".to_string(), None)
+            }
+            Ok(false) => self.add_info_message("This session is not starred.".to_string(), None),
+            Err(err) => self.add_error_message(format!("Failed to unstar session: {err}")),
+        }
+    }
+
+    /// Handles `/recipe save <name> [param=value ...]` and
+    /// `/recipe <name> [args]`.
+    fn run_recipe_command(&mut self, args: &str) {
+        let args = args.trim();
+        let (name, rest) = args.split_once(char::is_whitespace).unwrap_or((args, ""));
+        if name == "save" {
+            self.save_last_turn_as_recipe(rest);
+            return;
+        }
+        let Some(recipe) = self.recipes.iter().find(|r| r.name == name).cloned() else {
+            self.add_error_message(format!(
+                "Unknown recipe '{name}'. Type /recipe to list saved recipes."
+            ));
+            return;
+        };
+        match recipes::render_recipe(&recipe, rest) {
+            Ok(commands) => self.submit_op(Op::RunRecipe {
+                name: recipe.name,
+                commands,
+            }),
+            Err(err) => self.add_error_message(format!("/recipe {name}: {err}")),
+        }
+    }
+
+    fn save_last_turn_as_recipe(&mut self, args: &str) {
+        let words = shlex::split(args).unwrap_or_default();
+        let Some((name, bindings)) = words.split_first() else {
+            self.add_error_message("Usage: /recipe save <name> [param=value ...]".to_string());
+            return;
+        };
+        match recipes::recipe_from_commands(name, self.last_turn_commands.clone(), bindings) {
+            Ok(recipe) => {
+                self.add_info_message(
+                    format!(
+                        "Saved recipe '{name}' ({} commands).",
+                        recipe.commands.len()
+                    ),
+                    Some(format!("Run it with {}", recipes::recipe_usage(&recipe))),
+                );
+                self.submit_op(Op::SaveRecipe { recipe });
+            }
+            Err(err) => self.add_error_message(format!("/recipe save: {err}")),
+        }
+    }
+
     fn queue_user_message(&mut self, user_message: UserMessage) {
         if self.bottom_pane.is_task_running() {
             self.queued_user_messages.push_back(user_message);
@@ -1545,8 +1778,10 @@ impl ChatWidget {
             }
             EventMsg::TokenCount(ev) => {
                 self.set_token_info(ev.info);
+                self.token_cost = ev.cost;
             }
             EventMsg::Warning(WarningEvent { message }) => self.on_warning(message),
+            EventMsg::ModelProviderSwitched(ev) => self.on_model_provider_switched(ev),
             EventMsg::Error(ErrorEvent { message, .. }) => self.on_error(message),
             EventMsg::McpStartupUpdate(ev) => self.on_mcp_startup_update(ev),
             EventMsg::McpStartupComplete(ev) => self.on_mcp_startup_complete(ev),
@@ -1583,10 +1818,16 @@ impl ChatWidget {
             EventMsg::GetHistoryEntryResponse(ev) => self.on_get_history_entry_response(ev),
             EventMsg::McpListToolsResponse(ev) => self.on_list_mcp_tools(ev),
             EventMsg::ListCustomPromptsResponse(ev) => self.on_list_custom_prompts(ev),
+            EventMsg::ListRecipesResponse(ev) => self.on_list_recipes(ev),
+            EventMsg::SessionMetadataUpdated(metadata) => {
+                self.on_session_metadata_updated(metadata)
+            }
+            EventMsg::ApprovalGrantsUpdated(ev) => self.approval_grants = ev.grants,
             EventMsg::ListSkillsResponse(ev) => self.on_list_skills(ev),
             EventMsg::SkillsUpdateAvailable => {
                 self.submit_op(Op::ListSkills { force_reload: true });
             }
+            EventMsg::BackgroundJobsUpdate(ev) => self.background_jobs = ev.jobs,
             EventMsg::ShutdownComplete => self.on_shutdown_complete(),
             EventMsg::TurnDiff(TurnDiffEvent { unified_diff }) => self.on_turn_diff(unified_diff),
             EventMsg::DeprecationNotice(ev) => self.on_deprecation_notice(ev),
@@ -1686,12 +1927,23 @@ impl ChatWidget {
         } else {
             (&default_usage, Some(&default_usage))
         };
+        let credential_source = aish_core::auth::resolve_provider_credential(
+            &self.config.codex_home,
+            &self.config.model_provider_id,
+            &self.config.model_provider,
+        )
+        .ok()
+        .flatten()
+        .map(|credential| credential.source);
         self.add_to_history(crate::status::new_status_output(
             &self.config,
             &self.model_family,
             total_usage,
             context_usage,
+            self.token_cost.as_ref(),
             &self.conversation_id,
+            &self.background_jobs,
+            credential_source.as_ref(),
             self.plan_type,
             Local::now(),
             self.model_family.get_model_slug(),
@@ -1739,6 +1991,35 @@ impl ChatWidget {
             });
         }
 
+        for grant in &self.approval_grants {
+            let prefix = strip_bash_lc_and_escape(&grant.prefix);
+            let description = match &grant.directory {
+                Some(directory) => format!(
+                    "Allowed in {} for this session",
+                    format_directory_display(directory, None)
+                ),
+                None => "Allowed for this session".to_string(),
+            };
+            let grant = grant.clone();
+            items.push(SelectionItem {
+                name: format!("Revoke `{prefix}`"),
+                description: Some(description),
+                actions: vec![Box::new(move |tx| {
+                    tx.send(AppEvent::CodexOp(Op::RevokeApprovalGrant {
+                        grant: grant.clone(),
+                    }));
+                    tx.send(AppEvent::InsertHistoryCell(Box::new(
+                        history_cell::new_info_event(
+                            format!("Revoked the session approval for `{prefix}`."),
+                            None,
+                        ),
+                    )));
+                })],
+                dismiss_on_select: true,
+                ..Default::default()
+            });
+        }
+
         self.bottom_pane.show_selection_view(SelectionViewParams {
             title: Some("Select Approval Mode".to_string()),
             footer_hint: Some(standard_popup_hint_line()),
@@ -1966,6 +2247,12 @@ impl ChatWidget {
         self.bottom_pane.set_custom_prompts(ev.custom_prompts);
     }
 
+    fn on_list_recipes(&mut self, ev: ListRecipesResponseEvent) {
+        debug!("received {} recipes", ev.recipes.len());
+        self.bottom_pane.set_recipes(ev.recipes.clone());
+        self.recipes = ev.recipes;
+    }
+
     fn on_list_skills(&mut self, ev: ListSkillsResponseEvent) {
         self.set_skills_from_response(&ev);
     }
@@ -2140,5 +2427,30 @@ fn find_skill_mentions(text: &str, skills: &[SkillMetadata]) -> Vec<SkillMetadat
     matches
 }
 
+/// Parses `/tag` arguments: `tag` adds a tag, `-tag` removes it.
+fn parse_tag_args(args: &str) -> SessionMetadataUpdate {
+    let mut update = SessionMetadataUpdate::default();
+    for word in args.split_whitespace() {
+        match word.strip_prefix('-') {
+            Some(tag) => update.remove_tags.push(tag.to_string()),
+            None => update.add_tags.push(word.to_string()),
+        }
+    }
+    update
+}
+
+fn session_metadata_summary(metadata: &SessionMetadataItem) -> String {
+    let name = match metadata.name.as_deref() {
+        Some(name) => format!("Session name: {name}"),
+        None => "This session has no name".to_string(),
+    };
+    if metadata.tags.is_empty() {
+        format!("{name}; no tags.")
+    } else {
+        let tags: Vec<String> = metadata.tags.iter().map(|tag| format!("#{tag}")).collect();
+        format!("{name}; tags: {}.", tags.join(" "))
+    }
+}
+
 #[cfg(test)]
 pub(crate) mod tests;
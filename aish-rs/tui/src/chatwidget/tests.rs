@@ -27,6 +27,7 @@ use aish_core::protocol::ExecPolicyAmendment;
 use aish_core::protocol::FileChange;
 use aish_core::protocol::McpStartupStatus;
 use aish_core::protocol::McpStartupUpdateEvent;
+use aish_core::protocol::ModelProviderSwitchedEvent;
 use aish_core::protocol::Op;
 use aish_core::protocol::PatchApplyBeginEvent;
 use aish_core::protocol::PatchApplyEndEvent;
@@ -143,13 +144,17 @@ async fn token_count_none_resets_context_indicator() {
         id: "token-before".into(),
         msg: EventMsg::TokenCount(TokenCountEvent {
             info: Some(make_token_info(pre_compact_tokens, context_window)),
+            cost: None,
         }),
     });
     assert_eq!(chat.bottom_pane.context_window_percent(), Some(30));
 
     chat.handle_codex_event(Event {
         id: "token-cleared".into(),
-        msg: EventMsg::TokenCount(TokenCountEvent { info: None }),
+        msg: EventMsg::TokenCount(TokenCountEvent {
+            info: None,
+            cost: None,
+        }),
     });
     assert_eq!(chat.bottom_pane.context_window_percent(), None);
 }
@@ -178,6 +183,7 @@ async fn context_indicator_shows_used_tokens_when_window_unknown() {
         id: "token-usage".into(),
         msg: EventMsg::TokenCount(TokenCountEvent {
             info: Some(token_info),
+            cost: None,
         }),
     });
 
@@ -263,7 +269,13 @@ async fn make_chatwidget_manual(
         session_header: SessionHeader::new(resolved_model.clone()),
         initial_user_message: None,
         token_info: None,
+        token_cost: None,
         plan_type: None,
+        background_jobs: Vec::new(),
+        recipes: Vec::new(),
+        approval_grants: Vec::new(),
+        turn_commands: Vec::new(),
+        last_turn_commands: Vec::new(),
         stream_controller: None,
         running_commands: HashMap::new(),
         task_complete_pending: false,
@@ -660,6 +672,32 @@ async fn streaming_final_answer_keeps_task_running_state() {
     assert!(chat.bottom_pane.ctrl_c_quit_hint_visible());
 }
 
+#[tokio::test]
+async fn tag_command_submits_metadata_update() {
+    let (mut chat, _rx, mut op_rx) = make_chatwidget_manual(None).await;
+
+    chat.dispatch_command_with_args(SlashCommand::Tag, "infra #k8s -old".to_string());
+    match op_rx.try_recv() {
+        Ok(Op::UpdateSessionMetadata { update }) => assert_eq!(
+            update,
+            SessionMetadataUpdate {
+                name: None,
+                add_tags: vec!["infra".to_string(), "#k8s".to_string()],
+                remove_tags: vec!["old".to_string()],
+            }
+        ),
+        other => panic!("expected Op::UpdateSessionMetadata, got {other:?}"),
+    }
+
+    chat.dispatch_command_with_args(SlashCommand::Rename, " deploy fix ".to_string());
+    match op_rx.try_recv() {
+        Ok(Op::UpdateSessionMetadata { update }) => {
+            assert_eq!(update.name.as_deref(), Some("deploy fix"));
+        }
+        other => panic!("expected Op::UpdateSessionMetadata, got {other:?}"),
+    }
+}
+
 #[tokio::test]
 async fn ctrl_c_shutdown_ignores_caps_lock() {
     let (mut chat, _rx, mut op_rx) = make_chatwidget_manual(None).await;
@@ -1093,6 +1131,9 @@ async fn preset_matching_with_current_dir_write() {
         network_access: false,
         exclude_tmpdir_env_var: false,
         exclude_slash_tmp: false,
+        network_allowlist: Vec::new(),
+        writable_roots: Vec::new(),
+        deny_read: Vec::new(),
     };
 
     assert!(
@@ -2240,6 +2281,25 @@ async fn warning_event_adds_warning_history_cell() {
     );
 }
 
+#[tokio::test]
+async fn model_provider_switch_updates_status_config() {
+    let (mut chat, _rx, _op_rx) = make_chatwidget_manual(None).await;
+    chat.handle_codex_event(Event {
+        id: "sub-1".into(),
+        msg: EventMsg::ModelProviderSwitched(ModelProviderSwitchedEvent {
+            model_provider_id: "ollama".to_string(),
+            model: "qwen3:8b".to_string(),
+        }),
+    });
+
+    assert_eq!(chat.config.model_provider_id, "ollama");
+    assert_eq!(
+        Some(&chat.config.model_provider),
+        chat.config.model_providers.get("ollama")
+    );
+    assert_eq!(chat.config.model.as_deref(), Some("qwen3:8b"));
+}
+
 #[tokio::test]
 async fn stream_recovery_restores_previous_status_header() {
     let (mut chat, mut rx, _op_rx) = make_chatwidget_manual(None).await;
@@ -28,6 +28,11 @@ pub struct Cli {
     #[clap(skip)]
     pub resume_session_id: Option<String>,
 
+    /// Internal: fork the resumed session at this user turn (0-based). Set by
+    /// `aish resume --fork-at`; not exposed as a public flag.
+    #[clap(skip)]
+    pub resume_fork_at: Option<usize>,
+
     /// Model the agent should use.
     #[arg(long, short = 'm')]
     pub model: Option<String>,
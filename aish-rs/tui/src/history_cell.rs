@@ -22,6 +22,8 @@ use crate::wrapping::word_wrap_lines;
 use aish_common::format_env_display::format_env_display;
 use aish_core::config::Config;
 use aish_core::config::types::McpServerTransportConfig;
+use aish_core::protocol::BackgroundJobStatus;
+use aish_core::protocol::BackgroundJobSummary;
 use aish_core::protocol::FileChange;
 use aish_core::protocol::McpAuthStatus;
 use aish_core::protocol::McpInvocation;
@@ -30,6 +32,7 @@ use aish_protocol::openai_models::ReasoningEffort as ReasoningEffortConfig;
 use aish_protocol::plan_tool::PlanItemArg;
 use aish_protocol::plan_tool::StepStatus;
 use aish_protocol::plan_tool::UpdatePlanArgs;
+use aish_protocol::recipes::Recipe;
 use base64::Engine;
 use image::DynamicImage;
 use image::ImageReader;
@@ -376,6 +379,26 @@ pub fn new_approval_decision_cell(
                 ],
             )
         }
+        ApprovedWithGrant { grant } => {
+            let snippet = Span::from(exec_snippet(&command)).dim();
+            let scope = if grant.directory.is_some() {
+                " in this directory for the rest of this session"
+            } else {
+                " for the rest of this session"
+            };
+            (
+                "✔ ".green(),
+                vec![
+                    "You ".into(),
+                    "approved".bold(),
+                    " aish to run ".into(),
+                    snippet,
+                    " and commands starting with ".into(),
+                    Span::from(exec_snippet(&grant.prefix)).dim(),
+                    scope.bold(),
+                ],
+            )
+        }
         Denied => {
             let snippet = Span::from(exec_snippet(&command)).dim();
             (
@@ -938,6 +961,122 @@ pub(crate) fn empty_mcp_output() -> PlainHistoryCell {
     PlainHistoryCell { lines }
 }
 
+/// Render the background jobs started in this session, newest last.
+pub(crate) fn new_background_jobs_output(jobs: &[BackgroundJobSummary]) -> PlainHistoryCell {
+    let mut lines: Vec<Line<'static>> = vec![
+        "/jobs".magenta().into(),
+        "".into(),
+        vec!["⏱  ".into(), "Background Jobs".bold()].into(),
+        "".into(),
+    ];
+
+    if jobs.is_empty() {
+        lines.push("  • No background jobs.".italic().into());
+        lines.push("".into());
+        return PlainHistoryCell { lines };
+    }
+
+    for job in jobs {
+        let status = match &job.status {
+            BackgroundJobStatus::Running => "running".green(),
+            BackgroundJobStatus::Exited { exit_code: 0 } => "exited 0".dim(),
+            BackgroundJobStatus::Exited { exit_code } => format!("exited {exit_code}").red(),
+            BackgroundJobStatus::Killed => "killed".red(),
+            BackgroundJobStatus::Failed { error } => format!("failed: {error}").red(),
+        };
+        lines.push(
+            vec![
+                format!("  • Job {} ", job.job_id).into(),
+                status,
+                " — ".dim(),
+                strip_bash_lc_and_escape(&job.command).into(),
+            ]
+            .into(),
+        );
+        lines.push(vec!["    • Cwd: ".into(), job.cwd.display().to_string().dim()].into());
+    }
+    lines.push("".into());
+
+    PlainHistoryCell { lines }
+}
+
+/// Render saved recipes with their usage line and commands.
+pub(crate) fn new_recipes_output(recipes: &[Recipe]) -> PlainHistoryCell {
+    let mut lines: Vec<Line<'static>> = vec![
+        "/recipe".magenta().into(),
+        "".into(),
+        vec!["📜  ".into(), "Recipes".bold()].into(),
+        "".into(),
+    ];
+
+    if recipes.is_empty() {
+        lines.push("  • No saved recipes.".italic().into());
+        lines.push(
+            "    Save the commands from the last turn with /recipe save <name> [param=value ...]."
+                .italic()
+                .into(),
+        );
+        lines.push("".into());
+        return PlainHistoryCell { lines };
+    }
+
+    for recipe in recipes {
+        let mut header: Vec<Span<'static>> = vec![
+            "  • ".into(),
+            aish_core::recipes::recipe_usage(recipe).into(),
+        ];
+        if let Some(description) = recipe.description.as_ref() {
+            header.push(" — ".dim());
+            header.push(description.clone().dim());
+        }
+        lines.push(header.into());
+        for param in &recipe.params {
+            lines.push(
+                vec![
+                    format!("    • {}: ", param.name).into(),
+                    param.param_type.to_string().dim(),
+                ]
+                .into(),
+            );
+        }
+        for command in &recipe.commands {
+            lines.push(vec!["    $ ".dim(), command.clone().into()].into());
+        }
+    }
+    lines.push("".into());
+
+    PlainHistoryCell { lines }
+}
+
+/// Render `/policy`: the execpolicy rules, or how they decide `command`.
+pub(crate) fn new_policy_output(command: Option<&str>, body: &str) -> PlainHistoryCell {
+    let title = match command {
+        Some(command) => format!("/policy {command}"),
+        None => "/policy".to_string(),
+    };
+    let mut lines: Vec<Line<'static>> = vec![
+        title.magenta().into(),
+        "".into(),
+        vec!["📏  ".into(), "Execpolicy rules".bold()].into(),
+        "".into(),
+    ];
+
+    if body.trim().is_empty() {
+        lines.push("  • No rules in ~/.aish/rules.".italic().into());
+        lines.push(
+            "    Approving with \"don't ask again\" adds a rule to default.rules."
+                .italic()
+                .into(),
+        );
+    }
+    for line in body.lines() {
+        lines.push(format!("  {line}").into());
+    }
+    lines.push("".into());
+
+    PlainHistoryCell { lines }
+}
+
 /// Render MCP tools grouped by connection using the fully-qualified tool names.
 pub(crate) fn new_mcp_tools_output(
     config: &Config,
@@ -1437,6 +1576,38 @@ mod tests {
         insta::assert_snapshot!(rendered);
     }
 
+    #[test]
+    fn background_jobs_output_lists_each_job() {
+        let jobs = vec![
+            BackgroundJobSummary {
+                job_id: 1,
+                command: vec!["bash".into(), "-lc".into(), "npm run dev".into()],
+                cwd: PathBuf::from("/repo"),
+                status: BackgroundJobStatus::Running,
+            },
+            BackgroundJobSummary {
+                job_id: 2,
+                command: vec!["cargo".into(), "test".into()],
+                cwd: PathBuf::from("/repo"),
+                status: BackgroundJobStatus::Exited { exit_code: 101 },
+            },
+        ];
+        let rendered = render_lines(&new_background_jobs_output(&jobs).display_lines(120));
+
+        assert!(
+            rendered.contains(&"  • Job 1 running — npm run dev".to_string()),
+            "{rendered:?}"
+        );
+        assert!(
+            rendered.contains(&"  • Job 2 exited 101 — cargo test".to_string()),
+            "{rendered:?}"
+        );
+        assert!(
+            render_lines(&new_background_jobs_output(&[]).display_lines(120))
+                .contains(&"  • No background jobs.".to_string())
+        );
+    }
+
     #[test]
     fn empty_agent_message_cell_transcript() {
         let cell = AgentMessageCell::new(vec![Line::default()], false);
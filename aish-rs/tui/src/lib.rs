@@ -14,7 +14,9 @@ use aish_core::config::find_codex_home;
 use aish_core::config::load_config_as_toml_with_cli_overrides;
 use aish_core::config::resolve_oss_provider;
 use aish_core::find_conversation_path_by_id_str;
+use aish_core::fork_rollout_at;
 use aish_core::protocol::AskForApproval;
+use aish_core::prune_sessions;
 use aish_protocol::config_types::SandboxMode;
 use aish_utils_absolute_path::AbsolutePathBuf;
 use app::App;
@@ -399,6 +401,33 @@ async fn run_ratatui_app(
         resume_picker::ResumeSelection::StartFresh
     };
 
+    // `aish resume --fork-at`: continue in a copy of the chosen session.
+    let resume_selection = match (cli.resume_fork_at, resume_selection) {
+        (Some(turn_index), resume_picker::ResumeSelection::Resume(path)) => {
+            match fork_rollout_at(&config.codex_home, &path, turn_index).await {
+                Ok(forked) => resume_picker::ResumeSelection::Resume(forked.path),
+                Err(err) => {
+                    error!("Error forking {}: {err}", path.display());
+                    restore();
+                    session_log::log_session_end();
+                    let _ = tui.terminal.clear();
+                    if let Err(err) =
+                        writeln!(std::io::stdout(), "Failed to fork the session: {err}")
+                    {
+                        error!("Failed to write fork error message: {err}");
+                    }
+                    return Ok(AppExitInfo {
+                        token_usage: aish_core::protocol::TokenUsage::default(),
+                        conversation_id: None,
+                    });
+                }
+            }
+        }
+        (_, selection) => selection,
+    };
+
+    spawn_session_pruning(&config, &resume_selection);
+
     let Cli { prompt, images, .. } = cli;
 
     let app_result = App::run(
@@ -419,6 +448,31 @@ async fn run_ratatui_app(
     app_result
 }
 
+/// Applies the `[sessions]` retention policy in the background so a large
+/// sessions directory never delays startup.
+fn spawn_session_pruning(config: &Config, resume_selection: &resume_picker::ResumeSelection) {
+    if !config.sessions.is_enabled() {
+        return;
+    }
+    let codex_home = config.codex_home.clone();
+    let retention = config.sessions.clone();
+    let protected = match resume_selection {
+        resume_picker::ResumeSelection::Resume(path) => vec![path.clone()],
+        _ => Vec::new(),
+    };
+    tokio::spawn(async move {
+        match prune_sessions(&codex_home, &retention, protected, false).await {
+            Ok(report) if !report.pruned.is_empty() => tracing::info!(
+                "pruned {} session(s), freeing {} bytes",
+                report.pruned.len(),
+                report.freed_bytes()
+            ),
+            Ok(_) => {}
+            Err(err) => tracing::warn!("failed to prune sessions: {err}"),
+        }
+    });
+}
+
 #[expect(
     clippy::print_stderr,
     reason = "TUI should no longer be displayed, so we can write to stderr."
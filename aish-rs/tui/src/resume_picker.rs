@@ -2,12 +2,16 @@ use std::collections::HashSet;
 use std::path::Path;
 use std::path::PathBuf;
 use std::sync::Arc;
+use std::sync::Mutex;
 
 use aish_core::ConversationItem;
 use aish_core::ConversationsPage;
 use aish_core::Cursor;
 use aish_core::INTERACTIVE_SESSION_SOURCES;
 use aish_core::RolloutRecorder;
+use aish_core::SearchMatchKind;
+use aish_core::SessionIndex;
+use aish_core::SessionSearchHit;
 use aish_core::path_utils;
 use aish_protocol::items::TurnItem;
 use chrono::DateTime;
@@ -35,9 +39,11 @@ use crate::tui::Tui;
 use crate::tui::TuiEvent;
 use aish_protocol::models::ResponseItem;
 use aish_protocol::protocol::SessionMetaLine;
+use aish_protocol::protocol::normalize_session_tag;
 
 const PAGE_SIZE: usize = 25;
 const LOAD_NEAR_THRESHOLD: usize = 5;
+const FULL_TEXT_SEARCH_LIMIT: usize = 200;
 
 #[derive(Debug, Clone)]
 pub enum ResumeSelection {
@@ -57,17 +63,32 @@ struct PageLoadRequest {
 
 type PageLoader = Arc<dyn Fn(PageLoadRequest) + Send + Sync>;
 
+#[derive(Clone)]
+struct FullTextSearchRequest {
+    codex_home: PathBuf,
+    query: String,
+    token: usize,
+    default_provider: String,
+}
+
+type FullTextSearcher = Arc<dyn Fn(FullTextSearchRequest) + Send + Sync>;
+
 enum BackgroundEvent {
     PageLoaded {
         request_token: usize,
         search_token: Option<usize>,
         page: std::io::Result<ConversationsPage>,
     },
+    FullTextSearchCompleted {
+        token: usize,
+        query: String,
+        hits: std::io::Result<Vec<SessionSearchHit>>,
+    },
 }
 
-/// Interactive session picker that lists recorded rollout files with simple
-/// search and pagination. Shows the first user input as the preview, relative
-/// time (e.g., "5 seconds ago"), and the absolute path.
+/// Interactive session picker that lists recorded rollout files with
+/// full-text search and pagination. Shows the first user input as the
+/// preview, relative time (e.g., "5 seconds ago"), and the absolute path.
 pub async fn run_resume_picker(
     tui: &mut Tui,
     codex_home: &Path,
@@ -102,10 +123,28 @@ pub async fn run_resume_picker(
         });
     });
 
+    // The index is loaded and refreshed on the first search, then reused for
+    // every keystroke while the picker is open.
+    let search_tx = bg_tx.clone();
+    let session_index: Arc<Mutex<Option<SessionIndex>>> = Arc::new(Mutex::new(None));
+    let searcher: FullTextSearcher = Arc::new(move |request: FullTextSearchRequest| {
+        let tx = search_tx.clone();
+        let session_index = session_index.clone();
+        tokio::task::spawn_blocking(move || {
+            let hits = search_session_index(&session_index, &request);
+            let _ = tx.send(BackgroundEvent::FullTextSearchCompleted {
+                token: request.token,
+                query: request.query,
+                hits,
+            });
+        });
+    });
+
     let mut state = PickerState::new(
         codex_home.to_path_buf(),
         alt.tui.frame_requester(),
         page_loader,
+        searcher,
         default_provider.clone(),
         filter_cwd,
     );
@@ -149,6 +188,31 @@ pub async fn run_resume_picker(
     Ok(ResumeSelection::StartFresh)
 }
 
+fn search_session_index(
+    session_index: &Mutex<Option<SessionIndex>>,
+    request: &FullTextSearchRequest,
+) -> std::io::Result<Vec<SessionSearchHit>> {
+    let mut guard = session_index
+        .lock()
+        .map_err(|_| std::io::Error::other("session index lock poisoned"))?;
+    if guard.is_none() {
+        let mut index = SessionIndex::load(&request.codex_home);
+        index.refresh()?;
+        *guard = Some(index);
+    }
+    let Some(index) = guard.as_ref() else {
+        return Ok(Vec::new());
+    };
+    let provider_filter = vec![request.default_provider.clone()];
+    Ok(index.search(
+        &request.query,
+        FULL_TEXT_SEARCH_LIMIT,
+        INTERACTIVE_SESSION_SOURCES,
+        Some(provider_filter.as_slice()),
+        &request.default_provider,
+    ))
+}
+
 /// RAII guard that ensures we leave the alt-screen on scope exit.
 struct AltScreenGuard<'a> {
     tui: &'a mut Tui,
@@ -178,12 +242,56 @@ struct PickerState {
     scroll_top: usize,
     query: String,
     search_state: SearchState,
+    /// Token of the full-text search still running for `query`, if any.
+    full_text_pending: Option<usize>,
+    /// Full-text matches for `query`; replaces the preview-only filter once
+    /// available.
+    full_text_rows: Option<Vec<Row>>,
     next_request_token: usize,
     next_search_token: usize,
     page_loader: PageLoader,
+    searcher: FullTextSearcher,
     view_rows: Option<usize>,
     default_provider: String,
     filter_cwd: Option<PathBuf>,
+    sort: SortOrder,
+}
+
+/// Order of the filtered rows; Tab cycles through them.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+enum SortOrder {
+    /// As listed: newest first.
+    #[default]
+    Recent,
+    /// Named sessions first, by name.
+    Name,
+    /// Tagged sessions first, by their first tag.
+    Tag,
+}
+
+impl SortOrder {
+    fn next(self) -> Self {
+        match self {
+            SortOrder::Recent => SortOrder::Name,
+            SortOrder::Name => SortOrder::Tag,
+            SortOrder::Tag => SortOrder::Recent,
+        }
+    }
+
+    fn sort(self, rows: &mut [Row]) {
+        match self {
+            SortOrder::Recent => {}
+            SortOrder::Name => rows.sort_by_cached_key(|row| {
+                (
+                    row.name.is_none(),
+                    row.name.as_deref().map(str::to_lowercase),
+                )
+            }),
+            SortOrder::Tag => {
+                rows.sort_by_cached_key(|row| (row.tags.is_empty(), row.tags.first().cloned()))
+            }
+        }
+    }
 }
 
 struct PaginationState {
@@ -242,6 +350,29 @@ struct Row {
     created_at: Option<DateTime<Utc>>,
     updated_at: Option<DateTime<Utc>>,
     cwd: Option<PathBuf>,
+    name: Option<String>,
+    tags: Vec<String>,
+}
+
+impl Row {
+    /// Whether the row carries every `#tag` of `query` and its name or
+    /// preview contains the remaining text.
+    fn matches_query(&self, query: &str) -> bool {
+        let (tags, words): (Vec<&str>, Vec<&str>) = query
+            .split_whitespace()
+            .partition(|word| word.starts_with('#'));
+        let has_tags = tags
+            .into_iter()
+            .filter_map(normalize_session_tag)
+            .all(|tag| self.tags.contains(&tag));
+        let text = words.join(" ").to_lowercase();
+        has_tags
+            && (self.preview.to_lowercase().contains(&text)
+                || self
+                    .name
+                    .as_deref()
+                    .is_some_and(|name| name.to_lowercase().contains(&text)))
+    }
 }
 
 impl PickerState {
@@ -249,6 +380,7 @@ impl PickerState {
         codex_home: PathBuf,
         requester: FrameRequester,
         page_loader: PageLoader,
+        searcher: FullTextSearcher,
         default_provider: String,
         filter_cwd: Option<PathBuf>,
     ) -> Self {
@@ -268,12 +400,16 @@ impl PickerState {
             scroll_top: 0,
             query: String::new(),
             search_state: SearchState::Idle,
+            full_text_pending: None,
+            full_text_rows: None,
             next_request_token: 0,
             next_search_token: 0,
             page_loader,
+            searcher,
             view_rows: None,
             default_provider,
             filter_cwd,
+            sort: SortOrder::default(),
         }
     }
 
@@ -329,6 +465,12 @@ impl PickerState {
                     self.request_frame();
                 }
             }
+            KeyCode::Tab => {
+                self.sort = self.sort.next();
+                self.selected = 0;
+                self.scroll_top = 0;
+                self.apply_filter();
+            }
             KeyCode::Backspace => {
                 let mut new_query = self.query.clone();
                 new_query.pop();
@@ -395,6 +537,20 @@ impl PickerState {
                 let completed_token = pending.search_token.or(search_token);
                 self.continue_search_if_token_matches(completed_token);
             }
+            BackgroundEvent::FullTextSearchCompleted { token, query, hits } => {
+                if self.full_text_pending != Some(token) || self.query != query {
+                    return Ok(());
+                }
+                self.full_text_pending = None;
+                let hits = hits.map_err(color_eyre::Report::from)?;
+                self.full_text_rows = Some(
+                    hits.into_iter()
+                        .map(|hit| row_from_search_hit(hit, &query))
+                        .collect(),
+                );
+                self.search_state = SearchState::Idle;
+                self.apply_filter();
+            }
         }
         Ok(())
     }
@@ -437,13 +593,19 @@ impl PickerState {
             .filter(|row| self.row_matches_filter(row));
         if self.query.is_empty() {
             self.filtered_rows = base_iter.cloned().collect();
+        } else if let Some(rows) = self.full_text_rows.as_ref() {
+            self.filtered_rows = rows
+                .iter()
+                .filter(|row| self.row_matches_filter(row))
+                .cloned()
+                .collect();
         } else {
-            let q = self.query.to_lowercase();
             self.filtered_rows = base_iter
-                .filter(|r| r.preview.to_lowercase().contains(&q))
+                .filter(|r| r.matches_query(&self.query))
                 .cloned()
                 .collect();
         }
+        self.sort.sort(&mut self.filtered_rows);
         if self.selected >= self.filtered_rows.len() {
             self.selected = self.filtered_rows.len().saturating_sub(1);
         }
@@ -470,11 +632,14 @@ impl PickerState {
         }
         self.query = new_query;
         self.selected = 0;
+        self.full_text_rows = None;
+        self.full_text_pending = None;
         self.apply_filter();
         if self.query.is_empty() {
             self.search_state = SearchState::Idle;
             return;
         }
+        self.start_full_text_search();
         if !self.filtered_rows.is_empty() {
             self.search_state = SearchState::Idle;
             return;
@@ -488,11 +653,22 @@ impl PickerState {
         self.load_more_if_needed(LoadTrigger::Search { token });
     }
 
+    fn start_full_text_search(&mut self) {
+        let token = self.allocate_search_token();
+        self.full_text_pending = Some(token);
+        (self.searcher)(FullTextSearchRequest {
+            codex_home: self.codex_home.clone(),
+            query: self.query.clone(),
+            token,
+            default_provider: self.default_provider.clone(),
+        });
+    }
+
     fn continue_search_if_needed(&mut self) {
         let Some(token) = self.search_state.active_token() else {
             return;
         };
-        if !self.filtered_rows.is_empty() {
+        if !self.filtered_rows.is_empty() || self.full_text_rows.is_some() {
             self.search_state = SearchState::Idle;
             return;
         }
@@ -643,6 +819,47 @@ fn head_to_row(item: &ConversationItem) -> Row {
         created_at,
         updated_at,
         cwd,
+        name: item.name.clone(),
+        tags: item.tags.clone(),
+    }
+}
+
+/// Shows the first user message when it contains the query, otherwise the
+/// first excerpt that matched.
+fn row_from_search_hit(hit: SessionSearchHit, query: &str) -> Row {
+    let preview = hit
+        .preview
+        .filter(|preview| !preview.trim().is_empty())
+        .unwrap_or_else(|| String::from("(no message yet)"));
+    let lowered_preview = preview.to_lowercase();
+    let preview_matches = query
+        .split_whitespace()
+        .any(|term| lowered_preview.contains(&term.to_lowercase()));
+    let preview = match hit.matches.first() {
+        Some(first) if !preview_matches => {
+            if first.kind == SearchMatchKind::UserMessage {
+                first.snippet.clone()
+            } else {
+                format!("{}: {}", first.kind.label(), first.snippet)
+            }
+        }
+        _ => preview,
+    };
+    let created_at = hit.created_at.as_deref().and_then(parse_timestamp_str);
+    let updated_at = hit
+        .updated_at
+        .as_deref()
+        .and_then(parse_timestamp_str)
+        .or(created_at);
+
+    Row {
+        path: hit.path,
+        preview,
+        created_at,
+        updated_at,
+        cwd: hit.cwd,
+        name: hit.name,
+        tags: hit.tags,
     }
 }
 
@@ -719,7 +936,7 @@ fn draw_picker(tui: &mut Tui, state: &PickerState) -> std::io::Result<()> {
         let metrics = calculate_column_metrics(&state.filtered_rows);
 
         // Column headers and list
-        render_column_headers(frame, columns, &metrics);
+        render_column_headers(frame, columns, &metrics, state.sort);
         render_list(frame, list, state, &metrics);
 
         // Hint line
@@ -737,6 +954,9 @@ fn draw_picker(tui: &mut Tui, state: &PickerState) -> std::io::Result<()> {
             "/".dim(),
             key_hint::plain(KeyCode::Down).into(),
             " to browse".dim(),
+            "    ".dim(),
+            key_hint::plain(KeyCode::Tab).into(),
+            " to sort by name or tag".dim(),
         ]
         .into();
         frame.render_widget_ref(hint_line, hint);
@@ -809,6 +1029,20 @@ fn render_list(
         if add_leading_gap {
             preview_width = preview_width.saturating_sub(2);
         }
+        let mut label_spans: Vec<Span> = Vec::new();
+        if let Some(name) = row.name.as_deref() {
+            let name = truncate_text(name, preview_width);
+            preview_width = preview_width.saturating_sub(name.width() + 2);
+            label_spans.push(name.bold());
+            label_spans.push("  ".into());
+        }
+        if !row.tags.is_empty() {
+            let tags: Vec<String> = row.tags.iter().map(|tag| format!("#{tag}")).collect();
+            let tags = truncate_text(&tags.join(" "), preview_width);
+            preview_width = preview_width.saturating_sub(tags.width() + 2);
+            label_spans.push(tags.cyan());
+            label_spans.push("  ".into());
+        }
         let preview = truncate_text(&row.preview, preview_width);
         let mut spans: Vec<Span> = vec![marker];
         if let Some(updated) = updated_span {
@@ -822,7 +1056,12 @@ fn render_list(
         if add_leading_gap {
             spans.push("  ".into());
         }
-        spans.push(preview.into());
+        spans.extend(label_spans);
+        if row.name.is_some() {
+            spans.push(preview.dim());
+        } else {
+            spans.push(preview.into());
+        }
 
         let line: Line = spans.into();
         let rect = Rect::new(area.x, y, area.width, 1);
@@ -840,6 +1079,7 @@ fn render_list(
 fn render_empty_state_line(state: &PickerState) -> Line<'static> {
     if !state.query.is_empty() {
         if state.search_state.is_active()
+            || state.full_text_pending.is_some()
             || (state.pagination.loading.is_pending() && state.pagination.next_cursor.is_some())
         {
             return vec!["Searching…".italic().dim()].into();
@@ -912,6 +1152,7 @@ fn render_column_headers(
     frame: &mut crate::custom_terminal::Frame,
     area: Rect,
     metrics: &ColumnMetrics,
+    sort: SortOrder,
 ) {
     if area.height == 0 {
         return;
@@ -937,6 +1178,11 @@ fn render_column_headers(
         spans.push("  ".into());
     }
     spans.push("Conversation".bold());
+    match sort {
+        SortOrder::Recent => {}
+        SortOrder::Name => spans.push(" (by name)".dim()),
+        SortOrder::Tag => spans.push(" (by tag)".dim()),
+    }
     frame.render_widget_ref(Line::from(spans), area);
 }
 
@@ -1024,6 +1270,8 @@ mod tests {
             head: head_with_ts_and_user_text(ts, &[preview]),
             created_at: Some(ts.to_string()),
             updated_at: Some(ts.to_string()),
+            name: None,
+            tags: Vec::new(),
         }
     }
 
@@ -1090,12 +1338,16 @@ mod tests {
             head: head_with_ts_and_user_text("2025-01-01T00:00:00Z", &["A"]),
             created_at: Some("2025-01-01T00:00:00Z".into()),
             updated_at: Some("2025-01-01T00:00:00Z".into()),
+            name: None,
+            tags: Vec::new(),
         };
         let b = ConversationItem {
             path: PathBuf::from("/tmp/b.jsonl"),
             head: head_with_ts_and_user_text("2025-01-02T00:00:00Z", &["B"]),
             created_at: Some("2025-01-02T00:00:00Z".into()),
             updated_at: Some("2025-01-02T00:00:00Z".into()),
+            name: None,
+            tags: Vec::new(),
         };
         let rows = rows_from_items(vec![a, b]);
         assert_eq!(rows.len(), 2);
@@ -1112,6 +1364,8 @@ mod tests {
             head,
             created_at: Some("2025-01-01T00:00:00Z".into()),
             updated_at: Some("2025-01-01T01:00:00Z".into()),
+            name: None,
+            tags: Vec::new(),
         };
 
         let row = head_to_row(&item);
@@ -1138,6 +1392,7 @@ mod tests {
             PathBuf::from("/tmp"),
             FrameRequester::test_dummy(),
             loader,
+            Arc::new(|_| {}),
             String::from("openai"),
             None,
         );
@@ -1150,6 +1405,8 @@ mod tests {
                 created_at: Some(now - Duration::minutes(16)),
                 updated_at: Some(now - Duration::seconds(42)),
                 cwd: None,
+                name: None,
+                tags: Vec::new(),
             },
             Row {
                 path: PathBuf::from("/tmp/b.jsonl"),
@@ -1157,6 +1414,8 @@ mod tests {
                 created_at: Some(now - Duration::hours(1)),
                 updated_at: Some(now - Duration::minutes(35)),
                 cwd: None,
+                name: None,
+                tags: Vec::new(),
             },
             Row {
                 path: PathBuf::from("/tmp/c.jsonl"),
@@ -1164,6 +1423,8 @@ mod tests {
                 created_at: Some(now - Duration::hours(2)),
                 updated_at: Some(now - Duration::hours(2)),
                 cwd: None,
+                name: None,
+                tags: Vec::new(),
             },
         ];
         state.all_rows = rows.clone();
@@ -1283,6 +1544,7 @@ mod tests {
             PathBuf::from("/tmp"),
             FrameRequester::test_dummy(),
             loader,
+            Arc::new(|_| {}),
             String::from("openai"),
             None,
         );
@@ -1362,6 +1624,7 @@ mod tests {
             PathBuf::from("/tmp"),
             FrameRequester::test_dummy(),
             loader,
+            Arc::new(|_| {}),
             String::from("openai"),
             None,
         );
@@ -1429,6 +1692,7 @@ mod tests {
             PathBuf::from("/tmp"),
             FrameRequester::test_dummy(),
             loader,
+            Arc::new(|_| {}),
             String::from("openai"),
             None,
         );
@@ -1459,6 +1723,7 @@ mod tests {
             PathBuf::from("/tmp"),
             FrameRequester::test_dummy(),
             loader,
+            Arc::new(|_| {}),
             String::from("openai"),
             None,
         );
@@ -1502,6 +1767,7 @@ mod tests {
             PathBuf::from("/tmp"),
             FrameRequester::test_dummy(),
             loader,
+            Arc::new(|_| {}),
             String::from("openai"),
             None,
         );
@@ -1545,6 +1811,7 @@ mod tests {
             PathBuf::from("/tmp"),
             FrameRequester::test_dummy(),
             loader,
+            Arc::new(|_| {}),
             String::from("openai"),
             None,
         );
@@ -1644,4 +1911,143 @@ mod tests {
         assert!(!state.search_state.is_active());
         assert!(state.pagination.reached_scan_cap);
     }
+
+    #[tokio::test]
+    async fn tag_query_filters_rows_and_tab_sorts_by_name() {
+        let loader: PageLoader = Arc::new(|_| {});
+        let mut state = PickerState::new(
+            PathBuf::from("/tmp"),
+            FrameRequester::test_dummy(),
+            loader,
+            Arc::new(|_| {}),
+            String::from("openai"),
+            None,
+        );
+        let labelled = |path: &str, preview: &str, name: Option<&str>, tags: &[&str]| {
+            let mut item = make_item(path, "2025-01-01T00:00:00Z", preview);
+            item.name = name.map(str::to_string);
+            item.tags = tags.iter().map(|tag| tag.to_string()).collect();
+            item
+        };
+        state.reset_pagination();
+        state.ingest_page(page(
+            vec![
+                labelled("/tmp/a.jsonl", "fix flaky test", None, &["ci"]),
+                labelled(
+                    "/tmp/b.jsonl",
+                    "bump deps",
+                    Some("Release"),
+                    &["ci", "prod"],
+                ),
+                labelled("/tmp/c.jsonl", "tune alerts", Some("alerts"), &["prod"]),
+            ],
+            None,
+            3,
+            false,
+        ));
+
+        let paths = |state: &PickerState| {
+            state
+                .filtered_rows
+                .iter()
+                .map(|row| row.path.to_string_lossy().into_owned())
+                .collect::<Vec<_>>()
+        };
+
+        state.set_query("#CI".to_string());
+        assert_eq!(paths(&state), vec!["/tmp/a.jsonl", "/tmp/b.jsonl"]);
+        state.set_query("#prod release".to_string());
+        assert_eq!(paths(&state), vec!["/tmp/b.jsonl"]);
+
+        state.set_query(String::new());
+        state
+            .handle_key(KeyEvent::new(KeyCode::Tab, KeyModifiers::NONE))
+            .await
+            .unwrap();
+        assert_eq!(state.sort, SortOrder::Name);
+        assert_eq!(
+            paths(&state),
+            vec!["/tmp/c.jsonl", "/tmp/b.jsonl", "/tmp/a.jsonl"]
+        );
+    }
+
+    #[test]
+    fn full_text_results_replace_preview_filter() {
+        let recorded_searches: Arc<Mutex<Vec<FullTextSearchRequest>>> =
+            Arc::new(Mutex::new(Vec::new()));
+        let search_sink = recorded_searches.clone();
+        let loader: PageLoader = Arc::new(|_| {});
+        let mut state = PickerState::new(
+            PathBuf::from("/tmp"),
+            FrameRequester::test_dummy(),
+            loader,
+            Arc::new(move |req: FullTextSearchRequest| {
+                search_sink.lock().unwrap().push(req);
+            }),
+            String::from("openai"),
+            None,
+        );
+        state.reset_pagination();
+        state.ingest_page(page(
+            vec![make_item(
+                "/tmp/start.jsonl",
+                "2025-01-02T00:00:00Z",
+                "alpha",
+            )],
+            None,
+            1,
+            false,
+        ));
+
+        state.set_query("rip".to_string());
+        state.set_query("ripgrep".to_string());
+        let (stale, current) = {
+            let guard = recorded_searches.lock().unwrap();
+            assert_eq!(guard.len(), 2);
+            (guard[0].clone(), guard[1].clone())
+        };
+        assert!(state.filtered_rows.is_empty());
+
+        let hit = SessionSearchHit {
+            path: PathBuf::from("/tmp/older.jsonl"),
+            id: uuid::Uuid::nil(),
+            created_at: Some("2025-01-01T00:00:00Z".to_string()),
+            updated_at: None,
+            cwd: None,
+            preview: Some("set up the project".to_string()),
+            name: None,
+            tags: Vec::new(),
+            matches: vec![aish_core::SessionSearchMatch {
+                kind: SearchMatchKind::Command,
+                snippet: "cargo install ripgrep".to_string(),
+            }],
+        };
+
+        state
+            .handle_background_event(BackgroundEvent::FullTextSearchCompleted {
+                token: stale.token,
+                query: stale.query,
+                hits: Ok(Vec::new()),
+            })
+            .unwrap();
+        assert!(state.full_text_rows.is_none());
+
+        state
+            .handle_background_event(BackgroundEvent::FullTextSearchCompleted {
+                token: current.token,
+                query: current.query,
+                hits: Ok(vec![hit]),
+            })
+            .unwrap();
+
+        assert_eq!(state.filtered_rows.len(), 1);
+        assert_eq!(
+            state.filtered_rows[0].path,
+            PathBuf::from("/tmp/older.jsonl")
+        );
+        assert_eq!(
+            state.filtered_rows[0].preview,
+            "command: cargo install ripgrep"
+        );
+    }
 }
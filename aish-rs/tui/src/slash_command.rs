@@ -19,8 +19,15 @@ pub enum SlashCommand {
     Compact,
     // Undo,
     Mention,
+    Recipe,
     Status,
+    Jobs,
     Mcp,
+    Policy,
+    Rename,
+    Tag,
+    Star,
+    Unstar,
     Quit,
     Exit,
     Rollout,
@@ -37,10 +44,23 @@ impl SlashCommand {
             // SlashCommand::Undo => "ask Aish to undo a turn",
             SlashCommand::Quit | SlashCommand::Exit => "exit Aish",
             SlashCommand::Mention => "mention a file",
+            SlashCommand::Recipe => {
+                "run a saved recipe, or save the last turn's commands with /recipe save <name>"
+            }
             SlashCommand::Skills => "use skills to improve how Aish performs specific tasks",
             SlashCommand::Status => "show current session configuration and token usage",
             SlashCommand::Approvals => "choose what Aish can do without approval",
+            SlashCommand::Jobs => "list shell commands running in the background",
             SlashCommand::Mcp => "list configured MCP tools",
+            SlashCommand::Policy => {
+                "list execpolicy rules, or explain a command with /policy git push -f"
+            }
+            SlashCommand::Rename => "name this session so it is easy to find in the resume picker",
+            SlashCommand::Tag => {
+                "tag this session, e.g. /tag infra k8s; remove a tag with /tag -infra"
+            }
+            SlashCommand::Star => "star this session so pruning keeps it",
+            SlashCommand::Unstar => "remove the star from this session",
             SlashCommand::Rollout => "print the rollout file path",
             SlashCommand::TestApproval => "test approval request",
         }
@@ -61,9 +81,16 @@ impl SlashCommand {
             // | SlashCommand::Undo
             | SlashCommand::Approvals => false,
             SlashCommand::Mention
+            | SlashCommand::Recipe
             | SlashCommand::Skills
             | SlashCommand::Status
+            | SlashCommand::Jobs
             | SlashCommand::Mcp
+            | SlashCommand::Policy
+            | SlashCommand::Rename
+            | SlashCommand::Tag
+            | SlashCommand::Star
+            | SlashCommand::Unstar
             | SlashCommand::Quit
             | SlashCommand::Exit => true,
             SlashCommand::Rollout => true,
@@ -71,6 +98,15 @@ impl SlashCommand {
         }
     }
 
+    /// Whether text typed after the command name is passed to the command
+    /// instead of being submitted as a message.
+    pub fn accepts_args(self) -> bool {
+        matches!(
+            self,
+            SlashCommand::Recipe | SlashCommand::Policy | SlashCommand::Rename | SlashCommand::Tag
+        )
+    }
+
     fn is_visible(self) -> bool {
         match self {
             SlashCommand::Rollout | SlashCommand::TestApproval => cfg!(debug_assertions),
@@ -4,10 +4,14 @@ use crate::history_cell::PlainHistoryCell;
 use crate::history_cell::with_border_with_inner_width;
 use crate::version::AISH_CLI_VERSION;
 use aish_common::create_config_summary_entries;
+use aish_core::auth::CredentialSource;
 use aish_core::config::Config;
 use aish_core::models_manager::model_family::ModelFamily;
+use aish_core::protocol::BackgroundJobStatus;
+use aish_core::protocol::BackgroundJobSummary;
 use aish_core::protocol::NetworkAccess;
 use aish_core::protocol::SandboxPolicy;
+use aish_core::protocol::TokenCost;
 use aish_core::protocol::TokenUsage;
 use aish_protocol::ConversationId;
 use aish_protocol::account::PlanType;
@@ -38,6 +42,9 @@ pub(crate) struct StatusTokenUsageData {
     total: i64,
     input: i64,
     output: i64,
+    /// Prompt tokens served from the provider's cache, out of `prompt_input`.
+    cached_input: i64,
+    prompt_input: i64,
     context_window: Option<StatusContextWindowData>,
 }
 
@@ -48,9 +55,14 @@ struct StatusHistoryCell {
     directory: PathBuf,
     approval: String,
     sandbox: String,
+    writable_roots: Option<String>,
+    deny_read: Option<String>,
+    credential: Option<String>,
     agents_summary: String,
     session_id: Option<String>,
+    background_jobs: Option<String>,
     token_usage: StatusTokenUsageData,
+    cost: Option<TokenCost>,
 }
 
 #[allow(clippy::too_many_arguments)]
@@ -59,7 +71,10 @@ pub(crate) fn new_status_output(
     model_family: &ModelFamily,
     total_usage: &TokenUsage,
     context_usage: Option<&TokenUsage>,
+    token_cost: Option<&TokenCost>,
     session_id: &Option<ConversationId>,
+    background_jobs: &[BackgroundJobSummary],
+    credential_source: Option<&CredentialSource>,
     plan_type: Option<PlanType>,
     now: DateTime<Local>,
     model_name: &str,
@@ -70,7 +85,10 @@ pub(crate) fn new_status_output(
         model_family,
         total_usage,
         context_usage,
+        token_cost,
         session_id,
+        background_jobs,
+        credential_source,
         plan_type,
         now,
         model_name,
@@ -86,7 +104,10 @@ impl StatusHistoryCell {
         model_family: &ModelFamily,
         total_usage: &TokenUsage,
         context_usage: Option<&TokenUsage>,
+        token_cost: Option<&TokenCost>,
         session_id: &Option<ConversationId>,
+        background_jobs: &[BackgroundJobSummary],
+        credential_source: Option<&CredentialSource>,
         _plan_type: Option<PlanType>,
         _now: DateTime<Local>,
         model_name: &str,
@@ -101,7 +122,17 @@ impl StatusHistoryCell {
         let sandbox = match config.sandbox_policy.get() {
             SandboxPolicy::DangerFullAccess => "danger-full-access".to_string(),
             SandboxPolicy::ReadOnly => "read-only".to_string(),
-            SandboxPolicy::CurrentDirWrite { .. } => "current-dir-write".to_string(),
+            policy @ SandboxPolicy::CurrentDirWrite { .. } => {
+                let allowlist = policy.network_allowlist();
+                if allowlist.is_empty() {
+                    "current-dir-write".to_string()
+                } else {
+                    format!(
+                        "current-dir-write (network allowlist: {})",
+                        allowlist.join(", ")
+                    )
+                }
+            }
             SandboxPolicy::ExternalSandbox { network_access } => {
                 if matches!(network_access, NetworkAccess::Enabled) {
                     "external-sandbox (network access enabled)".to_string()
@@ -110,8 +141,26 @@ impl StatusHistoryCell {
                 }
             }
         };
+        let sandbox_policy = config.sandbox_policy.get();
+        let writable_roots = match sandbox_policy {
+            SandboxPolicy::CurrentDirWrite { writable_roots, .. } if !writable_roots.is_empty() => {
+                Some(
+                    writable_roots
+                        .iter()
+                        .map(|root| root.display().to_string())
+                        .collect::<Vec<_>>()
+                        .join(", "),
+                )
+            }
+            _ => None,
+        };
+        let deny_read =
+            (!sandbox_policy.deny_read().is_empty()).then(|| sandbox_policy.deny_read().join(", "));
+        let credential =
+            credential_source.map(|source| format!("{} via {source}", config.model_provider_id));
         let agents_summary = compose_agents_summary(config);
         let session_id = session_id.as_ref().map(std::string::ToString::to_string);
+        let background_jobs = compose_background_jobs_summary(background_jobs);
         let context_window = model_family.context_window.and_then(|window| {
             context_usage.map(|usage| StatusContextWindowData {
                 percent_remaining: usage.percent_of_context_window_remaining(window),
@@ -124,6 +173,8 @@ impl StatusHistoryCell {
             total: total_usage.blended_total(),
             input: total_usage.non_cached_input(),
             output: total_usage.output_tokens,
+            cached_input: total_usage.cached_input(),
+            prompt_input: total_usage.input_tokens,
             context_window,
         };
 
@@ -133,9 +184,14 @@ impl StatusHistoryCell {
             directory: config.cwd.clone(),
             approval,
             sandbox,
+            writable_roots,
+            deny_read,
+            credential,
             agents_summary,
             session_id,
+            background_jobs,
             token_usage,
+            cost: token_cost.copied(),
         }
     }
 
@@ -157,6 +213,43 @@ impl StatusHistoryCell {
         ]
     }
 
+    /// Share of prompt tokens the provider served from its cache. Hidden
+    /// until a hit is reported, since not every provider reports them.
+    fn prompt_cache_spans(&self) -> Option<Vec<Span<'static>>> {
+        let StatusTokenUsageData {
+            cached_input,
+            prompt_input,
+            ..
+        } = self.token_usage;
+        if cached_input <= 0 || prompt_input <= 0 {
+            return None;
+        }
+        let percent = cached_input * 100 / prompt_input;
+
+        Some(vec![
+            Span::from(format!("{percent}% hit")),
+            Span::from(" (").dim(),
+            Span::from(format_tokens_compact(cached_input)).dim(),
+            Span::from(" of ").dim(),
+            Span::from(format_tokens_compact(prompt_input)).dim(),
+            Span::from(" input tokens)").dim(),
+        ])
+    }
+
+    fn cost_spans(&self) -> Option<Vec<Span<'static>>> {
+        let cost = self.cost.as_ref()?;
+        let mut spans = vec![
+            Span::from(format!("${:.2}", cost.session)),
+            Span::from(" (").dim(),
+            Span::from(format!("${:.2} this turn", cost.turn)).dim(),
+        ];
+        if let Some(budget) = cost.budget {
+            spans.push(Span::from(format!(", ${budget:.2} budget")).dim());
+        }
+        spans.push(Span::from(")").dim());
+        Some(spans)
+    }
+
     fn context_window_spans(&self) -> Option<Vec<Span<'static>>> {
         let context = self.token_usage.context_window.as_ref()?;
         let percent = context.percent_remaining;
@@ -174,6 +267,27 @@ impl StatusHistoryCell {
     }
 }
 
+/// Summarizes background jobs as e.g. `2 running, 1 finished`; `None` when
+/// no job has been started in this session.
+fn compose_background_jobs_summary(jobs: &[BackgroundJobSummary]) -> Option<String> {
+    if jobs.is_empty() {
+        return None;
+    }
+    let running = jobs
+        .iter()
+        .filter(|job| matches!(job.status, BackgroundJobStatus::Running))
+        .count();
+    let finished = jobs.len() - running;
+    let mut parts = Vec::new();
+    if running > 0 {
+        parts.push(format!("{running} running"));
+    }
+    if finished > 0 {
+        parts.push(format!("{finished} finished"));
+    }
+    Some(parts.join(", "))
+}
+
 impl HistoryCell for StatusHistoryCell {
     fn display_lines(&self, width: u16) -> Vec<Line<'static>> {
         let mut lines: Vec<Line<'static>> = Vec::new();
@@ -196,10 +310,29 @@ impl HistoryCell for StatusHistoryCell {
             .collect();
         let mut seen: BTreeSet<String> = labels.iter().cloned().collect();
 
+        if self.writable_roots.is_some() {
+            push_label(&mut labels, &mut seen, "Writable roots");
+        }
+        if self.deny_read.is_some() {
+            push_label(&mut labels, &mut seen, "Deny read");
+        }
+        if self.credential.is_some() {
+            push_label(&mut labels, &mut seen, "Credential");
+        }
         if self.session_id.is_some() {
             push_label(&mut labels, &mut seen, "Session");
         }
+        if self.background_jobs.is_some() {
+            push_label(&mut labels, &mut seen, "Jobs");
+        }
         push_label(&mut labels, &mut seen, "Token usage");
+        let prompt_cache_spans = self.prompt_cache_spans();
+        if prompt_cache_spans.is_some() {
+            push_label(&mut labels, &mut seen, "Prompt cache");
+        }
+        if self.cost.is_some() {
+            push_label(&mut labels, &mut seen, "Cost");
+        }
         if self.token_usage.context_window.is_some() {
             push_label(&mut labels, &mut seen, "Context window");
         }
@@ -220,14 +353,32 @@ impl HistoryCell for StatusHistoryCell {
         lines.push(formatter.line("Model", model_spans));
         lines.push(formatter.line("Approval", vec![Span::from(self.approval.clone())]));
         lines.push(formatter.line("Sandbox", vec![Span::from(self.sandbox.clone())]));
+        if let Some(roots) = self.writable_roots.as_ref() {
+            lines.push(formatter.line("Writable roots", vec![Span::from(roots.clone())]));
+        }
+        if let Some(deny_read) = self.deny_read.as_ref() {
+            lines.push(formatter.line("Deny read", vec![Span::from(deny_read.clone())]));
+        }
+        if let Some(credential) = self.credential.as_ref() {
+            lines.push(formatter.line("Credential", vec![Span::from(credential.clone())]));
+        }
         // Agents.md removed
 
         if let Some(session) = self.session_id.as_ref() {
             lines.push(formatter.line("Session", vec![Span::from(session.clone())]));
         }
+        if let Some(jobs) = self.background_jobs.as_ref() {
+            lines.push(formatter.line("Jobs", vec![Span::from(jobs.clone())]));
+        }
 
         lines.push(Line::from(Vec::<Span<'static>>::new()));
         lines.push(formatter.line("Token usage", self.token_usage_spans()));
+        if let Some(spans) = prompt_cache_spans {
+            lines.push(formatter.line("Prompt cache", spans));
+        }
+        if let Some(spans) = self.cost_spans() {
+            lines.push(formatter.line("Cost", spans));
+        }
 
         if let Some(spans) = self.context_window_spans() {
             lines.push(formatter.line("Context window", spans));
@@ -3,6 +3,7 @@ mod format;
 mod helpers;
 
 pub(crate) use card::new_status_output;
+pub(crate) use helpers::format_directory_display;
 pub(crate) use helpers::format_tokens_compact;
 
 #[cfg(test)]
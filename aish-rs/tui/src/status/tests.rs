@@ -1,10 +1,14 @@
 use super::new_status_output;
 use crate::history_cell::HistoryCell;
+use aish_core::auth::CredentialSource;
 use aish_core::config::Config;
 use aish_core::config::ConfigBuilder;
 use aish_core::models_manager::manager::ModelsManager;
 use aish_core::models_manager::model_family::ModelFamily;
+use aish_core::protocol::SandboxPolicy;
+use aish_core::protocol::TokenCost;
 use aish_core::protocol::TokenUsage;
+use aish_utils_absolute_path::AbsolutePathBuf;
 use chrono::TimeZone;
 use std::path::PathBuf;
 use tempfile::TempDir;
@@ -60,7 +64,10 @@ async fn status_card_token_usage_excludes_cached_tokens() {
         &model_family,
         &usage,
         Some(&usage),
+        None,
         &None,
+        &[],
+        None,
         None,
         now,
         model_slug.as_deref().unwrap_or("test-model"),
@@ -73,6 +80,86 @@ async fn status_card_token_usage_excludes_cached_tokens() {
     );
 }
 
+#[tokio::test]
+async fn status_card_shows_prompt_cache_hit_rate() {
+    let temp_home = TempDir::new().expect("temp home");
+    let config = test_config(&temp_home).await;
+    let usage = TokenUsage {
+        input_tokens: 1_200,
+        cached_input_tokens: 1_000,
+        output_tokens: 50,
+        reasoning_output_tokens: 0,
+        total_tokens: 1_250,
+    };
+    let now = chrono::Local
+        .with_ymd_and_hms(2024, 1, 1, 0, 0, 0)
+        .single()
+        .expect("timestamp");
+
+    let model_family = test_model_family(None, &config);
+    let composite = new_status_output(
+        &config,
+        &model_family,
+        &usage,
+        Some(&usage),
+        None,
+        &None,
+        &[],
+        None,
+        None,
+        now,
+        "test-model",
+    );
+    let rendered = render_lines(&composite.display_lines(120));
+
+    assert!(
+        rendered
+            .iter()
+            .any(|line| line.contains("Prompt cache") && line.contains("83% hit")),
+        "expected the prompt cache hit rate, got: {rendered:?}"
+    );
+}
+
+#[tokio::test]
+async fn status_card_shows_session_cost_and_budget() {
+    let temp_home = TempDir::new().expect("temp home");
+    let config = test_config(&temp_home).await;
+    let usage = TokenUsage::default();
+    let cost = TokenCost {
+        turn: 0.25,
+        session: 1.5,
+        budget: Some(5.0),
+    };
+    let now = chrono::Local
+        .with_ymd_and_hms(2024, 1, 1, 0, 0, 0)
+        .single()
+        .expect("timestamp");
+
+    let model_family = test_model_family(None, &config);
+    let composite = new_status_output(
+        &config,
+        &model_family,
+        &usage,
+        Some(&usage),
+        Some(&cost),
+        &None,
+        &[],
+        None,
+        None,
+        now,
+        "test-model",
+    );
+    let rendered = render_lines(&composite.display_lines(120));
+
+    assert!(
+        rendered
+            .iter()
+            .any(|line| line.contains("Cost")
+                && line.contains("$1.50 ($0.25 this turn, $5.00 budget)")),
+        "expected the session cost, got: {rendered:?}"
+    );
+}
+
 #[tokio::test]
 async fn status_context_window_uses_last_usage() {
     let temp_home = TempDir::new().expect("temp home");
@@ -106,7 +193,10 @@ async fn status_context_window_uses_last_usage() {
         &model_family,
         &total_usage,
         Some(&last_usage),
+        None,
         &None,
+        &[],
+        None,
         None,
         now,
         model_slug.as_deref().unwrap_or("test-model"),
@@ -126,3 +216,93 @@ async fn status_context_window_uses_last_usage() {
         "context line should not use total aggregated tokens, got: {context_line}"
     );
 }
+
+#[tokio::test]
+async fn status_card_shows_writable_roots_and_deny_read() {
+    let temp_home = TempDir::new().expect("temp home");
+    let mut config = test_config(&temp_home).await;
+    config
+        .sandbox_policy
+        .set(SandboxPolicy::CurrentDirWrite {
+            network_access: false,
+            exclude_tmpdir_env_var: true,
+            exclude_slash_tmp: true,
+            network_allowlist: Vec::new(),
+            writable_roots: vec![AbsolutePathBuf::try_from("/opt/cache").expect("absolute")],
+            deny_read: vec!["~/.ssh".to_string(), ".env".to_string()],
+        })
+        .expect("set sandbox policy");
+
+    let usage = TokenUsage::default();
+    let now = chrono::Local
+        .with_ymd_and_hms(2024, 1, 1, 0, 0, 0)
+        .single()
+        .expect("timestamp");
+
+    let model_slug = ModelsManager::get_model_offline(config.model.as_deref());
+    let model_family = test_model_family(model_slug.as_deref(), &config);
+    let composite = new_status_output(
+        &config,
+        &model_family,
+        &usage,
+        None,
+        None,
+        &None,
+        &[],
+        None,
+        None,
+        now,
+        model_slug.as_deref().unwrap_or("test-model"),
+    );
+    let rendered = render_lines(&composite.display_lines(120));
+
+    assert!(
+        rendered
+            .iter()
+            .any(|line| line.contains("Writable roots") && line.contains("/opt/cache")),
+        "expected writable roots line, got: {rendered:?}"
+    );
+    assert!(
+        rendered
+            .iter()
+            .any(|line| line.contains("Deny read") && line.contains("~/.ssh, .env")),
+        "expected deny read line, got: {rendered:?}"
+    );
+}
+
+#[tokio::test]
+async fn status_card_shows_credential_source_without_the_key() {
+    let temp_home = TempDir::new().expect("temp home");
+    let config = test_config(&temp_home).await;
+
+    let usage = TokenUsage::default();
+    let now = chrono::Local
+        .with_ymd_and_hms(2024, 1, 1, 0, 0, 0)
+        .single()
+        .expect("timestamp");
+
+    let model_slug = ModelsManager::get_model_offline(config.model.as_deref());
+    let model_family = test_model_family(model_slug.as_deref(), &config);
+    let composite = new_status_output(
+        &config,
+        &model_family,
+        &usage,
+        None,
+        None,
+        &None,
+        &[],
+        Some(&CredentialSource::Keyring),
+        None,
+        now,
+        model_slug.as_deref().unwrap_or("test-model"),
+    );
+    let rendered = render_lines(&composite.display_lines(120));
+
+    let expected = format!("{} via OS keyring", config.model_provider_id);
+    assert!(
+        rendered
+            .iter()
+            .any(|line| line.contains("Credential") && line.contains(&expected)),
+        "expected credential line, got: {rendered:?}"
+    );
+}